@@ -0,0 +1,326 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::anyhow;
+use url::Url;
+
+use aoide_core::{
+    media::content::{
+        resolver::{vfs::VfsResolver, ContentPathResolver as _},
+        ContentPath, ContentPathConfig, VirtualFilePathConfig,
+    },
+    track::Track,
+    util::fs::DirPath,
+    TrackEntity,
+};
+use aoide_core_api::{
+    collection::LoadScope,
+    media::{tracker::Completion, SyncMode},
+    track::replace::Summary,
+};
+use aoide_media_file::{fs::visit, io::import::ImportTrackConfig};
+use aoide_repo::track::ReplaceMode;
+use aoide_storage_sqlite::connection::pool::gatekeeper::Gatekeeper;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// The directory to import, relative to the root directory
+    /// of the collection.
+    ///
+    /// The root directory is imported if the path is empty.
+    pub dir_path: ContentPath<'static>,
+
+    pub sync_mode: SyncMode,
+
+    pub import_track_config: ImportTrackConfig,
+
+    /// The number of files that are imported and stored within
+    /// a single database transaction.
+    pub batch_size: NonZeroUsize,
+
+    /// Compute the content digests of files in parallel with the
+    /// given number of worker threads while importing a batch.
+    ///
+    /// No digests are computed if `None`.
+    pub content_digest_workers: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub completion: Completion,
+
+    /// Files that could not be imported are listed as `skipped`
+    /// or `failed` without aborting the whole batch.
+    pub summary: Summary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub finished_files: usize,
+    pub total_files: usize,
+}
+
+/// Collects the paths of all files while visiting directories.
+#[derive(Default)]
+struct CollectFilePaths {
+    file_paths: Vec<PathBuf>,
+}
+
+impl visit::AncestorVisitor<(), Vec<PathBuf>, aoide_media_file::Error> for CollectFilePaths {
+    fn visit_dir_entry(
+        &mut self,
+        _context: &mut (),
+        dir_entry: &walkdir::DirEntry,
+    ) -> aoide_media_file::Result<()> {
+        if dir_entry.file_type().is_dir() {
+            // Visited separately
+            return Ok(());
+        }
+        if dir_entry.file_name().to_string_lossy().starts_with('.') {
+            // Skip hidden files
+            return Ok(());
+        }
+        self.file_paths.push(dir_entry.path().to_path_buf());
+        Ok(())
+    }
+
+    fn finish(self) -> Vec<PathBuf> {
+        self.file_paths
+    }
+}
+
+impl visit::DirectoryVisitor for CollectFilePaths {
+    type AncestorVisitor = Self;
+    type AncestorFinished = Vec<PathBuf>;
+    type AfterAncestorFinishedError = aoide_media_file::Error;
+
+    fn new_ancestor_visitor(&mut self, _dir_entry: &walkdir::DirEntry) -> Self::AncestorVisitor {
+        Self::default()
+    }
+
+    fn after_ancestor_finished(
+        &mut self,
+        _path: &Path,
+        mut file_paths: Vec<PathBuf>,
+    ) -> aoide_media_file::Result<visit::AfterAncestorFinished> {
+        self.file_paths.append(&mut file_paths);
+        Ok(visit::AfterAncestorFinished::Continue)
+    }
+}
+
+fn merge_summary(summary: &mut Summary, batch_summary: Summary) {
+    let Summary {
+        mut created,
+        mut updated,
+        mut unchanged,
+        mut skipped,
+        mut failed,
+        mut not_imported,
+        mut not_created,
+        mut not_updated,
+    } = batch_summary;
+    summary.created.append(&mut created);
+    summary.updated.append(&mut updated);
+    summary.unchanged.append(&mut unchanged);
+    summary.skipped.append(&mut skipped);
+    summary.failed.append(&mut failed);
+    summary.not_imported.append(&mut not_imported);
+    summary.not_created.append(&mut not_created);
+    summary.not_updated.append(&mut not_updated);
+}
+
+/// Collect the paths of all files in a directory and its subdirectories.
+///
+/// Runs on a blocking thread. Excluded paths are skipped and directories
+/// that are reachable through multiple paths, e.g. by symlinks, are only
+/// visited once.
+async fn collect_file_paths(
+    root_path: DirPath<'static>,
+    excluded_paths: Vec<DirPath<'static>>,
+    abort_flag: Arc<AtomicBool>,
+) -> Result<(visit::Completion, Vec<PathBuf>)> {
+    tokio::task::spawn_blocking(move || {
+        let mut collect_file_paths = CollectFilePaths::default();
+        let mut progress_event = visit::visit_directories(
+            &mut (),
+            &root_path,
+            &excluded_paths,
+            None,
+            &abort_flag,
+            &mut collect_file_paths,
+            &mut |_| {},
+        )?;
+        progress_event.try_finish();
+        let visit::Outcome {
+            completion,
+            progress: _,
+            skipped_entries,
+        } = progress_event.finalize();
+        for skipped_entry in skipped_entries {
+            log::info!(
+                "Skipped {path}: {reason:?}",
+                path = skipped_entry.path.display(),
+                reason = skipped_entry.reason
+            );
+        }
+        Ok::<_, Error>((completion, collect_file_paths.file_paths))
+    })
+    .await
+    .map_err(|err| Error::Other(anyhow!("failed to join blocking task: {err}")))?
+}
+
+/// Resolve the content paths of the collected files
+///
+/// Files are imported in a predictable order. Unresolvable file
+/// paths are skipped.
+fn resolve_content_paths(
+    resolver: &VfsResolver,
+    mut file_paths: Vec<PathBuf>,
+) -> Vec<ContentPath<'static>> {
+    file_paths.sort_unstable();
+    file_paths
+        .into_iter()
+        .filter_map(|file_path| {
+            let content_path = Url::from_file_path(&file_path)
+                .ok()
+                .and_then(|url| resolver.resolve_path_from_url(&url).ok().flatten());
+            if content_path.is_none() {
+                log::warn!(
+                    "Skipping invalid/unsupported file path: {file_path}",
+                    file_path = file_path.display()
+                );
+            }
+            content_path
+        })
+        .collect()
+}
+
+/// Import all files in a directory and its subdirectories
+///
+/// The files are imported in batches. Each batch is stored in
+/// a separate database transaction and afterwards passed to
+/// `update_index_fn` for updating a search index with the
+/// created and updated tracks.
+///
+/// Files that fail to import are collected in the summary of
+/// the outcome and don't abort the batch.
+///
+/// Failing to update the index aborts the import with an error.
+/// All batches that have been stored before remain in the database.
+pub async fn import_directory<InterceptImportedTrackFn, UpdateIndexFn, ReportProgressFn>(
+    db_gatekeeper: &Gatekeeper,
+    collection_uid: CollectionUid,
+    params: Params,
+    intercept_imported_track_fn: InterceptImportedTrackFn,
+    mut update_index_fn: UpdateIndexFn,
+    mut report_progress_fn: ReportProgressFn,
+    abort_flag: Arc<AtomicBool>,
+) -> Result<Outcome>
+where
+    InterceptImportedTrackFn: Fn(Track) -> Track + Clone + Send + 'static,
+    UpdateIndexFn: FnMut(&[TrackEntity]) -> anyhow::Result<()>,
+    ReportProgressFn: FnMut(Progress),
+{
+    let Params {
+        dir_path,
+        sync_mode,
+        import_track_config,
+        batch_size,
+        content_digest_workers,
+    } = params;
+    let collection =
+        crate::collection::load_one(db_gatekeeper, collection_uid.clone(), LoadScope::Entity)
+            .await?
+            .entity
+            .raw
+            .body;
+    let ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+        root_url,
+        excluded_paths,
+    }) = collection.media_source_config.content_path
+    else {
+        return Err(Error::Other(anyhow!("unsupported path kind")));
+    };
+    let resolver = VfsResolver::with_root_url(root_url);
+    let root_path = resolver.build_file_path(&dir_path).into();
+    let excluded_paths = excluded_paths
+        .iter()
+        .map(|excluded_path| resolver.build_file_path(excluded_path).into())
+        .collect();
+    let (completion, file_paths) =
+        collect_file_paths(root_path, excluded_paths, Arc::clone(&abort_flag)).await?;
+    if matches!(completion, visit::Completion::Aborted) {
+        return Ok(Outcome {
+            completion: Completion::Aborted,
+            summary: Summary::default(),
+        });
+    }
+    let content_paths = resolve_content_paths(&resolver, file_paths);
+    let total_files = content_paths.len();
+    let mut finished_files = 0;
+    report_progress_fn(Progress {
+        finished_files,
+        total_files,
+    });
+    let import_params = aoide_usecases::track::import_and_replace::Params {
+        sync_mode,
+        import_config: import_track_config,
+        replace_mode: ReplaceMode::UpdateOrCreate,
+        content_digest_workers,
+        force: false,
+    };
+    let mut outcome = Outcome {
+        completion: Completion::Finished,
+        summary: Summary::default(),
+    };
+    for batch in content_paths.chunks(batch_size.get()) {
+        if abort_flag.load(Ordering::Relaxed) {
+            outcome.completion = Completion::Aborted;
+            break;
+        }
+        let batch_outcome = crate::track::import_and_replace_many_by_local_file_path(
+            db_gatekeeper,
+            collection_uid.clone(),
+            import_params.clone(),
+            batch.to_vec(),
+            Some(batch.len()),
+            intercept_imported_track_fn.clone(),
+            Arc::clone(&abort_flag),
+        )
+        .await?;
+        let aoide_usecases::track::import_and_replace::Outcome {
+            completion,
+            summary: batch_summary,
+            ..
+        } = batch_outcome;
+        // The index is only updated after the batch has been committed.
+        update_index_fn(&batch_summary.created)
+            .and_then(|()| update_index_fn(&batch_summary.updated))
+            .map_err(|err| {
+                Error::Other(err.context("failed to update index after importing files"))
+            })?;
+        merge_summary(&mut outcome.summary, batch_summary);
+        finished_files += batch.len();
+        report_progress_fn(Progress {
+            finished_files,
+            total_files,
+        });
+        if matches!(completion, Completion::Aborted) {
+            outcome.completion = Completion::Aborted;
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+    Ok(outcome)
+}
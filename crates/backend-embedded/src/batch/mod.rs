@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+#[cfg(feature = "tokio")]
+pub mod import_directory;
 pub mod synchronize_collection_vfs;
 
 #[cfg(feature = "tantivy")]
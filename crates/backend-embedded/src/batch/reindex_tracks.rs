@@ -9,14 +9,19 @@ use tantivy::{
     IndexWriter,
 };
 
-use aoide_core::util::clock::OffsetDateTimeMs;
+use aoide_core::{util::clock::OffsetDateTimeMs, TrackEntity};
 use aoide_core_api::{
     track::search::{SortField, SortOrder},
     Pagination, SortDirection,
 };
+use aoide_repo::track::{IndexWatermark, RecordHeader};
+use aoide_repo_sqlite::DbConnection;
 use aoide_storage_sqlite::connection::pool::gatekeeper::Gatekeeper;
+use aoide_usecases_sqlite::track::index_watermark::{
+    load_index_watermark, load_updated_after_index_watermark, store_index_watermark,
+};
 
-use crate::{prelude::*, track::EntityCollector};
+use crate::prelude::*;
 
 #[derive(Debug, Clone)]
 pub enum IndexingMode {
@@ -27,6 +32,13 @@ pub enum IndexingMode {
     /// updated and stop when no more updated documents are
     /// expected.
     RecentlyUpdated,
+
+    /// Add or replace only documents of tracks that have been updated
+    /// after the watermark that has been stored when committing the
+    /// index for the last time.
+    ///
+    /// Tracks that have been deleted in the meantime are not detected.
+    Incremental,
 }
 
 #[cfg(feature = "tantivy")]
@@ -38,6 +50,9 @@ pub enum IndexingMode {
 ///
 /// The `mode` defaults to `RecentlyUpdated` if unspecified. It is
 /// irrelevant and ignored if the index is empty.
+///
+/// The watermark of the collection is updated within the same database
+/// transaction right before committing the index.
 pub async fn reindex_tracks(
     db_gatekeeper: &Gatekeeper,
     collection_uid: CollectionUid,
@@ -53,13 +68,6 @@ pub async fn reindex_tracks(
     db_gatekeeper
         .spawn_blocking_write_task(move |mut pooled_connection| {
             let connection = &mut *pooled_connection;
-            let search_params = aoide_core_api::track::search::Params {
-                ordering: vec![SortOrder {
-                    field: SortField::UpdatedAt,
-                    direction: SortDirection::Descending,
-                }],
-                ..Default::default()
-            };
             let index_searcher = index_writer.index().reader()?.searcher();
             let mode = if AllQuery.count(&index_searcher)? > 0 {
                 mode.unwrap_or(IndexingMode::RecentlyUpdated)
@@ -70,83 +78,175 @@ pub async fn reindex_tracks(
                 IndexingMode::All => {
                     index_writer.delete_all_documents()?;
                 }
-                IndexingMode::RecentlyUpdated => (),
+                IndexingMode::RecentlyUpdated | IndexingMode::Incremental => (),
             }
-            let mut offset = 0;
-            #[allow(clippy::cast_possible_truncation)]
-            let mut collector = EntityCollector::new(Vec::with_capacity(batch_size.get() as usize));
-            // Last timestamp to consider for updates
-            let mut last_updated_at: Option<OffsetDateTimeMs> = None;
             connection.transaction::<_, anyhow::Error, _>(|connection| {
-                'batch_loop: loop {
-                    let pagination = Pagination {
-                        offset: Some(offset),
-                        limit: Some(batch_size.get()),
-                    };
-                    aoide_usecases_sqlite::track::search::search(
+                let (num_visited, next_watermark) = match mode {
+                    IndexingMode::All | IndexingMode::RecentlyUpdated => {
+                        reindex_tracks_by_updated_at(
+                            connection,
+                            &collection_uid,
+                            &track_fields,
+                            batch_size,
+                            matches!(mode, IndexingMode::RecentlyUpdated),
+                            &mut index_writer,
+                            &mut progress_fn,
+                        )?
+                    }
+                    IndexingMode::Incremental => reindex_tracks_after_index_watermark(
                         connection,
                         &collection_uid,
-                        &search_params,
-                        &pagination,
-                        &mut collector,
-                    )?;
-                    let mut entities = collector.finish();
-                    if entities.is_empty() {
-                        break;
-                    }
-                    for entity in &entities {
-                        match mode {
-                            IndexingMode::All => (),
-                            IndexingMode::RecentlyUpdated => {
-                                if let Some(rev) = track_fields
-                                    .find_rev_by_uid(&index_searcher, &entity.hdr.uid)?
-                                {
-                                    if rev < entity.hdr.rev {
-                                        let term = track_fields.uid_term(&entity.hdr.uid);
-                                        index_writer.delete_term(term);
-                                    } else {
-                                        debug_assert_eq!(rev, entity.hdr.rev);
-                                        // After approaching the first unmodified entity all entities
-                                        // with an updated_at timestamp strictly less than the current
-                                        // one are guaranteed to be unmodified. But we still need to
-                                        // consider all entities with the same timestamp to be sure.
-                                        if let Some(last_updated_at) = &last_updated_at {
-                                            if entity.body.updated_at < *last_updated_at {
-                                                // No more updated entities expected to follow
-                                                break 'batch_loop;
-                                            }
-                                        } else {
-                                            // Initialize the high watermark that will eventually
-                                            // terminate the loop.
-                                            last_updated_at = Some(entity.body.updated_at.clone());
-                                        }
-                                        // Skip and continue with next entity
-                                        offset += 1;
-                                        continue;
-                                    }
-                                }
-                            }
-                        }
-                        // TODO: Load play counter
-                        let play_counter = None;
-                        let doc = track_fields.create_document(
-                            Some(&collection_uid),
-                            entity,
-                            play_counter,
-                        );
-                        index_writer.add_document(doc)?;
-                        offset += 1;
-                    }
-                    // Reuse the capacity of the allocated entities for the next round
-                    entities.clear();
-                    collector = EntityCollector::new(entities);
-                    progress_fn(offset);
+                        &track_fields,
+                        batch_size,
+                        &mut index_writer,
+                        &mut progress_fn,
+                    )?,
+                };
+                // Keep the current watermark if no tracks have been visited,
+                // unless all documents have been deleted.
+                if next_watermark.is_some() || matches!(mode, IndexingMode::All) {
+                    store_index_watermark(connection, &collection_uid, next_watermark.as_ref())?;
                 }
+                // Failing to commit the index rolls back the database transaction
                 index_writer.commit()?;
-                Ok(offset)
+                Ok(num_visited)
             })
         })
         .await
         .map_err(Into::into)
         .unwrap_or_else(Err)
 }
+
+/// Add or replace the documents of tracks in the order of their
+/// modification time stamp, most recently updated first.
+///
+/// If `recently_updated_only` is set then only documents of tracks
+/// that have been updated since indexing them are replaced. Stops
+/// when no more updated tracks are expected.
+///
+/// Returns the number of visited tracks and the new watermark.
+fn reindex_tracks_by_updated_at(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    track_fields: &aoide_search_index_tantivy::TrackFields,
+    batch_size: NonZeroU64,
+    recently_updated_only: bool,
+    index_writer: &mut IndexWriter,
+    progress_fn: &mut impl FnMut(u64),
+) -> anyhow::Result<(u64, Option<IndexWatermark>)> {
+    let search_params = aoide_core_api::track::search::Params {
+        ordering: vec![SortOrder {
+            field: SortField::UpdatedAt,
+            direction: SortDirection::Descending,
+        }],
+        ..Default::default()
+    };
+    let index_searcher = index_writer.index().reader()?.searcher();
+    let mut offset = 0;
+    #[allow(clippy::cast_possible_truncation)]
+    let mut records: Vec<(RecordHeader, TrackEntity)> =
+        Vec::with_capacity(batch_size.get() as usize);
+    // Last timestamp to consider for updates
+    let mut last_updated_at: Option<OffsetDateTimeMs> = None;
+    // The most recently updated track that has been visited
+    let mut watermark: Option<IndexWatermark> = None;
+    'batch_loop: loop {
+        let pagination = Pagination {
+            offset: Some(offset),
+            limit: Some(batch_size.get()),
+        };
+        aoide_usecases_sqlite::track::search::search(
+            connection,
+            collection_uid,
+            &search_params,
+            &pagination,
+            &mut records,
+        )?;
+        if records.is_empty() {
+            break;
+        }
+        for (record_header, entity) in &records {
+            watermark = watermark.max(Some(record_header.into()));
+            if recently_updated_only {
+                if let Some(rev) = track_fields.find_rev_by_uid(&index_searcher, &entity.hdr.uid)? {
+                    if rev < entity.hdr.rev {
+                        let term = track_fields.uid_term(&entity.hdr.uid);
+                        index_writer.delete_term(term);
+                    } else {
+                        debug_assert_eq!(rev, entity.hdr.rev);
+                        // After approaching the first unmodified entity all entities
+                        // with an updated_at timestamp strictly less than the current
+                        // one are guaranteed to be unmodified. But we still need to
+                        // consider all entities with the same timestamp to be sure.
+                        if let Some(last_updated_at) = &last_updated_at {
+                            if entity.body.updated_at < *last_updated_at {
+                                // No more updated entities expected to follow
+                                break 'batch_loop;
+                            }
+                        } else {
+                            // Initialize the high watermark that will eventually
+                            // terminate the loop.
+                            last_updated_at = Some(entity.body.updated_at.clone());
+                        }
+                        // Skip and continue with next entity
+                        offset += 1;
+                        continue;
+                    }
+                }
+            }
+            // TODO: Load play counter
+            let play_counter = None;
+            let doc = track_fields.create_document(Some(collection_uid), entity, play_counter);
+            index_writer.add_document(doc)?;
+            offset += 1;
+        }
+        // Reuse the capacity of the allocated records for the next round
+        records.clear();
+        progress_fn(offset);
+    }
+    Ok((offset, watermark))
+}
+
+/// Add or replace the documents of all tracks that have been updated
+/// after the stored index watermark.
+///
+/// Returns the number of indexed tracks and the new watermark.
+fn reindex_tracks_after_index_watermark(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    track_fields: &aoide_search_index_tantivy::TrackFields,
+    batch_size: NonZeroU64,
+    index_writer: &mut IndexWriter,
+    progress_fn: &mut impl FnMut(u64),
+) -> anyhow::Result<(u64, Option<IndexWatermark>)> {
+    let mut num_indexed = 0;
+    #[allow(clippy::cast_possible_truncation)]
+    let mut records: Vec<(RecordHeader, TrackEntity)> =
+        Vec::with_capacity(batch_size.get() as usize);
+    let mut watermark = load_index_watermark(connection, collection_uid)?;
+    loop {
+        load_updated_after_index_watermark(
+            connection,
+            collection_uid,
+            watermark.as_ref(),
+            batch_size.get(),
+            &mut records,
+        )?;
+        let Some((record_header, _)) = records.last() else {
+            break;
+        };
+        watermark = Some(record_header.into());
+        for (_, entity) in &records {
+            let term = track_fields.uid_term(&entity.hdr.uid);
+            index_writer.delete_term(term);
+            // TODO: Load play counter
+            let play_counter = None;
+            let doc = track_fields.create_document(Some(collection_uid), entity, play_counter);
+            index_writer.add_document(doc)?;
+            num_indexed += 1;
+        }
+        records.clear();
+        progress_fn(num_indexed);
+    }
+    Ok((num_indexed, watermark))
+}
@@ -242,6 +242,7 @@ where
     let import_files_params = aoide_core_api::media::tracker::import_files::Params {
         root_url: root_url.clone(),
         sync_mode,
+        content_digest_workers: None,
     };
     outcome.import_files = Some({
         let mut report_progress_fn = report_progress_fn.clone();
@@ -330,6 +331,7 @@ where
                 root_url: root_url.clone(),
                 excluded_paths: excluded_content_paths,
                 max_depth,
+                ignore_patterns: None,
             };
             outcome.find_untracked_files = Some({
                 let mut report_progress_fn = report_progress_fn.clone();
@@ -370,6 +372,7 @@ where
             let params = aoide_core_api::track::find_unsynchronized::Params {
                 content_path_predicate,
                 resolve_url_from_content_path: None,
+                check_file_exists: false,
             };
             outcome.find_unsynchronized_tracks = Some(
                 crate::track::find_unsynchronized(
@@ -377,6 +380,7 @@ where
                     collection_uid.clone(),
                     params,
                     Default::default(),
+                    abort_flag,
                 )
                 .await?,
             );
@@ -10,16 +10,17 @@ use aoide_core::{
     util::url::BaseUrl,
 };
 use aoide_core_api::{
-    collection::{EntityWithSummary, LoadScope},
-    Pagination,
+    collection::{EntityWithSummary, LoadScope, Patch},
+    Page, Pagination,
 };
 use aoide_repo::{
     collection::{EntityWithSummaryCollector, KindFilter, MediaSourceRootUrlFilter, RecordHeader},
     RepoError, ReservableRecordCollector,
 };
+use aoide_repo_sqlite::transaction::with_retrying_transaction;
 use aoide_storage_sqlite::connection::pool::gatekeeper::Gatekeeper;
 
-use crate::prelude::*;
+use crate::{prelude::*, MAX_TRANSACTION_ATTEMPTS};
 
 pub async fn load_all_kinds(db_gatekeeper: &Gatekeeper) -> Result<Vec<String>> {
     db_gatekeeper
@@ -40,17 +41,17 @@ pub async fn load_all(
     media_source_root_url: Option<MediaSourceRootUrlFilter>,
     scope: LoadScope,
     pagination: Option<Pagination>,
-) -> Result<Vec<EntityWithSummary>> {
+) -> Result<Page<EntityWithSummary>> {
     load_all_collecting(
         db_gatekeeper,
         kind_filter,
         media_source_root_url,
         scope,
-        pagination,
+        pagination.clone(),
         EntityWithSummaryCollector::new(Vec::new()),
     )
     .await
-    .map(EntityWithSummaryCollector::finish)
+    .map(|collector| Page::new(collector.finish(), &pagination.unwrap_or_default()))
 }
 
 pub async fn load_all_collecting<C>(
@@ -112,7 +113,7 @@ pub async fn try_load_one(
 ) -> Result<Option<EntityWithSummary>> {
     match load_one(db_gatekeeper, entity_uid.clone(), load_scope).await {
         Ok(entity_with_summary) => Ok(Some(entity_with_summary)),
-        Err(Error::Repository(RepoError::NotFound)) => Ok(None),
+        Err(Error::Repository(RepoError::NotFound { .. })) => Ok(None),
         Err(err) => Err(err),
     }
 }
@@ -138,11 +139,32 @@ pub async fn update(
     db_gatekeeper
         .spawn_blocking_write_task(move |mut pooled_connection| {
             let connection = &mut *pooled_connection;
-            connection.transaction::<_, Error, _>(|connection| {
+            with_retrying_transaction(connection, MAX_TRANSACTION_ATTEMPTS, |connection| {
                 aoide_usecases_sqlite::collection::update(
                     connection,
-                    entity_header,
-                    modified_collection,
+                    entity_header.clone(),
+                    modified_collection.clone(),
+                )
+            })
+        })
+        .await
+        .map_err(Into::into)
+        .unwrap_or_else(Err)
+}
+
+pub async fn patch(
+    db_gatekeeper: &Gatekeeper,
+    entity_header: EntityHeader,
+    patch: Patch,
+) -> Result<Entity> {
+    db_gatekeeper
+        .spawn_blocking_write_task(move |mut pooled_connection| {
+            let connection = &mut *pooled_connection;
+            with_retrying_transaction(connection, MAX_TRANSACTION_ATTEMPTS, |connection| {
+                aoide_usecases_sqlite::collection::patch(
+                    connection,
+                    entity_header.clone(),
+                    patch.clone(),
                 )
             })
         })
@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::num::NonZeroUsize;
+
 use aoide_storage_sqlite::connection::pool::gatekeeper::Gatekeeper;
 use storage::DatabaseConfig;
 
@@ -16,6 +18,9 @@ pub mod track;
 pub type Error = aoide_usecases_sqlite::Error;
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Maximum number of attempts for read-modify-write transactions
+const MAX_TRANSACTION_ATTEMPTS: NonZeroUsize = NonZeroUsize::new(3).unwrap();
+
 pub mod prelude {
     pub use aoide_core::CollectionUid;
 
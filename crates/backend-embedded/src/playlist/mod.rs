@@ -4,7 +4,7 @@
 use diesel::Connection as _;
 
 use aoide_core::playlist::{Entity, EntityHeader, EntityUid, EntityWithEntries, Playlist};
-use aoide_core_api::{playlist::EntityWithEntriesSummary, Pagination};
+use aoide_core_api::{playlist::EntityWithEntriesSummary, Page, Pagination};
 use aoide_repo::{
     playlist::{EntityWithEntriesSummaryCollector, KindFilter, RecordHeader},
     ReservableRecordCollector,
@@ -94,16 +94,16 @@ pub async fn load_all(
     collection_filter: Option<CollectionFilter<'static>>,
     kind_filter: Option<KindFilter<'static>>,
     pagination: Option<Pagination>,
-) -> Result<Vec<EntityWithEntriesSummary>> {
+) -> Result<Page<EntityWithEntriesSummary>> {
     load_all_collecting(
         db_gatekeeper,
         collection_filter,
         kind_filter,
-        pagination,
+        pagination.clone(),
         EntityWithEntriesSummaryCollector::new(Vec::new()),
     )
     .await
-    .map(EntityWithEntriesSummaryCollector::finish)
+    .map(|collector| Page::new(collector.finish(), &pagination.unwrap_or_default()))
 }
 
 /// Load a multiple entities, each with a summary of their entries
@@ -3,7 +3,8 @@
 
 use aoide_storage_sqlite::connection::{
     pool::{create_connection_pool, gatekeeper::Gatekeeper, get_pooled_connection},
-    Config as ConnectionConfig,
+    pragma::Config as PragmaConfig,
+    Config as ConnectionConfig, Storage,
 };
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -21,14 +22,27 @@ pub struct DatabaseConfig {
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub migrate_schema: Option<DatabaseSchemaMigrationMode>,
+
+    /// Open an existing database without ever modifying it
+    ///
+    /// The database is neither initialized nor migrated and all
+    /// connections reject changes. Write operations are rejected
+    /// early with [`aoide_storage_sqlite::Error::ReadOnly`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub read_only: bool,
 }
 
 pub fn provision_database(config: &DatabaseConfig) -> anyhow::Result<Gatekeeper> {
     let DatabaseConfig {
         connection,
         migrate_schema,
+        read_only,
     } = config;
 
+    if *read_only {
+        return provision_read_only_database(connection);
+    }
+
     log::info!(
         "Provisioning SQLite database: {storage}",
         storage = connection.storage,
@@ -40,7 +54,11 @@ pub fn provision_database(config: &DatabaseConfig) -> anyhow::Result<Gatekeeper>
         "Creating connection pool of max. size {max_size}",
         max_size = connection.pool.max_size
     );
-    let connection_pool = create_connection_pool(&connection.storage, connection.pool.max_size)?;
+    let connection_pool = create_connection_pool(
+        &connection.storage,
+        connection.pool.max_size,
+        connection.pragma,
+    )?;
 
     log::info!("Initializing database");
     aoide_repo_sqlite::initialize_database(&mut *get_pooled_connection(&connection_pool)?)?;
@@ -60,3 +78,32 @@ pub fn provision_database(config: &DatabaseConfig) -> anyhow::Result<Gatekeeper>
 
     Ok(gatekeeper)
 }
+
+fn provision_read_only_database(connection: &ConnectionConfig) -> anyhow::Result<Gatekeeper> {
+    log::info!(
+        "Provisioning read-only SQLite database: {storage}",
+        storage = connection.storage,
+    );
+
+    // Opening a connection would implicitly create a missing database file
+    if let Storage::File { path } = &connection.storage {
+        if !path.is_file() {
+            anyhow::bail!("database file not found: {path}", path = path.display());
+        }
+    }
+
+    let pragma = PragmaConfig {
+        query_only: Some(true),
+        ..connection.pragma
+    };
+    log::info!(
+        "Creating connection pool of max. size {max_size}",
+        max_size = connection.pool.max_size
+    );
+    let connection_pool =
+        create_connection_pool(&connection.storage, connection.pool.max_size, pragma)?;
+
+    let gatekeeper = Gatekeeper::new_read_only(connection_pool, connection.pool.gatekeeper);
+
+    Ok(gatekeeper)
+}
@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Self-contained bundles of single tracks
+//!
+//! A bundle is a JSON document with the track metadata and the
+//! base64 encoded image data of its artwork.
+
+use diesel::Connection as _;
+use serde::{Deserialize, Serialize};
+
+use aoide_core::TrackUid;
+use aoide_core_json::media::Base64;
+use aoide_media_file::io::import::LoadedArtworkImageData;
+use aoide_storage_sqlite::connection::pool::gatekeeper::Gatekeeper;
+use aoide_usecases::track::replace::ReplaceByContentPathOutcome;
+use aoide_usecases_sqlite::track::bundle::TrackBundle;
+
+use crate::prelude::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtworkImageData {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    apic_type: Option<u8>,
+
+    media_type: String,
+
+    data: Base64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Bundle {
+    track: aoide_core_json::track::Track,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    artwork_image: Option<ArtworkImageData>,
+}
+
+impl From<TrackBundle> for Bundle {
+    fn from(from: TrackBundle) -> Self {
+        let TrackBundle {
+            track,
+            artwork_image,
+        } = from;
+        let artwork_image = artwork_image.map(|artwork_image| {
+            let LoadedArtworkImageData {
+                apic_type,
+                media_type,
+                image_data,
+            } = artwork_image;
+            ArtworkImageData {
+                apic_type: apic_type.map(|apic_type| apic_type as u8),
+                media_type: media_type.to_string(),
+                data: Base64::encode(image_data),
+            }
+        });
+        Self {
+            track: track.into(),
+            artwork_image,
+        }
+    }
+}
+
+impl TryFrom<Bundle> for TrackBundle {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Bundle) -> anyhow::Result<Self> {
+        let Bundle {
+            track,
+            artwork_image,
+        } = from;
+        let track = track.try_into()?;
+        let artwork_image = artwork_image
+            .map(|artwork_image| {
+                let ArtworkImageData {
+                    apic_type,
+                    media_type,
+                    data,
+                } = artwork_image;
+                let apic_type = apic_type
+                    .map(|apic_type| {
+                        aoide_core::media::artwork::ApicType::from_repr(apic_type)
+                            .ok_or_else(|| anyhow::anyhow!("invalid APIC type: {apic_type}"))
+                    })
+                    .transpose()?;
+                let media_type = media_type.parse()?;
+                let image_data = data.try_decode()?;
+                Ok::<_, anyhow::Error>(LoadedArtworkImageData {
+                    apic_type,
+                    media_type,
+                    image_data,
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            track,
+            artwork_image,
+        })
+    }
+}
+
+/// Encode a [`TrackBundle`] as JSON.
+pub fn encode(bundle: TrackBundle) -> anyhow::Result<Vec<u8>> {
+    serde_json::to_vec(&Bundle::from(bundle)).map_err(Into::into)
+}
+
+/// Decode a [`TrackBundle`] from JSON.
+pub fn decode(json: &[u8]) -> anyhow::Result<TrackBundle> {
+    serde_json::from_slice::<Bundle>(json)?.try_into()
+}
+
+/// Export a track of a collection as an encoded bundle.
+///
+/// See also: [`encode()`]
+pub async fn export(
+    db_gatekeeper: &Gatekeeper,
+    collection_uid: CollectionUid,
+    track_uid: TrackUid,
+) -> Result<Vec<u8>> {
+    let bundle = db_gatekeeper
+        .spawn_blocking_read_task(move |mut pooled_connection| {
+            let connection = &mut *pooled_connection;
+            let exportable = connection.transaction::<_, Error, _>(|connection| {
+                aoide_usecases_sqlite::track::bundle::load_exportable_track(
+                    connection,
+                    &collection_uid,
+                    &track_uid,
+                )
+            })?;
+            // Load the artwork image after the transaction has been committed
+            exportable.into_bundle().map_err(Error::from)
+        })
+        .await
+        .map_err(Into::into)
+        .unwrap_or_else(Err)?;
+    encode(bundle).map_err(Error::Other)
+}
+
+/// Import an encoded bundle into a collection.
+///
+/// The track is created or updated at the content path of the
+/// bundled track. The artwork image is not stored and only returned
+/// to the caller.
+///
+/// The URI of [`Artwork::Linked`](aoide_core::media::artwork::Artwork::Linked)
+/// artwork is imported unmodified and might not resolve to an image
+/// file on the importing side. The caller is responsible for storing
+/// the returned image data and updating the link if needed.
+///
+/// Invalid bundles are rejected with [`Error::Input`].
+///
+/// See also: [`decode()`]
+pub async fn import(
+    db_gatekeeper: &Gatekeeper,
+    collection_uid: CollectionUid,
+    json: &[u8],
+) -> Result<(ReplaceByContentPathOutcome, Option<LoadedArtworkImageData>)> {
+    let bundle = decode(json).map_err(Error::Input)?;
+    db_gatekeeper
+        .spawn_blocking_write_task(move |mut pooled_connection| {
+            let connection = &mut *pooled_connection;
+            connection.transaction::<_, Error, _>(|connection| {
+                aoide_usecases_sqlite::track::bundle::import_bundle(
+                    connection,
+                    &collection_uid,
+                    bundle,
+                )
+            })
+        })
+        .await
+        .map_err(Into::into)
+        .unwrap_or_else(Err)
+}
@@ -9,12 +9,14 @@ use aoide_core::{
     media::content::ContentPath,
     track::{Entity, EntityUid, Track},
 };
-use aoide_core_api::{track::find_unsynchronized::UnsynchronizedTrackEntity, Pagination};
+use aoide_core_api::{track::find_unsynchronized::UnsynchronizedTrackEntity, Page, Pagination};
 use aoide_repo::{track::RecordHeader, RecordCollector, ReservableRecordCollector};
 use aoide_storage_sqlite::connection::pool::gatekeeper::Gatekeeper;
 
 use crate::prelude::*;
 
+#[cfg(feature = "json")]
+pub mod bundle;
 pub mod vfs;
 
 #[derive(Debug, Default)]
@@ -111,16 +113,16 @@ pub async fn search(
     collection_uid: CollectionUid,
     params: aoide_core_api::track::search::Params,
     pagination: Pagination,
-) -> Result<Vec<Entity>> {
+) -> Result<Page<Entity>> {
     search_collecting(
         db_gatekeeper,
         collection_uid,
         params,
-        pagination,
+        pagination.clone(),
         EntityCollector::new(Vec::new()),
     )
     .await
-    .map(EntityCollector::finish)
+    .map(|collector| Page::new(collector.finish(), &pagination))
 }
 
 pub async fn search_collecting<C>(
@@ -158,6 +160,7 @@ pub async fn find_unsynchronized(
     collection_uid: CollectionUid,
     params: aoide_core_api::track::find_unsynchronized::Params,
     pagination: Pagination,
+    abort_flag: Arc<AtomicBool>,
 ) -> Result<Vec<UnsynchronizedTrackEntity>> {
     db_gatekeeper
         .spawn_blocking_read_task(move |mut pooled_connection| {
@@ -168,6 +171,7 @@ pub async fn find_unsynchronized(
                     &collection_uid,
                     params,
                     &pagination,
+                    &abort_flag,
                 )
             })
         })
@@ -234,3 +238,26 @@ where
     .map_err(Into::into)
     .unwrap_or_else(Err)
 }
+
+pub async fn edit_tags(
+    db_gatekeeper: &Gatekeeper,
+    collection_uid: CollectionUid,
+    track_uids: Vec<EntityUid>,
+    edit: aoide_core_api::track::edit_tags::TagEdit,
+) -> Result<aoide_core_api::track::edit_tags::Summary> {
+    db_gatekeeper
+        .spawn_blocking_write_task(move |mut pooled_connection| {
+            let connection = &mut *pooled_connection;
+            connection.transaction::<_, Error, _>(|connection| {
+                aoide_usecases_sqlite::track::edit_tags::edit_tags(
+                    connection,
+                    &collection_uid,
+                    &track_uids,
+                    &edit,
+                )
+            })
+        })
+        .await
+        .map_err(Into::into)
+        .unwrap_or_else(Err)
+}
@@ -0,0 +1,159 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use url::Url;
+
+use aoide_backend_embedded::{
+    batch::import_directory::{import_directory, Params, Progress},
+    storage::DatabaseConfig,
+    Environment,
+};
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPath, ContentPathConfig, VirtualFilePathConfig},
+    util::url::BaseUrl,
+    Collection,
+};
+use aoide_core_api::{
+    collection::LoadScope,
+    media::{tracker::Completion, SyncMode},
+};
+use aoide_media_file::io::import::ImportTrackConfig;
+use aoide_storage_sqlite::connection::{
+    pool::{gatekeeper::Config as GatekeeperConfig, Config as PoolConfig},
+    Config as ConnectionConfig, Storage,
+};
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+fn copy_fixture(dir_path: &Path, file_name: &str) {
+    std::fs::copy(FIXTURE_FILE_PATH, dir_path.join(file_name)).unwrap();
+}
+
+fn commission_environment(storage_dir: &Path) -> Environment {
+    Environment::commission(&DatabaseConfig {
+        connection: ConnectionConfig {
+            storage: Storage::File {
+                path: storage_dir.join("aoide.sqlite"),
+            },
+            pool: PoolConfig {
+                max_size: NonZeroU32::MIN,
+                gatekeeper: GatekeeperConfig {
+                    acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    fairness_policy: Default::default(),
+                },
+            },
+            pragma: Default::default(),
+        },
+        migrate_schema: None,
+        read_only: false,
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn import_directory_continues_after_corrupt_file() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+    copy_fixture(music_dir.path(), "a.mp3");
+    copy_fixture(music_dir.path(), "b.mp3");
+    std::fs::create_dir(music_dir.path().join("sub")).unwrap();
+    copy_fixture(&music_dir.path().join("sub"), "c.mp3");
+    copy_fixture(&music_dir.path().join("sub"), "d.mp3");
+    std::fs::write(
+        music_dir.path().join("sub").join("corrupt.mp3"),
+        b"this is not an MPEG audio file",
+    )
+    .unwrap();
+    // Excluded directories are not imported
+    std::fs::create_dir(music_dir.path().join("excluded")).unwrap();
+    copy_fixture(&music_dir.path().join("excluded"), "e.mp3");
+
+    let env = commission_environment(storage_dir.path());
+    let root_url = BaseUrl::new(Url::from_directory_path(music_dir.path()).unwrap());
+    let collection_uid = aoide_backend_embedded::collection::create(
+        env.db_gatekeeper(),
+        Collection {
+            title: "Test Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url,
+                    excluded_paths: vec![ContentPath::from("excluded/")],
+                }),
+            },
+        },
+    )
+    .await
+    .unwrap()
+    .raw
+    .hdr
+    .uid;
+
+    let mut indexed_count = 0;
+    let mut progress = Vec::new();
+    let outcome = import_directory(
+        env.db_gatekeeper(),
+        collection_uid.clone(),
+        Params {
+            dir_path: ContentPath::default(),
+            sync_mode: SyncMode::Always,
+            import_track_config: ImportTrackConfig::default(),
+            batch_size: NonZeroUsize::new(2).unwrap(),
+            content_digest_workers: NonZeroUsize::new(2),
+        },
+        std::convert::identity,
+        |entities: &[_]| {
+            indexed_count += entities.len();
+            Ok(())
+        },
+        |event| progress.push(event),
+        Arc::new(AtomicBool::new(false)),
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(outcome.completion, Completion::Finished));
+    assert_eq!(4, outcome.summary.created.len());
+    // The content type of the corrupt file is not recognized
+    assert_eq!(
+        vec![ContentPath::from("sub/corrupt.mp3")],
+        outcome.summary.skipped
+    );
+    assert!(outcome.summary.failed.is_empty());
+    assert_eq!(4, indexed_count);
+    assert_eq!(
+        Some(&Progress {
+            finished_files: 0,
+            total_files: 5,
+        }),
+        progress.first()
+    );
+    assert_eq!(
+        Some(&Progress {
+            finished_files: 5,
+            total_files: 5,
+        }),
+        progress.last()
+    );
+    // 3 batches with 2 + 2 + 1 files
+    assert_eq!(4, progress.len());
+
+    let collection = aoide_backend_embedded::collection::load_one(
+        env.db_gatekeeper(),
+        collection_uid,
+        LoadScope::EntityWithSummary,
+    )
+    .await
+    .unwrap();
+    assert_eq!(4, collection.summary.unwrap().tracks.total_count);
+}
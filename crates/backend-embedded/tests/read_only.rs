@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    num::{NonZeroU32, NonZeroU64},
+    path::Path,
+};
+
+use diesel::connection::SimpleConnection as _;
+use url::Url;
+
+use aoide_backend_embedded::{storage::DatabaseConfig, Environment, Error};
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPathConfig, VirtualFilePathConfig},
+    util::url::BaseUrl,
+    Collection,
+};
+use aoide_core_api::collection::LoadScope;
+use aoide_storage_sqlite::{
+    connection::{
+        pool::{gatekeeper::Config as GatekeeperConfig, Config as PoolConfig},
+        Config as ConnectionConfig, Storage,
+    },
+    Error as StorageError,
+};
+
+fn database_config(storage_dir: &Path, read_only: bool) -> DatabaseConfig {
+    DatabaseConfig {
+        connection: ConnectionConfig {
+            storage: Storage::File {
+                path: storage_dir.join("aoide.sqlite"),
+            },
+            pool: PoolConfig {
+                max_size: NonZeroU32::MIN,
+                gatekeeper: GatekeeperConfig {
+                    acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    fairness_policy: Default::default(),
+                },
+            },
+            pragma: Default::default(),
+        },
+        migrate_schema: None,
+        read_only,
+    }
+}
+
+fn new_collection(title: &str, root_dir: &Path) -> Collection {
+    Collection {
+        title: title.into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::new(Url::from_directory_path(root_dir).unwrap()),
+                excluded_paths: vec![],
+            }),
+        },
+    }
+}
+
+#[tokio::test]
+async fn read_only_database_rejects_writes() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+
+    let env = Environment::commission(&database_config(storage_dir.path(), false)).unwrap();
+    aoide_backend_embedded::collection::create(
+        env.db_gatekeeper(),
+        new_collection("Existing Collection", music_dir.path()),
+    )
+    .await
+    .unwrap();
+    env.decommission();
+    drop(env);
+
+    let env = Environment::commission(&database_config(storage_dir.path(), true)).unwrap();
+    assert!(env.db_gatekeeper().is_read_only());
+
+    // Reading succeeds
+    let collections = aoide_backend_embedded::collection::load_all(
+        env.db_gatekeeper(),
+        None,
+        None,
+        LoadScope::Entity,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, collections.items.len());
+    assert!(collections.next.is_none());
+
+    // Write use cases are rejected early
+    let err = aoide_backend_embedded::collection::create(
+        env.db_gatekeeper(),
+        new_collection("New Collection", music_dir.path()),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, Error::Storage(StorageError::ReadOnly)));
+
+    // Changes are also rejected by the connections themselves
+    let result = env
+        .db_gatekeeper()
+        .spawn_blocking_read_task(|mut pooled_connection| {
+            pooled_connection.batch_execute("DELETE FROM collection")
+        })
+        .await
+        .unwrap();
+    assert!(result.is_err());
+
+    let collections = aoide_backend_embedded::collection::load_all(
+        env.db_gatekeeper(),
+        None,
+        None,
+        LoadScope::Entity,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, collections.items.len());
+    assert!(collections.next.is_none());
+}
+
+#[test]
+fn read_only_database_must_exist() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    assert!(Environment::commission(&database_config(storage_dir.path(), true)).is_err());
+    assert!(!storage_dir.path().join("aoide.sqlite").exists());
+}
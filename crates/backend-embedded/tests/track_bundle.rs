@@ -0,0 +1,263 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    num::{NonZeroU32, NonZeroU64},
+    path::Path,
+};
+
+use lofty::{
+    config::WriteOptions,
+    picture::{MimeType, Picture, PictureType},
+    tag::{Tag, TagExt as _, TagType},
+};
+use url::Url;
+
+use aoide_backend_embedded::{storage::DatabaseConfig, Environment, Error};
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::{
+        artwork::{ApicType, Artwork, ArtworkImage, EmbeddedArtwork, LinkedArtwork},
+        content::{
+            AudioContentMetadata, ContentLink, ContentPath, ContentPathConfig,
+            VirtualFilePathConfig,
+        },
+        Content, Source,
+    },
+    util::{clock::OffsetDateTimeMs, url::BaseUrl},
+    Collection, CollectionUid, Track, TrackEntity,
+};
+use aoide_media_file::io::import::LoadedArtworkImageData;
+use aoide_repo::track::ReplaceMode;
+use aoide_storage_sqlite::connection::{
+    pool::{gatekeeper::Config as GatekeeperConfig, Config as PoolConfig},
+    Config as ConnectionConfig, Storage,
+};
+use aoide_usecases::track::{replace::ReplaceAction, validate_input};
+
+const IMAGE_DATA: &[u8] = b"\x89PNG\r\n\x1a\nnot really an image";
+
+const CONTENT_PATH: &str = "artist/track.mp3";
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+fn commission_environment(storage_dir: &Path) -> Environment {
+    Environment::commission(&DatabaseConfig {
+        connection: ConnectionConfig {
+            storage: Storage::File {
+                path: storage_dir.join("aoide.sqlite"),
+            },
+            pool: PoolConfig {
+                max_size: NonZeroU32::MIN,
+                gatekeeper: GatekeeperConfig {
+                    acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    fairness_policy: Default::default(),
+                },
+            },
+            pragma: Default::default(),
+        },
+        migrate_schema: None,
+        read_only: false,
+    })
+    .unwrap()
+}
+
+async fn create_collection(env: &Environment, title: &str, music_dir: &Path) -> CollectionUid {
+    let root_url = BaseUrl::new(Url::from_directory_path(music_dir).unwrap());
+    aoide_backend_embedded::collection::create(
+        env.db_gatekeeper(),
+        Collection {
+            title: title.into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url,
+                    excluded_paths: vec![],
+                }),
+            },
+        },
+    )
+    .await
+    .unwrap()
+    .raw
+    .hdr
+    .uid
+}
+
+fn new_track_with_artwork(artwork: Artwork) -> Track {
+    let mut track = Track::new_from_media_source(Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: Content {
+            link: ContentLink {
+                path: ContentPath::from(CONTENT_PATH),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            metadata_flags: Default::default(),
+            metadata: AudioContentMetadata::default().into(),
+            digest: None,
+            size: None,
+        },
+        artwork: Some(artwork),
+    });
+    track.set_track_title("Title");
+    track
+}
+
+fn new_artwork_image() -> ArtworkImage {
+    ArtworkImage {
+        apic_type: ApicType::CoverFront,
+        media_type: "image/png".parse().unwrap(),
+        data_size: IMAGE_DATA.len() as u64,
+        digest: None,
+        image_size: None,
+        color: None,
+        thumbnail: None,
+    }
+}
+
+async fn create_track(
+    env: &Environment,
+    collection_uid: &CollectionUid,
+    track: Track,
+) -> TrackEntity {
+    let (validated_input, _) = validate_input(track).unwrap();
+    let mut summary = aoide_backend_embedded::track::replace_many_by_media_source_content_path(
+        env.db_gatekeeper(),
+        collection_uid.clone(),
+        aoide_usecases::track::replace::Params {
+            mode: ReplaceMode::CreateOnly,
+            resolve_path_from_url: false,
+            preserve_collected_at: true,
+            update_last_synchronized_rev: false,
+            decode_gigtags: false,
+        },
+        vec![validated_input],
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, summary.created.len());
+    summary.created.pop().unwrap()
+}
+
+async fn import_bundle(
+    env: &Environment,
+    collection_uid: CollectionUid,
+    json: &[u8],
+    exported_entity: &TrackEntity,
+) -> LoadedArtworkImageData {
+    let (outcome, artwork_image) =
+        aoide_backend_embedded::track::bundle::import(env.db_gatekeeper(), collection_uid, json)
+            .await
+            .unwrap();
+    assert_eq!(ReplaceAction::Created, outcome.action);
+    assert_ne!(exported_entity.hdr.uid, outcome.entity.hdr.uid);
+    assert!(outcome
+        .entity
+        .body
+        .track
+        .content_equivalent(&exported_entity.body.track));
+    artwork_image.unwrap()
+}
+
+#[tokio::test]
+async fn export_and_import_track_bundle() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+    let image_file_path = music_dir.path().join("cover.png");
+    std::fs::write(&image_file_path, IMAGE_DATA).unwrap();
+
+    let env = commission_environment(storage_dir.path());
+    let source_collection_uid = create_collection(&env, "Source", music_dir.path()).await;
+    let target_collection_uid = create_collection(&env, "Target", music_dir.path()).await;
+
+    let exported_entity = create_track(
+        &env,
+        &source_collection_uid,
+        new_track_with_artwork(Artwork::Linked(LinkedArtwork {
+            uri: Url::from_file_path(&image_file_path).unwrap().to_string(),
+            image: new_artwork_image(),
+        })),
+    )
+    .await;
+
+    let json = aoide_backend_embedded::track::bundle::export(
+        env.db_gatekeeper(),
+        source_collection_uid,
+        exported_entity.hdr.uid.clone(),
+    )
+    .await
+    .unwrap();
+    // The bundle is self-contained and doesn't depend on the image file.
+    std::fs::remove_file(&image_file_path).unwrap();
+
+    let artwork_image = import_bundle(&env, target_collection_uid, &json, &exported_entity).await;
+    assert_eq!(Some(ApicType::CoverFront), artwork_image.apic_type);
+    assert_eq!("image/png", artwork_image.media_type.essence_str());
+    assert_eq!(IMAGE_DATA, artwork_image.image_data.as_slice());
+}
+
+#[tokio::test]
+async fn export_and_import_track_bundle_with_embedded_artwork() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+    let media_file_path = music_dir.path().join(CONTENT_PATH);
+    std::fs::create_dir_all(media_file_path.parent().unwrap()).unwrap();
+    std::fs::copy(FIXTURE_FILE_PATH, &media_file_path).unwrap();
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.push_picture(Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(MimeType::Png),
+        None,
+        IMAGE_DATA.to_vec(),
+    ));
+    tag.save_to_path(&media_file_path, WriteOptions::default())
+        .unwrap();
+
+    let env = commission_environment(storage_dir.path());
+    let source_collection_uid = create_collection(&env, "Source", music_dir.path()).await;
+    let target_collection_uid = create_collection(&env, "Target", music_dir.path()).await;
+
+    let exported_entity = create_track(
+        &env,
+        &source_collection_uid,
+        new_track_with_artwork(Artwork::Embedded(EmbeddedArtwork {
+            image: new_artwork_image(),
+        })),
+    )
+    .await;
+
+    let json = aoide_backend_embedded::track::bundle::export(
+        env.db_gatekeeper(),
+        source_collection_uid,
+        exported_entity.hdr.uid.clone(),
+    )
+    .await
+    .unwrap();
+    // The bundle is self-contained and doesn't depend on the media file.
+    std::fs::remove_file(&media_file_path).unwrap();
+
+    let artwork_image = import_bundle(&env, target_collection_uid, &json, &exported_entity).await;
+    assert_eq!(Some(ApicType::CoverFront), artwork_image.apic_type);
+    assert_eq!("image/png", artwork_image.media_type.essence_str());
+    assert_eq!(IMAGE_DATA, artwork_image.image_data.as_slice());
+}
+
+#[tokio::test]
+async fn reject_invalid_track_bundle_as_input() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+    let env = commission_environment(storage_dir.path());
+    let collection_uid = create_collection(&env, "Target", music_dir.path()).await;
+
+    let result = aoide_backend_embedded::track::bundle::import(
+        env.db_gatekeeper(),
+        collection_uid,
+        b"{\"track\":{}}",
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Input(_))));
+}
@@ -1,8 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core_api::collection::LoadScope;
-use aoide_core_api_json::collection::EntityWithSummary;
+use aoide_core_api::{collection::LoadScope, Page};
+use aoide_core_api_json::{collection::EntityWithSummary, Page as PageJson};
 use aoide_repo::collection::KindFilter;
 use aoide_usecases_sqlite::collection as uc;
 
@@ -15,6 +15,12 @@ pub struct QueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_kind: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<bool>,
 
@@ -29,7 +35,7 @@ pub struct QueryParams {
     //pub pagination: PaginationQueryParams,
 }
 
-pub type ResponseBody = Vec<EntityWithSummary>;
+pub type ResponseBody = PageJson<EntityWithSummary>;
 
 pub fn handle_request(
     connection: &mut DbConnection,
@@ -37,20 +43,40 @@ pub fn handle_request(
 ) -> Result<ResponseBody> {
     let QueryParams {
         kind,
+        kinds,
+        no_kind,
         summary,
         limit,
         offset,
     } = query_params;
     // TODO: Optionally filter by media source root URL
     let media_source_root_url = None;
-    let kind_filter = kind.map(|kind| KindFilter::Equal(kind.into()));
+    let kind_filter = if kinds.is_some() || no_kind == Some(true) {
+        let kinds = kind
+            .into_iter()
+            .chain(
+                kinds
+                    .iter()
+                    .flat_map(|kinds| kinds.split(','))
+                    .filter(|kind| !kind.is_empty())
+                    .map(ToOwned::to_owned),
+            )
+            .map(Into::into)
+            .collect();
+        Some(KindFilter::AnyOf {
+            kinds,
+            include_none: no_kind.unwrap_or(false),
+        })
+    } else {
+        kind.map(|kind| KindFilter::Equal(kind.into()))
+    };
     let load_scope = if summary.unwrap_or(false) {
         LoadScope::EntityWithSummary
     } else {
         LoadScope::Entity
     };
-    let pagination = Pagination { limit, offset };
-    let pagination: Option<_> = pagination.into();
+    let page_pagination = Pagination { limit, offset };
+    let pagination: Option<_> = page_pagination.clone().into();
     let mut collector = EntityWithSummaryCollector::default();
     connection.transaction::<_, Error, _>(|connection| {
         uc::load_all(
@@ -63,5 +89,5 @@ pub fn handle_request(
         )
         .map_err(Into::into)
     })?;
-    Ok(collector.finish())
+    Ok(Page::new(collector.finish(), &page_pagination).into())
 }
@@ -17,6 +17,7 @@ pub mod create;
 pub mod load_all;
 pub mod load_all_kinds;
 pub mod load_one;
+pub mod patch;
 pub mod purge;
 pub mod update;
 
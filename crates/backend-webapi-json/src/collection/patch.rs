@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aoide_usecases_sqlite::collection as uc;
+
+use super::*;
+
+pub type QueryParams = EntityRevQueryParams;
+
+pub type RequestBody = aoide_core_api_json::collection::Patch;
+
+pub type ResponseBody = Entity;
+
+#[allow(clippy::needless_pass_by_value)] // consume arguments
+pub fn handle_request(
+    connection: &mut DbConnection,
+    uid: CollectionUid,
+    query_params: QueryParams,
+    request_body: RequestBody,
+) -> Result<ResponseBody> {
+    let EntityRevQueryParams { rev } = query_params;
+    let entity_header = _inner::EntityHeader { uid, rev };
+    let patch = request_body.into();
+    connection
+        .transaction::<_, Error, _>(|connection| {
+            uc::patch(connection, entity_header, patch).map_err(Into::into)
+        })
+        .map(Into::into)
+}
@@ -6,11 +6,10 @@ use std::result::Result as StdResult;
 use diesel::Connection as _;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use uuid::Uuid;
 
 use aoide_core::CollectionUid;
 use aoide_core_api::{Pagination, PaginationLimit, PaginationOffset};
-use aoide_core_json::entity::EntityRevision;
+use aoide_core_json::entity::{deserialize_entity_revision_number_or_opaque, EntityRevision};
 use aoide_repo_sqlite::DbConnection;
 use aoide_usecases_sqlite as uc;
 
@@ -52,9 +51,10 @@ pub type Result<T> = StdResult<T, Error>;
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EntityRevQueryParams {
+    /// Either the numeric or the opaque representation.
+    #[serde(deserialize_with = "deserialize_entity_revision_number_or_opaque")]
     pub rev: EntityRevision,
 }
 
-fn new_request_id() -> Uuid {
-    Uuid::new_v4()
-}
+#[cfg(test)]
+mod tests;
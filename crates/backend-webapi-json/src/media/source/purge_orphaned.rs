@@ -10,15 +10,7 @@ pub type RequestBody = aoide_core_api_json::media::source::purge_orphaned::Param
 pub type ResponseBody = aoide_core_api_json::media::source::purge_orphaned::Outcome;
 
 #[allow(clippy::panic_in_result_fn)] // tracing::instrument
-#[tracing::instrument(
-    name = "Purging orphaned media source",
-    skip(
-        connection,
-    ),
-    fields(
-        request_id = %new_request_id(),
-    )
-)]
+#[tracing::instrument(name = "Purging orphaned media source", skip(connection))]
 pub fn handle_request(
     connection: &mut DbConnection,
     collection_uid: &CollectionUid,
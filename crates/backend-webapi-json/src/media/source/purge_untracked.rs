@@ -8,15 +8,7 @@ pub type RequestBody = aoide_core_api_json::media::source::purge_untracked::Para
 pub type ResponseBody = aoide_core_api_json::media::source::purge_untracked::Outcome;
 
 #[allow(clippy::panic_in_result_fn)] // tracing::instrument
-#[tracing::instrument(
-    name = "Purging untracked media source",
-    skip(
-        connection,
-    ),
-    fields(
-        request_id = %new_request_id(),
-    )
-)]
+#[tracing::instrument(name = "Purging untracked media source", skip(connection))]
 pub fn handle_request(
     connection: &mut DbConnection,
     collection_uid: &CollectionUid,
@@ -6,7 +6,8 @@ use aoide_core::media::content::ContentPath;
 use super::*;
 
 #[derive(Debug, Deserialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct RequestBody {
     old_path_prefix: String,
@@ -14,7 +15,8 @@ pub struct RequestBody {
 }
 
 #[derive(Debug, Serialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ResponseBody {
     replaced_count: usize,
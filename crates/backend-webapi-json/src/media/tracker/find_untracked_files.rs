@@ -10,21 +10,14 @@ mod uc {
     pub(super) use aoide_usecases_sqlite::media::tracker::find_untracked_files::*;
 }
 
-pub type RequestBody = aoide_core_api_json::media::tracker::FsTraversalParams;
+pub type RequestBody = aoide_core_api_json::media::tracker::find_untracked_files::Params;
 
 pub type ResponseBody = aoide_core_api_json::media::tracker::find_untracked_files::Outcome;
 
 #[allow(clippy::panic_in_result_fn)] // tracing::instrument
 #[tracing::instrument(
     name = "Finding untracked media sources",
-    skip(
-        connection,
-        report_progress_fn,
-        abort_flag,
-    ),
-    fields(
-        request_id = %new_request_id(),
-    )
+    skip(connection, report_progress_fn, abort_flag)
 )]
 pub fn handle_request<ReportProgressFn: FnMut(uc::ProgressEvent)>(
     connection: &mut DbConnection,
@@ -20,14 +20,7 @@ pub type ResponseBody = aoide_core_api_json::media::tracker::import_files::Outco
 #[allow(clippy::panic_in_result_fn)] // tracing::instrument
 #[tracing::instrument(
     name = "Importing media sources",
-    skip(
-        connection,
-        report_progress_fn,
-        abort_flag,
-    ),
-    fields(
-        request_id = %new_request_id(),
-    )
+    skip(connection, report_progress_fn, abort_flag)
 )]
 pub fn handle_request<ReportProgressFn: FnMut(uc::ProgressEvent)>(
     connection: &mut DbConnection,
@@ -1,7 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core_api_json::playlist::EntityWithEntriesSummary;
+use aoide_core_api::Page;
+use aoide_core_api_json::{playlist::EntityWithEntriesSummary, Page as PageJson};
 use aoide_repo::playlist::KindFilter;
 use aoide_usecases::playlist::CollectionFilter;
 use aoide_usecases_sqlite::playlist as uc;
@@ -26,7 +27,7 @@ pub struct QueryParams {
     //pub pagination: PaginationQueryParams,
 }
 
-pub type ResponseBody = Vec<EntityWithEntriesSummary>;
+pub type ResponseBody = PageJson<EntityWithEntriesSummary>;
 
 pub fn handle_request(
     connection: &mut DbConnection,
@@ -39,8 +40,8 @@ pub fn handle_request(
         offset,
     } = query_params;
     let kind_filter = kind.map(|kind| KindFilter::Equal(kind.into()));
-    let pagination = Pagination { limit, offset };
-    let pagination: Option<_> = pagination.into();
+    let page_pagination = Pagination { limit, offset };
+    let pagination: Option<_> = page_pagination.clone().into();
     let mut collector = EntityWithEntriesSummaryCollector::default();
     connection.transaction::<_, Error, _>(|connection| {
         uc::load_all_with_entries_summary(
@@ -52,5 +53,5 @@ pub fn handle_request(
         )
         .map_err(Into::into)
     })?;
-    Ok(collector.finish())
+    Ok(Page::new(collector.finish(), &page_pagination).into())
 }
@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::de::value::{Error as ValueError, MapDeserializer};
+
+use super::*;
+
+/// Deserialize from string values like query parameters.
+fn deserialize_entity_rev_query_params(rev: &str) -> StdResult<EntityRevQueryParams, ValueError> {
+    EntityRevQueryParams::deserialize(MapDeserializer::new([("rev", rev)].into_iter()))
+}
+
+#[test]
+fn entity_rev_query_params_accept_number_or_opaque() {
+    let rev = EntityRevision::new_unchecked(42);
+    assert_eq!(rev, deserialize_entity_rev_query_params("42").unwrap().rev);
+    assert_eq!(
+        rev,
+        deserialize_entity_rev_query_params(&rev.encode_opaque())
+            .unwrap()
+            .rev
+    );
+    assert!(deserialize_entity_rev_query_params("").is_err());
+    assert!(deserialize_entity_rev_query_params("r").is_err());
+    assert!(deserialize_entity_rev_query_params("-1").is_err());
+}
@@ -1,11 +1,15 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::sync::atomic::AtomicBool;
+
+use url::Url;
+
 use aoide_core::util::url::BaseUrl;
-use aoide_core_api::media::source::ResolveUrlFromContentPath;
+use aoide_core_api::{media::source::ResolveUrlFromContentPath, Page};
 use aoide_core_api_json::{
-    filtering::StringPredicate,
-    track::{find_unsynchronized::UnsynchronizedTrackEntity, search::QueryParams},
+    filtering::StringPredicate, track::find_unsynchronized::UnsynchronizedTrackEntity,
+    Page as PageJson,
 };
 
 use super::*;
@@ -15,22 +19,43 @@ mod uc {
     pub(super) use aoide_usecases_sqlite::track::find_unsynchronized::*;
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct QueryParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolve_url_from_content_path: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_root_url: Option<Url>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_file_exists: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<PaginationLimit>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<PaginationOffset>,
+}
+
 pub type RequestBody = Option<StringPredicate>;
 
-pub type ResponseBody = Vec<UnsynchronizedTrackEntity>;
+pub type ResponseBody = PageJson<UnsynchronizedTrackEntity>;
 
 pub fn handle_request(
     connection: &mut DbConnection,
     collection_uid: &CollectionUid,
     query_params: QueryParams,
     request_body: RequestBody,
+    abort_flag: &AtomicBool,
 ) -> Result<ResponseBody> {
     // TODO: Share common code of search/find_unsynchronized use cases
     // vvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvvv
     let QueryParams {
         resolve_url_from_content_path,
         override_root_url,
-        encode_gigtags: _,
+        check_file_exists,
         limit,
         offset,
     } = query_params;
@@ -61,11 +86,12 @@ pub fn handle_request(
     let params = uc::Params {
         resolve_url_from_content_path,
         content_path_predicate: request_body.map(Into::into),
+        check_file_exists: check_file_exists.unwrap_or(false),
     };
     connection
         .transaction::<_, Error, _>(|connection| {
-            uc::find_unsynchronized(connection, collection_uid, params, &pagination)
+            uc::find_unsynchronized(connection, collection_uid, params, &pagination, abort_flag)
                 .map_err(Into::into)
         })
-        .map(|v| v.into_iter().map(Into::into).collect())
+        .map(|entities| Page::new(entities, &pagination).map(Into::into).into())
 }
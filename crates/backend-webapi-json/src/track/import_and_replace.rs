@@ -20,7 +20,8 @@ mod uc {
 }
 
 #[derive(Debug, Default, Serialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Summary {
     pub created: Vec<Entity>,
@@ -59,7 +60,8 @@ impl From<uc::Summary> for Summary {
 }
 
 #[derive(Debug, Serialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Outcome {
     pub completion: Completion,
     pub summary: Summary,
@@ -90,7 +92,8 @@ impl From<uc::Outcome> for Outcome {
 }
 
 #[derive(Debug, Deserialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct QueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sync_mode: Option<SyncMode>,
@@ -100,6 +103,9 @@ pub struct QueryParams {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub decode_gigtags: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
 }
 
 pub type RequestBody = Vec<String>;
@@ -107,16 +113,7 @@ pub type RequestBody = Vec<String>;
 pub type ResponseBody = Outcome;
 
 #[allow(clippy::panic_in_result_fn)] // tracing::instrument
-#[tracing::instrument(
-    name = "Importing and replacing tracks",
-    skip(
-        connection,
-        abort_flag,
-    ),
-    fields(
-        request_id = %new_request_id(),
-    )
-)]
+#[tracing::instrument(name = "Importing and replacing tracks", skip(connection, abort_flag))]
 pub fn handle_request(
     connection: &mut DbConnection,
     collection_uid: &CollectionUid,
@@ -128,6 +125,7 @@ pub fn handle_request(
         sync_mode,
         replace_mode,
         decode_gigtags,
+        force,
     } = query_params;
     let sync_mode = sync_mode.unwrap_or(SyncMode::Modified);
     let replace_mode = replace_mode.unwrap_or(ReplaceMode::UpdateOrCreate);
@@ -147,6 +145,8 @@ pub fn handle_request(
         sync_mode: sync_mode.into(),
         import_config,
         replace_mode: replace_mode.into(),
+        content_digest_workers: None,
+        force: force.unwrap_or(false),
     };
     let expected_content_path_count = request_body.len();
     connection
@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aoide_core::EntityUidTyped;
+use aoide_core_json::{entity::EntityUid, track::Entity};
+
+use super::*;
+
+mod uc {
+    pub(super) use aoide_usecases_sqlite::track::load::*;
+}
+
+pub type RequestBody = Vec<EntityUid>;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct ResponseBody {
+    /// The tracks that have been found in the requested order.
+    tracks: Vec<Entity>,
+
+    /// The UIDs of all tracks that have not been found.
+    not_found: Vec<EntityUid>,
+}
+
+pub fn handle_request(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    request_body: RequestBody,
+) -> Result<ResponseBody> {
+    let entity_uids = request_body
+        .into_iter()
+        .map(EntityUidTyped::from_untyped)
+        .collect::<Vec<_>>();
+    let collector_config = EntityCollectorConfig {
+        capacity: Some(entity_uids.len()),
+        encode_gigtags: None,
+    };
+    let mut collector = EntityCollector::new(collector_config);
+    let not_found = connection.transaction::<_, Error, _>(|connection| {
+        uc::load_many_in_collection(connection, collection_uid, &entity_uids, &mut collector)
+            .map_err(Into::into)
+    })?;
+    Ok(ResponseBody {
+        tracks: collector.into(),
+        not_found: not_found
+            .into_iter()
+            .map(EntityUidTyped::into_untyped)
+            .collect(),
+    })
+}
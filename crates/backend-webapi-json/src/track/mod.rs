@@ -21,6 +21,7 @@ pub mod export_metadata;
 pub mod find_unsynchronized;
 pub mod import_and_replace;
 pub mod load_many;
+pub mod load_many_in_collection;
 pub mod load_one;
 pub mod replace;
 pub mod resolve;
@@ -39,10 +40,6 @@ pub struct TrackQueryParams {
     pub encode_gigtags: Option<FacetId<'static>>,
 }
 
-fn new_request_id() -> Uuid {
-    Uuid::new_v4()
-}
-
 #[derive(Debug, Clone)]
 pub struct EntityCollectorConfig {
     pub capacity: Option<usize>,
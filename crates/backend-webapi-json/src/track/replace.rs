@@ -15,7 +15,8 @@ mod uc {
 
 #[derive(Debug, Deserialize)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub enum ReplaceMode {
     CreateOnly,
     UpdateOnly,
@@ -34,7 +35,8 @@ impl From<ReplaceMode> for uc::ReplaceMode {
 }
 
 #[derive(Debug, Default, Serialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Summary {
     pub created: Vec<Entity>,
@@ -70,7 +72,8 @@ impl From<uc::Summary> for Summary {
 }
 
 #[derive(Debug, Deserialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct QueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
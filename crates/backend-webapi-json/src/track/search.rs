@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aoide_core::util::url::BaseUrl;
-use aoide_core_api::media::source::ResolveUrlFromContentPath;
-use aoide_core_api_json::track::search::{QueryParams, SearchParams};
+use aoide_core_api::{media::source::ResolveUrlFromContentPath, Page};
+use aoide_core_api_json::track::search::{
+    field_groups_from, QueryParams, SearchParams, SearchResults,
+};
 use aoide_core_json::track::Entity;
 
 use super::*;
@@ -15,18 +17,10 @@ mod uc {
 
 pub type RequestBody = SearchParams;
 
-pub type ResponseBody = Vec<Entity>;
+pub type ResponseBody = SearchResults;
 
 #[allow(clippy::panic_in_result_fn)] // tracing::instrument
-#[tracing::instrument(
-    name = "Searching tracks",
-    skip(
-        connection,
-    ),
-    fields(
-        request_id = %new_request_id(),
-    )
-)]
+#[tracing::instrument(name = "Searching tracks", skip(connection))]
 pub fn handle_request(
     connection: &mut DbConnection,
     collection_uid: &CollectionUid,
@@ -66,10 +60,15 @@ pub fn handle_request(
             None
         };
     // ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
-    let RequestBody { filter, ordering } = request_body;
+    let RequestBody {
+        filter,
+        ordering,
+        field_groups,
+    } = request_body;
     let params = uc::Params {
         resolve_url_from_content_path,
         filter: filter.map(Into::into),
+        field_groups: field_groups_from(field_groups),
         ordering: ordering.into_iter().map(Into::into).collect(),
     };
     let collector_config = EntityCollectorConfig {
@@ -93,5 +92,6 @@ pub fn handle_request(
         )
         .map_err(Into::into)
     })?;
-    Ok(collector.into())
+    let page = Page::new(Vec::<Entity>::from(collector), &pagination);
+    Ok(SearchResults::new(page, params.field_groups))
 }
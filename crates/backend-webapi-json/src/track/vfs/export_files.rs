@@ -14,7 +14,8 @@ use aoide_usecases_sqlite::track::vfs::export_files;
 use crate::Result;
 
 #[derive(Debug, Default, Deserialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct RequestBody {
     target_root_path: String,
@@ -24,7 +25,8 @@ pub struct RequestBody {
 }
 
 #[derive(Debug, Default, Serialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ResponseBody {
     exported: u64,
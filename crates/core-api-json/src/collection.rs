@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core_json::{collection::Collection, entity::Entity};
+use aoide_core_json::{collection::Collection, entity::Entity, util::color::Color};
 
 use crate::prelude::*;
 
@@ -14,7 +14,7 @@ mod _inner {
     #[cfg(feature = "backend")]
     pub(super) use crate::_inner::collection::EntityWithSummary;
     pub(super) use crate::_inner::collection::{
-        MediaSourceSummary, PlaylistSummary, Summary, TrackSummary,
+        MediaSourceSummary, Patch, PlaylistSummary, Summary, TrackSummary,
     };
 }
 
@@ -23,7 +23,8 @@ mod _inner {
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MediaSourceSummary {
     pub total_count: u64,
 }
@@ -49,7 +50,8 @@ impl From<_inner::MediaSourceSummary> for MediaSourceSummary {
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct PlaylistSummary {
     pub total_count: u64,
 }
@@ -75,24 +77,43 @@ impl From<_inner::PlaylistSummary> for PlaylistSummary {
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct TrackSummary {
     pub total_count: u64,
+    pub total_duration_ms: u64,
+    pub total_bytes: u64,
 }
 
 #[cfg(feature = "frontend")]
 impl From<TrackSummary> for _inner::TrackSummary {
     fn from(from: TrackSummary) -> Self {
-        let TrackSummary { total_count } = from;
-        Self { total_count }
+        let TrackSummary {
+            total_count,
+            total_duration_ms,
+            total_bytes,
+        } = from;
+        Self {
+            total_count,
+            total_duration: std::time::Duration::from_millis(total_duration_ms),
+            total_bytes,
+        }
     }
 }
 
 #[cfg(feature = "backend")]
 impl From<_inner::TrackSummary> for TrackSummary {
     fn from(from: _inner::TrackSummary) -> Self {
-        let _inner::TrackSummary { total_count } = from;
-        Self { total_count }
+        let _inner::TrackSummary {
+            total_count,
+            total_duration,
+            total_bytes,
+        } = from;
+        Self {
+            total_count,
+            total_duration_ms: total_duration.as_millis().try_into().unwrap_or(u64::MAX),
+            total_bytes,
+        }
     }
 }
 
@@ -101,7 +122,8 @@ impl From<_inner::TrackSummary> for TrackSummary {
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Summary {
     pub media_sources: MediaSourceSummary,
     pub playlists: PlaylistSummary,
@@ -143,7 +165,8 @@ impl From<_inner::Summary> for Summary {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct CollectionWithSummary {
     #[serde(flatten)]
@@ -181,3 +204,88 @@ pub fn import_entity_with_summary(
     let summary = summary.map(Into::into);
     Ok((entity, summary))
 }
+
+/// Partial update of a collection
+///
+/// Omitted fields are left unchanged. Optional fields are reset
+/// by an explicit `null` value.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct Patch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<String>"))]
+    pub kind: Option<Option<String>>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<Color>"))]
+    pub color: Option<Option<Color>>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<String>"))]
+    pub notes: Option<Option<String>>,
+}
+
+/// Distinguish an explicit `null` from an omitted field.
+#[cfg(feature = "backend")]
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[cfg(feature = "frontend")]
+impl From<_inner::Patch> for Patch {
+    fn from(from: _inner::Patch) -> Self {
+        let _inner::Patch {
+            title,
+            kind,
+            color,
+            notes,
+        } = from;
+        Self {
+            title,
+            kind,
+            color: color.map(|color| color.map(Into::into)),
+            notes,
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl From<Patch> for _inner::Patch {
+    fn from(from: Patch) -> Self {
+        let Patch {
+            title,
+            kind,
+            color,
+            notes,
+        } = from;
+        Self {
+            title,
+            kind,
+            color: color.map(|color| color.map(Into::into)),
+            notes,
+        }
+    }
+}
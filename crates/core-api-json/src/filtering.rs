@@ -1,7 +1,11 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core_json::util::clock::DateTime;
+use aoide_core::tag::FacetId;
+use aoide_core_json::{
+    tag::{FacetKey, Label},
+    util::clock::DateTime,
+};
 
 use crate::prelude::*;
 
@@ -101,7 +105,8 @@ impl From<_inner::StringPredicate<'static>> for StringPredicate {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct WithTokensQueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -121,7 +126,8 @@ impl WithTokensQueryParams {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct StringFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -244,3 +250,136 @@ impl From<_inner::DateTimePredicate> for DateTimePredicate {
 #[cfg_attr(feature = "backend", derive(Deserialize))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ScalarFieldFilter<F, V>(pub(crate) F, pub(crate) ScalarPredicate<V>);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct FacetLabelsPredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifier: Option<FilterModifier>,
+
+    pub facet: FacetKey,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub any_of: Vec<Label>,
+}
+
+#[cfg(feature = "backend")]
+impl From<FacetLabelsPredicate> for _inner::FacetLabelsPredicate {
+    fn from(from: FacetLabelsPredicate) -> Self {
+        let FacetLabelsPredicate {
+            modifier,
+            facet,
+            any_of,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            facet: facet.into(),
+            any_of: any_of.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "frontend")]
+impl From<_inner::FacetLabelsPredicate> for FacetLabelsPredicate {
+    fn from(from: _inner::FacetLabelsPredicate) -> Self {
+        let _inner::FacetLabelsPredicate {
+            modifier,
+            facet,
+            any_of,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            facet: facet.into(),
+            any_of: any_of.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct FacetedTagsPredicate {
+    pub all_of: Vec<FacetLabelsPredicate>,
+}
+
+#[cfg(feature = "backend")]
+impl From<FacetedTagsPredicate> for _inner::FacetedTagsPredicate {
+    fn from(from: FacetedTagsPredicate) -> Self {
+        let FacetedTagsPredicate { all_of } = from;
+        Self {
+            all_of: all_of.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "frontend")]
+impl From<_inner::FacetedTagsPredicate> for FacetedTagsPredicate {
+    fn from(from: _inner::FacetedTagsPredicate) -> Self {
+        let _inner::FacetedTagsPredicate { all_of } = from;
+        Self {
+            all_of: all_of.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct FacetScoreAtLeast {
+    pub facet_id: FacetId<'static>,
+
+    pub min: f64,
+}
+
+#[cfg(feature = "backend")]
+impl From<FacetScoreAtLeast> for _inner::FacetScoreAtLeast {
+    fn from(from: FacetScoreAtLeast) -> Self {
+        let FacetScoreAtLeast { facet_id, min } = from;
+        Self { facet_id, min }
+    }
+}
+
+#[cfg(feature = "frontend")]
+impl From<_inner::FacetScoreAtLeast> for FacetScoreAtLeast {
+    fn from(from: _inner::FacetScoreAtLeast) -> Self {
+        let _inner::FacetScoreAtLeast { facet_id, min } = from;
+        Self { facet_id, min }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct CustomPropertyEquals {
+    pub key: String,
+
+    pub value: String,
+}
+
+#[cfg(feature = "backend")]
+impl From<CustomPropertyEquals> for _inner::CustomPropertyEquals {
+    fn from(from: CustomPropertyEquals) -> Self {
+        let CustomPropertyEquals { key, value } = from;
+        Self { key, value }
+    }
+}
+
+#[cfg(feature = "frontend")]
+impl From<_inner::CustomPropertyEquals> for CustomPropertyEquals {
+    fn from(from: _inner::CustomPropertyEquals) -> Self {
+        let _inner::CustomPropertyEquals { key, value } = from;
+        Self { key, value }
+    }
+}
@@ -25,7 +25,8 @@ pub mod track;
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Pagination {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -35,6 +36,23 @@ pub struct Pagination {
     pub offset: Option<PaginationOffset>,
 }
 
+impl Pagination {
+    /// Example values with and without optional fields.
+    #[must_use]
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                limit: Some(100),
+                offset: Some(200),
+            },
+            Self {
+                limit: None,
+                offset: None,
+            },
+        ]
+    }
+}
+
 #[cfg(feature = "frontend")]
 impl From<_inner::Pagination> for Pagination {
     fn from(from: _inner::Pagination) -> Self {
@@ -50,3 +68,65 @@ impl From<Pagination> for _inner::Pagination {
         Self { limit, offset }
     }
 }
+
+/// A page of items that have been loaded with [`Pagination`]
+///
+/// The next page starts at `next_offset` and should be requested
+/// with the same `limit`. It is only present if the page has been
+/// filled up to the requested limit.
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Deserialize))]
+#[cfg_attr(feature = "backend", derive(Serialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    pub offset: PaginationOffset,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<PaginationLimit>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<PaginationOffset>,
+}
+
+#[cfg(feature = "backend")]
+impl<T> From<_inner::Page<T>> for Page<T> {
+    fn from(from: _inner::Page<T>) -> Self {
+        let _inner::Page {
+            items,
+            offset,
+            limit,
+            next,
+        } = from;
+        Self {
+            items,
+            offset,
+            limit,
+            next_offset: next.and_then(|next| next.offset),
+        }
+    }
+}
+
+#[cfg(feature = "frontend")]
+impl<T> From<Page<T>> for _inner::Page<T> {
+    fn from(from: Page<T>) -> Self {
+        let Page {
+            items,
+            offset,
+            limit,
+            next_offset,
+        } = from;
+        Self {
+            items,
+            offset,
+            limit,
+            next: next_offset.map(|offset| _inner::Pagination {
+                limit,
+                offset: Some(offset),
+            }),
+        }
+    }
+}
@@ -14,13 +14,28 @@ mod _inner {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Params {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_url: Option<Url>,
 }
 
+impl Params {
+    /// Example values with and without optional fields.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                root_url: Some("file:///home/user/Music/".parse().expect("valid URL")),
+            },
+            Self { root_url: None },
+        ]
+    }
+}
+
 #[cfg(feature = "frontend")]
 impl From<_inner::Params> for Params {
     fn from(from: _inner::Params) -> Self {
@@ -45,7 +60,8 @@ impl TryFrom<Params> for _inner::Params {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Outcome {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -57,6 +73,26 @@ pub struct Outcome {
     pub summary: Summary,
 }
 
+impl Outcome {
+    /// Example values with and without optional fields.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                root_url: Some("file:///home/user/Music/".parse().expect("valid URL")),
+                root_path: Some(String::new()),
+                summary: Summary { purged: 3 },
+            },
+            Self {
+                root_url: None,
+                root_path: None,
+                summary: Summary { purged: 0 },
+            },
+        ]
+    }
+}
+
 #[cfg(feature = "frontend")]
 impl TryFrom<Outcome> for _inner::Outcome {
     type Error = aoide_core::util::url::BaseUrlError;
@@ -94,7 +130,8 @@ impl From<_inner::Outcome> for Outcome {
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Summary {
     pub purged: u64,
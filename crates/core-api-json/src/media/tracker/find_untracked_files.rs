@@ -1,21 +1,83 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use aoide_core::media::content::ContentPath;
+#[cfg(feature = "backend")]
+use aoide_core::util::url::{BaseUrl, BaseUrlError};
 use url::Url;
 
 use super::Completion;
 use crate::prelude::*;
 
-pub type Params = super::FsTraversalParams;
-
 mod _core {
     pub(super) use aoide_core_api::media::tracker::find_untracked_files::*;
 }
 
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Params {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_url: Option<Url>,
+
+    #[serde(default)]
+    pub excluded_paths: Vec<ContentPath<'static>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_patterns: Option<Vec<String>>,
+}
+
+#[cfg(feature = "frontend")]
+impl From<_core::Params> for Params {
+    fn from(from: _core::Params) -> Self {
+        let _core::Params {
+            root_url,
+            excluded_paths,
+            max_depth,
+            ignore_patterns,
+        } = from;
+        let root_url = root_url.map(Into::into);
+        Self {
+            root_url,
+            excluded_paths,
+            max_depth,
+            ignore_patterns,
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl TryFrom<Params> for _core::Params {
+    type Error = BaseUrlError;
+
+    fn try_from(from: Params) -> Result<Self, Self::Error> {
+        let Params {
+            root_url,
+            excluded_paths,
+            max_depth,
+            ignore_patterns,
+        } = from;
+        let root_url = root_url.map(BaseUrl::try_autocomplete_from).transpose()?;
+        Ok(Self {
+            root_url,
+            excluded_paths,
+            max_depth,
+            ignore_patterns,
+        })
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Outcome {
     pub root_url: Url,
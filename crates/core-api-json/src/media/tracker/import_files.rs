@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::num::NonZeroUsize;
+
 #[cfg(feature = "backend")]
 use aoide_core::util::url::{BaseUrl, BaseUrlError};
 use url::Url;
@@ -15,13 +17,37 @@ mod _inner {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Params {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_url: Option<Url>,
 
     pub sync_mode: SyncMode,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_digest_workers: Option<NonZeroUsize>,
+}
+
+impl Params {
+    /// Example values with and without optional fields.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                root_url: Some("file:///home/user/Music/".parse().expect("valid URL")),
+                sync_mode: SyncMode::Modified,
+                content_digest_workers: NonZeroUsize::new(4),
+            },
+            Self {
+                root_url: None,
+                sync_mode: SyncMode::Always,
+                content_digest_workers: None,
+            },
+        ]
+    }
 }
 
 #[cfg(feature = "frontend")]
@@ -30,10 +56,12 @@ impl From<_inner::Params> for Params {
         let _inner::Params {
             root_url,
             sync_mode,
+            content_digest_workers,
         } = from;
         Self {
             root_url: root_url.map(Into::into),
             sync_mode: sync_mode.into(),
+            content_digest_workers,
         }
     }
 }
@@ -46,11 +74,13 @@ impl TryFrom<Params> for _inner::Params {
         let Params {
             root_url,
             sync_mode,
+            content_digest_workers,
         } = from;
         let root_url = root_url.map(BaseUrl::try_autocomplete_from).transpose()?;
         Ok(Self {
             root_url,
             sync_mode: sync_mode.into(),
+            content_digest_workers,
         })
     }
 }
@@ -58,7 +88,8 @@ impl TryFrom<Params> for _inner::Params {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ImportedSourceWithIssues {
     pub path: String,
@@ -88,9 +119,90 @@ impl From<_inner::ImportedSourceWithIssues> for ImportedSourceWithIssues {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum IssueKind {
+    MissingArtwork,
+    MalformedDate,
+    UnsupportedTag,
+    Other,
+}
+
+#[cfg(feature = "frontend")]
+impl From<IssueKind> for _inner::IssueKind {
+    fn from(from: IssueKind) -> Self {
+        match from {
+            IssueKind::MissingArtwork => Self::MissingArtwork,
+            IssueKind::MalformedDate => Self::MalformedDate,
+            IssueKind::UnsupportedTag => Self::UnsupportedTag,
+            IssueKind::Other => Self::Other,
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl From<_inner::IssueKind> for IssueKind {
+    fn from(from: _inner::IssueKind) -> Self {
+        match from {
+            _inner::IssueKind::MissingArtwork => Self::MissingArtwork,
+            _inner::IssueKind::MalformedDate => Self::MalformedDate,
+            _inner::IssueKind::UnsupportedTag => Self::UnsupportedTag,
+            _inner::IssueKind::Other => Self::Other,
+        }
+    }
+}
+
+#[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[cfg_attr(feature = "backend", derive(Serialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct IssueGroup {
+    pub kind: IssueKind,
+    pub count: usize,
+    pub sample_paths: Vec<String>,
+}
+
+#[cfg(feature = "frontend")]
+impl From<IssueGroup> for _inner::IssueGroup {
+    fn from(from: IssueGroup) -> Self {
+        let IssueGroup {
+            kind,
+            count,
+            sample_paths,
+        } = from;
+        Self {
+            kind: kind.into(),
+            count,
+            sample_paths: sample_paths.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl From<_inner::IssueGroup> for IssueGroup {
+    fn from(from: _inner::IssueGroup) -> Self {
+        let _inner::IssueGroup {
+            kind,
+            count,
+            sample_paths,
+        } = from;
+        Self {
+            kind: kind.into(),
+            count,
+            sample_paths: sample_paths.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "backend", derive(Serialize))]
+#[cfg_attr(feature = "frontend", derive(Deserialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Outcome {
     pub root_url: Url,
@@ -100,6 +212,59 @@ pub struct Outcome {
 
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub imported_sources_with_issues: Vec<ImportedSourceWithIssues>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub issue_groups: Vec<IssueGroup>,
+}
+
+impl Outcome {
+    /// Example values with and without optional fields.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn examples() -> Vec<Self> {
+        let new_summary = || Summary {
+            tracks: TrackSummary {
+                created: 10,
+                updated: 5,
+                unchanged: 100,
+                skipped: 1,
+                failed: 1,
+                not_imported: 0,
+                not_created: 0,
+                not_updated: 0,
+            },
+            directories: DirectorySummary {
+                confirmed: 20,
+                skipped: 1,
+                untracked: 0,
+            },
+        };
+        vec![
+            Self {
+                root_url: "file:///home/user/Music/".parse().expect("valid URL"),
+                root_path: String::new(),
+                completion: Completion::Finished,
+                summary: new_summary(),
+                imported_sources_with_issues: vec![ImportedSourceWithIssues {
+                    path: "Album/Track.mp3".to_owned(),
+                    messages: vec!["Invalid tempo: 1000".to_owned()],
+                }],
+                issue_groups: vec![IssueGroup {
+                    kind: IssueKind::Other,
+                    count: 1,
+                    sample_paths: vec!["Album/Track.mp3".to_owned()],
+                }],
+            },
+            Self {
+                root_url: "file:///home/user/Music/Album/".parse().expect("valid URL"),
+                root_path: "Album/".to_owned(),
+                completion: Completion::Aborted,
+                summary: new_summary(),
+                imported_sources_with_issues: vec![],
+                issue_groups: vec![],
+            },
+        ]
+    }
 }
 
 #[cfg(feature = "frontend")]
@@ -113,6 +278,7 @@ impl TryFrom<Outcome> for _inner::Outcome {
             completion,
             summary,
             imported_sources_with_issues,
+            issue_groups,
         } = from;
         Ok(Self {
             root_url: root_url.try_into()?,
@@ -123,6 +289,9 @@ impl TryFrom<Outcome> for _inner::Outcome {
                 .into_iter()
                 .map(Into::into)
                 .collect(),
+            issue_summary: _inner::IssueSummary {
+                groups: issue_groups.into_iter().map(Into::into).collect(),
+            },
         })
     }
 }
@@ -136,6 +305,7 @@ impl From<_inner::Outcome> for Outcome {
             completion,
             summary,
             imported_sources_with_issues,
+            issue_summary,
         } = from;
         Self {
             root_url: root_url.into(),
@@ -146,6 +316,7 @@ impl From<_inner::Outcome> for Outcome {
                 .into_iter()
                 .map(Into::into)
                 .collect(),
+            issue_groups: issue_summary.groups.into_iter().map(Into::into).collect(),
         }
     }
 }
@@ -153,7 +324,8 @@ impl From<_inner::Outcome> for Outcome {
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct TrackSummary {
     pub created: usize,
@@ -221,7 +393,8 @@ impl From<_inner::TrackSummary> for TrackSummary {
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DirectorySummary {
     /// Successfully imported and marked as current.
@@ -276,7 +449,8 @@ impl From<_inner::DirectorySummary> for DirectorySummary {
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Summary {
     pub tracks: TrackSummary,
@@ -21,7 +21,8 @@ pub mod untrack_directories;
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct FsTraversalParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,6 +35,26 @@ pub struct FsTraversalParams {
     pub max_depth: Option<usize>,
 }
 
+impl FsTraversalParams {
+    /// Example values with and without optional fields.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                root_url: Some("file:///home/user/Music/".parse().expect("valid URL")),
+                excluded_paths: vec!["Podcasts/".into()],
+                max_depth: Some(3),
+            },
+            Self {
+                root_url: None,
+                excluded_paths: vec![],
+                max_depth: None,
+            },
+        ]
+    }
+}
+
 #[cfg(feature = "frontend")]
 impl From<_core::FsTraversalParams> for FsTraversalParams {
     fn from(from: _core::FsTraversalParams) -> Self {
@@ -108,12 +129,52 @@ impl From<_core::Progress> for Progress {
     }
 }
 
+#[derive(Debug)]
+#[cfg_attr(feature = "backend", derive(Serialize))]
+#[cfg_attr(feature = "frontend", derive(Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressPhase {
+    ScanningDirectories,
+    DigestingDirectories,
+    ImportingFiles,
+}
+
+#[cfg(feature = "frontend")]
+impl From<ProgressPhase> for _core::ProgressPhase {
+    fn from(from: ProgressPhase) -> Self {
+        use ProgressPhase as From;
+        match from {
+            From::ScanningDirectories => Self::ScanningDirectories,
+            From::DigestingDirectories => Self::DigestingDirectories,
+            From::ImportingFiles => Self::ImportingFiles,
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl From<_core::ProgressPhase> for ProgressPhase {
+    fn from(from: _core::ProgressPhase) -> Self {
+        use _core::ProgressPhase as From;
+        match from {
+            From::ScanningDirectories => Self::ScanningDirectories,
+            From::DigestingDirectories => Self::DigestingDirectories,
+            From::ImportingFiles => Self::ImportingFiles,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FsTraversalProgress {
+    pub phase: ProgressPhase,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub current_path: Option<String>,
+
     pub entries: FsTraversalEntriesProgress,
     pub directories: FsTraversalDirectoriesProgress,
 }
@@ -122,10 +183,14 @@ pub struct FsTraversalProgress {
 impl From<FsTraversalProgress> for _core::FsTraversalProgress {
     fn from(from: FsTraversalProgress) -> Self {
         let FsTraversalProgress {
+            phase,
+            current_path,
             entries,
             directories,
         } = from;
         Self {
+            phase: phase.into(),
+            current_path: current_path.map(Into::into),
             entries: entries.into(),
             directories: directories.into(),
         }
@@ -136,10 +201,14 @@ impl From<FsTraversalProgress> for _core::FsTraversalProgress {
 impl From<_core::FsTraversalProgress> for FsTraversalProgress {
     fn from(from: _core::FsTraversalProgress) -> Self {
         let _core::FsTraversalProgress {
+            phase,
+            current_path,
             entries,
             directories,
         } = from;
         Self {
+            phase: phase.into(),
+            current_path: current_path.map(Into::into),
             entries: entries.into(),
             directories: directories.into(),
         }
@@ -197,7 +266,53 @@ impl From<_core::FsTraversalDirectoriesProgress> for FsTraversalDirectoriesProgr
     }
 }
 
-pub type ImportingProgress = import_files::Summary;
+#[derive(Debug)]
+#[cfg_attr(feature = "backend", derive(Serialize))]
+#[cfg_attr(feature = "frontend", derive(Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ImportingProgress {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub current_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub estimated_remaining_ms: Option<u64>,
+
+    pub summary: import_files::Summary,
+}
+
+#[cfg(feature = "frontend")]
+impl From<ImportingProgress> for _core::ImportingProgress {
+    fn from(from: ImportingProgress) -> Self {
+        let ImportingProgress {
+            current_path,
+            estimated_remaining_ms,
+            summary,
+        } = from;
+        Self {
+            current_path: current_path.map(Into::into),
+            estimated_remaining: estimated_remaining_ms.map(std::time::Duration::from_millis),
+            summary: summary.into(),
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl From<_core::ImportingProgress> for ImportingProgress {
+    fn from(from: _core::ImportingProgress) -> Self {
+        let _core::ImportingProgress {
+            current_path,
+            estimated_remaining,
+            summary,
+        } = from;
+        Self {
+            current_path: current_path.map(Into::into),
+            estimated_remaining_ms: estimated_remaining
+                .map(|duration| duration.as_millis().try_into().unwrap_or(u64::MAX)),
+            summary: summary.into(),
+        }
+    }
+}
 
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
@@ -234,7 +349,8 @@ impl From<_core::Completion> for Completion {
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Status {
     pub directories: DirectoriesStatus,
@@ -263,7 +379,8 @@ impl From<Status> for _core::Status {
 #[derive(Debug)]
 #[cfg_attr(feature = "backend", derive(Serialize))]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DirectoriesStatus {
     pub current: usize,
@@ -315,7 +432,8 @@ impl From<DirectoriesStatus> for _core::DirectoriesStatus {
 
 // Used both in requests and responses.
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum DirTrackingStatus {
     Current,
@@ -15,7 +15,8 @@ mod _core {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Summary {
     pub current: usize,
@@ -68,13 +69,16 @@ impl From<_core::Summary> for Summary {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Outcome {
     pub root_url: Url,
     pub root_path: String,
     pub completion: Completion,
     pub summary: Summary,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_paths: Vec<String>,
 }
 
 #[cfg(feature = "frontend")]
@@ -87,12 +91,14 @@ impl TryFrom<Outcome> for _core::Outcome {
             root_path,
             completion,
             summary,
+            skipped_paths,
         } = from;
         Ok(Self {
             root_url: root_url.try_into()?,
             root_path: root_path.into(),
             completion: completion.into(),
             summary: summary.into(),
+            skipped_paths: skipped_paths.into_iter().map(Into::into).collect(),
         })
     }
 }
@@ -105,12 +111,14 @@ impl From<_core::Outcome> for Outcome {
             root_path,
             completion,
             summary,
+            skipped_paths,
         } = from;
         Self {
             root_url: root_url.into(),
             root_path: root_path.into(),
             completion: completion.into(),
             summary: summary.into(),
+            skipped_paths: skipped_paths.into_iter().map(Into::into).collect(),
         }
     }
 }
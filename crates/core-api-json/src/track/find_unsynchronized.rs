@@ -12,6 +12,38 @@ mod _inner {
     pub(super) use crate::_inner::track::find_unsynchronized::*;
 }
 
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(Deserialize))]
+#[cfg_attr(feature = "backend", derive(Serialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum FileStatus {
+    Present,
+    Missing,
+}
+
+#[cfg(feature = "frontend")]
+impl From<FileStatus> for _inner::FileStatus {
+    fn from(from: FileStatus) -> Self {
+        use FileStatus as From;
+        match from {
+            From::Present => Self::Present,
+            From::Missing => Self::Missing,
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl From<_inner::FileStatus> for FileStatus {
+    fn from(from: _inner::FileStatus) -> Self {
+        use _inner::FileStatus as From;
+        match from {
+            From::Present => Self::Present,
+            From::Missing => Self::Missing,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Deserialize))]
 #[cfg_attr(feature = "backend", derive(Serialize))]
@@ -22,6 +54,9 @@ pub struct UnsynchronizedTrack {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_synchronized_rev: Option<EntityRevision>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_status: Option<FileStatus>,
 }
 
 #[cfg(feature = "frontend")]
@@ -30,10 +65,12 @@ impl From<UnsynchronizedTrack> for _inner::UnsynchronizedTrack {
         let UnsynchronizedTrack {
             content_link,
             last_synchronized_rev,
+            file_status,
         } = from;
         Self {
             content_link: content_link.into(),
             last_synchronized_rev: last_synchronized_rev.map(Into::into),
+            file_status: file_status.map(Into::into),
         }
     }
 }
@@ -44,10 +81,12 @@ impl From<_inner::UnsynchronizedTrack> for UnsynchronizedTrack {
         let _inner::UnsynchronizedTrack {
             content_link,
             last_synchronized_rev,
+            file_status,
         } = from;
         Self {
             content_link: content_link.into(),
             last_synchronized_rev: last_synchronized_rev.map(Into::into),
+            file_status: file_status.map(Into::into),
         }
     }
 }
@@ -58,6 +97,44 @@ impl From<_inner::UnsynchronizedTrack> for UnsynchronizedTrack {
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct UnsynchronizedTrackEntity(EntityHeader, UnsynchronizedTrack);
 
+impl UnsynchronizedTrackEntity {
+    /// Example values with and without optional fields.
+    #[must_use]
+    pub fn examples() -> Vec<Self> {
+        let new_header = |rev| -> EntityHeader {
+            aoide_core::EntityHeader {
+                uid: aoide_core::EntityUid::new(),
+                rev: EntityRevision::new_unchecked(rev),
+            }
+            .into()
+        };
+        vec![
+            Self(
+                new_header(3),
+                UnsynchronizedTrack {
+                    content_link: ContentLink {
+                        path: "file:///home/user/Music/track.mp3".to_owned(),
+                        rev: Some(1_704_067_200_000),
+                    },
+                    last_synchronized_rev: Some(EntityRevision::new_unchecked(2)),
+                    file_status: Some(FileStatus::Present),
+                },
+            ),
+            Self(
+                new_header(1),
+                UnsynchronizedTrack {
+                    content_link: ContentLink {
+                        path: "Album/Deleted Track.flac".to_owned(),
+                        rev: None,
+                    },
+                    last_synchronized_rev: None,
+                    file_status: None,
+                },
+            ),
+        ]
+    }
+}
+
 #[cfg(feature = "frontend")]
 impl From<UnsynchronizedTrackEntity> for _inner::UnsynchronizedTrackEntity {
     fn from(from: UnsynchronizedTrackEntity) -> Self {
@@ -21,7 +21,10 @@ use url::Url;
 #[cfg(feature = "frontend")]
 use crate::Pagination;
 use crate::{
-    filtering::{FilterModifier, ScalarFieldFilter, StringFilter},
+    filtering::{
+        CustomPropertyEquals, FacetScoreAtLeast, FacetedTagsPredicate, FilterModifier,
+        ScalarFieldFilter, ScalarPredicate, StringFilter,
+    },
     tag::search::Filter as TagFilter,
     SortDirection,
 };
@@ -38,6 +41,7 @@ mod _inner {
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum SortField {
+    AlbumTitle,
     AudioBitrateBps,
     AudioChannelCount,
     AudioChannelMask,
@@ -67,6 +71,7 @@ impl From<SortField> for _inner::SortField {
     fn from(from: SortField) -> Self {
         use SortField as From;
         match from {
+            From::AlbumTitle => Self::AlbumTitle,
             From::AudioBitrateBps => Self::AudioBitrateBps,
             From::AudioChannelCount => Self::AudioChannelCount,
             From::AudioChannelMask => Self::AudioChannelMask,
@@ -98,6 +103,7 @@ impl From<_inner::SortField> for SortField {
     fn from(from: _inner::SortField) -> Self {
         use _inner::SortField as From;
         match from {
+            From::AlbumTitle => Self::AlbumTitle,
             From::AudioBitrateBps => Self::AudioBitrateBps,
             From::AudioChannelCount => Self::AudioChannelCount,
             From::AudioChannelMask => Self::AudioChannelMask,
@@ -127,7 +133,8 @@ impl From<_inner::SortField> for SortField {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SortOrder(SortField, SortDirection);
 
@@ -317,6 +324,9 @@ impl From<_inner::DateTimeField> for DateTimeField {
 pub enum ConditionFilter {
     SourceTracked,
     SourceUntracked,
+    SourceNeverSynchronized,
+    TrackTitleBlank,
+    AlbumTitleBlank,
 }
 
 #[cfg(feature = "backend")]
@@ -326,6 +336,9 @@ impl From<ConditionFilter> for _inner::ConditionFilter {
         match from {
             From::SourceTracked => Self::SourceTracked,
             From::SourceUntracked => Self::SourceUntracked,
+            From::SourceNeverSynchronized => Self::SourceNeverSynchronized,
+            From::TrackTitleBlank => Self::TrackTitleBlank,
+            From::AlbumTitleBlank => Self::AlbumTitleBlank,
         }
     }
 }
@@ -337,6 +350,9 @@ impl From<_inner::ConditionFilter> for ConditionFilter {
         match from {
             From::SourceTracked => Self::SourceTracked,
             From::SourceUntracked => Self::SourceUntracked,
+            From::SourceNeverSynchronized => Self::SourceNeverSynchronized,
+            From::TrackTitleBlank => Self::TrackTitleBlank,
+            From::AlbumTitleBlank => Self::AlbumTitleBlank,
         }
     }
 }
@@ -569,6 +585,9 @@ pub enum Filter {
     DateTime(DateTimeFieldFilter),
     Condition(ConditionFilter),
     Tag(TagFilter),
+    FacetedTags(FacetedTagsPredicate),
+    FacetScoreAtLeast(FacetScoreAtLeast),
+    CustomPropertyEquals(CustomPropertyEquals),
     CueLabel(StringFilter),
     AnyTrackUid(Vec<EntityUid>),
     AnyPlaylistUid(Vec<EntityUid>),
@@ -590,6 +609,9 @@ impl From<Filter> for _inner::Filter {
             From::DateTime(from) => Self::DateTime(from.into()),
             From::Condition(from) => Self::Condition(from.into()),
             From::Tag(from) => Self::Tag(from.into()),
+            From::FacetedTags(from) => Self::FacetedTags(from.into()),
+            From::FacetScoreAtLeast(from) => Self::FacetScoreAtLeast(from.into()),
+            From::CustomPropertyEquals(from) => Self::CustomPropertyEquals(from.into()),
             From::CueLabel(from) => Self::CueLabel(from.into()),
             From::AnyTrackUid(from) => {
                 Self::AnyTrackUid(from.into_iter().map(EntityUidTyped::from_untyped).collect())
@@ -616,6 +638,9 @@ impl From<_inner::Filter> for Filter {
             From::DateTime(from) => Self::DateTime(from.into()),
             From::Condition(from) => Self::Condition(from.into()),
             From::Tag(from) => Self::Tag(from.into()),
+            From::FacetedTags(from) => Self::FacetedTags(from.into()),
+            From::FacetScoreAtLeast(from) => Self::FacetScoreAtLeast(from.into()),
+            From::CustomPropertyEquals(from) => Self::CustomPropertyEquals(from.into()),
             From::CueLabel(from) => Self::CueLabel(from.into()),
             From::AnyTrackUid(from) => {
                 Self::AnyTrackUid(from.into_iter().map(Into::into).collect())
@@ -633,7 +658,8 @@ impl From<_inner::Filter> for Filter {
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct QueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -657,10 +683,67 @@ pub struct QueryParams {
     //pub pagination: Pagination,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "frontend", derive(Serialize))]
+#[cfg_attr(feature = "backend", derive(Deserialize))]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum FieldGroup {
+    Core,
+    Tags,
+    Artwork,
+    Metrics,
+}
+
+impl From<FieldGroup> for _inner::FieldGroups {
+    fn from(from: FieldGroup) -> Self {
+        match from {
+            FieldGroup::Core => Self::CORE,
+            FieldGroup::Tags => Self::TAGS,
+            FieldGroup::Artwork => Self::ARTWORK,
+            FieldGroup::Metrics => Self::METRICS,
+        }
+    }
+}
+
+/// Convert the requested field groups.
+///
+/// All field groups are requested if `None`.
+#[must_use]
+pub fn field_groups_from(field_groups: Option<Vec<FieldGroup>>) -> _inner::FieldGroups {
+    field_groups.map_or_else(_inner::FieldGroups::all, |field_groups| {
+        field_groups
+            .into_iter()
+            .map(_inner::FieldGroups::from)
+            .collect()
+    })
+}
+
+/// Convert the requested field groups.
+///
+/// Returns `None` if all field groups are requested.
+#[must_use]
+pub fn field_groups_into(field_groups: _inner::FieldGroups) -> Option<Vec<FieldGroup>> {
+    if field_groups.is_all() {
+        return None;
+    }
+    let field_groups = [
+        FieldGroup::Core,
+        FieldGroup::Tags,
+        FieldGroup::Artwork,
+        FieldGroup::Metrics,
+    ]
+    .into_iter()
+    .filter(|field_group| field_groups.contains((*field_group).into()))
+    .collect();
+    Some(field_groups)
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "frontend", derive(Serialize))]
 #[cfg_attr(feature = "backend", derive(Deserialize))]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SearchParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -668,6 +751,69 @@ pub struct SearchParams {
 
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub ordering: Vec<SortOrder>,
+
+    /// The requested field groups
+    ///
+    /// The core fields are always included. All field groups
+    /// are included if missing.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub field_groups: Option<Vec<FieldGroup>>,
+}
+
+impl QueryParams {
+    /// Example values with and without optional fields.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                resolve_url_from_content_path: Some(true),
+                override_root_url: Some("file:///home/user/Music/".parse().expect("valid URL")),
+                encode_gigtags: Some(FacetId::new_unchecked("gigtags".into())),
+                limit: Some(100),
+                offset: Some(200),
+            },
+            Self {
+                resolve_url_from_content_path: None,
+                override_root_url: None,
+                encode_gigtags: None,
+                limit: None,
+                offset: None,
+            },
+        ]
+    }
+}
+
+impl SearchParams {
+    /// Example values with and without optional fields.
+    #[must_use]
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                filter: Some(Filter::All(vec![
+                    Filter::Phrase(PhraseFieldFilter(
+                        vec![StringField::ContentPath],
+                        vec!["house".to_owned()],
+                    )),
+                    Filter::Numeric(ScalarFieldFilter(
+                        NumericField::MusicTempoBpm,
+                        ScalarPredicate::GreaterOrEqual(120.0),
+                    )),
+                    Filter::Not(Box::new(Filter::Condition(ConditionFilter::SourceTracked))),
+                ])),
+                ordering: vec![
+                    SortOrder(SortField::MusicTempoBpm, SortDirection::Ascending),
+                    SortOrder(SortField::UpdatedAt, SortDirection::Descending),
+                ],
+                field_groups: Some(vec![FieldGroup::Core, FieldGroup::Tags]),
+            },
+            Self {
+                filter: None,
+                ordering: vec![],
+                field_groups: None,
+            },
+        ]
+    }
 }
 
 #[cfg(feature = "frontend")]
@@ -707,6 +853,7 @@ pub fn client_request_params(
     let _inner::Params {
         resolve_url_from_content_path,
         filter,
+        field_groups,
         ordering,
     } = params;
     let query_params =
@@ -714,6 +861,56 @@ pub fn client_request_params(
     let search_params = SearchParams {
         filter: filter.map(Into::into),
         ordering: ordering.into_iter().map(Into::into).collect(),
+        field_groups: field_groups_into(field_groups),
     };
     (query_params, search_params)
 }
+
+/// A page of track search results
+///
+/// The entities are `partial` if not all field groups have been requested.
+/// Partial entities still carry the revision of the complete entity and
+/// must not be written back, otherwise the omitted fields would be lost.
+#[derive(Debug)]
+#[cfg_attr(feature = "frontend", derive(serde::Deserialize))]
+#[cfg_attr(feature = "backend", derive(serde::Serialize))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct SearchResults {
+    pub items: Vec<aoide_core_json::track::Entity>,
+
+    pub offset: PaginationOffset,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<PaginationLimit>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<PaginationOffset>,
+
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub partial: bool,
+}
+
+#[cfg(feature = "backend")]
+impl SearchResults {
+    #[must_use]
+    pub fn new(
+        page: aoide_core_api::Page<aoide_core_json::track::Entity>,
+        field_groups: _inner::FieldGroups,
+    ) -> Self {
+        let crate::Page {
+            items,
+            offset,
+            limit,
+            next_offset,
+        } = crate::Page::from(page);
+        Self {
+            items,
+            offset,
+            limit,
+            next_offset,
+            partial: !field_groups.is_all(),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Round-trip all examples through JSON to detect a drift between
+//! serialization, deserialization, and the generated JSON Schema.
+//!
+//! Requires both the `frontend` and the `backend` feature for
+//! serializing and deserializing the same types.
+
+#![cfg(all(feature = "frontend", feature = "backend"))]
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use aoide_core_api_json::{
+    media::{
+        source::purge_orphaned,
+        tracker::{import_files, FsTraversalParams},
+    },
+    track::{
+        find_unsynchronized::UnsynchronizedTrackEntity,
+        search::{QueryParams, SearchParams},
+    },
+    Pagination,
+};
+
+#[cfg(feature = "json-schema")]
+fn validate_json_schema<T: schemars::JsonSchema>(json: &Value) {
+    let schema = serde_json::to_value(schemars::schema_for!(T)).unwrap();
+    let validator = jsonschema::JSONSchema::compile(&schema).unwrap();
+    let result = validator.validate(json);
+    if let Err(errors) = result {
+        let errors = errors.map(|err| err.to_string()).collect::<Vec<_>>();
+        panic!(
+            "{type_name} example {json} does not match the JSON Schema: {errors:?}",
+            type_name = std::any::type_name::<T>()
+        );
+    }
+}
+
+#[cfg(not(feature = "json-schema"))]
+fn validate_json_schema<T>(_json: &Value) {}
+
+/// The types don't implement [`PartialEq`]. Instead the deserialized
+/// value is serialized again and compared with the initial JSON.
+fn round_trip<T: Serialize + DeserializeOwned>(example: &T) -> Value {
+    let json = serde_json::to_value(example).unwrap();
+    let deserialized = serde_json::from_value::<T>(json.clone()).unwrap_or_else(|err| {
+        panic!(
+            "failed to deserialize {type_name} example {json}: {err}",
+            type_name = std::any::type_name::<T>()
+        )
+    });
+    assert_eq!(json, serde_json::to_value(&deserialized).unwrap());
+    json
+}
+
+macro_rules! assert_examples {
+    ($type:ty) => {{
+        let examples = <$type>::examples();
+        // Examples should cover optional fields both present and absent
+        assert!(examples.len() > 1);
+        for example in &examples {
+            let json = round_trip(example);
+            validate_json_schema::<$type>(&json);
+        }
+    }};
+}
+
+#[test]
+fn round_trip_examples() {
+    assert_examples!(Pagination);
+    assert_examples!(QueryParams);
+    assert_examples!(SearchParams);
+    assert_examples!(UnsynchronizedTrackEntity);
+    assert_examples!(FsTraversalParams);
+    assert_examples!(import_files::Params);
+    assert_examples!(import_files::Outcome);
+    assert_examples!(purge_orphaned::Params);
+    assert_examples!(purge_orphaned::Outcome);
+}
+
+#[test]
+fn omit_absent_optional_fields() {
+    let [full, empty] = <[_; 2]>::try_from(Pagination::examples()).unwrap();
+    assert_eq!(
+        serde_json::json!({ "limit": 100, "offset": 200 }),
+        round_trip(&full)
+    );
+    assert_eq!(serde_json::json!({}), round_trip(&empty));
+}
@@ -1,7 +1,12 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core::collection::{Collection, Entity};
+use std::{fmt, time::Duration};
+
+use aoide_core::{
+    collection::{Collection, Entity},
+    util::color::Color,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoadScope {
@@ -21,10 +26,60 @@ impl MediaSourceSummary {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TrackSummary {
     pub total_count: u64,
+
+    /// The total play time of all tracks with a known duration
+    pub total_duration: Duration,
+
+    /// The total size of all tracks with a known content size
+    pub total_bytes: u64,
 }
 
 impl TrackSummary {
-    pub const EMPTY: Self = Self { total_count: 0 };
+    pub const EMPTY: Self = Self {
+        total_count: 0,
+        total_duration: Duration::ZERO,
+        total_bytes: 0,
+    };
+}
+
+const BYTE_UNITS: [&str; 5] = ["kB", "MB", "GB", "TB", "PB"];
+
+/// Formats the summary for display, e.g. `3 tracks, 1:02:03, 45.6 MB`.
+impl fmt::Display for TrackSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            total_count,
+            total_duration,
+            total_bytes,
+        } = self;
+        let total_secs = total_duration.as_secs();
+        let hours = total_secs / 3600;
+        let minutes = total_secs / 60 % 60;
+        let seconds = total_secs % 60;
+        let tracks = if *total_count == 1 { "track" } else { "tracks" };
+        write!(
+            f,
+            "{total_count} {tracks}, {hours}:{minutes:02}:{seconds:02}, "
+        )?;
+        if *total_bytes < 1000 {
+            return write!(f, "{total_bytes} B");
+        }
+        // Decimal units with a single, truncated fractional digit
+        let mut unit_index = 0;
+        let mut unit_bytes = 1000;
+        while unit_index + 1 < BYTE_UNITS.len() && *total_bytes / unit_bytes >= 1000 {
+            unit_index += 1;
+            unit_bytes *= 1000;
+        }
+        let tenths = *total_bytes / (unit_bytes / 10);
+        write!(
+            f,
+            "{integral}.{fractional} {unit}",
+            integral = tenths / 10,
+            fractional = tenths % 10,
+            unit = BYTE_UNITS[unit_index]
+        )
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -72,3 +127,58 @@ pub struct EntityWithSummary {
     pub entity: Entity,
     pub summary: Option<Summary>,
 }
+
+/// Partial update of a collection
+///
+/// Fields that are `None` are left unchanged. Optional fields
+/// of the collection are reset by `Some(None)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch {
+    pub title: Option<String>,
+    pub kind: Option<Option<String>>,
+    pub color: Option<Option<Color>>,
+    pub notes: Option<Option<String>>,
+}
+
+impl Patch {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        let Self {
+            title,
+            kind,
+            color,
+            notes,
+        } = self;
+        title.is_none() && kind.is_none() && color.is_none() && notes.is_none()
+    }
+
+    /// Apply the patch
+    ///
+    /// Returns `true` if the collection has been modified.
+    pub fn apply(self, collection: &mut Collection) -> bool {
+        let Self {
+            title,
+            kind,
+            color,
+            notes,
+        } = self;
+        let mut modified = false;
+        if let Some(title) = title {
+            modified |= collection.title != title;
+            collection.title = title;
+        }
+        if let Some(kind) = kind {
+            modified |= collection.kind != kind;
+            collection.kind = kind;
+        }
+        if let Some(color) = color {
+            modified |= collection.color != color;
+            collection.color = color;
+        }
+        if let Some(notes) = notes {
+            modified |= collection.notes != notes;
+            collection.notes = notes;
+        }
+        modified
+    }
+}
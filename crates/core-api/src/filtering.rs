@@ -3,7 +3,10 @@
 
 use std::borrow::Cow;
 
-use aoide_core::util::clock::OffsetDateTimeMs;
+use aoide_core::{
+    tag::{FacetId, FacetKey, Label, ScoreValue},
+    util::clock::OffsetDateTimeMs,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FilterModifier {
@@ -137,3 +140,53 @@ pub type NumericValue = f64;
 pub type NumericPredicate = ScalarPredicate<NumericValue>;
 
 pub type DateTimePredicate = ScalarPredicate<OffsetDateTimeMs>;
+
+/// Predicate for the labels of a single facet
+///
+/// Matches if a tag with the given facet and any of the given labels
+/// exists, i.e. labels are combined by OR. An empty list of labels
+/// matches any tag with the given facet.
+///
+/// The complement matches if no such tag exists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FacetLabelsPredicate {
+    pub modifier: Option<FilterModifier>,
+    pub facet: FacetKey<'static>,
+    pub any_of: Vec<Label<'static>>,
+}
+
+/// Predicate for faceted tags
+///
+/// Matches if all of the given facet predicates match, i.e. facets are
+/// combined by AND. An empty list matches everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FacetedTagsPredicate {
+    pub all_of: Vec<FacetLabelsPredicate>,
+}
+
+/// Predicate for the scores of a single facet
+///
+/// Matches if a tag with the given facet and a score of at least
+/// `min` exists, e.g. `energy >= 0.7`. Multiple thresholds are
+/// combined by the enclosing filter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FacetScoreAtLeast {
+    pub facet_id: FacetId<'static>,
+    pub min: ScoreValue,
+}
+
+/// Predicate for a custom property
+///
+/// Matches if a custom property with the given key exists and
+/// its value is equal to `value`. Values are compared as strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomPropertyEquals {
+    pub key: String,
+    pub value: String,
+}
+
+/// The maximum nesting depth of the boolean combinations of filters
+///
+/// Protects against pathological input that would otherwise result
+/// in deep recursion and excessively complex database queries.
+pub const MAX_NESTING_DEPTH: usize = 32;
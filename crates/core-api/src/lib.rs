@@ -65,3 +65,78 @@ impl Pagination {
         self.limit.unwrap_or(PaginationLimit::MAX)
     }
 }
+
+/// A page of items that have been loaded with [`Pagination`]
+///
+/// The continuation for loading the next page is only available if
+/// the page has been filled up to the requested limit. The total
+/// number of available items is unknown and the next page might
+/// still turn out to be empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// The offset of the first item.
+    pub offset: PaginationOffset,
+
+    /// The requested limit, if any.
+    pub limit: Option<PaginationLimit>,
+
+    /// The continuation for loading the next page, if any.
+    pub next: Option<Pagination>,
+}
+
+impl<T> Page<T> {
+    #[must_use]
+    pub fn new(items: Vec<T>, pagination: &Pagination) -> Self {
+        let offset = pagination.mandatory_offset();
+        let limit = pagination.limit;
+        let next = limit.and_then(|limit| {
+            let len = items.len() as PaginationLimit;
+            debug_assert!(len <= limit);
+            (limit > 0 && len >= limit).then(|| Pagination {
+                limit: Some(limit),
+                offset: Some(offset.saturating_add(len)),
+            })
+        });
+        Self {
+            items,
+            offset,
+            limit,
+            next,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_last(&self) -> bool {
+        self.next.is_none()
+    }
+
+    #[must_use]
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        let Self {
+            items,
+            offset,
+            limit,
+            next,
+        } = self;
+        Page {
+            items: items.into_iter().map(f).collect(),
+            offset,
+            limit,
+            next,
+        }
+    }
+}
+
+impl<T> IntoIterator for Page<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -5,7 +5,46 @@ use aoide_core::{media::content::ContentPath, util::url::BaseUrl};
 
 use super::Completion;
 
-pub type Params = super::FsTraversalParams;
+/// File name patterns that are ignored by default
+///
+/// Hidden files and directories as well as common junk files
+/// like incomplete downloads or metadata of file managers.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    ".*",
+    "*.part",
+    "*.crdownload",
+    "*.tmp",
+    "Thumbs.db",
+    "desktop.ini",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    pub root_url: Option<BaseUrl>,
+    pub excluded_paths: Vec<ContentPath<'static>>,
+    pub max_depth: Option<usize>,
+
+    /// Ignore file and directory names that match any of these patterns
+    ///
+    /// Patterns are matched case-insensitive against the individual
+    /// components of content paths. The wildcard `*` matches any
+    /// sequence of characters and `?` matches a single character.
+    ///
+    /// [`DEFAULT_IGNORE_PATTERNS`] are used if `None`. An empty list
+    /// doesn't ignore anything.
+    pub ignore_patterns: Option<Vec<String>>,
+}
+
+impl Params {
+    /// The effective ignore patterns
+    #[must_use]
+    pub fn ignore_patterns(&self) -> Vec<&str> {
+        self.ignore_patterns.as_ref().map_or_else(
+            || DEFAULT_IGNORE_PATTERNS.to_vec(),
+            |patterns| patterns.iter().map(String::as_str).collect(),
+        )
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Outcome {
@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{num::NonZeroUsize, ops::AddAssign};
+
+use aoide_core::{media::content::ContentPath, util::url::BaseUrl};
+
+use super::Completion;
+use crate::{media::SyncMode, track::replace::Summary as TrackReplaceSummary};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub root_url: Option<BaseUrl>,
+    pub sync_mode: SyncMode,
+
+    /// Compute the digests of the audio content of imported files
+    /// with the given number of worker threads.
+    ///
+    /// No digests are computed if `None`.
+    pub content_digest_workers: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedSourceWithIssues {
+    pub path: ContentPath<'static>,
+    pub messages: Vec<String>,
+}
+
+/// Category of issues while importing a media source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IssueKind {
+    MissingArtwork,
+    MalformedDate,
+    UnsupportedTag,
+    Other,
+}
+
+/// Maximum number of sample paths per [`IssueGroup`]
+pub const ISSUE_GROUP_SAMPLE_PATHS_LIMIT: usize = 5;
+
+/// Imported media sources with issues of the same kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueGroup {
+    pub kind: IssueKind,
+
+    /// The number of affected media sources
+    pub count: usize,
+
+    /// The paths of the first affected media sources
+    ///
+    /// Contains at most [`ISSUE_GROUP_SAMPLE_PATHS_LIMIT`] paths.
+    pub sample_paths: Vec<ContentPath<'static>>,
+}
+
+/// Issues of all imported media sources grouped by kind
+///
+/// The groups are ordered by kind.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IssueSummary {
+    pub groups: Vec<IssueGroup>,
+}
+
+impl IssueSummary {
+    /// Add the issues of an imported media source
+    ///
+    /// Each media source is only counted once per kind, even if
+    /// multiple issues of the same kind occurred.
+    pub fn add_source(
+        &mut self,
+        path: &ContentPath<'static>,
+        kinds: impl IntoIterator<Item = IssueKind>,
+    ) {
+        let mut kinds = kinds.into_iter().collect::<Vec<_>>();
+        kinds.sort_unstable();
+        kinds.dedup();
+        for kind in kinds {
+            let index = match self.groups.binary_search_by_key(&kind, |group| group.kind) {
+                Ok(index) => index,
+                Err(index) => {
+                    self.groups.insert(
+                        index,
+                        IssueGroup {
+                            kind,
+                            count: 0,
+                            sample_paths: Vec::new(),
+                        },
+                    );
+                    index
+                }
+            };
+            let group = &mut self.groups[index];
+            group.count += 1;
+            if group.sample_paths.len() < ISSUE_GROUP_SAMPLE_PATHS_LIMIT {
+                group.sample_paths.push(path.clone());
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub root_url: BaseUrl,
+    pub root_path: ContentPath<'static>,
+    pub completion: Completion,
+    pub summary: Summary,
+    pub imported_sources_with_issues: Vec<ImportedSourceWithIssues>,
+    pub issue_summary: IssueSummary,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub tracks: TrackSummary,
+    pub directories: DirectorySummary,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TrackSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub not_imported: usize,
+    pub not_created: usize,
+    pub not_updated: usize,
+}
+
+impl AddAssign<&TrackReplaceSummary> for TrackSummary {
+    fn add_assign(&mut self, rhs: &TrackReplaceSummary) {
+        let Self {
+            created,
+            updated,
+            unchanged,
+            skipped,
+            failed,
+            not_imported,
+            not_created,
+            not_updated,
+        } = self;
+        let TrackReplaceSummary {
+            created: rhs_created,
+            updated: rhs_updated,
+            unchanged: rhs_unchanged,
+            skipped: rhs_skipped,
+            failed: rhs_failed,
+            not_imported: rhs_not_imported,
+            not_created: rhs_not_created,
+            not_updated: rhs_not_updated,
+        } = rhs;
+        *created += rhs_created.len();
+        *updated += rhs_updated.len();
+        *unchanged += rhs_unchanged.len();
+        *skipped += rhs_skipped.len();
+        *failed += rhs_failed.len();
+        *not_imported += rhs_not_imported.len();
+        *not_created += rhs_not_created.len();
+        *not_updated += rhs_not_updated.len();
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DirectorySummary {
+    pub confirmed: usize,
+    pub skipped: usize,
+    pub untracked: usize,
+}
+
+#[cfg(test)]
+mod tests;
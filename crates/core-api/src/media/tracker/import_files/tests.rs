@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+fn content_path(index: usize) -> ContentPath<'static> {
+    ContentPath::from(format!("Album/Track{index}.mp3"))
+}
+
+#[test]
+fn group_mixed_issues_by_kind() {
+    let mut summary = IssueSummary::default();
+    for index in 0..10 {
+        let kinds = if index % 2 == 0 {
+            vec![IssueKind::MissingArtwork, IssueKind::MalformedDate]
+        } else {
+            vec![IssueKind::MissingArtwork]
+        };
+        summary.add_source(&content_path(index), kinds);
+    }
+    summary.add_source(
+        &content_path(10),
+        [
+            IssueKind::UnsupportedTag,
+            IssueKind::Other,
+            // Multiple issues of the same kind are only counted once.
+            IssueKind::UnsupportedTag,
+        ],
+    );
+
+    let groups = summary
+        .groups
+        .iter()
+        .map(|group| (group.kind, group.count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (IssueKind::MissingArtwork, 10),
+            (IssueKind::MalformedDate, 5),
+            (IssueKind::UnsupportedTag, 1),
+            (IssueKind::Other, 1),
+        ],
+        groups
+    );
+
+    // Only the first paths are kept as samples.
+    assert_eq!(
+        (0..ISSUE_GROUP_SAMPLE_PATHS_LIMIT)
+            .map(content_path)
+            .collect::<Vec<_>>(),
+        summary.groups[0].sample_paths
+    );
+    assert_eq!(
+        [0, 2, 4, 6, 8].map(content_path).to_vec(),
+        summary.groups[1].sample_paths
+    );
+    assert_eq!(vec![content_path(10)], summary.groups[2].sample_paths);
+    assert_eq!(vec![content_path(10)], summary.groups[3].sample_paths);
+}
+
+#[test]
+fn sources_without_issues_are_ignored() {
+    let mut summary = IssueSummary::default();
+    summary.add_source(&content_path(0), []);
+    assert!(summary.is_empty());
+}
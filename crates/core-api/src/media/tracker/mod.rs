@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Duration;
+
 use aoide_core::{media::content::ContentPath, util::url::BaseUrl};
 use strum::FromRepr;
 
@@ -79,8 +81,38 @@ pub enum Progress {
     FindingUntracked(FsTraversalProgress),
 }
 
+impl Progress {
+    /// The current phase or `None` if idle.
+    #[must_use]
+    pub const fn phase(&self) -> Option<ProgressPhase> {
+        match self {
+            Self::Idle => None,
+            Self::Scanning(progress) | Self::FindingUntracked(progress) => Some(progress.phase),
+            Self::Importing(_) => Some(ProgressPhase::ImportingFiles),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressPhase {
+    /// Traversing the directory tree
+    #[default]
+    ScanningDirectories,
+
+    /// Storing the computed digest of a visited directory
+    DigestingDirectories,
+
+    /// Importing the files of pending directories
+    ImportingFiles,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct FsTraversalProgress {
+    pub phase: ProgressPhase,
+
+    /// The directory that is currently processed, if known
+    pub current_path: Option<ContentPath<'static>>,
+
     pub entries: FsTraversalEntriesProgress,
     pub directories: FsTraversalDirectoriesProgress,
 }
@@ -96,4 +128,17 @@ pub struct FsTraversalDirectoriesProgress {
     pub finished: usize,
 }
 
-pub type ImportingProgress = import_files::Summary;
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportingProgress {
+    /// The directory or file that is currently imported, if any
+    pub current_path: Option<ContentPath<'static>>,
+
+    /// The estimated remaining time
+    ///
+    /// Extrapolated from the throughput of the directories that
+    /// have been processed so far. Unknown until the first pending
+    /// directory has been processed.
+    pub estimated_remaining: Option<Duration>,
+
+    pub summary: import_files::Summary,
+}
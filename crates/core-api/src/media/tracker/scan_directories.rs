@@ -22,4 +22,12 @@ pub struct Outcome {
     pub root_path: ContentPath<'static>,
     pub completion: Completion,
     pub summary: Summary,
+
+    /// Entries that have been skipped while scanning, e.g. directories
+    /// that have already been visited through a symlink or entries
+    /// that could not be accessed.
+    ///
+    /// The scan is considered incomplete and finishes as
+    /// [`Completion::Aborted`] if any entries have been skipped.
+    pub skipped_paths: Vec<ContentPath<'static>>,
 }
@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+#[test]
+fn page_without_limit_is_last() {
+    let page = Page::new(vec![1, 2, 3], &Pagination::new());
+    assert_eq!(0, page.offset);
+    assert!(page.limit.is_none());
+    assert!(page.next.is_none());
+    assert!(page.is_last());
+}
+
+#[test]
+fn partially_filled_page_is_last() {
+    let pagination = Pagination {
+        limit: Some(3),
+        offset: Some(6),
+    };
+    let page = Page::new(vec![7, 8], &pagination);
+    assert_eq!(6, page.offset);
+    assert_eq!(Some(3), page.limit);
+    assert!(page.next.is_none());
+}
+
+#[test]
+fn empty_page_is_last() {
+    let pagination = Pagination {
+        limit: Some(3),
+        offset: Some(9),
+    };
+    let page = Page::<i32>::new(vec![], &pagination);
+    assert!(page.is_last());
+}
+
+#[test]
+fn filled_page_continues_with_next() {
+    let pagination = Pagination {
+        limit: Some(3),
+        offset: None,
+    };
+    let page = Page::new(vec![1, 2, 3], &pagination);
+    assert_eq!(
+        Some(Pagination {
+            limit: Some(3),
+            offset: Some(3),
+        }),
+        page.next
+    );
+    let next_page = Page::new(vec![4, 5, 6], page.next.as_ref().unwrap());
+    assert_eq!(3, next_page.offset);
+    assert_eq!(
+        Some(Pagination {
+            limit: Some(3),
+            offset: Some(6),
+        }),
+        next_page.next
+    );
+}
+
+#[test]
+fn map_page_items() {
+    let pagination = Pagination {
+        limit: Some(2),
+        offset: Some(2),
+    };
+    let page = Page::new(vec![1, 2], &pagination).map(|item| item * 10);
+    assert_eq!(vec![10, 20], page.items);
+    assert_eq!(2, page.offset);
+    assert_eq!(pagination.limit, page.next.unwrap().limit);
+}
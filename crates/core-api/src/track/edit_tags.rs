@@ -0,0 +1,41 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aoide_core::{
+    tag::{FacetId, Label, PlainTag},
+    track::Entity,
+    TrackUid,
+};
+
+/// An edit operation that is applied to the tags of tracks.
+///
+/// The facet is `None` for editing plain tags.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagEdit {
+    /// Add tags unless a tag with the same label already exists.
+    Add {
+        facet_id: Option<FacetId<'static>>,
+        tags: Vec<PlainTag<'static>>,
+    },
+
+    /// Remove all tags with one of the given labels.
+    Remove {
+        facet_id: Option<FacetId<'static>>,
+        labels: Vec<Label<'static>>,
+    },
+
+    /// Replace all existing tags.
+    Replace {
+        facet_id: Option<FacetId<'static>>,
+        tags: Vec<PlainTag<'static>>,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    pub updated: Vec<Entity>,
+    pub unchanged: Vec<TrackUid>,
+
+    /// Tracks that could not be edited, e.g. because they don't exist.
+    pub failed: Vec<TrackUid>,
+}
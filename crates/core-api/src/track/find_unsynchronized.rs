@@ -9,12 +9,30 @@ use crate::{filtering::StringPredicate, media::source::ResolveUrlFromContentPath
 pub struct Params {
     pub resolve_url_from_content_path: Option<ResolveUrlFromContentPath>,
     pub content_path_predicate: Option<StringPredicate<'static>>,
+
+    /// Check if the files of unsynchronized tracks still exist.
+    ///
+    /// Only applicable for collections with local files.
+    pub check_file_exists: bool,
+}
+
+/// Existence of the file of an unsynchronized track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file exists, i.e. the track needs to be re-imported.
+    Present,
+
+    /// The file doesn't exist (anymore), i.e. the track is orphaned.
+    Missing,
 }
 
 #[derive(Debug, Clone)]
 pub struct UnsynchronizedTrack {
     pub content_link: ContentLink,
     pub last_synchronized_rev: Option<EntityRevision>,
+
+    /// `None` if not checked or unknown.
+    pub file_status: Option<FileStatus>,
 }
 
 pub type UnsynchronizedTrackEntity = Entity<track::EntityType, UnsynchronizedTrack, ()>;
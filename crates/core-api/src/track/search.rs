@@ -8,14 +8,16 @@ use aoide_core::{
         title::Kind as TitleKind,
     },
     util::clock::{DateOrDateTime, OffsetDateTimeMs},
-    PlaylistUid, TrackUid,
+    PlaylistUid, Track, TrackUid,
 };
+use bitflags::bitflags;
 use strum::FromRepr;
 
 use crate::{
     filtering::{
-        DateTimePredicate, FilterModifier, NumericPredicate, NumericValue, ScalarFieldFilter,
-        StringFilter, StringPredicate,
+        CustomPropertyEquals, DateTimePredicate, FacetScoreAtLeast, FacetedTagsPredicate,
+        FilterModifier, NumericPredicate, NumericValue, ScalarFieldFilter, StringFilter,
+        StringPredicate,
     },
     media::source::ResolveUrlFromContentPath,
     tag, SortDirection,
@@ -64,6 +66,14 @@ pub enum DateTimeField {
 pub enum ConditionFilter {
     SourceTracked,
     SourceUntracked,
+    /// The track has never been synchronized with its media source.
+    SourceNeverSynchronized,
+    /// The track has no main title or only a main title that is
+    /// either empty or consists only of whitespace.
+    TrackTitleBlank,
+    /// The track has no main album title or only a main album title
+    /// that is either empty or consists only of whitespace.
+    AlbumTitleBlank,
 }
 
 pub type NumericFieldFilter = ScalarFieldFilter<NumericField, NumericValue>;
@@ -129,6 +139,8 @@ pub struct TitlePhraseFilter {
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SortField {
+    /// The main album title
+    AlbumTitle,
     AudioBitrateBps,
     AudioChannelCount,
     AudioChannelMask,
@@ -168,6 +180,9 @@ pub enum Filter {
     DateTime(DateTimeFieldFilter),
     Condition(ConditionFilter),
     Tag(tag::search::Filter),
+    FacetedTags(FacetedTagsPredicate),
+    FacetScoreAtLeast(FacetScoreAtLeast),
+    CustomPropertyEquals(CustomPropertyEquals),
     CueLabel(StringFilter<'static>),
     AnyTrackUid(Vec<TrackUid>),
     AnyPlaylistUid(Vec<PlaylistUid>),
@@ -177,6 +192,20 @@ pub enum Filter {
 }
 
 impl Filter {
+    /// The nesting depth of boolean combinations, i.e. 0 for a single filter.
+    #[must_use]
+    pub fn nesting_depth(&self) -> usize {
+        match self {
+            Self::All(filters) | Self::Any(filters) => filters
+                .iter()
+                .map(|filter| filter.nesting_depth() + 1)
+                .max()
+                .unwrap_or(1),
+            Self::Not(filter) => filter.nesting_depth() + 1,
+            _ => 0,
+        }
+    }
+
     #[must_use]
     pub fn recorded_at_equals(recorded_at: DateOrDateTime) -> Self {
         match recorded_at {
@@ -219,6 +248,28 @@ impl Filter {
         }
     }
 
+    /// Filter by the half-open window `[since, until)` of the
+    /// collection time stamp.
+    #[must_use]
+    pub fn collected_at_window(
+        since: Option<OffsetDateTimeMs>,
+        until: Option<OffsetDateTimeMs>,
+    ) -> Self {
+        let since = since.map(|since| {
+            Self::DateTime(DateTimeFieldFilter {
+                field: DateTimeField::CollectedAt,
+                predicate: DateTimePredicate::GreaterOrEqual(since),
+            })
+        });
+        let until = until.map(|until| {
+            Self::DateTime(DateTimeFieldFilter {
+                field: DateTimeField::CollectedAt,
+                predicate: DateTimePredicate::LessThan(until),
+            })
+        });
+        Self::All(since.into_iter().chain(until).collect())
+    }
+
     #[must_use]
     pub fn audio_duration_around(duration: DurationMs, epsilon: DurationMs) -> Self {
         debug_assert!(duration.is_valid());
@@ -247,9 +298,56 @@ impl Filter {
     }
 }
 
+bitflags! {
+    /// Groups of track fields that are requested
+    ///
+    /// The core fields are always included, including the media source
+    /// without its artwork, the titles, actors, album, indexes, and cues.
+    /// Unrequested groups are omitted, i.e. they are empty.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct FieldGroups: u8 {
+        const CORE = 1 << 0;
+        const TAGS = 1 << 1;
+        const ARTWORK = 1 << 2;
+        const METRICS = 1 << 3;
+    }
+}
+
+impl Default for FieldGroups {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FieldGroups {
+    /// Clear all fields of unrequested groups
+    pub fn omit_unrequested(self, track: &mut Track) {
+        if !self.contains(Self::TAGS) {
+            track.tags = Default::default();
+        }
+        if !self.contains(Self::ARTWORK) {
+            track.media_source.artwork = None;
+        }
+        if !self.contains(Self::METRICS) {
+            track.metrics = Default::default();
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Params {
     pub resolve_url_from_content_path: Option<ResolveUrlFromContentPath>,
     pub filter: Option<Filter>,
+
+    /// All field groups are included by default.
+    pub field_groups: FieldGroups,
+
+    /// Sort keys in order of precedence.
+    ///
+    /// Each key has its own direction. Results that tie on a key
+    /// are sorted by the next key. Results that tie on all keys
+    /// are returned in their insertion order, i.e. the order is
+    /// always deterministic. Missing values are sorted before
+    /// all present values in ascending order.
     pub ordering: Vec<SortOrder>,
 }
@@ -1,6 +1,10 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::fmt;
+
+use serde::{de, Deserializer, Serializer};
+
 use crate::prelude::*;
 
 mod _core {
@@ -9,6 +13,75 @@ mod _core {
 
 pub use aoide_core::{EntityRevision, EntityUid};
 
+///////////////////////////////////////////////////////////////////////
+// EntityRevision
+///////////////////////////////////////////////////////////////////////
+
+/// Serialize an [`EntityRevision`] as an opaque string.
+///
+/// Intended for use with `#[serde(serialize_with = "...")]`.
+pub fn serialize_entity_revision_opaque<S>(
+    rev: &EntityRevision,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&rev.encode_opaque())
+}
+
+struct EntityRevisionDeserializeVisitor;
+
+impl de::Visitor<'_> for EntityRevisionDeserializeVisitor {
+    type Value = EntityRevision;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "entity revision as an unsigned integer or an opaque string"
+        )
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(EntityRevision::new_unchecked(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        u64::try_from(value)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            .and_then(|value| self.visit_u64(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        value
+            .parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+/// Deserialize an [`EntityRevision`] from either its numeric or its
+/// opaque string representation.
+///
+/// Numbers are also accepted as strings, e.g. when parsing query
+/// parameters. Intended for use with `#[serde(deserialize_with = "...")]`.
+pub fn deserialize_entity_revision_number_or_opaque<'de, D>(
+    deserializer: D,
+) -> Result<EntityRevision, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(EntityRevisionDeserializeVisitor)
+}
+
 ///////////////////////////////////////////////////////////////////////
 // EntityHeader
 ///////////////////////////////////////////////////////////////////////
@@ -52,3 +125,6 @@ impl<T> From<_core::EntityHeaderTyped<T>> for EntityHeader {
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Entity<B>(pub EntityHeader, pub B);
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,45 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct Rev {
+    #[serde(
+        serialize_with = "serialize_entity_revision_opaque",
+        deserialize_with = "deserialize_entity_revision_number_or_opaque"
+    )]
+    rev: EntityRevision,
+}
+
+#[test]
+fn opaque_entity_revision_roundtrip() {
+    let rev = Rev {
+        rev: EntityRevision::new_unchecked(12_345),
+    };
+    let json = serde_json::to_value(&rev).unwrap();
+    assert_eq!(serde_json::json!({"rev": "r9ix"}), json);
+    assert_eq!(rev, serde_json::from_value(json).unwrap());
+}
+
+#[test]
+fn deserialize_entity_revision_from_number_or_opaque() {
+    let expected = Rev {
+        rev: EntityRevision::new_unchecked(42),
+    };
+    for json in [
+        serde_json::json!({"rev": 42}),
+        serde_json::json!({"rev": "42"}),
+        serde_json::json!({"rev": "r16"}),
+    ] {
+        assert_eq!(expected, serde_json::from_value(json).unwrap());
+    }
+    for json in [
+        serde_json::json!({"rev": -1}),
+        serde_json::json!({"rev": 4.2}),
+        serde_json::json!({"rev": "R16"}),
+        serde_json::json!({"rev": "x"}),
+    ] {
+        assert!(serde_json::from_value::<Rev>(json).is_err());
+    }
+}
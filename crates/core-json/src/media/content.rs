@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aoide_core::{
-    audio::{ChannelFlags, Channels},
+    audio::{ChannelFlags, Channels, WaveformOverview, WaveformPeak},
     media::content::{ContentPath, VirtualFilePathConfig},
     util::url::BaseUrl,
 };
@@ -20,7 +20,8 @@ mod _core {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ContentLink {
     pub path: String,
 
@@ -86,7 +87,8 @@ impl From<ContentPathKind> for _core::ContentPathKind {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ContentPathConfig {
     pub path_kind: ContentPathKind,
 
@@ -171,7 +173,8 @@ impl From<_core::ContentMetadata> for ContentMetadata {
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AudioContentMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     duration_ms: Option<DurationMs>,
@@ -193,6 +196,9 @@ pub struct AudioContentMetadata {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     encoder: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    waveform_overview: Option<Vec<WaveformPeak>>,
 }
 
 impl From<AudioContentMetadata> for _core::AudioContentMetadata {
@@ -205,6 +211,7 @@ impl From<AudioContentMetadata> for _core::AudioContentMetadata {
             bitrate_bps,
             loudness_lufs,
             encoder,
+            waveform_overview,
         } = from;
         let channel_flags = channel_mask.map(ChannelFlags::from_bits_truncate);
         let channels = Channels::try_from_flags_or_count(channel_flags, channel_count);
@@ -215,6 +222,7 @@ impl From<AudioContentMetadata> for _core::AudioContentMetadata {
             bitrate: bitrate_bps.map(Into::into),
             loudness: loudness_lufs.map(Into::into),
             encoder: encoder.map(Into::into),
+            waveform_overview: waveform_overview.map(WaveformOverview::new),
         }
     }
 }
@@ -228,6 +236,7 @@ impl From<_core::AudioContentMetadata> for AudioContentMetadata {
             bitrate,
             loudness,
             encoder,
+            waveform_overview,
         } = from;
         Self {
             duration_ms: duration.map(Into::into),
@@ -240,6 +249,7 @@ impl From<_core::AudioContentMetadata> for AudioContentMetadata {
             bitrate_bps: bitrate.map(Into::into),
             loudness_lufs: loudness.map(Into::into),
             encoder: encoder.map(Into::into),
+            waveform_overview: waveform_overview.map(WaveformOverview::into_peaks),
         }
     }
 }
@@ -107,7 +107,8 @@ fn is_default_content_metadata_flags(flags: &u8) -> bool {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Content {
     link: ContentLink,
 
@@ -117,6 +118,9 @@ pub struct Content {
     #[serde(skip_serializing_if = "Option::is_none")]
     digest: Option<Digest>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+
     #[serde(flatten)]
     metadata: ContentMetadata,
 
@@ -130,6 +134,7 @@ impl From<_core::Content> for Content {
             link,
             r#type,
             digest,
+            size,
             metadata,
             metadata_flags,
         } = from;
@@ -137,6 +142,7 @@ impl From<_core::Content> for Content {
             link: link.into(),
             r#type: r#type.to_string(),
             digest: digest.map(Into::into),
+            size,
             metadata: metadata.into(),
             metadata_flags: metadata_flags.bits(),
         }
@@ -151,6 +157,7 @@ impl TryFrom<Content> for _core::Content {
             link,
             r#type,
             digest,
+            size,
             metadata,
             metadata_flags,
         } = from;
@@ -160,6 +167,7 @@ impl TryFrom<Content> for _core::Content {
             link: link.into(),
             r#type,
             digest,
+            size,
             metadata: metadata.into(),
             metadata_flags: ContentMetadataFlags::from_bits_truncate(metadata_flags),
         };
@@ -170,7 +178,8 @@ impl TryFrom<Content> for _core::Content {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Source {
     collected_at: DateTime,
 
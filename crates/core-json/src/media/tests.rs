@@ -36,6 +36,7 @@ fn deserialize_audio_source() {
             },
             "type": "audio/mpeg",
             "digest": "aGVsbG8gaW50ZXJuZXR-Cg",
+            "size": 12345,
             "audio": {},
         },
     })
@@ -58,9 +59,43 @@ fn deserialize_audio_source() {
                 digest: Digest::from_encoded("aGVsbG8gaW50ZXJuZXR-Cg")
                     .try_decode()
                     .ok(),
+                size: Some(12345),
             },
             artwork: None,
         },
         source
     );
 }
+
+fn audio_source_json_with_unknown_field() -> String {
+    serde_json::json!({
+        "collectedAt": OffsetDateTimeMs::now_local(),
+        "content": {
+            "link": {
+                "path": "/home/test file.mp3",
+                "unknownField": "value",
+            },
+            "type": "audio/mpeg",
+            "audio": {},
+        },
+        "unknownField": "value",
+    })
+    .to_string()
+}
+
+#[cfg(not(feature = "lenient"))]
+#[test]
+fn reject_unknown_fields() {
+    assert!(serde_json::from_str::<Source>(&audio_source_json_with_unknown_field()).is_err());
+}
+
+#[cfg(feature = "lenient")]
+#[test]
+fn ignore_unknown_fields() {
+    let source: _core::Source =
+        serde_json::from_str::<Source>(&audio_source_json_with_unknown_field())
+            .unwrap()
+            .try_into()
+            .unwrap();
+    assert_eq!("/home/test file.mp3", source.content.link.path.as_str());
+}
@@ -14,7 +14,8 @@ mod _core {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(untagged, deny_unknown_fields)]
+#[serde(untagged)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub enum TimeSignature {
     Top(_core::BeatNumber),
     TopBottom(_core::BeatNumber, _core::BeatNumber),
@@ -3,7 +3,12 @@
 
 use aoide_core::{playlist::Flags, TrackUid};
 
-use crate::{entity::EntityUid, prelude::*, util::clock::DateTime};
+use crate::{
+    audio::{DurationMs, PositionMs},
+    entity::EntityUid,
+    prelude::*,
+    util::clock::DateTime,
+};
 
 mod _core {
     pub(super) use aoide_core::playlist::*;
@@ -16,7 +21,8 @@ mod _core {
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SeparatorItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) kind: Option<String>,
@@ -37,33 +43,62 @@ impl From<_core::SeparatorItem> for SeparatorItem {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Eq))]
+#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct TrackItem {
     pub(crate) uid: EntityUid,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cue_in_ms: Option<PositionMs>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cue_out_ms: Option<PositionMs>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) crossfade_ms: Option<DurationMs>,
 }
 
 impl From<TrackItem> for _core::TrackItem {
     fn from(from: TrackItem) -> Self {
-        let TrackItem { uid } = from;
+        let TrackItem {
+            uid,
+            cue_in_ms,
+            cue_out_ms,
+            crossfade_ms,
+        } = from;
         Self {
             uid: TrackUid::from_untyped(uid),
+            cue_in: cue_in_ms,
+            cue_out: cue_out_ms,
+            crossfade: crossfade_ms,
         }
     }
 }
 
 impl From<_core::TrackItem> for TrackItem {
     fn from(from: _core::TrackItem) -> Self {
-        let _core::TrackItem { uid } = from;
-        Self { uid: uid.into() }
+        let _core::TrackItem {
+            uid,
+            cue_in,
+            cue_out,
+            crossfade,
+        } = from;
+        Self {
+            uid: uid.into(),
+            cue_in_ms: cue_in,
+            cue_out_ms: cue_out,
+            crossfade_ms: crossfade,
+        }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Eq))]
+#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub enum Item {
     Separator(SeparatorItem),
     Track(TrackItem),
@@ -94,9 +129,10 @@ impl From<_core::Item> for Item {
 ///////////////////////////////////////////////////////////////////////
 
 #[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Eq))]
+#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Entry {
     added_at: DateTime,
 
@@ -156,7 +192,8 @@ fn is_default_flags(flags: &u8) -> bool {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Playlist {
     title: String,
 
@@ -212,9 +249,10 @@ impl From<_core::Playlist> for Playlist {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Eq))]
+#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct PlaylistWithEntries {
     #[serde(flatten)]
     playlist: Playlist,
@@ -283,7 +321,8 @@ impl From<_core::EntityWithEntries> for EntityWithEntries {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct EntriesSummary {
     total_count: usize,
 
@@ -330,7 +369,8 @@ impl From<EntriesSummary> for _core::EntriesSummary {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct TracksSummary {
     total_count: usize,
     distinct_count: usize,
@@ -369,7 +409,8 @@ impl From<TracksSummary> for _core::TracksSummary {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct PlaylistWithEntriesSummary {
     #[serde(flatten)]
     pub playlist: Playlist,
@@ -32,7 +32,12 @@ fn deserialize_playlist() {
         entries: vec![
             Entry {
                 added_at: added_at1.clone().into(),
-                item: Item::Track(TrackItem { uid: uid.clone() }),
+                item: Item::Track(TrackItem {
+                    uid: uid.clone(),
+                    cue_in_ms: Some(PositionMs::new(1_000.0)),
+                    cue_out_ms: Some(PositionMs::new(180_000.5)),
+                    crossfade_ms: Some(DurationMs::new(8_000.0)),
+                }),
                 title: None,
                 notes: None,
             },
@@ -58,7 +63,10 @@ fn deserialize_playlist() {
         "entries": [
             {
                 "track": {
-                    "uid": uid.to_string()
+                    "uid": uid.to_string(),
+                    "cueInMs": 1_000.0,
+                    "cueOutMs": 180_000.5,
+                    "crossfadeMs": 8_000.0
                 },
                 "addedAt": added_at1.to_string()
             },
@@ -241,7 +241,8 @@ impl<'de> Deserialize<'de> for Score {
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(Clone, PartialEq))]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
-#[serde(untagged, deny_unknown_fields)]
+#[serde(untagged)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub enum PlainTag {
     Label(Label),
     Score(Score),
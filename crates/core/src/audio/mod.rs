@@ -14,6 +14,9 @@ pub use self::sample::*;
 pub mod signal;
 pub use self::signal::*;
 
+pub mod waveform;
+pub use self::waveform::*;
+
 ///////////////////////////////////////////////////////////////////////
 // Position
 ///////////////////////////////////////////////////////////////////////
@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::fmt;
+
 use semval::prelude::*;
 
 use crate::util::IsInteger;
@@ -36,6 +38,74 @@ impl Validate for SampleLayout {
 
 pub type BitsPerSample = u8;
 
+/// Number of bits per sample of the (uncompressed) audio signal
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(schemars::JsonSchema),
+    schemars(transparent)
+)]
+pub struct BitDepth(BitsPerSample);
+
+impl BitDepth {
+    pub const UNIT_OF_MEASURE: &'static str = "bit";
+
+    pub const ZERO: Self = Self(0);
+    pub const MIN: Self = Self(1);
+    /// 64-bit floating-point samples
+    pub const MAX: Self = Self(64);
+
+    #[must_use]
+    pub const fn new(value: BitsPerSample) -> Self {
+        Self(value)
+    }
+
+    #[must_use]
+    pub const fn value(self) -> BitsPerSample {
+        let Self(value) = self;
+        value
+    }
+
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        <Self as IsValid>::is_valid(self)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum BitDepthInvalidity {
+    Min(BitDepth),
+    Max(BitDepth),
+}
+
+impl Validate for BitDepth {
+    type Invalidity = BitDepthInvalidity;
+
+    fn validate(&self) -> ValidationResult<Self::Invalidity> {
+        ValidationContext::new()
+            .invalidate_if(*self < Self::MIN, Self::Invalidity::Min(Self::MIN))
+            .invalidate_if(*self > Self::MAX, Self::Invalidity::Max(Self::MAX))
+            .into()
+    }
+}
+
+impl fmt::Display for BitDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{value} {unit}",
+            value = self.value(),
+            unit = Self::UNIT_OF_MEASURE
+        )
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, derive_more::Display)]
 pub enum SampleFormat {
     Float32,
@@ -167,3 +237,10 @@ impl IsInteger for SampleLength {
         self.0.is_integer()
     }
 }
+
+///////////////////////////////////////////////////////////////////////
+// Tests
+///////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+#[test]
+fn bit_depth_default() {
+    assert_eq!(BitDepth::ZERO, BitDepth::default());
+    assert!(!BitDepth::default().is_valid());
+}
+
+#[test]
+fn bit_depth_is_valid() {
+    assert!(!BitDepth::ZERO.is_valid());
+    assert!(BitDepth::MIN.is_valid());
+    assert!(BitDepth::MAX.is_valid());
+    assert!(!BitDepth::new(BitDepth::MAX.value() + 1).is_valid());
+    for value in [8, 16, 24, 32] {
+        assert!(BitDepth::new(value).is_valid());
+    }
+}
+
+#[test]
+fn bit_depth_to_string() {
+    assert_eq!("24 bit", BitDepth::new(24).to_string());
+}
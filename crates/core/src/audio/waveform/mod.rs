@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use semval::prelude::*;
+
+use crate::audio::sample::SampleType;
+
+///////////////////////////////////////////////////////////////////////
+// WaveformOverview
+///////////////////////////////////////////////////////////////////////
+
+/// The quantized peak amplitude within a bin of the waveform overview.
+///
+/// Ranges from 0 (silence) to 255 (full scale).
+pub type WaveformPeak = u8;
+
+/// Downsampled peak amplitudes of an audio signal
+///
+/// The signal is divided into equally sized bins, one peak per bin.
+/// The number of bins denotes the resolution of the overview, i.e.
+/// the overview is independent of the duration of the signal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaveformOverview(Vec<WaveformPeak>);
+
+impl WaveformOverview {
+    #[must_use]
+    pub const fn new(peaks: Vec<WaveformPeak>) -> Self {
+        Self(peaks)
+    }
+
+    /// Quantize the peak amplitudes of sample values within [-1.0, 1.0].
+    ///
+    /// Amplitudes that exceed full scale are clamped.
+    #[must_use]
+    pub fn from_amplitudes(amplitudes: impl IntoIterator<Item = SampleType>) -> Self {
+        let peaks = amplitudes.into_iter().map(quantize_peak).collect();
+        Self(peaks)
+    }
+
+    #[must_use]
+    pub fn peaks(&self) -> &[WaveformPeak] {
+        let Self(peaks) = self;
+        peaks
+    }
+
+    #[must_use]
+    pub fn into_peaks(self) -> Vec<WaveformPeak> {
+        let Self(peaks) = self;
+        peaks
+    }
+
+    /// The number of bins.
+    #[must_use]
+    pub fn resolution(&self) -> usize {
+        self.peaks().len()
+    }
+}
+
+#[must_use]
+#[allow(clippy::cast_sign_loss)] // Clamped to a non-negative range
+fn quantize_peak(amplitude: SampleType) -> WaveformPeak {
+    let peak = amplitude.abs();
+    if peak.is_nan() {
+        return 0;
+    }
+    (peak.min(1.0) * SampleType::from(WaveformPeak::MAX)).round() as WaveformPeak
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WaveformOverviewInvalidity {
+    Empty,
+}
+
+impl Validate for WaveformOverview {
+    type Invalidity = WaveformOverviewInvalidity;
+
+    fn validate(&self) -> ValidationResult<Self::Invalidity> {
+        ValidationContext::new()
+            .invalidate_if(self.peaks().is_empty(), Self::Invalidity::Empty)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests;
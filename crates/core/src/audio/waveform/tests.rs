@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+#[test]
+fn quantize_amplitudes() {
+    let overview = WaveformOverview::from_amplitudes([0.0, -0.5, 0.5, 1.0, -1.5, SampleType::NAN]);
+    assert_eq!(&[0, 128, 128, 255, 255, 0], overview.peaks());
+    assert_eq!(6, overview.resolution());
+}
+
+#[test]
+fn validate() {
+    assert!(WaveformOverview::new(vec![0]).validate().is_ok());
+    assert!(WaveformOverview::new(vec![]).validate().is_err());
+}
@@ -9,6 +9,7 @@ use std::{
     str,
 };
 
+use data_encoding::BASE64URL_NOPAD;
 use nonicle::{Canonicalize, IsCanonical};
 use rand::RngCore;
 use semval::prelude::*;
@@ -67,6 +68,43 @@ impl EntityUid {
         let Self { ulid } = self;
         ulid.array_to_str(buf);
     }
+
+    /// Length of the URL-safe encoding
+    ///
+    /// 16 bytes in base64url encoding without padding.
+    pub const URL_SAFE_LEN: usize = 22;
+
+    /// Encode into a compact, URL-safe string.
+    ///
+    /// Uses base64url encoding without padding, which is shorter than
+    /// the canonical base-32 encoding of [`Display`](fmt::Display).
+    #[must_use]
+    pub fn to_url_safe(&self) -> String {
+        let Self { ulid } = self;
+        let encoded = BASE64URL_NOPAD.encode(&ulid.to_bytes());
+        debug_assert_eq!(Self::URL_SAFE_LEN, encoded.len());
+        encoded
+    }
+
+    /// Decode from the URL-safe encoding.
+    ///
+    /// Inverse of [`Self::to_url_safe()`]. Rejects input of the wrong length,
+    /// with invalid characters, or with non-canonical trailing bits.
+    pub fn from_url_safe(encoded: &str) -> anyhow::Result<Self> {
+        if encoded.len() != Self::URL_SAFE_LEN {
+            anyhow::bail!(
+                "invalid length of URL-safe entity UID: expected {expected}, actual {actual}",
+                expected = Self::URL_SAFE_LEN,
+                actual = encoded.len()
+            );
+        }
+        let decoded = BASE64URL_NOPAD.decode(encoded.as_bytes())?;
+        let bytes = decoded
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("invalid URL-safe entity UID: {encoded}"))?;
+        let ulid = Ulid::from_bytes(bytes);
+        Ok(Self { ulid })
+    }
 }
 
 impl fmt::Display for EntityUid {
@@ -122,6 +160,13 @@ impl<T> EntityUidTyped<T> {
         } = self;
         untyped
     }
+
+    /// Decode from the URL-safe encoding.
+    ///
+    /// See also [`EntityUid::from_url_safe()`].
+    pub fn from_url_safe(encoded: &str) -> anyhow::Result<Self> {
+        EntityUid::from_url_safe(encoded).map(Self::from_untyped)
+    }
 }
 
 impl<T> From<EntityUidTyped<T>> for EntityUid {
@@ -339,6 +384,67 @@ impl EntityRevision {
     pub fn is_valid(&self) -> bool {
         <Self as IsValid>::is_valid(self)
     }
+
+    /// Prefix that distinguishes the opaque from the numeric string representation.
+    pub const OPAQUE_PREFIX: char = 'r';
+
+    const OPAQUE_RADIX: u32 = 36;
+
+    /// Encode the revision as an opaque token.
+    ///
+    /// Clients should treat the token as an arbitrary string that
+    /// could only be compared for equality.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn encode_opaque(self) -> String {
+        let Self(mut value) = self;
+        let mut digits = Vec::new();
+        loop {
+            let digit = (value % u64::from(Self::OPAQUE_RADIX)) as u32;
+            digits.push(char::from_digit(digit, Self::OPAQUE_RADIX).expect("valid digit"));
+            value /= u64::from(Self::OPAQUE_RADIX);
+            if value == 0 {
+                break;
+            }
+        }
+        std::iter::once(Self::OPAQUE_PREFIX)
+            .chain(digits.into_iter().rev())
+            .collect()
+    }
+
+    /// Decode a revision from an opaque token.
+    ///
+    /// See also: [`Self::encode_opaque()`]
+    pub fn decode_opaque(encoded: &str) -> anyhow::Result<Self> {
+        let digits = encoded
+            .strip_prefix(Self::OPAQUE_PREFIX)
+            .filter(|digits| !digits.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("invalid opaque entity revision: {encoded}"))?;
+        // Reject non-canonical encodings, i.e. upper case letters or leading zeros.
+        if (digits.len() > 1 && digits.starts_with('0'))
+            || digits.chars().any(|c| c.is_ascii_uppercase())
+        {
+            anyhow::bail!("invalid opaque entity revision: {encoded}");
+        }
+        EntityRevisionValue::from_str_radix(digits, Self::OPAQUE_RADIX)
+            .map(Self::new_unchecked)
+            .map_err(|_| anyhow::anyhow!("invalid opaque entity revision: {encoded}"))
+    }
+}
+
+/// Parses both the numeric and the opaque representation.
+impl std::str::FromStr for EntityRevision {
+    type Err = anyhow::Error;
+
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        if encoded.starts_with(Self::OPAQUE_PREFIX) {
+            return Self::decode_opaque(encoded);
+        }
+        encoded
+            .parse()
+            .map(Self::new_unchecked)
+            .map_err(|_| anyhow::anyhow!("invalid entity revision: {encoded}"))
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -44,6 +44,61 @@ fn should_encode_decode_uid() {
     assert_eq!(uid, decoded);
 }
 
+#[test]
+fn url_safe_uid_roundtrip() {
+    for uid in std::iter::repeat_with(EntityUid::new)
+        .take(100)
+        .chain([EntityUid::NIL])
+    {
+        let encoded = uid.to_url_safe();
+        assert_eq!(EntityUid::URL_SAFE_LEN, encoded.len());
+        assert!(encoded
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        assert_eq!(uid, EntityUid::from_url_safe(&encoded).unwrap());
+    }
+}
+
+#[test]
+fn url_safe_uid_typed_roundtrip() {
+    let uid = EntityUidTyped::<EntityType>::from_untyped(EntityUid::new());
+    let encoded = uid.to_url_safe();
+    assert_eq!(uid, EntityUidTyped::from_url_safe(&encoded).unwrap());
+}
+
+#[test]
+fn should_fail_to_decode_url_safe_uid_with_invalid_characters() {
+    let encoded = EntityUid::new().to_url_safe();
+    for invalid_char in ['+', '/', '=', '.', ' ', '\u{e4}'] {
+        let mut invalid = encoded.clone();
+        invalid.replace_range(3..4, "");
+        invalid.insert(3, invalid_char);
+        assert!(EntityUid::from_url_safe(&invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn should_fail_to_decode_url_safe_uid_with_wrong_length() {
+    let encoded = EntityUid::new().to_url_safe();
+    assert!(EntityUid::from_url_safe("").is_err());
+    assert!(EntityUid::from_url_safe(&encoded[..EntityUid::URL_SAFE_LEN - 1]).is_err());
+    assert!(EntityUid::from_url_safe(&format!("{encoded}A")).is_err());
+    // The canonical encoding is not accepted
+    assert!(EntityUid::from_url_safe(&EntityUid::new().to_string()).is_err());
+}
+
+#[test]
+fn should_fail_to_decode_url_safe_uid_with_non_canonical_trailing_bits() {
+    let encoded = EntityUid::NIL.to_url_safe();
+    assert_eq!("AAAAAAAAAAAAAAAAAAAAAA", encoded);
+    // The last character encodes 2 significant bits followed by 4 zero bits
+    let non_canonical = format!(
+        "{prefix}B",
+        prefix = &encoded[..EntityUid::URL_SAFE_LEN - 1]
+    );
+    assert!(EntityUid::from_url_safe(&non_canonical).is_err());
+}
+
 #[test]
 fn should_fail_to_decode_too_long_string() {
     let uid = EntityUid::new();
@@ -115,3 +170,36 @@ fn initial_entity_revision() {
     assert!(!EntityRevision::INITIAL.prev().unwrap().is_valid());
     assert!(EntityRevision::INITIAL.next().unwrap().is_valid());
 }
+
+#[test]
+fn opaque_entity_revision_roundtrip() {
+    for rev in [
+        EntityRevision::INITIAL,
+        EntityRevision::new_unchecked(35),
+        EntityRevision::new_unchecked(36),
+        EntityRevision::new_unchecked(EntityRevisionValue::MAX),
+    ] {
+        let encoded = rev.encode_opaque();
+        assert!(encoded.starts_with(EntityRevision::OPAQUE_PREFIX));
+        assert_eq!(rev, EntityRevision::decode_opaque(&encoded).unwrap());
+    }
+    assert_eq!("r1", EntityRevision::INITIAL.encode_opaque());
+    assert_eq!("r10", EntityRevision::new_unchecked(36).encode_opaque());
+}
+
+#[test]
+fn should_fail_to_decode_invalid_opaque_entity_revision() {
+    for encoded in ["", "r", "1", "r01", "rA", "r-1", "r_", "rzzzzzzzzzzzzzz"] {
+        assert!(EntityRevision::decode_opaque(encoded).is_err(), "{encoded}");
+    }
+}
+
+#[test]
+fn parse_entity_revision_from_number_or_opaque_str() {
+    let rev = EntityRevision::new_unchecked(42);
+    assert_eq!(rev, "42".parse().unwrap());
+    assert_eq!(rev, rev.encode_opaque().parse().unwrap());
+    assert!("".parse::<EntityRevision>().is_err());
+    assert!("-1".parse::<EntityRevision>().is_err());
+    assert!("x42".parse::<EntityRevision>().is_err());
+}
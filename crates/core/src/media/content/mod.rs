@@ -14,6 +14,7 @@ use crate::{
             BitrateBps, BitrateBpsInvalidity, LoudnessLufs, LoudnessLufsInvalidity, SampleRateHz,
             SampleRateHzInvalidity,
         },
+        waveform::{WaveformOverview, WaveformOverviewInvalidity},
         DurationMs, DurationMsInvalidity,
     },
     util::url::BaseUrl,
@@ -505,6 +506,9 @@ pub struct AudioContentMetadata {
 
     // Encoder and settings
     pub encoder: Option<String>,
+
+    /// Precomputed peaks for rendering an overview of the waveform
+    pub waveform_overview: Option<WaveformOverview>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -515,6 +519,7 @@ pub enum AudioContentMetadataInvalidity {
     Bitrate(BitrateBpsInvalidity),
     Loudness(LoudnessLufsInvalidity),
     EncoderEmpty,
+    WaveformOverview(WaveformOverviewInvalidity),
 }
 
 impl Validate for AudioContentMetadata {
@@ -534,6 +539,7 @@ impl Validate for AudioContentMetadata {
                     .is_some_and(str::is_empty),
                 Self::Invalidity::EncoderEmpty,
             )
+            .validate_with(&self.waveform_overview, Self::Invalidity::WaveformOverview)
             .into()
     }
 }
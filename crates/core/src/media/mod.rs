@@ -61,6 +61,9 @@ pub struct Content {
     /// calculation of the fingerprint.
     pub digest: Option<Vec<u8>>,
 
+    /// Size of the content data in bytes
+    pub size: Option<u64>,
+
     pub metadata: ContentMetadata,
 
     pub metadata_flags: ContentMetadataFlags,
@@ -101,6 +104,7 @@ impl Validate for Source {
                     metadata: content_metadata,
                     metadata_flags: content_metadata_flags,
                     digest: _,
+                    size: _,
                 },
             artwork,
         } = self;
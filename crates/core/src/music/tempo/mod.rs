@@ -43,6 +43,38 @@ impl TempoBpm {
     pub fn is_valid(&self) -> bool {
         <Self as IsValid>::is_valid(self)
     }
+
+    /// Check if the value is valid and within [`TempoBpmRange::PLAUSIBLE`].
+    ///
+    /// Imported values outside of this range are considered as
+    /// nonsense, e.g. placeholders like 0 or 999.
+    #[must_use]
+    pub fn is_plausible(&self) -> bool {
+        self.is_valid() && TempoBpmRange::PLAUSIBLE.contains(*self)
+    }
+
+    /// Fold double or half-time tempo into the given range.
+    ///
+    /// The tempo is repeatedly halved or doubled until it is within
+    /// the range. Values that cannot be folded into the range, e.g.
+    /// if the range spans less than an octave, are returned as close
+    /// to the range as possible.
+    #[must_use]
+    pub fn fold_into_range(self, range: TempoBpmRange) -> Self {
+        debug_assert!(range.is_valid());
+        let TempoBpmRange { min, max } = range;
+        let mut value = self.value();
+        if !(value > 0.0 && value.is_finite()) {
+            return self;
+        }
+        while value > max.value() && value / 2.0 >= min.value() {
+            value /= 2.0;
+        }
+        while value < min.value() && value * 2.0 <= max.value() {
+            value *= 2.0;
+        }
+        Self(value)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -73,3 +105,66 @@ impl fmt::Display for TempoBpm {
         )
     }
 }
+
+/// A closed range of tempo values.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TempoBpmRange {
+    pub min: TempoBpm,
+    pub max: TempoBpm,
+}
+
+impl TempoBpmRange {
+    /// Bounds for the tempo of music that are considered as plausible.
+    pub const PLAUSIBLE: Self = Self {
+        min: TempoBpm(20.0),
+        max: TempoBpm(500.0),
+    };
+
+    /// The typical tempo of most music.
+    ///
+    /// Spans more than an octave, i.e. every plausible tempo could
+    /// be folded into this range by doubling or halving it.
+    pub const TYPICAL: Self = Self {
+        min: TempoBpm(60.0),
+        max: TempoBpm(180.0),
+    };
+
+    #[must_use]
+    pub fn contains(&self, tempo_bpm: TempoBpm) -> bool {
+        tempo_bpm >= self.min && tempo_bpm <= self.max
+    }
+
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        <Self as IsValid>::is_valid(self)
+    }
+}
+
+impl Default for TempoBpmRange {
+    fn default() -> Self {
+        Self::TYPICAL
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum TempoBpmRangeInvalidity {
+    Min(TempoBpmInvalidity),
+    Max(TempoBpmInvalidity),
+    Empty,
+}
+
+impl Validate for TempoBpmRange {
+    type Invalidity = TempoBpmRangeInvalidity;
+
+    fn validate(&self) -> ValidationResult<Self::Invalidity> {
+        let Self { min, max } = self;
+        ValidationContext::new()
+            .validate_with(min, Self::Invalidity::Min)
+            .validate_with(max, Self::Invalidity::Max)
+            .invalidate_if(min > max, Self::Invalidity::Empty)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+#[test]
+fn reject_implausible_values() {
+    assert!(!TempoBpm::new(0.0).is_plausible());
+    assert!(!TempoBpm::new(-120.0).is_plausible());
+    assert!(!TempoBpm::new(999.0).is_plausible());
+    assert!(!TempoBpm::new(10000.0).is_plausible());
+    assert!(!TempoBpm::new(TempoBpmValue::NAN).is_plausible());
+    assert!(!TempoBpm::new(TempoBpmValue::INFINITY).is_plausible());
+    assert!(TempoBpm::new(120.0).is_plausible());
+    assert!(TempoBpmRange::PLAUSIBLE.min.is_plausible());
+    assert!(TempoBpmRange::PLAUSIBLE.max.is_plausible());
+}
+
+#[test]
+fn fold_into_typical_range() {
+    let range = TempoBpmRange::TYPICAL;
+    assert_eq!(
+        TempoBpm::new(120.0),
+        TempoBpm::new(240.0).fold_into_range(range)
+    );
+    assert_eq!(
+        TempoBpm::new(175.0),
+        TempoBpm::new(350.0).fold_into_range(range)
+    );
+    assert_eq!(
+        TempoBpm::new(70.0),
+        TempoBpm::new(35.0).fold_into_range(range)
+    );
+    // Values within the range are not modified
+    assert_eq!(
+        TempoBpm::new(60.0),
+        TempoBpm::new(60.0).fold_into_range(range)
+    );
+    assert_eq!(
+        TempoBpm::new(174.0),
+        TempoBpm::new(174.0).fold_into_range(range)
+    );
+}
+
+#[test]
+fn fold_into_custom_range() {
+    let range = TempoBpmRange {
+        min: TempoBpm::new(80.0),
+        max: TempoBpm::new(160.0),
+    };
+    assert!(range.is_valid());
+    assert_eq!(
+        TempoBpm::new(87.0),
+        TempoBpm::new(174.0).fold_into_range(range)
+    );
+    assert_eq!(
+        TempoBpm::new(150.0),
+        TempoBpm::new(75.0).fold_into_range(range)
+    );
+    // Not within the range, because the range spans less than an octave
+    let range = TempoBpmRange {
+        min: TempoBpm::new(100.0),
+        max: TempoBpm::new(150.0),
+    };
+    assert_eq!(
+        TempoBpm::new(160.0),
+        TempoBpm::new(160.0).fold_into_range(range)
+    );
+    assert_eq!(
+        TempoBpm::new(90.0),
+        TempoBpm::new(90.0).fold_into_range(range)
+    );
+}
+
+#[test]
+fn fold_invalid_values_unmodified() {
+    let range = TempoBpmRange::TYPICAL;
+    assert_eq!(TempoBpm::ZERO, TempoBpm::ZERO.fold_into_range(range));
+    assert_eq!(
+        TempoBpm::new(-240.0),
+        TempoBpm::new(-240.0).fold_into_range(range)
+    );
+}
+
+#[test]
+fn validate_range() {
+    assert!(TempoBpmRange::PLAUSIBLE.is_valid());
+    assert!(TempoBpmRange::TYPICAL.is_valid());
+    assert!(!TempoBpmRange {
+        min: TempoBpm::new(120.0),
+        max: TempoBpm::new(60.0),
+    }
+    .is_valid());
+    assert!(!TempoBpmRange {
+        min: TempoBpm::ZERO,
+        max: TempoBpm::new(60.0),
+    }
+    .is_valid());
+}
@@ -8,6 +8,7 @@ use rand::{seq::SliceRandom as _, RngCore};
 use semval::prelude::*;
 
 use crate::{
+    audio::{DurationMs, DurationMsInvalidity, PositionMs, PositionMsInvalidity},
     util::{
         clock::OffsetDateTimeMs,
         color::{Color, ColorInvalidity},
@@ -25,28 +26,99 @@ pub struct SeparatorItem {
     pub kind: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct TrackItem {
     /// A reference to the track.
     pub uid: TrackUid,
+
+    /// Optional start position of playback, e.g. the mix-in point
+    /// within a DJ set.
+    pub cue_in: Option<PositionMs>,
+
+    /// Optional end position of playback, e.g. the mix-out point
+    /// within a DJ set.
+    pub cue_out: Option<PositionMs>,
+
+    /// Optional hint for the duration of the crossfade into the
+    /// subsequent track.
+    ///
+    /// An empty duration denotes a hard cut.
+    pub crossfade: Option<DurationMs>,
+}
+
+impl TrackItem {
+    #[must_use]
+    pub const fn new(uid: TrackUid) -> Self {
+        Self {
+            uid,
+            cue_in: None,
+            cue_out: None,
+            crossfade: None,
+        }
+    }
+
+    /// Validate the item against the duration of the referenced track.
+    ///
+    /// Both cue positions must fall within the duration, if known.
+    pub fn validate_with_duration(
+        &self,
+        duration: Option<DurationMs>,
+    ) -> ValidationResult<TrackItemInvalidity> {
+        let Self {
+            cue_in, cue_out, ..
+        } = self;
+        let is_out_of_duration = |position: &PositionMs| {
+            duration.is_some_and(|duration| {
+                position.value() < 0.0 || position.value() > duration.value()
+            })
+        };
+        ValidationContext::new()
+            .merge_result(self.validate())
+            .invalidate_if(
+                cue_in.as_ref().is_some_and(is_out_of_duration)
+                    || cue_out.as_ref().is_some_and(is_out_of_duration),
+                TrackItemInvalidity::CueOutOfDuration,
+            )
+            .into()
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
 pub enum TrackItemInvalidity {
     Uid(EntityUidInvalidity),
+    CueIn(PositionMsInvalidity),
+    CueOut(PositionMsInvalidity),
+    CueOutNotAfterCueIn,
+    CueOutOfDuration,
+    Crossfade(DurationMsInvalidity),
 }
 
 impl Validate for TrackItem {
     type Invalidity = TrackItemInvalidity;
 
     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+        let Self {
+            uid,
+            cue_in,
+            cue_out,
+            crossfade,
+        } = self;
         ValidationContext::new()
-            .validate_with(&self.uid, Self::Invalidity::Uid)
+            .validate_with(uid, Self::Invalidity::Uid)
+            .validate_with(cue_in, Self::Invalidity::CueIn)
+            .validate_with(cue_out, Self::Invalidity::CueOut)
+            .invalidate_if(
+                cue_in
+                    .zip(*cue_out)
+                    .is_some_and(|(cue_in, cue_out)| cue_out <= cue_in),
+                Self::Invalidity::CueOutNotAfterCueIn,
+            )
+            .validate_with(crossfade, Self::Invalidity::Crossfade)
             .into()
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Item {
     Separator(SeparatorItem),
     Track(TrackItem),
@@ -85,7 +157,7 @@ impl Validate for Item {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Entry {
     /// Time stamp added when this entry is part of the playlist,
     /// i.e. when it has been created and added.
@@ -214,7 +286,7 @@ pub type EntityHeader = EntityHeaderTyped<EntityType>;
 
 pub type Entity = crate::entity::Entity<EntityType, Playlist, PlaylistInvalidity>;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PlaylistWithEntries {
     pub playlist: Playlist,
 
@@ -1,7 +1,55 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-///////////////////////////////////////////////////////////////////////
+use super::*;
+use crate::EntityUid as UntypedEntityUid;
 
-// TODO
-//use super::*;
+fn new_track_item(cue_in: Option<f64>, cue_out: Option<f64>) -> TrackItem {
+    TrackItem {
+        cue_in: cue_in.map(PositionMs::new),
+        cue_out: cue_out.map(PositionMs::new),
+        ..TrackItem::new(TrackUid::from_untyped(UntypedEntityUid::new()))
+    }
+}
+
+#[test]
+fn track_item_with_valid_cue_range() {
+    assert!(new_track_item(None, None).is_valid());
+    assert!(new_track_item(Some(1_000.0), None).is_valid());
+    assert!(new_track_item(None, Some(1_000.0)).is_valid());
+    assert!(new_track_item(Some(1_000.0), Some(180_000.0)).is_valid());
+    let item = TrackItem {
+        crossfade: Some(DurationMs::new(8_000.0)),
+        ..new_track_item(Some(1_000.0), Some(180_000.0))
+    };
+    assert!(item.is_valid());
+    assert!(item
+        .validate_with_duration(Some(DurationMs::new(180_000.0)))
+        .is_ok());
+}
+
+#[test]
+fn track_item_with_invalid_cue_range() {
+    assert!(!new_track_item(Some(1_000.0), Some(1_000.0)).is_valid());
+    assert!(!new_track_item(Some(2_000.0), Some(1_000.0)).is_valid());
+    assert!(!new_track_item(Some(f64::NAN), None).is_valid());
+    let item = TrackItem {
+        crossfade: Some(DurationMs::new(-1.0)),
+        ..new_track_item(None, None)
+    };
+    assert!(!item.is_valid());
+}
+
+#[test]
+fn track_item_with_cue_range_exceeding_duration() {
+    let item = new_track_item(Some(1_000.0), Some(200_000.0));
+    assert!(item.is_valid());
+    // Unknown duration
+    assert!(item.validate_with_duration(None).is_ok());
+    assert!(item
+        .validate_with_duration(Some(DurationMs::new(180_000.0)))
+        .is_err());
+    assert!(new_track_item(Some(-1.0), None)
+        .validate_with_duration(Some(DurationMs::new(180_000.0)))
+        .is_err());
+}
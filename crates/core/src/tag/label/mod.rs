@@ -15,7 +15,8 @@ use crate::util::string::trimmed_non_empty_from;
 
 /// The name of a tag.
 ///
-/// Format: Unicode string without leading/trailing whitespace
+/// Format: Unicode string without leading/trailing whitespace and
+/// control characters with at most [`Label::MAX_CHAR_COUNT`] characters
 #[derive(Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 #[cfg_attr(
@@ -31,6 +32,9 @@ use crate::util::string::trimmed_non_empty_from;
 pub struct Label<'a>(Cow<'a, str>);
 
 impl<'a> Label<'a> {
+    /// The maximum length in characters.
+    pub const MAX_CHAR_COUNT: usize = 255;
+
     #[must_use]
     fn is_invalid_format(inner: &str) -> bool {
         !Self::is_valid_format(inner)
@@ -38,12 +42,51 @@ impl<'a> Label<'a> {
 
     #[must_use]
     fn is_valid_format(inner: &str) -> bool {
-        inner.trim() == inner
+        inner.trim() == inner && !inner.contains(char::is_control)
+    }
+
+    #[must_use]
+    fn exceeds_max_char_count(inner: &str) -> bool {
+        inner.chars().nth(Self::MAX_CHAR_COUNT).is_some()
+    }
+
+    /// Check if [`Label::clamp_from()`] needs to modify the string
+    /// beyond trimming whitespace.
+    ///
+    /// This is the case if the string contains control characters or
+    /// exceeds the maximum length.
+    #[must_use]
+    pub fn needs_sanitizing(from: &str) -> bool {
+        let trimmed = from.trim();
+        trimmed.contains(char::is_control) || Self::exceeds_max_char_count(trimmed)
+    }
+
+    /// Remove control characters and truncate to the maximum length.
+    ///
+    /// Control characters that represent whitespace like line breaks
+    /// or tabs are replaced by a space to preserve word boundaries.
+    fn sanitize(inner: &str) -> String {
+        inner
+            .chars()
+            .filter_map(|c| {
+                if c.is_control() {
+                    c.is_whitespace().then_some(' ')
+                } else {
+                    Some(c)
+                }
+            })
+            .take(Self::MAX_CHAR_COUNT)
+            .collect()
     }
 
     #[must_use]
     fn clamp_inner(inner: Cow<'a, str>) -> Option<Cow<'a, str>> {
-        trimmed_non_empty_from(inner)
+        let trimmed = trimmed_non_empty_from(inner)?;
+        if !Self::needs_sanitizing(&trimmed) {
+            return Some(trimmed);
+        }
+        // Sanitizing might result in leading or trailing whitespace
+        trimmed_non_empty_from(Self::sanitize(&trimmed))
     }
 
     pub fn clamp_from(from: impl Into<Cow<'a, str>>) -> Option<Self> {
@@ -129,6 +172,7 @@ impl<'a> From<Label<'a>> for Cow<'a, str> {
 pub enum LabelInvalidity {
     Empty,
     Format,
+    TooLong,
 }
 
 impl Validate for Label<'_> {
@@ -141,6 +185,10 @@ impl Validate for Label<'_> {
                 Self::is_invalid_format(self.as_ref()),
                 Self::Invalidity::Format,
             )
+            .invalidate_if(
+                Self::exceeds_max_char_count(self.as_str()),
+                Self::Invalidity::TooLong,
+            )
             .into()
     }
 }
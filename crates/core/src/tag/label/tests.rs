@@ -34,3 +34,44 @@ fn default_is_invalid() {
 fn parse_empty() {
     assert!(Label::clamp_from("").is_none());
 }
+
+#[test]
+fn clamp_from_sanitizes_control_characters() {
+    assert!(Label::needs_sanitizing("A\0Label"));
+    assert_eq!(
+        Some(Label::from_unchecked("ALabel")),
+        Label::clamp_from("A\0Label")
+    );
+    assert!(Label::needs_sanitizing("A\nLabel"));
+    assert_eq!(
+        Some(Label::from_unchecked("A  Label")),
+        Label::clamp_from("A\r\nLabel")
+    );
+    assert_eq!(
+        Some(Label::from_unchecked("A Label")),
+        Label::clamp_from("\0A Label\n\0"),
+    );
+    assert!(Label::clamp_from("\0\n\0").is_none());
+}
+
+#[test]
+fn clamp_from_truncates_to_max_char_count() {
+    let max_len = "ä".repeat(Label::MAX_CHAR_COUNT);
+    assert!(!Label::needs_sanitizing(&max_len));
+    assert_eq!(
+        Some(Label::from_unchecked(max_len.as_str())),
+        Label::clamp_from(max_len.as_str())
+    );
+    let too_long = format!("{max_len}ö");
+    assert!(Label::needs_sanitizing(&too_long));
+    assert!(Label::new(too_long.as_str().into()).validate().is_err());
+    let truncated = Label::clamp_from(too_long).unwrap();
+    assert_eq!(Label::MAX_CHAR_COUNT, truncated.as_str().chars().count());
+    assert_eq!(max_len, truncated.as_str());
+}
+
+#[test]
+fn validate_control_characters() {
+    assert!(Label::new("A\0Label".into()).validate().is_err());
+    assert!(Label::new("A\nLabel".into()).validate().is_err());
+}
@@ -260,6 +260,74 @@ impl<'a> Tags<'a> {
         });
         facets
     }
+
+    /// Merge plain and faceted tags from another set of tags.
+    ///
+    /// Tags with a label that is already present in the same facet are
+    /// not added again. Their scores are resolved according to `conflict`.
+    ///
+    /// The result needs to be canonicalized afterwards.
+    pub fn merge_from(&mut self, other: &Tags<'_>, conflict: TagConflictPolicy) {
+        let Self { plain, facets } = self;
+        merge_plain_tags(plain, &other.plain, conflict);
+        for other_faceted_tags in &other.facets {
+            if let Some(faceted_tags) = facets
+                .iter_mut()
+                .find(|faceted_tags| faceted_tags.facet_id == other_faceted_tags.facet_id)
+            {
+                merge_plain_tags(&mut faceted_tags.tags, &other_faceted_tags.tags, conflict);
+            } else {
+                facets.push(FacetedTags {
+                    facet_id: other_faceted_tags.facet_id.clone_owned(),
+                    tags: other_faceted_tags
+                        .tags
+                        .iter()
+                        .map(PlainTag::clone_owned)
+                        .collect(),
+                });
+            }
+        }
+    }
+}
+
+/// Resolution of score conflicts when merging tags with the same label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagConflictPolicy {
+    /// Keep the higher score.
+    MaxScore,
+
+    /// Keep the existing score.
+    KeepExisting,
+
+    /// Replace the existing score.
+    Overwrite,
+}
+
+fn merge_plain_tags(
+    plain_tags: &mut Vec<PlainTag<'_>>,
+    other_plain_tags: &[PlainTag<'_>],
+    conflict: TagConflictPolicy,
+) {
+    for other_plain_tag in other_plain_tags {
+        let Some(plain_tag) = plain_tags
+            .iter_mut()
+            .find(|plain_tag| plain_tag.label == other_plain_tag.label)
+        else {
+            plain_tags.push(other_plain_tag.clone_owned());
+            continue;
+        };
+        match conflict {
+            TagConflictPolicy::MaxScore => {
+                if other_plain_tag.score > plain_tag.score {
+                    plain_tag.score = other_plain_tag.score;
+                }
+            }
+            TagConflictPolicy::KeepExisting => (),
+            TagConflictPolicy::Overwrite => {
+                plain_tag.score = other_plain_tag.score;
+            }
+        }
+    }
 }
 
 impl IsCanonical for Tags<'_> {
@@ -273,6 +341,11 @@ impl IsCanonical for Tags<'_> {
 }
 
 impl Canonicalize for Tags<'_> {
+    /// Canonicalize plain and faceted tags
+    ///
+    /// Faceted tags that share the same facet are merged into a single
+    /// entry and their labels are deduplicated, keeping the highest
+    /// score. Facets without any tags are removed.
     fn canonicalize(&mut self) {
         let Self {
             plain: plain_tags,
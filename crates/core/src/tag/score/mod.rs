@@ -42,6 +42,15 @@ impl Score {
         Self::new_unchecked(Self::clamp_value(value))
     }
 
+    /// Clamp a finite value into the valid range.
+    ///
+    /// Returns `None` for `NaN` and infinite values that could
+    /// not be mapped to a meaningful score.
+    pub fn from_value_clamped(value: impl Into<ScoreValue>) -> Option<Self> {
+        let value = value.into();
+        value.is_finite().then(|| Self::clamp_from(value))
+    }
+
     #[must_use]
     pub const fn new_unchecked(value: ScoreValue) -> Self {
         Self(value)
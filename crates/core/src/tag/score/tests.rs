@@ -25,3 +25,20 @@ fn display() {
     assert_eq!("90.1%", Score(0.901_234_5).to_string());
     assert_eq!("90.2%", Score(0.901_5).to_string());
 }
+
+#[test]
+fn from_value_clamped() {
+    for value in [Score::MIN_VALUE, 0.5, Score::MAX_VALUE] {
+        assert_eq!(Some(Score(value)), Score::from_value_clamped(value));
+    }
+    assert_eq!(Some(Score::MIN), Score::from_value_clamped(-0.5));
+    assert_eq!(Some(Score::MAX), Score::from_value_clamped(1.5));
+    assert_eq!(None, Score::from_value_clamped(ScoreValue::NAN));
+    assert_eq!(None, Score::from_value_clamped(ScoreValue::INFINITY));
+    assert_eq!(None, Score::from_value_clamped(ScoreValue::NEG_INFINITY));
+}
+
+#[test]
+fn validate_nan() {
+    assert!(Score::new_unchecked(ScoreValue::NAN).validate().is_err());
+}
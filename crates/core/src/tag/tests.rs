@@ -332,3 +332,80 @@ fn canonicalize_should_remove_facets_without_tags() {
     assert!(actual_tags.is_canonical());
     assert_eq!(expected_tags, actual_tags);
 }
+
+#[test]
+fn canonicalize_should_merge_duplicate_facets() {
+    let genre = FacetId::from_unchecked("genre");
+    let mood = FacetId::from_unchecked("mood");
+    let mut tags = Tags {
+        plain: vec![],
+        facets: vec![
+            FacetedTags {
+                facet_id: genre.clone(),
+                tags: vec![
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Rock")),
+                        score: Score::new_unchecked(0.5),
+                    },
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Pop")),
+                        ..Default::default()
+                    },
+                ],
+            },
+            FacetedTags {
+                facet_id: mood.clone(),
+                tags: vec![PlainTag {
+                    label: Some(Label::from_unchecked("Happy")),
+                    ..Default::default()
+                }],
+            },
+            FacetedTags {
+                facet_id: genre.clone(),
+                tags: vec![
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Jazz")),
+                        ..Default::default()
+                    },
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Rock")),
+                        score: Score::new_unchecked(0.75),
+                    },
+                ],
+            },
+        ],
+    };
+    tags.canonicalize();
+    assert!(tags.is_canonical());
+    assert!(tags.validate().is_ok());
+    assert_eq!(
+        vec![
+            FacetedTags {
+                facet_id: genre,
+                tags: vec![
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Jazz")),
+                        ..Default::default()
+                    },
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Pop")),
+                        ..Default::default()
+                    },
+                    // The duplicate label with the higher score is kept
+                    PlainTag {
+                        label: Some(Label::from_unchecked("Rock")),
+                        score: Score::new_unchecked(0.75),
+                    },
+                ],
+            },
+            FacetedTags {
+                facet_id: mood,
+                tags: vec![PlainTag {
+                    label: Some(Label::from_unchecked("Happy")),
+                    ..Default::default()
+                }],
+            },
+        ],
+        tags.facets
+    );
+}
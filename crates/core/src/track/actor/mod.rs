@@ -142,6 +142,9 @@ pub enum ActorsInvalidity {
 pub const ANY_ROLE_FILTER: Option<Role> = None;
 pub const ANY_RANK_FILTER: Option<Kind> = None;
 
+/// Roles of actors that are considered as the artists of a track.
+pub const DEFAULT_ARTIST_ROLES: &[Role] = &[Role::Artist, Role::Performer];
+
 impl Actors {
     pub fn validate<'a, I>(actors: &I) -> ValidationResult<ActorsInvalidity>
     where
@@ -258,6 +261,31 @@ impl Actors {
         actor_names.dedup();
         actor_names
     }
+
+    /// Collect the unique names of all actors with one of the given roles.
+    pub fn collect_names_for_roles<'a, I>(actors: I, roles: &[Role]) -> Vec<&'a str>
+    where
+        I: Iterator<Item = &'a Actor> + Clone,
+    {
+        Self::collect_all_unique_actor_names(actors.filter(|actor| roles.contains(&actor.role)))
+    }
+
+    /// The name of the primary artist.
+    ///
+    /// Selects the main [`Role::Artist`] if available. Otherwise the first
+    /// individual artist is selected. The main [`Role::Performer`] is only
+    /// used as a fallback if no artists exist.
+    pub fn primary_artist_name<'a, I>(actors: I) -> Option<&'a str>
+    where
+        I: Iterator<Item = &'a Actor> + Clone,
+    {
+        Self::main_actor(actors.clone(), Role::Artist)
+            .or_else(|| {
+                Self::filter_kind_role(actors.clone(), Kind::Individual, Role::Artist).next()
+            })
+            .or_else(|| Self::main_actor(actors, Role::Performer))
+            .map(|actor| actor.name.as_str())
+    }
 }
 
 #[derive(Debug)]
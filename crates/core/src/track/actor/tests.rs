@@ -125,6 +125,78 @@ fn summary_individual_actor_names() {
     ));
 }
 
+fn new_actor(role: Role, kind: Kind, name: &str) -> Actor {
+    Actor {
+        role,
+        kind,
+        name: name.to_owned(),
+        role_notes: None,
+    }
+}
+
+#[test]
+fn collect_names_for_roles() {
+    let actors = [
+        new_actor(Role::Artist, Kind::Summary, "Artist 1 & Artist 2"),
+        new_actor(Role::Artist, Kind::Individual, "Artist 1"),
+        new_actor(Role::Artist, Kind::Individual, "Artist 2"),
+        new_actor(Role::Performer, Kind::Individual, "Artist 1"),
+        new_actor(Role::Performer, Kind::Individual, "Performer"),
+        new_actor(Role::Producer, Kind::Individual, "Producer"),
+        new_actor(Role::Composer, Kind::Individual, "Composer"),
+    ];
+    assert_eq!(
+        vec!["Artist 1", "Artist 1 & Artist 2", "Artist 2", "Performer"],
+        Actors::collect_names_for_roles(actors.iter(), DEFAULT_ARTIST_ROLES)
+    );
+    assert_eq!(
+        vec!["Composer", "Producer"],
+        Actors::collect_names_for_roles(actors.iter(), &[Role::Producer, Role::Composer])
+    );
+    assert!(Actors::collect_names_for_roles(actors.iter(), &[Role::Remixer]).is_empty());
+    assert!(Actors::collect_names_for_roles(actors.iter(), &[]).is_empty());
+}
+
+#[test]
+fn primary_artist_name() {
+    let summary_artist = new_actor(Role::Artist, Kind::Summary, "Artist 1 & Artist 2");
+    let individual_artist_1 = new_actor(Role::Artist, Kind::Individual, "Artist 1");
+    let individual_artist_2 = new_actor(Role::Artist, Kind::Individual, "Artist 2");
+    let performer = new_actor(Role::Performer, Kind::Summary, "Performer");
+    let producer = new_actor(Role::Producer, Kind::Summary, "Producer");
+
+    // The summary artist is preferred
+    let actors = [
+        summary_artist.clone(),
+        individual_artist_1.clone(),
+        individual_artist_2.clone(),
+        performer.clone(),
+    ];
+    assert_eq!(
+        Some("Artist 1 & Artist 2"),
+        Actors::primary_artist_name(actors.iter())
+    );
+
+    // The first of multiple individual artists
+    let actors = [
+        individual_artist_1.clone(),
+        individual_artist_2.clone(),
+        performer.clone(),
+    ];
+    assert_eq!(Some("Artist 1"), Actors::primary_artist_name(actors.iter()));
+
+    // The performer if no artist exists
+    let actors = [producer.clone(), performer];
+    assert_eq!(
+        Some("Performer"),
+        Actors::primary_artist_name(actors.iter())
+    );
+
+    // Other roles are never selected
+    let actors = [producer];
+    assert_eq!(None, Actors::primary_artist_name(actors.iter()));
+}
+
 const ACTOR_NAME_SEPARATORS: &[&str] = &[
     " & ",
     " and ",
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use ::url::Url;
-use nonicle::{Canonical, CanonicalizeInto as _, IsCanonical};
+use nonicle::{Canonical, Canonicalize, CanonicalizeInto as _, IsCanonical};
 use semval::prelude::*;
 use strum::FromRepr;
 
@@ -31,8 +31,8 @@ pub use self::title::{Title, TitleInvalidity, Titles, TitlesInvalidity};
 use crate::util::clock::{DateOrDateTime, DateOrDateTimeInvalidity, OffsetDateTimeMs};
 use crate::util::color::{Color, ColorInvalidity};
 use crate::{
-    media::{Source, SourceInvalidity},
-    tag::{Tags, TagsInvalidity},
+    media::{content::ContentLink, Content, Source, SourceInvalidity},
+    tag::{TagConflictPolicy, Tags, TagsInvalidity},
 };
 use crate::{EntityHeaderTyped, EntityRevision, EntityUidTyped};
 
@@ -67,6 +67,12 @@ impl AdvisoryRating {
     }
 }
 
+/// Display title of tracks without a title.
+pub const UNTITLED_DISPLAY_TITLE: &str = "Untitled";
+
+/// Display artist of tracks without an artist.
+pub const UNKNOWN_DISPLAY_ARTIST: &str = "Unknown Artist";
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Track {
     pub media_source: Source,
@@ -172,6 +178,27 @@ impl Track {
         res
     }
 
+    /// Merge tags from another source into the existing tags.
+    ///
+    /// Both plain and faceted tags are merged without duplicating labels
+    /// within a facet. Scores of existing tags are resolved according to
+    /// `conflict`.
+    ///
+    /// Returns `true` if the tags have been modified.
+    pub fn merge_tags_from(&mut self, other: &Tags<'_>, conflict: TagConflictPolicy) -> bool {
+        let old_tags = std::mem::take(&mut self.tags).untie();
+        let mut new_tags = old_tags.clone();
+        new_tags.merge_from(other, conflict);
+        let new_tags = new_tags.canonicalize_into();
+        let res = *new_tags != old_tags;
+        self.tags = if res {
+            new_tags
+        } else {
+            Canonical::tie(old_tags)
+        };
+        res
+    }
+
     #[must_use]
     pub fn track_artist(&self) -> Option<&str> {
         Actors::main_actor(self.actors.iter(), actor::Role::Artist).map(|actor| actor.name.as_str())
@@ -212,6 +239,114 @@ impl Track {
         Actors::main_actor(self.album.actors.iter(), actor::Role::Artist)
             .map(|actor| actor.name.as_str())
     }
+
+    /// The track title for display purposes.
+    ///
+    /// Falls back to [`UNTITLED_DISPLAY_TITLE`].
+    #[must_use]
+    pub fn display_title(&self) -> &str {
+        self.track_title().unwrap_or(UNTITLED_DISPLAY_TITLE)
+    }
+
+    /// The track artist for display purposes.
+    ///
+    /// Falls back to the album artist unless the album is a compilation,
+    /// because the album artist of a compilation is usually a placeholder
+    /// like "Various Artists". Otherwise falls back to
+    /// [`UNKNOWN_DISPLAY_ARTIST`].
+    #[must_use]
+    pub fn display_artist(&self) -> &str {
+        self.track_artist()
+            .or_else(|| {
+                if self.album.kind == Some(album::Kind::Compilation) {
+                    return None;
+                }
+                self.album_artist()
+            })
+            .unwrap_or(UNKNOWN_DISPLAY_ARTIST)
+    }
+
+    /// Check if the contents of both tracks are equivalent.
+    ///
+    /// Compares all fields except for the following volatile fields
+    /// that are updated when (re-)importing a track without affecting
+    /// its contents:
+    ///
+    /// - `media_source.collected_at`
+    /// - `media_source.content.link.rev`
+    ///
+    /// Titles, actors, tags, and cues are compared in their canonical
+    /// order, i.e. the original ordering doesn't matter.
+    ///
+    /// Could be used to detect if an update of a track is required.
+    #[must_use]
+    pub fn content_equivalent(&self, other: &Self) -> bool {
+        let Self {
+            media_source:
+                Source {
+                    collected_at: _,
+                    content:
+                        Content {
+                            link: ContentLink { path, rev: _ },
+                            r#type,
+                            digest,
+                            size,
+                            metadata,
+                            metadata_flags,
+                        },
+                    artwork,
+                },
+            recorded_at,
+            released_at,
+            released_orig_at,
+            publisher,
+            copyright,
+            advisory_rating,
+            album,
+            indexes,
+            titles,
+            actors,
+            tags,
+            color,
+            metrics,
+            cues,
+        } = self;
+        let other_content = &other.media_source.content;
+        *path == other_content.link.path
+            && *r#type == other_content.r#type
+            && *digest == other_content.digest
+            && *size == other_content.size
+            && *metadata == other_content.metadata
+            && *metadata_flags == other_content.metadata_flags
+            && *artwork == other.media_source.artwork
+            && *recorded_at == other.recorded_at
+            && *released_at == other.released_at
+            && *released_orig_at == other.released_orig_at
+            && *publisher == other.publisher
+            && *copyright == other.copyright
+            && *advisory_rating == other.advisory_rating
+            && **album == *other.album
+            && *indexes == other.indexes
+            && canonical_eq(titles, &other.titles)
+            && canonical_eq(actors, &other.actors)
+            && canonical_eq(tags, &other.tags)
+            && *color == other.color
+            && *metrics == other.metrics
+            && canonical_eq(cues, &other.cues)
+    }
+}
+
+/// Compare canonical components regardless of how they have been tied.
+fn canonical_eq<T>(lhs: &Canonical<T>, rhs: &Canonical<T>) -> bool
+where
+    T: Canonicalize + IsCanonical + Clone + PartialEq + std::fmt::Debug,
+{
+    // `Canonical` itself is always considered as canonical, even if
+    // it has been tied unchecked. Only the enclosed values are relevant.
+    if (**lhs).is_canonical() && (**rhs).is_canonical() {
+        return **lhs == **rhs;
+    }
+    (**lhs).clone().canonicalize_into() == (**rhs).clone().canonicalize_into()
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -289,7 +424,48 @@ impl Validate for Track {
 
 impl IsCanonical for Track {
     fn is_canonical(&self) -> bool {
-        true
+        let Self {
+            album,
+            titles,
+            actors,
+            tags,
+            cues,
+            ..
+        } = self;
+        album.titles.is_canonical()
+            && album.actors.is_canonical()
+            && titles.is_canonical()
+            && actors.is_canonical()
+            && tags.is_canonical()
+            && cues.is_canonical()
+    }
+}
+
+impl Canonicalize for Track {
+    /// Canonicalize all components
+    ///
+    /// Titles and tags are sorted and deduplicated, actors are ordered
+    /// by role, kind, and name. Components that have been tied without
+    /// canonicalizing them first are fixed up. The result is stable,
+    /// i.e. canonicalizing a track again doesn't change it.
+    fn canonicalize(&mut self) {
+        let Self {
+            album,
+            titles,
+            actors,
+            tags,
+            cues,
+            ..
+        } = self;
+        let mut untied_album = std::mem::take(album).untie();
+        untied_album.titles = untied_album.titles.untie().canonicalize_into();
+        untied_album.actors = untied_album.actors.untie().canonicalize_into();
+        *album = Canonical::tie(untied_album);
+        *titles = std::mem::take(titles).untie().canonicalize_into();
+        *actors = std::mem::take(actors).untie().canonicalize_into();
+        *tags = std::mem::take(tags).untie().canonicalize_into();
+        *cues = std::mem::take(cues).untie().canonicalize_into();
+        debug_assert!(self.is_canonical());
     }
 }
 
@@ -346,3 +522,6 @@ pub struct PlayCounter {
     pub last_played_at: Option<OffsetDateTimeMs>,
     pub times_played: Option<PlayCount>,
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,341 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+use crate::{
+    media::{
+        content::{AudioContentMetadata, ContentLink, ContentRevision},
+        Content,
+    },
+    tag::{FacetedTags, Label, PlainTag, Score},
+    track::{
+        actor::{Kind as ActorKind, Role as ActorRole},
+        tag::{FACET_ID_GENRE, FACET_ID_MOOD},
+    },
+};
+
+fn new_track() -> Track {
+    Track::new_from_media_source(Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: Content {
+            link: ContentLink {
+                path: "track.mp3".into(),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            metadata_flags: Default::default(),
+            metadata: AudioContentMetadata::default().into(),
+            digest: None,
+            size: None,
+        },
+        artwork: None,
+    })
+}
+
+fn actor(role: ActorRole, name: &str) -> Actor {
+    Actor {
+        role,
+        kind: ActorKind::Individual,
+        name: name.to_owned(),
+        role_notes: None,
+    }
+}
+
+fn plain_tag(label: &'static str) -> PlainTag<'static> {
+    PlainTag {
+        label: Some(Label::from_unchecked(label)),
+        score: Default::default(),
+    }
+}
+
+fn genre_tags(labels: &[&'static str]) -> FacetedTags<'static> {
+    FacetedTags {
+        facet_id: FACET_ID_GENRE.clone_owned(),
+        tags: labels.iter().copied().map(plain_tag).collect(),
+    }
+}
+
+#[test]
+fn canonicalize_dedups_tags_and_sorts_actors() {
+    let mut track = new_track();
+    track.actors = vec![
+        actor(ActorRole::Producer, "B"),
+        actor(ActorRole::Artist, "Z"),
+        actor(ActorRole::Producer, "A"),
+        actor(ActorRole::Artist, "Y"),
+        actor(ActorRole::Artist, "Z"),
+    ]
+    .canonicalize_into();
+    track.tags = Tags {
+        plain: vec![plain_tag("b"), plain_tag("a"), plain_tag("b")],
+        facets: vec![genre_tags(&["Rock", "Pop"]), genre_tags(&["Rock"])],
+    }
+    .canonicalize_into();
+    track.canonicalize();
+    assert!(track.is_canonical());
+
+    assert_eq!(
+        vec![
+            actor(ActorRole::Artist, "Y"),
+            actor(ActorRole::Artist, "Z"),
+            actor(ActorRole::Producer, "A"),
+            actor(ActorRole::Producer, "B"),
+        ],
+        *track.actors
+    );
+    assert_eq!(vec![plain_tag("a"), plain_tag("b")], track.tags.plain);
+    assert_eq!(vec![genre_tags(&["Pop", "Rock"])], track.tags.facets);
+}
+
+#[test]
+fn canonicalize_merges_duplicate_facets() {
+    let mut track = new_track();
+    // Tie the tags without canonicalizing them first, e.g. as
+    // if they had been assembled from multiple sources.
+    track.tags = Canonical::tie_unchecked(Tags {
+        plain: vec![],
+        facets: vec![
+            genre_tags(&["Rock", "Pop"]),
+            FacetedTags {
+                facet_id: FACET_ID_MOOD.clone_owned(),
+                tags: vec![plain_tag("Happy")],
+            },
+            genre_tags(&["Jazz", "Rock"]),
+        ],
+    });
+    track.canonicalize();
+    assert!(track.is_canonical());
+
+    assert_eq!(
+        vec![
+            genre_tags(&["Jazz", "Pop", "Rock"]),
+            FacetedTags {
+                facet_id: FACET_ID_MOOD.clone_owned(),
+                tags: vec![plain_tag("Happy")],
+            },
+        ],
+        track.tags.facets
+    );
+}
+
+#[test]
+fn canonicalize_is_stable_and_idempotent() {
+    let actors = [
+        actor(ActorRole::Composer, "C"),
+        actor(ActorRole::Artist, "B"),
+        actor(ActorRole::Artist, "A"),
+    ];
+    let plain_tags = [plain_tag("x"), plain_tag("y"), plain_tag("x")];
+
+    let mut track = new_track();
+    track.actors = actors.to_vec().canonicalize_into();
+    track.tags = Tags {
+        plain: plain_tags.to_vec(),
+        facets: vec![],
+    }
+    .canonicalize_into();
+    track.canonicalize();
+
+    // The input order must not affect the result.
+    let mut reversed_track = new_track();
+    reversed_track.media_source = track.media_source.clone();
+    reversed_track.actors = actors
+        .iter()
+        .rev()
+        .cloned()
+        .collect::<Vec<_>>()
+        .canonicalize_into();
+    reversed_track.tags = Tags {
+        plain: plain_tags.iter().rev().cloned().collect(),
+        facets: vec![],
+    }
+    .canonicalize_into();
+    reversed_track.canonicalize();
+    assert_eq!(track, reversed_track);
+
+    let mut canonicalized_track = track.clone();
+    canonicalized_track.canonicalize();
+    assert_eq!(track, canonicalized_track);
+}
+
+fn scored_tag(label: &'static str, score: f64) -> PlainTag<'static> {
+    PlainTag {
+        label: Some(Label::from_unchecked(label)),
+        score: Score::new_unchecked(score),
+    }
+}
+
+fn new_track_with_tags(tags: Tags<'static>) -> Track {
+    let mut track = new_track();
+    track.tags = tags.canonicalize_into();
+    track
+}
+
+#[test]
+fn merge_tags_without_duplicates() {
+    let mut track = new_track_with_tags(Tags {
+        plain: vec![plain_tag("a")],
+        facets: vec![genre_tags(&["Rock"])],
+    });
+    let other = Tags {
+        plain: vec![plain_tag("a"), plain_tag("b")],
+        facets: vec![
+            genre_tags(&["Pop", "Rock"]),
+            FacetedTags {
+                facet_id: FACET_ID_MOOD.clone_owned(),
+                tags: vec![plain_tag("Rock")],
+            },
+        ],
+    };
+    assert!(track.merge_tags_from(&other, TagConflictPolicy::KeepExisting));
+    assert!(track.tags.is_canonical());
+    assert_eq!(vec![plain_tag("a"), plain_tag("b")], track.tags.plain);
+    assert_eq!(
+        vec![
+            genre_tags(&["Pop", "Rock"]),
+            FacetedTags {
+                facet_id: FACET_ID_MOOD.clone_owned(),
+                tags: vec![plain_tag("Rock")],
+            },
+        ],
+        track.tags.facets
+    );
+
+    // Merging the same tags again has no effect.
+    assert!(!track.merge_tags_from(&other, TagConflictPolicy::KeepExisting));
+}
+
+#[test]
+fn merge_tags_with_score_conflicts() {
+    let existing_tags = Tags {
+        plain: vec![scored_tag("higher", 0.75), scored_tag("lower", 0.25)],
+        facets: vec![],
+    };
+    let other = Tags {
+        plain: vec![scored_tag("higher", 0.5), scored_tag("lower", 0.5)],
+        facets: vec![],
+    };
+    for (conflict, expected_scores) in [
+        (TagConflictPolicy::MaxScore, [0.75, 0.5]),
+        (TagConflictPolicy::KeepExisting, [0.75, 0.25]),
+        (TagConflictPolicy::Overwrite, [0.5, 0.5]),
+    ] {
+        let mut track = new_track_with_tags(existing_tags.clone());
+        track.merge_tags_from(&other, conflict);
+        assert_eq!(
+            vec![
+                scored_tag("higher", expected_scores[0]),
+                scored_tag("lower", expected_scores[1]),
+            ],
+            track.tags.plain,
+            "{conflict:?}"
+        );
+    }
+}
+
+#[test]
+fn content_equivalent_ignores_volatile_fields() {
+    let track = new_track();
+    let mut other = track.clone();
+    other.media_source.collected_at = OffsetDateTimeMs::from_timestamp_millis(
+        track.media_source.collected_at.timestamp_millis() + 1_000,
+    );
+    other.media_source.content.link.rev = Some(ContentRevision::new(1));
+    assert_ne!(track, other);
+    assert!(track.content_equivalent(&other));
+    assert!(other.content_equivalent(&track));
+}
+
+#[test]
+fn content_equivalent_ignores_ordering_of_tied_components() {
+    let mut track = new_track();
+    track.actors =
+        vec![actor(ActorRole::Artist, "A"), actor(ActorRole::Artist, "B")].canonicalize_into();
+    let mut other = track.clone();
+    other.actors = Canonical::tie_unchecked(vec![
+        actor(ActorRole::Artist, "B"),
+        actor(ActorRole::Artist, "A"),
+    ]);
+    assert!(track.content_equivalent(&other));
+}
+
+#[test]
+fn content_equivalent_detects_modified_contents() {
+    let mut track = new_track();
+    track.set_track_title("Title");
+    let mut other = track.clone();
+    assert!(track.content_equivalent(&other));
+    other.set_track_title("Other title");
+    assert!(!track.content_equivalent(&other));
+
+    let mut other = track.clone();
+    other.media_source.content.digest = Some(vec![1, 2, 3]);
+    assert!(!track.content_equivalent(&other));
+}
+
+fn new_track_with_artists(
+    track_artist: Option<&str>,
+    album_artist: Option<&str>,
+    album_kind: Option<album::Kind>,
+) -> Track {
+    let mut track = new_track();
+    track.actors = track_artist
+        .into_iter()
+        .map(|name| actor(ActorRole::Artist, name))
+        .collect::<Vec<_>>()
+        .canonicalize_into();
+    track.album = Canonical::tie(Album {
+        kind: album_kind,
+        titles: Default::default(),
+        actors: album_artist
+            .into_iter()
+            .map(|name| actor(ActorRole::Artist, name))
+            .collect::<Vec<_>>()
+            .canonicalize_into(),
+    });
+    track
+}
+
+#[test]
+fn display_title_falls_back_to_untitled() {
+    let mut track = new_track();
+    assert_eq!(UNTITLED_DISPLAY_TITLE, track.display_title());
+    track.set_track_title("Title");
+    assert_eq!("Title", track.display_title());
+}
+
+#[test]
+fn display_artist_prefers_track_artist() {
+    let track = new_track_with_artists(Some("Track Artist"), Some("Album Artist"), None);
+    assert_eq!("Track Artist", track.display_artist());
+    let track = new_track_with_artists(
+        Some("Track Artist"),
+        Some("Various Artists"),
+        Some(album::Kind::Compilation),
+    );
+    assert_eq!("Track Artist", track.display_artist());
+}
+
+#[test]
+fn display_artist_falls_back_to_album_artist() {
+    let track = new_track_with_artists(None, Some("Album Artist"), None);
+    assert_eq!("Album Artist", track.display_artist());
+    let track = new_track_with_artists(None, Some("Album Artist"), Some(album::Kind::Album));
+    assert_eq!("Album Artist", track.display_artist());
+}
+
+#[test]
+fn display_artist_of_compilation_ignores_album_artist() {
+    let track = new_track_with_artists(
+        None,
+        Some("Various Artists"),
+        Some(album::Kind::Compilation),
+    );
+    assert_eq!(UNKNOWN_DISPLAY_ARTIST, track.display_artist());
+}
+
+#[test]
+fn display_artist_falls_back_to_unknown() {
+    let track = new_track_with_artists(None, None, None);
+    assert_eq!(UNKNOWN_DISPLAY_ARTIST, track.display_artist());
+}
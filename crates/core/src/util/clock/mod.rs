@@ -175,6 +175,17 @@ pub const YEAR_MAX: YearType = 9999;
 
 pub type YyyyMmDdDateValue = i32;
 
+/// The known precision of a [`YyyyMmDdDate`].
+///
+/// Unknown components are encoded as zero, i.e. a year-only date
+/// has neither a month nor a day of month.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DatePrecision {
+    Year,
+    YearMonth,
+    YearMonthDay,
+}
+
 /// 8-digit year+month+day (YYYYMMDD)
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
@@ -190,6 +201,10 @@ impl YyyyMmDdDate {
         Self(value)
     }
 
+    /// The comparable integer value.
+    ///
+    /// Partial dates are ordered before all more precise dates
+    /// within the same year or month.
     #[must_use]
     pub const fn value(self) -> YyyyMmDdDateValue {
         let Self(value) = self;
@@ -231,11 +246,36 @@ impl YyyyMmDdDate {
         Self(YyyyMmDdDateValue::from(year) * 10_000 + YyyyMmDdDateValue::from(month) * 100)
     }
 
+    #[must_use]
+    pub fn from_year_month_day(
+        year: YearType,
+        month: MonthType,
+        day_of_month: DayOfMonthType,
+    ) -> Self {
+        Self(
+            YyyyMmDdDateValue::from(year) * 10_000
+                + YyyyMmDdDateValue::from(month) * 100
+                + YyyyMmDdDateValue::from(day_of_month),
+        )
+    }
+
     #[must_use]
     pub fn is_year(self) -> bool {
         Self::from_year(self.year()) == self
     }
 
+    /// The precision of the date, i.e. which components are known.
+    #[must_use]
+    pub const fn precision(self) -> DatePrecision {
+        if self.month() == 0 {
+            DatePrecision::Year
+        } else if self.day_of_month() == 0 {
+            DatePrecision::YearMonth
+        } else {
+            DatePrecision::YearMonthDay
+        }
+    }
+
     #[must_use]
     pub fn is_valid(&self) -> bool {
         <Self as IsValid>::is_valid(self)
@@ -313,6 +353,41 @@ impl fmt::Display for YyyyMmDdDate {
     }
 }
 
+/// Parses the formats produced by [`fmt::Display`], i.e.
+/// `YYYY`, `YYYY-MM`, or `YYYY-MM-DD`.
+impl FromStr for YyyyMmDdDate {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        fn parse_digits<T: FromStr>(input: &str, len: usize) -> Option<T> {
+            (input.len() == len && input.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| input.parse().ok())
+                .flatten()
+        }
+        let components = input.split('-').collect::<Vec<_>>();
+        let date = match components.as_slice() {
+            [year] => parse_digits(year, 4).map(Self::from_year),
+            [year, month] => parse_digits(year, 4)
+                .zip(parse_digits(month, 2))
+                .map(|(year, month)| Self::from_year_month(year, month)),
+            [year, month, day_of_month] => parse_digits(year, 4)
+                .zip(parse_digits(month, 2))
+                .zip(parse_digits(day_of_month, 2))
+                .map(|((year, month), day_of_month)| {
+                    Self::from_year_month_day(year, month, day_of_month)
+                }),
+            _ => None,
+        };
+        let expected_precision = match components.len() {
+            1 => DatePrecision::Year,
+            2 => DatePrecision::YearMonth,
+            _ => DatePrecision::YearMonthDay,
+        };
+        date.filter(|date| date.is_valid() && date.precision() == expected_precision)
+            .ok_or_else(|| anyhow::anyhow!("invalid date: {input}"))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DateOrDateTime {
     Date(YyyyMmDdDate),
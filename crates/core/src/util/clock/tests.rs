@@ -109,3 +109,60 @@ fn deserialize_date_time() {
         .unwrap()
     );
 }
+
+#[test]
+fn yyyymmdd_precision() {
+    assert_eq!(
+        DatePrecision::Year,
+        YyyyMmDdDate::from_year(2021).precision()
+    );
+    assert_eq!(
+        DatePrecision::YearMonth,
+        YyyyMmDdDate::from_year_month(2021, 6).precision()
+    );
+    assert_eq!(
+        DatePrecision::YearMonthDay,
+        YyyyMmDdDate::from_year_month_day(2021, 6, 15).precision()
+    );
+}
+
+#[test]
+fn yyyymmdd_from_to_string() {
+    for (input, value, precision) in [
+        ("2021", 20_210_000, DatePrecision::Year),
+        ("2021-06", 20_210_600, DatePrecision::YearMonth),
+        ("2021-06-15", 20_210_615, DatePrecision::YearMonthDay),
+    ] {
+        let date = input.parse::<YyyyMmDdDate>().unwrap();
+        assert_eq!(YyyyMmDdDate::new_unchecked(value), date);
+        assert_eq!(precision, date.precision());
+        assert_eq!(input, date.to_string());
+    }
+}
+
+#[test]
+fn yyyymmdd_from_invalid_string() {
+    for input in [
+        "",
+        "21",
+        "2021-6",
+        "2021-00",
+        "2021-13",
+        "2021-06-00",
+        "2021-02-30",
+        "2021-06-15-01",
+        "+021",
+    ] {
+        assert!(input.parse::<YyyyMmDdDate>().is_err(), "{input}");
+    }
+}
+
+#[test]
+fn yyyymmdd_partial_dates_are_ordered_before_full_dates() {
+    let year = YyyyMmDdDate::from_year(2021);
+    let year_month = YyyyMmDdDate::from_year_month(2021, 1);
+    let year_month_day = YyyyMmDdDate::from_year_month_day(2021, 1, 1);
+    assert!(year < year_month);
+    assert!(year_month < year_month_day);
+    assert!(year_month_day < YyyyMmDdDate::from_year(2022));
+}
@@ -20,6 +20,39 @@ pub enum ColorInvalidity {
     Rgb(RgbColorInvalidity),
 }
 
+impl Color {
+    /// Parse an RGB color from a hex string, i.e. `#rrggbb` or `#rgb`.
+    ///
+    /// See also: [`RgbColor::from_hex()`]
+    pub fn from_hex(hex: &str) -> Result<Self, ParseError> {
+        RgbColor::from_hex(hex).map(Self::Rgb)
+    }
+
+    /// Format an RGB color as a hex string, i.e. `#RRGGBB`.
+    ///
+    /// Returns `None` for indexed colors.
+    #[must_use]
+    pub fn to_hex(self) -> Option<String> {
+        match self {
+            Self::Rgb(rgb_color) => Some(rgb_color.to_hex()),
+            Self::Index(_) => None,
+        }
+    }
+
+    /// Either black or white, whatever is more readable on this color.
+    ///
+    /// Returns `None` for indexed colors.
+    ///
+    /// See also: [`RgbColor::contrasting_text_color()`]
+    #[must_use]
+    pub fn contrasting_text_color(self) -> Option<RgbColor> {
+        match self {
+            Self::Rgb(rgb_color) => Some(rgb_color.contrasting_text_color()),
+            Self::Index(_) => None,
+        }
+    }
+}
+
 impl Validate for Color {
     type Invalidity = ColorInvalidity;
 
@@ -51,6 +84,7 @@ pub struct RgbColor(RgbColorCode); // 0xRRGGBB
 impl RgbColor {
     const STRING_PREFIX: &'static str = "#";
     const STRING_LEN: usize = 1 + 2 + 2 + 2;
+    const SHORT_STRING_LEN: usize = 1 + 1 + 1 + 1;
 
     pub const RED_MASK: RgbColorCode = 0xff_00_00;
     pub const GREEN_MASK: RgbColorCode = 0x00_ff_00;
@@ -101,6 +135,81 @@ impl RgbColor {
     pub const fn blue(self) -> u8 {
         ((self.0 >> Self::BLUE_MASK.trailing_zeros()) & 0xff) as u8
     }
+
+    /// Parse a hex string, i.e. `#rrggbb` or the short form `#rgb`.
+    ///
+    /// Hex digits are case-insensitive. In the short form each digit
+    /// is repeated, i.e. `#f80` is the same as `#ff8800`.
+    pub fn from_hex(hex: &str) -> Result<Self, ParseError> {
+        let Some(digits) = hex.strip_prefix(Self::STRING_PREFIX) else {
+            return Err(ParseError::InputPrefix);
+        };
+        if !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+            return Err(ParseError::InputDigits);
+        }
+        match hex.len() {
+            Self::STRING_LEN => hex.parse(),
+            Self::SHORT_STRING_LEN => {
+                let code = u32::from_str_radix(digits, 16).map_err(ParseError::ParseIntError)?;
+                let expand = |digit: u32| ((digit << 4) | digit) as u8;
+                Ok(Self::rgb(
+                    expand((code >> 8) & 0xf),
+                    expand((code >> 4) & 0xf),
+                    expand(code & 0xf),
+                ))
+            }
+            _ => Err(ParseError::InputLen),
+        }
+    }
+
+    /// Format as hex string, i.e. `#RRGGBB`.
+    #[must_use]
+    pub fn to_hex(self) -> String {
+        self.to_string()
+    }
+
+    /// The relative luminance as defined by WCAG 2.x.
+    ///
+    /// Ranges from 0.0 for black to 1.0 for white.
+    ///
+    /// <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>
+    #[must_use]
+    pub fn relative_luminance(self) -> f64 {
+        let linearize = |channel: u8| {
+            let value = f64::from(channel) / 255.0;
+            if value <= 0.040_45 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linearize(self.red())
+            + 0.7152 * linearize(self.green())
+            + 0.0722 * linearize(self.blue())
+    }
+
+    /// The contrast ratio between two colors as defined by WCAG 2.x.
+    ///
+    /// Ranges from 1.0 for identical colors to 21.0 for black and white.
+    ///
+    /// <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>
+    #[must_use]
+    pub fn contrast_ratio(self, other: Self) -> f64 {
+        let lhs = self.relative_luminance();
+        let rhs = other.relative_luminance();
+        (lhs.max(rhs) + 0.05) / (lhs.min(rhs) + 0.05)
+    }
+
+    /// Either black or white, whatever has the higher contrast ratio
+    /// when used as the text color on this background color.
+    #[must_use]
+    pub fn contrasting_text_color(self) -> Self {
+        if self.contrast_ratio(Self::BLACK) >= self.contrast_ratio(Self::WHITE) {
+            Self::BLACK
+        } else {
+            Self::WHITE
+        }
+    }
 }
 
 impl fmt::Display for RgbColor {
@@ -119,6 +228,7 @@ impl fmt::Display for RgbColor {
 pub enum ParseError {
     InputLen,
     InputPrefix,
+    InputDigits,
     ParseIntError(ParseIntError),
 }
 
@@ -135,6 +245,7 @@ impl fmt::Display for ParseError {
                 "Invalid input prefix: expected = {expected}",
                 expected = RgbColor::STRING_PREFIX,
             ),
+            Self::InputDigits => f.write_str("Invalid input: expected hex digits"),
             Self::ParseIntError(err) => err.fmt(f),
         }
     }
@@ -53,3 +53,114 @@ fn rgb_color_channels() {
     assert_eq!(0xff, RgbColor::WHITE.green());
     assert_eq!(0xff, RgbColor::WHITE.blue());
 }
+
+#[test]
+fn rgb_color_from_hex() {
+    assert_eq!(
+        RgbColor::new(0x12_ab_ef),
+        RgbColor::from_hex("#12abef").unwrap()
+    );
+    assert_eq!(
+        RgbColor::new(0x12_ab_ef),
+        RgbColor::from_hex("#12ABEF").unwrap()
+    );
+    assert_eq!(
+        RgbColor::new(0xff_88_00),
+        RgbColor::from_hex("#f80").unwrap()
+    );
+    assert_eq!(RgbColor::WHITE, RgbColor::from_hex("#FFF").unwrap());
+    assert_eq!(RgbColor::BLACK, RgbColor::from_hex("#000").unwrap());
+    assert_eq!(Color::Rgb(RgbColor::CYAN), Color::from_hex("#0ff").unwrap());
+}
+
+#[test]
+fn rgb_color_from_hex_invalid() {
+    assert!(matches!(
+        RgbColor::from_hex("123456"),
+        Err(ParseError::InputPrefix)
+    ));
+    assert!(matches!(
+        RgbColor::from_hex(""),
+        Err(ParseError::InputPrefix)
+    ));
+    assert!(matches!(RgbColor::from_hex("#"), Err(ParseError::InputLen)));
+    assert!(matches!(
+        RgbColor::from_hex("#1234"),
+        Err(ParseError::InputLen)
+    ));
+    assert!(matches!(
+        RgbColor::from_hex("#1234567"),
+        Err(ParseError::InputLen)
+    ));
+    assert!(matches!(
+        RgbColor::from_hex("#12345g"),
+        Err(ParseError::InputDigits)
+    ));
+    assert!(matches!(
+        RgbColor::from_hex("#+12345"),
+        Err(ParseError::InputDigits)
+    ));
+    assert!(matches!(
+        RgbColor::from_hex("#-12"),
+        Err(ParseError::InputDigits)
+    ));
+    assert!(matches!(
+        RgbColor::from_hex("#ä12"),
+        Err(ParseError::InputDigits)
+    ));
+}
+
+#[test]
+fn rgb_color_to_hex_round_trip() {
+    for rgb_color in [
+        RgbColor::BLACK,
+        RgbColor::WHITE,
+        RgbColor::MAGENTA,
+        RgbColor::new(0x01_23_45),
+        RgbColor::new(0xab_cd_ef),
+    ] {
+        let hex = rgb_color.to_hex();
+        assert_eq!(7, hex.len());
+        assert_eq!(rgb_color, RgbColor::from_hex(&hex).unwrap());
+        assert_eq!(Some(hex), Color::Rgb(rgb_color).to_hex());
+    }
+    assert_eq!("#AABBCC", RgbColor::from_hex("#abc").unwrap().to_hex());
+    assert_eq!(None, Color::Index(1).to_hex());
+}
+
+#[test]
+fn rgb_color_contrasting_text_color() {
+    // Light backgrounds
+    for background_color in [
+        RgbColor::WHITE,
+        RgbColor::YELLOW,
+        RgbColor::CYAN,
+        RgbColor::GREEN,
+        RgbColor::new(0xee_ee_ee),
+    ] {
+        assert_eq!(RgbColor::BLACK, background_color.contrasting_text_color());
+    }
+    // Dark backgrounds
+    for background_color in [
+        RgbColor::BLACK,
+        RgbColor::BLUE,
+        RgbColor::new(0x80_00_00),
+        RgbColor::new(0x33_33_33),
+    ] {
+        assert_eq!(RgbColor::WHITE, background_color.contrasting_text_color());
+    }
+    assert_eq!(
+        Some(RgbColor::BLACK),
+        Color::Rgb(RgbColor::WHITE).contrasting_text_color()
+    );
+    assert_eq!(None, Color::Index(0).contrasting_text_color());
+}
+
+#[test]
+fn rgb_color_contrast_ratio() {
+    assert!((RgbColor::BLACK.relative_luminance() - 0.0).abs() < f64::EPSILON);
+    assert!((RgbColor::WHITE.relative_luminance() - 1.0).abs() < 1e-9);
+    assert!((RgbColor::BLACK.contrast_ratio(RgbColor::WHITE) - 21.0).abs() < 1e-9);
+    assert!((RgbColor::WHITE.contrast_ratio(RgbColor::BLACK) - 21.0).abs() < 1e-9);
+    assert!((RgbColor::RED.contrast_ratio(RgbColor::RED) - 1.0).abs() < f64::EPSILON);
+}
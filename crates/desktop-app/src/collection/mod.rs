@@ -45,6 +45,9 @@ use crate::{
 
 pub mod tasklet;
 
+#[cfg(test)]
+mod tests;
+
 #[must_use]
 pub const fn vfs_root_url(collection: &Collection) -> Option<&BaseUrl> {
     if let ContentPathConfig::VirtualFilePath(VirtualFilePathConfig { root_url, .. }) =
@@ -80,6 +83,13 @@ pub enum RestoreEntityStrategy {
 
     /// Create a new collection if no existing collection is found.
     LoadOrCreateNew,
+
+    /// Create a new collection if no existing collection is found
+    /// and import all files from the music directory.
+    ///
+    /// The collection only becomes ready after the initial import
+    /// has finished. The progress is reported while pending.
+    LoadOrCreateNewAndImport,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -151,7 +161,14 @@ fn parse_music_dir_path(path: &Path) -> anyhow::Result<(BaseUrl, PathBuf)> {
 impl RestoringFromMusicDirectoryContext {
     #[allow(clippy::missing_panics_doc)]
     #[allow(clippy::too_many_lines)] // TODO
-    pub async fn restore(self, env: &Environment) -> anyhow::Result<State> {
+    pub async fn restore<ReportProgressFn>(
+        self,
+        env: &Environment,
+        mut report_progress_fn: ReportProgressFn,
+    ) -> anyhow::Result<State>
+    where
+        ReportProgressFn: FnMut(RestoringFromMusicDirectoryProgress) + Clone + Send + 'static,
+    {
         let Self {
             kind,
             music_dir,
@@ -180,7 +197,7 @@ impl RestoringFromMusicDirectoryContext {
         .await?;
         log::info!(
             "Found {num_candidates} existing collection candidate(s) for selection",
-            num_candidates = candidates.len()
+            num_candidates = candidates.items.len()
         );
         let mut selected_candidate: Option<EntityWithSummary> = None;
         for candidate in candidates {
@@ -233,7 +250,9 @@ impl RestoringFromMusicDirectoryContext {
                 nested_music_dirs,
             };
             let state = RestoringFromMusicDirectoryState::Finished(
-                RestoringFromMusicDirectoryFinishedState::NestedDirectoriesConflict { candidates },
+                RestoringFromMusicDirectoryFinishedState::NestedDirectoriesConflict {
+                    candidates: candidates.items,
+                },
             );
             return Ok(State::RestoringFromMusicDirectory { context, state });
         }
@@ -253,7 +272,8 @@ impl RestoringFromMusicDirectoryContext {
                 );
                 Ok(State::RestoringFromMusicDirectory { context, state })
             }
-            RestoreEntityStrategy::LoadOrCreateNew => {
+            RestoreEntityStrategy::LoadOrCreateNew
+            | RestoreEntityStrategy::LoadOrCreateNewAndImport => {
                 // Create a new collection
                 let new_collection = Collection {
                     title: music_dir.display().to_string(),
@@ -273,6 +293,28 @@ impl RestoringFromMusicDirectoryContext {
                         .raw
                         .hdr
                         .uid;
+                if matches!(
+                    restore_entity,
+                    RestoreEntityStrategy::LoadOrCreateNewAndImport
+                ) {
+                    log::info!("Importing files into new collection {entity_uid}");
+                    let progress =
+                        Arc::new(Mutex::new(RestoringFromMusicDirectoryProgress::default()));
+                    let report_progress_fn = move |sync_progress: Progress| {
+                        let mut progress = progress.lock().unwrap();
+                        if progress.update(&sync_progress) {
+                            report_progress_fn(progress.clone());
+                        }
+                    };
+                    synchronize_vfs(
+                        env,
+                        entity_uid.clone(),
+                        default_import_track_config(),
+                        report_progress_fn,
+                        Arc::new(AtomicBool::new(false)),
+                    )
+                    .await?;
+                }
                 // Reload the newly created entity with its summary
                 let entity_with_summary = aoide_backend_embedded::collection::load_one(
                     env.db_gatekeeper(),
@@ -308,9 +350,71 @@ impl RestoringFromMusicDirectoryError {
     }
 }
 
+/// Progress of the initial import when creating a new collection
+///
+/// See also: [`RestoreEntityStrategy::LoadOrCreateNewAndImport`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoringFromMusicDirectoryProgress {
+    /// The number of entries that have been discovered while scanning
+    /// the music directory.
+    pub discovered_sources: usize,
+
+    /// The number of files that have been processed while importing,
+    /// including files that could not be imported.
+    pub imported_sources: usize,
+}
+
+impl RestoringFromMusicDirectoryProgress {
+    /// Update from the progress of synchronizing the collection
+    ///
+    /// Returns `true` if changed.
+    fn update(&mut self, progress: &Progress) -> bool {
+        let next = match progress {
+            Progress::Step2ScanDirectories(event) => Self {
+                discovered_sources: event.progress.entries.finished,
+                ..*self
+            },
+            Progress::Step4ImportFiles(event) => {
+                let aoide_core_api::media::tracker::import_files::TrackSummary {
+                    created,
+                    updated,
+                    unchanged,
+                    skipped,
+                    failed,
+                    not_imported,
+                    not_created,
+                    not_updated,
+                } = &event.progress.summary.tracks;
+                Self {
+                    imported_sources: created
+                        + updated
+                        + unchanged
+                        + skipped
+                        + failed
+                        + not_imported
+                        + not_created
+                        + not_updated,
+                    ..*self
+                }
+            }
+            _ => return false,
+        };
+        if *self == next {
+            return false;
+        }
+        *self = next;
+        true
+    }
+}
+
 #[derive(Debug)]
 pub enum RestoringFromMusicDirectoryState {
-    Pending { since: Instant, task: AbortHandle },
+    Pending {
+        since: Instant,
+        task: AbortHandle,
+        /// Only available when creating and importing a new collection.
+        progress: Option<RestoringFromMusicDirectoryProgress>,
+    },
     Finished(RestoringFromMusicDirectoryFinishedState),
 }
 
@@ -611,7 +715,9 @@ impl State {
                 context: Some(context.clone()),
                 entity_uid: None,
             };
-            async move { load_state_from_database(env, params).await }
+            let report_progress_fn =
+                this.report_restoring_from_music_directory_progress_fn(pending_since);
+            async move { load_state_from_database(env, params, report_progress_fn).await }
         });
         let abort_worker_task = worker_task.abort_handle();
         let _supervisor_task = rt.spawn({
@@ -630,6 +736,7 @@ impl State {
             state: RestoringFromMusicDirectoryState::Pending {
                 since: pending_since,
                 task: abort_worker_task.clone(),
+                progress: None,
             },
         };
 
@@ -671,7 +778,9 @@ impl State {
                         entity_uid: None,
                         context: Some(context.clone()),
                     };
-                    async move { load_state_from_database(env, params).await }
+                    let report_progress_fn =
+                        this.report_restoring_from_music_directory_progress_fn(pending_since);
+                    async move { load_state_from_database(env, params, report_progress_fn).await }
                 });
                 let abort_worker_task = worker_task.abort_handle();
                 let _supervisor_task = rt.spawn({
@@ -690,6 +799,7 @@ impl State {
                     state: RestoringFromMusicDirectoryState::Pending {
                         since: pending_since,
                         task: abort_worker_task.clone(),
+                        progress: None,
                     },
                 };
 
@@ -749,7 +859,8 @@ impl State {
 
         let worker_task = rt.spawn({
             let env = Arc::clone(env);
-            async move { load_state_from_database(env, params).await }
+            // No progress is reported without a context.
+            async move { load_state_from_database(env, params, |_| {}).await }
         });
         let abort_worker_task = worker_task.abort_handle();
         let _supervisor_task = rt.spawn({
@@ -786,6 +897,7 @@ impl State {
                 RestoringFromMusicDirectoryState::Pending {
                     since: pending_since,
                     task,
+                    progress: _,
                 },
         } = self
         else {
@@ -836,6 +948,33 @@ impl State {
         }
     }
 
+    fn update_restoring_from_music_directory_progress(
+        &mut self,
+        pending_since: Instant,
+        next_progress: RestoringFromMusicDirectoryProgress,
+    ) -> ActionEffect {
+        let Self::RestoringFromMusicDirectory {
+            state:
+                RestoringFromMusicDirectoryState::Pending {
+                    since, progress, ..
+                },
+            ..
+        } = self
+        else {
+            log::debug!("State changed while restoring from music directory: current state {self:?} - discarding {next_progress:?}");
+            return ActionEffect::Unchanged;
+        };
+        if *since != pending_since {
+            log::debug!("State changed while restoring from music directory: current state {self:?} - discarding {next_progress:?}");
+            return ActionEffect::Unchanged;
+        }
+        if progress.as_ref() == Some(&next_progress) {
+            return ActionEffect::Unchanged;
+        }
+        *progress = Some(next_progress);
+        ActionEffect::Changed
+    }
+
     fn continue_after_loading_from_database_task_joined(
         &mut self,
         joined: JoinedTask<anyhow::Result<State>>,
@@ -1149,6 +1288,18 @@ impl SharedState {
         })
     }
 
+    fn report_restoring_from_music_directory_progress_fn(
+        &self,
+        pending_since: Instant,
+    ) -> impl FnMut(RestoringFromMusicDirectoryProgress) + Clone + Send + 'static + use<> {
+        let this = self.clone();
+        move |progress| {
+            let _ = modify_shared_state_action_effect(&this.0, |state| {
+                state.update_restoring_from_music_directory_progress(pending_since, progress)
+            });
+        }
+    }
+
     fn continue_after_loading_from_database_task_joined(
         &self,
         joined: JoinedTask<anyhow::Result<State>>,
@@ -1238,12 +1389,14 @@ struct LoadStateFromDatabaseParams {
     context: Option<RestoringFromMusicDirectoryContext>,
 }
 
-async fn load_state_from_database<E>(
+async fn load_state_from_database<E, ReportProgressFn>(
     env: E,
     params: LoadStateFromDatabaseParams,
+    report_progress_fn: ReportProgressFn,
 ) -> anyhow::Result<State>
 where
     E: AsRef<Environment> + Send + 'static,
+    ReportProgressFn: FnMut(RestoringFromMusicDirectoryProgress) + Clone + Send + 'static,
 {
     let LoadStateFromDatabaseParams {
         entity_uid,
@@ -1290,18 +1443,25 @@ where
             uid = entity_with_summary.entity.hdr.uid
         );
     }
-    context.restore(env.as_ref()).await
+    context.restore(env.as_ref(), report_progress_fn).await
 }
 
-async fn synchronize_vfs<E, ReportProgressFn>(
-    env: E,
+fn default_import_track_config() -> ImportTrackConfig {
+    ImportTrackConfig {
+        // TODO: Customize faceted tag mapping
+        faceted_tag_mapping: predefined_faceted_tag_mapping_config(),
+        ..Default::default()
+    }
+}
+
+async fn synchronize_vfs<ReportProgressFn>(
+    env: &Environment,
     entity_uid: EntityUid,
     import_track_config: ImportTrackConfig,
     report_progress_fn: ReportProgressFn,
     abort_flag: Arc<AtomicBool>,
 ) -> SynchronizeVfsResult
 where
-    E: AsRef<Environment> + Send + 'static,
     ReportProgressFn: FnMut(batch::synchronize_collection_vfs::Progress) + Clone + Send + 'static,
 {
     let params = batch::synchronize_collection_vfs::Params {
@@ -1315,7 +1475,7 @@ where
         unsynchronized_tracks: UnsynchronizedTracks::Find,
     };
     batch::synchronize_collection_vfs::synchronize_collection_vfs(
-        env.as_ref().db_gatekeeper(),
+        env.db_gatekeeper(),
         entity_uid,
         params,
         std::convert::identity,
@@ -1353,17 +1513,13 @@ impl SynchronizingVfsTask {
                     progress_pub.lock().unwrap().write(Some(progress));
                 }
             };
-            let import_track_config = ImportTrackConfig {
-                // TODO: Customize faceted tag mapping
-                faceted_tag_mapping: predefined_faceted_tag_mapping_config(),
-                ..Default::default()
-            };
+            let import_track_config = default_import_track_config();
             let abort_flag = Arc::clone(&abort_flag);
             let entity_uid = continuation.context.entity.hdr.uid.clone();
             async move {
                 log::debug!("Synchronizing collection with local file system...");
                 synchronize_vfs(
-                    env,
+                    &env,
                     entity_uid,
                     import_track_config,
                     report_progress_fn,
@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::num::{NonZeroU32, NonZeroU64};
+
+use aoide_backend_embedded::storage::DatabaseConfig;
+use aoide_storage_sqlite::connection::{
+    pool::{gatekeeper::Config as GatekeeperConfig, Config as PoolConfig},
+    Config as ConnectionConfig, Storage,
+};
+
+use super::*;
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+fn commission_environment(storage_dir: &Path) -> Environment {
+    Environment::commission(&DatabaseConfig {
+        connection: ConnectionConfig {
+            storage: Storage::File {
+                path: storage_dir.join("aoide.sqlite"),
+            },
+            pool: PoolConfig {
+                max_size: NonZeroU32::MIN,
+                gatekeeper: GatekeeperConfig {
+                    acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    fairness_policy: Default::default(),
+                },
+            },
+            pragma: Default::default(),
+        },
+        migrate_schema: None,
+        read_only: false,
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn report_progress_while_creating_and_importing_new_collection() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+    for file_name in ["a.mp3", "b.mp3"] {
+        std::fs::copy(FIXTURE_FILE_PATH, music_dir.path().join(file_name)).unwrap();
+    }
+    let env = Arc::new(commission_environment(storage_dir.path()));
+
+    let state = SharedState::default();
+    let mut subscriber = state.subscribe_changed();
+    let reaction = state
+        .spawn_restoring_from_music_directory_task(
+            &tokio::runtime::Handle::current(),
+            &env,
+            None,
+            DirPath::from_owned(music_dir.path().to_path_buf()),
+            RestoreEntityStrategy::LoadOrCreateNewAndImport,
+            NestedMusicDirectoriesStrategy::Permit,
+        )
+        .unwrap();
+    assert!(matches!(
+        reaction,
+        SpawnRestoringFromMusicDirectoryTaskReaction::SpawnedAndChanged(_)
+    ));
+
+    let mut reported_progress = Vec::new();
+    loop {
+        subscriber.changed().await.unwrap();
+        match &*subscriber.read_ack() {
+            State::RestoringFromMusicDirectory {
+                state: RestoringFromMusicDirectoryState::Pending { progress, .. },
+                ..
+            } => {
+                // Progress is only reported while pending
+                reported_progress.extend(progress.clone());
+            }
+            State::Ready { summary, .. } => {
+                assert_eq!(2, summary.tracks.total_count);
+                break;
+            }
+            state => panic!("unexpected state: {state:?}"),
+        }
+    }
+
+    // Progress updates precede the ready state
+    assert!(!reported_progress.is_empty());
+    // Progress never decreases
+    assert!(reported_progress.windows(2).all(|pair| {
+        pair[0].discovered_sources <= pair[1].discovered_sources
+            && pair[0].imported_sources <= pair[1].imported_sources
+    }));
+}
@@ -10,6 +10,9 @@ pub use aoide_backend_embedded::Environment;
 
 pub mod fs;
 
+/// Change notifications
+pub mod notify;
+
 /// Collection management
 pub mod collection;
 
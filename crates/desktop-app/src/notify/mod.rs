@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::future::Future;
+
+use discro::Subscriber;
+use tokio::sync::broadcast;
+
+use crate::ActionEffect;
+
+/// Default capacity of the notification channel.
+///
+/// Slow receivers that fall behind by more events will skip the oldest ones.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryChangedKind {
+    Collection,
+    TrackSearch,
+    Settings,
+}
+
+/// Notification that some state of the library has (maybe) changed.
+///
+/// Only the kind of the affected state is transmitted. Receivers
+/// are supposed to read the current state themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryChanged {
+    pub kind: LibraryChangedKind,
+}
+
+/// Broadcasts change notifications to any number of subscribers.
+#[derive(Debug, Clone)]
+pub struct LibraryNotifier {
+    tx: broadcast::Sender<LibraryChanged>,
+}
+
+impl LibraryNotifier {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx }
+    }
+
+    #[must_use]
+    pub fn subscribe(&self) -> LibrarySubscriber {
+        LibrarySubscriber(self.tx.subscribe())
+    }
+
+    pub fn notify(&self, kind: LibraryChangedKind) {
+        // Sending only fails if there are no subscribers.
+        let _ = self.tx.send(LibraryChanged { kind });
+    }
+
+    /// Notify subscribers unless the state is unchanged.
+    pub fn notify_action_effect(
+        &self,
+        kind: LibraryChangedKind,
+        effect: ActionEffect,
+    ) -> ActionEffect {
+        match effect {
+            ActionEffect::Unchanged => (),
+            ActionEffect::MaybeChanged | ActionEffect::Changed => self.notify(kind),
+        }
+        effect
+    }
+
+    /// Forward all changes of a shared state as notifications.
+    ///
+    /// The returned future finishes when the publisher of the state
+    /// has been dropped.
+    pub fn forward_changes<State>(
+        &self,
+        mut subscriber: Subscriber<State>,
+        kind: LibraryChangedKind,
+    ) -> impl Future<Output = ()> + Send + 'static + use<State>
+    where
+        State: Send + Sync + 'static,
+    {
+        let this = self.clone();
+        async move {
+            log::debug!("Starting forward_changes: {kind:?}");
+            loop {
+                if subscriber.changed().await.is_err() {
+                    // No publisher(s).
+                    break;
+                }
+                // Mark the current state as seen.
+                drop(subscriber.read_ack());
+                this.notify(kind);
+            }
+            log::debug!("Stopping forward_changes: {kind:?}");
+        }
+    }
+}
+
+impl Default for LibraryNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[derive(Debug)]
+pub struct LibrarySubscriber(broadcast::Receiver<LibraryChanged>);
+
+impl LibrarySubscriber {
+    /// Receive the next notification.
+    ///
+    /// Subscribers that are lagging behind silently skip the oldest
+    /// notifications and continue with those that are still buffered.
+    ///
+    /// Returns `None` after all notifiers have been dropped.
+    pub async fn recv(&mut self) -> Option<LibraryChanged> {
+        let Self(rx) = self;
+        loop {
+            match rx.recv().await {
+                Ok(changed) => return Some(changed),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::debug!("Skipped {skipped} change notification(s)");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aoide_core::util::fs::DirPath;
+
+use super::*;
+use crate::settings;
+
+#[tokio::test]
+async fn multiple_subscribers_receive_change() {
+    let notifier = LibraryNotifier::default();
+    let mut first = notifier.subscribe();
+    let mut second = notifier.subscribe();
+
+    let settings_state = settings::SharedState::default();
+    let forward = tokio::spawn(notifier.forward_changes(
+        settings_state.subscribe_changed(),
+        LibraryChangedKind::Settings,
+    ));
+
+    let music_dir = DirPath::from_owned(std::env::temp_dir());
+    assert_eq!(
+        ActionEffect::Changed,
+        settings_state.update_music_dir(Some(&music_dir))
+    );
+
+    let expected = LibraryChanged {
+        kind: LibraryChangedKind::Settings,
+    };
+    assert_eq!(Some(expected), first.recv().await);
+    assert_eq!(Some(expected), second.recv().await);
+
+    drop(settings_state);
+    forward.await.unwrap();
+}
+
+#[tokio::test]
+async fn lagging_subscriber_skips_oldest() {
+    let notifier = LibraryNotifier::new(1);
+    let mut subscriber = notifier.subscribe();
+    notifier.notify(LibraryChangedKind::Collection);
+    notifier.notify(LibraryChangedKind::TrackSearch);
+    assert_eq!(
+        Some(LibraryChanged {
+            kind: LibraryChangedKind::TrackSearch,
+        }),
+        subscriber.recv().await
+    );
+    drop(notifier);
+    assert_eq!(None, subscriber.recv().await);
+}
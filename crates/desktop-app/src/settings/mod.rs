@@ -12,6 +12,7 @@ use url::Url;
 
 use aoide_backend_embedded::storage::DatabaseConfig;
 use aoide_core::util::fs::DirPath;
+use aoide_core_api::track::search::{Filter, SortOrder};
 
 use crate::{modify_shared_state_action_effect, ActionEffect};
 
@@ -23,9 +24,34 @@ pub const DEFAULT_DATABASE_FILE_NAME: &str = "aoide";
 
 pub const DEFAULT_DATABASE_FILE_SUFFIX: &str = "sqlite";
 
+/// Version of the persisted settings.
+///
+/// Must be incremented when the persisted representation changes.
+/// Older versions are migrated when loading, see [`State::from_bytes()`].
+pub const VERSION: u32 = 2;
+
 pub mod tasklet;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+mod track_search;
+use self::track_search::PersistedTrackSearch;
+
+/// The last track search.
+///
+/// Only the query and the number of fetched results are persisted,
+/// but not the results themselves. The search is supposed to be
+/// re-issued when restoring it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PersistedTrackSearch", into = "PersistedTrackSearch")]
+pub struct TrackSearch {
+    pub filter: Option<Filter>,
+
+    pub ordering: Vec<SortOrder>,
+
+    /// The number of fetched results.
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct State {
     /// File path of the _SQLite_ database.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -43,6 +69,10 @@ pub struct State {
     /// ignored.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub collection_kind: Option<String>,
+
+    /// The last track search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_track_search: Option<TrackSearch>,
 }
 
 impl State {
@@ -78,12 +108,35 @@ impl State {
             file_path = file_path.display()
         );
         match fs::read(&file_path) {
-            Ok(bytes) => ron::de::from_bytes(&bytes).map_err(Into::into),
+            Ok(bytes) => Self::from_bytes(&bytes),
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Default::default()),
             Err(err) => Err(err.into()),
         }
     }
 
+    /// Deserialize persisted settings.
+    ///
+    /// Older versions are migrated to the current [`VERSION`]. Newer,
+    /// unknown versions are rejected.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<State> {
+        let PersistedVersion { version } = ron::de::from_bytes(bytes)?;
+        match version {
+            1 => {
+                let v1: PersistedStateV1 = ron::de::from_bytes(bytes)?;
+                log::info!("Migrating settings from version 1 to {VERSION}");
+                Ok(v1.into())
+            }
+            VERSION => {
+                let current: PersistedState = ron::de::from_bytes(bytes)?;
+                debug_assert_eq!(VERSION, current.version);
+                Ok(current.into())
+            }
+            version => {
+                anyhow::bail!("unsupported settings version {version} (current: {VERSION})");
+            }
+        }
+    }
+
     pub fn save(&self, parent_dir: &Path) -> anyhow::Result<()> {
         let file_path = new_settings_file_path(parent_dir.to_path_buf());
         log::info!(
@@ -91,7 +144,11 @@ impl State {
             file_path = file_path.display()
         );
         let mut bytes = vec![];
-        ron::ser::to_writer_pretty(&mut bytes, self, Default::default())?;
+        ron::ser::to_writer_pretty(
+            &mut bytes,
+            &PersistedState::from(self.clone()),
+            Default::default(),
+        )?;
         if let Some(parent_path) = file_path.parent() {
             fs::create_dir_all(parent_path)?;
         }
@@ -144,10 +201,13 @@ impl State {
                     gatekeeper: aoide_storage_sqlite::connection::pool::gatekeeper::Config {
                         acquire_read_timeout_millis: 10_000.try_into().expect("non-zero"),
                         acquire_write_timeout_millis: 30_000.try_into().expect("non-zero"),
+                        fairness_policy: Default::default(),
                     },
                 },
+                pragma: Default::default(),
             },
             migrate_schema: None,
+            read_only: false,
         };
         Ok(config)
     }
@@ -174,6 +234,111 @@ impl State {
         };
         ActionEffect::Changed
     }
+
+    #[must_use]
+    pub const fn last_track_search(&self) -> Option<&TrackSearch> {
+        self.last_track_search.as_ref()
+    }
+
+    fn update_last_track_search(&mut self, last_track_search: Option<TrackSearch>) -> ActionEffect {
+        if self.last_track_search == last_track_search {
+            log::debug!("Unchanged last track search: {last_track_search:?}");
+            return ActionEffect::Unchanged;
+        }
+        log::debug!("Updating last track search: {last_track_search:?}");
+        self.last_track_search = last_track_search;
+        ActionEffect::Changed
+    }
+}
+
+/// Only the version of the persisted settings.
+///
+/// The initial version had no version field and defaults to 1.
+#[derive(Deserialize)]
+struct PersistedVersion {
+    #[serde(default = "initial_version")]
+    version: u32,
+}
+
+const fn initial_version() -> u32 {
+    1
+}
+
+/// The initial, unversioned representation.
+#[derive(Deserialize)]
+struct PersistedStateV1 {
+    #[serde(default)]
+    database_url: Option<Url>,
+    #[serde(default)]
+    music_dir: Option<DirPath<'static>>,
+    #[serde(default)]
+    collection_kind: Option<String>,
+}
+
+impl From<PersistedStateV1> for State {
+    fn from(from: PersistedStateV1) -> Self {
+        let PersistedStateV1 {
+            database_url,
+            music_dir,
+            collection_kind,
+        } = from;
+        Self {
+            database_url,
+            music_dir,
+            collection_kind,
+            last_track_search: None,
+        }
+    }
+}
+
+/// The current representation with an explicit [`VERSION`].
+#[derive(Serialize, Deserialize)]
+struct PersistedState {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    database_url: Option<Url>,
+    #[serde(default)]
+    music_dir: Option<DirPath<'static>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    collection_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_track_search: Option<TrackSearch>,
+}
+
+impl From<State> for PersistedState {
+    fn from(from: State) -> Self {
+        let State {
+            database_url,
+            music_dir,
+            collection_kind,
+            last_track_search,
+        } = from;
+        Self {
+            version: VERSION,
+            database_url,
+            music_dir,
+            collection_kind,
+            last_track_search,
+        }
+    }
+}
+
+impl From<PersistedState> for State {
+    fn from(from: PersistedState) -> Self {
+        let PersistedState {
+            version: _,
+            database_url,
+            music_dir,
+            collection_kind,
+            last_track_search,
+        } = from;
+        Self {
+            database_url,
+            music_dir,
+            collection_kind,
+            last_track_search,
+        }
+    }
 }
 
 #[must_use]
@@ -224,6 +389,12 @@ impl SharedState {
     pub fn update_music_dir(&self, music_dir: Option<&DirPath<'_>>) -> ActionEffect {
         modify_shared_state_action_effect(&self.0, |state| state.update_music_dir(music_dir))
     }
+
+    pub fn update_last_track_search(&self, last_track_search: Option<TrackSearch>) -> ActionEffect {
+        modify_shared_state_action_effect(&self.0, |state| {
+            state.update_last_track_search(last_track_search)
+        })
+    }
 }
 
 impl Default for SharedState {
@@ -231,3 +402,6 @@ impl Default for SharedState {
         Self::new(Default::default())
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+const V1_BYTES: &[u8] = br#"(
+    database_url: Some("file:///home/test/aoide.sqlite"),
+    music_dir: Some("/home/test/Music"),
+    collection_kind: Some("mixxx"),
+)"#;
+
+#[test]
+fn load_v1_and_upgrade_to_current_version() {
+    let state = State::from_bytes(V1_BYTES).unwrap();
+    assert_eq!(
+        State {
+            database_url: Some("file:///home/test/aoide.sqlite".parse().unwrap()),
+            music_dir: Some(DirPath::from_owned(PathBuf::from("/home/test/Music"))),
+            collection_kind: Some("mixxx".to_owned()),
+            last_track_search: None,
+        },
+        state
+    );
+
+    // Saving writes the current version
+    let temp_dir = tempfile::tempdir().unwrap();
+    state.save(temp_dir.path()).unwrap();
+    let bytes = fs::read(new_settings_file_path(temp_dir.path().to_path_buf())).unwrap();
+    let PersistedVersion { version } = ron::de::from_bytes(&bytes).unwrap();
+    assert_eq!(VERSION, version);
+    assert_eq!(state, State::load(temp_dir.path()).unwrap());
+}
+
+#[test]
+fn save_and_load_last_track_search() {
+    use aoide_core::tag::FacetId;
+    use aoide_core_api::{
+        filtering::{NumericPredicate, ScalarFieldFilter, StringPredicate},
+        tag::search::{FacetsFilter, Filter as TagFilter},
+        track::search::{NumericField, PhraseFieldFilter, SortField, StringField},
+        SortDirection,
+    };
+
+    let state = State {
+        database_url: Some("file:///home/test/aoide.sqlite".parse().unwrap()),
+        last_track_search: Some(TrackSearch {
+            filter: Some(Filter::All(vec![
+                Filter::Phrase(PhraseFieldFilter {
+                    fields: vec![StringField::ContentPath],
+                    terms: vec!["house".to_owned()],
+                }),
+                Filter::Not(Box::new(Filter::Tag(TagFilter {
+                    facets: Some(FacetsFilter::AnyOf(vec![FacetId::new_unchecked(
+                        "genre".into(),
+                    )
+                    .into()])),
+                    label: Some(StringPredicate::Equals("Techno".into())),
+                    ..Default::default()
+                }))),
+                Filter::Numeric(ScalarFieldFilter {
+                    field: NumericField::MusicTempoBpm,
+                    predicate: NumericPredicate::GreaterOrEqual(120.0),
+                }),
+            ])),
+            ordering: vec![SortOrder {
+                field: SortField::AlbumTitle,
+                direction: SortDirection::Ascending,
+            }],
+            offset: 250,
+        }),
+        ..Default::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    state.save(temp_dir.path()).unwrap();
+    assert_eq!(state, State::load(temp_dir.path()).unwrap());
+}
+
+#[test]
+fn reject_unknown_newer_version() {
+    let bytes = format!("(version: {next_version})", next_version = VERSION + 1);
+    assert!(State::from_bytes(bytes.as_bytes()).is_err());
+}
+
+#[test]
+fn restore_corrupt_file_falls_back_to_defaults() {
+    let config_dir = tempfile::tempdir().unwrap();
+    let data_dir = tempfile::tempdir().unwrap();
+    fs::write(
+        new_settings_file_path(config_dir.path().to_path_buf()),
+        b"(music_dir: Some(\"/home/test/Music\"",
+    )
+    .unwrap();
+    assert!(State::load(config_dir.path()).is_err());
+    let state = State::restore(config_dir.path(), || Ok(data_dir.path().to_path_buf())).unwrap();
+    assert_eq!(None, state.music_dir);
+    assert_eq!(None, state.collection_kind);
+    // The default database is used
+    assert_eq!(
+        Url::from_file_path(default_database_file_path(data_dir.path().to_path_buf())).ok(),
+        state.database_url
+    );
+}
@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Persistent representation of [`TrackSearch`].
+
+use serde::{Deserialize, Serialize};
+
+use aoide_repo::track::search::{PersistedFilter, PersistedSortOrder};
+
+use super::TrackSearch;
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct PersistedTrackSearch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filter: Option<PersistedFilter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ordering: Vec<PersistedSortOrder>,
+    #[serde(default)]
+    offset: usize,
+}
+
+impl From<TrackSearch> for PersistedTrackSearch {
+    fn from(from: TrackSearch) -> Self {
+        let TrackSearch {
+            filter,
+            ordering,
+            offset,
+        } = from;
+        Self {
+            filter: filter.map(Into::into),
+            ordering: ordering.into_iter().map(Into::into).collect(),
+            offset,
+        }
+    }
+}
+
+impl From<PersistedTrackSearch> for TrackSearch {
+    fn from(from: PersistedTrackSearch) -> Self {
+        let PersistedTrackSearch {
+            filter,
+            ordering,
+            offset,
+        } = from;
+        Self {
+            filter: filter.map(Into::into),
+            ordering: ordering.into_iter().map(Into::into).collect(),
+            offset,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Export of track search results as CSV.
+
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use aoide_core::{
+    media::content::ContentMetadata,
+    tag::Label,
+    track::{tag::FACET_ID_GENRE, Track},
+};
+
+use super::repo_search::FetchedEntity;
+
+/// Separates multiple values within a single field, e.g. genres.
+const MULTI_VALUE_SEPARATOR: &str = "; ";
+
+/// A column of the exported CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportField {
+    Title,
+    Artist,
+    Album,
+    Bpm,
+    Key,
+    /// Duration in seconds.
+    Duration,
+    Genre,
+}
+
+impl ExportField {
+    pub const ALL: &'static [Self] = &[
+        Self::Title,
+        Self::Artist,
+        Self::Album,
+        Self::Bpm,
+        Self::Key,
+        Self::Duration,
+        Self::Genre,
+    ];
+
+    #[must_use]
+    pub const fn header(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Artist => "artist",
+            Self::Album => "album",
+            Self::Bpm => "bpm",
+            Self::Key => "key",
+            Self::Duration => "duration",
+            Self::Genre => "genre",
+        }
+    }
+
+    fn value(self, track: &Track) -> Option<Cow<'_, str>> {
+        match self {
+            Self::Title => track.track_title().map(Into::into),
+            Self::Artist => track.track_artist().map(Into::into),
+            Self::Album => track.album_title().map(Into::into),
+            Self::Bpm => track
+                .metrics
+                .tempo_bpm
+                .map(|tempo_bpm| tempo_bpm.value().to_string().into()),
+            Self::Key => track
+                .metrics
+                .key_signature
+                .map(|key_signature| key_signature.to_string().into()),
+            Self::Duration => {
+                let ContentMetadata::Audio(audio) = &track.media_source.content.metadata;
+                audio
+                    .duration
+                    .map(|duration| format!("{:.3}", duration.value() / 1000.0).into())
+            }
+            Self::Genre => {
+                let genres = track
+                    .tags
+                    .facets
+                    .iter()
+                    .filter(|faceted_tags| faceted_tags.facet_id == *FACET_ID_GENRE)
+                    .flat_map(|faceted_tags| &faceted_tags.tags)
+                    .filter_map(|plain_tag| plain_tag.label.as_ref())
+                    .map(Label::as_str)
+                    .collect::<Vec<_>>();
+                (!genres.is_empty()).then(|| genres.join(MULTI_VALUE_SEPARATOR).into())
+            }
+        }
+    }
+}
+
+/// Quote a field if it contains a separator, quotes, or line breaks.
+///
+/// Quotes within the field are escaped by doubling them (RFC 4180).
+fn escape_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+fn write_record<'a>(
+    writer: &mut impl Write,
+    fields: impl IntoIterator<Item = &'a str>,
+) -> io::Result<()> {
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(escape_field(field).as_bytes())?;
+    }
+    writer.write_all(b"\r\n")
+}
+
+/// Write a header row and one row per track with the selected columns.
+///
+/// Missing values are written as empty fields.
+pub fn write_tracks_csv<'a>(
+    writer: &mut impl Write,
+    tracks: impl IntoIterator<Item = &'a Track>,
+    fields: &[ExportField],
+) -> io::Result<()> {
+    write_record(writer, fields.iter().map(|field| field.header()))?;
+    for track in tracks {
+        let values = fields
+            .iter()
+            .map(|field| field.value(track))
+            .collect::<Vec<_>>();
+        write_record(
+            writer,
+            values
+                .iter()
+                .map(|value| value.as_deref().unwrap_or_default()),
+        )?;
+    }
+    writer.flush()
+}
+
+/// Export the fetched entities of a track search to a CSV file.
+///
+/// An existing file at `path` is overwritten.
+pub fn export_search_results_csv(
+    path: &Path,
+    fetched_entities: &[FetchedEntity],
+    fields: &[ExportField],
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_tracks_csv(
+        &mut writer,
+        fetched_entities
+            .iter()
+            .map(|fetched_entity| &fetched_entity.entity.body.track),
+        fields,
+    )
+}
+
+#[cfg(test)]
+mod tests;
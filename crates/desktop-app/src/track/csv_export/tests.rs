@@ -0,0 +1,197 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use nonicle::CanonicalizeInto as _;
+
+use aoide_core::{
+    audio::DurationMs,
+    media::{
+        self,
+        content::{AudioContentMetadata, ContentLink},
+    },
+    music::{
+        key::{KeyCode, KeySignature},
+        tempo::TempoBpm,
+    },
+    tag::{FacetedTags, PlainTag, Tags},
+    track::{
+        actor::{Actor, Kind as ActorKind, Role as ActorRole},
+        EntityBody, EntityHeader,
+    },
+    util::clock::OffsetDateTimeMs,
+};
+
+use super::*;
+
+/// Parse CSV records according to RFC 4180.
+fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    assert!(!quoted);
+    assert!(field.is_empty() && record.is_empty());
+    records
+}
+
+fn new_fetched_entity(
+    title: &str,
+    artist: &str,
+    genres: &[&str],
+    tempo_bpm: Option<TempoBpm>,
+) -> FetchedEntity {
+    let media_source = media::Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: media::Content {
+            link: ContentLink {
+                path: format!("{title}.mp3").into(),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            metadata_flags: Default::default(),
+            metadata: AudioContentMetadata {
+                duration: Some(DurationMs::new(123_456.0)),
+                ..Default::default()
+            }
+            .into(),
+            digest: None,
+            size: None,
+        },
+        artwork: None,
+    };
+    let mut track = Track::new_from_media_source(media_source);
+    track.set_track_title(title);
+    track.set_album_title("Album");
+    track.actors = vec![Actor {
+        role: ActorRole::Artist,
+        kind: ActorKind::Summary,
+        name: artist.to_owned(),
+        role_notes: None,
+    }]
+    .canonicalize_into();
+    track.metrics.tempo_bpm = tempo_bpm;
+    track.metrics.key_signature = Some(KeySignature::new(KeyCode::Amin));
+    if !genres.is_empty() {
+        track.tags = Tags {
+            plain: vec![],
+            facets: vec![FacetedTags {
+                facet_id: FACET_ID_GENRE.clone_owned(),
+                tags: genres
+                    .iter()
+                    .map(|genre| PlainTag {
+                        label: Some(Label::from_unchecked((*genre).to_owned())),
+                        score: Default::default(),
+                    })
+                    .collect(),
+            }],
+        }
+        .canonicalize_into();
+    }
+    let entity_body = EntityBody {
+        track,
+        updated_at: OffsetDateTimeMs::now_utc(),
+        last_synchronized_rev: None,
+        content_url: None,
+    };
+    FetchedEntity {
+        offset_hash: 0,
+        entity: aoide_core::track::Entity::new(EntityHeader::initial_random(), entity_body),
+    }
+}
+
+#[test]
+fn escape_fields() {
+    assert_eq!("plain", escape_field("plain"));
+    assert_eq!("\"a,b\"", escape_field("a,b"));
+    assert_eq!("\"say \"\"hi\"\"\"", escape_field("say \"hi\""));
+    assert_eq!("\"line\nbreak\"", escape_field("line\nbreak"));
+}
+
+#[test]
+fn export_and_parse_back() {
+    let fetched_entities = [
+        new_fetched_entity(
+            "Hello, World",
+            "The \"Quoted\" Band",
+            &["Rock", "Pop"],
+            Some(TempoBpm::new(123.5)),
+        ),
+        new_fetched_entity("Multi\nLine", "Artist", &["Jazz"], None),
+    ];
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.csv");
+    export_search_results_csv(&path, &fetched_entities, ExportField::ALL).unwrap();
+
+    let records = parse_csv(&std::fs::read_to_string(&path).unwrap());
+    let key = KeySignature::new(KeyCode::Amin).to_string();
+    assert_eq!(
+        vec![
+            vec!["title", "artist", "album", "bpm", "key", "duration", "genre"],
+            vec![
+                "Hello, World",
+                "The \"Quoted\" Band",
+                "Album",
+                "123.5",
+                key.as_str(),
+                "123.456",
+                "Pop; Rock",
+            ],
+            vec![
+                "Multi\nLine",
+                "Artist",
+                "Album",
+                "",
+                key.as_str(),
+                "123.456",
+                "Jazz",
+            ],
+        ],
+        records
+    );
+}
+
+#[test]
+fn export_selected_fields_in_order() {
+    let fetched_entities = [new_fetched_entity("Title", "Artist, Jr.", &[], None)];
+    let mut output = Vec::new();
+    write_tracks_csv(
+        &mut output,
+        fetched_entities
+            .iter()
+            .map(|fetched_entity| &fetched_entity.entity.body.track),
+        &[ExportField::Artist, ExportField::Bpm, ExportField::Title],
+    )
+    .unwrap();
+    let records = parse_csv(std::str::from_utf8(&output).unwrap());
+    assert_eq!(
+        vec![
+            vec!["artist", "bpm", "title"],
+            vec!["Artist, Jr.", "", "Title"]
+        ],
+        records
+    );
+}
@@ -1,4 +1,30 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use aoide_core::{track::EntityUid, CollectionUid};
+
+pub use aoide_core_api::track::edit_tags::{Summary as TagEditSummary, TagEdit};
+
+use crate::Environment;
+
+pub mod csv_export;
+pub use self::csv_export::{export_search_results_csv, ExportField};
+
 pub mod repo_search;
+
+/// Apply a tag edit to multiple tracks of a collection.
+///
+/// Tracks that could not be edited are reported in the summary
+/// instead of failing the whole operation.
+pub async fn apply_tag_edit(
+    env: &Environment,
+    collection_uid: CollectionUid,
+    track_uids: Vec<EntityUid>,
+    edit: TagEdit,
+) -> aoide_backend_embedded::Result<TagEditSummary> {
+    aoide_backend_embedded::track::edit_tags(env.db_gatekeeper(), collection_uid, track_uids, edit)
+        .await
+}
+
+#[cfg(test)]
+mod tests;
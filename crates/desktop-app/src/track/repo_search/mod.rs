@@ -1,7 +1,13 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{hash::Hash as _, num::NonZeroUsize, sync::Arc, time::Instant};
+use std::{
+    future::Future,
+    hash::Hash as _,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use discro::Publisher;
 use highway::{HighwayHash, HighwayHasher, Key};
@@ -14,7 +20,7 @@ use aoide_core::{
 use aoide_core_api::{track::search::Params, Pagination};
 use tokio::task::AbortHandle;
 
-use crate::{modify_shared_state_action_effect, ActionEffect, Environment, JoinedTask};
+use crate::{modify_shared_state_action_effect, settings, ActionEffect, Environment, JoinedTask};
 
 pub mod tasklet;
 
@@ -183,6 +189,8 @@ impl FetchState {
             // No effect
             return ActionEffect::Unchanged;
         }
+        // A pending task has been superseded and its results would be discarded.
+        let _ = self.abort_pending_task();
         *self = Default::default();
         debug_assert!(matches!(self, Self::Initial));
         ActionEffect::Changed
@@ -348,11 +356,43 @@ pub struct MemoDelta {
     pub fetch: Option<FetchMemo>,
 }
 
+/// Maximum number of collections for which the last search is remembered.
+pub const MAX_REMEMBERED_COLLECTION_SEARCHES: usize = 8;
+
+/// The last searches of recently visited collections.
+///
+/// Ordered from least to most recently used. Bounded by
+/// [`MAX_REMEMBERED_COLLECTION_SEARCHES`].
+#[derive(Debug, Default)]
+struct CollectionSearches(Vec<(CollectionUid, settings::TrackSearch)>);
+
+impl CollectionSearches {
+    fn remember(&mut self, collection_uid: CollectionUid, last_search: settings::TrackSearch) {
+        let Self(searches) = self;
+        searches.retain(|(uid, _)| *uid != collection_uid);
+        if searches.len() >= MAX_REMEMBERED_COLLECTION_SEARCHES {
+            // Forget the least recently used search.
+            searches.remove(0);
+        }
+        searches.push((collection_uid, last_search));
+    }
+
+    fn take(&mut self, collection_uid: &CollectionUid) -> Option<settings::TrackSearch> {
+        let Self(searches) = self;
+        let index = searches.iter().position(|(uid, _)| uid == collection_uid)?;
+        let (_, last_search) = searches.remove(index);
+        Some(last_search)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct State {
     default_params: Params,
     context: Context,
+    debounce_delay: Option<Duration>,
     fetch: FetchState,
+    restored_offset: Option<NonZeroUsize>,
+    collection_searches: CollectionSearches,
 }
 
 impl State {
@@ -365,10 +405,29 @@ impl State {
         Self {
             default_params,
             context,
+            debounce_delay: None,
             fetch: Default::default(),
+            restored_offset: None,
+            collection_searches: Default::default(),
         }
     }
 
+    /// Delay the initial fetch after the search context has changed.
+    ///
+    /// Rapid, successive changes, e.g. while typing, then only result in
+    /// a single search for the latest context. Fetching more results for
+    /// an unchanged context is not delayed.
+    #[must_use]
+    pub const fn with_debounce_delay(mut self, debounce_delay: Option<Duration>) -> Self {
+        self.debounce_delay = debounce_delay;
+        self
+    }
+
+    #[must_use]
+    pub const fn debounce_delay(&self) -> Option<Duration> {
+        self.debounce_delay
+    }
+
     #[must_use]
     pub const fn default_params(&self) -> &Params {
         &self.default_params
@@ -425,12 +484,36 @@ impl State {
         self.fetch.fetched_entities().map(<[_]>::len)
     }
 
+    /// The current search for persisting it in the settings
+    ///
+    /// Only includes the query and the number of fetched results.
+    /// A restored offset is preserved until the results have been
+    /// fetched again.
+    #[must_use]
+    pub fn last_search(&self) -> settings::TrackSearch {
+        let Params {
+            filter, ordering, ..
+        } = &self.context.params;
+        let fetched_len = self.fetched_entities_len().unwrap_or_default();
+        let offset = self.restored_offset.map_or(fetched_len, |restored_offset| {
+            fetched_len.max(restored_offset.get())
+        });
+        settings::TrackSearch {
+            filter: filter.clone(),
+            ordering: ordering.clone(),
+            offset,
+        }
+    }
+
     #[must_use]
     fn clone_memo(&self) -> Memo {
         let Self {
             default_params,
             context,
+            debounce_delay: _,
             fetch,
+            restored_offset: _,
+            collection_searches: _,
         } = self;
         Memo {
             default_params: default_params.clone(),
@@ -445,7 +528,10 @@ impl State {
         let Self {
             default_params,
             context,
+            debounce_delay: _,
             fetch,
+            restored_offset: _,
+            collection_searches: _,
         } = self;
         let Memo {
             default_params: memo_default_params,
@@ -521,9 +607,13 @@ impl State {
         let Self {
             default_params: _,
             context,
+            debounce_delay: _,
             fetch,
+            restored_offset,
+            collection_searches: _,
         } = self;
         let reset_context = Default::default();
+        *restored_offset = None;
         let reset_fetch_effect = fetch.reset();
         if *context == reset_context && matches!(reset_fetch_effect, ActionEffect::Unchanged) {
             // No effect.
@@ -540,6 +630,10 @@ impl State {
     /// Update the collection UID
     ///
     /// Consumed the argument when returning `true`.
+    ///
+    /// The last search of the previous collection is remembered and
+    /// restored when switching back to it. Switching to a collection
+    /// without a remembered search keeps the current search parameters.
     fn update_collection_uid(
         &mut self,
         collection_uid: &mut Option<CollectionUid>,
@@ -549,12 +643,27 @@ impl State {
             log::debug!("Collection UID unchanged: {collection_uid:?}");
             return ActionEffect::Unchanged;
         }
+        let restored_search = collection_uid
+            .as_ref()
+            .and_then(|uid| self.collection_searches.take(uid));
+        if let Some(prev_collection_uid) = self.context.collection_uid.take() {
+            log::debug!("Remembering last search of collection {prev_collection_uid}");
+            let last_search = self.last_search();
+            self.collection_searches
+                .remember(prev_collection_uid, last_search);
+            // The restored offset only applies to the previous collection.
+            self.restored_offset = None;
+        }
         self.context.collection_uid = collection_uid.take();
         log::debug!(
             "Collection UID updated: {uid:?}",
             uid = self.context.collection_uid
         );
-        ActionEffect::Changed + self.fetch.reset()
+        let effect = ActionEffect::Changed + self.fetch.reset();
+        let Some(restored_search) = restored_search else {
+            return effect;
+        };
+        effect + self.restore_last_search(restored_search)
     }
 
     /// Update the search parameters
@@ -568,9 +677,35 @@ impl State {
         }
         self.context.params = std::mem::take(params);
         log::debug!("Params updated: {params:?}", params = self.context.params);
+        self.restored_offset = None;
         ActionEffect::Changed + self.fetch.reset()
     }
 
+    /// Restore the last search
+    ///
+    /// Replaces the filter and ordering of the search parameters. The
+    /// next initial fetch is extended to include all results that have
+    /// been fetched before.
+    fn restore_last_search(&mut self, last_search: settings::TrackSearch) -> ActionEffect {
+        let settings::TrackSearch {
+            filter,
+            ordering,
+            offset,
+        } = last_search;
+        let mut params = Params {
+            filter,
+            ordering,
+            ..self.default_params.clone()
+        };
+        let effect = self.update_params(&mut params);
+        self.restored_offset = NonZeroUsize::new(offset);
+        log::debug!(
+            "Last search restored with offset {offset}: {params:?}",
+            params = self.context.params
+        );
+        effect
+    }
+
     fn continue_after_fetching_more_task_joined(
         &mut self,
         joined: JoinedTask<FetchMoreResult>,
@@ -579,12 +714,15 @@ impl State {
         let Self {
             default_params,
             context,
+            debounce_delay: _,
             fetch:
                 FetchState::Pending {
                     fetched_entities_before,
                     since: pending_since,
                     task,
                 },
+            restored_offset: _,
+            collection_searches: _,
         } = self
         else {
             return ActionEffect::Unchanged;
@@ -646,6 +784,29 @@ impl State {
         env: &Arc<Environment>,
         fetch_limit: Option<NonZeroUsize>,
     ) -> ActionEffect {
+        let env = Arc::clone(env);
+        self.spawn_fetching_more_task_with(
+            this,
+            rt,
+            move |collection_uid, params, pagination| async move {
+                search(env.db_gatekeeper(), collection_uid, params, pagination)
+                    .await
+                    .map(|page| page.items)
+            },
+            fetch_limit,
+        )
+    }
+
+    fn spawn_fetching_more_task_with<T>(
+        &mut self,
+        this: &SharedState,
+        rt: &tokio::runtime::Handle,
+        search: impl FnOnce(CollectionUid, Params, Pagination) -> T,
+        fetch_limit: Option<NonZeroUsize>,
+    ) -> ActionEffect
+    where
+        T: Future<Output = FetchMoreResult> + Send + 'static,
+    {
         let Some(collection_uid) = &self.context.collection_uid else {
             debug_assert!(self.can_fetch_more() != Some(true));
             return ActionEffect::Unchanged;
@@ -672,6 +833,15 @@ impl State {
         debug_assert!(self.can_fetch_more() == Some(true));
         let pending_since = Instant::now();
 
+        // The initial fetch after restoring the last search includes
+        // all results that have been fetched before.
+        let restored_offset = fetched_entities_before
+            .is_none()
+            .then(|| self.restored_offset.take())
+            .flatten();
+        let fetch_limit =
+            fetch_limit.map(|limit| restored_offset.map_or(limit, |offset| limit.max(offset)));
+
         let continuation = {
             let default_params = self.default_params.clone();
             let context = self.context.clone();
@@ -688,8 +858,11 @@ impl State {
             }
         };
 
+        // Only the initial fetch for a new context is debounced.
+        let debounce_delay = self
+            .debounce_delay
+            .filter(|_| fetched_entities_before.is_none());
         let worker_task = rt.spawn({
-            let env = Arc::clone(env);
             let collection_uid = collection_uid.clone();
             let params = continuation.context.params.clone();
             let offset = continuation
@@ -698,7 +871,14 @@ impl State {
                 .map(|memo| memo.offset.try_into().expect("convertible"));
             let limit = fetch_limit.map(|limit| limit.get().try_into().expect("convertible"));
             let pagination = Pagination { limit, offset };
-            async move { search(env.db_gatekeeper(), collection_uid, params, pagination).await }
+            let search = search(collection_uid, params, pagination);
+            async move {
+                if let Some(debounce_delay) = debounce_delay {
+                    // The task is aborted if superseded while waiting.
+                    tokio::time::sleep(debounce_delay).await;
+                }
+                search.await
+            }
         });
         let abort_worker_task = worker_task.abort_handle();
         let _supervisor_task = rt.spawn({
@@ -775,6 +955,10 @@ impl SharedState {
         modify_shared_state_action_effect(&self.0, |state| state.update_params(params))
     }
 
+    pub fn restore_last_search(&self, last_search: settings::TrackSearch) -> ActionEffect {
+        modify_shared_state_action_effect(&self.0, |state| state.restore_last_search(last_search))
+    }
+
     pub fn spawn_fetching_more_task(
         &self,
         rt: &tokio::runtime::Handle,
@@ -786,6 +970,21 @@ impl SharedState {
         })
     }
 
+    #[cfg(test)]
+    fn spawn_fetching_more_task_with<T>(
+        &self,
+        rt: &tokio::runtime::Handle,
+        search: impl FnOnce(CollectionUid, Params, Pagination) -> T,
+        fetch_limit: Option<NonZeroUsize>,
+    ) -> ActionEffect
+    where
+        T: Future<Output = FetchMoreResult> + Send + 'static,
+    {
+        modify_shared_state_action_effect(&self.0, |state| {
+            state.spawn_fetching_more_task_with(self, rt, search, fetch_limit)
+        })
+    }
+
     fn continue_after_fetching_more_task_joined(
         &self,
         joined: JoinedTask<FetchMoreResult>,
@@ -820,16 +1019,4 @@ fn hash_entity_header_at_offset(seed: u64, offset: usize, entity_header: &Entity
 }
 
 #[cfg(test)]
-mod tests {
-    use highway::Key;
-
-    use crate::track::repo_search::{hash_key_for_offset, INITIAL_OFFSET_HASH_SEED};
-
-    #[test]
-    fn default_hash_key_equals_offset_zero() {
-        assert_eq!(
-            Key::default().0,
-            hash_key_for_offset(INITIAL_OFFSET_HASH_SEED, 0).0
-        );
-    }
-}
+mod tests;
@@ -10,7 +10,7 @@ use std::{
 use discro::{tasklet::OnChanged, Subscriber};
 use unnest::{some_or_break, some_or_return_with};
 
-use crate::{collection, Environment};
+use crate::{collection, settings, Environment};
 
 use super::{SharedState, State};
 
@@ -109,3 +109,35 @@ pub fn on_collection_state_changed(
         }
     }
 }
+
+/// Keep the last search in the settings up to date.
+///
+/// Only updated while no fetch operation is pending.
+pub fn on_state_changed_update_last_search(
+    this: &SharedState,
+    settings_state: Weak<settings::SharedState>,
+) -> impl Future<Output = ()> + Send + 'static + use<> {
+    let mut subscriber = this.subscribe_changed();
+    async move {
+        log::debug!("Starting on_state_changed_update_last_search");
+        loop {
+            log::debug!("Suspending on_state_changed_update_last_search");
+            if subscriber.changed().await.is_err() {
+                // No publisher(s).
+                break;
+            }
+            log::debug!("Resuming on_state_changed_update_last_search");
+
+            let settings_state = some_or_break!(settings_state.upgrade());
+
+            let last_search = {
+                let state = subscriber.read_ack();
+                if state.is_pending() {
+                    continue;
+                }
+                state.last_search()
+            };
+            let _ = settings_state.update_last_track_search(Some(last_search));
+        }
+    }
+}
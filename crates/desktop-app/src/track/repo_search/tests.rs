@@ -0,0 +1,265 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Mutex,
+};
+
+use highway::Key;
+use tokio::sync::Notify;
+
+use aoide_core::EntityUid;
+use aoide_core_api::{
+    track::search::{Filter, PhraseFieldFilter, SortField, SortOrder},
+    SortDirection,
+};
+
+use super::*;
+
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(50);
+
+const WAIT_FOR_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn new_shared_state(debounce_delay: Option<Duration>) -> SharedState {
+    let state = State::new(Params::default()).with_debounce_delay(debounce_delay);
+    let shared_state = SharedState::new(state);
+    let mut collection_uid = Some(CollectionUid::from_untyped(EntityUid::new()));
+    let _ = shared_state.update_collection_uid(&mut collection_uid);
+    shared_state
+}
+
+fn phrase_params(phrase: &str) -> Params {
+    Params {
+        filter: Some(Filter::Phrase(PhraseFieldFilter {
+            fields: vec![],
+            terms: vec![phrase.to_owned()],
+        })),
+        ..Default::default()
+    }
+}
+
+async fn wait_for_ready(shared_state: &SharedState) {
+    tokio::time::timeout(WAIT_FOR_READY_TIMEOUT, async {
+        while shared_state.read().is_pending() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn default_hash_key_equals_offset_zero() {
+    assert_eq!(
+        Key::default().0,
+        hash_key_for_offset(INITIAL_OFFSET_HASH_SEED, 0).0
+    );
+}
+
+#[tokio::test]
+async fn rapid_successive_queries_are_debounced() {
+    let rt = tokio::runtime::Handle::current();
+    let shared_state = new_shared_state(Some(DEBOUNCE_DELAY));
+    let search_count = Arc::new(AtomicUsize::new(0));
+    for phrase in ["a", "ab", "abc"] {
+        let _ = shared_state.update_params(&mut phrase_params(phrase));
+        let search_count = Arc::clone(&search_count);
+        let _ = shared_state.spawn_fetching_more_task_with(
+            &rt,
+            move |_, _, _| async move {
+                search_count.fetch_add(1, Ordering::Relaxed);
+                Ok(vec![])
+            },
+            None,
+        );
+        assert!(shared_state.read().is_pending());
+    }
+
+    wait_for_ready(&shared_state).await;
+
+    assert_eq!(1, search_count.load(Ordering::Relaxed));
+    let state = shared_state.read();
+    assert_eq!(phrase_params("abc"), state.context().params);
+    assert_eq!(Some(0), state.fetched_entities_len());
+    assert!(state.last_fetch_error().is_none());
+}
+
+#[tokio::test]
+async fn discard_results_of_superseded_query() {
+    let rt = tokio::runtime::Handle::current();
+    let shared_state = new_shared_state(None);
+
+    let _ = shared_state.update_params(&mut phrase_params("superseded"));
+    let superseded_search_released = Arc::new(Notify::new());
+    let superseded_search_finished = Arc::new(AtomicBool::new(false));
+    let _ = shared_state.spawn_fetching_more_task_with(
+        &rt,
+        {
+            let superseded_search_released = Arc::clone(&superseded_search_released);
+            let superseded_search_finished = Arc::clone(&superseded_search_finished);
+            move |_, _, _| async move {
+                superseded_search_released.notified().await;
+                superseded_search_finished.store(true, Ordering::Relaxed);
+                Ok(vec![])
+            }
+        },
+        None,
+    );
+    assert!(shared_state.read().is_pending());
+
+    // Issuing a newer query cancels the pending search.
+    let _ = shared_state.update_params(&mut phrase_params("latest"));
+    assert!(!shared_state.read().is_pending());
+    let _ = shared_state.spawn_fetching_more_task_with(&rt, |_, _, _| async { Ok(vec![]) }, None);
+
+    wait_for_ready(&shared_state).await;
+    superseded_search_released.notify_one();
+    tokio::task::yield_now().await;
+
+    assert!(!superseded_search_finished.load(Ordering::Relaxed));
+    let state = shared_state.read();
+    assert_eq!(phrase_params("latest"), state.context().params);
+    assert_eq!(Some(0), state.fetched_entities_len());
+    assert!(state.last_fetch_error().is_none());
+}
+
+#[tokio::test]
+async fn restoring_last_search_refetches_all_previously_fetched_results() {
+    let rt = tokio::runtime::Handle::current();
+    let default_params = Params {
+        ordering: vec![SortOrder {
+            field: SortField::UpdatedAt,
+            direction: SortDirection::Descending,
+        }],
+        ..Default::default()
+    };
+    let shared_state = SharedState::new(State::new(default_params));
+
+    let last_search = settings::TrackSearch {
+        filter: phrase_params("restored").filter,
+        ordering: vec![],
+        offset: 300,
+    };
+    let _ = shared_state.restore_last_search(last_search.clone());
+    assert_eq!(last_search, shared_state.read().last_search());
+
+    // The search is issued after the collection has become available.
+    let mut collection_uid = Some(CollectionUid::from_untyped(EntityUid::new()));
+    let _ = shared_state.update_collection_uid(&mut collection_uid);
+    assert!(shared_state.read().should_prefetch());
+    let issued_search = Arc::new(Mutex::new(None));
+    let _ = shared_state.spawn_fetching_more_task_with(
+        &rt,
+        {
+            let issued_search = Arc::clone(&issued_search);
+            move |_, params, pagination| {
+                *issued_search.lock().unwrap() = Some((params, pagination));
+                async { Ok(vec![]) }
+            }
+        },
+        NonZeroUsize::new(100),
+    );
+    wait_for_ready(&shared_state).await;
+
+    let (params, pagination) = issued_search.lock().unwrap().take().unwrap();
+    assert_eq!(
+        Params {
+            filter: last_search.filter,
+            ..Default::default()
+        },
+        params
+    );
+    assert_eq!(
+        Pagination {
+            limit: Some(300),
+            offset: None,
+        },
+        pagination
+    );
+    assert_eq!(0, shared_state.read().last_search().offset);
+}
+
+#[test]
+fn changing_params_discards_restored_offset() {
+    let shared_state = new_shared_state(None);
+    let _ = shared_state.restore_last_search(settings::TrackSearch {
+        filter: phrase_params("restored").filter,
+        ordering: vec![],
+        offset: 300,
+    });
+    let _ = shared_state.update_params(&mut phrase_params("changed"));
+    let last_search = shared_state.read().last_search();
+    assert_eq!(phrase_params("changed").filter, last_search.filter);
+    assert_eq!(0, last_search.offset);
+}
+
+fn new_collection_uid() -> CollectionUid {
+    CollectionUid::from_untyped(EntityUid::new())
+}
+
+fn switch_collection(shared_state: &SharedState, collection_uid: Option<&CollectionUid>) {
+    let _ = shared_state.update_collection_uid(&mut collection_uid.cloned());
+    assert_eq!(
+        collection_uid,
+        shared_state.read().context().collection_uid.as_ref()
+    );
+}
+
+#[test]
+fn switching_collections_restores_their_last_search() {
+    let shared_state = SharedState::new(State::new(Params::default()));
+    let collection_a = new_collection_uid();
+    let collection_b = new_collection_uid();
+
+    switch_collection(&shared_state, Some(&collection_a));
+    let last_search_a = settings::TrackSearch {
+        filter: phrase_params("a").filter,
+        ordering: vec![],
+        offset: 300,
+    };
+    let _ = shared_state.restore_last_search(last_search_a.clone());
+
+    switch_collection(&shared_state, Some(&collection_b));
+    let _ = shared_state.update_params(&mut phrase_params("b"));
+    let last_search_b = shared_state.read().last_search();
+    assert_eq!(phrase_params("b").filter, last_search_b.filter);
+    assert_eq!(0, last_search_b.offset);
+
+    switch_collection(&shared_state, Some(&collection_a));
+    assert_eq!(last_search_a, shared_state.read().last_search());
+    assert!(shared_state.read().should_prefetch());
+
+    switch_collection(&shared_state, Some(&collection_b));
+    assert_eq!(last_search_b, shared_state.read().last_search());
+
+    // The collection becomes temporarily unavailable, e.g. while reloading it.
+    switch_collection(&shared_state, None);
+    switch_collection(&shared_state, Some(&collection_a));
+    assert_eq!(last_search_a, shared_state.read().last_search());
+    switch_collection(&shared_state, Some(&collection_b));
+    assert_eq!(last_search_b, shared_state.read().last_search());
+}
+
+#[test]
+fn forget_last_search_of_least_recently_used_collection() {
+    let shared_state = SharedState::new(State::new(Params::default()));
+    let collection_uids = (0..=MAX_REMEMBERED_COLLECTION_SEARCHES)
+        .map(|_| new_collection_uid())
+        .collect::<Vec<_>>();
+    for (i, collection_uid) in collection_uids.iter().enumerate() {
+        switch_collection(&shared_state, Some(collection_uid));
+        let _ = shared_state.update_params(&mut phrase_params(&i.to_string()));
+    }
+    let last_params = phrase_params(&MAX_REMEMBERED_COLLECTION_SEARCHES.to_string());
+    assert_eq!(last_params, shared_state.read().context().params);
+
+    // Switching to another collection remembers the last search of the
+    // previous collection and forgets the least recently used one.
+    switch_collection(&shared_state, Some(&new_collection_uid()));
+    assert_eq!(last_params, shared_state.read().context().params);
+    switch_collection(&shared_state, Some(&collection_uids[1]));
+    assert_eq!(phrase_params("1"), shared_state.read().context().params);
+    switch_collection(&shared_state, Some(&collection_uids[0]));
+    assert_eq!(phrase_params("1"), shared_state.read().context().params);
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use url::Url;
+
+use aoide_backend_embedded::{batch::import_directory, storage::DatabaseConfig};
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPath, ContentPathConfig, VirtualFilePathConfig},
+    tag::{Label, PlainTag},
+    track::tag::FACET_ID_GENRE,
+    util::url::BaseUrl,
+    Collection,
+};
+use aoide_core_api::media::SyncMode;
+use aoide_storage_sqlite::connection::{
+    pool::{gatekeeper::Config as GatekeeperConfig, Config as PoolConfig},
+    Config as ConnectionConfig, Storage,
+};
+
+use super::*;
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+fn commission_environment(storage_dir: &Path) -> Environment {
+    Environment::commission(&DatabaseConfig {
+        connection: ConnectionConfig {
+            storage: Storage::File {
+                path: storage_dir.join("aoide.sqlite"),
+            },
+            pool: PoolConfig {
+                max_size: NonZeroU32::MIN,
+                gatekeeper: GatekeeperConfig {
+                    acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+                    fairness_policy: Default::default(),
+                },
+            },
+            pragma: Default::default(),
+        },
+        migrate_schema: None,
+        read_only: false,
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn apply_tag_edit_to_multiple_tracks() {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let music_dir = tempfile::tempdir().unwrap();
+    for file_name in ["a.mp3", "b.mp3", "c.mp3"] {
+        std::fs::copy(FIXTURE_FILE_PATH, music_dir.path().join(file_name)).unwrap();
+    }
+    let env = commission_environment(storage_dir.path());
+    let root_url = BaseUrl::new(Url::from_directory_path(music_dir.path()).unwrap());
+    let collection_uid = aoide_backend_embedded::collection::create(
+        env.db_gatekeeper(),
+        Collection {
+            title: "Test Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url,
+                    excluded_paths: vec![],
+                }),
+            },
+        },
+    )
+    .await
+    .unwrap()
+    .raw
+    .hdr
+    .uid;
+
+    let mut track_uids = Vec::new();
+    import_directory::import_directory(
+        env.db_gatekeeper(),
+        collection_uid.clone(),
+        import_directory::Params {
+            dir_path: ContentPath::default(),
+            sync_mode: SyncMode::Always,
+            import_track_config: Default::default(),
+            batch_size: NonZeroUsize::MIN,
+            content_digest_workers: None,
+        },
+        std::convert::identity,
+        |entities: &[aoide_core::TrackEntity]| {
+            track_uids.extend(entities.iter().map(|entity| entity.hdr.uid.clone()));
+            Ok(())
+        },
+        |_| {},
+        Arc::new(AtomicBool::new(false)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(3, track_uids.len());
+
+    let unknown_uid = EntityUid::from_untyped(aoide_core::EntityUid::new());
+    let mut requested_uids = track_uids.clone();
+    requested_uids.insert(1, unknown_uid.clone());
+    let genre_tag = PlainTag {
+        label: Some(Label::from_unchecked("Techno")),
+        score: Default::default(),
+    };
+    let edit = TagEdit::Add {
+        facet_id: Some(FACET_ID_GENRE.clone()),
+        tags: vec![genre_tag.clone()],
+    };
+
+    let summary = apply_tag_edit(
+        &env,
+        collection_uid.clone(),
+        requested_uids.clone(),
+        edit.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        track_uids,
+        summary
+            .updated
+            .iter()
+            .map(|entity| entity.hdr.uid.clone())
+            .collect::<Vec<_>>()
+    );
+    for entity in &summary.updated {
+        let genre_tags = entity
+            .body
+            .track
+            .tags
+            .facets
+            .iter()
+            .find(|faceted_tags| faceted_tags.facet_id == *FACET_ID_GENRE)
+            .map(|faceted_tags| faceted_tags.tags.as_slice());
+        assert_eq!(Some([genre_tag.clone()].as_slice()), genre_tags);
+    }
+    assert!(summary.unchanged.is_empty());
+    assert_eq!(vec![unknown_uid.clone()], summary.failed);
+
+    // Adding the same tag again doesn't modify any track.
+    let summary = apply_tag_edit(&env, collection_uid, requested_uids, edit)
+        .await
+        .unwrap();
+    assert!(summary.updated.is_empty());
+    assert_eq!(track_uids, summary.unchanged);
+    assert_eq!(vec![unknown_uid], summary.failed);
+}
@@ -10,7 +10,7 @@ use lofty::id3::v2::Id3v2Tag;
 use crate::{
     io::{
         export::{ExportTrackConfig, ExportTrackFlags},
-        import::{ImportTrackConfig, ImportTrackFlags, ImportedTempoBpm, Importer},
+        import::{ImportTrackConfig, ImportTrackFlags, ImportedTempoBpm, Importer, IssueKind},
     },
     util::{artwork::EditEmbeddedArtworkImage, format_validated_tempo_bpm},
 };
@@ -105,7 +105,10 @@ pub(super) fn import_serato_markers(
                 }
             }
         } else {
-            importer.add_issue(format!("Unexpected Serato Markers frame: {frame:?}"));
+            importer.add_issue_of_kind(
+                IssueKind::UnsupportedTag,
+                format!("Unexpected Serato Markers frame: {frame:?}"),
+            );
         }
     }
     if let Some(frame) = tag.get(&FrameId::Valid(
@@ -121,7 +124,10 @@ pub(super) fn import_serato_markers(
                 }
             }
         } else {
-            importer.add_issue(format!("Unexpected Serato Markers2 frame: {frame:?}"));
+            importer.add_issue_of_kind(
+                IssueKind::UnsupportedTag,
+                format!("Unexpected Serato Markers2 frame: {frame:?}"),
+            );
         }
     }
 
@@ -3,6 +3,7 @@
 
 use std::{borrow::Cow, ops::Not as _};
 
+use image::{guess_format, ImageFormat};
 use lofty::{
     config::ParseOptions,
     file::{AudioFile as _, TaggedFile, TaggedFileExt as _},
@@ -10,12 +11,14 @@ use lofty::{
     properties::FileProperties,
     tag::{Accessor as _, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagItem, TagType},
 };
+use mime::Mime;
 use nonicle::{Canonical, CanonicalizeInto as _};
 use semval::prelude::*;
 
 use aoide_core::{
     audio::{
         channel::ChannelCount,
+        sample::BitDepth,
         signal::{BitrateBps, SampleRateHz},
         BitrateBpsValue, ChannelFlags, Channels, DurationMs,
     },
@@ -45,17 +48,22 @@ use aoide_core::{
 use crate::{
     io::{
         export::{ExportTrackConfig, ExportTrackFlags, FilteredActorNames},
-        import::{ImportTrackConfig, ImportTrackFlags, Importer, TrackScope},
+        import::{
+            FileTagType, ImportTrackConfig, ImportTrackFlags, Importer, IssueKind, TrackScope,
+            DEFAULT_TAG_TYPE_PRECEDENCE,
+        },
     },
     util::{
         artwork::{
-            try_ingest_embedded_artwork_image, EditEmbeddedArtworkImage,
-            EditOtherEmbeddedArtworkImages, RemoveEmbeddedArtworkImage,
+            media_type_from_image_format, try_ingest_embedded_artwork_image,
+            try_ingest_embedded_artwork_image_presence_only, ArtworkImageLimits,
+            EditEmbeddedArtworkImage, EditOtherEmbeddedArtworkImages, RemoveEmbeddedArtworkImage,
             ReplaceEmbeddedArtworkImage,
         },
         digest::MediaDigest,
-        format_valid_replay_gain, format_validated_tempo_bpm, ingest_title_from,
-        key_signature_as_str, push_next_actor,
+        format_valid_replay_gain, format_validated_tempo_bpm,
+        genre::translate_id3v1_genre_codes,
+        ingest_title_from, key_signature_as_str, parse_index, parse_index_number, push_next_actor,
         tag::TagMappingConfig,
         FormattedTempoBpm, TempoBpmFormat,
     },
@@ -77,6 +85,9 @@ pub(crate) mod opus;
 
 pub(crate) mod vorbis;
 
+#[cfg(test)]
+mod tests;
+
 const ENCODER_FIELD_SEPARATOR: &str = "|";
 
 /// All facets that could be stored as native file tags.
@@ -146,14 +157,27 @@ pub(crate) fn parse_options() -> ParseOptions {
     ParseOptions::new().read_properties(true)
 }
 
-fn import_audio_content_from_file_properties(properties: &FileProperties) -> AudioContentMetadata {
+/// Import the audio properties of a file.
+///
+/// Implausible values are discarded and reported as issues.
+fn import_audio_content_from_file_properties(
+    importer: &mut Importer,
+    properties: &FileProperties,
+) -> AudioContentMetadata {
     let bitrate = properties
         .audio_bitrate()
         .map(|kbps| BitrateBps::new(BitrateBpsValue::from(kbps) * 1000.0))
         .filter(IsValid::is_valid);
     let channel_count = properties
         .channels()
-        .map(|count| ChannelCount::new(count.into()));
+        .map(|count| ChannelCount::new(count.into()))
+        .filter(|channel_count| {
+            let is_valid = channel_count.is_valid();
+            if !is_valid {
+                importer.add_issue(format!("Invalid number of channels: {channel_count}"));
+            }
+            is_valid
+        });
     let channel_flags = properties
         .channel_mask()
         .map(|mask| ChannelFlags::from_bits_truncate(mask.bits()));
@@ -163,7 +187,20 @@ fn import_audio_content_from_file_properties(properties: &FileProperties) -> Aud
     let sample_rate = properties
         .sample_rate()
         .map(|hz| SampleRateHz::new(hz.into()))
-        .filter(IsValid::is_valid);
+        .filter(|sample_rate| {
+            let is_valid = sample_rate.is_valid();
+            if !is_valid {
+                importer.add_issue(format!("Invalid sample rate: {sample_rate}"));
+            }
+            is_valid
+        });
+    // The bit depth is not stored, but an invalid value indicates
+    // that the other properties might be unreliable.
+    if let Some(bit_depth) = properties.bit_depth().map(BitDepth::new) {
+        if !bit_depth.is_valid() {
+            importer.add_issue(format!("Invalid bit depth: {bit_depth}"));
+        }
+    }
     AudioContentMetadata {
         bitrate,
         channels,
@@ -171,6 +208,7 @@ fn import_audio_content_from_file_properties(properties: &FileProperties) -> Aud
         sample_rate,
         encoder: None,
         loudness: None,
+        waveform_overview: None,
     }
 }
 
@@ -182,6 +220,61 @@ pub(crate) fn take_primary_or_first_tag(tagged_file: &mut TaggedFile) -> Option<
     tagged_file.remove(first_tag_type)
 }
 
+/// Take all tags and merge them into a single tag.
+///
+/// The values of each item key are taken from the first tag in order of
+/// precedence that contains this key. The merged tag has the primary tag
+/// type of the file. A single tag is returned unmodified.
+///
+/// Without an explicit precedence the primary tag comes first, followed
+/// by [`DEFAULT_TAG_TYPE_PRECEDENCE`].
+pub(crate) fn take_merged_tag(
+    tagged_file: &mut TaggedFile,
+    tag_type_precedence: Option<&[FileTagType]>,
+) -> Option<Tag> {
+    let mut tags = Vec::new();
+    if tag_type_precedence.is_none() {
+        tags.extend(tagged_file.remove(tagged_file.primary_tag_type()));
+    }
+    tags.extend(
+        tag_type_precedence
+            .unwrap_or(DEFAULT_TAG_TYPE_PRECEDENCE)
+            .iter()
+            .filter_map(|&tag_type| tagged_file.remove(tag_type.into())),
+    );
+    while let Some(tag) = take_primary_or_first_tag(tagged_file) {
+        tags.push(tag);
+    }
+    if tags.len() <= 1 {
+        return tags.pop();
+    }
+    let mut merged_tag = Tag::new(tagged_file.primary_tag_type());
+    for tag in tags {
+        merge_missing_tag_items(&mut merged_tag, &tag);
+    }
+    Some(merged_tag)
+}
+
+fn merge_missing_tag_items(merged_tag: &mut Tag, tag: &Tag) {
+    let missing_item_keys = tag
+        .items()
+        .map(TagItem::key)
+        .filter(|item_key| merged_tag.get(item_key).is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+    for item in tag.items() {
+        if missing_item_keys.contains(item.key()) {
+            // Items that are not supported by the merged tag type are dropped.
+            merged_tag.push(item.clone());
+        }
+    }
+    if merged_tag.picture_count() == 0 {
+        for picture in tag.pictures() {
+            merged_tag.push_picture(picture.clone());
+        }
+    }
+}
+
 fn apic_type_from_picture_type(picture_type: PictureType) -> Option<ApicType> {
     let apic_type = match picture_type {
         PictureType::Artist => ApicType::Artist,
@@ -243,8 +336,8 @@ const fn picture_type_from_apic_type(apic_type: ApicType) -> PictureType {
     }
 }
 
-#[must_use]
-pub(crate) fn find_embedded_artwork_image(tag: &Tag) -> Option<(ApicType, &str, &[u8])> {
+/// Iterate over all pictures in order of precedence for the artwork.
+fn embedded_artwork_pictures(tag: &Tag) -> impl Iterator<Item = (ApicType, &Picture)> {
     tag.pictures()
         .iter()
         .filter_map(|p| {
@@ -282,31 +375,133 @@ pub(crate) fn find_embedded_artwork_image(tag: &Tag) -> Option<(ApicType, &str,
                 p,
             )
         }))
+}
+
+#[must_use]
+pub(crate) fn find_embedded_artwork_image(tag: &Tag) -> Option<(ApicType, &str, &[u8])> {
+    embedded_artwork_pictures(tag)
         .find_map(|(apic_type, p)| Some((apic_type, p.mime_type()?.as_str(), p.data())))
 }
 
+/// Find an embedded artwork image and sniff its format from the image data.
+///
+/// The declared MIME type of FLAC `METADATA_BLOCK_PICTURE` entries is
+/// unreliable, i.e. it might be missing or wrong. It is replaced by the
+/// MIME type of the sniffed image format. Pictures with image data that
+/// is not recognized are skipped.
+fn find_embedded_artwork_image_sniffed<'a>(
+    importer: &mut Importer,
+    tag: &'a Tag,
+) -> Option<(ApicType, ImageFormat, Mime, &'a [u8])> {
+    // Pictures might be visited repeatedly
+    let mut skipped_pictures = Vec::<&Picture>::new();
+    embedded_artwork_pictures(tag).find_map(|(apic_type, p)| {
+        if skipped_pictures
+            .iter()
+            .any(|skipped| std::ptr::eq(*skipped, p))
+        {
+            return None;
+        }
+        let declared_mime_type = p.mime_type().map(MimeType::as_str);
+        let Some((image_format, media_type)) =
+            guess_format(p.data()).ok().and_then(|image_format| {
+                media_type_from_image_format(image_format)
+                    .ok()
+                    .map(|media_type| (image_format, media_type))
+            })
+        else {
+            let issue = format!(
+                "Skipping embedded {apic_type:?} artwork image with unrecognized image data \
+                 (declared MIME type: {declared_mime_type:?})"
+            );
+            log::warn!("{issue}");
+            importer.add_issue_of_kind(IssueKind::MissingArtwork, issue);
+            skipped_pictures.push(p);
+            return None;
+        };
+        if !declared_mime_type
+            .is_some_and(|mime_type| mime_type.eq_ignore_ascii_case(media_type.essence_str()))
+        {
+            log::info!(
+                "Correcting MIME type of embedded {apic_type:?} artwork image: \
+                 {declared_mime_type:?} -> {media_type}"
+            );
+        }
+        Some((apic_type, image_format, media_type, p.data()))
+    })
+}
+
+/// Import an index from either a combined "number/total" item
+/// or from separate items for the number and the total.
+///
+/// A separate total takes precedence over a combined total.
+fn import_index(tag: &Tag, number_key: &ItemKey, total_key: &ItemKey) -> Index {
+    let Index { number, total } = tag
+        .get_items(number_key)
+        .find_map(|item| match item.value() {
+            ItemValue::Text(text) => Some(parse_index(text)),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let total = tag
+        .get_items(total_key)
+        .find_map(|item| match item.value() {
+            ItemValue::Text(text) => parse_index_number(text),
+            _ => None,
+        })
+        .or(total);
+    Index { number, total }
+}
+
 pub(crate) fn import_embedded_artwork(
     importer: &mut Importer,
     tag: &Tag,
     mut media_digest: MediaDigest,
+    presence_only: bool,
+    limits: &ArtworkImageLimits,
 ) -> Artwork {
-    let artwork = if let Some((apic_type, mime_type, image_data)) = find_embedded_artwork_image(tag)
-    {
-        let (artwork, _, issues) = try_ingest_embedded_artwork_image(
-            apic_type,
-            image_data,
-            None,
-            Some(mime_type),
-            &mut media_digest,
-        );
+    let embedded_artwork_image = if tag.tag_type() == TagType::VorbisComments {
+        find_embedded_artwork_image_sniffed(importer, tag).map(
+            |(apic_type, image_format, media_type, image_data)| {
+                (
+                    apic_type,
+                    Some(image_format),
+                    Cow::Owned(media_type.to_string()),
+                    image_data,
+                )
+            },
+        )
+    } else {
+        find_embedded_artwork_image(tag).map(|(apic_type, mime_type, image_data)| {
+            (apic_type, None, Cow::Borrowed(mime_type), image_data)
+        })
+    };
+    if let Some((apic_type, image_format_hint, mime_type, image_data)) = embedded_artwork_image {
+        let (artwork, issues) = if presence_only {
+            try_ingest_embedded_artwork_image_presence_only(
+                apic_type,
+                image_data,
+                image_format_hint,
+                Some(&*mime_type),
+                &mut media_digest,
+            )
+        } else {
+            try_ingest_embedded_artwork_image(
+                apic_type,
+                image_data,
+                image_format_hint,
+                Some(&*mime_type),
+                limits,
+                &mut media_digest,
+            )
+        };
         for issue in issues {
-            importer.add_issue(issue);
+            importer.add_issue_of_kind(IssueKind::MissingArtwork, issue);
         }
         artwork
     } else {
         Artwork::Missing
-    };
-    artwork
+    }
 }
 
 pub(crate) fn import_tagged_file_into_track(
@@ -315,7 +510,7 @@ pub(crate) fn import_tagged_file_into_track(
     mut tagged_file: TaggedFile,
     track: &mut Track,
 ) {
-    let tag = take_primary_or_first_tag(&mut tagged_file);
+    let tag = take_merged_tag(&mut tagged_file, config.tag_type_precedence.as_deref());
     if let Some(tag) = tag {
         log::debug!(
             "Importing track metadata from {tag_type:?} tag in {file_type:?} file \
@@ -421,6 +616,46 @@ fn try_parse_boolean_flag(input: &str) -> Option<bool> {
         })
 }
 
+/// Common keys of Vorbis comments for grouping and classical music
+///
+/// These keys are written by various taggers, but are not necessarily
+/// mapped onto the corresponding [`ItemKey`] variants when reading the
+/// file. Their values are only used if the tag contains no items for
+/// the corresponding [`ItemKey`]. Keys are matched case-insensitive.
+const VORBIS_COMMENTS_ITEM_KEY_ALIASES: [(ItemKey, &[&str]); 5] = [
+    (ItemKey::ContentGroup, &["GROUPING", "CONTENTGROUP"]),
+    (ItemKey::Work, &["WORK"]),
+    (ItemKey::Movement, &["MOVEMENTNAME"]),
+    (ItemKey::MovementNumber, &["MOVEMENT", "MOVEMENTNUMBER"]),
+    (ItemKey::MovementTotal, &["MOVEMENTTOTAL", "MOVEMENTCOUNT"]),
+];
+
+fn remap_vorbis_comments_item_keys(tag: &mut Tag) {
+    debug_assert_eq!(TagType::VorbisComments, tag.tag_type());
+    for (item_key, aliases) in &VORBIS_COMMENTS_ITEM_KEY_ALIASES {
+        for alias in *aliases {
+            if tag.get_items(item_key).next().is_some() {
+                break;
+            }
+            let alias_keys = tag
+                .items()
+                .filter_map(|item| match item.key() {
+                    ItemKey::Unknown(key) if key.eq_ignore_ascii_case(alias) => {
+                        Some(item.key().clone())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for alias_key in alias_keys {
+                for item in tag.take(&alias_key).collect::<Vec<_>>() {
+                    log::debug!("Importing Vorbis comment {alias_key:?} as {item_key:?}");
+                    tag.push_unchecked(TagItem::new(item_key.clone(), item.into_value()));
+                }
+            }
+        }
+    }
+}
+
 fn tag_take_strings<'a>(tag: &'a mut Tag, key: &'a ItemKey) -> impl Iterator<Item = String> + 'a {
     // Retain all items with a non-empty description.
     tag.take_filter(key, |item| item.description().is_empty())
@@ -440,7 +675,7 @@ pub(crate) fn import_file_tag_into_track(
         .content
         .metadata_flags
         .update(ContentMetadataFlags::UNRELIABLE)
-        .then(|| import_audio_content_from_file_properties(file_properties));
+        .then(|| import_audio_content_from_file_properties(importer, file_properties));
     if let Some(mut audio_content) = audio_content {
         // Import the remaining audio content properties
         debug_assert!(audio_content.encoder.is_none());
@@ -473,6 +708,9 @@ pub(crate) fn import_file_tag_into_track(
         return;
     }
 
+    if tag.tag_type() == TagType::VorbisComments {
+        remap_vorbis_comments_item_keys(&mut tag);
+    }
     let compatibility = Compatibility::import(tag.tag_type(), config.flags);
 
     // Musical metrics: tempo (bpm)
@@ -724,6 +962,28 @@ pub(crate) fn import_file_tag_into_track(
     }
     *old_album_titles = new_album_titles;
 
+    if let Some(item) = tag.take(&ItemKey::FlagCompilation).next() {
+        if let Some(kind) =
+            item.value()
+                .text()
+                .and_then(try_parse_boolean_flag)
+                .map(|compilation| {
+                    if compilation {
+                        AlbumKind::Compilation
+                    } else {
+                        AlbumKind::NoCompilation
+                    }
+                })
+        {
+            album.kind = Some(kind);
+        } else {
+            importer.add_issue_of_kind(
+                IssueKind::UnsupportedTag,
+                format!("Unexpected compilation flag item: {item:?}"),
+            );
+        }
+    }
+
     // Album actors
     let mut album_actors = Vec::with_capacity(4);
     for name in tag_take_strings(&mut tag, &ItemKey::AlbumArtist) {
@@ -742,6 +1002,29 @@ pub(crate) fn import_file_tag_into_track(
             ActorRole::Artist,
         );
     }
+    if album_actors
+        .iter()
+        .any(|actor| actor.role == ActorRole::Artist && config.is_various_artists_name(&actor.name))
+        && album.kind.is_none()
+    {
+        album.kind = Some(AlbumKind::Compilation);
+    }
+    if album.kind == Some(AlbumKind::Compilation)
+        && !album_actors
+            .iter()
+            .any(|actor| actor.role == ActorRole::Artist && actor.kind != ActorKind::Sorting)
+    {
+        // Group all tracks of a compilation under a common album artist
+        // instead of splitting the album by the individual track artists.
+        if let Some(name) = config.various_artists_names.first() {
+            push_next_actor(
+                &mut album_actors,
+                name.as_str(),
+                Default::default(),
+                ActorRole::Artist,
+            );
+        }
+    }
     let new_album_actors = importer.finish_import_of_actors(TrackScope::Album, album_actors);
     let old_album_actors = &mut album.actors;
     if !old_album_actors.is_empty() && *old_album_actors != new_album_actors {
@@ -749,25 +1032,6 @@ pub(crate) fn import_file_tag_into_track(
     }
     *old_album_actors = new_album_actors;
 
-    if let Some(item) = tag.take(&ItemKey::FlagCompilation).next() {
-        if let Some(kind) =
-            item.value()
-                .text()
-                .and_then(try_parse_boolean_flag)
-                .map(|compilation| {
-                    if compilation {
-                        AlbumKind::Compilation
-                    } else {
-                        AlbumKind::NoCompilation
-                    }
-                })
-        {
-            album.kind = Some(kind);
-        } else {
-            importer.add_issue(format!("Unexpected compilation flag item: {item:?}"));
-        }
-    }
-
     let new_album = Canonical::tie(album);
     let old_album = &mut track.album;
     if *old_album != Default::default() && *old_album != new_album {
@@ -800,87 +1064,46 @@ pub(crate) fn import_file_tag_into_track(
 
     // Index pairs
     // Import both values consistently if any of them is available!
-    // TODO: Verify u32 -> u16 conversions
     let old_track_index = &mut track.indexes.track;
-    let track_number = tag
-        .track()
-        .map(TryInto::try_into)
-        .transpose()
-        .ok()
-        .flatten();
-    let track_total = tag
-        .track_total()
-        .map(TryInto::try_into)
-        .transpose()
-        .ok()
-        .flatten();
-    if track_number.is_some() || track_total.is_some() {
-        let new_track_index = Index {
-            number: track_number,
-            total: track_total,
-        };
-        if *old_track_index != Default::default() && *old_track_index != new_track_index {
-            log::debug!("Replacing track index: {old_track_index:?} -> {new_track_index:?}");
-        }
-        *old_track_index = new_track_index;
-    } else {
+    let new_track_index = import_index(&tag, &ItemKey::TrackNumber, &ItemKey::TrackTotal);
+    if new_track_index == Default::default() {
         if *old_track_index != Default::default() {
             log::debug!("Resetting track index: {old_track_index:?}");
         }
         *old_track_index = Default::default();
+    } else {
+        if *old_track_index != Default::default() && *old_track_index != new_track_index {
+            log::debug!("Replacing track index: {old_track_index:?} -> {new_track_index:?}");
+        }
+        *old_track_index = new_track_index;
     }
     let old_disc_index = &mut track.indexes.disc;
-    let disc_number = tag.disk().map(TryInto::try_into).transpose().ok().flatten();
-    let disc_total = tag
-        .disk_total()
-        .map(TryInto::try_into)
-        .transpose()
-        .ok()
-        .flatten();
-    if disc_number.is_some() || disc_total.is_some() {
-        let new_disc_index = Index {
-            number: disc_number,
-            total: disc_total,
-        };
-        if *old_disc_index != Default::default() && *old_disc_index != new_disc_index {
-            log::debug!("Replacing disc index: {old_disc_index:?} -> {new_disc_index:?}");
-        }
-        *old_disc_index = new_disc_index;
-    } else {
+    let new_disc_index = import_index(&tag, &ItemKey::DiscNumber, &ItemKey::DiscTotal);
+    if new_disc_index == Default::default() {
         if *old_disc_index != Default::default() {
             log::debug!("Resetting disc index: {old_disc_index:?}");
         }
         *old_disc_index = Default::default();
+    } else {
+        if *old_disc_index != Default::default() && *old_disc_index != new_disc_index {
+            log::debug!("Replacing disc index: {old_disc_index:?} -> {new_disc_index:?}");
+        }
+        *old_disc_index = new_disc_index;
     }
     let old_movement_index = &mut track.indexes.movement;
-    let movement_number =
-        tag.get_items(&ItemKey::MovementNumber)
-            .find_map(|item| match item.value() {
-                ItemValue::Text(number) => number.parse::<u16>().ok(),
-                _ => None,
-            });
-    let movement_total =
-        tag.get_items(&ItemKey::MovementNumber)
-            .find_map(|item| match item.value() {
-                ItemValue::Text(number) => number.parse::<u16>().ok(),
-                _ => None,
-            });
-    if movement_number.is_some() || movement_total.is_some() {
-        let new_movement_index = Index {
-            number: movement_number,
-            total: movement_total,
-        };
+    let new_movement_index = import_index(&tag, &ItemKey::MovementNumber, &ItemKey::MovementTotal);
+    if new_movement_index == Default::default() {
+        if *old_movement_index != Default::default() {
+            log::debug!("Resetting movement index: {old_movement_index:?}");
+        }
+        *old_movement_index = Default::default();
+    } else {
         if *old_movement_index != Default::default() && *old_movement_index != new_movement_index {
             log::debug!(
                 "Replacing movement index: {old_movement_index:?} -> {new_movement_index:?}"
             );
         }
         *old_movement_index = new_movement_index;
-    } else {
-        if *old_movement_index != Default::default() {
-            log::debug!("Resetting movement index: {old_movement_index:?}");
-        }
-        *old_movement_index = Default::default();
     }
 
     let old_recorded_at = &mut track.recorded_at;
@@ -974,12 +1197,26 @@ pub(crate) fn import_file_tag_into_track(
         let mut next_score_value = PlainTag::DEFAULT_SCORE.value();
         let mut plain_tags = Vec::with_capacity(8);
         for genre in tag_take_strings(&mut tag, &ItemKey::Genre) {
-            importer.import_plain_tags_from_joined_label_value(
-                tag_mapping_config,
-                &mut next_score_value,
-                &mut plain_tags,
-                genre,
-            );
+            if config
+                .flags
+                .contains(ImportTrackFlags::COMPATIBILITY_ID3V1_GENRE_CODES)
+            {
+                for genre in translate_id3v1_genre_codes(&genre) {
+                    importer.import_plain_tags_from_joined_label_value(
+                        tag_mapping_config,
+                        &mut next_score_value,
+                        &mut plain_tags,
+                        genre.into_owned(),
+                    );
+                }
+            } else {
+                importer.import_plain_tags_from_joined_label_value(
+                    tag_mapping_config,
+                    &mut next_score_value,
+                    &mut plain_tags,
+                    genre,
+                );
+            }
         }
         tags_map.update_faceted_plain_tags_by_label_ordering(FACET_ID_GENRE, plain_tags);
     }
@@ -1054,8 +1291,15 @@ pub(crate) fn import_file_tag_into_track(
         .flags
         .contains(ImportTrackFlags::METADATA_EMBEDDED_ARTWORK)
     {
-        let new_artwork =
-            import_embedded_artwork(importer, &tag, config.flags.new_artwork_digest());
+        let new_artwork = import_embedded_artwork(
+            importer,
+            &tag,
+            config.flags.new_artwork_digest(),
+            config
+                .flags
+                .contains(ImportTrackFlags::METADATA_EMBEDDED_ARTWORK_PRESENCE_ONLY),
+            &config.artwork_image_limits,
+        );
         if let Some(old_artwork) = &track.media_source.artwork {
             if *old_artwork != new_artwork {
                 log::debug!("Replacing artwork: {old_artwork:?} -> {new_artwork:?}");
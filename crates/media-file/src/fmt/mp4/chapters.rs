@@ -0,0 +1,384 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Chapter markers of MPEG-4 files
+//!
+//! Two different, coexisting formats are supported:
+//!   - QuickTime chapter tracks, i.e. timed text tracks that are
+//!     referenced by a `chap` atom in the `tref` atom of another track
+//!   - Nero chapters stored in the `chpl` atom of `moov/udta`
+//!
+//! QuickTime chapter tracks take precedence if both are present.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Time scale of the start times in Nero chapter lists (100 ns).
+const NERO_CHAPTER_TIMESCALE: u64 = 10_000_000;
+
+/// Upper bound for the size of a single text sample in bytes.
+///
+/// Chapter titles are short. Larger samples are truncated.
+const MAX_TEXT_SAMPLE_SIZE: u64 = 0x1_0000;
+
+const MILLIS_PER_SEC: f64 = 1_000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Chapter {
+    pub(crate) start_ms: f64,
+    pub(crate) title: String,
+}
+
+type AtomType = [u8; 4];
+
+/// Iterates over the atoms within the body of a parent atom.
+///
+/// Stops at the first malformed atom.
+struct Atoms<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Atoms<'a> {
+    const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+}
+
+impl<'a> Iterator for Atoms<'a> {
+    type Item = (AtomType, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (size, atom_type, header_size) = parse_atom_header(self.data)?;
+        let size = if size == 0 {
+            // The atom extends to the end of its parent
+            self.data.len()
+        } else {
+            usize::try_from(size).ok()?
+        };
+        if size < header_size || size > self.data.len() {
+            self.data = &[];
+            return None;
+        }
+        let (atom, remainder) = self.data.split_at(size);
+        self.data = remainder;
+        Some((atom_type, &atom[header_size..]))
+    }
+}
+
+/// Parse the size, type, and header size of an atom.
+fn parse_atom_header(data: &[u8]) -> Option<(u64, AtomType, usize)> {
+    let size = read_u32(data, 0)?;
+    let atom_type = data.get(4..8)?.try_into().ok()?;
+    if size == 1 {
+        // 64-bit extended size
+        Some((read_u64(data, 8)?, atom_type, 16))
+    } else {
+        Some((size.into(), atom_type, 8))
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_be_bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_be_bytes)
+}
+
+fn find_atom(data: &[u8], atom_type: AtomType) -> Option<&[u8]> {
+    Atoms::new(data).find_map(|(next_type, body)| (next_type == atom_type).then_some(body))
+}
+
+fn find_atom_path<'a>(data: &'a [u8], path: &[AtomType]) -> Option<&'a [u8]> {
+    path.iter()
+        .try_fold(data, |data, &atom_type| find_atom(data, atom_type))
+}
+
+/// The version of a full atom
+const fn full_atom_version(body: &[u8]) -> Option<u8> {
+    body.first().copied()
+}
+
+/// Iterate over the fixed-size entries of a full atom that
+/// starts with an entry count after the version and flags.
+fn table_entries(body: &[u8], entry_size: usize) -> impl Iterator<Item = &[u8]> {
+    let entry_count = read_u32(body, 4).unwrap_or(0) as usize;
+    body.get(8..)
+        .unwrap_or_default()
+        .chunks_exact(entry_size)
+        .take(entry_count)
+}
+
+/// Read the `moov` atom from the top-level atoms of the file.
+fn read_moov_atom<R: Read + Seek + ?Sized>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = [0; 16];
+    loop {
+        match reader.read_exact(&mut header[..8]) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let (size, header_size) = if read_u32(&header, 0) == Some(1) {
+            reader.read_exact(&mut header[8..16])?;
+            (read_u64(&header, 8).unwrap_or_default(), 16)
+        } else {
+            (read_u32(&header, 0).unwrap_or_default().into(), 8)
+        };
+        if size == 0 {
+            // The last atom extends to the end of the file
+            if &header[4..8] != b"moov" {
+                return Ok(None);
+            }
+            let mut body = Vec::new();
+            reader.read_to_end(&mut body)?;
+            return Ok(Some(body));
+        }
+        let Some(body_size) = size.checked_sub(header_size) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid atom size: {size}"),
+            ));
+        };
+        if &header[4..8] == b"moov" {
+            let mut body = Vec::new();
+            Read::take(&mut *reader, body_size).read_to_end(&mut body)?;
+            if (body.len() as u64) < body_size {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            return Ok(Some(body));
+        }
+        let body_size = i64::try_from(body_size)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        reader.seek(SeekFrom::Current(body_size))?;
+    }
+}
+
+/// Parse the Nero chapter list from the body of a `chpl` atom.
+fn parse_nero_chapters(chpl: &[u8]) -> Vec<Chapter> {
+    let Some(version) = full_atom_version(chpl) else {
+        return Vec::new();
+    };
+    // Version 1 has 4 additional, reserved bytes
+    let offset = if version > 0 { 8 } else { 4 };
+    let Some(&chapter_count) = chpl.get(offset) else {
+        return Vec::new();
+    };
+    let mut data = &chpl[offset + 1..];
+    let mut chapters = Vec::with_capacity(chapter_count.into());
+    for _ in 0..chapter_count {
+        let Some(start) = read_u64(data, 0) else {
+            break;
+        };
+        let Some(&title_len) = data.get(8) else {
+            break;
+        };
+        let Some(title) = data.get(9..9 + usize::from(title_len)) else {
+            break;
+        };
+        chapters.push(Chapter {
+            start_ms: start as f64 * MILLIS_PER_SEC / NERO_CHAPTER_TIMESCALE as f64,
+            title: String::from_utf8_lossy(title).trim().to_owned(),
+        });
+        data = &data[9 + usize::from(title_len)..];
+    }
+    chapters
+}
+
+fn track_id(trak: &[u8]) -> Option<u32> {
+    let tkhd = find_atom(trak, *b"tkhd")?;
+    let offset = if full_atom_version(tkhd)? > 0 { 20 } else { 12 };
+    read_u32(tkhd, offset)
+}
+
+fn chapter_track_ids(trak: &[u8]) -> Vec<u32> {
+    find_atom_path(trak, &[*b"tref", *b"chap"])
+        .map(|chap| {
+            chap.chunks_exact(4)
+                .filter_map(|bytes| read_u32(bytes, 0))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A sample of a text track
+struct TextSample {
+    start_ms: f64,
+    offset: u64,
+    size: u64,
+}
+
+/// Locate all samples of a track in the file.
+fn text_samples(trak: &[u8]) -> Option<Vec<TextSample>> {
+    let mdia = find_atom(trak, *b"mdia")?;
+    let mdhd = find_atom(mdia, *b"mdhd")?;
+    let timescale_offset = if full_atom_version(mdhd)? > 0 { 20 } else { 12 };
+    let timescale = read_u32(mdhd, timescale_offset).filter(|timescale| *timescale > 0)?;
+    let stbl = find_atom_path(mdia, &[*b"minf", *b"stbl"])?;
+
+    // Sample sizes
+    let stsz = find_atom(stbl, *b"stsz")?;
+    let uniform_sample_size = read_u32(stsz, 4)?;
+    let sample_count = read_u32(stsz, 8)? as usize;
+    let sample_sizes = if uniform_sample_size > 0 {
+        vec![uniform_sample_size; sample_count.min(stsz.len())]
+    } else {
+        stsz.get(12..)?
+            .chunks_exact(4)
+            .take(sample_count)
+            .filter_map(|bytes| read_u32(bytes, 0))
+            .collect()
+    };
+
+    // Chunk offsets
+    let chunk_offsets = if let Some(stco) = find_atom(stbl, *b"stco") {
+        table_entries(stco, 4)
+            .filter_map(|entry| read_u32(entry, 0))
+            .map(u64::from)
+            .collect::<Vec<_>>()
+    } else {
+        let co64 = find_atom(stbl, *b"co64")?;
+        table_entries(co64, 8)
+            .filter_map(|entry| read_u64(entry, 0))
+            .collect()
+    };
+
+    // Sample offsets from the sample-to-chunk table
+    let stsc = find_atom(stbl, *b"stsc")?;
+    let sample_to_chunk = table_entries(stsc, 12)
+        .filter_map(|entry| Some((read_u32(entry, 0)?, read_u32(entry, 4)?)))
+        .collect::<Vec<_>>();
+    let mut sample_offsets = Vec::with_capacity(sample_sizes.len());
+    let mut sample_sizes_iter = sample_sizes.iter().copied();
+    'chunks: for (chunk_index, chunk_offset) in chunk_offsets.iter().copied().enumerate() {
+        let chunk_number = chunk_index as u64 + 1;
+        let samples_per_chunk = sample_to_chunk
+            .iter()
+            .take_while(|(first_chunk, _)| u64::from(*first_chunk) <= chunk_number)
+            .last()
+            .map_or(0, |(_, samples_per_chunk)| *samples_per_chunk);
+        let mut offset = chunk_offset;
+        for _ in 0..samples_per_chunk {
+            let Some(size) = sample_sizes_iter.next() else {
+                break 'chunks;
+            };
+            sample_offsets.push(offset);
+            offset += u64::from(size);
+        }
+    }
+
+    // Sample start times from the time-to-sample table
+    let stts = find_atom(stbl, *b"stts")?;
+    let sample_deltas = table_entries(stts, 8)
+        .filter_map(|entry| Some((read_u32(entry, 0)?, read_u32(entry, 4)?)))
+        .flat_map(|(count, delta)| std::iter::repeat_n(delta, count as usize));
+    let mut start = 0u64;
+    let samples = sample_offsets
+        .into_iter()
+        .zip(sample_sizes)
+        .zip(sample_deltas)
+        .map(|((offset, size), delta)| {
+            let start_ms = start as f64 * MILLIS_PER_SEC / f64::from(timescale);
+            start += u64::from(delta);
+            TextSample {
+                start_ms,
+                offset,
+                size: size.into(),
+            }
+        })
+        .collect();
+    Some(samples)
+}
+
+/// Decode the text of a timed text sample.
+///
+/// The text is prefixed by its length in bytes and optionally
+/// followed by modifier atoms that are ignored.
+fn decode_text_sample(sample: &[u8]) -> String {
+    let Some(text_len) = sample
+        .get(..2)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u16::from_be_bytes)
+    else {
+        return String::new();
+    };
+    let text = &sample[2..(2 + usize::from(text_len)).min(sample.len())];
+    let title = if let Some(utf16_be) = text.strip_prefix(&[0xFE, 0xFF]) {
+        String::from_utf16_lossy(
+            &utf16_be
+                .chunks_exact(2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        String::from_utf8_lossy(text).into_owned()
+    };
+    title.trim().to_owned()
+}
+
+fn read_quicktime_chapters<R: Read + Seek + ?Sized>(
+    reader: &mut R,
+    moov: &[u8],
+) -> io::Result<Vec<Chapter>> {
+    let traks = Atoms::new(moov)
+        .filter_map(|(atom_type, body)| (&atom_type == b"trak").then_some(body))
+        .collect::<Vec<_>>();
+    let chapter_track_ids = traks
+        .iter()
+        .flat_map(|trak| chapter_track_ids(trak))
+        .collect::<Vec<_>>();
+    let Some(chapter_trak) = traks
+        .iter()
+        .find(|trak| track_id(trak).is_some_and(|id| chapter_track_ids.contains(&id)))
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(samples) = text_samples(chapter_trak) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "malformed chapter track",
+        ));
+    };
+    let mut chapters = Vec::with_capacity(samples.len());
+    for TextSample {
+        start_ms,
+        offset,
+        size,
+    } in samples
+    {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut sample = Vec::new();
+        Read::take(&mut *reader, size.min(MAX_TEXT_SAMPLE_SIZE)).read_to_end(&mut sample)?;
+        chapters.push(Chapter {
+            start_ms,
+            title: decode_text_sample(&sample),
+        });
+    }
+    Ok(chapters)
+}
+
+/// Read the chapters of an MPEG-4 file.
+///
+/// The position of the reader is restored afterwards.
+pub(crate) fn read_chapters<R: Read + Seek + ?Sized>(reader: &mut R) -> io::Result<Vec<Chapter>> {
+    let position = reader.stream_position()?;
+    let chapters = read_chapters_from_start(reader);
+    reader.seek(SeekFrom::Start(position))?;
+    chapters
+}
+
+fn read_chapters_from_start<R: Read + Seek + ?Sized>(reader: &mut R) -> io::Result<Vec<Chapter>> {
+    let Some(moov) = read_moov_atom(reader)? else {
+        return Ok(Vec::new());
+    };
+    let chapters = read_quicktime_chapters(reader, &moov)?;
+    if !chapters.is_empty() {
+        return Ok(chapters);
+    }
+    Ok(find_atom_path(&moov, &[*b"udta", *b"chpl"])
+        .map(parse_nero_chapters)
+        .unwrap_or_default())
+}
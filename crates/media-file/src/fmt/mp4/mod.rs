@@ -3,23 +3,44 @@
 
 use std::{borrow::Cow, fs::File};
 
-use aoide_core::track::Track;
+use aoide_core::{
+    audio::PositionMs,
+    track::{
+        cue::{BankIndex, Cue, CueFlags, InMarker},
+        Track,
+    },
+};
 use lofty::{
     config::WriteOptions,
     file::AudioFile,
-    mp4::{AtomData, AtomIdent, Ilst, Mp4File},
+    mp4::{Atom, AtomData, AtomIdent, Ilst, Mp4File},
 };
+use nonicle::CanonicalizeInto as _;
 
 use super::parse_options;
 use crate::{
     io::{
         export::{ExportTrackConfig, ExportTrackFlags},
-        import::{ImportTrackConfig, ImportTrackFlags, Importer},
+        import::{ImportTrackConfig, ImportTrackFlags, Importer, IssueKind},
     },
     util::artwork::EditEmbeddedArtworkImage,
     Result,
 };
 
+mod chapters;
+
+pub(crate) use self::chapters::Chapter;
+
+/// The bank of cues that are imported from chapter markers.
+const CHAPTER_CUE_BANK_INDEX: BankIndex = 0;
+
+/// Distinguishes chapter cues from other cues within the same bank.
+const CHAPTER_CUE_KIND: &str = "chapter";
+
+const MOVEMENT_NUMBER_IDENT: AtomIdent<'_> = AtomIdent::Fourcc(*b"\xa9mvi");
+
+const MOVEMENT_COUNT_IDENT: AtomIdent<'_> = AtomIdent::Fourcc(*b"\xa9mvc");
+
 #[cfg(feature = "serato-markers")]
 const SERATO_MARKERS_IDENT: AtomIdent<'_> = AtomIdent::Freeform {
     mean: Cow::Borrowed(<triseratops::tag::Markers as triseratops::tag::format::mp4::MP4Tag>::MP4_ATOM_FREEFORM_MEAN),
@@ -59,7 +80,10 @@ fn import_serato_markers(
                 }
             }
             data => {
-                importer.add_issue(format!("Unexpected data for Serato Markers: {data:?}"));
+                importer.add_issue_of_kind(
+                    IssueKind::UnsupportedTag,
+                    format!("Unexpected data for Serato Markers: {data:?}"),
+                );
             }
         }
     }
@@ -81,7 +105,10 @@ fn import_serato_markers(
                 }
             }
             data => {
-                importer.add_issue(format!("Unexpected data for Serato Markers2: {data:?}"));
+                importer.add_issue_of_kind(
+                    IssueKind::UnsupportedTag,
+                    format!("Unexpected data for Serato Markers2: {data:?}"),
+                );
             }
         }
     }
@@ -89,6 +116,20 @@ fn import_serato_markers(
     parsed.then_some(serato_tags)
 }
 
+/// Convert the integer atoms of the movement number and count into text.
+///
+/// Integer atoms are ignored when converting [`Ilst`] into a generic tag.
+fn convert_movement_atoms_into_text(ilst: &mut Ilst) {
+    for ident in [MOVEMENT_NUMBER_IDENT, MOVEMENT_COUNT_IDENT] {
+        let number = match ilst.get(&ident).and_then(|atom| atom.data().next()) {
+            Some(AtomData::SignedInteger(number)) => i64::from(*number),
+            Some(AtomData::UnsignedInteger(number)) => i64::from(*number),
+            _ => continue,
+        };
+        ilst.replace_atom(Atom::new(ident, AtomData::UTF8(number.to_string())));
+    }
+}
+
 #[derive(Debug, Default)]
 struct Import {
     #[cfg(feature = "serato-markers")]
@@ -130,12 +171,63 @@ impl Import {
     }
 }
 
+/// Read the chapter markers of an MPEG-4 file.
+///
+/// Failures are reported as issues. The position of the reader is
+/// restored afterwards.
+pub(crate) fn import_chapters<R>(importer: &mut Importer, reader: &mut R) -> Option<Vec<Chapter>>
+where
+    R: std::io::Read + std::io::Seek + ?Sized,
+{
+    chapters::read_chapters(reader)
+        .map_err(|err| {
+            importer.add_issue(format!("Failed to read chapters: {err}"));
+        })
+        .ok()
+}
+
+fn chapter_cue(slot_index: usize, chapter: Chapter) -> Cue {
+    let Chapter { start_ms, title } = chapter;
+    Cue {
+        bank_index: CHAPTER_CUE_BANK_INDEX,
+        slot_index: slot_index.try_into().ok(),
+        in_marker: Some(InMarker {
+            position: PositionMs::new(start_ms),
+        }),
+        out_marker: None,
+        kind: Some(CHAPTER_CUE_KIND.to_owned()),
+        label: (!title.is_empty()).then_some(title),
+        color: None,
+        flags: CueFlags::empty(),
+    }
+}
+
+/// Replace all chapter cues of the track.
+fn import_chapters_as_cues(track: &mut Track, chapters: Vec<Chapter>) {
+    let mut cues = std::mem::take(&mut track.cues).untie();
+    cues.retain(|cue| {
+        cue.bank_index != CHAPTER_CUE_BANK_INDEX || cue.kind.as_deref() != Some(CHAPTER_CUE_KIND)
+    });
+    cues.extend(
+        chapters
+            .into_iter()
+            .enumerate()
+            .map(|(slot_index, chapter)| chapter_cue(slot_index, chapter)),
+    );
+    track.cues = cues.canonicalize_into();
+}
+
 pub(crate) fn import_file_into_track(
     importer: &mut Importer,
     config: &ImportTrackConfig,
-    mp4_file: Mp4File,
+    mut mp4_file: Mp4File,
+    chapters: Option<Vec<Chapter>>,
     track: &mut Track,
 ) {
+    if let Some(ilst) = mp4_file.ilst_mut() {
+        convert_movement_atoms_into_text(ilst);
+    }
+
     // Pre-processing
     let import = config
         .flags
@@ -152,6 +244,9 @@ pub(crate) fn import_file_into_track(
     if let Some(import) = import {
         import.finish(track);
     }
+    if let Some(chapters) = chapters {
+        import_chapters_as_cues(track, chapters);
+    }
 }
 
 pub(crate) fn export_track_to_file(
@@ -189,3 +284,6 @@ pub(crate) fn export_track_to_tag(
         log::warn!("TODO: Export Serato markers");
     }
 }
+
+#[cfg(test)]
+mod tests;
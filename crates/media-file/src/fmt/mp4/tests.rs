@@ -0,0 +1,255 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::{Cursor, Seek as _, SeekFrom};
+
+use aoide_core::{media::content::ContentLink, util::clock::OffsetDateTimeMs};
+
+use super::{chapters::read_chapters, *};
+use crate::io::import::ImportTrack;
+
+const QUICKTIME_CHAPTERS: &[(u32, &str)] = &[
+    (60_000, "Intro"),
+    (90_000, "Chapter 1"),
+    (30_000, "Chapter 2"),
+];
+
+const NERO_CHAPTERS: &[(u64, &str)] = &[(0, "Nero Intro"), (600_000_000, "Nero Chapter 1")];
+
+fn atom(atom_type: [u8; 4], body: &[u8]) -> Vec<u8> {
+    let size = u32::try_from(8 + body.len()).unwrap();
+    let mut atom = Vec::with_capacity(8 + body.len());
+    atom.extend_from_slice(&size.to_be_bytes());
+    atom.extend_from_slice(&atom_type);
+    atom.extend_from_slice(body);
+    atom
+}
+
+fn full_atom(atom_type: [u8; 4], body: &[u8]) -> Vec<u8> {
+    // Version 0 without flags
+    atom(atom_type, &[[0; 4].as_slice(), body].concat())
+}
+
+/// Encode a table with an entry count followed by the entries.
+fn table<const N: usize>(entries: &[[u32; N]]) -> Vec<u8> {
+    let mut table = u32::try_from(entries.len()).unwrap().to_be_bytes().to_vec();
+    for value in entries.iter().flatten() {
+        table.extend_from_slice(&value.to_be_bytes());
+    }
+    table
+}
+
+fn tkhd(track_id: u32) -> Vec<u8> {
+    // Creation time, modification time, track id, reserved, duration
+    full_atom(
+        *b"tkhd",
+        &[[0; 8].as_slice(), &track_id.to_be_bytes(), &[0; 8]].concat(),
+    )
+}
+
+fn text_sample(text: &str) -> Vec<u8> {
+    let text_len = u16::try_from(text.len()).unwrap();
+    [text_len.to_be_bytes().as_slice(), text.as_bytes()].concat()
+}
+
+fn chapter_trak(track_id: u32, chunk_offset: u32) -> Vec<u8> {
+    let sample_sizes = QUICKTIME_CHAPTERS
+        .iter()
+        .map(|(_, title)| u32::try_from(text_sample(title).len()).unwrap())
+        .collect::<Vec<_>>();
+    let sample_count = u32::try_from(sample_sizes.len()).unwrap();
+    // Sample count and duration
+    let stts = full_atom(
+        *b"stts",
+        &table(
+            &QUICKTIME_CHAPTERS
+                .iter()
+                .map(|(duration, _)| [1, *duration])
+                .collect::<Vec<_>>(),
+        ),
+    );
+    // Uniform sample size (none) followed by the individual sample sizes
+    let stsz = full_atom(
+        *b"stsz",
+        &[
+            0u32.to_be_bytes().as_slice(),
+            &table(&sample_sizes.iter().map(|size| [*size]).collect::<Vec<_>>()),
+        ]
+        .concat(),
+    );
+    // All samples in a single chunk: first chunk, samples per chunk, sample description
+    let stsc = full_atom(*b"stsc", &table(&[[1, sample_count, 1]]));
+    let stco = full_atom(*b"stco", &table(&[[chunk_offset]]));
+    let stbl = atom(*b"stbl", &[stts, stsz, stsc, stco].concat());
+    let minf = atom(*b"minf", &stbl);
+    // Creation time, modification time, timescale, duration, language, quality
+    let mdhd = full_atom(
+        *b"mdhd",
+        &[[0; 8].as_slice(), &1_000u32.to_be_bytes(), &[0; 8]].concat(),
+    );
+    let mdia = atom(*b"mdia", &[mdhd, minf].concat());
+    atom(*b"trak", &[tkhd(track_id), mdia].concat())
+}
+
+fn nero_chpl() -> Vec<u8> {
+    let mut body = vec![u8::try_from(NERO_CHAPTERS.len()).unwrap()];
+    for (start, title) in NERO_CHAPTERS {
+        body.extend_from_slice(&start.to_be_bytes());
+        body.push(u8::try_from(title.len()).unwrap());
+        body.extend_from_slice(title.as_bytes());
+    }
+    full_atom(*b"chpl", &body)
+}
+
+/// Create the contents of an MPEG-4 file with chapters.
+///
+/// The file contains no audio data, only the atoms that are
+/// required for locating the chapters.
+fn new_mp4_file_with_chapters(with_quicktime_chapters: bool) -> Vec<u8> {
+    let ftyp = atom(*b"ftyp", b"M4A \0\0\0\0M4A mp42isom");
+    let mdat_body = QUICKTIME_CHAPTERS
+        .iter()
+        .flat_map(|(_, title)| text_sample(title))
+        .collect::<Vec<_>>();
+    let mdat = atom(*b"mdat", &mdat_body);
+    let chunk_offset = u32::try_from(ftyp.len() + 8).unwrap();
+    let audio_trak = if with_quicktime_chapters {
+        atom(
+            *b"trak",
+            &[
+                tkhd(1),
+                atom(*b"tref", &atom(*b"chap", &2u32.to_be_bytes())),
+            ]
+            .concat(),
+        )
+    } else {
+        atom(*b"trak", &tkhd(1))
+    };
+    let moov = atom(
+        *b"moov",
+        &[
+            full_atom(*b"mvhd", &[0; 96]),
+            audio_trak,
+            chapter_trak(2, chunk_offset),
+            atom(*b"udta", &nero_chpl()),
+        ]
+        .concat(),
+    );
+    [ftyp, mdat, moov].concat()
+}
+
+#[test]
+fn read_quicktime_chapters_with_precedence_over_nero_chapters() {
+    let mut reader = Cursor::new(new_mp4_file_with_chapters(true));
+    let chapters = read_chapters(&mut reader).unwrap();
+    assert_eq!(
+        vec![
+            Chapter {
+                start_ms: 0.0,
+                title: "Intro".to_owned(),
+            },
+            Chapter {
+                start_ms: 60_000.0,
+                title: "Chapter 1".to_owned(),
+            },
+            Chapter {
+                start_ms: 150_000.0,
+                title: "Chapter 2".to_owned(),
+            },
+        ],
+        chapters
+    );
+}
+
+#[test]
+fn read_nero_chapters() {
+    let mut reader = Cursor::new(new_mp4_file_with_chapters(false));
+    let chapters = read_chapters(&mut reader).unwrap();
+    assert_eq!(
+        vec![
+            Chapter {
+                start_ms: 0.0,
+                title: "Nero Intro".to_owned(),
+            },
+            Chapter {
+                start_ms: 60_000.0,
+                title: "Nero Chapter 1".to_owned(),
+            },
+        ],
+        chapters
+    );
+}
+
+#[test]
+fn read_chapters_restores_reader_position() {
+    let mut reader = Cursor::new(new_mp4_file_with_chapters(true));
+    reader.seek(SeekFrom::Start(4)).unwrap();
+    assert_eq!(3, read_chapters(&mut reader).unwrap().len());
+    assert_eq!(4, reader.position());
+}
+
+#[test]
+fn read_no_chapters_without_moov_atom() {
+    let mut reader = Cursor::new(atom(*b"ftyp", b"M4A \0\0\0\0"));
+    assert!(read_chapters(&mut reader).unwrap().is_empty());
+}
+
+#[test]
+fn import_chapters_replaces_previous_chapter_cues() {
+    let mut track = ImportTrack::NewTrack {
+        collected_at: OffsetDateTimeMs::now_utc(),
+    }
+    .with_content(
+        ContentLink {
+            path: Default::default(),
+            rev: None,
+        },
+        "audio/m4a".parse().unwrap(),
+    );
+    let hot_cue = Cue {
+        bank_index: 1,
+        slot_index: Some(0),
+        in_marker: Some(InMarker {
+            position: PositionMs::new(1_000.0),
+        }),
+        out_marker: None,
+        kind: None,
+        label: None,
+        color: None,
+        flags: CueFlags::empty(),
+    };
+    let outdated_chapter_cue = chapter_cue(
+        0,
+        Chapter {
+            start_ms: 0.0,
+            title: "Outdated".to_owned(),
+        },
+    );
+    track.cues = vec![hot_cue.clone(), outdated_chapter_cue].canonicalize_into();
+
+    let mut reader = Cursor::new(new_mp4_file_with_chapters(true));
+    let chapters = read_chapters(&mut reader).unwrap();
+    import_chapters_as_cues(&mut track, chapters);
+
+    let chapter_cues = track
+        .cues
+        .iter()
+        .filter(|cue| cue.kind.as_deref() == Some(CHAPTER_CUE_KIND))
+        .map(|cue| {
+            (
+                cue.in_marker.as_ref().unwrap().position.value(),
+                cue.label.as_deref().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (0.0, "Intro"),
+            (60_000.0, "Chapter 1"),
+            (150_000.0, "Chapter 2")
+        ],
+        chapter_cues
+    );
+    assert!(track.cues.contains(&hot_cue));
+    assert_eq!(4, track.cues.len());
+}
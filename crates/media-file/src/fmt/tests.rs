@@ -0,0 +1,435 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::Cursor;
+
+use image::{Rgb, RgbImage};
+use lofty::{
+    config::{ParseOptions, WriteOptions},
+    file::{AudioFile as _, FileType},
+    id3::v2::Id3v2Tag,
+    mp4::{Atom, AtomData, AtomIdent, Ilst, Mp4File},
+    ogg::VorbisComments,
+    tag::{Accessor as _, TagExt as _},
+};
+use mime::IMAGE_PNG;
+
+use aoide_core::{media::content::ContentLink, tag::Label, util::clock::OffsetDateTimeMs};
+
+use super::*;
+use crate::io::import::{ImportTrack, DEFAULT_VARIOUS_ARTISTS_NAME};
+
+fn import_tag_into_new_track(config: &ImportTrackConfig, tag: Tag) -> Track {
+    let mut track = ImportTrack::NewTrack {
+        collected_at: OffsetDateTimeMs::now_utc(),
+    }
+    .with_content(
+        ContentLink {
+            path: Default::default(),
+            rev: None,
+        },
+        "audio/mpeg".parse().unwrap(),
+    );
+    let mut importer = Importer::new();
+    import_file_tag_into_track(
+        &mut importer,
+        config,
+        &FileProperties::default(),
+        tag,
+        &mut track,
+    );
+    track
+}
+
+fn id3v2_compilation_track_tag(track_artist: &str) -> Tag {
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.insert_text(ItemKey::TrackArtist, track_artist.to_owned());
+    tag.insert_text(ItemKey::AlbumTitle, "Compilation".to_owned());
+    tag.insert_text(ItemKey::FlagCompilation, "1".to_owned());
+    // Convert into the native tag format to map the compilation flag onto
+    // the corresponding TCMP frame and back.
+    Id3v2Tag::from(tag).into()
+}
+
+fn mp4_compilation_track_tag(track_artist: &str) -> Tag {
+    let mut ilst = Ilst::default();
+    ilst.set_artist(track_artist.to_owned());
+    ilst.set_album("Compilation".to_owned());
+    ilst.insert(Atom::new(AtomIdent::Fourcc(*b"cpil"), AtomData::Bool(true)));
+    ilst.into()
+}
+
+#[test]
+fn import_id3v2_compilation_flag_groups_tracks_by_album_artist() {
+    let config = ImportTrackConfig::default();
+    let tracks = ["Artist 1", "Artist 2"]
+        .into_iter()
+        .map(|track_artist| {
+            import_tag_into_new_track(&config, id3v2_compilation_track_tag(track_artist))
+        })
+        .collect::<Vec<_>>();
+    for track in &tracks {
+        assert_eq!(Some(AlbumKind::Compilation), track.album.kind);
+        assert_eq!(Some(DEFAULT_VARIOUS_ARTISTS_NAME), track.album_artist());
+    }
+    assert_ne!(tracks[0].track_artist(), tracks[1].track_artist());
+    assert_eq!(tracks[0].album, tracks[1].album);
+}
+
+#[test]
+fn import_mp4_cpil_atom_groups_tracks_by_album_artist() {
+    let config = ImportTrackConfig::default();
+    let tracks = ["Artist 1", "Artist 2"]
+        .into_iter()
+        .map(|track_artist| {
+            import_tag_into_new_track(&config, mp4_compilation_track_tag(track_artist))
+        })
+        .collect::<Vec<_>>();
+    for track in &tracks {
+        assert_eq!(Some(AlbumKind::Compilation), track.album.kind);
+        assert_eq!(Some(DEFAULT_VARIOUS_ARTISTS_NAME), track.album_artist());
+    }
+    assert_ne!(tracks[0].track_artist(), tracks[1].track_artist());
+    assert_eq!(tracks[0].album, tracks[1].album);
+}
+
+#[test]
+fn import_compilation_preserves_album_artist() {
+    let config = ImportTrackConfig::default();
+    let mut tag = id3v2_compilation_track_tag("Artist");
+    tag.insert_text(ItemKey::AlbumArtist, "DJ Mix".to_owned());
+    let track = import_tag_into_new_track(&config, tag);
+    assert_eq!(Some(AlbumKind::Compilation), track.album.kind);
+    assert_eq!(Some("DJ Mix"), track.album_artist());
+}
+
+#[test]
+fn import_configured_various_artists_album_artist_as_compilation() {
+    let config = ImportTrackConfig {
+        various_artists_names: vec!["Verschiedene Interpreten".to_owned()],
+        ..Default::default()
+    };
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.insert_text(ItemKey::TrackArtist, "Artist".to_owned());
+    tag.insert_text(ItemKey::AlbumArtist, "verschiedene interpreten".to_owned());
+    let track = import_tag_into_new_track(&config, tag);
+    assert_eq!(Some(AlbumKind::Compilation), track.album.kind);
+    assert_eq!(Some("verschiedene interpreten"), track.album_artist());
+
+    // Case-insensitive beyond ASCII
+    let config = ImportTrackConfig {
+        various_artists_names: vec!["Różni wykonawcy".to_owned()],
+        ..Default::default()
+    };
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.insert_text(ItemKey::TrackArtist, "Artist".to_owned());
+    tag.insert_text(ItemKey::AlbumArtist, "RÓŻNI WYKONAWCY".to_owned());
+    let track = import_tag_into_new_track(&config, tag);
+    assert_eq!(Some(AlbumKind::Compilation), track.album.kind);
+
+    // Not configured as various artists
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.insert_text(ItemKey::TrackArtist, "Artist".to_owned());
+    tag.insert_text(
+        ItemKey::AlbumArtist,
+        DEFAULT_VARIOUS_ARTISTS_NAME.to_owned(),
+    );
+    let track = import_tag_into_new_track(&config, tag);
+    assert_eq!(None, track.album.kind);
+}
+
+fn encode_png_image_data() -> Vec<u8> {
+    let picture = RgbImage::from_pixel(8, 8, Rgb([0xff, 0x00, 0x00]));
+    let mut image_data = Vec::new();
+    picture
+        .write_to(&mut Cursor::new(&mut image_data), ImageFormat::Png)
+        .unwrap();
+    image_data
+}
+
+fn vorbis_comments_tag_with_pictures(pictures: impl IntoIterator<Item = Picture>) -> Tag {
+    let mut tag = Tag::new(TagType::VorbisComments);
+    for picture in pictures {
+        tag.push_picture(picture);
+    }
+    tag
+}
+
+fn import_embedded_artwork_with_issues(tag: &Tag) -> (Artwork, Vec<String>) {
+    let mut importer = Importer::new();
+    let artwork = import_embedded_artwork(
+        &mut importer,
+        tag,
+        MediaDigest::new(),
+        false,
+        &ArtworkImageLimits::UNLIMITED,
+    );
+    (artwork, importer.finish().into_messages())
+}
+
+#[test]
+fn import_vorbis_artwork_with_wrong_mime_type() {
+    let tag = vorbis_comments_tag_with_pictures([Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(MimeType::Jpeg),
+        None,
+        encode_png_image_data(),
+    )]);
+    let (artwork, issues) = import_embedded_artwork_with_issues(&tag);
+    assert!(issues.is_empty());
+    let Artwork::Embedded(EmbeddedArtwork { image }) = artwork else {
+        panic!("unexpected artwork: {artwork:?}");
+    };
+    assert_eq!(IMAGE_PNG, image.media_type);
+    assert_eq!(ApicType::CoverFront, image.apic_type);
+    assert!(image.image_size.is_some());
+}
+
+#[test]
+fn import_vorbis_artwork_skips_unrecognized_image_data() {
+    let garbage_picture = Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(MimeType::Png),
+        None,
+        b"garbage".to_vec(),
+    );
+    let tag = vorbis_comments_tag_with_pictures([garbage_picture.clone()]);
+    let (artwork, issues) = import_embedded_artwork_with_issues(&tag);
+    assert_eq!(Artwork::Missing, artwork);
+    assert_eq!(1, issues.len());
+
+    // Fall back to the next picture, even without a declared MIME type
+    let tag = vorbis_comments_tag_with_pictures([
+        garbage_picture,
+        Picture::new_unchecked(PictureType::Leaflet, None, None, encode_png_image_data()),
+    ]);
+    let (artwork, issues) = import_embedded_artwork_with_issues(&tag);
+    assert_eq!(1, issues.len());
+    let Artwork::Embedded(EmbeddedArtwork { image }) = artwork else {
+        panic!("unexpected artwork: {artwork:?}");
+    };
+    assert_eq!(IMAGE_PNG, image.media_type);
+    assert_eq!(ApicType::Leaflet, image.apic_type);
+}
+
+fn file_properties(sample_rate: u32, bit_depth: u8, channels: u8) -> FileProperties {
+    FileProperties::new(
+        std::time::Duration::from_secs(180),
+        None,
+        Some(320),
+        Some(sample_rate),
+        Some(bit_depth),
+        Some(channels),
+        None,
+    )
+}
+
+#[test]
+fn import_standard_audio_properties() {
+    for sample_rate in [44_100, 48_000, 96_000] {
+        let mut importer = Importer::new();
+        let audio_content = import_audio_content_from_file_properties(
+            &mut importer,
+            &file_properties(sample_rate, 16, 2),
+        );
+        assert!(importer.finish().is_empty());
+        assert_eq!(
+            Some(SampleRateHz::new(sample_rate.into())),
+            audio_content.sample_rate
+        );
+        assert_eq!(
+            Some(ChannelCount::new(2)),
+            audio_content.channels.map(Channels::count)
+        );
+    }
+}
+
+#[test]
+fn import_implausible_audio_properties_with_issues() {
+    let mut importer = Importer::new();
+    let audio_content =
+        import_audio_content_from_file_properties(&mut importer, &file_properties(0, 0, 0));
+    assert!(audio_content.sample_rate.is_none());
+    assert!(audio_content.channels.is_none());
+    // One issue for each of sample rate, channel count, and bit depth
+    assert_eq!(3, importer.finish().len());
+}
+
+const GROUPING: &str = "Grouping";
+const WORK: &str = "Work";
+const MOVEMENT: &str = "Movement";
+
+fn classical_music_tag(tag_type: TagType) -> Tag {
+    let mut tag = Tag::new(tag_type);
+    let grouping_key = if tag_type == TagType::Id3v2 {
+        ItemKey::AppleId3v2ContentGroup // GRP1
+    } else {
+        ItemKey::ContentGroup
+    };
+    tag.insert_text(grouping_key, GROUPING.to_owned());
+    tag.insert_text(ItemKey::Work, WORK.to_owned());
+    tag.insert_text(ItemKey::Movement, MOVEMENT.to_owned());
+    tag.insert_text(ItemKey::MovementNumber, "2/4".to_owned());
+    tag
+}
+
+fn assert_classical_music_fields(track: &Track) {
+    let grouping_labels = track
+        .tags
+        .facets
+        .iter()
+        .find(|faceted_tags| faceted_tags.facet_id == *FACET_ID_GROUPING)
+        .map(|faceted_tags| {
+            faceted_tags
+                .tags
+                .iter()
+                .filter_map(|tag| tag.label.as_ref().map(Label::as_str))
+                .collect::<Vec<_>>()
+        });
+    assert_eq!(Some(vec![GROUPING]), grouping_labels);
+    assert_eq!(
+        Some(WORK),
+        Titles::kind_title(track.titles.iter(), TitleKind::Work).map(|title| title.name.as_str())
+    );
+    assert_eq!(
+        Some(MOVEMENT),
+        Titles::kind_title(track.titles.iter(), TitleKind::Movement)
+            .map(|title| title.name.as_str())
+    );
+    assert_eq!(
+        Index {
+            number: Some(2),
+            total: Some(4),
+        },
+        track.indexes.movement
+    );
+}
+
+#[test]
+fn import_id3v2_grouping_work_and_movement() {
+    // GRP1, TXXX:WORK, MVNM, MVIN
+    let tag = Id3v2Tag::from(classical_music_tag(TagType::Id3v2)).into();
+    let track = import_tag_into_new_track(&ImportTrackConfig::default(), tag);
+    assert_classical_music_fields(&track);
+}
+
+fn mp4_atom(atom_type: [u8; 4], body: &[u8]) -> Vec<u8> {
+    let size = u32::try_from(8 + body.len()).unwrap();
+    [size.to_be_bytes().as_slice(), &atom_type, body].concat()
+}
+
+/// Write the atoms into the contents of a minimal MPEG-4 file.
+fn new_mp4_file_with_ilst(ilst: &Ilst) -> Cursor<Vec<u8>> {
+    let ftyp = mp4_atom(*b"ftyp", b"M4A \0\0\0\0M4A mp42isom");
+    // Version 0 without flags
+    let mvhd = mp4_atom(*b"mvhd", &[0; 100]);
+    let moov = mp4_atom(*b"moov", &mvhd);
+    let mut file = Cursor::new([ftyp, moov, mp4_atom(*b"mdat", &[])].concat());
+    ilst.save_to(&mut file, WriteOptions::default()).unwrap();
+    file.set_position(0);
+    file
+}
+
+#[test]
+fn import_mp4_grouping_work_and_movement() {
+    // ©grp, ©wrk, ©mvn, ©mvi, ©mvc
+    let mut tag = classical_music_tag(TagType::Mp4Ilst);
+    tag.remove_key(&ItemKey::MovementNumber);
+    let mut ilst = Ilst::from(tag);
+    ilst.insert(Atom::new(
+        AtomIdent::Fourcc(*b"\xa9mvi"),
+        AtomData::SignedInteger(2),
+    ));
+    ilst.insert(Atom::new(
+        AtomIdent::Fourcc(*b"\xa9mvc"),
+        AtomData::SignedInteger(4),
+    ));
+    let mut file = new_mp4_file_with_ilst(&ilst);
+    let mp4_file =
+        Mp4File::read_from(&mut file, ParseOptions::new().read_properties(false)).unwrap();
+    let mut track = ImportTrack::NewTrack {
+        collected_at: OffsetDateTimeMs::now_utc(),
+    }
+    .with_content(
+        ContentLink {
+            path: Default::default(),
+            rev: None,
+        },
+        "audio/m4a".parse().unwrap(),
+    );
+    let mut importer = Importer::new();
+    mp4::import_file_into_track(
+        &mut importer,
+        &ImportTrackConfig::default(),
+        mp4_file,
+        None,
+        &mut track,
+    );
+    assert_classical_music_fields(&track);
+}
+
+#[test]
+fn import_vorbis_comments_grouping_work_and_movement() {
+    for [grouping_key, movement_number_key, movement_total_key] in [
+        ["GROUPING", "MOVEMENT", "MOVEMENTTOTAL"],
+        // Aliases
+        ["CONTENTGROUP", "MOVEMENTNUMBER", "MOVEMENTCOUNT"],
+        // Aliases are matched case-insensitive
+        ["contentgroup", "movementNumber", "MOVEMENTCOUNT"],
+    ] {
+        let mut vorbis_comments = VorbisComments::default();
+        for (key, value) in [
+            (grouping_key, GROUPING),
+            ("WORK", WORK),
+            ("MOVEMENTNAME", MOVEMENT),
+            (movement_number_key, "2"),
+            (movement_total_key, "4"),
+        ] {
+            vorbis_comments.push(key.to_owned(), value.to_owned());
+        }
+        let track =
+            import_tag_into_new_track(&ImportTrackConfig::default(), vorbis_comments.into());
+        assert_classical_music_fields(&track);
+    }
+}
+
+fn new_tag_with_title(tag_type: TagType, title: &str) -> Tag {
+    let mut tag = Tag::new(tag_type);
+    tag.insert_text(ItemKey::TrackTitle, title.to_owned());
+    tag
+}
+
+#[test]
+fn merge_tags_with_primary_tag_type_first_by_default() {
+    for (file_type, primary_tag_type) in [
+        (FileType::Flac, TagType::VorbisComments),
+        (FileType::Ape, TagType::Ape),
+    ] {
+        let mut tagged_file = TaggedFile::new(
+            file_type,
+            FileProperties::default(),
+            vec![
+                new_tag_with_title(TagType::Id3v2, "ID3v2"),
+                new_tag_with_title(primary_tag_type, "Primary"),
+            ],
+        );
+        let merged_tag = take_merged_tag(&mut tagged_file, None).unwrap();
+        assert_eq!(primary_tag_type, merged_tag.tag_type());
+        assert_eq!(Some("Primary"), merged_tag.get_string(&ItemKey::TrackTitle));
+    }
+}
+
+#[test]
+fn merge_tags_with_explicit_precedence() {
+    let mut tagged_file = TaggedFile::new(
+        FileType::Flac,
+        FileProperties::default(),
+        vec![
+            new_tag_with_title(TagType::VorbisComments, "Primary"),
+            new_tag_with_title(TagType::Id3v2, "ID3v2"),
+        ],
+    );
+    let merged_tag = take_merged_tag(&mut tagged_file, Some(&[FileTagType::Id3v2])).unwrap();
+    assert_eq!(TagType::VorbisComments, merged_tag.tag_type());
+    assert_eq!(Some("ID3v2"), merged_tag.get_string(&ItemKey::TrackTitle));
+}
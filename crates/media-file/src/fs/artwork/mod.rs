@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Album artwork from image files in the directory of media files.
+
+use std::path::{Path, PathBuf};
+
+use image::ImageFormat;
+use url::Url;
+
+use aoide_core::{
+    media::artwork::{ApicType, Artwork, EmbeddedArtwork, LinkedArtwork},
+    Track,
+};
+
+use crate::{
+    io::import::{ImportTrackConfig, ImportTrackFlags, IssueKind, Issues},
+    util::artwork::{
+        try_ingest_embedded_artwork_image, try_ingest_embedded_artwork_image_presence_only,
+    },
+};
+
+/// Find the first file in a directory that matches one of the file names.
+///
+/// File names are matched case-insensitive. The order of `file_names`
+/// determines the precedence if multiple files match.
+#[must_use]
+pub fn find_folder_artwork_file_path(
+    dir_path: &Path,
+    file_names: &[impl AsRef<str>],
+) -> Option<PathBuf> {
+    let dir_file_names = std::fs::read_dir(dir_path)
+        .ok()?
+        .filter_map(|dir_entry| {
+            let dir_entry = dir_entry.ok()?;
+            if !dir_entry.file_type().ok()?.is_file() {
+                return None;
+            }
+            dir_entry.file_name().into_string().ok()
+        })
+        .collect::<Vec<_>>();
+    file_names.iter().find_map(|file_name| {
+        dir_file_names
+            .iter()
+            .find(|dir_file_name| dir_file_name.eq_ignore_ascii_case(file_name.as_ref()))
+            .map(|dir_file_name| dir_path.join(dir_file_name))
+    })
+}
+
+/// Link an image file in the same directory as artwork of the track.
+///
+/// Only applies if [`ImportTrackFlags::FOLDER_ARTWORK`] is enabled and
+/// no artwork is embedded in the file at `file_path`, i.e. should be
+/// invoked after importing the file.
+pub fn import_folder_artwork_into_track(
+    file_path: &Path,
+    config: &ImportTrackConfig,
+    track: &mut Track,
+    issues: &mut Issues,
+) {
+    if !config.flags.contains(ImportTrackFlags::FOLDER_ARTWORK) {
+        return;
+    }
+    if !matches!(track.media_source.artwork, None | Some(Artwork::Missing)) {
+        log::debug!("Skipping import of folder artwork");
+        return;
+    }
+    let Some(image_path) = file_path.parent().and_then(|dir_path| {
+        find_folder_artwork_file_path(dir_path, &config.folder_artwork_file_names)
+    }) else {
+        return;
+    };
+    let Ok(uri) = Url::from_file_path(&image_path) else {
+        issues.add_message_of_kind(
+            IssueKind::MissingArtwork,
+            format!(
+                "Invalid folder artwork file path {image_path}",
+                image_path = image_path.display()
+            ),
+        );
+        return;
+    };
+    let image_data = match std::fs::read(&image_path) {
+        Ok(image_data) => image_data,
+        Err(err) => {
+            issues.add_message_of_kind(
+                IssueKind::MissingArtwork,
+                format!(
+                    "Failed to read folder artwork image {image_path}: {err}",
+                    image_path = image_path.display()
+                ),
+            );
+            return;
+        }
+    };
+    let image_format_hint = ImageFormat::from_path(&image_path).ok();
+    let mut image_digest = config.flags.new_artwork_digest();
+    let (artwork, new_issues) = if config
+        .flags
+        .contains(ImportTrackFlags::METADATA_EMBEDDED_ARTWORK_PRESENCE_ONLY)
+    {
+        try_ingest_embedded_artwork_image_presence_only(
+            ApicType::CoverFront,
+            &image_data,
+            image_format_hint,
+            None,
+            &mut image_digest,
+        )
+    } else {
+        try_ingest_embedded_artwork_image(
+            ApicType::CoverFront,
+            &image_data,
+            image_format_hint,
+            None,
+            &config.artwork_image_limits,
+            &mut image_digest,
+        )
+    };
+    for issue in new_issues {
+        issues.add_message_of_kind(IssueKind::MissingArtwork, issue);
+    }
+    let Artwork::Embedded(EmbeddedArtwork { image }) = artwork else {
+        // Keep the missing artwork if the image could not be imported
+        return;
+    };
+    track.media_source.artwork = Some(Artwork::Linked(LinkedArtwork {
+        uri: uri.into(),
+        image,
+    }));
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgb, RgbImage};
+use lofty::{
+    config::WriteOptions,
+    picture::{MimeType, Picture, PictureType},
+    tag::{Tag, TagExt as _, TagType},
+};
+
+use aoide_core::{media::content::ContentLink, util::clock::OffsetDateTimeMs};
+
+use super::*;
+use crate::io::import::{import_into_track, ImportTrack, Reader};
+
+fn encode_image(color: Rgb<u8>, format: ImageFormat) -> Vec<u8> {
+    let mut image_data = Vec::new();
+    RgbImage::from_pixel(8, 8, color)
+        .write_to(&mut Cursor::new(&mut image_data), format)
+        .unwrap();
+    image_data
+}
+
+fn folder_artwork_config() -> ImportTrackConfig {
+    let mut config = ImportTrackConfig::default();
+    config.flags.insert(ImportTrackFlags::FOLDER_ARTWORK);
+    config
+}
+
+fn import_file(file_path: &Path, config: &ImportTrackConfig) -> Track {
+    let mut track = ImportTrack::NewTrack {
+        collected_at: OffsetDateTimeMs::now_utc(),
+    }
+    .with_content(
+        ContentLink {
+            path: Default::default(),
+            rev: None,
+        },
+        "audio/mpeg".parse().unwrap(),
+    );
+    let mut reader: Box<dyn Reader> = Box::new(std::fs::File::open(file_path).unwrap());
+    let mut issues = import_into_track(&mut reader, config, &mut track).unwrap();
+    import_folder_artwork_into_track(file_path, config, &mut track, &mut issues);
+    assert!(issues.is_empty());
+    track
+}
+
+#[test]
+fn find_folder_artwork_file_path_case_insensitive_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Folder.JPG"), []).unwrap();
+    std::fs::write(dir.path().join("front.png"), []).unwrap();
+    assert_eq!(
+        Some(dir.path().join("Folder.JPG")),
+        find_folder_artwork_file_path(dir.path(), &["cover.jpg", "folder.jpg", "front.png"])
+    );
+    assert_eq!(
+        Some(dir.path().join("front.png")),
+        find_folder_artwork_file_path(dir.path(), &["front.png", "folder.jpg"])
+    );
+    assert_eq!(
+        None,
+        find_folder_artwork_file_path(dir.path(), &["cover.jpg"])
+    );
+}
+
+#[test]
+fn import_folder_artwork_without_embedded_artwork() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("track.mp3");
+    std::fs::copy("tests/assets/empty.mp3", &file_path).unwrap();
+    let image_path = dir.path().join("cover.jpg");
+    std::fs::write(
+        &image_path,
+        encode_image(Rgb([255, 0, 0]), ImageFormat::Jpeg),
+    )
+    .unwrap();
+
+    let track = import_file(&file_path, &folder_artwork_config());
+    let Some(Artwork::Linked(LinkedArtwork { uri, image })) = track.media_source.artwork else {
+        panic!("unexpected artwork: {:?}", track.media_source.artwork);
+    };
+    assert_eq!(Url::from_file_path(&image_path).unwrap().as_str(), uri);
+    assert_eq!(ApicType::CoverFront, image.apic_type);
+    assert_eq!("image/jpeg", image.media_type.essence_str());
+
+    // Disabled by default. The artwork of the untagged file remains unknown.
+    let track = import_file(&file_path, &ImportTrackConfig::default());
+    assert_eq!(None, track.media_source.artwork);
+}
+
+#[test]
+fn ignore_folder_artwork_with_embedded_artwork() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("track.mp3");
+    std::fs::copy("tests/assets/empty.mp3", &file_path).unwrap();
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.push_picture(Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(MimeType::Png),
+        None,
+        encode_image(Rgb([0, 0, 255]), ImageFormat::Png),
+    ));
+    tag.save_to_path(&file_path, WriteOptions::default())
+        .unwrap();
+    std::fs::write(
+        dir.path().join("cover.jpg"),
+        encode_image(Rgb([255, 0, 0]), ImageFormat::Jpeg),
+    )
+    .unwrap();
+
+    let track = import_file(&file_path, &folder_artwork_config());
+    let Some(Artwork::Embedded(EmbeddedArtwork { image })) = track.media_source.artwork else {
+        panic!("unexpected artwork: {:?}", track.media_source.artwork);
+    };
+    assert_eq!("image/png", image.media_type.essence_str());
+}
@@ -0,0 +1,216 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Sample-accurate duration computed by reading the whole audio stream of media files.
+
+use std::{
+    fs::File,
+    io::{ErrorKind, Read},
+    path::Path,
+};
+
+use anyhow::anyhow;
+use mime::Mime;
+use symphonia::core::{
+    codecs::CODEC_TYPE_NULL, errors::Error as DecodeError, formats::FormatOptions,
+    io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+use aoide_core::{audio::DurationMs, media::content::ContentMetadata, Track};
+
+use crate::{
+    io::import::{ImportTrackConfig, ImportTrackFlags, Issues},
+    Error, Result,
+};
+
+fn map_decode_error(err: DecodeError) -> Error {
+    match err {
+        DecodeError::IoError(err) => Error::Io(err),
+        err => Error::Other(err.into()),
+    }
+}
+
+/// Read all packets of the audio stream and sum up their frames.
+///
+/// Only the packets are demultiplexed, the audio samples are not decoded.
+/// The encoder delay and padding are subtracted if known, e.g. from the
+/// LAME tag of MPEG audio files.
+pub fn decode_duration(file_path: &Path) -> Result<DurationMs> {
+    let file = File::open(file_path)?;
+    let source_stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            source_stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(map_decode_error)?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| Error::Other(anyhow!("no audio track")))?;
+    let track_id = track.id;
+    let delay_and_padding = u64::from(track.codec_params.delay.unwrap_or(0))
+        + u64::from(track.codec_params.padding.unwrap_or(0));
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .filter(|sample_rate| *sample_rate > 0)
+        .ok_or_else(|| Error::Other(anyhow!("unknown sample rate")))?;
+    let mut frame_count = 0u64;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(err)) if err.kind() == ErrorKind::UnexpectedEof => {
+                // End of stream
+                break;
+            }
+            Err(err) => return Err(map_decode_error(err)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        frame_count += packet.dur;
+    }
+    let frame_count = frame_count.saturating_sub(delay_and_padding);
+    if frame_count == 0 {
+        return Err(Error::Other(anyhow!("no audio frames")));
+    }
+    Ok(DurationMs::new(
+        frame_count as f64 * 1_000.0 / f64::from(sample_rate),
+    ))
+}
+
+/// Maximum number of bytes that are searched for the first MPEG audio
+/// frame after skipping an ID3v2 tag.
+const MAX_MPEG_AUDIO_FRAME_SYNC_SEARCH_LEN: u64 = 64 * 1024;
+
+/// Check if the first MPEG audio frame contains a Xing/Info or VBRI header.
+///
+/// These headers contain the total number of frames, i.e. the reported
+/// duration is accurate and doesn't need to be estimated from the bitrate.
+fn has_mpeg_audio_vbr_header(reader: &mut impl Read) -> std::io::Result<bool> {
+    let mut id3v2_header = [0u8; 10];
+    reader.read_exact(&mut id3v2_header)?;
+    let mut data = Vec::new();
+    if id3v2_header.starts_with(b"ID3") {
+        // Skip the ID3v2 tag with a synchsafe size
+        let tag_size = id3v2_header[6..10]
+            .iter()
+            .fold(0u64, |size, byte| (size << 7) | u64::from(byte & 0x7f));
+        let footer_size = if (id3v2_header[5] & 0x10) == 0 { 0 } else { 10 };
+        std::io::copy(
+            &mut reader.by_ref().take(tag_size + footer_size),
+            &mut std::io::sink(),
+        )?;
+    } else {
+        data.extend_from_slice(&id3v2_header);
+    }
+    reader
+        .by_ref()
+        .take(MAX_MPEG_AUDIO_FRAME_SYNC_SEARCH_LEN)
+        .read_to_end(&mut data)?;
+    let Some(frame_start) = data
+        .windows(2)
+        .position(|sync| sync[0] == 0xff && (sync[1] & 0xe0) == 0xe0)
+    else {
+        return Ok(false);
+    };
+    let frame = &data[frame_start..];
+    if frame.len() < 4 {
+        return Ok(false);
+    }
+    let is_mpeg1 = ((frame[1] >> 3) & 0b11) == 0b11;
+    let is_mono = frame[3] >> 6 == 0b11;
+    // The Xing/Info header follows the side information
+    let side_info_len = match (is_mpeg1, is_mono) {
+        (true, false) => 32,
+        (true, true) | (false, false) => 17,
+        (false, true) => 9,
+    };
+    let xing_offset = 4 + side_info_len;
+    let has_xing_header = frame
+        .get(xing_offset..xing_offset + 4)
+        .is_some_and(|id| id == b"Xing" || id == b"Info");
+    // The VBRI header is always located at a fixed offset
+    let vbri_offset = 4 + 32;
+    let has_vbri_header = frame
+        .get(vbri_offset..vbri_offset + 4)
+        .is_some_and(|id| id == b"VBRI");
+    Ok(has_xing_header || has_vbri_header)
+}
+
+/// Check if the reported duration needs to be verified by decoding.
+///
+/// The duration is suspicious if it is missing or zero. The duration
+/// of MPEG audio files without a Xing/Info or VBRI header is suspicious,
+/// because it is estimated from the bitrate of the first frame.
+#[must_use]
+pub fn is_reported_duration_suspicious(
+    file_path: &Path,
+    content_type: &Mime,
+    duration: Option<DurationMs>,
+) -> bool {
+    if !duration.is_some_and(|duration| duration.value() > 0.0) {
+        return true;
+    }
+    if content_type.type_() != mime::AUDIO || content_type.subtype() != "mpeg" {
+        return false;
+    }
+    match File::open(file_path).and_then(|mut file| has_mpeg_audio_vbr_header(&mut file)) {
+        Ok(has_vbr_header) => !has_vbr_header,
+        Err(err) => {
+            log::warn!(
+                "Failed to read MPEG audio header of {file_path}: {err}",
+                file_path = file_path.display()
+            );
+            false
+        }
+    }
+}
+
+/// Replace a suspicious duration of the track by the decoded duration.
+///
+/// Only applies if [`ImportTrackFlags::DECODED_DURATION`] is enabled,
+/// i.e. should be invoked after importing the file.
+///
+/// See also: [`is_reported_duration_suspicious()`]
+pub fn import_decoded_duration_into_track(
+    file_path: &Path,
+    config: &ImportTrackConfig,
+    track: &mut Track,
+    issues: &mut Issues,
+) {
+    if !config.flags.contains(ImportTrackFlags::DECODED_DURATION) {
+        return;
+    }
+    let content_type = &track.media_source.content.r#type;
+    let ContentMetadata::Audio(audio) = &mut track.media_source.content.metadata;
+    if !is_reported_duration_suspicious(file_path, content_type, audio.duration) {
+        return;
+    }
+    let duration = match decode_duration(file_path) {
+        Ok(duration) => duration,
+        Err(err) => {
+            issues.add_message(format!(
+                "Failed to decode duration of {file_path}: {err}",
+                file_path = file_path.display()
+            ));
+            return;
+        }
+    };
+    if let Some(reported) = audio.duration {
+        log::debug!(
+            "Replacing reported duration {reported} with decoded duration {duration} of {file_path}",
+            file_path = file_path.display()
+        );
+    }
+    audio.duration = Some(duration);
+}
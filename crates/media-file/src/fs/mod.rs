@@ -12,8 +12,13 @@ use url::Url;
 
 use crate::{Error, IoError, Result};
 
+pub mod artwork;
 pub mod digest;
+#[cfg(feature = "decoded-duration")]
+pub mod duration;
 pub mod visit;
+#[cfg(feature = "waveform")]
+pub mod waveform;
 
 pub fn file_path_from_url(url: &Url) -> Result<PathBuf> {
     let url_scheme = url.scheme();
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::{
+    collections::HashSet,
     fs::read_link,
+    io,
     ops::Not as _,
     path::{Path, PathBuf},
     sync::atomic::{AtomicBool, Ordering},
@@ -29,6 +31,45 @@ fn is_hidden_dir_entry(dir_entry: &DirEntry) -> bool {
     false
 }
 
+/// Identifies a directory independent of the path by which it is reached.
+#[cfg(unix)]
+type DirectoryId = (u64, u64);
+
+/// Identifies a directory independent of the path by which it is reached.
+#[cfg(not(unix))]
+type DirectoryId = PathBuf;
+
+/// Device and inode of the directory, following symlinks.
+#[cfg(unix)]
+fn directory_id(dir_entry: &DirEntry) -> Option<DirectoryId> {
+    use std::os::unix::fs::MetadataExt as _;
+    let metadata = dir_entry.metadata().ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// The canonical path of the directory, i.e. with all symlinks resolved.
+#[cfg(not(unix))]
+fn directory_id(dir_entry: &DirEntry) -> Option<DirectoryId> {
+    dir_entry.path().canonicalize().ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The directory has already been visited, e.g. through a symlink.
+    AlreadyVisited,
+
+    /// The entry could not be accessed, e.g. due to missing permissions
+    /// or a broken symlink.
+    Inaccessible(io::ErrorKind),
+}
+
+/// An entry that has been skipped while visiting directories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AfterAncestorFinished {
     Continue,
@@ -70,6 +111,7 @@ pub enum Completion {
 pub struct Outcome {
     pub completion: Completion,
     pub progress: Progress,
+    pub skipped_entries: Vec<SkippedEntry>,
 }
 
 /// A state machine for tracking progress
@@ -78,6 +120,7 @@ pub struct ProgressEvent {
     pub started_at: Instant,
     pub status: Status,
     pub progress: Progress,
+    pub skipped_entries: Vec<SkippedEntry>,
 }
 
 impl ProgressEvent {
@@ -87,6 +130,7 @@ impl ProgressEvent {
             started_at: Instant::now(),
             status: Status::InProgress,
             progress: Default::default(),
+            skipped_entries: Vec::new(),
         }
     }
 
@@ -113,12 +157,18 @@ impl ProgressEvent {
         self.status = Status::Failed;
     }
 
+    fn skip_entry(&mut self, path: PathBuf, reason: SkipReason) {
+        self.progress.entries.skipped += 1;
+        self.skipped_entries.push(SkippedEntry { path, reason });
+    }
+
     #[must_use]
     pub fn finalize(self) -> Outcome {
         let Self {
             started_at: _,
             status,
             progress,
+            skipped_entries,
         } = self;
         let completion = match status {
             Status::InProgress => {
@@ -133,6 +183,7 @@ impl ProgressEvent {
         Outcome {
             completion,
             progress,
+            skipped_entries,
         }
     }
 }
@@ -178,6 +229,11 @@ pub trait DirectoryVisitor {
 
 /// Visit directories and their entries in depth-first order
 ///
+/// Directories that have already been visited through a different path,
+/// e.g. by following symlinks, are skipped. Entries that could not be
+/// accessed are skipped, unless the root directory itself is inaccessible.
+/// All skipped entries are collected in the progress event.
+///
 /// Returns the unfinished progress event that could be finished and
 /// finalized by the caller for reporting, i.e. for sending a final
 /// update after invoking [`ProgressEvent::try_finish()`] and for obtaining
@@ -221,9 +277,11 @@ pub fn visit_directories<
         walkdir = walkdir.max_depth(max_depth);
     }
 
-    for dir_entry in walkdir.into_iter().filter_entry(|e| {
+    let mut visited_directory_ids = HashSet::new();
+    let mut walkdir_iter = walkdir.into_iter().filter_entry(|e| {
         is_hidden_dir_entry(e).not() && !excluded_paths.iter().any(|p| e.path().starts_with(p))
-    }) {
+    });
+    while let Some(dir_entry) = walkdir_iter.next() {
         if abort_flag.load(Ordering::Relaxed) {
             log::debug!("Aborting directory tree traversal");
             progress_event.abort();
@@ -242,11 +300,24 @@ pub fn visit_directories<
                         path = loop_ancestor.display()
                     );
                     // Skip and continue
-                    progress_event.progress.entries.skipped += 1;
+                    let path = err.path().unwrap_or(loop_ancestor).to_path_buf();
+                    progress_event.skip_entry(path, SkipReason::AlreadyVisited);
                     continue;
                 }
                 debug_assert!(err.io_error().is_some());
                 debug_assert!(err.path().is_some());
+                if err.depth() > 0 {
+                    if let (Some(path), Some(io_error)) = (err.path(), err.io_error()) {
+                        log::warn!(
+                            "Skipping inaccessible entry {path}: {io_error}",
+                            path = path.display()
+                        );
+                        // Skip and continue
+                        let reason = SkipReason::Inaccessible(io_error.kind());
+                        progress_event.skip_entry(path.to_path_buf(), reason);
+                        continue;
+                    }
+                }
                 if let Some(path) = err.path() {
                     // The actual path is probably not mentioned in the I/O error
                     // and should be logged here.
@@ -263,6 +334,20 @@ pub fn visit_directories<
             }
         };
 
+        if dir_entry.file_type().is_dir() {
+            if let Some(directory_id) = directory_id(&dir_entry) {
+                if !visited_directory_ids.insert(directory_id) {
+                    log::info!(
+                        "Skipping already visited directory: {path}",
+                        path = dir_entry.path().display()
+                    );
+                    walkdir_iter.skip_current_dir();
+                    progress_event.skip_entry(dir_entry.into_path(), SkipReason::AlreadyVisited);
+                    continue;
+                }
+            }
+        }
+
         // Get the relative path
         let relative_path = if dir_entry.depth() == 0 {
             // Root directory has an empty relative path and no ancestors
@@ -382,3 +467,6 @@ pub fn visit_directories<
     }
     Ok(progress_event)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fs;
+
+use super::*;
+
+struct NoopAncestorVisitor;
+
+impl AncestorVisitor<(), (), Error> for NoopAncestorVisitor {
+    fn visit_dir_entry(&mut self, _context: &mut (), _dir_entry: &DirEntry) -> Result<()> {
+        Ok(())
+    }
+
+    fn finish(self) {}
+}
+
+#[derive(Default)]
+struct FinishedDirectories(Vec<PathBuf>);
+
+impl DirectoryVisitor for FinishedDirectories {
+    type AncestorVisitor = NoopAncestorVisitor;
+    type AncestorFinished = ();
+    type AfterAncestorFinishedError = Error;
+
+    fn new_ancestor_visitor(&mut self, _dir_entry: &DirEntry) -> Self::AncestorVisitor {
+        NoopAncestorVisitor
+    }
+
+    fn after_ancestor_finished(
+        &mut self,
+        path: &Path,
+        (): Self::AncestorFinished,
+    ) -> Result<AfterAncestorFinished> {
+        self.0.push(path.to_path_buf());
+        Ok(AfterAncestorFinished::Continue)
+    }
+}
+
+fn visit_root_dir(root_path: &Path) -> Result<(Outcome, Vec<PathBuf>)> {
+    let mut directory_visitor = FinishedDirectories::default();
+    let mut progress_event = visit_directories(
+        &mut (),
+        &DirPath::from_borrowed(root_path),
+        &[],
+        None,
+        &AtomicBool::new(false),
+        &mut directory_visitor,
+        &mut |_| {},
+    )?;
+    assert!(progress_event.try_finish());
+    let mut finished_dirs = directory_visitor.0;
+    finished_dirs.sort();
+    Ok((progress_event.finalize(), finished_dirs))
+}
+
+#[cfg(unix)]
+#[test]
+fn skip_symlink_loops() -> anyhow::Result<()> {
+    use std::os::unix::fs::symlink;
+
+    let root_dir = tempfile::tempdir()?;
+    let root_path = root_dir.path();
+    fs::create_dir(root_path.join("dir"))?;
+    fs::write(root_path.join("dir/file"), [])?;
+    let loop_path = root_path.join("dir/loop");
+    symlink(root_path, &loop_path)?;
+
+    let (outcome, finished_dirs) = visit_root_dir(root_path)?;
+
+    assert_eq!(Completion::Finished, outcome.completion);
+    assert_eq!(1, outcome.progress.entries.finished);
+    assert_eq!(1, outcome.progress.entries.skipped);
+    assert_eq!(vec![PathBuf::new(), PathBuf::from("dir")], finished_dirs);
+    assert_eq!(
+        vec![SkippedEntry {
+            path: loop_path,
+            reason: SkipReason::AlreadyVisited,
+        }],
+        outcome.skipped_entries
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn skip_already_visited_directories() -> anyhow::Result<()> {
+    use std::os::unix::fs::symlink;
+
+    let root_dir = tempfile::tempdir()?;
+    let root_path = root_dir.path();
+    fs::create_dir(root_path.join("dir"))?;
+    fs::write(root_path.join("dir/file"), [])?;
+    symlink(root_path.join("dir"), root_path.join("link"))?;
+
+    let (outcome, finished_dirs) = visit_root_dir(root_path)?;
+
+    // The traversal order is unspecified, i.e. either the directory
+    // or the symlink is visited first.
+    assert_eq!(Completion::Finished, outcome.completion);
+    assert_eq!(1, outcome.progress.entries.finished);
+    assert_eq!(1, outcome.progress.entries.skipped);
+    assert_eq!(2, finished_dirs.len());
+    let [skipped_entry] = outcome.skipped_entries.as_slice() else {
+        panic!("unexpected skipped entries: {:?}", outcome.skipped_entries);
+    };
+    assert_eq!(SkipReason::AlreadyVisited, skipped_entry.reason);
+    assert!([root_path.join("dir"), root_path.join("link")].contains(&skipped_entry.path));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn skip_inaccessible_directories() -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let root_dir = tempfile::tempdir()?;
+    let root_path = root_dir.path();
+    let locked_path = root_path.join("locked");
+    fs::create_dir(&locked_path)?;
+    fs::write(locked_path.join("file"), [])?;
+    fs::write(root_path.join("file"), [])?;
+    fs::set_permissions(&locked_path, fs::Permissions::from_mode(0o000))?;
+    if fs::read_dir(&locked_path).is_ok() {
+        // Permissions are not enforced, e.g. when running as root
+        fs::set_permissions(&locked_path, fs::Permissions::from_mode(0o755))?;
+        return Ok(());
+    }
+
+    let result = visit_root_dir(root_path);
+    // Restore the permissions for cleaning up the temporary directory
+    fs::set_permissions(&locked_path, fs::Permissions::from_mode(0o755))?;
+    let (outcome, _) = result?;
+
+    assert_eq!(Completion::Finished, outcome.completion);
+    assert_eq!(1, outcome.progress.entries.finished);
+    assert_eq!(
+        vec![SkippedEntry {
+            path: locked_path,
+            reason: SkipReason::Inaccessible(io::ErrorKind::PermissionDenied),
+        }],
+        outcome.skipped_entries
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fail_on_inaccessible_root_directory() {
+    let root_dir = tempfile::tempdir().unwrap();
+    let root_path = root_dir.path().join("missing");
+    assert!(visit_root_dir(&root_path).is_err());
+}
@@ -0,0 +1,203 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Waveform overview computed by decoding the audio stream of media files.
+
+use std::{fs::File, io::ErrorKind, path::Path};
+
+use anyhow::anyhow;
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as DecodeError,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+use aoide_core::{
+    audio::{SampleType, WaveformOverview},
+    media::content::ContentMetadata,
+    Track,
+};
+
+use crate::{
+    io::import::{ImportTrackConfig, ImportTrackFlags, Issues},
+    Error, Result,
+};
+
+/// The maximum number of consecutive frames that share a single peak.
+///
+/// Decoded packets are split into chunks of frames. Otherwise peaks
+/// of large packets would spill into adjacent bins.
+const MAX_FRAMES_PER_PEAK: usize = 64;
+
+/// Peak amplitude of consecutive frames within a decoded packet.
+#[derive(Debug, Clone, Copy)]
+struct FramesPeak {
+    frame_count: u64,
+    amplitude: SampleType,
+}
+
+fn map_decode_error(err: DecodeError) -> Error {
+    match err {
+        DecodeError::IoError(err) => Error::Io(err),
+        err => Error::Other(err.into()),
+    }
+}
+
+fn decode_frames_peaks(file_path: &Path) -> Result<Vec<FramesPeak>> {
+    let file = File::open(file_path)?;
+    let source_stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            source_stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(map_decode_error)?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| Error::Other(anyhow!("no audio track")))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(map_decode_error)?;
+    let mut sample_buf: Option<SampleBuffer<SampleType>> = None;
+    let mut frames_peaks = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(err)) if err.kind() == ErrorKind::UnexpectedEof => {
+                // End of stream
+                break;
+            }
+            Err(err) => return Err(map_decode_error(err)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(DecodeError::DecodeError(err)) => {
+                log::debug!("Skipping undecodable packet: {err}");
+                continue;
+            }
+            Err(err) => return Err(map_decode_error(err)),
+        };
+        if decoded.frames() == 0 {
+            continue;
+        }
+        let channel_count = decoded.spec().channels.count();
+        let required_capacity = decoded.capacity() * channel_count;
+        if sample_buf
+            .as_ref()
+            .is_none_or(|sample_buf| sample_buf.capacity() < required_capacity)
+        {
+            // (Re-)allocate the buffer for the largest packet seen so far
+            sample_buf = Some(SampleBuffer::new(
+                decoded.capacity() as u64,
+                *decoded.spec(),
+            ));
+        }
+        let sample_buf = sample_buf.as_mut().expect("sample buffer");
+        sample_buf.copy_interleaved_ref(decoded);
+        for chunk in sample_buf
+            .samples()
+            .chunks(MAX_FRAMES_PER_PEAK * channel_count)
+        {
+            let amplitude = chunk
+                .iter()
+                .fold(0.0, |peak: SampleType, sample| peak.max(sample.abs()));
+            frames_peaks.push(FramesPeak {
+                frame_count: (chunk.len() / channel_count) as u64,
+                amplitude,
+            });
+        }
+    }
+    Ok(frames_peaks)
+}
+
+/// Distribute the peaks of consecutive frames into equally sized bins.
+///
+/// Frames that span multiple bins contribute to all of them.
+fn bin_frames_peaks(frames_peaks: &[FramesPeak], resolution: usize) -> Vec<SampleType> {
+    let total_frame_count = frames_peaks
+        .iter()
+        .map(|frames_peak| frames_peak.frame_count)
+        .sum::<u64>();
+    let mut bins = vec![0.0; resolution];
+    if total_frame_count == 0 {
+        return bins;
+    }
+    let resolution = resolution as u128;
+    let total_frame_count = u128::from(total_frame_count);
+    let mut start_frame = 0;
+    for FramesPeak {
+        frame_count,
+        amplitude,
+    } in frames_peaks
+    {
+        let end_frame = start_frame + u128::from(*frame_count);
+        let start_bin = (start_frame * resolution / total_frame_count) as usize;
+        let end_bin = (end_frame * resolution).div_ceil(total_frame_count) as usize;
+        for peak in &mut bins[start_bin..end_bin] {
+            *peak = peak.max(*amplitude);
+        }
+        start_frame = end_frame;
+    }
+    bins
+}
+
+/// Decode the audio stream of a file and compute the waveform overview.
+///
+/// The `resolution` determines the number of bins, independent of the
+/// duration of the audio signal.
+pub fn decode_waveform_overview(file_path: &Path, resolution: usize) -> Result<WaveformOverview> {
+    if resolution == 0 {
+        return Err(Error::UnsupportedImportOptions);
+    }
+    let frames_peaks = decode_frames_peaks(file_path)?;
+    if frames_peaks.is_empty() {
+        return Err(Error::Other(anyhow!("no audio frames decoded")));
+    }
+    let peaks = bin_frames_peaks(&frames_peaks, resolution);
+    Ok(WaveformOverview::from_amplitudes(peaks))
+}
+
+/// Compute the waveform overview of the track from the decoded file.
+///
+/// Only applies if [`ImportTrackFlags::WAVEFORM_OVERVIEW`] is enabled,
+/// i.e. should be invoked after importing the file.
+pub fn import_waveform_overview_into_track(
+    file_path: &Path,
+    config: &ImportTrackConfig,
+    track: &mut Track,
+    issues: &mut Issues,
+) {
+    if !config.flags.contains(ImportTrackFlags::WAVEFORM_OVERVIEW) {
+        return;
+    }
+    let waveform_overview =
+        match decode_waveform_overview(file_path, config.waveform_overview_resolution) {
+            Ok(waveform_overview) => waveform_overview,
+            Err(err) => {
+                issues.add_message(format!(
+                    "Failed to compute waveform overview of {file_path}: {err}",
+                    file_path = file_path.display()
+                ));
+                return;
+            }
+        };
+    let ContentMetadata::Audio(audio) = &mut track.media_source.content.metadata;
+    audio.waveform_overview = Some(waveform_overview);
+}
@@ -0,0 +1,216 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Import of remote media sources from HTTP(S) URLs.
+
+use std::{io::Cursor, num::NonZeroU64, time::Duration};
+
+use bytes::Bytes;
+use mime::Mime;
+use reqwest::{
+    header::{HeaderMap, CONTENT_RANGE, CONTENT_TYPE, RANGE},
+    redirect, Client, StatusCode,
+};
+use thiserror::Error;
+use url::Url;
+
+use aoide_core::{media::content::ContentLink, Track};
+
+use crate::{
+    io::import::{import_into_track, ImportTrack, ImportTrackConfig, Issues, Reader},
+    util::guess_mime_from_url,
+};
+
+/// The maximum number of redirects that are followed.
+pub const MAX_REDIRECTS: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unsupported URL scheme '{0}'")]
+    UnsupportedUrlScheme(String),
+
+    #[error("too many redirects")]
+    TooManyRedirects,
+
+    #[error("unexpected HTTP status: {0}")]
+    UnexpectedStatus(StatusCode),
+
+    #[error(transparent)]
+    Request(reqwest::Error),
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_redirect() {
+            return Self::TooManyRedirects;
+        }
+        if let Some(status) = err.status() {
+            return Self::UnexpectedStatus(status);
+        }
+        Self::Request(err)
+    }
+}
+
+/// Connection pool settings of the client.
+///
+/// Clients should be shared and reused for multiple requests to
+/// benefit from reusing connections.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// The maximum number of idle connections that are kept alive per host.
+    ///
+    /// Unlimited if `None`.
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// The timeout after which idle connections are closed.
+    ///
+    /// Uses the default timeout of the client if `None`.
+    pub pool_idle_timeout: Option<Duration>,
+
+    /// Only use HTTP/2 without negotiating the protocol version.
+    ///
+    /// Requests will fail for servers that don't support HTTP/2.
+    pub http2_prior_knowledge: bool,
+}
+
+/// Create a new client that follows up to [`MAX_REDIRECTS`] redirects.
+pub fn new_client(config: &ClientConfig) -> Result<Client, Error> {
+    let ClientConfig {
+        pool_max_idle_per_host,
+        pool_idle_timeout,
+        http2_prior_knowledge,
+    } = config;
+    let mut builder = Client::builder().redirect(redirect::Policy::limited(MAX_REDIRECTS));
+    if let Some(pool_max_idle_per_host) = pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(*pool_max_idle_per_host);
+    }
+    if let Some(pool_idle_timeout) = pool_idle_timeout {
+        builder = builder.pool_idle_timeout(*pool_idle_timeout);
+    }
+    if *http2_prior_knowledge {
+        builder = builder.http2_prior_knowledge();
+    }
+    builder.build().map_err(Into::into)
+}
+
+/// Content that has been fetched from a remote URL.
+#[derive(Debug, Clone)]
+pub struct FetchedContent {
+    /// The final URL after following all redirects.
+    pub url: Url,
+
+    /// The content type from the response headers.
+    pub content_type: Option<Mime>,
+
+    /// The total length of the remote content if known.
+    pub total_len: Option<u64>,
+
+    /// The fetched data, starting at the beginning of the content.
+    pub data: Bytes,
+}
+
+impl FetchedContent {
+    /// Check if only a prefix of the remote content has been fetched.
+    #[must_use]
+    pub fn is_partial(&self) -> bool {
+        self.total_len
+            .is_some_and(|total_len| (self.data.len() as u64) < total_len)
+    }
+
+    #[must_use]
+    pub fn into_reader(self) -> Box<dyn Reader> {
+        Box::new(Cursor::new(self.data))
+    }
+}
+
+/// Parse the total length from a `Content-Range` header, e.g. `bytes 0-1023/4096`.
+fn total_len_from_content_range(headers: &HeaderMap) -> Option<u64> {
+    let content_range = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (_, total_len) = content_range.rsplit_once('/')?;
+    total_len.parse().ok()
+}
+
+/// Fetch the content of an HTTP(S) URL.
+///
+/// Only the first `prefix_len` bytes are requested if specified. Servers
+/// that don't support range requests will respond with the whole content.
+pub async fn fetch_url(
+    client: &Client,
+    url: &Url,
+    prefix_len: Option<NonZeroU64>,
+) -> Result<FetchedContent, Error> {
+    let url_scheme = url.scheme();
+    if !matches!(url_scheme, "http" | "https") {
+        return Err(Error::UnsupportedUrlScheme(url_scheme.to_owned()));
+    }
+    let mut request = client.get(url.clone());
+    if let Some(prefix_len) = prefix_len {
+        let last_byte_pos = prefix_len.get() - 1;
+        request = request.header(RANGE, format!("bytes=0-{last_byte_pos}"));
+    }
+    let response = request.send().await?;
+    let total_len = match response.status() {
+        StatusCode::OK => response.content_length(),
+        StatusCode::PARTIAL_CONTENT => total_len_from_content_range(response.headers()),
+        status => {
+            return Err(Error::UnexpectedStatus(status));
+        }
+    };
+    let url = response.url().clone();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+    let data = response.bytes().await?;
+    Ok(FetchedContent {
+        url,
+        content_type,
+        total_len,
+        data,
+    })
+}
+
+/// Import a track from an HTTP(S) URL.
+///
+/// The content type is guessed from the URL or otherwise taken from the
+/// response headers.
+///
+/// If `prefix_len` is specified then the import is first attempted with
+/// only the header/tag region at the start of the content. The whole
+/// content is fetched if this attempt fails. Audio properties that are
+/// determined at the end of the content, e.g. the duration of Ogg files,
+/// might be inaccurate when importing from a prefix.
+pub async fn import_track_from_url(
+    client: &Client,
+    url: &Url,
+    prefix_len: Option<NonZeroU64>,
+    import_track: ImportTrack,
+    config: &ImportTrackConfig,
+) -> crate::Result<(Track, Issues)> {
+    let mut fetched = fetch_url(client, url, prefix_len).await?;
+    let content_type = match guess_mime_from_url(url) {
+        Ok(content_type) => content_type,
+        Err(err) => fetched.content_type.clone().ok_or(err)?,
+    };
+    let content_link = ContentLink {
+        path: url.to_string().into(),
+        rev: None,
+    };
+    let mut track = import_track.with_content(content_link, content_type);
+    track.media_source.content.size = fetched.total_len;
+    if fetched.is_partial() {
+        let mut partial_track = track.clone();
+        let mut reader = fetched.into_reader();
+        match import_into_track(&mut reader, config, &mut partial_track) {
+            Ok(issues) => return Ok((partial_track, issues)),
+            Err(err) => {
+                log::debug!("Failed to import track from the start of {url}: {err}");
+            }
+        }
+        fetched = fetch_url(client, url, None).await?;
+    }
+    let mut reader = fetched.into_reader();
+    let issues = import_into_track(&mut reader, config, &mut track)?;
+    Ok((track, issues))
+}
@@ -15,6 +15,7 @@ use lofty::{
     config::ParseOptions,
     file::{AudioFile, FileType},
     probe::Probe,
+    tag::TagType,
 };
 use mime::Mime;
 use nonicle::{Canonical, CanonicalizeInto as _};
@@ -31,13 +32,17 @@ use aoide_core::{
     music::{key::KeySignature, tempo::TempoBpm},
     tag::ScoreValue,
     track::{actor::Actor, title::Title, Track},
-    util::clock::{DateOrDateTime, OffsetDateTimeMs},
+    util::{
+        clock::{DateOrDateTime, OffsetDateTimeMs},
+        string::trimmed_non_empty_from,
+    },
     PlainTag, TagFacetId, TagLabel, TagScore, TagsMap,
 };
 
 use crate::{
     fmt::parse_options,
     util::{
+        artwork::ArtworkImageLimits,
         db2lufs,
         digest::MediaDigest,
         parse_key_signature, parse_replay_gain_db, parse_year_tag,
@@ -69,6 +74,42 @@ bitflags! {
         /// Hash cover image
         const METADATA_EMBEDDED_ARTWORK_DIGEST                  = 0b0000_0000_0000_0100;
 
+        /// Only record the presence of the embedded cover image
+        ///
+        /// Skips decoding of the image data for faster imports. Only the
+        /// media type, size, and digest of the raw image data are recorded
+        /// while the image size, color, and thumbnail remain empty.
+        const METADATA_EMBEDDED_ARTWORK_PRESENCE_ONLY           = 0b0000_0000_0000_1000;
+
+        /// Import artwork from an image file in the same directory
+        ///
+        /// Links the first image file in the directory of a file that
+        /// matches one of the configured folder artwork file names if no
+        /// artwork is embedded.
+        const FOLDER_ARTWORK                                    = 0b0000_0000_0001_0000;
+
+        #[cfg(feature = "waveform")]
+        /// Compute a waveform overview by decoding the audio stream
+        ///
+        /// Decoding the whole file is expensive and considerably slows
+        /// down the import.
+        const WAVEFORM_OVERVIEW                                 = 0b0000_0000_0010_0000;
+
+        /// Import chapter markers as cues
+        ///
+        /// Currently only supported for MPEG-4 files. Chapters are
+        /// imported into a dedicated bank and replace all previously
+        /// imported chapter cues.
+        const CHAPTERS                                          = 0b0000_0000_0100_0000;
+
+        #[cfg(feature = "decoded-duration")]
+        /// Compute the duration by reading the whole audio stream
+        ///
+        /// Replaces a missing or estimated duration, e.g. of VBR MPEG
+        /// audio files without a VBR header. Reading the whole file is
+        /// expensive and considerably slows down the import.
+        const DECODED_DURATION                                  = 0b0000_0000_1000_0000;
+
         /// Use Apple GRP1/TIT1 instead of TIT1/TXXX:WORK ID3v2 frames for Content Group
         /// and Work Title respectively.
         ///
@@ -77,6 +118,12 @@ bitflags! {
         /// of GRP1 for storing the grouping property should be updated accordingly.
         const COMPATIBILITY_ID3V2_APPLE_GRP1                    = 0b0000_0001_0000_0000;
 
+        /// Translate numeric ID3v1 genre codes into genre names
+        ///
+        /// Replaces genre codes like "(17)" or "17" with the corresponding
+        /// genre names. Unknown codes are imported verbatim.
+        const COMPATIBILITY_ID3V1_GENRE_CODES                   = 0b0000_0010_0000_0000;
+
         #[cfg(feature = "gigtag")]
         /// Import gigtags from Content Group/Grouping file tag
         const GIGTAGS_CGRP                                      = 0b0001_0000_0000_0000;
@@ -113,18 +160,136 @@ impl Default for ImportTrackFlags {
     }
 }
 
+/// The default album artist name of compilations
+pub const DEFAULT_VARIOUS_ARTISTS_NAME: &str = "Various Artists";
+
+/// The default file names of folder artwork images in order of precedence
+pub const DEFAULT_FOLDER_ARTWORK_FILE_NAMES: &[&str] = &[
+    "cover.jpg",
+    "cover.png",
+    "folder.jpg",
+    "folder.png",
+    "front.jpg",
+    "front.png",
+];
+
+/// The default number of bins of a waveform overview
+pub const DEFAULT_WAVEFORM_OVERVIEW_RESOLUTION: usize = 256;
+
+/// Types of metadata tags that could coexist in a single file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileTagType {
+    Id3v2,
+    Id3v1,
+    Ape,
+    VorbisComments,
+    Mp4Ilst,
+    RiffInfo,
+    AiffText,
+}
+
+impl From<FileTagType> for TagType {
+    fn from(from: FileTagType) -> Self {
+        match from {
+            FileTagType::Id3v2 => Self::Id3v2,
+            FileTagType::Id3v1 => Self::Id3v1,
+            FileTagType::Ape => Self::Ape,
+            FileTagType::VorbisComments => Self::VorbisComments,
+            FileTagType::Mp4Ilst => Self::Mp4Ilst,
+            FileTagType::RiffInfo => Self::RiffInfo,
+            FileTagType::AiffText => Self::AiffText,
+        }
+    }
+}
+
+/// The default precedence of tag types
+///
+/// Only applies to the secondary tags of a file. The primary tag type
+/// of the file format always comes first by default, e.g. Vorbis comments
+/// for FLAC or APE tags for Monkey's Audio files. ID3v1 tags come last,
+/// because their fields are limited in length.
+pub const DEFAULT_TAG_TYPE_PRECEDENCE: &[FileTagType] =
+    &[FileTagType::Id3v2, FileTagType::Ape, FileTagType::Id3v1];
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ImportTrackConfig {
     pub faceted_tag_mapping: FacetedTagMappingConfig,
     pub flags: ImportTrackFlags,
+
+    /// Album artist names that denote a compilation
+    ///
+    /// Names are matched case-insensitive. An album artist with one
+    /// of these names marks the album as a compilation. Compilations
+    /// without an album artist are grouped under the first name.
+    pub various_artists_names: Vec<String>,
+
+    /// File names of folder artwork images
+    ///
+    /// Only considered if [`ImportTrackFlags::FOLDER_ARTWORK`] is set.
+    /// Names are matched case-insensitive and the first existing file
+    /// in the order of this list is selected.
+    pub folder_artwork_file_names: Vec<String>,
+
+    /// Limits for decoded artwork images
+    ///
+    /// Checked against the image header before decoding. Oversized
+    /// images are downscaled after decoding. Not considered
+    /// if [`ImportTrackFlags::METADATA_EMBEDDED_ARTWORK_PRESENCE_ONLY`]
+    /// is set, because then images are not decoded at all.
+    pub artwork_image_limits: ArtworkImageLimits,
+
+    /// Number of bins of the waveform overview
+    ///
+    /// Only considered if `ImportTrackFlags::WAVEFORM_OVERVIEW` is set.
+    pub waveform_overview_resolution: usize,
+
+    /// Precedence of tags if a file contains multiple tags
+    ///
+    /// Each field is imported from the first tag in this order that
+    /// has a value for it. Tags of types that are not listed follow
+    /// after all listed types, starting with the primary tag type
+    /// of the file format.
+    ///
+    /// If `None` the primary tag type of the file format comes first,
+    /// followed by [`DEFAULT_TAG_TYPE_PRECEDENCE`].
+    pub tag_type_precedence: Option<Vec<FileTagType>>,
+}
+
+impl ImportTrackConfig {
+    #[must_use]
+    pub fn is_various_artists_name(&self, name: &str) -> bool {
+        fn lowercase_chars(name: &str) -> impl Iterator<Item = char> + '_ {
+            name.trim().chars().flat_map(char::to_lowercase)
+        }
+        self.various_artists_names
+            .iter()
+            .any(|various_artists_name| {
+                lowercase_chars(various_artists_name).eq(lowercase_chars(name))
+            })
+    }
 }
 
 impl Default for ImportTrackConfig {
     fn default() -> Self {
+        let flags = ImportTrackFlags::all()
+            .difference(ImportTrackFlags::COMPATIBILITY_ID3V2_APPLE_GRP1)
+            .difference(ImportTrackFlags::METADATA_EMBEDDED_ARTWORK_PRESENCE_ONLY)
+            .difference(ImportTrackFlags::FOLDER_ARTWORK);
+        #[cfg(feature = "waveform")]
+        let flags = flags.difference(ImportTrackFlags::WAVEFORM_OVERVIEW);
+        #[cfg(feature = "decoded-duration")]
+        let flags = flags.difference(ImportTrackFlags::DECODED_DURATION);
         Self {
             faceted_tag_mapping: Default::default(),
-            flags: ImportTrackFlags::all()
-                .difference(ImportTrackFlags::COMPATIBILITY_ID3V2_APPLE_GRP1),
+            flags,
+            various_artists_names: vec![DEFAULT_VARIOUS_ARTISTS_NAME.to_owned()],
+            folder_artwork_file_names: DEFAULT_FOLDER_ARTWORK_FILE_NAMES
+                .iter()
+                .map(|&file_name| file_name.to_owned())
+                .collect(),
+            artwork_image_limits: ArtworkImageLimits::UNLIMITED,
+            waveform_overview_resolution: DEFAULT_WAVEFORM_OVERVIEW_RESOLUTION,
+            tag_type_precedence: None,
         }
     }
 }
@@ -147,6 +312,7 @@ impl ImportTrack {
                     metadata: ContentMetadata::Audio(Default::default()),
                     metadata_flags: Default::default(),
                     digest: None,
+                    size: None,
                 };
                 let media_source = Source {
                     collected_at,
@@ -171,10 +337,25 @@ pub trait Reader: Read + Seek + 'static {}
 
 impl<T> Reader for T where T: Read + Seek + 'static {}
 
+/// Category of an [`Issues`] message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IssueKind {
+    /// Artwork could not be imported
+    MissingArtwork,
+
+    /// A date or year could not be parsed
+    MalformedDate,
+
+    /// A tag or frame is not supported or could not be imported
+    UnsupportedTag,
+
+    Other,
+}
+
 /// Recoverable errors and warnings
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Issues {
-    messages: Vec<String>,
+    messages: Vec<(IssueKind, String)>,
 }
 
 impl Issues {
@@ -198,15 +379,25 @@ impl Issues {
     }
 
     pub fn add_message(&mut self, message: impl Into<String>) {
+        self.add_message_of_kind(IssueKind::Other, message);
+    }
+
+    pub fn add_message_of_kind(&mut self, kind: IssueKind, message: impl Into<String>) {
         let message = message.into();
         debug_assert!(!message.trim().is_empty());
-        self.messages.push(message);
+        self.messages.push((kind, message));
+    }
+
+    /// The kinds of all messages in order
+    pub fn kinds(&self) -> impl Iterator<Item = IssueKind> + '_ {
+        let Self { messages } = self;
+        messages.iter().map(|(kind, _)| *kind)
     }
 
     #[must_use]
     pub fn into_messages(self) -> Vec<String> {
         let Self { messages } = self;
-        messages
+        messages.into_iter().map(|(_, message)| message).collect()
     }
 }
 
@@ -242,8 +433,19 @@ pub fn import_into_track(
         }
         FileType::Mp4 => {
             let reader = probe.into_inner();
+            let chapters = config
+                .flags
+                .contains(ImportTrackFlags::CHAPTERS)
+                .then(|| crate::fmt::mp4::import_chapters(&mut importer, reader))
+                .flatten();
             let mp4_file = AudioFile::read_from(reader, parse_options())?;
-            crate::fmt::mp4::import_file_into_track(&mut importer, config, mp4_file, track);
+            crate::fmt::mp4::import_file_into_track(
+                &mut importer,
+                config,
+                mp4_file,
+                chapters,
+                track,
+            );
         }
         FileType::Mpeg => {
             let reader = probe.into_inner();
@@ -405,7 +607,13 @@ pub fn try_import_plain_tag<'a>(
     score_value: impl Into<ScoreValue>,
 ) -> StdResult<PlainTag<'a>, PlainTag<'a>> {
     let label = label.into();
-    let score = TagScore::clamp_from(score_value);
+    let score_value = score_value.into();
+    let Some(score) = TagScore::from_value_clamped(score_value) else {
+        return Err(PlainTag {
+            label,
+            score: TagScore::new_unchecked(score_value),
+        });
+    };
     let plain_tag = PlainTag { label, score };
     if plain_tag.is_valid() {
         Ok(plain_tag)
@@ -469,6 +677,10 @@ impl Importer {
         self.issues.add_message(message);
     }
 
+    pub(crate) fn add_issue_of_kind(&mut self, kind: IssueKind, message: impl Into<String>) {
+        self.issues.add_message_of_kind(kind, message);
+    }
+
     #[must_use]
     pub(crate) fn finish(self) -> Issues {
         let Self { issues } = self;
@@ -483,9 +695,10 @@ impl Importer {
     ) -> Option<DateOrDateTime> {
         let parsed = parse_year_tag(input);
         if parsed.is_none() {
-            self.issues.messages.push(format!(
-                "Failed to parse year tag from input '{input}' in field '{field}'"
-            ));
+            self.add_issue_of_kind(
+                IssueKind::MalformedDate,
+                format!("Failed to parse year tag from input '{input}' in field '{field}'"),
+            );
         }
         parsed
     }
@@ -545,6 +758,12 @@ impl Importer {
                     }
                     return None;
                 }
+                if !tempo_bpm.is_plausible() {
+                    self.add_issue(format!(
+                        "Implausible tempo parsed from input '{input}': {tempo_bpm}"
+                    ));
+                    return None;
+                }
                 log::debug!("Parsed tempo from input '{input}': {tempo_bpm}");
                 let imported = if input.contains('.') {
                     ImportedTempoBpm::Fractional(tempo_bpm)
@@ -642,6 +861,21 @@ impl Importer {
         count
     }
 
+    /// Sanitize a tag label and report if it needed to be modified.
+    fn import_tag_label<'a>(
+        &mut self,
+        label_value: impl Into<Cow<'a, str>>,
+    ) -> Option<TagLabel<'a>> {
+        let label_value = label_value.into();
+        let needs_sanitizing = TagLabel::needs_sanitizing(&label_value);
+        let label = TagLabel::clamp_from(label_value)?;
+        if needs_sanitizing {
+            log::warn!("Sanitized tag label: {label}");
+            self.add_issue(format!("Sanitized tag label: {label}"));
+        }
+        Some(label)
+    }
+
     pub(crate) fn import_plain_tags_from_joined_label_value<'a>(
         &mut self,
         tag_mapping_config: Option<&TagMappingConfig>,
@@ -649,16 +883,13 @@ impl Importer {
         plain_tags: &mut Vec<PlainTag<'a>>,
         joined_label_value: impl Into<Cow<'a, str>>,
     ) -> usize {
-        if let Some(joined_label) = TagLabel::clamp_from(joined_label_value) {
-            debug_assert!(!joined_label.is_empty());
+        // The joined label must not be truncated before splitting it.
+        if let Some(joined_label_value) = trimmed_non_empty_from(joined_label_value) {
             let mut import_count = 0;
             if let Some(tag_mapping_config) = tag_mapping_config {
                 if !tag_mapping_config.label_separator.is_empty() {
-                    for split in joined_label
-                        .as_str()
-                        .split(&tag_mapping_config.label_separator)
-                    {
-                        let label = TagLabel::clamp_from(split).map(TagLabel::into_owned);
+                    for split in joined_label_value.split(&tag_mapping_config.label_separator) {
+                        let label = self.import_tag_label(split).map(TagLabel::into_owned);
                         match try_import_plain_tag(label, *next_score_value) {
                             Ok(plain_tag) => {
                                 plain_tags.push(plain_tag);
@@ -667,9 +898,10 @@ impl Importer {
                                     tag_mapping_config.next_score_value(*next_score_value);
                             }
                             Err(plain_tag) => {
-                                self.add_issue(format!(
-                                    "Failed to import plain tag: {plain_tag:?}"
-                                ));
+                                self.add_issue_of_kind(
+                                    IssueKind::UnsupportedTag,
+                                    format!("Failed to import plain tag: {plain_tag:?}"),
+                                );
                             }
                         }
                     }
@@ -677,6 +909,7 @@ impl Importer {
             }
             if import_count == 0 {
                 // Try to import the whole string as a single tag label
+                let joined_label = self.import_tag_label(joined_label_value);
                 match try_import_plain_tag(joined_label, *next_score_value) {
                     Ok(plain_tag) => {
                         plain_tags.push(plain_tag);
@@ -687,7 +920,10 @@ impl Importer {
                         }
                     }
                     Err(plain_tag) => {
-                        self.add_issue(format!("Failed to import plain tag: {plain_tag:?}"));
+                        self.add_issue_of_kind(
+                            IssueKind::UnsupportedTag,
+                            format!("Failed to import plain tag: {plain_tag:?}"),
+                        );
                     }
                 }
             }
@@ -41,3 +41,82 @@ fn import_replay_gain_invalid() {
         .import_loudness_from_replay_gain("+0.178062")
         .is_none());
 }
+
+#[test]
+fn import_plain_tag_score() {
+    let label = || TagLabel::clamp_from("label");
+    assert_eq!(
+        Ok(TagScore::new_unchecked(0.5)),
+        try_import_plain_tag(label(), 0.5).map(|tag| tag.score)
+    );
+    assert_eq!(
+        Ok(TagScore::MAX),
+        try_import_plain_tag(label(), 1.5).map(|tag| tag.score)
+    );
+    assert!(try_import_plain_tag(label(), ScoreValue::NAN).is_err());
+    assert!(try_import_plain_tag(label(), ScoreValue::INFINITY).is_err());
+    assert!(try_import_plain_tag(label(), ScoreValue::NEG_INFINITY).is_err());
+}
+
+#[test]
+fn import_tempo_bpm_plausible() {
+    let mut importer = Importer::new();
+    assert_eq!(
+        Some(TempoBpm::new(123.5)),
+        importer.import_tempo_bpm(" 123.5 ").map(Into::into)
+    );
+    assert_eq!(
+        Some(TempoBpm::new(240.0)),
+        importer.import_tempo_bpm("240").map(Into::into)
+    );
+    assert!(importer.finish().into_messages().is_empty());
+}
+
+#[test]
+fn import_tempo_bpm_implausible() {
+    let mut importer = Importer::new();
+    // The value 0 is silently ignored
+    assert!(importer.import_tempo_bpm("0").is_none());
+    assert!(importer.finish().into_messages().is_empty());
+
+    let mut importer = Importer::new();
+    assert!(importer.import_tempo_bpm("-120").is_none());
+    assert!(importer.import_tempo_bpm("999").is_none());
+    assert!(importer.import_tempo_bpm("10000").is_none());
+    assert_eq!(3, importer.finish().into_messages().len());
+}
+
+#[test]
+fn import_plain_tags_sanitizes_labels() {
+    let mut importer = Importer::new();
+    let mut next_score_value = PlainTag::DEFAULT_SCORE.value();
+    let mut plain_tags = Vec::new();
+    let truncated_label = "x".repeat(TagLabel::MAX_CHAR_COUNT);
+    let too_long_label = format!("{truncated_label}x");
+    assert_eq!(
+        1,
+        importer.import_plain_tags_from_joined_label_value(
+            None,
+            &mut next_score_value,
+            &mut plain_tags,
+            "Drum\0 &\nBass",
+        )
+    );
+    assert_eq!(
+        1,
+        importer.import_plain_tags_from_joined_label_value(
+            None,
+            &mut next_score_value,
+            &mut plain_tags,
+            too_long_label.as_str(),
+        )
+    );
+    assert_eq!(
+        vec![Some("Drum & Bass"), Some(truncated_label.as_str())],
+        plain_tags
+            .iter()
+            .map(|plain_tag| plain_tag.label.as_ref().map(TagLabel::as_str))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(2, importer.finish().into_messages().len());
+}
@@ -3,6 +3,8 @@
 
 pub mod fmt;
 pub mod fs;
+#[cfg(feature = "http")]
+pub mod http;
 pub mod io;
 pub mod util;
 
@@ -30,6 +32,10 @@ pub enum Error {
     #[error(transparent)]
     Metadata(anyhow::Error),
 
+    #[cfg(feature = "http")]
+    #[error(transparent)]
+    Http(#[from] crate::http::Error),
+
     #[error(transparent)]
     // TODO: Remove implicit conversion from anyhow::Error.
     Other(#[from] anyhow::Error),
@@ -0,0 +1,582 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{convert::TryFrom as _, io::Cursor, ops::Not as _, rc::Rc};
+
+use anyhow::anyhow;
+use aoide_core::{
+    media::artwork::{
+        ApicType, Artwork, ArtworkImage, EmbeddedArtwork, ImageDimension, ImageSize,
+        Thumbnail4x4Rgb8, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH,
+    },
+    util::color::RgbColor,
+};
+use image::{
+    guess_format, load_from_memory, load_from_memory_with_format, ColorType, DynamicImage,
+    GenericImageView, ImageDecoder as _, ImageError, ImageFormat, ImageReader,
+};
+use kmeans_colors::{get_kmeans_hamerly, Kmeans, Sort as _};
+use mime::{Mime, IMAGE_BMP, IMAGE_GIF, IMAGE_JPEG, IMAGE_PNG, IMAGE_STAR};
+use palette::{cast::from_component_slice, FromColor as _, IntoColor as _, Lab, Srgb, Srgba};
+use thiserror::Error;
+
+use super::digest::MediaDigest;
+use crate::Result;
+
+#[derive(Debug, Error)]
+pub enum ArtworkImageError {
+    #[error("unsupported format {0:?}")]
+    UnsupportedFormat(ImageFormat),
+
+    #[error(transparent)]
+    Image(#[from] ImageError),
+
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<ArtworkImageError> for crate::Error {
+    fn from(err: ArtworkImageError) -> crate::Error {
+        match err {
+            ArtworkImageError::UnsupportedFormat(image_format) => Self::Metadata(anyhow::anyhow!(
+                "unsupported artwork image format: {image_format:?}"
+            )),
+            ArtworkImageError::Image(err) => Self::Metadata(err.into()),
+            ArtworkImageError::Other(err) => Self::Metadata(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedArtworkPicture {
+    pub media_type: Mime,
+    pub picture: DynamicImage,
+    pub recoverable_errors: Vec<anyhow::Error>,
+}
+
+pub type LoadArtworkPictureResult = std::result::Result<LoadedArtworkPicture, ArtworkImageError>;
+
+#[allow(clippy::missing_panics_doc)] // Never panics
+pub fn media_type_from_image_format(
+    image_format: ImageFormat,
+) -> std::result::Result<Mime, ArtworkImageError> {
+    let media_type = match image_format {
+        ImageFormat::Jpeg => IMAGE_JPEG,
+        ImageFormat::Png => IMAGE_PNG,
+        ImageFormat::Gif => IMAGE_GIF,
+        ImageFormat::Bmp => IMAGE_BMP,
+        ImageFormat::WebP => "image/webp".parse().expect("valid MIME type"),
+        ImageFormat::Tiff => "image/tiff".parse().expect("valid MIME type"),
+        ImageFormat::Tga => "image/tga".parse().expect("valid MIME type"),
+        unsupported_format => {
+            return Err(ArtworkImageError::UnsupportedFormat(unsupported_format));
+        }
+    };
+    Ok(media_type)
+}
+
+pub fn load_artwork_picture(
+    image_data: &[u8],
+    image_format_hint: Option<ImageFormat>,
+    media_type_hint: Option<&str>,
+) -> LoadArtworkPictureResult {
+    let image_format = image_format_hint.or_else(|| guess_format(image_data).ok());
+    let mut recoverable_errors = Vec::new();
+    let picture = if let Some(image_format) = image_format {
+        load_from_memory_with_format(image_data, image_format)
+    } else {
+        load_from_memory(image_data)
+    }?;
+    let media_type = resolve_media_type(image_format, media_type_hint, &mut recoverable_errors)?;
+    Ok(LoadedArtworkPicture {
+        media_type,
+        picture,
+        recoverable_errors,
+    })
+}
+
+fn resolve_media_type(
+    image_format: Option<ImageFormat>,
+    media_type_hint: Option<&str>,
+    recoverable_errors: &mut Vec<anyhow::Error>,
+) -> std::result::Result<Mime, ArtworkImageError> {
+    let media_type = media_type_hint
+        .and_then(|media_type_hint| {
+            media_type_hint
+                .parse::<Mime>()
+                .map_err(|err| {
+                    recoverable_errors.push(anyhow::anyhow!(
+                        "failed to parse MIME type from '{media_type_hint}': {err}"
+                    ));
+                    err
+                })
+                // Ignore and continue
+                .ok()
+        })
+        .map(Ok)
+        .or_else(|| image_format.map(media_type_from_image_format))
+        .transpose()?
+        .unwrap_or(IMAGE_STAR);
+    Ok(media_type)
+}
+
+#[derive(Debug)]
+pub struct IngestedArtworkImage {
+    pub artwork_image: ArtworkImage,
+
+    /// The decoded picture
+    ///
+    /// Downscaled if the original image exceeded the limits.
+    pub picture: DynamicImage,
+
+    /// Whether `picture` has been downscaled
+    pub downscaled: bool,
+
+    pub recoverable_errors: Vec<anyhow::Error>,
+}
+
+type IngestArtworkImageResult = std::result::Result<IngestedArtworkImage, ArtworkImageError>;
+
+const KMEANS_COLORS_SEED: u64 = 1143;
+
+const KMEANS_COLORS_RUNS: usize = 2;
+
+const KMEANS_COLORS_MAX_ITER: usize = 20;
+
+const KMEANS_COLORS_MAX_COLORS: usize = 8;
+
+const KMEANS_COLORS_MAX_SIZE: u16 = 128;
+
+/// Limits for decoded artwork images
+///
+/// Images that exceed any of these limits are downscaled while
+/// preserving their aspect ratio. The limits are checked against
+/// the image header before decoding. The size of the original
+/// image is still recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArtworkImageLimits {
+    /// Maximum width and height in pixels
+    pub max_dimension: Option<ImageDimension>,
+
+    /// Maximum size of the decoded pixel buffer in bytes
+    pub max_decoded_size: Option<u64>,
+}
+
+impl ArtworkImageLimits {
+    pub const UNLIMITED: Self = Self {
+        max_dimension: None,
+        max_decoded_size: None,
+    };
+
+    /// Bounds for downscaling an image that exceeds the limits
+    ///
+    /// Returns `None` if the image is within the limits.
+    #[must_use]
+    pub fn downscale_bounds(
+        &self,
+        width: u32,
+        height: u32,
+        color_type: ColorType,
+    ) -> Option<(u32, u32)> {
+        let (mut max_width, mut max_height) = (width, height);
+        if let Some(max_dimension) = self.max_dimension {
+            let max_dimension = u32::from(max_dimension.max(1));
+            max_width = max_width.min(max_dimension);
+            max_height = max_height.min(max_dimension);
+        }
+        if let Some(max_decoded_size) = self.max_decoded_size {
+            let bytes_per_pixel = u64::from(color_type.bytes_per_pixel());
+            let decoded_size = u64::from(width) * u64::from(height) * bytes_per_pixel;
+            if decoded_size > max_decoded_size {
+                let scale = (max_decoded_size as f64 / decoded_size as f64).sqrt();
+                let scaled = |dimension: u32| ((f64::from(dimension) * scale) as u32).max(1);
+                max_width = max_width.min(scaled(width));
+                max_height = max_height.min(scaled(height));
+            }
+        }
+        (max_width < width || max_height < height).then_some((max_width, max_height))
+    }
+}
+
+/// Downscale a picture to fit into the given bounds.
+///
+/// The aspect ratio is preserved.
+fn downscale_picture(picture: &DynamicImage, max_width: u32, max_height: u32) -> DynamicImage {
+    picture.resize(max_width, max_height, image::imageops::FilterType::Lanczos3)
+}
+
+/// Determine the dominant color of a picture using k-means clustering.
+fn dominant_color(picture: &DynamicImage) -> Option<RgbColor> {
+    // Convert pixel buffer to Lab for k-means
+    let lab_pixels = match picture.color() {
+        image::ColorType::Rgb8 => from_component_slice::<Srgb<u8>>(picture.as_bytes())
+            .iter()
+            .map(|rgb| rgb.into_format().into_color())
+            .collect::<Vec<Lab>>(),
+        image::ColorType::Rgba8 => from_component_slice::<Srgba<u8>>(picture.as_bytes())
+            .iter()
+            .map(|rgba| rgba.color.into_format().into_color())
+            .collect::<Vec<Lab>>(),
+        _ => {
+            // Format conversion requires a temporary allocation. Could be optimized,
+            // but should only happen rarely.
+            from_component_slice::<Srgb<u8>>(picture.to_rgb8().as_raw())
+                .iter()
+                .map(|rgb| rgb.into_format().into_color())
+                .collect::<Vec<Lab>>()
+        }
+    };
+    lab_pixels
+        .is_empty()
+        .not()
+        .then(|| {
+            let mut result = Kmeans::new();
+            for i in 0..KMEANS_COLORS_RUNS {
+                let run_result = get_kmeans_hamerly(
+                    KMEANS_COLORS_MAX_COLORS,
+                    KMEANS_COLORS_MAX_ITER,
+                    5.0, // default convergence factor for Lab
+                    false,
+                    &lab_pixels,
+                    KMEANS_COLORS_SEED + i as u64,
+                );
+                if run_result.score < result.score {
+                    result = run_result;
+                }
+            }
+            let result = Lab::sort_indexed_colors(&result.centroids, &result.indices);
+            Lab::get_dominant_color(&result).map(|x| {
+                let rgb = Srgb::from_color(x).into_format();
+                RgbColor::rgb(rgb.red, rgb.green, rgb.blue)
+            })
+        })
+        .flatten()
+}
+
+#[allow(clippy::missing_panics_doc)] // Never panics
+pub fn ingest_artwork_image(
+    apic_type: ApicType,
+    image_data: &[u8],
+    image_format_hint: Option<ImageFormat>,
+    media_type_hint: Option<&str>,
+    limits: &ArtworkImageLimits,
+    image_digest: &mut MediaDigest,
+) -> IngestArtworkImageResult {
+    let image_format = image_format_hint.or_else(|| guess_format(image_data).ok());
+    let mut recoverable_errors = Vec::new();
+    let reader = if let Some(image_format) = image_format {
+        ImageReader::with_format(Cursor::new(image_data), image_format)
+    } else {
+        ImageReader::new(Cursor::new(image_data))
+            .with_guessed_format()
+            .map_err(ImageError::IoError)?
+    };
+    let decoder = reader.into_decoder()?;
+    // Inspect the image header before decoding the whole image
+    let (width, height) = decoder.dimensions();
+    let image_size = ImageDimension::try_from(width)
+        .and_then(|width| {
+            ImageDimension::try_from(height).map(|height| ImageSize { width, height })
+        })
+        .map_err(|_| {
+            ArtworkImageError::Other(anyhow!("unsupported image size: {width}x{height}"))
+        })?;
+    let downscale_bounds = limits.downscale_bounds(width, height, decoder.color_type());
+    let picture = DynamicImage::from_decoder(decoder)?;
+    let media_type = resolve_media_type(image_format, media_type_hint, &mut recoverable_errors)?;
+    // The size and digest of the original image are recorded even
+    // if the picture is downscaled
+    let data_size = image_data.len() as u64;
+    let digest = image_digest.digest_content(image_data).finalize_reset();
+    let downscaled = downscale_bounds.is_some();
+    let picture = if let Some((max_width, max_height)) = downscale_bounds {
+        downscale_picture(&picture, max_width, max_height)
+    } else {
+        picture
+    };
+    let picture = Rc::new(picture);
+    let (width, height) = picture.dimensions();
+    let kmeans_colors_max_size = u32::from(KMEANS_COLORS_MAX_SIZE);
+    let scaled_picture = if width > kmeans_colors_max_size || height > kmeans_colors_max_size {
+        Rc::new(downscale_picture(
+            &picture,
+            kmeans_colors_max_size,
+            kmeans_colors_max_size,
+        ))
+    } else {
+        Rc::clone(&picture)
+    };
+    let color = dominant_color(&scaled_picture);
+    // TODO: Calculate thumbnail based on k-means color palette?
+    let thumbnail_picture = scaled_picture.resize_exact(
+        THUMBNAIL_WIDTH.into(),
+        THUMBNAIL_HEIGHT.into(),
+        image::imageops::FilterType::Lanczos3,
+    );
+    let thumbnail = Thumbnail4x4Rgb8::try_from(thumbnail_picture.to_rgb8().into_raw()).ok();
+    debug_assert!(thumbnail.is_some());
+    let artwork_image = ArtworkImage {
+        media_type,
+        apic_type,
+        data_size,
+        image_size: Some(image_size),
+        digest,
+        color,
+        thumbnail,
+    };
+    drop(scaled_picture);
+    debug_assert_eq!(Rc::strong_count(&picture), 1);
+    let picture = Rc::into_inner(picture).expect("infallible");
+    Ok(IngestedArtworkImage {
+        artwork_image,
+        picture,
+        downscaled,
+        recoverable_errors,
+    })
+}
+
+/// Ingest an artwork image without decoding it.
+///
+/// Only records the media type, the size and the digest of the raw image data.
+/// The image format is guessed from the leading magic bytes if no hint is given.
+/// Neither the image dimensions nor the color or thumbnail are available.
+fn ingest_artwork_image_presence_only(
+    apic_type: ApicType,
+    image_data: &[u8],
+    image_format_hint: Option<ImageFormat>,
+    media_type_hint: Option<&str>,
+    image_digest: &mut MediaDigest,
+) -> std::result::Result<(ArtworkImage, Vec<anyhow::Error>), ArtworkImageError> {
+    let image_format = image_format_hint.or_else(|| guess_format(image_data).ok());
+    let mut recoverable_errors = Vec::new();
+    let media_type = resolve_media_type(image_format, media_type_hint, &mut recoverable_errors)?;
+    let data_size = image_data.len() as u64;
+    let digest = image_digest.digest_content(image_data).finalize_reset();
+    let artwork_image = ArtworkImage {
+        media_type,
+        apic_type,
+        data_size,
+        image_size: None,
+        digest,
+        color: None,
+        thumbnail: None,
+    };
+    Ok((artwork_image, recoverable_errors))
+}
+
+fn artwork_issues_from_recoverable_errors(
+    apic_type: ApicType,
+    recoverable_errors: Vec<anyhow::Error>,
+) -> Vec<String> {
+    recoverable_errors
+        .into_iter()
+        .map(|err| {
+            format!("Recoverable error while loading embedded {apic_type:?} artwork image: {err}")
+        })
+        .collect()
+}
+
+fn artwork_with_issue_from_error(err: ArtworkImageError) -> (Artwork, String) {
+    match err {
+        ArtworkImageError::UnsupportedFormat(unsupported_format) => {
+            let issue = format!("Unsupported image format: {unsupported_format:?}");
+            (Artwork::Unsupported, issue)
+        }
+        ArtworkImageError::Image(err) => {
+            let issue = format!("Failed to load embedded artwork image: {err}");
+            (Artwork::Irregular, issue)
+        }
+        ArtworkImageError::Other(err) => {
+            let issue = format!("Failed to load embedded artwork image: {err}");
+            (Artwork::Irregular, issue)
+        }
+    }
+}
+
+pub fn try_ingest_embedded_artwork_image(
+    apic_type: ApicType,
+    image_data: &[u8],
+    image_format_hint: Option<ImageFormat>,
+    media_type_hint: Option<&str>,
+    limits: &ArtworkImageLimits,
+    image_digest: &mut MediaDigest,
+) -> (Artwork, Vec<String>) {
+    ingest_artwork_image(
+        apic_type,
+        image_data,
+        image_format_hint,
+        media_type_hint,
+        limits,
+        image_digest,
+    )
+    .map_or_else(
+        |err| {
+            let (artwork, issue) = artwork_with_issue_from_error(err);
+            (artwork, vec![issue])
+        },
+        |IngestedArtworkImage {
+             artwork_image,
+             picture: _,
+             downscaled: _,
+             recoverable_errors,
+         }| {
+            let issues = artwork_issues_from_recoverable_errors(apic_type, recoverable_errors);
+            let artwork = Artwork::Embedded(EmbeddedArtwork {
+                image: artwork_image,
+            });
+            (artwork, issues)
+        },
+    )
+}
+
+/// Record the presence of an embedded artwork image without decoding it.
+///
+/// Much faster than [`try_ingest_embedded_artwork_image()`] but the resulting
+/// artwork image lacks the image size, color, and thumbnail.
+pub fn try_ingest_embedded_artwork_image_presence_only(
+    apic_type: ApicType,
+    image_data: &[u8],
+    image_format_hint: Option<ImageFormat>,
+    media_type_hint: Option<&str>,
+    image_digest: &mut MediaDigest,
+) -> (Artwork, Vec<String>) {
+    ingest_artwork_image_presence_only(
+        apic_type,
+        image_data,
+        image_format_hint,
+        media_type_hint,
+        image_digest,
+    )
+    .map_or_else(
+        |err| {
+            let (artwork, issue) = artwork_with_issue_from_error(err);
+            (artwork, vec![issue])
+        },
+        |(artwork_image, recoverable_errors)| {
+            let issues = artwork_issues_from_recoverable_errors(apic_type, recoverable_errors);
+            let artwork = Artwork::Embedded(EmbeddedArtwork {
+                image: artwork_image,
+            });
+            (artwork, issues)
+        },
+    )
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum EditEmbeddedArtworkImage {
+    Replace(ReplaceEmbeddedArtworkImage),
+    Remove(RemoveEmbeddedArtworkImage),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EditOtherEmbeddedArtworkImages {
+    Keep,
+    Remove,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplaceEmbeddedArtworkImage {
+    pub artwork_image: ArtworkImage,
+    pub image_data: Vec<u8>,
+    pub others: EditOtherEmbeddedArtworkImages,
+}
+
+impl ReplaceEmbeddedArtworkImage {
+    /// Prepare the replacement of an embedded artwork image.
+    ///
+    /// Stores the downscaled picture instead of the original `image_data`
+    /// if the image exceeded the limits during ingestion. The downscaled
+    /// picture is re-encoded in its original format or as PNG if encoding
+    /// in the original format is not supported.
+    pub fn from_ingested_artwork_image(
+        ingested_artwork_image: IngestedArtworkImage,
+        image_data: Vec<u8>,
+        image_digest: &mut MediaDigest,
+        others: EditOtherEmbeddedArtworkImages,
+    ) -> Result<Self> {
+        let IngestedArtworkImage {
+            mut artwork_image,
+            picture,
+            downscaled,
+            recoverable_errors: _,
+        } = ingested_artwork_image;
+        let image_data = if downscaled {
+            let (media_type, image_data) =
+                encode_downscaled_picture(&picture, &artwork_image.media_type)?;
+            artwork_image.media_type = media_type;
+            artwork_image.data_size = image_data.len() as u64;
+            artwork_image.digest = image_digest.digest_content(&image_data).finalize_reset();
+            image_data
+        } else {
+            image_data
+        };
+        Ok(Self {
+            artwork_image,
+            image_data,
+            others,
+        })
+    }
+
+    pub fn reingest_artwork_image(&self, image_digest: &mut MediaDigest) -> Result<ArtworkImage> {
+        let ArtworkImage {
+            media_type,
+            apic_type,
+            data_size: _,
+            digest: _,
+            image_size: _,
+            color: _,
+            thumbnail: _,
+        } = &self.artwork_image;
+        let image_format_hint = None;
+        let ingested_artwork_image = ingest_artwork_image(
+            *apic_type,
+            &self.image_data,
+            image_format_hint,
+            Some(media_type.essence_str()),
+            &ArtworkImageLimits::UNLIMITED,
+            image_digest,
+        )?;
+        let IngestedArtworkImage {
+            artwork_image,
+            picture: _,
+            downscaled: _,
+            recoverable_errors: _,
+        } = ingested_artwork_image;
+        Ok(artwork_image)
+    }
+}
+
+/// Encode a downscaled picture.
+///
+/// Falls back to PNG if the picture could not be encoded in
+/// the original format.
+fn encode_downscaled_picture(
+    picture: &DynamicImage,
+    media_type: &Mime,
+) -> std::result::Result<(Mime, Vec<u8>), ArtworkImageError> {
+    if let Some(image_format) = ImageFormat::from_mime_type(media_type.essence_str()) {
+        let mut image_data = Vec::new();
+        match picture.write_to(&mut Cursor::new(&mut image_data), image_format) {
+            Ok(()) => return Ok((media_type.clone(), image_data)),
+            Err(err) => {
+                log::debug!(
+                    "Failed to encode downscaled picture as {image_format:?}, falling back to \
+                     PNG: {err}"
+                );
+            }
+        }
+    }
+    let mut image_data = Vec::new();
+    picture.write_to(&mut Cursor::new(&mut image_data), ImageFormat::Png)?;
+    Ok((IMAGE_PNG, image_data))
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoveEmbeddedArtworkImage {
+    pub apic_type: ApicType,
+    pub others: EditOtherEmbeddedArtworkImages,
+}
+
+#[cfg(test)]
+mod tests;
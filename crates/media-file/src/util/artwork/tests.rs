@@ -0,0 +1,238 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::Cursor;
+
+use image::{Rgb, RgbImage};
+
+use super::*;
+
+fn encode_png_image_data() -> Vec<u8> {
+    let picture = RgbImage::from_pixel(8, 8, Rgb([0xff, 0x00, 0x00]));
+    let mut image_data = Vec::new();
+    picture
+        .write_to(&mut Cursor::new(&mut image_data), ImageFormat::Png)
+        .unwrap();
+    image_data
+}
+
+#[test]
+fn ingest_embedded_artwork_image_presence_only_without_decoding() {
+    let image_data = encode_png_image_data();
+    let (artwork, issues) = try_ingest_embedded_artwork_image_presence_only(
+        ApicType::CoverFront,
+        &image_data,
+        None,
+        None,
+        &mut MediaDigest::new(),
+    );
+    assert!(issues.is_empty());
+    let Artwork::Embedded(EmbeddedArtwork { image }) = artwork else {
+        panic!("unexpected artwork: {artwork:?}");
+    };
+    assert_eq!(IMAGE_PNG, image.media_type);
+    assert_eq!(ApicType::CoverFront, image.apic_type);
+    assert_eq!(image_data.len() as u64, image.data_size);
+    assert!(image.digest.is_some());
+    assert!(image.image_size.is_none());
+    assert!(image.color.is_none());
+    assert!(image.thumbnail.is_none());
+}
+
+#[test]
+fn ingest_embedded_artwork_image_with_decoding() {
+    let image_data = encode_png_image_data();
+    let (artwork, issues) = try_ingest_embedded_artwork_image(
+        ApicType::CoverFront,
+        &image_data,
+        None,
+        None,
+        &ArtworkImageLimits::UNLIMITED,
+        &mut MediaDigest::new(),
+    );
+    assert!(issues.is_empty());
+    let Artwork::Embedded(EmbeddedArtwork { image }) = artwork else {
+        panic!("unexpected artwork: {artwork:?}");
+    };
+    assert_eq!(IMAGE_PNG, image.media_type);
+    assert_eq!(image_data.len() as u64, image.data_size);
+    assert_eq!(
+        Some(ImageSize {
+            width: 8,
+            height: 8
+        }),
+        image.image_size
+    );
+    assert!(image.color.is_some());
+    assert!(image.thumbnail.is_some());
+}
+
+#[test]
+fn ingest_embedded_artwork_image_digest_is_independent_of_decoding() {
+    let image_data = encode_png_image_data();
+    let (presence_only_artwork, _) = try_ingest_embedded_artwork_image_presence_only(
+        ApicType::Other,
+        &image_data,
+        None,
+        None,
+        &mut MediaDigest::new(),
+    );
+    let (decoded_artwork, _) = try_ingest_embedded_artwork_image(
+        ApicType::Other,
+        &image_data,
+        None,
+        None,
+        &ArtworkImageLimits::UNLIMITED,
+        &mut MediaDigest::new(),
+    );
+    let (
+        Artwork::Embedded(EmbeddedArtwork {
+            image: presence_only_image,
+        }),
+        Artwork::Embedded(EmbeddedArtwork {
+            image: decoded_image,
+        }),
+    ) = (presence_only_artwork, decoded_artwork)
+    else {
+        panic!("unexpected artwork");
+    };
+    assert_eq!(decoded_image.digest, presence_only_image.digest);
+}
+
+fn encode_png_image_data_with_size(width: u32, height: u32) -> Vec<u8> {
+    let picture = RgbImage::from_pixel(width, height, Rgb([0x00, 0x80, 0xff]));
+    let mut image_data = Vec::new();
+    picture
+        .write_to(&mut Cursor::new(&mut image_data), ImageFormat::Png)
+        .unwrap();
+    image_data
+}
+
+#[test]
+fn ingest_oversized_artwork_image_downscales_picture_to_max_dimension() {
+    let image_data = encode_png_image_data_with_size(64, 32);
+    let limits = ArtworkImageLimits {
+        max_dimension: Some(16),
+        max_decoded_size: None,
+    };
+    let IngestedArtworkImage {
+        artwork_image,
+        picture,
+        downscaled,
+        recoverable_errors,
+    } = ingest_artwork_image(
+        ApicType::CoverFront,
+        &image_data,
+        None,
+        None,
+        &limits,
+        &mut MediaDigest::new(),
+    )
+    .unwrap();
+    assert!(recoverable_errors.is_empty());
+    assert!(downscaled);
+    assert_eq!((16, 8), picture.dimensions());
+    // The original dimensions are recorded
+    assert_eq!(
+        Some(ImageSize {
+            width: 64,
+            height: 32
+        }),
+        artwork_image.image_size
+    );
+    assert_eq!(image_data.len() as u64, artwork_image.data_size);
+    assert!(artwork_image.thumbnail.is_some());
+}
+
+#[test]
+fn ingest_oversized_artwork_image_downscales_picture_to_max_decoded_size() {
+    let image_data = encode_png_image_data_with_size(64, 64);
+    // 3 bytes per RGB pixel: 32 x 32 x 3
+    let limits = ArtworkImageLimits {
+        max_dimension: None,
+        max_decoded_size: Some(3_072),
+    };
+    let IngestedArtworkImage {
+        picture,
+        downscaled,
+        ..
+    } = ingest_artwork_image(
+        ApicType::CoverFront,
+        &image_data,
+        None,
+        None,
+        &limits,
+        &mut MediaDigest::new(),
+    )
+    .unwrap();
+    assert!(downscaled);
+    assert_eq!((32, 32), picture.dimensions());
+    assert!(picture.as_bytes().len() <= 3_072);
+}
+
+#[test]
+fn ingest_artwork_image_within_limits_as_is() {
+    let image_data = encode_png_image_data_with_size(64, 32);
+    let limits = ArtworkImageLimits {
+        max_dimension: Some(64),
+        max_decoded_size: Some(64 * 32 * 3),
+    };
+    let IngestedArtworkImage {
+        artwork_image,
+        picture,
+        downscaled,
+        ..
+    } = ingest_artwork_image(
+        ApicType::CoverFront,
+        &image_data,
+        None,
+        None,
+        &limits,
+        &mut MediaDigest::new(),
+    )
+    .unwrap();
+    assert!(!downscaled);
+    assert_eq!((64, 32), picture.dimensions());
+    let expected_picture = load_from_memory(&image_data).unwrap();
+    assert_eq!(expected_picture.as_bytes(), picture.as_bytes());
+    assert_eq!(
+        Some(ImageSize {
+            width: 64,
+            height: 32
+        }),
+        artwork_image.image_size
+    );
+}
+
+#[test]
+fn replace_embedded_artwork_image_stores_downscaled_picture() {
+    let image_data = encode_png_image_data_with_size(64, 32);
+    let limits = ArtworkImageLimits {
+        max_dimension: Some(16),
+        max_decoded_size: None,
+    };
+    let ingested_artwork_image = ingest_artwork_image(
+        ApicType::CoverFront,
+        &image_data,
+        None,
+        None,
+        &limits,
+        &mut MediaDigest::new(),
+    )
+    .unwrap();
+    let replace = ReplaceEmbeddedArtworkImage::from_ingested_artwork_image(
+        ingested_artwork_image,
+        image_data.clone(),
+        &mut MediaDigest::new(),
+        EditOtherEmbeddedArtworkImages::Keep,
+    )
+    .unwrap();
+    assert_ne!(image_data, replace.image_data);
+    assert_eq!(IMAGE_PNG, replace.artwork_image.media_type);
+    assert_eq!(
+        replace.image_data.len() as u64,
+        replace.artwork_image.data_size
+    );
+    let stored_picture = load_from_memory(&replace.image_data).unwrap();
+    assert_eq!((16, 8), stored_picture.dimensions());
+}
@@ -0,0 +1,329 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Fingerprints of the encoded audio stream that ignore metadata.
+
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+
+use super::digest::MediaDigest;
+use crate::prelude::*;
+
+pub type AudioStreamDigest = [u8; MediaDigest::digest_size()];
+
+const ID3V2_HEADER_LEN: usize = 10;
+const ID3V1_TAG_LEN: u64 = 128;
+const APE_TAG_FOOTER_LEN: usize = 32;
+
+const OGG_PAGE_HEADER_LEN: usize = 27;
+
+/// Compute a digest of only the encoded audio data.
+///
+/// The digest is independent of any embedded metadata, i.e. it remains
+/// unchanged when adding, modifying, or removing tags. It is not affected
+/// by decoding and only matches for identical encoded audio data.
+///
+/// Supported containers are FLAC, Ogg, MP4, AIFF, and WAV. All other
+/// content is considered as a stream of MPEG audio frames that might be
+/// surrounded by ID3v2, ID3v1, or APEv2 tags.
+#[allow(clippy::missing_panics_doc)] // Never panics
+pub fn audio_stream_digest<R: Read + Seek>(reader: &mut R) -> Result<AudioStreamDigest> {
+    let stream_len = reader.seek(SeekFrom::End(0))?;
+    let start = skip_id3v2_tags(reader, stream_len)?;
+    reader.seek(SeekFrom::Start(start))?;
+    let mut magic = [0u8; 12];
+    let magic_len = read_up_to(reader, &mut magic)?;
+    let magic = &magic[..magic_len];
+    reader.seek(SeekFrom::Start(start))?;
+    let mut digest = MediaDigest::new();
+    if magic.starts_with(b"fLaC") {
+        digest_flac_frames(reader, &mut digest, stream_len)?;
+    } else if magic.starts_with(b"OggS") {
+        digest_ogg_pages(reader, &mut digest)?;
+    } else if matches!(magic.get(4..8), Some(b"ftyp")) {
+        digest_mp4_media_data(reader, &mut digest, stream_len)?;
+    } else if magic.starts_with(b"FORM") && matches!(magic.get(8..12), Some(b"AIFF" | b"AIFC")) {
+        reader.seek(SeekFrom::Current(12))?;
+        digest_riff_chunks(reader, &mut digest, Endianness::Big, *b"SSND")?;
+    } else if magic.starts_with(b"RIFF") && matches!(magic.get(8..12), Some(b"WAVE")) {
+        reader.seek(SeekFrom::Current(12))?;
+        digest_riff_chunks(reader, &mut digest, Endianness::Little, *b"data")?;
+    } else {
+        let end = skip_trailing_tags(reader, start, stream_len)?;
+        reader.seek(SeekFrom::Start(start))?;
+        digest_len(reader, &mut digest, end - start)?;
+    }
+    Ok(digest
+        .finalize_reset()
+        .expect("digest of a non-dummy hasher"))
+}
+
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(read_len) => len += read_len,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(len)
+}
+
+/// Feed the next `len` bytes into the digest, stopping at the end of the stream.
+fn digest_len<R: Read>(reader: &mut R, digest: &mut MediaDigest, len: u64) -> Result<()> {
+    let mut buf = [0u8; 8192];
+    let mut remaining_len = len;
+    while remaining_len > 0 {
+        let chunk_len = usize::try_from(remaining_len).map_or(buf.len(), |len| len.min(buf.len()));
+        let read_len = read_up_to(reader, &mut buf[..chunk_len])?;
+        digest.digest_content(&buf[..read_len]);
+        if read_len < chunk_len {
+            break;
+        }
+        remaining_len -= read_len as u64;
+    }
+    Ok(())
+}
+
+fn decode_syncsafe_u32(bytes: [u8; 4]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 7) | u64::from(byte & 0x7f))
+}
+
+/// Skip consecutive ID3v2 tags at the start of the stream.
+///
+/// Returns the position after the last tag.
+fn skip_id3v2_tags<R: Read + Seek>(reader: &mut R, stream_len: u64) -> Result<u64> {
+    let mut pos = 0;
+    loop {
+        reader.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; ID3V2_HEADER_LEN];
+        if read_up_to(reader, &mut header)? < header.len() || !header.starts_with(b"ID3") {
+            return Ok(pos);
+        }
+        let flags = header[5];
+        let size = decode_syncsafe_u32([header[6], header[7], header[8], header[9]]);
+        let footer_len = if flags & 0x10 == 0 {
+            0
+        } else {
+            ID3V2_HEADER_LEN as u64
+        };
+        pos = (pos + ID3V2_HEADER_LEN as u64 + size + footer_len).min(stream_len);
+    }
+}
+
+/// Skip ID3v1 and APEv2 tags at the end of the stream.
+///
+/// Returns the end position of the audio data.
+fn skip_trailing_tags<R: Read + Seek>(reader: &mut R, start: u64, stream_len: u64) -> Result<u64> {
+    let mut end = stream_len;
+    loop {
+        if end >= start + ID3V1_TAG_LEN {
+            reader.seek(SeekFrom::Start(end - ID3V1_TAG_LEN))?;
+            let mut id = [0u8; 3];
+            read_up_to(reader, &mut id)?;
+            if &id == b"TAG" {
+                end -= ID3V1_TAG_LEN;
+                continue;
+            }
+        }
+        if end >= start + APE_TAG_FOOTER_LEN as u64 {
+            reader.seek(SeekFrom::Start(end - APE_TAG_FOOTER_LEN as u64))?;
+            let mut footer = [0u8; APE_TAG_FOOTER_LEN];
+            read_up_to(reader, &mut footer)?;
+            if footer.starts_with(b"APETAGEX") {
+                // The size includes the footer but not the optional header
+                let size = u64::from(u32::from_le_bytes([
+                    footer[12], footer[13], footer[14], footer[15],
+                ]));
+                let flags = u32::from_le_bytes([footer[20], footer[21], footer[22], footer[23]]);
+                let header_len = if flags & 0x8000_0000 == 0 {
+                    0
+                } else {
+                    APE_TAG_FOOTER_LEN as u64
+                };
+                end = end.saturating_sub(size + header_len).max(start);
+                continue;
+            }
+        }
+        return Ok(end);
+    }
+}
+
+/// Skip all metadata blocks and digest the audio frames.
+fn digest_flac_frames<R: Read + Seek>(
+    reader: &mut R,
+    digest: &mut MediaDigest,
+    stream_len: u64,
+) -> Result<()> {
+    // Skip the stream marker
+    let mut pos = reader.seek(SeekFrom::Current(4))?;
+    loop {
+        let mut block_header = [0u8; 4];
+        if read_up_to(reader, &mut block_header)? < block_header.len() {
+            return Ok(());
+        }
+        let is_last = block_header[0] & 0x80 != 0;
+        let block_len = u64::from(u32::from_be_bytes([
+            0,
+            block_header[1],
+            block_header[2],
+            block_header[3],
+        ]));
+        pos = reader.seek(SeekFrom::Start(pos + 4 + block_len))?;
+        if is_last {
+            break;
+        }
+    }
+    let end = skip_trailing_tags(reader, pos, stream_len)?;
+    reader.seek(SeekFrom::Start(pos))?;
+    digest_len(reader, digest, end.saturating_sub(pos))
+}
+
+/// Number of header packets at the start of an Ogg stream.
+///
+/// Includes the identification header that is the first packet.
+fn ogg_header_packet_count(identification_header: &[u8]) -> usize {
+    if identification_header.starts_with(b"\x01vorbis") {
+        // Identification, comment, and setup header
+        3
+    } else if identification_header.starts_with(b"OpusHead") {
+        // Identification and comment header
+        2
+    } else if identification_header.starts_with(b"\x7fFLAC") {
+        // Mapping header followed by the number of metadata blocks
+        identification_header.get(7..9).map_or(1, |count| {
+            1 + usize::from(u16::from_be_bytes([count[0], count[1]]))
+        })
+    } else {
+        1
+    }
+}
+
+/// Digest the payload of all audio packets.
+///
+/// The header packets of Vorbis, Opus, and FLAC streams with the comments
+/// are skipped. These packets may span multiple pages and the last header
+/// packet may share a page with the first audio packets. Packets are
+/// counted by their lacing values, i.e. a segment shorter than 255 bytes
+/// finishes a packet.
+fn digest_ogg_pages<R: Read + Seek>(reader: &mut R, digest: &mut MediaDigest) -> Result<()> {
+    // Unknown until the identification header has been read
+    let mut remaining_header_packets = None;
+    loop {
+        let mut header = [0u8; OGG_PAGE_HEADER_LEN];
+        if read_up_to(reader, &mut header)? < header.len() || !header.starts_with(b"OggS") {
+            return Ok(());
+        }
+        let mut segment_table = vec![0u8; usize::from(header[26])];
+        reader.read_exact(&mut segment_table)?;
+        let payload_len: u64 = segment_table
+            .iter()
+            .map(|&segment_len| u64::from(segment_len))
+            .sum();
+        let payload_pos = reader.stream_position()?;
+        let mut remaining = if let Some(remaining) = remaining_header_packets {
+            remaining
+        } else {
+            let mut identification_header = [0u8; 9];
+            let read_len = usize::try_from(payload_len)
+                .map_or(identification_header.len(), |len| {
+                    len.min(identification_header.len())
+                });
+            let read_len = read_up_to(reader, &mut identification_header[..read_len])?;
+            ogg_header_packet_count(&identification_header[..read_len])
+        };
+        let mut header_packets_len = 0;
+        for &segment_len in &segment_table {
+            if remaining == 0 {
+                break;
+            }
+            header_packets_len += u64::from(segment_len);
+            if segment_len < 255 {
+                remaining -= 1;
+            }
+        }
+        remaining_header_packets = Some(remaining);
+        reader.seek(SeekFrom::Start(payload_pos + header_packets_len))?;
+        digest_len(reader, digest, payload_len - header_packets_len)?;
+        reader.seek(SeekFrom::Start(payload_pos + payload_len))?;
+    }
+}
+
+/// Digest the contents of all top-level `mdat` atoms.
+///
+/// The metadata is stored in the `moov` atom.
+fn digest_mp4_media_data<R: Read + Seek>(
+    reader: &mut R,
+    digest: &mut MediaDigest,
+    stream_len: u64,
+) -> Result<()> {
+    let mut pos = reader.stream_position()?;
+    while pos < stream_len {
+        let mut header = [0u8; 8];
+        if read_up_to(reader, &mut header)? < header.len() {
+            break;
+        }
+        let atom_type = &header[4..8];
+        let (header_len, atom_len) =
+            match u32::from_be_bytes(header[..4].try_into().expect("4 bytes")) {
+                0 => (8, stream_len - pos),
+                1 => {
+                    let mut largesize = [0u8; 8];
+                    reader.read_exact(&mut largesize)?;
+                    (16, u64::from_be_bytes(largesize))
+                }
+                size => (8, u64::from(size)),
+            };
+        if atom_len < header_len {
+            // Invalid atom
+            break;
+        }
+        let Some(next_pos) = pos.checked_add(atom_len) else {
+            // Invalid atom length
+            break;
+        };
+        if atom_type == b"mdat" {
+            digest_len(reader, digest, atom_len - header_len)?;
+        }
+        pos = reader.seek(SeekFrom::Start(next_pos))?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Endianness {
+    Big,
+    Little,
+}
+
+/// Digest the contents of all chunks with the given id in an AIFF or WAV file.
+fn digest_riff_chunks<R: Read + Seek>(
+    reader: &mut R,
+    digest: &mut MediaDigest,
+    endianness: Endianness,
+    audio_chunk_id: [u8; 4],
+) -> Result<()> {
+    loop {
+        let mut header = [0u8; 8];
+        if read_up_to(reader, &mut header)? < header.len() {
+            return Ok(());
+        }
+        let size_bytes = header[4..8].try_into().expect("4 bytes");
+        let chunk_len = u64::from(match endianness {
+            Endianness::Big => u32::from_be_bytes(size_bytes),
+            Endianness::Little => u32::from_le_bytes(size_bytes),
+        });
+        let chunk_pos = reader.stream_position()?;
+        if header[..4] == audio_chunk_id {
+            digest_len(reader, digest, chunk_len)?;
+        }
+        // Chunks are padded to an even size
+        reader.seek(SeekFrom::Start(chunk_pos + chunk_len + (chunk_len & 1)))?;
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::Cursor;
+
+use lofty::{
+    config::WriteOptions,
+    tag::{ItemKey, Tag, TagExt as _, TagType},
+};
+
+use super::*;
+
+fn digest(data: &[u8]) -> AudioStreamDigest {
+    audio_stream_digest(&mut Cursor::new(data)).unwrap()
+}
+
+fn audio_data(seed: u8) -> Vec<u8> {
+    (0..=255u8).map(|i| i.wrapping_mul(seed)).collect()
+}
+
+fn id3v2_tag(payload_len: usize) -> Vec<u8> {
+    let size = u32::try_from(payload_len).unwrap();
+    let mut tag = b"ID3\x04\x00\x00".to_vec();
+    tag.extend([21, 14, 7, 0].map(|shift| u8::try_from((size >> shift) & 0x7f).unwrap()));
+    tag.resize(tag.len() + payload_len, 0);
+    tag
+}
+
+fn id3v1_tag() -> Vec<u8> {
+    let mut tag = b"TAG".to_vec();
+    tag.resize(128, b' ');
+    tag
+}
+
+fn apev2_tag(items_len: usize) -> Vec<u8> {
+    let mut tag = vec![0; items_len];
+    tag.extend(b"APETAGEX");
+    tag.extend(2000u32.to_le_bytes());
+    tag.extend(u32::try_from(items_len + 32).unwrap().to_le_bytes());
+    tag.extend(1u32.to_le_bytes());
+    tag.extend(0u32.to_le_bytes());
+    tag.extend([0; 8]);
+    tag
+}
+
+fn mpeg_stream(seed: u8) -> Vec<u8> {
+    let mut stream = vec![0xff, 0xfb, 0x90, 0x00];
+    stream.extend(audio_data(seed));
+    stream
+}
+
+#[test]
+fn mpeg_digest_ignores_surrounding_tags() {
+    let audio = mpeg_stream(3);
+    let expected = digest(&audio);
+    assert_eq!(expected, digest(&[id3v2_tag(100), audio.clone()].concat()));
+    assert_eq!(
+        expected,
+        digest(&[id3v2_tag(10), id3v2_tag(50), audio.clone(), id3v1_tag()].concat())
+    );
+    assert_eq!(
+        expected,
+        digest(&[id3v2_tag(10), audio.clone(), apev2_tag(40), id3v1_tag()].concat())
+    );
+    assert_ne!(expected, digest(&mpeg_stream(5)));
+}
+
+fn flac_metadata_block(block_type: u8, is_last: bool, len: usize) -> Vec<u8> {
+    let mut block = vec![if is_last {
+        block_type | 0x80
+    } else {
+        block_type
+    }];
+    block.extend(&u32::try_from(len).unwrap().to_be_bytes()[1..]);
+    block.resize(block.len() + len, block_type);
+    block
+}
+
+fn flac_stream(seed: u8, comment_len: usize) -> Vec<u8> {
+    [
+        b"fLaC".to_vec(),
+        flac_metadata_block(0, false, 34),
+        flac_metadata_block(4, true, comment_len),
+        audio_data(seed),
+    ]
+    .concat()
+}
+
+#[test]
+fn flac_digest_ignores_metadata_blocks() {
+    let expected = digest(&flac_stream(3, 10));
+    assert_eq!(expected, digest(&flac_stream(3, 200)));
+    assert_eq!(
+        expected,
+        digest(&[id3v2_tag(20), flac_stream(3, 0)].concat())
+    );
+    assert_ne!(expected, digest(&flac_stream(5, 10)));
+    // Only the audio frames are considered
+    assert_ne!(expected, digest(&mpeg_stream(3)));
+}
+
+/// Split the packets into pages with at most `max_segments` lacing values.
+///
+/// Pages on which no packet finishes have a granule position of -1.
+fn ogg_pages(granule_position: u64, packets: &[&[u8]], max_segments: usize) -> Vec<u8> {
+    let mut segments = Vec::new();
+    for packet in packets {
+        let mut chunks = packet.chunks(255).collect::<Vec<_>>();
+        if packet.len() % 255 == 0 {
+            // A packet of a multiple of 255 bytes is terminated by an empty segment
+            chunks.push(&[]);
+        }
+        segments.extend(chunks);
+    }
+    let mut pages = Vec::new();
+    for page_segments in segments.chunks(max_segments) {
+        let finishes_packet = page_segments.iter().any(|segment| segment.len() < 255);
+        let granule_position = if finishes_packet {
+            granule_position
+        } else {
+            u64::MAX
+        };
+        pages.extend(b"OggS\x00\x00");
+        pages.extend(granule_position.to_le_bytes());
+        // Serial number, sequence number, and checksum
+        pages.extend([0; 12]);
+        pages.push(u8::try_from(page_segments.len()).unwrap());
+        pages.extend(
+            page_segments
+                .iter()
+                .map(|segment| u8::try_from(segment.len()).unwrap()),
+        );
+        pages.extend(page_segments.concat());
+    }
+    pages
+}
+
+fn ogg_page(granule_position: u64, payload: &[u8]) -> Vec<u8> {
+    ogg_pages(granule_position, &[payload], 255)
+}
+
+fn ogg_stream(seed: u8, comment_len: usize) -> Vec<u8> {
+    [
+        ogg_page(0, b"\x01vorbis"),
+        // The comment and setup headers are stored on pages with
+        // at most 4 segments, i.e. long comments span multiple pages.
+        ogg_pages(0, &[&vec![b'c'; comment_len], b"\x05vorbis"], 4),
+        ogg_page(1024, &audio_data(seed)),
+        ogg_page(2048, &audio_data(seed.wrapping_add(1))),
+    ]
+    .concat()
+}
+
+#[test]
+fn ogg_digest_ignores_header_pages() {
+    let expected = digest(&ogg_stream(3, 10));
+    assert_eq!(expected, digest(&ogg_stream(3, 600)));
+    assert_ne!(expected, digest(&ogg_stream(5, 10)));
+}
+
+#[test]
+fn ogg_digest_ignores_comment_packet_spanning_multiple_pages() {
+    let expected = digest(&ogg_stream(3, 10));
+    // The comment packet spans 3 pages, the first 2 with a granule position of -1
+    assert_eq!(expected, digest(&ogg_stream(3, 3000)));
+    assert_eq!(expected, digest(&ogg_stream(3, 255 * 8)));
+}
+
+fn opus_stream(seed: u8, comment_len: usize) -> Vec<u8> {
+    let mut comment_header = b"OpusTags".to_vec();
+    comment_header.resize(comment_len, b'c');
+    [
+        ogg_page(0, b"OpusHead\x01\x02"),
+        // The first audio packets share a page with the end of the comment header
+        ogg_pages(
+            960,
+            &[
+                &comment_header,
+                &audio_data(seed),
+                &audio_data(seed.wrapping_add(1)),
+            ],
+            4,
+        ),
+    ]
+    .concat()
+}
+
+#[test]
+fn opus_digest_ignores_comment_packet_spanning_multiple_pages() {
+    let expected = digest(&opus_stream(3, 10));
+    assert_eq!(expected, digest(&opus_stream(3, 2000)));
+    assert_ne!(expected, digest(&opus_stream(5, 10)));
+}
+
+fn mp4_atom(atom_type: [u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut atom = u32::try_from(payload.len() + 8)
+        .unwrap()
+        .to_be_bytes()
+        .to_vec();
+    atom.extend(atom_type);
+    atom.extend(payload);
+    atom
+}
+
+#[test]
+fn mp4_digest_ignores_metadata_atoms() {
+    let ftyp = mp4_atom(*b"ftyp", b"M4A \x00\x00\x00\x00");
+    let moov = |udta_len| mp4_atom(*b"moov", &mp4_atom(*b"udta", &vec![0; udta_len]));
+    let expected = digest(&[ftyp.clone(), moov(10), mp4_atom(*b"mdat", &audio_data(3))].concat());
+    assert_eq!(
+        expected,
+        digest(&[ftyp.clone(), mp4_atom(*b"mdat", &audio_data(3)), moov(100)].concat())
+    );
+    assert_ne!(
+        expected,
+        digest(&[ftyp, moov(10), mp4_atom(*b"mdat", &audio_data(5))].concat())
+    );
+}
+
+#[test]
+fn mp4_digest_stops_at_atom_with_overflowing_length() {
+    let ftyp = mp4_atom(*b"ftyp", b"M4A \x00\x00\x00\x00");
+    let mdat = mp4_atom(*b"mdat", &audio_data(3));
+    let mut malformed = 1u32.to_be_bytes().to_vec();
+    malformed.extend(b"free");
+    malformed.extend(u64::MAX.to_be_bytes());
+    assert_eq!(
+        digest(&[ftyp.clone(), mdat.clone()].concat()),
+        digest(&[ftyp, mdat, malformed].concat())
+    );
+}
+
+fn riff_stream(
+    endianness: Endianness,
+    form: [u8; 8],
+    audio_chunk_id: [u8; 4],
+    seed: u8,
+    tag_len: usize,
+) -> Vec<u8> {
+    let encode_len = |len: usize| {
+        let len = u32::try_from(len).unwrap();
+        match endianness {
+            Endianness::Big => len.to_be_bytes(),
+            Endianness::Little => len.to_le_bytes(),
+        }
+    };
+    let mut chunks = Vec::new();
+    for (chunk_id, data) in [
+        (*b"ID3 ", vec![0; tag_len]),
+        (audio_chunk_id, audio_data(seed)),
+    ] {
+        chunks.extend(chunk_id);
+        chunks.extend(encode_len(data.len()));
+        chunks.extend(&data);
+        if data.len() % 2 == 1 {
+            chunks.push(0);
+        }
+    }
+    let mut stream = form[..4].to_vec();
+    stream.extend(encode_len(chunks.len() + 4));
+    stream.extend(&form[4..]);
+    stream.extend(chunks);
+    stream
+}
+
+#[test]
+fn aiff_and_wav_digest_ignores_metadata_chunks() {
+    for (endianness, form, audio_chunk_id) in [
+        (Endianness::Big, *b"FORMAIFF", *b"SSND"),
+        (Endianness::Little, *b"RIFFWAVE", *b"data"),
+    ] {
+        let expected = digest(&riff_stream(endianness, form, audio_chunk_id, 3, 10));
+        assert_eq!(
+            expected,
+            digest(&riff_stream(endianness, form, audio_chunk_id, 3, 11))
+        );
+        assert_ne!(
+            expected,
+            digest(&riff_stream(endianness, form, audio_chunk_id, 5, 10))
+        );
+    }
+}
+
+#[test]
+fn mp3_file_digest_is_unchanged_by_retagging() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::copy("tests/assets/empty.mp3", file.path()).unwrap();
+    let file_digest =
+        || audio_stream_digest(&mut std::fs::File::open(file.path()).unwrap()).unwrap();
+    let expected = file_digest();
+
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.insert_text(ItemKey::TrackTitle, "Title".to_owned());
+    tag.save_to_path(file.path(), WriteOptions::default())
+        .unwrap();
+    assert_ne!(
+        std::fs::read("tests/assets/empty.mp3").unwrap(),
+        std::fs::read(file.path()).unwrap()
+    );
+    assert_eq!(expected, file_digest());
+
+    tag.remove_from_path(file.path()).unwrap();
+    assert_eq!(expected, file_digest());
+}
@@ -0,0 +1,277 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::borrow::Cow;
+
+/// Canonical genre names of the numeric ID3v1 genre codes
+///
+/// The index in this table is the numeric code. Codes 0..=79 are
+/// defined by the ID3v1 specification, the codes 80..=191 are the
+/// extensions introduced by Winamp.
+pub const ID3V1_GENRES: [&str; 192] = [
+    "Blues",
+    "Classic Rock",
+    "Country",
+    "Dance",
+    "Disco",
+    "Funk",
+    "Grunge",
+    "Hip-Hop",
+    "Jazz",
+    "Metal",
+    "New Age",
+    "Oldies",
+    "Other",
+    "Pop",
+    "R&B",
+    "Rap",
+    "Reggae",
+    "Rock",
+    "Techno",
+    "Industrial",
+    "Alternative",
+    "Ska",
+    "Death Metal",
+    "Pranks",
+    "Soundtrack",
+    "Euro-Techno",
+    "Ambient",
+    "Trip-Hop",
+    "Vocal",
+    "Jazz+Funk",
+    "Fusion",
+    "Trance",
+    "Classical",
+    "Instrumental",
+    "Acid",
+    "House",
+    "Game",
+    "Sound Clip",
+    "Gospel",
+    "Noise",
+    "AlternRock",
+    "Bass",
+    "Soul",
+    "Punk",
+    "Space",
+    "Meditative",
+    "Instrumental Pop",
+    "Instrumental Rock",
+    "Ethnic",
+    "Gothic",
+    "Darkwave",
+    "Techno-Industrial",
+    "Electronic",
+    "Pop-Folk",
+    "Eurodance",
+    "Dream",
+    "Southern Rock",
+    "Comedy",
+    "Cult",
+    "Gangsta",
+    "Top 40",
+    "Christian Rap",
+    "Pop/Funk",
+    "Jungle",
+    "Native American",
+    "Cabaret",
+    "New Wave",
+    "Psychadelic",
+    "Rave",
+    "Showtunes",
+    "Trailer",
+    "Lo-Fi",
+    "Tribal",
+    "Acid Punk",
+    "Acid Jazz",
+    "Polka",
+    "Retro",
+    "Musical",
+    "Rock & Roll",
+    "Hard Rock",
+    // Winamp extensions
+    "Folk",
+    "Folk-Rock",
+    "National Folk",
+    "Swing",
+    "Fast Fusion",
+    "Bebop",
+    "Latin",
+    "Revival",
+    "Celtic",
+    "Bluegrass",
+    "Avantgarde",
+    "Gothic Rock",
+    "Progressive Rock",
+    "Psychedelic Rock",
+    "Symphonic Rock",
+    "Slow Rock",
+    "Big Band",
+    "Chorus",
+    "Easy Listening",
+    "Acoustic",
+    "Humour",
+    "Speech",
+    "Chanson",
+    "Opera",
+    "Chamber Music",
+    "Sonata",
+    "Symphony",
+    "Booty Bass",
+    "Primus",
+    "Porn Groove",
+    "Satire",
+    "Slow Jam",
+    "Club",
+    "Tango",
+    "Samba",
+    "Folklore",
+    "Ballad",
+    "Power Ballad",
+    "Rhythmic Soul",
+    "Freestyle",
+    "Duet",
+    "Punk Rock",
+    "Drum Solo",
+    "A capella",
+    "Euro-House",
+    "Dance Hall",
+    "Goa",
+    "Drum & Bass",
+    "Club-House",
+    "Hardcore",
+    "Terror",
+    "Indie",
+    "BritPop",
+    "Afro-Punk",
+    "Polsk Punk",
+    "Beat",
+    "Christian Gangsta Rap",
+    "Heavy Metal",
+    "Black Metal",
+    "Crossover",
+    "Contemporary Christian",
+    "Christian Rock",
+    "Merengue",
+    "Salsa",
+    "Thrash Metal",
+    "Anime",
+    "JPop",
+    "Synthpop",
+    "Abstract",
+    "Art Rock",
+    "Baroque",
+    "Bhangra",
+    "Big Beat",
+    "Breakbeat",
+    "Chillout",
+    "Downtempo",
+    "Dub",
+    "EBM",
+    "Eclectic",
+    "Electro",
+    "Electroclash",
+    "Emo",
+    "Experimental",
+    "Garage",
+    "Global",
+    "IDM",
+    "Illbient",
+    "Industro-Goth",
+    "Jam Band",
+    "Krautrock",
+    "Leftfield",
+    "Lounge",
+    "Math Rock",
+    "New Romantic",
+    "Nu-Breakz",
+    "Post-Punk",
+    "Post-Rock",
+    "Psytrance",
+    "Shoegaze",
+    "Space Rock",
+    "Trop Rock",
+    "World Music",
+    "Neoclassical",
+    "Audiobook",
+    "Audio Theatre",
+    "Neue Deutsche Welle",
+    "Podcast",
+    "Indie Rock",
+    "G-Funk",
+    "Dubstep",
+    "Garage Rock",
+    "Psybient",
+];
+
+/// Look up the canonical name of a numeric ID3v1 genre code
+#[must_use]
+pub fn id3v1_genre_name(code: &str) -> Option<&'static str> {
+    if code.is_empty() || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    code.parse::<usize>()
+        .ok()
+        .and_then(|index| ID3V1_GENRES.get(index))
+        .copied()
+}
+
+fn translate_id3v2_genre_reference(code: &str) -> Option<&'static str> {
+    match code {
+        "RX" => Some("Remix"),
+        "CR" => Some("Cover"),
+        _ => id3v1_genre_name(code),
+    }
+}
+
+/// Translate numeric ID3v1 genre codes into genre names
+///
+/// Accepts both a plain numeric code like `"17"` and the ID3v2.3
+/// syntax with one or more parenthesized references followed by an
+/// optional refinement like `"(17)"` or `"(17)Progressive"`. Each
+/// reference and the refinement are returned as separate genres.
+/// A leading `"(("` escapes a refinement that starts with `'('`.
+///
+/// A refinement that only repeats the name of the preceding reference
+/// is omitted. Unknown codes are preserved verbatim. If the input contains no
+/// references at all it is returned unmodified as a single genre.
+#[must_use]
+pub fn translate_id3v1_genre_codes(input: &str) -> Vec<Cow<'_, str>> {
+    let trimmed = input.trim();
+    if let Some(name) = id3v1_genre_name(trimmed) {
+        return vec![Cow::Borrowed(name)];
+    }
+    let mut genres = Vec::with_capacity(2);
+    let mut rest = trimmed;
+    while rest.starts_with('(') && !rest.starts_with("((") {
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let reference = &rest[..=end];
+        let code = &reference[1..end];
+        genres.push(Cow::Borrowed(
+            translate_id3v2_genre_reference(code).unwrap_or(reference),
+        ));
+        rest = &rest[end + 1..];
+    }
+    if genres.is_empty() {
+        return vec![Cow::Borrowed(input)];
+    }
+    let refinement = rest
+        .strip_prefix('(')
+        .filter(|refinement| refinement.starts_with('('))
+        .unwrap_or(rest)
+        .trim();
+    // Redundant refinements that repeat the preceding genre are omitted
+    if !refinement.is_empty()
+        && !genres
+            .last()
+            .is_some_and(|genre| genre.eq_ignore_ascii_case(refinement))
+    {
+        genres.push(Cow::Borrowed(refinement));
+    }
+    genres
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use super::*;
+
+#[test]
+fn id3v1_genres_table() {
+    assert_eq!("Blues", ID3V1_GENRES[0]);
+    assert_eq!("Rock", ID3V1_GENRES[17]);
+    assert_eq!("Hard Rock", ID3V1_GENRES[79]);
+    // Winamp extensions
+    assert_eq!("Folk", ID3V1_GENRES[80]);
+    assert_eq!("Psybient", ID3V1_GENRES[191]);
+}
+
+#[test]
+fn translate_id3v1_genre_code() {
+    assert_eq!(vec!["Rock"], translate_id3v1_genre_codes("(17)"));
+    assert_eq!(vec!["Rock"], translate_id3v1_genre_codes("17"));
+    assert_eq!(vec!["Psybient"], translate_id3v1_genre_codes(" (191) "));
+}
+
+#[test]
+fn translate_id3v1_genre_code_with_refinement() {
+    assert_eq!(
+        vec!["Rock", "Progressive"],
+        translate_id3v1_genre_codes("(17)Progressive")
+    );
+    assert_eq!(
+        vec!["Techno-Industrial", "Electronic"],
+        translate_id3v1_genre_codes("(51)(52)")
+    );
+    assert_eq!(vec!["Rock"], translate_id3v1_genre_codes("(17)Rock"));
+    assert_eq!(
+        vec!["Remix", "(Escaped)"],
+        translate_id3v1_genre_codes("(RX)((Escaped)")
+    );
+}
+
+#[test]
+fn translate_id3v1_genre_codes_preserves_unknown_codes() {
+    assert_eq!(vec!["(255)"], translate_id3v1_genre_codes("(255)"));
+    assert_eq!(vec!["192"], translate_id3v1_genre_codes("192"));
+    assert_eq!(
+        vec!["(255)", "Progressive"],
+        translate_id3v1_genre_codes("(255)Progressive")
+    );
+}
+
+#[test]
+fn translate_id3v1_genre_codes_preserves_plain_genres() {
+    assert_eq!(vec!["Rock"], translate_id3v1_genre_codes("Rock"));
+    assert_eq!(vec!["(Rock"], translate_id3v1_genre_codes("(Rock"));
+    assert_eq!(vec!["((Rock)"], translate_id3v1_genre_codes("((Rock)"));
+    assert_eq!(
+        vec!["Drum & Bass (Liquid)"],
+        translate_id3v1_genre_codes("Drum & Bass (Liquid)")
+    );
+}
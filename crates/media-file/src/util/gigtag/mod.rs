@@ -143,8 +143,8 @@ fn try_import_tag(tag: &Tag) -> Option<(FacetKey<'_>, PlainTag<'_>)> {
             }
             // Skip non-aoide tag if property value fails to parse
             let score_value = prop.value().parse::<ScoreValue>().ok()?;
-            let score = Score::clamp_from(score_value);
-            // Skip non-aoide tag if property value fails is not a valid score value
+            // Skip non-aoide tag if property value is not a valid score value
+            let score = Score::from_value_clamped(score_value)?;
             #[allow(clippy::float_cmp)]
             if score_value != score.value() {
                 return None;
@@ -206,8 +206,9 @@ fn reencode_roundtrip() {
     assert!(retain);
     assert_eq!(2, num_imported);
     assert_eq!(tags_map.total_count(), num_imported);
+    // The line break has been replaced by a space when sanitizing the label
     assert_eq!(
-        "Some text\n ?name=value#TagWithUnsupportedProperties",
+        "Some text  ?name=value#TagWithUnsupportedProperties",
         encoded_label.as_str()
     );
 
@@ -19,6 +19,7 @@ use time::{
     },
     OffsetDateTime, PrimitiveDateTime,
 };
+use url::Url;
 
 use aoide_core::{
     audio::signal::LoudnessLufs,
@@ -31,6 +32,7 @@ use aoide_core::{
             is_valid_summary_individual_actor_name, Actor, Actors, Kind as ActorKind,
             Role as ActorRole,
         },
+        index::Index,
         title::{Kind as TitleKind, Title},
     },
     util::{
@@ -42,7 +44,9 @@ use aoide_core::{
 use crate::{io::import::Importer, prelude::*};
 
 pub mod artwork;
+pub mod audio_stream;
 pub mod digest;
+pub mod genre;
 pub mod tag;
 
 #[cfg(feature = "gigtag")]
@@ -92,6 +96,18 @@ pub fn guess_mime_from_file_path(path: impl AsRef<Path>) -> Result<Mime> {
         )))
 }
 
+/// Guess the content type from the file extension in the path of an URL.
+pub fn guess_mime_from_url(url: &Url) -> Result<Mime> {
+    let file_name = url
+        .path_segments()
+        .and_then(Iterator::last)
+        .unwrap_or_default();
+    let Some((_, file_ext)) = file_name.rsplit_once('.') else {
+        return Err(Error::UnknownContentType(format!("URL \"{url}\"")));
+    };
+    guess_mime_from_file_ext(file_ext)
+}
+
 /// Determines the next kind and adjusts the previous kind.
 ///
 /// The `actors` slice must contain continues chunks of the same role,
@@ -327,12 +343,18 @@ pub(crate) fn format_tempo_bpm(
             let formatted = format_parseable_value(&mut value);
             debug_assert!({
                 // Verify the formatted float value by re-parsing it.
-                let mut importer = Importer::new();
-                debug_assert_eq!(
-                    Some(*tempo_bpm),
-                    importer.import_tempo_bpm(&formatted).map(Into::into)
-                );
-                debug_assert!(importer.finish().into_messages().is_empty());
+                // Implausible values are not imported and only need
+                // to be parseable.
+                if tempo_bpm.is_plausible() {
+                    let mut importer = Importer::new();
+                    debug_assert_eq!(
+                        Some(*tempo_bpm),
+                        importer.import_tempo_bpm(&formatted).map(Into::into)
+                    );
+                    debug_assert!(importer.finish().into_messages().is_empty());
+                } else {
+                    debug_assert_eq!(Ok(tempo_bpm.value()), formatted.parse());
+                }
                 true
             });
             if value.fract() == 0.0 {
@@ -344,6 +366,40 @@ pub(crate) fn format_tempo_bpm(
     }
 }
 
+/// Parse a single index number or total.
+///
+/// Leading zeros are ignored. Zero, malformed, and out-of-range
+/// numbers are rejected.
+#[must_use]
+pub(crate) fn parse_index_number(input: &str) -> Option<u16> {
+    let input = trim_readable(input);
+    if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let number = input.trim_start_matches('0').parse::<u16>().ok()?;
+    debug_assert_eq!(Index::MIN_NUMBER, Index::MIN_TOTAL);
+    (number >= Index::MIN_NUMBER).then_some(number)
+}
+
+/// Parse an index with an optional total, e.g. "3" or "3/12".
+///
+/// Both parts are parsed independently, i.e. a malformed number
+/// does not affect the total and vice versa.
+#[must_use]
+pub(crate) fn parse_index(input: &str) -> Index {
+    if let Some((number, total)) = input.split_once('/') {
+        Index {
+            number: parse_index_number(number),
+            total: parse_index_number(total),
+        }
+    } else {
+        Index {
+            number: parse_index_number(input),
+            total: None,
+        }
+    }
+}
+
 #[must_use]
 pub(crate) fn parse_key_signature(input: &str) -> Option<KeySignature> {
     let input = trim_readable(input);
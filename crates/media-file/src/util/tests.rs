@@ -5,7 +5,10 @@
 
 use nonicle::CanonicalizeInto as _;
 
-use aoide_core::track::actor::Actors;
+use aoide_core::{
+    track::{actor::Actors, index::Index},
+    util::clock::DatePrecision,
+};
 
 use super::*;
 
@@ -58,6 +61,89 @@ fn parse_year_tag_valid() {
     );
 }
 
+#[test]
+fn parse_year_tag_precision() {
+    for (input, precision) in [
+        ("2021", DatePrecision::Year),
+        ("2021-06", DatePrecision::YearMonth),
+        ("2021-06-15", DatePrecision::YearMonthDay),
+    ] {
+        let Some(DateOrDateTime::Date(date)) = parse_year_tag(input) else {
+            panic!("failed to parse {input}");
+        };
+        assert_eq!(precision, date.precision());
+        // Round-trip without fabricating unknown components
+        assert_eq!(input, date.to_string());
+    }
+}
+
+#[test]
+fn parse_index_number_only() {
+    assert_eq!(
+        Index {
+            number: Some(3),
+            total: None,
+        },
+        parse_index("3")
+    );
+    assert_eq!(
+        Index {
+            number: Some(3),
+            total: None,
+        },
+        parse_index(" 003 ")
+    );
+}
+
+#[test]
+fn parse_index_number_and_total() {
+    assert_eq!(
+        Index {
+            number: Some(3),
+            total: Some(12),
+        },
+        parse_index("3/12")
+    );
+    assert_eq!(
+        Index {
+            number: Some(3),
+            total: Some(12),
+        },
+        parse_index("03 / 012")
+    );
+    assert_eq!(
+        Index {
+            number: None,
+            total: Some(12),
+        },
+        parse_index("/12")
+    );
+}
+
+#[test]
+fn parse_index_separate_total() {
+    assert_eq!(Some(12), parse_index_number("12"));
+    assert_eq!(Some(12), parse_index_number("0012"));
+}
+
+#[test]
+fn parse_index_garbage() {
+    assert_eq!(Index::default(), parse_index(""));
+    assert_eq!(Index::default(), parse_index("abc"));
+    assert_eq!(Index::default(), parse_index("0"));
+    assert_eq!(Index::default(), parse_index("-3"));
+    assert_eq!(Index::default(), parse_index("3a/b12"));
+    assert_eq!(Index::default(), parse_index("99999/99999"));
+    assert_eq!(
+        Index {
+            number: Some(3),
+            total: None,
+        },
+        parse_index("3/twelve")
+    );
+    assert_eq!(None, parse_index_number("1.5"));
+}
+
 #[test]
 fn trim_readable_should_ignore_whitespace_and_control_characters() {
     assert!(trim_readable(" \t \n ").is_empty());
@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{fs, path::Path};
+
+use aoide_core::{
+    audio::DurationMs,
+    media::{
+        content::{AudioContentMetadata, ContentLink, ContentMetadata},
+        Content, Source,
+    },
+    util::clock::OffsetDateTimeMs,
+    Track,
+};
+use aoide_media_file::{
+    fs::duration::{decode_duration, import_decoded_duration_into_track},
+    io::import::{ImportTrackConfig, ImportTrackFlags, Issues},
+};
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// MPEG-1 Layer III
+const SAMPLES_PER_FRAME: u32 = 1_152;
+
+/// Bitrate indexes and bitrates (kbps) of MPEG-1 Layer III frames
+const BITRATES: [(u8, u32); 2] = [(9, 128), (14, 320)];
+
+const FRAME_COUNT: u32 = 200;
+
+/// A bogus duration as reported by the container
+const REPORTED_DURATION_MS: f64 = 1.0;
+
+/// Offset of the Xing/Info header in mono MPEG-1 Layer III frames
+const XING_HEADER_OFFSET: usize = 4 + 17;
+
+/// Write a mono MPEG-1 Layer III stream with silent frames of varying
+/// bitrate and neither ID3 tags nor a Xing/VBRI header.
+fn write_headerless_vbr_mp3_fixture(file_path: &Path) {
+    fs::write(file_path, new_vbr_mp3_frames()).unwrap();
+}
+
+/// Write a mono MPEG-1 Layer III stream with silent frames of varying
+/// bitrate and a Xing header in the first frame.
+fn write_vbr_mp3_fixture_with_xing_header(file_path: &Path) {
+    let mut mp3 = new_vbr_mp3_frames();
+    mp3[XING_HEADER_OFFSET..XING_HEADER_OFFSET + 4].copy_from_slice(b"Xing");
+    fs::write(file_path, mp3).unwrap();
+}
+
+fn new_vbr_mp3_frames() -> Vec<u8> {
+    let mut mp3 = Vec::new();
+    for i in 0..FRAME_COUNT {
+        let (bitrate_index, bitrate_kbps) = BITRATES[(i % 2) as usize];
+        let frame_len = 144 * bitrate_kbps * 1_000 / SAMPLE_RATE;
+        // Sync word, MPEG-1, Layer III, no CRC
+        mp3.extend_from_slice(&[0xff, 0xfb]);
+        // Bitrate, 44.1 kHz, no padding
+        mp3.push(bitrate_index << 4);
+        // Mono
+        mp3.push(0b1100_0000);
+        // Empty side info and main data
+        mp3.resize(mp3.len() + frame_len as usize - 4, 0);
+    }
+    mp3
+}
+
+fn expected_duration_ms() -> f64 {
+    f64::from(FRAME_COUNT * SAMPLES_PER_FRAME) * 1_000.0 / f64::from(SAMPLE_RATE)
+}
+
+fn new_track_with_reported_duration() -> Track {
+    Track::new_from_media_source(Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: Content {
+            link: ContentLink {
+                path: "fixture.mp3".into(),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            digest: None,
+            size: None,
+            metadata: ContentMetadata::Audio(AudioContentMetadata {
+                duration: Some(DurationMs::new(REPORTED_DURATION_MS)),
+                ..Default::default()
+            }),
+            metadata_flags: Default::default(),
+        },
+        artwork: None,
+    })
+}
+
+fn track_duration_ms(track: &Track) -> Option<f64> {
+    let ContentMetadata::Audio(audio) = &track.media_source.content.metadata;
+    audio.duration.map(DurationMs::value)
+}
+
+#[test]
+fn decode_duration_of_headerless_vbr_mp3() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("fixture.mp3");
+    write_headerless_vbr_mp3_fixture(&file_path);
+
+    let duration = decode_duration(&file_path).unwrap();
+    assert!((duration.value() - expected_duration_ms()).abs() < 1.0);
+}
+
+#[test]
+fn replace_reported_duration_only_if_enabled() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("fixture.mp3");
+    write_headerless_vbr_mp3_fixture(&file_path);
+
+    let mut config = ImportTrackConfig::default();
+    assert!(!config.flags.contains(ImportTrackFlags::DECODED_DURATION));
+    let mut track = new_track_with_reported_duration();
+    let mut issues = Issues::default();
+    import_decoded_duration_into_track(&file_path, &config, &mut track, &mut issues);
+    assert!(issues.is_empty());
+    assert_eq!(Some(REPORTED_DURATION_MS), track_duration_ms(&track));
+
+    config.flags |= ImportTrackFlags::DECODED_DURATION;
+    import_decoded_duration_into_track(&file_path, &config, &mut track, &mut issues);
+    assert!(issues.is_empty());
+    let decoded_duration_ms = track_duration_ms(&track).unwrap();
+    assert!((decoded_duration_ms - expected_duration_ms()).abs() < 1.0);
+}
+
+#[test]
+fn keep_reported_duration_of_mp3_with_xing_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("fixture.mp3");
+    write_vbr_mp3_fixture_with_xing_header(&file_path);
+
+    let mut config = ImportTrackConfig::default();
+    config.flags |= ImportTrackFlags::DECODED_DURATION;
+    let mut track = new_track_with_reported_duration();
+    let mut issues = Issues::default();
+    import_decoded_duration_into_track(&file_path, &config, &mut track, &mut issues);
+    assert!(issues.is_empty());
+    assert_eq!(Some(REPORTED_DURATION_MS), track_duration_ms(&track));
+}
@@ -0,0 +1,315 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    net::SocketAddr,
+    num::NonZeroU64,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use lofty::{
+    config::WriteOptions,
+    tag::{ItemKey, Tag, TagExt as _, TagType},
+};
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use url::Url;
+use warp::{
+    http::{Response, StatusCode, Uri},
+    Filter as _,
+};
+
+use aoide_core::util::clock::OffsetDateTimeMs;
+use aoide_media_file::{
+    http::{fetch_url, import_track_from_url, new_client, ClientConfig, Error as HttpError},
+    io::import::ImportTrack,
+    Error,
+};
+
+const TITLE: &str = "Remote Title";
+
+fn tagged_mp3_data() -> Bytes {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::copy("tests/assets/empty.mp3", file.path()).unwrap();
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.insert_text(ItemKey::TrackTitle, TITLE.to_owned());
+    tag.save_to_path(file.path(), WriteOptions::default())
+        .unwrap();
+    std::fs::read(file.path()).unwrap().into()
+}
+
+/// Respond with the requested range of the data or with the whole data.
+fn range_response(data: &Bytes, range: Option<&str>, content_type: &str) -> Response<Bytes> {
+    let last_byte_pos = range
+        .and_then(|range| range.strip_prefix("bytes=0-"))
+        .and_then(|last_byte_pos| last_byte_pos.parse::<usize>().ok());
+    let Some(last_byte_pos) = last_byte_pos else {
+        return Response::builder()
+            .header("content-type", content_type)
+            .body(data.clone())
+            .unwrap();
+    };
+    let end = (last_byte_pos + 1).min(data.len());
+    Response::builder()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header("content-type", content_type)
+        .header(
+            "content-range",
+            format!("bytes 0-{last}/{total}", last = end - 1, total = data.len()),
+        )
+        .body(data.slice(..end))
+        .unwrap()
+}
+
+struct MockServer {
+    addr: SocketAddr,
+    range_requests: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    fn url(&self, path: &str) -> Url {
+        format!("http://{addr}{path}", addr = self.addr)
+            .parse()
+            .unwrap()
+    }
+}
+
+fn spawn_mock_server(data: Bytes) -> MockServer {
+    let range_requests = Arc::new(AtomicUsize::new(0));
+    let track = {
+        let data = data.clone();
+        let range_requests = Arc::clone(&range_requests);
+        warp::path!("music" / "track.mp3")
+            .and(warp::header::optional::<String>("range"))
+            .map(move |range: Option<String>| {
+                if range.is_some() {
+                    range_requests.fetch_add(1, Ordering::Relaxed);
+                }
+                range_response(&data, range.as_deref(), "application/octet-stream")
+            })
+    };
+    // Without a file extension the content type is taken from the response.
+    let stream = warp::path!("stream").map(move || range_response(&data, None, "audio/mpeg"));
+    let moved = warp::path!("moved.mp3")
+        .map(|| warp::redirect::temporary(Uri::from_static("/music/track.mp3")));
+    let redirect_loop =
+        warp::path!("loop.mp3").map(|| warp::redirect::temporary(Uri::from_static("/loop.mp3")));
+    let routes = track.or(stream).or(moved).or(redirect_loop);
+    let (addr, server) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    MockServer {
+        addr,
+        range_requests,
+    }
+}
+
+fn new_import_track() -> ImportTrack {
+    ImportTrack::NewTrack {
+        collected_at: OffsetDateTimeMs::now_utc(),
+    }
+}
+
+#[tokio::test]
+async fn import_track_from_whole_content() {
+    let server = spawn_mock_server(tagged_mp3_data());
+    let client = new_client(&Default::default()).unwrap();
+    let url = server.url("/music/track.mp3");
+    let (track, _issues) =
+        import_track_from_url(&client, &url, None, new_import_track(), &Default::default())
+            .await
+            .unwrap();
+    assert_eq!(Some(TITLE), track.track_title());
+    assert_eq!(
+        "audio/mpeg",
+        track.media_source.content.r#type.essence_str()
+    );
+    assert_eq!(url.as_str(), track.media_source.content.link.path.as_str());
+    assert_eq!(0, server.range_requests.load(Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn import_track_from_content_prefix() {
+    let data = tagged_mp3_data();
+    let server = spawn_mock_server(data.clone());
+    let client = new_client(&Default::default()).unwrap();
+    let url = server.url("/music/track.mp3");
+
+    let prefix_len = NonZeroU64::new(1024).unwrap();
+    let fetched = fetch_url(&client, &url, Some(prefix_len)).await.unwrap();
+    assert!(fetched.is_partial());
+    assert_eq!(Some(data.len() as u64), fetched.total_len);
+    assert_eq!(data.slice(..1024), fetched.data);
+
+    let (track, _issues) = import_track_from_url(
+        &client,
+        &url,
+        Some(prefix_len),
+        new_import_track(),
+        &Default::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(Some(TITLE), track.track_title());
+    assert_eq!(2, server.range_requests.load(Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn import_track_with_content_type_from_response() {
+    let server = spawn_mock_server(tagged_mp3_data());
+    let client = new_client(&Default::default()).unwrap();
+    let (track, _issues) = import_track_from_url(
+        &client,
+        &server.url("/stream"),
+        None,
+        new_import_track(),
+        &Default::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(Some(TITLE), track.track_title());
+    assert_eq!(
+        "audio/mpeg",
+        track.media_source.content.r#type.essence_str()
+    );
+}
+
+#[tokio::test]
+async fn fetch_url_follows_redirects() {
+    let data = tagged_mp3_data();
+    let server = spawn_mock_server(data.clone());
+    let client = new_client(&Default::default()).unwrap();
+    let fetched = fetch_url(&client, &server.url("/moved.mp3"), None)
+        .await
+        .unwrap();
+    assert_eq!(server.url("/music/track.mp3"), fetched.url);
+    assert!(!fetched.is_partial());
+    assert_eq!(data, fetched.data);
+}
+
+#[tokio::test]
+async fn fetch_url_fails_on_redirect_loop() {
+    let server = spawn_mock_server(tagged_mp3_data());
+    let client = new_client(&Default::default()).unwrap();
+    let err = fetch_url(&client, &server.url("/loop.mp3"), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, HttpError::TooManyRedirects));
+}
+
+#[tokio::test]
+async fn import_track_fails_if_not_found() {
+    let server = spawn_mock_server(tagged_mp3_data());
+    let client = new_client(&Default::default()).unwrap();
+    let err = import_track_from_url(
+        &client,
+        &server.url("/missing.mp3"),
+        None,
+        new_import_track(),
+        &Default::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Http(HttpError::UnexpectedStatus(status)) if status.as_u16() == 404
+    ));
+}
+
+#[tokio::test]
+async fn fetch_url_rejects_unsupported_scheme() {
+    let client = new_client(&Default::default()).unwrap();
+    let url = "file:///home/test/track.mp3".parse().unwrap();
+    let err = fetch_url(&client, &url, None).await.unwrap_err();
+    assert!(matches!(err, HttpError::UnsupportedUrlScheme(scheme) if scheme == "file"));
+}
+
+/// Respond to all requests on a keep-alive connection with the same data.
+async fn serve_connection(stream: TcpStream, data: Bytes) {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        // Skip the request line and headers
+        loop {
+            line.clear();
+            if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                // Connection closed
+                return;
+            }
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let header = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: audio/mpeg\r\ncontent-length: {len}\r\n\r\n",
+            len = data.len()
+        );
+        let stream = stream.get_mut();
+        if stream.write_all(header.as_bytes()).await.is_err()
+            || stream.write_all(&data).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Spawn a server that counts the number of accepted connections.
+async fn spawn_connection_counting_server(data: Bytes) -> (Url, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let url = format!(
+        "http://{addr}/track.mp3",
+        addr = listener.local_addr().unwrap()
+    )
+    .parse()
+    .unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    {
+        let connections = Arc::clone(&connections);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.fetch_add(1, Ordering::Relaxed);
+                tokio::spawn(serve_connection(stream, data.clone()));
+            }
+        });
+    }
+    (url, connections)
+}
+
+#[tokio::test]
+async fn client_reuses_pooled_connections() {
+    let data = tagged_mp3_data();
+    let (url, connections) = spawn_connection_counting_server(data.clone()).await;
+    let client = new_client(&ClientConfig {
+        pool_max_idle_per_host: Some(1),
+        pool_idle_timeout: Some(Duration::from_secs(60)),
+        http2_prior_knowledge: false,
+    })
+    .unwrap();
+    for _ in 0..3 {
+        let fetched = fetch_url(&client, &url, None).await.unwrap();
+        assert_eq!(data, fetched.data);
+    }
+    assert_eq!(1, connections.load(Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn client_without_idle_connections_reconnects() {
+    let data = tagged_mp3_data();
+    let (url, connections) = spawn_connection_counting_server(data.clone()).await;
+    let client = new_client(&ClientConfig {
+        pool_max_idle_per_host: Some(0),
+        ..Default::default()
+    })
+    .unwrap();
+    for _ in 0..3 {
+        let fetched = fetch_url(&client, &url, None).await.unwrap();
+        assert_eq!(data, fetched.data);
+    }
+    assert_eq!(3, connections.load(Ordering::Relaxed));
+}
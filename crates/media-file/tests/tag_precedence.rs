@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{io::BufReader, path::Path};
+
+use aoide_core::{media::content::ContentLink, util::clock::OffsetDateTimeMs, Track};
+use aoide_media_file::io::import::{
+    import_into_track, FileTagType, ImportTrack, ImportTrackConfig, Reader,
+};
+use lofty::{
+    config::WriteOptions,
+    tag::{ItemKey, Tag, TagExt as _, TagType},
+};
+use tempfile::NamedTempFile;
+
+/// Create an MP3 file with conflicting ID3v2 and ID3v1 tags.
+///
+/// The artist is only stored in the ID3v1 tag.
+fn new_file_with_conflicting_id3v2_and_id3v1_titles() -> NamedTempFile {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::copy("tests/assets/empty.mp3", file.path()).unwrap();
+
+    let mut id3v2 = Tag::new(TagType::Id3v2);
+    id3v2.insert_text(ItemKey::TrackTitle, "ID3v2 Title".to_owned());
+    id3v2
+        .save_to_path(file.path(), WriteOptions::default())
+        .unwrap();
+
+    let mut id3v1 = Tag::new(TagType::Id3v1);
+    id3v1.insert_text(ItemKey::TrackTitle, "ID3v1 Title".to_owned());
+    id3v1.insert_text(ItemKey::TrackArtist, "ID3v1 Artist".to_owned());
+    id3v1
+        .save_to_path(file.path(), WriteOptions::default())
+        .unwrap();
+
+    file
+}
+
+fn import_new_track_from_file_path(file_path: &Path, config: &ImportTrackConfig) -> Track {
+    let mut track = ImportTrack::NewTrack {
+        collected_at: OffsetDateTimeMs::now_utc(),
+    }
+    .with_content(
+        ContentLink {
+            path: Default::default(),
+            rev: None,
+        },
+        "audio/mpeg".parse().unwrap(),
+    );
+    let file = std::fs::File::open(file_path).unwrap();
+    let mut reader: Box<dyn Reader> = Box::new(BufReader::new(file));
+    let issues = import_into_track(&mut reader, config, &mut track).unwrap();
+    assert!(issues.is_empty());
+    track
+}
+
+#[test]
+fn prefer_id3v2_over_id3v1_by_default() {
+    let file = new_file_with_conflicting_id3v2_and_id3v1_titles();
+    let track = import_new_track_from_file_path(file.path(), &Default::default());
+    assert_eq!(Some("ID3v2 Title"), track.track_title());
+    // Missing fields are imported from tags with a lower precedence.
+    assert_eq!(Some("ID3v1 Artist"), track.track_artist());
+}
+
+#[test]
+fn prefer_id3v1_over_id3v2_if_configured() {
+    let file = new_file_with_conflicting_id3v2_and_id3v1_titles();
+    let config = ImportTrackConfig {
+        tag_type_precedence: Some(vec![FileTagType::Id3v1, FileTagType::Id3v2]),
+        ..Default::default()
+    };
+    let track = import_new_track_from_file_path(file.path(), &config);
+    assert_eq!(Some("ID3v1 Title"), track.track_title());
+    assert_eq!(Some("ID3v1 Artist"), track.track_artist());
+}
@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{fs, path::Path};
+
+use aoide_media_file::{fs::waveform::decode_waveform_overview, Error};
+
+const SAMPLE_RATE: u32 = 8_000;
+
+/// Write a mono 16-bit PCM WAV file with one second of silence
+/// followed by one second at full scale.
+fn write_wav_fixture(file_path: &Path) {
+    let frame_count = 2 * SAMPLE_RATE;
+    let data_len = frame_count * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for frame in 0..frame_count {
+        let sample = if frame < SAMPLE_RATE {
+            0
+        } else if frame % 2 == 0 {
+            i16::MAX
+        } else {
+            -i16::MAX
+        };
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    fs::write(file_path, wav).unwrap();
+}
+
+#[test]
+fn overview_length_matches_resolution() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("fixture.wav");
+    write_wav_fixture(&file_path);
+
+    for resolution in [1, 16, 100, 1_000] {
+        let overview = decode_waveform_overview(&file_path, resolution).unwrap();
+        assert_eq!(resolution, overview.resolution());
+    }
+
+    let overview = decode_waveform_overview(&file_path, 16).unwrap();
+    let (silence, full_scale) = overview.peaks().split_at(8);
+    // Packets that straddle the transition might raise the last bin of the silence
+    assert!(silence[..7].iter().all(|&peak| peak == 0));
+    assert!(full_scale.iter().all(|&peak| peak == u8::MAX));
+}
+
+#[test]
+fn zero_resolution_is_unsupported() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("fixture.wav");
+    write_wav_fixture(&file_path);
+
+    assert!(matches!(
+        decode_waveform_overview(&file_path, 0),
+        Err(Error::UnsupportedImportOptions)
+    ));
+}
@@ -0,0 +1,5 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- The size of the content data in bytes.
+ALTER TABLE media_source ADD COLUMN content_size INTEGER;
@@ -0,0 +1,11 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- The stored search query of dynamic playlists.
+CREATE TABLE IF NOT EXISTS playlist_dynamic_query (
+    playlist_id              INTEGER PRIMARY KEY,
+    -- serialized search parameters (filter and ordering)
+    search_params            TEXT NOT NULL,
+    --
+    FOREIGN KEY(playlist_id) REFERENCES playlist(row_id) ON DELETE CASCADE
+) STRICT;
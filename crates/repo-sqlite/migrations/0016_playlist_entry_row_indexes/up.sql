@@ -0,0 +1,14 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- The row indexes of playlist entries have accidentally been
+-- created on the table playlist instead of playlist_entry.
+DROP INDEX IF EXISTS idx_playlist_entry_row_created_ms_desc;
+CREATE INDEX idx_playlist_entry_row_created_ms_desc ON playlist_entry (
+    row_created_ms DESC
+);
+
+DROP INDEX IF EXISTS idx_playlist_entry_row_updated_ms_desc;
+CREATE INDEX idx_playlist_entry_row_updated_ms_desc ON playlist_entry (
+    row_updated_ms DESC
+);
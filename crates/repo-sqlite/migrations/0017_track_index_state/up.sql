@@ -0,0 +1,13 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- The position up to which the tracks of a collection have been indexed,
+-- i.e. the (row_updated_ms, row_id) of the last indexed track.
+CREATE TABLE IF NOT EXISTS track_index_state (
+    collection_id            INTEGER PRIMARY KEY,
+    track_updated_ms         INTEGER NOT NULL,
+    -- no foreign key, the track might have been deleted in the meantime
+    track_id                 INTEGER NOT NULL,
+    --
+    FOREIGN KEY(collection_id) REFERENCES collection(row_id) ON DELETE CASCADE
+) STRICT;
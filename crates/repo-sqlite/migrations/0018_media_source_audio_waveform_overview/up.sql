@@ -0,0 +1,5 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- Precomputed peaks of the audio signal, one byte per bin.
+ALTER TABLE media_source ADD COLUMN audio_waveform_overview BLOB;
@@ -0,0 +1,7 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- Optional playback annotations of track entries, e.g. for DJ sets.
+ALTER TABLE playlist_entry ADD COLUMN cue_in_ms REAL;
+ALTER TABLE playlist_entry ADD COLUMN cue_out_ms REAL;
+ALTER TABLE playlist_entry ADD COLUMN crossfade_ms REAL;
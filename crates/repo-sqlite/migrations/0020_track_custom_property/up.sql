@@ -0,0 +1,22 @@
+-- SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- Arbitrary custom properties of tracks that are not covered by
+-- the schema. Not loaded together with tracks.
+CREATE TABLE IF NOT EXISTS track_custom_property (
+    row_id        INTEGER PRIMARY KEY,
+    -- relations (immutable)
+    track_id      INTEGER NOT NULL,
+    -- properties
+    key           TEXT NOT NULL,
+    value         TEXT NOT NULL,
+    --
+    FOREIGN KEY(track_id) REFERENCES track(row_id) ON DELETE CASCADE,
+    UNIQUE (track_id, key)
+) STRICT;
+
+DROP INDEX IF EXISTS idx_track_custom_property_key_value;
+CREATE INDEX idx_track_custom_property_key_value ON track_custom_property (
+    key,
+    value
+);
@@ -11,6 +11,7 @@ use aoide_core::{
     audio::{
         BitrateBps, BitrateBpsValue, ChannelCount, ChannelFlags, Channels, DurationMs,
         DurationMsValue, LoudnessLufs, LoudnessLufsValue, SampleRateHz, SampleRateHzValue,
+        WaveformOverview,
     },
     media::{
         artwork::{
@@ -65,6 +66,8 @@ pub struct QueryableRecord {
     pub artwork_image_height: Option<i16>,
     pub artwork_color: Option<i32>,
     pub artwork_thumbnail: Option<Vec<u8>>,
+    pub content_size: Option<i64>,
+    pub audio_waveform_overview: Option<Vec<u8>>,
 }
 
 impl TryFrom<QueryableRecord> for (RecordHeader, Source) {
@@ -101,6 +104,8 @@ impl TryFrom<QueryableRecord> for (RecordHeader, Source) {
             artwork_image_height,
             artwork_color,
             artwork_thumbnail,
+            content_size,
+            audio_waveform_overview,
         } = from;
         let channel_flags =
             audio_channel_mask.map(|val| ChannelFlags::from_bits_truncate(val as _));
@@ -113,6 +118,7 @@ impl TryFrom<QueryableRecord> for (RecordHeader, Source) {
             bitrate: audio_bitrate_bps.map(|val| BitrateBps::new(val as BitrateBpsValue)),
             loudness: audio_loudness_lufs.map(LoudnessLufs::new),
             encoder: audio_encoder,
+            waveform_overview: audio_waveform_overview.map(WaveformOverview::new),
         };
         let artwork = if let Some(source) = artwork_source
             .map(ArtworkSource::decode)
@@ -212,6 +218,7 @@ impl TryFrom<QueryableRecord> for (RecordHeader, Source) {
                 link: content_link,
                 r#type: content_type,
                 digest: content_digest,
+                size: content_size.and_then(|size| size.try_into().ok()),
                 metadata: content_metadata,
                 metadata_flags: content_metadata_flags,
             },
@@ -234,6 +241,7 @@ pub struct InsertableRecord<'a> {
     pub content_link_path: &'a str,
     pub content_type: String,
     pub content_digest: Option<&'a [u8]>,
+    pub content_size: Option<i64>,
     pub content_metadata_flags: i16,
     pub audio_duration_ms: Option<DurationMsValue>,
     pub audio_channel_count: Option<i16>,
@@ -241,6 +249,7 @@ pub struct InsertableRecord<'a> {
     pub audio_bitrate_bps: Option<BitrateBpsValue>,
     pub audio_loudness_lufs: Option<LoudnessLufsValue>,
     pub audio_encoder: Option<&'a str>,
+    pub audio_waveform_overview: Option<&'a [u8]>,
     pub artwork_source: Option<i16>,
     pub artwork_uri: Option<&'a str>,
     pub artwork_apic_type: Option<i16>,
@@ -271,6 +280,7 @@ impl<'a> InsertableRecord<'a> {
                         },
                     r#type: content_type,
                     digest: content_digest,
+                    size: content_size,
                     metadata: content_metadata,
                     metadata_flags: content_metadata_flags,
                 },
@@ -342,6 +352,7 @@ impl<'a> InsertableRecord<'a> {
             content_link_rev: content_link_rev.map(ContentRevision::to_signed_value),
             content_type: content_type.to_string(),
             content_digest: content_digest.as_ref().map(Vec::as_slice),
+            content_size: content_size.and_then(|size| size.try_into().ok()),
             content_metadata_flags: i16::from(content_metadata_flags.bits()),
             audio_duration_ms: audio_metadata
                 .and_then(|audio| audio.duration)
@@ -359,6 +370,9 @@ impl<'a> InsertableRecord<'a> {
                 .and_then(|audio| audio.loudness)
                 .map(LoudnessLufs::value),
             audio_encoder: audio_metadata.and_then(|audio| audio.encoder.as_deref()),
+            audio_waveform_overview: audio_metadata
+                .and_then(|audio| audio.waveform_overview.as_ref())
+                .map(WaveformOverview::peaks),
             artwork_source: artwork_source.map(ArtworkSource::encode),
             artwork_uri,
             artwork_apic_type,
@@ -383,6 +397,7 @@ pub struct UpdatableRecord<'a> {
     pub content_link_path: &'a str,
     pub content_type: String,
     pub content_digest: Option<&'a [u8]>,
+    pub content_size: Option<i64>,
     pub content_metadata_flags: i16,
     pub audio_duration_ms: Option<DurationMsValue>,
     pub audio_channel_count: Option<i16>,
@@ -391,6 +406,7 @@ pub struct UpdatableRecord<'a> {
     pub audio_bitrate_bps: Option<BitrateBpsValue>,
     pub audio_loudness_lufs: Option<LoudnessLufsValue>,
     pub audio_encoder: Option<&'a str>,
+    pub audio_waveform_overview: Option<&'a [u8]>,
     pub artwork_source: Option<i16>,
     pub artwork_uri: Option<&'a str>,
     pub artwork_apic_type: Option<i16>,
@@ -417,6 +433,7 @@ impl<'a> UpdatableRecord<'a> {
                         },
                     r#type: content_type,
                     digest: content_digest,
+                    size: content_size,
                     metadata: content_metadata,
                     metadata_flags: content_metadata_flags,
                 },
@@ -485,6 +502,7 @@ impl<'a> UpdatableRecord<'a> {
             content_link_rev: content_link_rev.map(ContentRevision::to_signed_value),
             content_type: content_type.to_string(),
             content_digest: content_digest.as_ref().map(Vec::as_slice),
+            content_size: content_size.and_then(|size| size.try_into().ok()),
             content_metadata_flags: i16::from(content_metadata_flags.bits()),
             audio_duration_ms: audio_metadata
                 .and_then(|audio| audio.duration)
@@ -506,6 +524,9 @@ impl<'a> UpdatableRecord<'a> {
                 .and_then(|audio| audio.loudness)
                 .map(LoudnessLufs::value),
             audio_encoder: audio_metadata.and_then(|audio| audio.encoder.as_deref()),
+            audio_waveform_overview: audio_metadata
+                .and_then(|audio| audio.waveform_overview.as_ref())
+                .map(WaveformOverview::peaks),
             artwork_source: artwork_source.map(ArtworkSource::encode),
             artwork_uri,
             artwork_apic_type,
@@ -35,6 +35,8 @@ diesel::table! {
         artwork_image_height -> Nullable<SmallInt>,
         artwork_color -> Nullable<Integer>,
         artwork_thumbnail -> Nullable<Binary>,
+        content_size -> Nullable<BigInt>,
+        audio_waveform_overview -> Nullable<Binary>,
     }
 }
 
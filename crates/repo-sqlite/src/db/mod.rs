@@ -9,6 +9,7 @@ pub(crate) mod playlist_entry;
 pub(crate) mod track;
 pub(crate) mod track_actor;
 pub(crate) mod track_cue;
+pub(crate) mod track_custom_property;
 pub(crate) mod track_tag;
 pub(crate) mod track_title;
 pub(crate) mod view_album;
@@ -18,7 +19,7 @@ mod join {
     use crate::db::{
         collection::schema::*, media_source::schema::*, media_tracker::schema::*,
         playlist::schema::*, playlist_entry::schema::*, track::schema::*, track_actor::schema::*,
-        view_track_search::schema::*,
+        track_tag::schema::*, view_track_search::schema::*,
     };
 
     diesel::allow_tables_to_appear_in_same_query!(
@@ -27,7 +28,10 @@ mod join {
         media_source,
         track,
         track_actor,
+        track_index_state,
+        track_tag,
         playlist,
+        playlist_dynamic_query,
         playlist_entry,
         media_tracker_directory,
         media_tracker_source,
@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Database representation of [`DynamicQuery`].
+
+use serde::{Deserialize, Serialize};
+
+use aoide_repo::{
+    playlist::DynamicQuery,
+    track::search::{PersistedFilter, PersistedSortOrder},
+    RepoError, RepoResult,
+};
+
+#[derive(Serialize, Deserialize)]
+struct PersistedDynamicQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filter: Option<PersistedFilter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ordering: Vec<PersistedSortOrder>,
+}
+
+impl From<DynamicQuery> for PersistedDynamicQuery {
+    fn from(from: DynamicQuery) -> Self {
+        let DynamicQuery { filter, ordering } = from;
+        Self {
+            filter: filter.map(Into::into),
+            ordering: ordering.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<PersistedDynamicQuery> for DynamicQuery {
+    fn from(from: PersistedDynamicQuery) -> Self {
+        let PersistedDynamicQuery { filter, ordering } = from;
+        Self {
+            filter: filter.map(Into::into),
+            ordering: ordering.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+pub(crate) fn encode_dynamic_query(query: DynamicQuery) -> RepoResult<String> {
+    serde_json::to_string(&PersistedDynamicQuery::from(query))
+        .map_err(|err| RepoError::Other(err.into()))
+}
+
+pub(crate) fn decode_dynamic_query(encoded: &str) -> RepoResult<DynamicQuery> {
+    serde_json::from_str::<PersistedDynamicQuery>(encoded)
+        .map(Into::into)
+        .map_err(|err| RepoError::Other(err.into()))
+}
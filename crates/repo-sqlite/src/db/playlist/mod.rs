@@ -1,5 +1,6 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub(crate) mod dynamic_query;
 pub(crate) mod models;
 pub(crate) mod schema;
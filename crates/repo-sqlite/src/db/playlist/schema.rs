@@ -23,3 +23,12 @@ diesel::table! {
 }
 
 diesel::joinable!(playlist -> collection (collection_id));
+
+diesel::table! {
+    playlist_dynamic_query (playlist_id) {
+        playlist_id -> BigInt,
+        search_params -> Text,
+    }
+}
+
+diesel::joinable!(playlist_dynamic_query -> playlist (playlist_id));
@@ -4,6 +4,7 @@
 use diesel::prelude::*;
 
 use aoide_core::{
+    audio::{DurationMs, PositionMs},
     playlist::{Entry, Item, SeparatorItem, TrackItem},
     util::clock::{OffsetDateTimeMs, TimestampMillis},
 };
@@ -29,6 +30,9 @@ pub struct QueryableRecord {
     pub title: Option<String>,
     pub notes: Option<String>,
     pub item_data: Option<String>,
+    pub cue_in_ms: Option<f64>,
+    pub cue_out_ms: Option<f64>,
+    pub crossfade_ms: Option<f64>,
 }
 
 impl From<QueryableRecord> for (PlaylistId, i64, Option<TrackId>, Entry) {
@@ -43,13 +47,22 @@ impl From<QueryableRecord> for (PlaylistId, i64, Option<TrackId>, Entry) {
             title,
             notes,
             item_data,
+            cue_in_ms,
+            cue_out_ms,
+            crossfade_ms,
         } = from;
         let item = if let Some(track_uid) = &track_uid {
             debug_assert!(item_data.is_none());
             Item::Track(TrackItem {
                 uid: decode_entity_uid_typed(track_uid),
+                cue_in: cue_in_ms.map(PositionMs::new),
+                cue_out: cue_out_ms.map(PositionMs::new),
+                crossfade: crossfade_ms.map(DurationMs::new),
             })
         } else {
+            debug_assert!(cue_in_ms.is_none());
+            debug_assert!(cue_out_ms.is_none());
+            debug_assert!(crossfade_ms.is_none());
             Item::Separator(SeparatorItem { kind: item_data })
         };
         let entry = Entry {
@@ -80,6 +93,9 @@ pub struct InsertableRecord<'a> {
     pub title: Option<&'a str>,
     pub notes: Option<&'a str>,
     pub item_data: Option<&'a str>,
+    pub cue_in_ms: Option<f64>,
+    pub cue_out_ms: Option<f64>,
+    pub crossfade_ms: Option<f64>,
 }
 
 impl<'a> InsertableRecord<'a> {
@@ -97,14 +113,19 @@ impl<'a> InsertableRecord<'a> {
             notes,
             item,
         } = created_entry;
-        let item_data = match item {
+        let (item_data, cue_in, cue_out, crossfade) = match item {
             Item::Separator(SeparatorItem { kind }) => {
                 debug_assert!(track_id.is_none());
-                kind.as_deref()
+                (kind.as_deref(), None, None, None)
             }
-            Item::Track(TrackItem { uid: _ }) => {
+            Item::Track(TrackItem {
+                uid: _,
+                cue_in,
+                cue_out,
+                crossfade,
+            }) => {
                 debug_assert!(track_id.is_some());
-                None
+                (None, *cue_in, *cue_out, *crossfade)
             }
         };
         Self {
@@ -118,6 +139,9 @@ impl<'a> InsertableRecord<'a> {
             title: title.as_deref(),
             notes: notes.as_deref(),
             item_data,
+            cue_in_ms: cue_in.map(PositionMs::value),
+            cue_out_ms: cue_out.map(PositionMs::value),
+            crossfade_ms: crossfade.map(DurationMs::value),
         }
     }
 }
@@ -18,6 +18,9 @@ diesel::table! {
         title -> Nullable<Text>,
         notes -> Nullable<Text>,
         item_data -> Nullable<Text>,
+        cue_in_ms -> Nullable<Double>,
+        cue_out_ms -> Nullable<Double>,
+        crossfade_ms -> Nullable<Double>,
     }
 }
 
@@ -44,3 +44,11 @@ diesel::table! {
 }
 
 diesel::joinable!(track -> media_source (media_source_id));
+
+diesel::table! {
+    track_index_state (collection_id) {
+        collection_id -> BigInt,
+        track_updated_ms -> BigInt,
+        track_id -> BigInt,
+    }
+}
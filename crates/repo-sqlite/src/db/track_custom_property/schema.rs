@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+///////////////////////////////////////////////////////////////////////
+
+use crate::db::track::schema::*;
+
+diesel::table! {
+    track_custom_property (row_id) {
+        row_id -> BigInt,
+        track_id -> BigInt,
+        key -> Text,
+        value -> Text,
+    }
+}
+
+diesel::joinable!(track_custom_property -> track (track_id));
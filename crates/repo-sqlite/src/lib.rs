@@ -24,6 +24,8 @@ mod db;
 
 pub mod repo;
 
+pub mod transaction;
+
 mod util;
 
 pub(crate) use aoide_repo::RecordId as RowId;
@@ -80,7 +82,7 @@ impl DerefMut for Connection<'_> {
 
 pub(crate) fn repo_error(err: DieselError) -> RepoError {
     match err {
-        DieselError::NotFound => RepoError::NotFound,
+        DieselError::NotFound => RepoError::NotFound { entity: None },
         err => RepoError::Other(err.into()),
     }
 }
@@ -121,9 +123,14 @@ pub fn run_migrations(connection: &mut DbConnection) -> MigrationResult<Vec<Migr
 #[cfg(test)]
 pub mod tests {
     use anyhow::anyhow;
-    use diesel::Connection as _;
+    use diesel::{
+        query_builder::{QueryBuilder as _, QueryFragment},
+        sql_types::Text,
+        sqlite::{Sqlite, SqliteQueryBuilder},
+        Connection as _, QueryableByName, RunQueryDsl as _,
+    };
 
-    use super::{initialize_database, repo_error, run_migrations, DbConnection};
+    use super::{initialize_database, repo_error, run_migrations, DbBackend, DbConnection};
 
     pub type TestResult<T> = anyhow::Result<T>;
 
@@ -135,4 +142,54 @@ pub mod tests {
         run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
         Ok(connection)
     }
+
+    #[derive(QueryableByName)]
+    struct QueryPlanRow {
+        #[diesel(sql_type = Text)]
+        detail: String,
+    }
+
+    /// Explain the query plan of a query.
+    ///
+    /// Returns the details of all steps, e.g. `SEARCH track USING INDEX ...`.
+    /// Bind parameters don't affect the choice of indexes and remain unbound.
+    pub fn explain_query_plan(
+        connection: &mut DbConnection,
+        query: &impl QueryFragment<DbBackend>,
+    ) -> TestResult<Vec<String>> {
+        let mut query_builder = SqliteQueryBuilder::new();
+        query.to_sql(&mut query_builder, &Sqlite)?;
+        let sql = query_builder.finish();
+        let details = diesel::sql_query(format!("EXPLAIN QUERY PLAN {sql}"))
+            .load::<QueryPlanRow>(connection)?
+            .into_iter()
+            .map(|QueryPlanRow { detail }| detail)
+            .collect();
+        Ok(details)
+    }
+
+    /// Assert that a query uses all expected indexes and doesn't scan any tables.
+    #[allow(clippy::missing_panics_doc)] // Only panics if the assertion fails
+    pub fn assert_query_plan_uses_indexes(
+        connection: &mut DbConnection,
+        query: &impl QueryFragment<DbBackend>,
+        expected_indexes: &[&str],
+    ) -> TestResult<()> {
+        let details = explain_query_plan(connection, query)?;
+        for detail in &details {
+            assert!(
+                !detail.starts_with("SCAN "),
+                "unexpected scan \"{detail}\" in query plan {details:?}"
+            );
+        }
+        for expected_index in expected_indexes {
+            assert!(
+                details.iter().any(|detail| detail
+                    .split_whitespace()
+                    .any(|word| word == *expected_index)),
+                "missing index {expected_index} in query plan {details:?}"
+            );
+        }
+        Ok(())
+    }
 }
@@ -1,8 +1,14 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Duration;
+
 use anyhow::anyhow;
-use diesel::prelude::*;
+use diesel::{
+    dsl::sql,
+    prelude::*,
+    sql_types::{BigInt, Nullable},
+};
 
 use aoide_core::{
     media::content::{ContentPath, ContentPathConfig, VirtualFilePathConfig},
@@ -18,8 +24,8 @@ use aoide_core_api::{
 };
 use aoide_repo::{
     collection::{EntityRepo, KindFilter, MediaSourceRootUrlFilter, RecordHeader},
-    fetch_and_collect_filtered_records, CollectionId, RepoError, RepoResult,
-    ReservableRecordCollector,
+    fetch_and_collect_filtered_records, CollectionId, EntityContext, EntityKind, RepoError,
+    RepoResult, ReservableRecordCollector,
 };
 
 use crate::{
@@ -118,7 +124,9 @@ impl EntityRepo for Connection<'_> {
             ))
             .filter(collection::entity_uid.eq(EncodedEntityUid::from(uid).as_str()))
             .get_result::<(RowId, TimestampMillis, TimestampMillis, i64)>(self.as_mut())
-            .map_err(repo_error)
+            .map_err(|err| {
+                repo_error(err).with_entity_context(EntityContext::new(EntityKind::Collection, uid))
+            })
             .map(|(row_id, row_created_ms, row_updated_ms, entity_rev)| {
                 let header = RecordHeader {
                     id: row_id.into(),
@@ -163,7 +171,11 @@ impl EntityRepo for Connection<'_> {
         let resolved = self.resolve_collection_entity_revision(uid)?;
         if rows_affected < 1 {
             // Resolved by UID, but not touched due to revision conflict
-            return Err(RepoError::Conflict);
+            let (_, current_rev) = resolved;
+            return Err(RepoError::Conflict {
+                entity: Some(EntityContext::new(EntityKind::Collection, uid)),
+                current_rev,
+            });
         }
         Ok(resolved)
     }
@@ -181,7 +193,7 @@ impl EntityRepo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         store_vfs(self, id, &updated_entity.body)?;
         Ok(())
@@ -231,6 +243,31 @@ impl EntityRepo for Connection<'_> {
                     KindFilter::NotEqual(kind) => {
                         target = target.filter(collection::kind.ne(kind));
                     }
+                    KindFilter::AnyOf {
+                        kinds,
+                        include_none,
+                    } => {
+                        let kinds = kinds.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+                        match (kinds.is_empty(), *include_none) {
+                            (true, false) => {
+                                // Nothing to do
+                                return Ok(Default::default());
+                            }
+                            (true, true) => {
+                                target = target.filter(collection::kind.is_null());
+                            }
+                            (false, false) => {
+                                target = target.filter(collection::kind.eq_any(kinds));
+                            }
+                            (false, true) => {
+                                target = target.filter(
+                                    collection::kind
+                                        .eq_any(kinds)
+                                        .or(collection::kind.is_null()),
+                                );
+                            }
+                        }
+                    }
                 }
             }
 
@@ -359,8 +396,26 @@ impl EntityRepo for Connection<'_> {
             .get_result::<i64>(self.as_mut())
             .map_err(repo_error)?;
         debug_assert!(track_count >= 0);
+        // Each track refers to a distinct media source. Tracks with an unknown
+        // duration or size are excluded from the sums, i.e. SUM() ignores NULL.
+        let (total_duration_ms, total_bytes) = media_source::table
+            .filter(media_source::collection_id.eq(RowId::from(id)))
+            .filter(media_source::row_id.eq_any(track::table.select(track::media_source_id)))
+            .select((
+                diesel::dsl::sum(media_source::audio_duration_ms),
+                // The typed sum of a BigInt column would be a Numeric value
+                sql::<Nullable<BigInt>>("SUM(content_size)"),
+            ))
+            .get_result::<(Option<f64>, Option<i64>)>(self.as_mut())
+            .map_err(repo_error)?;
+        let total_duration_ms = total_duration_ms.unwrap_or_default();
+        debug_assert!(total_duration_ms >= 0.0);
+        let total_bytes = total_bytes.unwrap_or_default();
+        debug_assert!(total_bytes >= 0);
         let track_summary = TrackSummary {
             total_count: track_count as u64,
+            total_duration: Duration::from_millis(total_duration_ms.round() as u64),
+            total_bytes: total_bytes as u64,
         };
         let playlist_count = playlist::table
             .filter(playlist::collection_id.eq(RowId::from(id)))
@@ -384,7 +439,7 @@ impl EntityRepo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         Ok(())
     }
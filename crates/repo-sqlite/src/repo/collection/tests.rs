@@ -1,10 +1,26 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Duration;
+
 use test_log::test;
 
-use aoide_core::{util::clock::OffsetDateTimeMs, Collection, CollectionEntity, CollectionHeader};
-use aoide_repo::{collection::EntityRepo, RepoError, RepoResult};
+use aoide_core::{
+    audio::DurationMs,
+    media::{
+        self,
+        content::{AudioContentMetadata, ContentLink, ContentMetadata},
+    },
+    util::clock::OffsetDateTimeMs,
+    Collection, CollectionEntity, CollectionHeader, Track, TrackBody, TrackEntity, TrackHeader,
+};
+use aoide_core_api::collection::{LoadScope, TrackSummary};
+use aoide_repo::{
+    collection::{EntityRepo, EntityWithSummaryCollector, KindFilter},
+    media::source::CollectionRepo as _,
+    track::EntityRepo as _,
+    CollectionId, MediaSourceId, RepoError, RepoResult,
+};
 
 use crate::{repo::tests::vfs_media_source_config, tests::*, DbConnection};
 
@@ -79,7 +95,7 @@ fn update_collection() -> TestResult<()> {
     // Revision not bumped -> Conflict
     assert!(matches!(
         db.update_collection_entity_revision(&OffsetDateTimeMs::now_utc(), &updated_entity),
-        Err(RepoError::Conflict),
+        Err(RepoError::Conflict { current_rev, .. }) if current_rev == entity.hdr.rev,
     ));
     // Unchanged
     assert_eq!(entity, db.load_collection_entity(id)?.1);
@@ -94,7 +110,7 @@ fn update_collection() -> TestResult<()> {
         .unwrap();
     assert!(matches!(
         db.update_collection_entity_revision(&OffsetDateTimeMs::now_utc(), &updated_entity),
-        Err(RepoError::Conflict),
+        Err(RepoError::Conflict { .. }),
     ));
     // Unchanged
     assert_eq!(entity, db.load_collection_entity(id)?.1);
@@ -135,3 +151,235 @@ fn purge_collection() -> TestResult<()> {
     println!("Removed entity: {uid}");
     Ok(())
 }
+
+fn load_collection_titles_filtered_by_kind(
+    repo: &mut dyn EntityRepo,
+    kind_filter: Option<KindFilter<'_>>,
+) -> RepoResult<Vec<String>> {
+    let mut collector = EntityWithSummaryCollector::default();
+    repo.load_collection_entities(kind_filter, None, LoadScope::Entity, None, &mut collector)?;
+    let mut titles = collector
+        .finish()
+        .into_iter()
+        .map(|entity_with_summary| entity_with_summary.entity.raw.body.title)
+        .collect::<Vec<_>>();
+    titles.sort_unstable();
+    Ok(titles)
+}
+
+#[test]
+fn load_collections_filtered_by_kinds() -> TestResult<()> {
+    let mut fixture = Fixture::new()?;
+    let mut db = crate::Connection::new(&mut fixture.db);
+
+    for (title, kind) in [
+        ("a", Some("kind a")),
+        ("b", Some("kind b")),
+        ("c", Some("kind c")),
+        ("none", None),
+    ] {
+        create_collection(
+            &mut db,
+            Collection {
+                title: title.into(),
+                notes: None,
+                kind: kind.map(Into::into),
+                color: None,
+                media_source_config: vfs_media_source_config(),
+            },
+        )?;
+    }
+
+    assert_eq!(
+        vec!["a", "b", "c", "none"],
+        load_collection_titles_filtered_by_kind(&mut db, None)?
+    );
+    assert_eq!(
+        vec!["a", "c"],
+        load_collection_titles_filtered_by_kind(
+            &mut db,
+            Some(KindFilter::AnyOf {
+                kinds: vec!["kind a".into(), "kind c".into()],
+                include_none: false,
+            })
+        )?
+    );
+    assert_eq!(
+        vec!["b", "none"],
+        load_collection_titles_filtered_by_kind(
+            &mut db,
+            Some(KindFilter::AnyOf {
+                kinds: vec!["kind b".into()],
+                include_none: true,
+            })
+        )?
+    );
+    assert_eq!(
+        vec!["none"],
+        load_collection_titles_filtered_by_kind(
+            &mut db,
+            Some(KindFilter::AnyOf {
+                kinds: vec![],
+                include_none: true,
+            })
+        )?
+    );
+    assert!(load_collection_titles_filtered_by_kind(
+        &mut db,
+        Some(KindFilter::AnyOf {
+            kinds: vec![],
+            include_none: false,
+        })
+    )?
+    .is_empty());
+    Ok(())
+}
+
+fn new_media_source(path: &str, duration_ms: Option<f64>, size: Option<u64>) -> media::Source {
+    media::Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: media::Content {
+            link: ContentLink {
+                path: path.to_owned().into(),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            digest: None,
+            size,
+            metadata: ContentMetadata::Audio(AudioContentMetadata {
+                duration: duration_ms.map(DurationMs::new),
+                ..Default::default()
+            }),
+            metadata_flags: Default::default(),
+        },
+        artwork: Default::default(),
+    }
+}
+
+fn create_media_source(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    media_source: &media::Source,
+) -> RepoResult<MediaSourceId> {
+    let collected_at = media_source.collected_at.clone();
+    Ok(db
+        .insert_media_source(collection_id, collected_at, media_source)?
+        .id)
+}
+
+fn create_track(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    media_source: media::Source,
+) -> RepoResult<()> {
+    let media_source_id = create_media_source(db, collection_id, &media_source)?;
+    let entity_body = TrackBody {
+        updated_at: media_source.collected_at.clone(),
+        track: Track::new_from_media_source(media_source),
+        last_synchronized_rev: None,
+        content_url: None,
+    };
+    let track_entity = TrackEntity::new(TrackHeader::initial_random(), entity_body);
+    db.insert_track_entity(media_source_id, &track_entity)?;
+    Ok(())
+}
+
+#[test]
+fn load_collection_summary_with_total_duration_and_bytes() -> TestResult<()> {
+    let mut fixture = Fixture::new()?;
+    let mut db = crate::Connection::new(&mut fixture.db);
+    let new_collection = |title: &str| Collection {
+        title: title.into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_id = db.insert_collection_entity(
+        &OffsetDateTimeMs::now_utc(),
+        &CollectionEntity::new(
+            CollectionHeader::initial_random(),
+            new_collection("Collection"),
+        ),
+    )?;
+    let other_collection_id = db.insert_collection_entity(
+        &OffsetDateTimeMs::now_utc(),
+        &CollectionEntity::new(
+            CollectionHeader::initial_random(),
+            new_collection("Other Collection"),
+        ),
+    )?;
+
+    create_track(
+        &mut db,
+        collection_id,
+        new_media_source("a.mp3", Some(60_000.0), Some(1_000_000)),
+    )?;
+    // Missing size
+    create_track(
+        &mut db,
+        collection_id,
+        new_media_source("b.mp3", Some(90_500.0), None),
+    )?;
+    // Missing duration
+    create_track(
+        &mut db,
+        collection_id,
+        new_media_source("c.mp3", None, Some(2_345_678)),
+    )?;
+    // Media sources without a track are not considered
+    create_media_source(
+        &mut db,
+        collection_id,
+        &new_media_source("orphan.mp3", Some(1_000.0), Some(1_000)),
+    )?;
+    // Tracks of other collections are not considered
+    create_track(
+        &mut db,
+        other_collection_id,
+        new_media_source("a.mp3", Some(1_000.0), Some(1_000)),
+    )?;
+
+    let summary = db.load_collection_summary(collection_id)?;
+    assert_eq!(4, summary.media_sources.total_count);
+    assert_eq!(
+        TrackSummary {
+            total_count: 3,
+            total_duration: Duration::from_millis(150_500),
+            total_bytes: 3_345_678,
+        },
+        summary.tracks
+    );
+    assert_eq!("3 tracks, 0:02:30, 3.3 MB", summary.tracks.to_string());
+
+    let empty_collection_id = db.insert_collection_entity(
+        &OffsetDateTimeMs::now_utc(),
+        &CollectionEntity::new(CollectionHeader::initial_random(), new_collection("Empty")),
+    )?;
+    let summary = db.load_collection_summary(empty_collection_id)?;
+    assert_eq!(TrackSummary::EMPTY, summary.tracks);
+    assert_eq!("0 tracks, 0:00:00, 0 B", summary.tracks.to_string());
+    Ok(())
+}
+
+#[test]
+fn display_track_summary() {
+    let summary = |total_count, total_secs, total_bytes| TrackSummary {
+        total_count,
+        total_duration: Duration::from_secs(total_secs),
+        total_bytes,
+    };
+    assert_eq!("1 track, 0:03:05, 999 B", summary(1, 185, 999).to_string());
+    assert_eq!(
+        "12 tracks, 1:02:03, 1.0 kB",
+        summary(12, 3723, 1_099).to_string()
+    );
+    assert_eq!(
+        "1234 tracks, 100:00:00, 12.3 GB",
+        summary(1234, 360_000, 12_345_678_901).to_string()
+    );
+    assert_eq!(
+        "2 tracks, 0:00:01, 2000.0 PB",
+        summary(2, 1, 2_000_000_000_000_000_000).to_string()
+    );
+}
@@ -4,12 +4,15 @@
 use diesel::prelude::*;
 
 use aoide_core::{
-    media::{content::ContentPath, Source},
+    media::{
+        content::{ContentPath, ContentRevision},
+        Source,
+    },
     util::clock::OffsetDateTimeMs,
 };
 use aoide_core_api::filtering::StringPredicate;
 use aoide_repo::{
-    media::source::{CollectionRepo, RecordHeader, Repo},
+    media::source::{CollectionRepo, ContentDigestRecord, RecordHeader, Repo},
     CollectionId, MediaSourceId, RepoError, RepoResult,
 };
 
@@ -24,6 +27,12 @@ use crate::{
     Connection, RowId,
 };
 
+/// Maximum number of content paths that are bound as parameters of a single query.
+///
+/// Stays safely below the default limit of 999 host parameters of
+/// older SQLite versions.
+const CONTENT_PATHS_CHUNK_SIZE: usize = 900;
+
 impl Repo for Connection<'_> {
     fn update_media_source(
         &mut self,
@@ -37,7 +46,7 @@ impl Repo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         Ok(())
     }
@@ -48,7 +57,7 @@ impl Repo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         Ok(())
     }
@@ -103,6 +112,63 @@ impl CollectionRepo for Connection<'_> {
             .collect::<RepoResult<_>>()
     }
 
+    fn load_media_source_content_paths(
+        &mut self,
+        collection_id: CollectionId,
+    ) -> RepoResult<Vec<ContentPath<'static>>> {
+        let query = media_source::table
+            .select(media_source::content_link_path)
+            .filter(media_source::collection_id.eq(RowId::from(collection_id)))
+            .order_by(media_source::content_link_path);
+        let rows = query
+            .load_iter::<String, _>(self.as_mut())
+            .map_err(repo_error)?;
+        rows.map(|row| row.map_err(repo_error).map(ContentPath::from))
+            .collect::<RepoResult<_>>()
+    }
+
+    fn load_media_source_content_digests(
+        &mut self,
+        collection_id: CollectionId,
+        content_paths: &[&ContentPath<'_>],
+    ) -> RepoResult<Vec<ContentDigestRecord>> {
+        let mut records = Vec::new();
+        for content_paths_chunk in content_paths.chunks(CONTENT_PATHS_CHUNK_SIZE) {
+            let query = media_source::table
+                .select((
+                    media_source::content_link_path,
+                    media_source::content_link_rev,
+                    media_source::content_size,
+                    media_source::content_digest,
+                ))
+                .filter(media_source::collection_id.eq(RowId::from(collection_id)))
+                .filter(
+                    media_source::content_link_path.eq_any(
+                        content_paths_chunk
+                            .iter()
+                            .map(|content_path| content_path.as_str()),
+                    ),
+                )
+                .filter(media_source::content_link_rev.is_not_null())
+                .filter(media_source::content_size.is_not_null())
+                .filter(media_source::content_digest.is_not_null());
+            let rows = query
+                .load::<(String, Option<i64>, Option<i64>, Option<Vec<u8>>)>(self.as_mut())
+                .map_err(repo_error)?;
+            records.extend(rows.into_iter().filter_map(
+                |(content_link_path, content_link_rev, content_size, content_digest)| {
+                    Some(ContentDigestRecord {
+                        content_path: content_link_path.into(),
+                        content_rev: ContentRevision::from_signed_value(content_link_rev?),
+                        content_size: content_size?.try_into().ok()?,
+                        content_digest: content_digest?,
+                    })
+                },
+            ));
+        }
+        Ok(records)
+    }
+
     fn relocate_media_sources_by_content_path_prefix(
         &mut self,
         collection_id: CollectionId,
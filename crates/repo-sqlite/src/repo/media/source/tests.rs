@@ -71,6 +71,7 @@ fn insert_media_source() -> anyhow::Result<()> {
             },
             r#type: "audio/mpeg".parse().unwrap(),
             digest: None,
+            size: None,
             metadata_flags: Default::default(),
             metadata: AudioContentMetadata {
                 duration: Some(DurationMs::new(543.0)),
@@ -125,6 +126,7 @@ fn filter_by_content_path_predicate() -> anyhow::Result<()> {
             },
             r#type: "audio/mpeg".parse().unwrap(),
             digest: None,
+            size: None,
             metadata_flags: Default::default(),
             metadata: AudioContentMetadata {
                 duration: Some(DurationMs::new(1.0)),
@@ -146,6 +148,7 @@ fn filter_by_content_path_predicate() -> anyhow::Result<()> {
             },
             r#type: "audio/mpeg".parse().unwrap(),
             digest: None,
+            size: None,
             metadata_flags: ContentMetadataFlags::RELIABLE,
             metadata: AudioContentMetadata {
                 duration: Some(DurationMs::new(1.0)),
@@ -309,6 +312,7 @@ fn relocate_by_content_path() -> anyhow::Result<()> {
             },
             r#type: "audio/mpeg".parse().unwrap(),
             digest: None,
+            size: None,
             metadata_flags: Default::default(),
             metadata: AudioContentMetadata {
                 duration: Some(DurationMs::new(1.0)),
@@ -330,6 +334,7 @@ fn relocate_by_content_path() -> anyhow::Result<()> {
             },
             r#type: "audio/mpeg".parse().unwrap(),
             digest: None,
+            size: None,
             metadata_flags: ContentMetadataFlags::RELIABLE,
             metadata: AudioContentMetadata {
                 duration: Some(DurationMs::new(1.0)),
@@ -380,6 +385,90 @@ fn relocate_by_content_path() -> anyhow::Result<()> {
             StringPredicate::Prefix("file:///Ho''".into())
         )?
     );
+    assert_eq!(
+        vec![
+            ContentPath::from("file:///Ho''me/File.mp3"),
+            ContentPath::from("file:///h'o''me/file.mp3"),
+        ],
+        db.load_media_source_content_paths(collection_id)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn load_content_digests() -> anyhow::Result<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let fixture = Fixture::new(&mut db)?;
+
+    let new_source = |path: &'static str, digest: Option<Vec<u8>>| media::Source {
+        collected_at: OffsetDateTimeMs::now_local(),
+        content: media::Content {
+            link: ContentLink {
+                path: ContentPath::from(path),
+                rev: Some(ContentRevision::new(6)),
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            digest,
+            size: Some(4096),
+            metadata_flags: Default::default(),
+            metadata: AudioContentMetadata::default().into(),
+        },
+        artwork: None,
+    };
+    let created_at = OffsetDateTimeMs::now_local();
+    db.insert_media_source(
+        fixture.collection_id,
+        created_at.clone(),
+        &new_source("file:///home/test/file1.mp3", Some(vec![1, 2, 3])),
+    )?;
+    db.insert_media_source(
+        fixture.collection_id,
+        created_at.clone(),
+        &new_source("file:///home/test/file2.mp3", None),
+    )?;
+    db.insert_media_source(
+        fixture.collection_id,
+        created_at,
+        &new_source("file:///home/test/file3.mp3", Some(vec![4, 5, 6])),
+    )?;
+
+    let content_path1 = ContentPath::from("file:///home/test/file1.mp3");
+    let content_path2 = ContentPath::from("file:///home/test/file2.mp3");
+    assert_eq!(
+        vec![ContentDigestRecord {
+            content_path: content_path1.clone(),
+            content_rev: ContentRevision::new(6),
+            content_size: 4096,
+            content_digest: vec![1, 2, 3],
+        }],
+        db.load_media_source_content_digests(
+            fixture.collection_id,
+            &[&content_path1, &content_path2]
+        )?
+    );
+    assert!(db
+        .load_media_source_content_digests(fixture.collection_id, &[])?
+        .is_empty());
+
+    // Exceed both the chunk size and the SQLite parameter limit
+    // with the existing media sources located in different chunks.
+    let content_path3 = ContentPath::from("file:///home/test/file3.mp3");
+    let unknown_content_paths = (0..2 * CONTENT_PATHS_CHUNK_SIZE)
+        .map(|i| ContentPath::from(format!("file:///home/test/unknown{i}.mp3")))
+        .collect::<Vec<_>>();
+    let mut requested = unknown_content_paths.iter().collect::<Vec<_>>();
+    requested.insert(0, &content_path1);
+    requested.insert(CONTENT_PATHS_CHUNK_SIZE + 1, &content_path3);
+    assert!(requested.len() > 999);
+    assert_eq!(
+        vec![content_path1.clone(), content_path3.clone()],
+        db.load_media_source_content_digests(fixture.collection_id, &requested)?
+            .into_iter()
+            .map(|record| record.content_path)
+            .collect::<Vec<_>>()
+    );
 
     Ok(())
 }
@@ -7,12 +7,23 @@ pub mod playlist;
 pub mod track;
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use aoide_core::{
         collection::MediaSourceConfig,
-        media::content::{ContentPathConfig, VirtualFilePathConfig},
-        util::url::BaseUrl,
+        media::{
+            self,
+            content::{
+                AudioContentMetadata, ContentLink, ContentPathConfig, VirtualFilePathConfig,
+            },
+        },
+        util::{clock::OffsetDateTimeMs, url::BaseUrl},
+        Track, TrackBody, TrackEntity, TrackHeader,
     };
+    use aoide_repo::{
+        media::source::CollectionRepo as _, track::EntityRepo as _, CollectionId, TrackId,
+    };
+
+    use crate::tests::TestResult;
 
     pub(crate) fn vfs_media_source_config() -> MediaSourceConfig {
         MediaSourceConfig {
@@ -22,4 +33,50 @@ mod tests {
             }),
         }
     }
+
+    /// Insert a new track with an audio file at the given content path.
+    ///
+    /// The entity could be modified by `customize` before inserting
+    /// both the media source and the track.
+    pub(crate) fn insert_track(
+        db: &mut crate::Connection<'_>,
+        collection_id: CollectionId,
+        path: &str,
+        customize: impl FnOnce(&mut TrackEntity),
+    ) -> TestResult<(TrackId, TrackEntity)> {
+        let created_at = OffsetDateTimeMs::now_utc();
+        let media_source = media::Source {
+            collected_at: created_at.clone(),
+            content: media::Content {
+                link: ContentLink {
+                    path: path.to_owned().into(),
+                    rev: None,
+                },
+                r#type: "audio/mpeg".parse().unwrap(),
+                metadata_flags: Default::default(),
+                metadata: AudioContentMetadata::default().into(),
+                digest: None,
+                size: None,
+            },
+            artwork: Default::default(),
+        };
+        let entity_body = TrackBody {
+            track: Track::new_from_media_source(media_source),
+            updated_at: created_at,
+            last_synchronized_rev: None,
+            content_url: None,
+        };
+        let mut track_entity = TrackEntity::new(TrackHeader::initial_random(), entity_body);
+        customize(&mut track_entity);
+        let media_source = &track_entity.body.track.media_source;
+        let media_source_id = db
+            .insert_media_source(
+                collection_id,
+                media_source.collected_at.clone(),
+                media_source,
+            )?
+            .id;
+        let track_id = db.insert_track_entity(media_source_id, &track_entity)?;
+        Ok((track_id, track_entity))
+    }
 }
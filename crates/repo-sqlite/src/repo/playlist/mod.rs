@@ -18,13 +18,17 @@ use aoide_core::{
 };
 use aoide_core_api::{playlist::EntityWithEntriesSummary, Pagination};
 use aoide_repo::{
-    playlist::*, track::EntityRepo as _, CollectionId, RepoError, RepoResult,
-    ReservableRecordCollector, TrackId,
+    playlist::*, track::EntityRepo as _, CollectionId, EntityContext, EntityKind, RepoError,
+    RepoResult, ReservableRecordCollector, TrackId,
 };
 
 use crate::{
     db::{
-        playlist::{models::*, schema::*},
+        playlist::{
+            dynamic_query::{decode_dynamic_query, encode_dynamic_query},
+            models::*,
+            schema::*,
+        },
         playlist_entry as playlist_entry_db,
         track::schema as track_schema,
     },
@@ -51,7 +55,9 @@ impl EntityRepo for Connection<'_> {
             ))
             .filter(playlist::entity_uid.eq(EncodedEntityUid::from(uid).as_str()))
             .get_result::<(RowId, TimestampMillis, TimestampMillis, i64)>(self.as_mut())
-            .map_err(repo_error)
+            .map_err(|err| {
+                repo_error(err).with_entity_context(EntityContext::new(EntityKind::Playlist, uid))
+            })
             .map(|(row_id, row_created_ms, row_updated_ms, entity_rev)| {
                 let header = RecordHeader {
                     id: row_id.into(),
@@ -82,7 +88,11 @@ impl EntityRepo for Connection<'_> {
         let resolved = self.resolve_playlist_entity_revision(uid)?;
         if rows_affected < 1 {
             // Successfully resolved by UID, but not touched due to revision conflict
-            return Err(RepoError::Conflict);
+            let (_, current_rev) = resolved;
+            return Err(RepoError::Conflict {
+                entity: Some(EntityContext::new(EntityKind::Playlist, uid)),
+                current_rev,
+            });
         }
         Ok(resolved)
     }
@@ -100,7 +110,7 @@ impl EntityRepo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         Ok(())
     }
@@ -130,7 +140,7 @@ impl EntityRepo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         Ok(())
     }
@@ -213,6 +223,51 @@ impl EntityRepo for Connection<'_> {
         }
         Ok(())
     }
+
+    fn load_playlist_collection_id(&mut self, id: RecordId) -> RepoResult<Option<CollectionId>> {
+        playlist::table
+            .select(playlist::collection_id)
+            .filter(playlist::row_id.eq(RowId::from(id)))
+            .get_result::<Option<RowId>>(self.as_mut())
+            .map(|collection_id| collection_id.map(Into::into))
+            .map_err(repo_error)
+    }
+
+    fn load_playlist_dynamic_query(&mut self, id: RecordId) -> RepoResult<Option<DynamicQuery>> {
+        playlist_dynamic_query::table
+            .select(playlist_dynamic_query::search_params)
+            .filter(playlist_dynamic_query::playlist_id.eq(RowId::from(id)))
+            .get_result::<String>(self.as_mut())
+            .optional()
+            .map_err(repo_error)?
+            .as_deref()
+            .map(decode_dynamic_query)
+            .transpose()
+    }
+
+    fn store_playlist_dynamic_query(
+        &mut self,
+        id: RecordId,
+        query: Option<&DynamicQuery>,
+    ) -> RepoResult<()> {
+        let Some(query) = query else {
+            let target = playlist_dynamic_query::table
+                .filter(playlist_dynamic_query::playlist_id.eq(RowId::from(id)));
+            let rows_affected: usize = diesel::delete(target)
+                .execute(self.as_mut())
+                .map_err(repo_error)?;
+            debug_assert!(rows_affected <= 1);
+            return Ok(());
+        };
+        let search_params = encode_dynamic_query(query.clone())?;
+        let query = diesel::replace_into(playlist_dynamic_query::table).values((
+            playlist_dynamic_query::playlist_id.eq(RowId::from(id)),
+            playlist_dynamic_query::search_params.eq(search_params),
+        ));
+        let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
+        debug_assert_eq!(1, rows_affected);
+        Ok(())
+    }
 }
 
 fn min_playlist_entry_ordering(
@@ -319,6 +374,9 @@ fn load_playlist_entry_records(
             playlist_entry::title,
             playlist_entry::notes,
             playlist_entry::item_data,
+            playlist_entry::cue_in_ms,
+            playlist_entry::cue_out_ms,
+            playlist_entry::crossfade_ms,
         ))
         .order_by(playlist_entry::ordering)
         .load::<QueryableRecord>(db.as_mut())
@@ -434,7 +492,7 @@ impl EntryRepo for crate::Connection<'_> {
             ordering = ordering.saturating_add(1);
             let track_id = match &entry.item {
                 Item::Separator(_) => None,
-                Item::Track(TrackItem { uid }) => Some(self.resolve_track_id(uid)?),
+                Item::Track(TrackItem { uid, .. }) => Some(self.resolve_track_id(uid)?),
             };
             let insertable = InsertableRecord::bind(id, track_id, ordering, &created_at, entry);
             let rows_affected = insertable
@@ -459,7 +517,7 @@ impl EntryRepo for crate::Connection<'_> {
         for entry in new_entries {
             let track_id = match &entry.item {
                 Item::Separator(_) => None,
-                Item::Track(TrackItem { uid }) => Some(self.resolve_track_id(uid)?),
+                Item::Track(TrackItem { uid, .. }) => Some(self.resolve_track_id(uid)?),
             };
             let insertable = InsertableRecord::bind(id, track_id, ordering, &created_at, entry);
             let rows_affected = insertable
@@ -630,7 +688,7 @@ impl EntryRepo for crate::Connection<'_> {
         for entry in new_entries {
             let track_id = match &entry.item {
                 Item::Separator(_) => None,
-                Item::Track(TrackItem { uid }) => Some(self.resolve_track_id(uid)?),
+                Item::Track(TrackItem { uid, .. }) => Some(self.resolve_track_id(uid)?),
             };
             let insertable = InsertableRecord::bind(id, track_id, ordering, &created_at, entry);
             let rows_affected = insertable
@@ -4,7 +4,7 @@
 use test_log::test;
 
 use aoide_core::{
-    audio::DurationMs,
+    audio::{DurationMs, PositionMs},
     media::{
         self,
         content::{AudioContentMetadata, ContentLink},
@@ -70,6 +70,7 @@ impl Fixture {
                     }
                     .into(),
                     digest: None,
+                    size: None,
                 },
                 artwork: Default::default(),
             };
@@ -125,7 +126,7 @@ impl Fixture {
                 added_at: OffsetDateTimeMs::now_local(),
                 title: Some(format!("Entry {i}")),
                 notes: None,
-                item: Item::Track(TrackItem { uid: track_uid }),
+                item: Item::Track(TrackItem::new(track_uid)),
             })
             .collect::<Vec<_>>();
         db.append_playlist_entries(playlist_id, &playlist_entries)?;
@@ -584,6 +585,44 @@ fn copy_all_entries() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn append_and_copy_track_entries_with_cue_range() -> anyhow::Result<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let fixture = Fixture::new(&mut db)?;
+
+    let source_entity_with_entries =
+        fixture.create_playlists_with_track_entries(&mut db, PlaylistScope::Collection, 0)?;
+    let source_playlist_id = db.resolve_playlist_id(&source_entity_with_entries.raw.hdr.uid)?;
+    let target_entity_with_entries =
+        fixture.create_playlists_with_track_entries(&mut db, PlaylistScope::Global, 0)?;
+    let target_playlist_id = db.resolve_playlist_id(&target_entity_with_entries.raw.hdr.uid)?;
+
+    let entries = fixture
+        .create_media_sources_and_tracks(&mut db, 2)?
+        .into_iter()
+        .map(|(_media_source_id, _track_id, track_uid)| Entry {
+            added_at: OffsetDateTimeMs::now_local(),
+            title: None,
+            notes: None,
+            item: Item::Track(TrackItem {
+                cue_in: Some(PositionMs::new(1_000.0)),
+                cue_out: Some(PositionMs::new(180_000.5)),
+                crossfade: Some(DurationMs::new(8_000.0)),
+                ..TrackItem::new(track_uid)
+            }),
+        })
+        .chain(std::iter::once(new_separator_entry()))
+        .collect::<Vec<_>>();
+    db.append_playlist_entries(source_playlist_id, &entries)?;
+    assert_eq!(entries, db.load_all_playlist_entries(source_playlist_id)?);
+
+    db.copy_all_playlist_entries(source_playlist_id, target_playlist_id)?;
+    assert_eq!(entries, db.load_all_playlist_entries(target_playlist_id)?);
+
+    Ok(())
+}
+
 #[test]
 fn load_global_playlist_entities_with_entries_summary() -> anyhow::Result<()> {
     let mut db = establish_connection()?;
@@ -1,7 +1,10 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{collections::HashSet, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
 
 use anyhow::anyhow;
 use diesel::prelude::*;
@@ -22,18 +25,18 @@ use aoide_core::{
     EncodedEntityUid, Track, TrackBody, TrackEntity, TrackHeader, TrackUid,
 };
 use aoide_core_api::{
-    filtering::StringPredicate,
-    track::search::{Filter, Scope, SortOrder},
+    filtering::{StringPredicate, MAX_NESTING_DEPTH},
+    track::search::{FieldGroups, Filter, Scope, SortOrder},
     Pagination,
 };
 use aoide_repo::{
     media::source::{CollectionRepo as _, Repo as _},
     track::{
-        ActorRepo, CollectionRepo, EntityRepo, RecordHeader, RecordTrail, ReplaceMode,
-        ReplaceOutcome, ReplaceParams,
+        ActorRepo, CollectionRepo, CustomPropertyRepo, DeleteOutcome, EntityRepo, IndexWatermark,
+        RecordHeader, RecordTrail, ReplaceMode, ReplaceOutcome, ReplaceParams, TagRepo,
     },
-    CollectionId, MediaSourceId, OptionalRepoResult as _, RepoError, RepoResult,
-    ReservableRecordCollector, TrackId,
+    CollectionId, EntityContext, EntityKind, MediaSourceId, OptionalRepoResult as _, RepoError,
+    RepoResult, ReservableRecordCollector, TrackId,
 };
 
 use crate::{
@@ -44,7 +47,9 @@ use crate::{
             select_row_id_filtered_by_collection_id as select_media_source_id_filtered_by_collection_id,
             select_row_id_filtered_by_content_path_predicate as select_media_source_id_filtered_by_content_path_predicate,
         },
+        playlist_entry::schema::*,
         track::{models::*, schema::*, *},
+        track_custom_property::schema::*,
         view_track_search::{
             models::{load_repo_entity, QueryableRecord as SearchQueryableRecord},
             schema::*,
@@ -55,12 +60,36 @@ use crate::{
         entity::{decode_entity_header, decode_entity_revision},
         pagination_to_limit_offset,
     },
-    Connection, RowId,
+    Connection, DbBackend, RowId,
 };
 
+/// Select all tracks of a collection from the search view.
+pub(crate) fn select_tracks_in_collection<'db>(
+    collection_id: CollectionId,
+) -> view_track_search::BoxedQuery<'db, DbBackend> {
+    view_track_search::table
+        .select(view_track_search::all_columns)
+        // TODO: Filtering by collection_id from the view is SLOOWWWWWWW!?!
+        //.filter(view_track_search::collection_id.eq(RowId::from(collection_id)))
+        // Filtering the collection_id by subselect through media_source (with an index) is much faster.
+        .filter(view_track_search::media_source_id.eq_any(
+            select_media_source_id_filtered_by_collection_id(collection_id),
+        ))
+        .into_boxed()
+}
+
 mod search;
 use self::search::{TrackSearchExpressionBoxedBuilder as _, TrackSearchQueryTransform as _};
 
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of UIDs that are bound as parameters of a single query.
+///
+/// Stays safely below the default limit of 999 host parameters of
+/// older SQLite versions.
+const TRACK_UIDS_CHUNK_SIZE: usize = 900;
+
 // TODO: Define a dedicated return type
 #[allow(clippy::type_complexity)]
 fn load_track_and_album_titles(
@@ -426,15 +455,29 @@ fn preload_entity(
     db: &mut crate::Connection<'_>,
     id: TrackId,
     media_source: Source,
+) -> RepoResult<EntityPreload> {
+    preload_entity_with_field_groups(db, id, media_source, FieldGroups::all())
+}
+
+fn preload_entity_with_field_groups(
+    db: &mut crate::Connection<'_>,
+    id: TrackId,
+    media_source: Source,
+    field_groups: FieldGroups,
 ) -> RepoResult<EntityPreload> {
     let (track_titles, album_titles) = load_track_and_album_titles(db, id)?;
     let (track_actors, album_actors) = load_track_and_album_actors(db, id)?;
+    let tags = if field_groups.contains(FieldGroups::TAGS) {
+        load_track_tags(db, id)?
+    } else {
+        Default::default()
+    };
     Ok(EntityPreload {
         media_source,
         album_actors,
         album_titles,
         cues: load_track_cues(db, id)?,
-        tags: load_track_tags(db, id)?,
+        tags,
         track_actors,
         track_titles,
     })
@@ -446,7 +489,9 @@ impl EntityRepo for Connection<'_> {
             .select(track::row_id)
             .filter(track::entity_uid.eq(EncodedEntityUid::from(uid).as_str()))
             .get_result::<RowId>(self.as_mut())
-            .map_err(repo_error)
+            .map_err(|err| {
+                repo_error(err).with_entity_context(EntityContext::new(EntityKind::Track, uid))
+            })
             .map(Into::into)
     }
 
@@ -497,7 +542,7 @@ impl EntityRepo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         update_track_and_album_titles(
             self,
@@ -537,7 +582,9 @@ impl EntityRepo for Connection<'_> {
         let queryable = view_track_search::table
             .filter(view_track_search::entity_uid.eq(EncodedEntityUid::from(uid).as_str()))
             .get_result::<SearchQueryableRecord>(self.as_mut())
-            .map_err(repo_error)?;
+            .map_err(|err| {
+                repo_error(err).with_entity_context(EntityContext::new(EntityKind::Track, uid))
+            })?;
         let (_, media_source) = self.load_media_source(queryable.media_source_id.into())?;
         let preload = preload_entity(self, queryable.row_id.into(), media_source)?;
         load_repo_entity(preload, queryable)
@@ -549,7 +596,7 @@ impl EntityRepo for Connection<'_> {
         let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
         debug_assert!(rows_affected <= 1);
         if rows_affected < 1 {
-            return Err(RepoError::NotFound);
+            return Err(RepoError::NotFound { entity: None });
         }
         Ok(())
     }
@@ -696,32 +743,143 @@ impl CollectionRepo for crate::Connection<'_> {
         }
     }
 
-    fn search_tracks(
+    fn load_tracks_by_uids(
+        &mut self,
+        collection_id: CollectionId,
+        uids: &[TrackUid],
+        collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+    ) -> RepoResult<Vec<TrackUid>> {
+        let mut loaded = HashMap::with_capacity(uids.len());
+        for uids_chunk in uids.chunks(TRACK_UIDS_CHUNK_SIZE) {
+            let encoded_uids = uids_chunk
+                .iter()
+                .map(|uid| EncodedEntityUid::from(uid).as_str().to_owned())
+                .collect::<Vec<_>>();
+            let queryables = view_track_search::table
+                .filter(view_track_search::entity_uid.eq_any(encoded_uids))
+                .filter(view_track_search::media_source_id.eq_any(
+                    select_media_source_id_filtered_by_collection_id(collection_id),
+                ))
+                .load::<SearchQueryableRecord>(self.as_mut())
+                .map_err(repo_error)?;
+            for queryable in queryables {
+                let (_, media_source) = self.load_media_source(queryable.media_source_id.into())?;
+                let preload = preload_entity(self, queryable.row_id.into(), media_source)?;
+                let (record_header, entity) = load_repo_entity(preload, queryable)?;
+                loaded.insert(entity.hdr.uid.clone(), (record_header, entity));
+            }
+        }
+        collector.reserve(loaded.len());
+        let mut not_found = Vec::new();
+        for uid in uids {
+            // Tracks are cloned instead of removed to support duplicate UIDs
+            if let Some((record_header, entity)) = loaded.get(uid) {
+                collector.collect(record_header.clone(), entity.clone());
+            } else {
+                not_found.push(uid.clone());
+            }
+        }
+        Ok(not_found)
+    }
+
+    fn delete_tracks_by_uids(
+        &mut self,
+        collection_id: CollectionId,
+        uids: &[TrackUid],
+    ) -> RepoResult<Vec<(TrackUid, DeleteOutcome)>> {
+        let mut outcomes = HashMap::with_capacity(uids.len());
+        for uids_chunk in uids.chunks(TRACK_UIDS_CHUNK_SIZE) {
+            let encoded_uids = uids_chunk
+                .iter()
+                .map(|uid| EncodedEntityUid::from(uid).as_str().to_owned())
+                .collect::<Vec<_>>();
+            let found = track::table
+                .select((track::entity_uid, track::row_id, track::media_source_id))
+                .filter(track::entity_uid.eq_any(encoded_uids))
+                .filter(track::media_source_id.eq_any(
+                    select_media_source_id_filtered_by_collection_id(collection_id),
+                ))
+                .load::<(String, RowId, RowId)>(self.as_mut())
+                .map_err(repo_error)?;
+            // Playlist entries would be deleted silently by cascading,
+            // which is not desired.
+            let referenced = playlist_entry::table
+                .select(playlist_entry::track_id)
+                .filter(
+                    playlist_entry::track_id.eq_any(
+                        found
+                            .iter()
+                            .map(|(_, row_id, _)| *row_id)
+                            .collect::<Vec<_>>(),
+                    ),
+                )
+                .distinct()
+                .load::<Option<RowId>>(self.as_mut())
+                .map_err(repo_error)?
+                .into_iter()
+                .flatten()
+                .collect::<HashSet<_>>();
+            let mut deleted_row_ids = Vec::with_capacity(found.len());
+            let mut deleted_media_source_ids = Vec::with_capacity(found.len());
+            for (entity_uid, row_id, media_source_id) in found {
+                let outcome = if referenced.contains(&row_id) {
+                    DeleteOutcome::Blocked
+                } else {
+                    deleted_row_ids.push(row_id);
+                    deleted_media_source_ids.push(media_source_id);
+                    DeleteOutcome::Deleted
+                };
+                outcomes.insert(entity_uid, outcome);
+            }
+            diesel::delete(track::table.filter(track::row_id.eq_any(deleted_row_ids)))
+                .execute(self.as_mut())
+                .map_err(repo_error)?;
+            // The media sources including their artwork are owned by the
+            // tracks and would otherwise remain as orphans.
+            diesel::delete(
+                media_source::table.filter(media_source::row_id.eq_any(deleted_media_source_ids)),
+            )
+            .execute(self.as_mut())
+            .map_err(repo_error)?;
+        }
+        Ok(uids
+            .iter()
+            .map(|uid| {
+                let outcome = outcomes
+                    .get(EncodedEntityUid::from(uid).as_str())
+                    .copied()
+                    .unwrap_or(DeleteOutcome::NotFound);
+                (uid.clone(), outcome)
+            })
+            .collect())
+    }
+
+    fn search_tracks_with_field_groups(
         &mut self,
         collection_id: CollectionId,
         pagination: &Pagination,
         filter: Option<&Filter>,
         ordering: &[SortOrder],
+        field_groups: FieldGroups,
         collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
     ) -> RepoResult<usize> {
-        let mut query = view_track_search::table
-            .select(view_track_search::all_columns)
-            // TODO: Filtering by collection_id from the view is SLOOWWWWWWW!?!
-            //.filter(view_track_search::collection_id.eq(RowId::from(collection_id)))
-            // Filtering the collection_id by subselect through media_source (with an index) is much faster.
-            .filter(
-                view_track_search::media_source_id.eq_any(
-                    media_source::table
-                        .select(media_source::row_id)
-                        .filter(media_source::collection_id.eq(RowId::from(collection_id))),
-                ),
-            )
-            .into_boxed();
+        let mut query = select_tracks_in_collection(collection_id);
 
         if let Some(filter) = filter {
+            // Deeply nested filters would result in SQL queries that
+            // exceed the parser stack and expression depth of SQLite.
+            let nesting_depth = filter.nesting_depth();
+            if nesting_depth > MAX_NESTING_DEPTH {
+                return Err(RepoError::Other(anyhow!(
+                    "filter nesting depth {nesting_depth} exceeds the maximum of \
+                     {MAX_NESTING_DEPTH}"
+                )));
+            }
             query = query.filter(filter.build_expression());
         }
 
+        // All sort keys are applied in order of precedence, i.e. subsequent
+        // keys only affect the relative order of results with equal values.
         for sort_order in ordering {
             query = sort_order.apply_to_query(query);
         }
@@ -758,8 +916,14 @@ impl CollectionRepo for crate::Connection<'_> {
         for record in records {
             let media_source_id = record.media_source_id.into();
             let (_, media_source) = self.load_media_source(media_source_id)?;
-            let preload = preload_entity(self, record.row_id.into(), media_source)?;
-            let (record_header, entity) = load_repo_entity(preload, record)?;
+            let preload = preload_entity_with_field_groups(
+                self,
+                record.row_id.into(),
+                media_source,
+                field_groups,
+            )?;
+            let (record_header, mut entity) = load_repo_entity(preload, record)?;
+            field_groups.omit_unrequested(&mut entity.body.track);
             collector.collect(record_header, entity);
         }
         log::debug!(
@@ -900,6 +1064,134 @@ impl CollectionRepo for crate::Connection<'_> {
         })
         .collect::<RepoResult<_>>()
     }
+
+    fn load_tracks_updated_after(
+        &mut self,
+        collection_id: CollectionId,
+        watermark: Option<&IndexWatermark>,
+        limit: u64,
+        collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+    ) -> RepoResult<usize> {
+        let mut query = select_tracks_in_collection(collection_id);
+        if let Some(IndexWatermark { updated_at, id }) = watermark {
+            let updated_ms = updated_at.timestamp_millis();
+            query = query.filter(
+                view_track_search::row_updated_ms.gt(updated_ms).or(
+                    view_track_search::row_updated_ms
+                        .eq(updated_ms)
+                        .and(view_track_search::row_id.gt(RowId::from(*id))),
+                ),
+            );
+        }
+        let records = query
+            .order_by(view_track_search::row_updated_ms)
+            .then_order_by(view_track_search::row_id)
+            .limit(limit.try_into().unwrap_or(i64::MAX))
+            .load::<SearchQueryableRecord>(self.as_mut())
+            .map_err(repo_error)?;
+        let count = records.len();
+        collector.reserve(count);
+        for record in records {
+            let (_, media_source) = self.load_media_source(record.media_source_id.into())?;
+            let preload = preload_entity(self, record.row_id.into(), media_source)?;
+            let (record_header, entity) = load_repo_entity(preload, record)?;
+            collector.collect(record_header, entity);
+        }
+        Ok(count)
+    }
+
+    fn load_track_index_watermark(
+        &mut self,
+        collection_id: CollectionId,
+    ) -> RepoResult<Option<IndexWatermark>> {
+        let row = track_index_state::table
+            .select((
+                track_index_state::track_updated_ms,
+                track_index_state::track_id,
+            ))
+            .filter(track_index_state::collection_id.eq(RowId::from(collection_id)))
+            .get_result::<(i64, RowId)>(self.as_mut())
+            .optional()
+            .map_err(repo_error)?;
+        Ok(row.map(|(track_updated_ms, track_id)| IndexWatermark {
+            updated_at: OffsetDateTimeMs::from_timestamp_millis(track_updated_ms),
+            id: track_id.into(),
+        }))
+    }
+
+    fn store_track_index_watermark(
+        &mut self,
+        collection_id: CollectionId,
+        watermark: Option<&IndexWatermark>,
+    ) -> RepoResult<()> {
+        let Some(IndexWatermark { updated_at, id }) = watermark else {
+            let target = track_index_state::table
+                .filter(track_index_state::collection_id.eq(RowId::from(collection_id)));
+            let rows_affected: usize = diesel::delete(target)
+                .execute(self.as_mut())
+                .map_err(repo_error)?;
+            debug_assert!(rows_affected <= 1);
+            return Ok(());
+        };
+        let query = diesel::replace_into(track_index_state::table).values((
+            track_index_state::collection_id.eq(RowId::from(collection_id)),
+            track_index_state::track_updated_ms.eq(updated_at.timestamp_millis()),
+            track_index_state::track_id.eq(RowId::from(*id)),
+        ));
+        let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
+        debug_assert_eq!(1, rows_affected);
+        Ok(())
+    }
+}
+
+impl CustomPropertyRepo for crate::Connection<'_> {
+    fn load_track_custom_properties(&mut self, id: TrackId) -> RepoResult<Vec<(String, String)>> {
+        track_custom_property::table
+            .select((track_custom_property::key, track_custom_property::value))
+            .filter(track_custom_property::track_id.eq(RowId::from(id)))
+            .order_by(track_custom_property::key)
+            .load(self.as_mut())
+            .map_err(repo_error)
+    }
+
+    fn load_track_custom_property(&mut self, id: TrackId, key: &str) -> RepoResult<String> {
+        track_custom_property::table
+            .select(track_custom_property::value)
+            .filter(track_custom_property::track_id.eq(RowId::from(id)))
+            .filter(track_custom_property::key.eq(key))
+            .get_result(self.as_mut())
+            .map_err(repo_error)
+    }
+
+    fn store_track_custom_property(
+        &mut self,
+        id: TrackId,
+        key: &str,
+        value: &str,
+    ) -> RepoResult<()> {
+        let query = diesel::replace_into(track_custom_property::table).values((
+            track_custom_property::track_id.eq(RowId::from(id)),
+            track_custom_property::key.eq(key),
+            track_custom_property::value.eq(value),
+        ));
+        let rows_affected: usize = query.execute(self.as_mut()).map_err(repo_error)?;
+        debug_assert_eq!(1, rows_affected);
+        Ok(())
+    }
+
+    fn delete_track_custom_property(&mut self, id: TrackId, key: &str) -> RepoResult<()> {
+        let target = track_custom_property::table
+            .filter(track_custom_property::track_id.eq(RowId::from(id)))
+            .filter(track_custom_property::key.eq(key));
+        let rows_affected: usize = diesel::delete(target)
+            .execute(self.as_mut())
+            .map_err(repo_error)?;
+        debug_assert!(rows_affected <= 1);
+        if rows_affected < 1 {
+            return Err(RepoError::NotFound { entity: None });
+        }
+        Ok(())
+    }
 }
 
 impl ActorRepo for crate::Connection<'_> {
@@ -958,3 +1250,53 @@ impl ActorRepo for crate::Connection<'_> {
         Ok(actor_names.into_iter().collect())
     }
 }
+
+impl TagRepo for crate::Connection<'_> {
+    fn related_tags(
+        &mut self,
+        collection_id: CollectionId,
+        facet_id: Option<&FacetId<'_>>,
+        label: &Label<'_>,
+        limit: u64,
+    ) -> RepoResult<Vec<(String, usize)>> {
+        use diesel::dsl::count_star;
+
+        use crate::db::track_tag::schema::*;
+
+        let related_tag = diesel::alias!(track_tag as related_tag);
+        let related_facet = related_tag.field(track_tag::facet);
+        let related_label = related_tag.field(track_tag::label);
+        let facet = facet_id.map(FacetId::as_str);
+        let track_id_subselect =
+            track::table
+                .select(track::row_id)
+                .filter(track::media_source_id.eq_any(
+                    select_media_source_id_filtered_by_collection_id(collection_id),
+                ));
+        let rows = track_tag::table
+            .inner_join(
+                related_tag.on(related_tag
+                    .field(track_tag::track_id)
+                    .eq(track_tag::track_id)),
+            )
+            .filter(track_tag::track_id.eq_any(track_id_subselect))
+            .filter(track_tag::facet.is(facet))
+            .filter(track_tag::label.eq(label.as_str()))
+            .filter(related_facet.is(facet))
+            .filter(related_label.is_not_null())
+            .filter(related_label.ne(label.as_str()))
+            .group_by(related_label)
+            .select((related_label.assume_not_null(), count_star()))
+            .order_by((count_star().desc(), related_label))
+            .limit(limit.try_into().unwrap_or(i64::MAX))
+            .load::<(String, i64)>(self.as_mut())
+            .map_err(repo_error)?;
+        Ok(rows
+            .into_iter()
+            .map(|(label, count)| {
+                debug_assert!(count > 0);
+                (label, count as usize)
+            })
+            .collect())
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use diesel::{prelude::*, sql_types};
+use diesel::{expression::SqlLiteral, prelude::*, sql_types};
 
 use aoide_core::{
     audio::{
@@ -10,19 +10,21 @@ use aoide_core::{
         ChannelFlags, DurationMs,
     },
     tag::{FacetKey, Label},
+    track::title::Kind as TitleKind,
     util::clock::YyyyMmDdDateValue,
     PlaylistUid, TrackUid,
 };
 use aoide_core_api::{
     filtering::{
+        CustomPropertyEquals, FacetLabelsPredicate, FacetScoreAtLeast, FacetedTagsPredicate,
         FilterModifier, NumericPredicate, ScalarPredicate, StringCompare, StringFilter,
         StringPredicate,
     },
     tag::search::{FacetsFilter, Filter as TagFilter},
     track::search::{
         ActorPhraseFilter, ConditionFilter, DateTimeField, DateTimeFieldFilter,
-        Filter as TrackFilter, NumericField, NumericFieldFilter, PhraseFieldFilter, SortField,
-        SortOrder, StringField, TitlePhraseFilter,
+        Filter as TrackFilter, NumericField, NumericFieldFilter, PhraseFieldFilter, Scope,
+        SortField, SortOrder, StringField, TitlePhraseFilter,
     },
     SortDirection,
 };
@@ -35,6 +37,7 @@ use crate::{
         track::{encode_advisory_rating, encode_search_scope},
         track_actor::schema::*,
         track_cue::schema::*,
+        track_custom_property::schema::*,
         track_tag::schema::*,
         track_title::schema::*,
         view_track_search::schema::*,
@@ -48,6 +51,12 @@ use crate::{
 };
 
 define_sql_function! { fn ifnull<ST: sql_types::SingleValue>(x: sql_types::Nullable<ST>, y: ST) -> ST; }
+define_sql_function! { fn trim(x: sql_types::Text, y: sql_types::Text) -> sql_types::Text; }
+
+/// Characters that are stripped by `TRIM()` when checking for blank text.
+///
+/// The single-argument `TRIM()` of SQLite only strips spaces.
+const BLANK_TEXT_CHARACTERS: &str = " \t\n\r";
 
 type TrackSearchExpressionBoxed<'db> = Box<
     dyn BoxableExpression<view_track_search::table, DbBackend, SqlType = sql_types::Bool> + 'db,
@@ -74,6 +83,17 @@ pub(crate) trait TrackSearchQueryTransform<'db> {
     ) -> view_track_search::BoxedQuery<'db, DbBackend>;
 }
 
+/// Correlated subselect of the main album title for sorting.
+///
+/// Uses the least name if a track has multiple main album titles.
+fn sql_album_title_subselect() -> SqlLiteral<sql_types::Nullable<sql_types::Text>> {
+    diesel::dsl::sql(&format!(
+        "(SELECT MIN(name) FROM track_title WHERE track_id=view_track_search.row_id AND scope={scope} AND kind={kind})",
+        scope = encode_search_scope(Scope::Album),
+        kind = crate::db::track_title::encode_kind(TitleKind::Main),
+    ))
+}
+
 impl<'db> TrackSearchQueryTransform<'db> for SortOrder {
     #[allow(clippy::too_many_lines)] // TODO
     fn apply_to_query(
@@ -82,6 +102,13 @@ impl<'db> TrackSearchQueryTransform<'db> for SortOrder {
     ) -> view_track_search::BoxedQuery<'db, DbBackend> {
         let direction = self.direction;
         match self.field {
+            SortField::AlbumTitle => {
+                let album_title = sql_album_title_subselect();
+                match direction {
+                    SortDirection::Ascending => query.then_order_by(album_title.asc()),
+                    SortDirection::Descending => query.then_order_by(album_title.desc()),
+                }
+            }
             SortField::AudioBitrateBps => match direction {
                 SortDirection::Ascending => {
                     query.then_order_by(view_track_search::audio_bitrate_bps.asc())
@@ -978,9 +1005,34 @@ fn build_condition_filter_expression(
             view_track_search::media_source_id
                 .ne_all(media_tracker_source::table.select(media_tracker_source::source_id)),
         ),
+        ConditionFilter::SourceNeverSynchronized => {
+            Box::new(view_track_search::last_synchronized_rev.is_null())
+        }
+        ConditionFilter::TrackTitleBlank => Box::new(
+            view_track_search::row_id
+                .ne_all(select_track_ids_with_non_blank_main_title(Scope::Track)),
+        ),
+        ConditionFilter::AlbumTitleBlank => Box::new(
+            view_track_search::row_id
+                .ne_all(select_track_ids_with_non_blank_main_title(Scope::Album)),
+        ),
     }
 }
 
+/// Select all tracks with a main title that contains non-whitespace characters.
+///
+/// Null, empty and whitespace-only titles are considered as blank.
+fn select_track_ids_with_non_blank_main_title(
+    scope: Scope,
+) -> track_title::BoxedQuery<'static, DbBackend, sql_types::BigInt> {
+    track_title::table
+        .select(track_title::track_id)
+        .filter(track_title::scope.eq(encode_search_scope(scope)))
+        .filter(track_title::kind.eq(crate::db::track_title::encode_kind(TitleKind::Main)))
+        .filter(trim(track_title::name, BLANK_TEXT_CHARACTERS).ne(""))
+        .into_boxed()
+}
+
 #[allow(clippy::too_many_lines)] // TODO
 fn select_track_ids_matching_tag_filter(
     filter: &TagFilter,
@@ -1160,6 +1212,68 @@ fn build_tag_filter_expression(filter: &TagFilter) -> TrackSearchExpressionBoxed
     }
 }
 
+fn select_track_ids_matching_facet_labels_predicate(
+    predicate: &FacetLabelsPredicate,
+) -> track_tag::BoxedQuery<'_, DbBackend, sql_types::BigInt> {
+    let FacetLabelsPredicate {
+        modifier: _,
+        facet,
+        any_of,
+    } = predicate;
+    let mut select = track_tag::table.select(track_tag::track_id).into_boxed();
+    select = if let Some(facet_id) = facet.as_ref() {
+        select.filter(track_tag::facet.eq(facet_id.as_str()))
+    } else {
+        select.filter(track_tag::facet.is_null())
+    };
+    if !any_of.is_empty() {
+        // Tags with any of the given labels.
+        select = select.filter(track_tag::label.eq_any(any_of.iter().map(Label::as_str)));
+    }
+    select
+}
+
+fn build_faceted_tags_filter_expression(
+    predicate: &FacetedTagsPredicate,
+) -> TrackSearchExpressionBoxed<'_> {
+    predicate
+        .all_of
+        .iter()
+        .fold(dummy_true_expression(), |expr, predicate| {
+            let subselect = select_track_ids_matching_facet_labels_predicate(predicate);
+            match predicate.modifier {
+                None => Box::new(expr.and(view_track_search::row_id.eq_any(subselect))),
+                Some(FilterModifier::Complement) => {
+                    Box::new(expr.and(view_track_search::row_id.ne_all(subselect)))
+                }
+            }
+        })
+}
+
+fn build_facet_score_at_least_filter_expression(
+    predicate: &FacetScoreAtLeast,
+) -> TrackSearchExpressionBoxed<'_> {
+    let FacetScoreAtLeast { facet_id, min } = predicate;
+    let subselect = track_tag::table
+        .select(track_tag::track_id)
+        .filter(track_tag::facet.eq(facet_id.as_str()))
+        .filter(track_tag::score.ge(*min))
+        .into_boxed();
+    Box::new(view_track_search::row_id.eq_any(subselect))
+}
+
+fn build_custom_property_equals_filter_expression(
+    predicate: &CustomPropertyEquals,
+) -> TrackSearchExpressionBoxed<'_> {
+    let CustomPropertyEquals { key, value } = predicate;
+    let subselect = track_custom_property::table
+        .select(track_custom_property::track_id)
+        .filter(track_custom_property::key.eq(key.as_str()))
+        .filter(track_custom_property::value.eq(value.as_str()))
+        .into_boxed();
+    Box::new(view_track_search::row_id.eq_any(subselect))
+}
+
 fn build_cue_label_filter_expression<'a>(
     filter: &StringFilter<'_>,
 ) -> TrackSearchExpressionBoxed<'a> {
@@ -1387,6 +1501,13 @@ impl TrackSearchExpressionBoxedBuilder for TrackFilter {
             DateTime(filter) => build_datetime_field_filter_expression(filter),
             Condition(filter) => build_condition_filter_expression(*filter),
             Tag(filter) => build_tag_filter_expression(filter),
+            FacetedTags(predicate) => build_faceted_tags_filter_expression(predicate),
+            Self::FacetScoreAtLeast(predicate) => {
+                build_facet_score_at_least_filter_expression(predicate)
+            }
+            Self::CustomPropertyEquals(predicate) => {
+                build_custom_property_equals_filter_expression(predicate)
+            }
             CueLabel(filter) => build_cue_label_filter_expression(filter),
             AnyTrackUid(any_track_uid) => build_any_track_uid_filter_expression(any_track_uid),
             AnyPlaylistUid(any_playlist_uid) => {
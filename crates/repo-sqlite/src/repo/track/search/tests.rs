@@ -6,29 +6,33 @@ use nonicle::CanonicalizeInto as _;
 use aoide_core::{
     audio::DurationMs,
     collection::{Entity as CollectionEntity, EntityHeader as CollectionHeader},
-    media::{
-        self,
-        content::{AudioContentMetadata, ContentLink},
+    media::content::AudioContentMetadata,
+    tag::{FacetId, FacetKey, Label, PlainTag, Score, ScoreValue, TagsMap, TagsMapInner},
+    track::tag::{
+        FACET_ID_COMMENT, FACET_ID_DANCEABILITY, FACET_ID_ENERGY, FACET_ID_GENRE, FACET_ID_MOOD,
     },
-    tag::{FacetKey, Label, PlainTag, TagsMap, TagsMapInner},
-    track::tag::FACET_ID_COMMENT,
     util::clock::OffsetDateTimeMs,
-    Collection, Track, TrackBody, TrackEntity, TrackHeader,
+    Collection,
 };
 use aoide_core_api::{
-    filtering::StringPredicate,
+    filtering::{
+        FacetLabelsPredicate, FacetScoreAtLeast, FacetedTagsPredicate, FilterModifier,
+        StringPredicate, MAX_NESTING_DEPTH,
+    },
     tag::search::{FacetsFilter, Filter as TagFilter},
-    track::search::{Filter as TrackFilter, Scope, TitlePhraseFilter},
+    track::search::{
+        ConditionFilter, Filter as TrackFilter, PhraseFieldFilter, Scope, SortField, SortOrder,
+        StringField, TitlePhraseFilter,
+    },
+    SortDirection,
 };
 use aoide_repo::{
-    collection::EntityRepo as _,
-    media::source::CollectionRepo as _,
-    track::{CollectionRepo, EntityRepo as _},
-    CollectionId, RecordCollector, ReservableRecordCollector,
+    collection::EntityRepo as _, track::CollectionRepo, CollectionId, RecordCollector,
+    ReservableRecordCollector,
 };
 
 use crate::{
-    repo::tests::vfs_media_source_config,
+    repo::tests::{insert_track, vfs_media_source_config},
     tests::{establish_connection, TestResult},
 };
 
@@ -70,59 +74,36 @@ fn create_single_track_collection_with_tags(
     let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
     let collection_id =
         db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
-    let created_at = OffsetDateTimeMs::now_local();
-    let media_source = media::Source {
-        collected_at: created_at.clone(),
-        content: media::Content {
-            link: ContentLink {
-                path: "/home/test/file.mp3".into(),
-                rev: None,
-            },
-            r#type: "audio/mpeg".parse().unwrap(),
-            metadata_flags: Default::default(),
-            metadata: AudioContentMetadata {
-                duration: Some(DurationMs::new(1.0)),
-                ..Default::default()
-            }
-            .into(),
-            digest: None,
-        },
-        artwork: Default::default(),
-    };
-    let media_source_id = db
-        .insert_media_source(collection_id, created_at.clone(), &media_source)?
-        .id;
-    let mut track = Track::new_from_media_source(media_source);
-    // Track title with Unicode characters.
-    track.set_track_title("ÄäÖöÜüÉéÈè");
-    // Album title with ASCII characters.
-    track.set_album_title("AaOoUuEeEe");
-    let plain_tags = (1..10)
-        .flat_map(|i| {
-            [
-                PlainTag {
-                    label: Some(Label::from_unchecked(format!("Tag\\{i}"))),
-                    score: Default::default(),
-                },
-                PlainTag {
-                    label: Some(Label::from_unchecked(format!("tag\\{i}"))),
-                    score: Default::default(),
-                },
-            ]
-        })
-        .collect::<Vec<_>>();
-    let tags = [(FacetKey::default(), plain_tags)]
-        .into_iter()
-        .collect::<TagsMapInner<'static>>();
-    track.tags = TagsMap::new(tags).canonicalize_into();
-    let entity_body = TrackBody {
-        track,
-        updated_at: created_at,
-        last_synchronized_rev: None,
-        content_url: None,
-    };
-    let track_entity = TrackEntity::new(TrackHeader::initial_random(), entity_body);
-    db.insert_track_entity(media_source_id, &track_entity)?;
+    insert_track(db, collection_id, "/home/test/file.mp3", |track_entity| {
+        let track = &mut track_entity.body.track;
+        track.media_source.content.metadata = AudioContentMetadata {
+            duration: Some(DurationMs::new(1.0)),
+            ..Default::default()
+        }
+        .into();
+        // Track title with Unicode characters.
+        track.set_track_title("ÄäÖöÜüÉéÈè");
+        // Album title with ASCII characters.
+        track.set_album_title("AaOoUuEeEe");
+        let plain_tags = (1..10)
+            .flat_map(|i| {
+                [
+                    PlainTag {
+                        label: Some(Label::from_unchecked(format!("Tag\\{i}"))),
+                        score: Default::default(),
+                    },
+                    PlainTag {
+                        label: Some(Label::from_unchecked(format!("tag\\{i}"))),
+                        score: Default::default(),
+                    },
+                ]
+            })
+            .collect::<Vec<_>>();
+        let tags = [(FacetKey::default(), plain_tags)]
+            .into_iter()
+            .collect::<TagsMapInner<'static>>();
+        track.tags = TagsMap::new(tags).canonicalize_into();
+    })?;
     Ok(collection_id)
 }
 
@@ -216,6 +197,498 @@ fn filter_plain_tags() -> TestResult<()> {
     Ok(())
 }
 
+fn create_collection_with_faceted_tags(db: &mut crate::Connection<'_>) -> TestResult<CollectionId> {
+    let collection = Collection {
+        title: "Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
+    let collection_id =
+        db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
+    let tracks = [
+        ("rock.mp3", "Rock", Some("Energetic")),
+        ("jazz.mp3", "Jazz", Some("Calm")),
+        ("pop.mp3", "Pop", None),
+    ];
+    for (path, genre, mood) in tracks {
+        insert_track(
+            db,
+            collection_id,
+            &format!("/home/test/{path}"),
+            |track_entity| {
+                let track = &mut track_entity.body.track;
+                let mut tags = TagsMap::<'static>::default();
+                tags.insert(
+                    FACET_ID_GENRE.clone_owned(),
+                    PlainTag {
+                        label: Some(Label::from_unchecked(genre)),
+                        score: Default::default(),
+                    },
+                );
+                if let Some(mood) = mood {
+                    tags.insert(
+                        FACET_ID_MOOD.clone_owned(),
+                        PlainTag {
+                            label: Some(Label::from_unchecked(mood)),
+                            score: Default::default(),
+                        },
+                    );
+                }
+                track.tags = tags.canonicalize_into();
+            },
+        )?;
+    }
+    Ok(collection_id)
+}
+
+fn facet_labels(
+    modifier: Option<FilterModifier>,
+    facet: &FacetId<'_>,
+    any_of: &[&str],
+) -> FacetLabelsPredicate {
+    FacetLabelsPredicate {
+        modifier,
+        facet: FacetKey::from(facet.clone_owned()),
+        any_of: any_of
+            .iter()
+            .map(|label| Label::from_unchecked((*label).to_owned()))
+            .collect(),
+    }
+}
+
+fn count_faceted_tags_matches(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    all_of: Vec<FacetLabelsPredicate>,
+) -> TestResult<usize> {
+    let filter = TrackFilter::FacetedTags(FacetedTagsPredicate { all_of });
+    let count = db.search_tracks(
+        collection_id,
+        &Default::default(),
+        Some(&filter),
+        Default::default(),
+        &mut DummyCollector::new(),
+    )?;
+    Ok(count)
+}
+
+#[test]
+fn filter_faceted_tags_and_across_facets() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_faceted_tags(&mut db)?;
+    assert_eq!(
+        1,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![
+                facet_labels(None, FACET_ID_GENRE, &["Rock"]),
+                facet_labels(None, FACET_ID_MOOD, &["Energetic"]),
+            ],
+        )?
+    );
+    assert_eq!(
+        0,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![
+                facet_labels(None, FACET_ID_GENRE, &["Rock"]),
+                facet_labels(None, FACET_ID_MOOD, &["Calm"]),
+            ],
+        )?
+    );
+    // No predicates match all tracks.
+    assert_eq!(
+        3,
+        count_faceted_tags_matches(&mut db, collection_id, vec![])?
+    );
+    Ok(())
+}
+
+#[test]
+fn filter_faceted_tags_or_within_facet() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_faceted_tags(&mut db)?;
+    assert_eq!(
+        2,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![facet_labels(None, FACET_ID_GENRE, &["Rock", "Jazz"])],
+        )?
+    );
+    // Labels are matched per facet.
+    assert_eq!(
+        0,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![facet_labels(None, FACET_ID_MOOD, &["Rock", "Jazz"])],
+        )?
+    );
+    // Any label of the facet.
+    assert_eq!(
+        2,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![facet_labels(None, FACET_ID_MOOD, &[])],
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn filter_faceted_tags_complement() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_faceted_tags(&mut db)?;
+    assert_eq!(
+        2,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![facet_labels(
+                Some(FilterModifier::Complement),
+                FACET_ID_GENRE,
+                &["Rock"],
+            )],
+        )?
+    );
+    // Tracks without any mood.
+    assert_eq!(
+        1,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![facet_labels(
+                Some(FilterModifier::Complement),
+                FACET_ID_MOOD,
+                &[],
+            )],
+        )?
+    );
+    // Combined with a positive predicate.
+    assert_eq!(
+        1,
+        count_faceted_tags_matches(
+            &mut db,
+            collection_id,
+            vec![
+                facet_labels(None, FACET_ID_GENRE, &["Rock", "Jazz"]),
+                facet_labels(Some(FilterModifier::Complement), FACET_ID_MOOD, &["Calm"]),
+            ],
+        )?
+    );
+    Ok(())
+}
+
+fn create_collection_with_scored_tags(db: &mut crate::Connection<'_>) -> TestResult<CollectionId> {
+    let collection = Collection {
+        title: "Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
+    let collection_id =
+        db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
+    // (path, energy, danceability)
+    let tracks = [
+        ("energetic_dance.mp3", Some(0.9), Some(0.8)),
+        ("energetic.mp3", Some(0.7), Some(0.3)),
+        ("dance.mp3", Some(0.4), Some(0.9)),
+        ("unscored.mp3", None, None),
+    ];
+    for (path, energy, danceability) in tracks {
+        insert_track(
+            db,
+            collection_id,
+            &format!("/home/test/{path}"),
+            |track_entity| {
+                let track = &mut track_entity.body.track;
+                let mut tags = TagsMap::<'static>::default();
+                for (facet_id, score) in [
+                    (FACET_ID_ENERGY, energy),
+                    (FACET_ID_DANCEABILITY, danceability),
+                ] {
+                    let Some(score) = score else {
+                        continue;
+                    };
+                    tags.insert(
+                        facet_id.clone_owned(),
+                        PlainTag {
+                            label: None,
+                            score: Score::new_unchecked(score),
+                        },
+                    );
+                }
+                track.tags = tags.canonicalize_into();
+            },
+        )?;
+    }
+    Ok(collection_id)
+}
+
+fn search_content_paths_by_filter(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    filter: &TrackFilter,
+) -> TestResult<Vec<String>> {
+    let mut collector = Vec::new();
+    db.search_tracks(
+        collection_id,
+        &Default::default(),
+        Some(filter),
+        &[SortOrder {
+            field: SortField::ContentPath,
+            direction: SortDirection::Ascending,
+        }],
+        &mut collector,
+    )?;
+    let content_paths = collector
+        .into_iter()
+        .map(|(_, entity)| {
+            entity
+                .body
+                .track
+                .media_source
+                .content
+                .link
+                .path
+                .as_str()
+                .trim_start_matches("/home/test/")
+                .to_owned()
+        })
+        .collect();
+    Ok(content_paths)
+}
+
+fn facet_score_at_least(facet_id: &FacetId<'_>, min: ScoreValue) -> TrackFilter {
+    TrackFilter::FacetScoreAtLeast(FacetScoreAtLeast {
+        facet_id: facet_id.clone_owned(),
+        min,
+    })
+}
+
+#[test]
+fn filter_by_facet_score_threshold() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_scored_tags(&mut db)?;
+    // The threshold is inclusive.
+    assert_eq!(
+        vec!["energetic.mp3", "energetic_dance.mp3"],
+        search_content_paths_by_filter(
+            &mut db,
+            collection_id,
+            &facet_score_at_least(FACET_ID_ENERGY, 0.7),
+        )?
+    );
+    assert!(search_content_paths_by_filter(
+        &mut db,
+        collection_id,
+        &facet_score_at_least(FACET_ID_ENERGY, 0.95),
+    )?
+    .is_empty());
+    Ok(())
+}
+
+#[test]
+fn filter_by_combined_facet_score_thresholds() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_scored_tags(&mut db)?;
+    let filter = TrackFilter::All(vec![
+        facet_score_at_least(FACET_ID_ENERGY, 0.7),
+        facet_score_at_least(FACET_ID_DANCEABILITY, 0.7),
+    ]);
+    assert_eq!(
+        vec!["energetic_dance.mp3"],
+        search_content_paths_by_filter(&mut db, collection_id, &filter)?
+    );
+    let filter = TrackFilter::Any(vec![
+        facet_score_at_least(FACET_ID_ENERGY, 0.7),
+        facet_score_at_least(FACET_ID_DANCEABILITY, 0.7),
+    ]);
+    assert_eq!(
+        3,
+        search_content_paths_by_filter(&mut db, collection_id, &filter)?.len()
+    );
+    Ok(())
+}
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+fn create_collection_with_collected_tracks(
+    db: &mut crate::Connection<'_>,
+) -> TestResult<CollectionId> {
+    let collection = Collection {
+        title: "Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
+    let collection_id =
+        db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
+    // Tracks collected on consecutive weeks, only the second has been synchronized.
+    let tracks = [
+        ("week1.mp3", DAY_MILLIS, false),
+        ("week2.mp3", 8 * DAY_MILLIS, true),
+        ("week3.mp3", 15 * DAY_MILLIS, false),
+    ];
+    for (path, collected_ms, synchronized) in tracks {
+        insert_track(
+            db,
+            collection_id,
+            &format!("/home/test/{path}"),
+            |track_entity| {
+                let collected_at = OffsetDateTimeMs::from_timestamp_millis(collected_ms);
+                track_entity.body.track.media_source.collected_at = collected_at.clone();
+                track_entity.body.updated_at = collected_at;
+                track_entity.body.last_synchronized_rev =
+                    synchronized.then_some(track_entity.hdr.rev);
+            },
+        )?;
+    }
+    Ok(collection_id)
+}
+
+fn count_filter_matches(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    filter: &TrackFilter,
+) -> TestResult<usize> {
+    let count = db.search_tracks(
+        collection_id,
+        &Default::default(),
+        Some(filter),
+        Default::default(),
+        &mut DummyCollector::new(),
+    )?;
+    Ok(count)
+}
+
+#[test]
+fn filter_collected_at_window() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_collected_tracks(&mut db)?;
+    assert_eq!(
+        1,
+        count_filter_matches(
+            &mut db,
+            collection_id,
+            &TrackFilter::collected_at_window(
+                Some(OffsetDateTimeMs::from_timestamp_millis(7 * DAY_MILLIS)),
+                Some(OffsetDateTimeMs::from_timestamp_millis(14 * DAY_MILLIS)),
+            ),
+        )?
+    );
+    // The upper bound is exclusive
+    assert_eq!(
+        1,
+        count_filter_matches(
+            &mut db,
+            collection_id,
+            &TrackFilter::collected_at_window(
+                None,
+                Some(OffsetDateTimeMs::from_timestamp_millis(8 * DAY_MILLIS)),
+            ),
+        )?
+    );
+    // The lower bound is inclusive
+    assert_eq!(
+        2,
+        count_filter_matches(
+            &mut db,
+            collection_id,
+            &TrackFilter::collected_at_window(
+                Some(OffsetDateTimeMs::from_timestamp_millis(8 * DAY_MILLIS)),
+                None,
+            ),
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn filter_source_never_synchronized() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_collected_tracks(&mut db)?;
+    let filter = TrackFilter::Condition(ConditionFilter::SourceNeverSynchronized);
+    assert_eq!(2, count_filter_matches(&mut db, collection_id, &filter)?);
+    assert_eq!(
+        1,
+        count_filter_matches(&mut db, collection_id, &TrackFilter::Not(Box::new(filter)))?
+    );
+    Ok(())
+}
+
+fn create_collection_with_blank_titles(db: &mut crate::Connection<'_>) -> TestResult<CollectionId> {
+    let collection = Collection {
+        title: "Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
+    let collection_id =
+        db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
+    // Only the last track has a non-blank track title and album title.
+    let tracks = [
+        ("missing.mp3", None),
+        ("empty.mp3", Some("")),
+        ("whitespace.mp3", Some(" \t ")),
+        ("title.mp3", Some("Title")),
+    ];
+    for (path, title) in tracks {
+        insert_track(
+            db,
+            collection_id,
+            &format!("/home/test/{path}"),
+            |track_entity| {
+                let track = &mut track_entity.body.track;
+                if let Some(title) = title {
+                    track.set_track_title(title);
+                    track.set_album_title(title);
+                }
+            },
+        )?;
+    }
+    Ok(collection_id)
+}
+
+#[test]
+fn filter_blank_titles() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_blank_titles(&mut db)?;
+    for condition in [
+        ConditionFilter::TrackTitleBlank,
+        ConditionFilter::AlbumTitleBlank,
+    ] {
+        let filter = TrackFilter::Condition(condition);
+        assert_eq!(3, count_filter_matches(&mut db, collection_id, &filter)?);
+        assert_eq!(
+            1,
+            count_filter_matches(&mut db, collection_id, &TrackFilter::Not(Box::new(filter)))?
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn search_title_phrase() -> TestResult<()> {
     let mut db = establish_connection()?;
@@ -272,3 +745,236 @@ fn search_title_phrase() -> TestResult<()> {
     // );
     Ok(())
 }
+
+fn create_collection_with_album_tracks(db: &mut crate::Connection<'_>) -> TestResult<CollectionId> {
+    let collection = Collection {
+        title: "Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
+    let collection_id =
+        db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
+    // Inserted in an arbitrary order that differs from the sort order.
+    let tracks = [
+        ("b2.mp3", Some("B"), Some(2)),
+        ("a2.mp3", Some("A"), Some(2)),
+        ("b1.mp3", Some("B"), Some(1)),
+        ("a1.mp3", Some("A"), Some(1)),
+        ("single.mp3", None, Some(1)),
+        ("a1-copy.mp3", Some("A"), Some(1)),
+    ];
+    for (path, album_title, track_number) in tracks {
+        insert_track(
+            db,
+            collection_id,
+            &format!("/home/test/{path}"),
+            |track_entity| {
+                let track = &mut track_entity.body.track;
+                if let Some(album_title) = album_title {
+                    track.set_album_title(album_title);
+                }
+                track.indexes.track.number = track_number;
+            },
+        )?;
+    }
+    Ok(collection_id)
+}
+
+fn search_content_paths(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    ordering: &[SortOrder],
+) -> TestResult<Vec<String>> {
+    let mut collector = Vec::new();
+    db.search_tracks(
+        collection_id,
+        &Default::default(),
+        None,
+        ordering,
+        &mut collector,
+    )?;
+    let content_paths = collector
+        .into_iter()
+        .map(|(_, entity)| {
+            entity
+                .body
+                .track
+                .media_source
+                .content
+                .link
+                .path
+                .as_str()
+                .trim_start_matches("/home/test/")
+                .to_owned()
+        })
+        .collect();
+    Ok(content_paths)
+}
+
+#[test]
+fn sort_by_multiple_fields() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_album_tracks(&mut db)?;
+
+    // Tracks without an album title are sorted first. Tracks that tie
+    // on all keys are sorted by their insertion order.
+    assert_eq!(
+        vec![
+            "single.mp3",
+            "a1.mp3",
+            "a1-copy.mp3",
+            "a2.mp3",
+            "b1.mp3",
+            "b2.mp3",
+        ],
+        search_content_paths(
+            &mut db,
+            collection_id,
+            &[
+                SortOrder {
+                    field: SortField::AlbumTitle,
+                    direction: SortDirection::Ascending,
+                },
+                SortOrder {
+                    field: SortField::TrackNumber,
+                    direction: SortDirection::Ascending,
+                },
+            ],
+        )?
+    );
+
+    // The direction of each key is independent.
+    assert_eq!(
+        vec![
+            "b2.mp3",
+            "b1.mp3",
+            "a2.mp3",
+            "a1.mp3",
+            "a1-copy.mp3",
+            "single.mp3",
+        ],
+        search_content_paths(
+            &mut db,
+            collection_id,
+            &[
+                SortOrder {
+                    field: SortField::AlbumTitle,
+                    direction: SortDirection::Descending,
+                },
+                SortOrder {
+                    field: SortField::TrackNumber,
+                    direction: SortDirection::Descending,
+                },
+            ],
+        )?
+    );
+    assert_eq!(
+        vec![
+            "a2.mp3",
+            "b2.mp3",
+            "single.mp3",
+            "a1.mp3",
+            "a1-copy.mp3",
+            "b1.mp3",
+        ],
+        search_content_paths(
+            &mut db,
+            collection_id,
+            &[
+                SortOrder {
+                    field: SortField::TrackNumber,
+                    direction: SortDirection::Descending,
+                },
+                SortOrder {
+                    field: SortField::AlbumTitle,
+                    direction: SortDirection::Ascending,
+                },
+            ],
+        )?
+    );
+    Ok(())
+}
+
+fn content_path_phrase(term: &str) -> TrackFilter {
+    TrackFilter::Phrase(PhraseFieldFilter {
+        fields: vec![StringField::ContentPath],
+        terms: vec![term.to_owned()],
+    })
+}
+
+#[test]
+fn filter_by_boolean_combination_of_phrases() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_album_tracks(&mut db)?;
+
+    // b AND (2 OR copy)
+    assert_eq!(
+        vec!["b2.mp3"],
+        search_content_paths_by_filter(
+            &mut db,
+            collection_id,
+            &TrackFilter::All(vec![
+                content_path_phrase("b"),
+                TrackFilter::Any(vec![content_path_phrase("2"), content_path_phrase("copy")]),
+            ])
+        )?
+    );
+    // (b AND 2) OR copy
+    assert_eq!(
+        vec!["a1-copy.mp3", "b2.mp3"],
+        search_content_paths_by_filter(
+            &mut db,
+            collection_id,
+            &TrackFilter::Any(vec![
+                TrackFilter::All(vec![content_path_phrase("b"), content_path_phrase("2")]),
+                content_path_phrase("copy"),
+            ])
+        )?
+    );
+    // NOT a
+    assert_eq!(
+        vec!["b1.mp3", "b2.mp3", "single.mp3"],
+        search_content_paths_by_filter(
+            &mut db,
+            collection_id,
+            &TrackFilter::Not(Box::new(content_path_phrase("a")))
+        )?
+    );
+    // NOT (b OR single OR 1-copy) AND 1
+    assert_eq!(
+        vec!["a1.mp3"],
+        search_content_paths_by_filter(
+            &mut db,
+            collection_id,
+            &TrackFilter::All(vec![
+                TrackFilter::Not(Box::new(TrackFilter::Any(vec![
+                    content_path_phrase("b"),
+                    content_path_phrase("single"),
+                    content_path_phrase("1-copy"),
+                ]))),
+                content_path_phrase("1"),
+            ])
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn reject_deeply_nested_filters() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection_with_album_tracks(&mut db)?;
+
+    let filter = (0..=MAX_NESTING_DEPTH).fold(
+        TrackFilter::Condition(ConditionFilter::SourceNeverSynchronized),
+        |filter, _| TrackFilter::Not(Box::new(filter)),
+    );
+    assert_eq!(MAX_NESTING_DEPTH + 1, filter.nesting_depth());
+    assert!(count_filter_matches(&mut db, collection_id, &filter).is_err());
+    Ok(())
+}
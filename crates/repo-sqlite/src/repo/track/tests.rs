@@ -0,0 +1,759 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use diesel::prelude::*;
+use mime::IMAGE_JPEG;
+use nonicle::CanonicalizeInto as _;
+
+use aoide_core::{
+    collection::{Entity as CollectionEntity, EntityHeader as CollectionHeader},
+    media::artwork::{ApicType, Artwork, ArtworkImage, LinkedArtwork},
+    playlist::{Entry, Item, TrackItem},
+    tag::{FacetId, FacetKey, Label, PlainTag, TagsMap, TagsMapInner},
+    track::{actor, title, Actor, Cue, Title},
+    util::clock::OffsetDateTimeMs,
+    Collection, EncodedEntityUid, EntityUid, Playlist, PlaylistEntity, PlaylistHeader, TrackEntity,
+    TrackUid,
+};
+use aoide_core_api::{
+    filtering::{CustomPropertyEquals, StringPredicate},
+    track::search::{FieldGroups, Filter},
+    Pagination,
+};
+use aoide_repo::{
+    collection::EntityRepo as _,
+    playlist::{EntityRepo as _, EntryRepo as _},
+    track::{
+        CollectionRepo as _, CustomPropertyRepo as _, DeleteOutcome, EntityRepo as _,
+        IndexWatermark, RecordHeader, TagRepo as _,
+    },
+    CollectionId, EntityKind, RepoError,
+};
+
+use super::{select_tracks_in_collection, TRACK_UIDS_CHUNK_SIZE};
+use crate::{
+    db::{
+        media_source::{schema::media_source, select_row_id_filtered_by_content_path_predicate},
+        track::schema::track,
+        track_actor::schema::track_actor,
+        track_cue::schema::track_cue,
+        track_custom_property::schema::track_custom_property,
+        track_tag::schema::track_tag,
+        track_title::schema::track_title,
+        view_track_search::schema::view_track_search,
+    },
+    repo::tests::{insert_track, vfs_media_source_config},
+    tests::{assert_query_plan_uses_indexes, establish_connection, TestResult},
+};
+
+fn create_collection(db: &mut crate::Connection<'_>) -> TestResult<CollectionId> {
+    let collection = Collection {
+        title: "Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: vfs_media_source_config(),
+    };
+    let collection_entity = CollectionEntity::new(CollectionHeader::initial_random(), collection);
+    let collection_id =
+        db.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &collection_entity)?;
+    Ok(collection_id)
+}
+
+fn create_tracks(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    count: usize,
+) -> TestResult<Vec<TrackUid>> {
+    let mut uids = Vec::with_capacity(count);
+    for i in 0..count {
+        let (_, track_entity) = insert_track(
+            db,
+            collection_id,
+            &format!("/home/test/file{i}.mp3"),
+            |_| {},
+        )?;
+        uids.push(track_entity.hdr.uid.clone());
+    }
+    Ok(uids)
+}
+
+fn create_track_with_child_rows(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+) -> TestResult<TrackUid> {
+    let (track_id, track_entity) = insert_track(
+        db,
+        collection_id,
+        "/home/test/artwork.mp3",
+        |track_entity| {
+            let track = &mut track_entity.body.track;
+            track.media_source.artwork = Some(Artwork::Linked(LinkedArtwork {
+                uri: "file://folder.jpg".to_owned(),
+                image: ArtworkImage {
+                    media_type: IMAGE_JPEG,
+                    apic_type: ApicType::CoverFront,
+                    data_size: 1024,
+                    image_size: None,
+                    color: None,
+                    digest: None,
+                    thumbnail: None,
+                },
+            }));
+            track.titles = vec![Title {
+                kind: title::Kind::Main,
+                name: "Title".to_owned(),
+            }]
+            .canonicalize_into();
+            track.actors = vec![Actor {
+                role: actor::Role::Artist,
+                kind: actor::Kind::Summary,
+                name: "Artist".to_owned(),
+                role_notes: None,
+            }]
+            .canonicalize_into();
+            let tags = [(
+                FacetKey::default(),
+                vec![PlainTag {
+                    label: Some(Label::from_unchecked("Tag")),
+                    score: Default::default(),
+                }],
+            )]
+            .into_iter()
+            .collect::<TagsMapInner<'static>>();
+            track.tags = TagsMap::new(tags).canonicalize_into();
+            track.cues = vec![Cue {
+                bank_index: 0,
+                slot_index: None,
+                in_marker: None,
+                out_marker: None,
+                kind: None,
+                label: Some("Cue".to_owned()),
+                color: None,
+                flags: Default::default(),
+            }]
+            .canonicalize_into();
+        },
+    )?;
+    db.store_track_custom_property(track_id, "cue_count", "1")?;
+    Ok(track_entity.hdr.uid.clone())
+}
+
+/// Count the rows of all child tables that reference a track,
+/// either in total or only those without a track.
+fn count_track_child_rows(
+    db: &mut crate::Connection<'_>,
+    orphaned_only: bool,
+) -> TestResult<Vec<(&'static str, i64)>> {
+    // Boxed subselects are needed for tables that are not allowed
+    // to appear in the same query.
+    let track_ids = || track::table.select(track::row_id).into_boxed();
+    let counts = if orphaned_only {
+        vec![
+            (
+                "track_title",
+                track_title::table
+                    .filter(track_title::track_id.ne_all(track_ids()))
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+            (
+                "track_actor",
+                track_actor::table
+                    .filter(track_actor::track_id.ne_all(track_ids()))
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+            (
+                "track_tag",
+                track_tag::table
+                    .filter(track_tag::track_id.ne_all(track_ids()))
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+            (
+                "track_cue",
+                track_cue::table
+                    .filter(track_cue::track_id.ne_all(track_ids()))
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+            (
+                "track_custom_property",
+                track_custom_property::table
+                    .filter(track_custom_property::track_id.ne_all(track_ids()))
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+            (
+                "media_source",
+                media_source::table
+                    .filter(
+                        media_source::row_id.ne_all(track::table.select(track::media_source_id)),
+                    )
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+        ]
+    } else {
+        vec![
+            (
+                "track_title",
+                track_title::table.count().get_result(db.as_mut())?,
+            ),
+            (
+                "track_actor",
+                track_actor::table.count().get_result(db.as_mut())?,
+            ),
+            (
+                "track_tag",
+                track_tag::table.count().get_result(db.as_mut())?,
+            ),
+            (
+                "track_cue",
+                track_cue::table.count().get_result(db.as_mut())?,
+            ),
+            (
+                "track_custom_property",
+                track_custom_property::table
+                    .count()
+                    .get_result(db.as_mut())?,
+            ),
+            (
+                "media_source",
+                media_source::table.count().get_result(db.as_mut())?,
+            ),
+        ]
+    };
+    Ok(counts)
+}
+
+fn random_track_uid() -> TrackUid {
+    TrackUid::from_untyped(EntityUid::new())
+}
+
+fn loaded_uids(loaded: &[(RecordHeader, TrackEntity)]) -> Vec<TrackUid> {
+    loaded
+        .iter()
+        .map(|(_, entity)| entity.hdr.uid.clone())
+        .collect()
+}
+
+#[test]
+fn load_tracks_by_uids_preserves_requested_order() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uids = create_tracks(&mut db, collection_id, 3)?;
+
+    let requested = vec![uids[2].clone(), uids[0].clone(), uids[1].clone()];
+    let mut loaded = Vec::new();
+    let not_found = db.load_tracks_by_uids(collection_id, &requested, &mut loaded)?;
+
+    assert!(not_found.is_empty());
+    assert_eq!(requested, loaded_uids(&loaded));
+    Ok(())
+}
+
+#[test]
+fn load_tracks_by_uids_reports_missing_uids() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uids = create_tracks(&mut db, collection_id, 2)?;
+    // Tracks of other collections must not be found.
+    let other_collection_id = create_collection(&mut db)?;
+    let other_uids = create_tracks(&mut db, other_collection_id, 1)?;
+
+    let unknown_uid = random_track_uid();
+    let requested = vec![
+        uids[1].clone(),
+        unknown_uid.clone(),
+        other_uids[0].clone(),
+        uids[0].clone(),
+    ];
+    let mut loaded = Vec::new();
+    let not_found = db.load_tracks_by_uids(collection_id, &requested, &mut loaded)?;
+
+    assert_eq!(vec![uids[1].clone(), uids[0].clone()], loaded_uids(&loaded));
+    assert_eq!(vec![unknown_uid, other_uids[0].clone()], not_found);
+    Ok(())
+}
+
+#[test]
+fn load_missing_track_entity_reports_uid() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    create_tracks(&mut db, collection_id, 1)?;
+
+    let unknown_uid = random_track_uid();
+    let err = db.load_track_entity_by_uid(&unknown_uid).unwrap_err();
+    let RepoError::NotFound {
+        entity: Some(entity),
+    } = &err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(EntityKind::Track, entity.kind);
+    assert_eq!(unknown_uid.as_ref(), &entity.uid);
+    assert_eq!(format!("track {unknown_uid} not found"), err.to_string());
+    let err = db.resolve_track_id(&unknown_uid).unwrap_err();
+    assert!(matches!(
+        err,
+        RepoError::NotFound { entity: Some(entity) } if entity.uid == *unknown_uid.as_ref()
+    ));
+    Ok(())
+}
+
+#[test]
+fn load_tracks_by_uids_in_multiple_chunks() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uids = create_tracks(&mut db, collection_id, 3)?;
+
+    // Exceed both the chunk size and the SQLite parameter limit
+    // with the existing tracks located in different chunks.
+    let mut requested = std::iter::repeat_with(random_track_uid)
+        .take(2 * TRACK_UIDS_CHUNK_SIZE)
+        .collect::<Vec<_>>();
+    requested.insert(0, uids[0].clone());
+    requested.insert(TRACK_UIDS_CHUNK_SIZE + 1, uids[1].clone());
+    requested.push(uids[2].clone());
+    assert!(requested.len() > 999);
+
+    let mut loaded = Vec::new();
+    let not_found = db.load_tracks_by_uids(collection_id, &requested, &mut loaded)?;
+
+    assert_eq!(uids, loaded_uids(&loaded));
+    assert_eq!(2 * TRACK_UIDS_CHUNK_SIZE, not_found.len());
+    Ok(())
+}
+
+#[test]
+fn delete_tracks_by_uids_reports_outcome_per_uid() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uids = create_tracks(&mut db, collection_id, 3)?;
+    // Tracks of other collections must not be deleted.
+    let other_collection_id = create_collection(&mut db)?;
+    let other_uids = create_tracks(&mut db, other_collection_id, 1)?;
+
+    // Reference the 2nd track by a playlist entry
+    let playlist = Playlist {
+        title: "Playlist".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        flags: Default::default(),
+    };
+    let playlist_entity = PlaylistEntity::new(PlaylistHeader::initial_random(), playlist);
+    let playlist_id = db.insert_playlist_entity(
+        Some(collection_id),
+        &OffsetDateTimeMs::now_utc(),
+        &playlist_entity,
+    )?;
+    db.append_playlist_entries(
+        playlist_id,
+        &[Entry {
+            added_at: OffsetDateTimeMs::now_utc(),
+            title: None,
+            notes: None,
+            item: Item::Track(TrackItem::new(uids[1].clone())),
+        }],
+    )?;
+
+    let unknown_uid = random_track_uid();
+    let requested = vec![
+        uids[0].clone(),
+        unknown_uid.clone(),
+        uids[1].clone(),
+        other_uids[0].clone(),
+        uids[2].clone(),
+    ];
+    let outcomes = db.delete_tracks_by_uids(collection_id, &requested)?;
+
+    assert_eq!(
+        vec![
+            (uids[0].clone(), DeleteOutcome::Deleted),
+            (unknown_uid, DeleteOutcome::NotFound),
+            (uids[1].clone(), DeleteOutcome::Blocked),
+            (other_uids[0].clone(), DeleteOutcome::NotFound),
+            (uids[2].clone(), DeleteOutcome::Deleted),
+        ],
+        outcomes
+    );
+    assert_eq!(1, db.count_tracks(collection_id)?);
+    assert_eq!(1, db.count_tracks(other_collection_id)?);
+    // The playlist entry is still present
+    assert_eq!(1, db.count_playlist_entries(playlist_id)?);
+    Ok(())
+}
+
+#[test]
+fn delete_tracks_by_uids_leaves_no_orphaned_rows() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uid = create_track_with_child_rows(&mut db, collection_id)?;
+    for (table, count) in count_track_child_rows(&mut db, false)? {
+        assert_eq!(1, count, "{table}");
+    }
+
+    let outcomes = db.delete_tracks_by_uids(collection_id, std::slice::from_ref(&uid))?;
+    assert_eq!(vec![(uid, DeleteOutcome::Deleted)], outcomes);
+    assert_eq!(0, db.count_tracks(collection_id)?);
+
+    for (table, count) in count_track_child_rows(&mut db, true)? {
+        assert_eq!(0, count, "orphaned rows in {table}");
+    }
+    for (table, count) in count_track_child_rows(&mut db, false)? {
+        assert_eq!(0, count, "{table}");
+    }
+    Ok(())
+}
+
+#[test]
+fn search_tracks_with_core_field_groups_only() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    create_track_with_child_rows(&mut db, collection_id)?;
+
+    let mut collector = Vec::new();
+    db.search_tracks(
+        collection_id,
+        &Pagination::default(),
+        None,
+        &[],
+        &mut collector,
+    )?;
+    let [(_, entity)] = collector.as_slice() else {
+        panic!("unexpected search results: {collector:?}");
+    };
+    assert!(!entity.body.track.tags.is_empty());
+    assert!(entity.body.track.media_source.artwork.is_some());
+
+    let mut collector = Vec::new();
+    db.search_tracks_with_field_groups(
+        collection_id,
+        &Pagination::default(),
+        None,
+        &[],
+        FieldGroups::CORE,
+        &mut collector,
+    )?;
+    let [(_, entity)] = collector.as_slice() else {
+        panic!("unexpected search results: {collector:?}");
+    };
+    let track = &entity.body.track;
+    assert!(track.tags.is_empty());
+    assert!(track.media_source.artwork.is_none());
+    assert_eq!(Some("Title"), track.track_title());
+    assert_eq!(1, track.actors.len());
+    assert_eq!(1, track.cues.len());
+    assert_eq!(
+        "/home/test/artwork.mp3",
+        track.media_source.content.link.path.as_str()
+    );
+    Ok(())
+}
+
+#[test]
+fn query_plan_of_uid_lookup_uses_index() -> TestResult<()> {
+    let mut connection = establish_connection()?;
+    let uid = random_track_uid();
+    let encoded_uid = EncodedEntityUid::from(&uid);
+
+    let query = track::table
+        .select(track::row_id)
+        .filter(track::entity_uid.eq(encoded_uid.as_str()));
+    assert_query_plan_uses_indexes(&mut connection, &query, &["sqlite_autoindex_track_1"])?;
+
+    let query =
+        view_track_search::table.filter(view_track_search::entity_uid.eq(encoded_uid.as_str()));
+    assert_query_plan_uses_indexes(&mut connection, &query, &["sqlite_autoindex_track_1"])
+}
+
+#[test]
+fn query_plan_of_collection_scoped_search_uses_indexes() -> TestResult<()> {
+    let mut connection = establish_connection()?;
+    let mut db = crate::Connection::new(&mut connection);
+    let collection_id = create_collection(&mut db)?;
+
+    let query = select_tracks_in_collection(collection_id).then_order_by(view_track_search::row_id);
+    assert_query_plan_uses_indexes(
+        &mut connection,
+        &query,
+        &[
+            "sqlite_autoindex_media_source_1",
+            "idx_track_media_source_id",
+        ],
+    )?;
+
+    let query = select_tracks_in_collection(collection_id).filter(
+        view_track_search::media_source_id.eq_any(
+            select_row_id_filtered_by_content_path_predicate(
+                collection_id,
+                StringPredicate::StartsWith("/home/test/".into()),
+            ),
+        ),
+    );
+    assert_query_plan_uses_indexes(
+        &mut connection,
+        &query,
+        &[
+            "sqlite_autoindex_media_source_1",
+            "idx_track_media_source_id",
+        ],
+    )
+}
+
+#[test]
+fn load_tracks_updated_after_index_watermark() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uids = create_tracks(&mut db, collection_id, 3)?;
+    // Tracks of other collections must not be loaded.
+    let other_collection_id = create_collection(&mut db)?;
+    create_tracks(&mut db, other_collection_id, 1)?;
+    assert_eq!(None, db.load_track_index_watermark(collection_id)?);
+
+    // Initially all tracks need to be indexed
+    let mut loaded = Vec::new();
+    assert_eq!(
+        3,
+        db.load_tracks_updated_after(collection_id, None, 10, &mut loaded)?
+    );
+    assert_eq!(uids, loaded_uids(&loaded));
+    let watermark = IndexWatermark::from(&loaded.last().unwrap().0);
+    db.store_track_index_watermark(collection_id, Some(&watermark))?;
+    assert_eq!(
+        Some(&watermark),
+        db.load_track_index_watermark(collection_id)?.as_ref()
+    );
+    assert_eq!(None, db.load_track_index_watermark(other_collection_id)?);
+
+    // Nothing changed since indexing
+    let mut loaded = Vec::new();
+    assert_eq!(
+        0,
+        db.load_tracks_updated_after(collection_id, Some(&watermark), 10, &mut loaded)?
+    );
+
+    // Modify an existing track and add a new track
+    let (media_source_id, _, mut entity) = db.load_track_entity_by_media_source_content_path(
+        collection_id,
+        &"/home/test/file0.mp3".into(),
+    )?;
+    entity.body.updated_at = OffsetDateTimeMs::from_timestamp_millis(
+        watermark.updated_at.timestamp_millis() + 3_600_000,
+    );
+    let updated_id = db.resolve_track_id(&entity.hdr.uid)?;
+    db.update_track_entity(updated_id, media_source_id, &entity)?;
+    let (_, new_entity) = insert_track(&mut db, collection_id, "/home/test/new.mp3", |_| {})?;
+    let new_uids = vec![new_entity.hdr.uid.clone()];
+
+    // Only the modified and new tracks are loaded in ascending order
+    let mut loaded = Vec::new();
+    assert_eq!(
+        2,
+        db.load_tracks_updated_after(collection_id, Some(&watermark), 10, &mut loaded)?
+    );
+    assert_eq!(
+        vec![new_uids[0].clone(), uids[0].clone()],
+        loaded_uids(&loaded)
+    );
+    let mut loaded = Vec::new();
+    assert_eq!(
+        1,
+        db.load_tracks_updated_after(collection_id, Some(&watermark), 1, &mut loaded)?
+    );
+    assert_eq!(new_uids, loaded_uids(&loaded));
+
+    // Advance the watermark
+    let next_watermark = IndexWatermark {
+        updated_at: entity.body.updated_at.clone(),
+        id: updated_id,
+    };
+    assert!(next_watermark > watermark);
+    db.store_track_index_watermark(collection_id, Some(&next_watermark))?;
+    assert_eq!(
+        Some(next_watermark),
+        db.load_track_index_watermark(collection_id)?
+    );
+
+    // Reset the watermark
+    db.store_track_index_watermark(collection_id, None)?;
+    assert_eq!(None, db.load_track_index_watermark(collection_id)?);
+    Ok(())
+}
+
+fn create_track_with_tags(
+    db: &mut crate::Connection<'_>,
+    collection_id: CollectionId,
+    content_path: &str,
+    faceted_labels: &[(Option<&'static str>, &'static [&'static str])],
+) -> TestResult<()> {
+    insert_track(db, collection_id, content_path, |track_entity| {
+        let tags = faceted_labels
+            .iter()
+            .map(|(facet, labels)| {
+                let facet_key = FacetKey::new(facet.map(FacetId::from_unchecked));
+                let plain_tags = labels
+                    .iter()
+                    .map(|label| PlainTag {
+                        label: Some(Label::from_unchecked(*label)),
+                        score: Default::default(),
+                    })
+                    .collect();
+                (facet_key, plain_tags)
+            })
+            .collect::<TagsMapInner<'_>>();
+        track_entity.body.track.tags = TagsMap::new(tags).canonicalize_into();
+    })?;
+    Ok(())
+}
+
+#[test]
+fn related_tags_ranked_by_co_occurrence() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let genre = Some("genre");
+    for (content_path, faceted_labels) in [
+        (
+            "/home/test/file0.mp3",
+            vec![(genre, ["House", "Techno", "Deep"].as_slice())],
+        ),
+        (
+            "/home/test/file1.mp3",
+            vec![(genre, ["House", "Techno", "Disco"].as_slice())],
+        ),
+        (
+            "/home/test/file2.mp3",
+            vec![(genre, ["Deep", "House", "Techno"].as_slice())],
+        ),
+        // Not tagged with the requested label
+        (
+            "/home/test/file3.mp3",
+            vec![(genre, ["Ambient", "Techno"].as_slice())],
+        ),
+        // Same label, but a different facet
+        (
+            "/home/test/file4.mp3",
+            vec![
+                (None, ["House", "Jazz"].as_slice()),
+                (genre, ["Soul"].as_slice()),
+            ],
+        ),
+    ] {
+        create_track_with_tags(&mut db, collection_id, content_path, &faceted_labels)?;
+    }
+    // Tracks of other collections must not be considered.
+    let other_collection_id = create_collection(&mut db)?;
+    for i in 0..3 {
+        create_track_with_tags(
+            &mut db,
+            other_collection_id,
+            &format!("/home/test/other{i}.mp3"),
+            &[(genre, ["Disco", "House"].as_slice())],
+        )?;
+    }
+
+    let genre_id = FacetId::from_unchecked("genre");
+    let house = Label::from_unchecked("House");
+    assert_eq!(
+        vec![
+            ("Techno".to_owned(), 3),
+            ("Deep".to_owned(), 2),
+            ("Disco".to_owned(), 1),
+        ],
+        db.related_tags(collection_id, Some(&genre_id), &house, 10)?
+    );
+    assert_eq!(
+        vec![("Techno".to_owned(), 3), ("Deep".to_owned(), 2)],
+        db.related_tags(collection_id, Some(&genre_id), &house, 2)?
+    );
+    assert_eq!(
+        vec![("Jazz".to_owned(), 1)],
+        db.related_tags(collection_id, None, &house, 10)?
+    );
+    assert!(db
+        .related_tags(
+            collection_id,
+            Some(&genre_id),
+            &Label::from_unchecked("Unknown"),
+            10
+        )?
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn store_query_and_delete_custom_properties() -> TestResult<()> {
+    let mut db = establish_connection()?;
+    let mut db = crate::Connection::new(&mut db);
+    let collection_id = create_collection(&mut db)?;
+    let uids = create_tracks(&mut db, collection_id, 2)?;
+    let track_id = db.resolve_track_id(&uids[0])?;
+    let other_track_id = db.resolve_track_id(&uids[1])?;
+
+    db.store_track_custom_property(track_id, "my_rating", "4")?;
+    db.store_track_custom_property(track_id, "cue_count", "8")?;
+    db.store_track_custom_property(other_track_id, "my_rating", "2")?;
+    // Storing an existing key replaces its value.
+    db.store_track_custom_property(track_id, "my_rating", "5")?;
+    assert_eq!("5", db.load_track_custom_property(track_id, "my_rating")?);
+    assert_eq!(
+        vec![
+            ("cue_count".to_owned(), "8".to_owned()),
+            ("my_rating".to_owned(), "5".to_owned()),
+        ],
+        db.load_track_custom_properties(track_id)?
+    );
+
+    let mut search_by_custom_property = |key: &str, value: &str| -> TestResult<_> {
+        let filter = Filter::CustomPropertyEquals(CustomPropertyEquals {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        });
+        let mut collector = Vec::new();
+        db.search_tracks(
+            collection_id,
+            &Pagination::default(),
+            Some(&filter),
+            &[],
+            &mut collector,
+        )?;
+        Ok(loaded_uids(&collector))
+    };
+    assert_eq!(
+        vec![uids[0].clone()],
+        search_by_custom_property("my_rating", "5")?
+    );
+    assert_eq!(
+        vec![uids[1].clone()],
+        search_by_custom_property("my_rating", "2")?
+    );
+    assert!(search_by_custom_property("my_rating", "4")?.is_empty());
+    assert!(search_by_custom_property("cue_count", "5")?.is_empty());
+
+    db.delete_track_custom_property(track_id, "my_rating")?;
+    assert!(matches!(
+        db.load_track_custom_property(track_id, "my_rating"),
+        Err(RepoError::NotFound { .. })
+    ));
+    assert!(matches!(
+        db.delete_track_custom_property(track_id, "my_rating"),
+        Err(RepoError::NotFound { .. })
+    ));
+    assert_eq!(
+        vec![("cue_count".to_owned(), "8".to_owned())],
+        db.load_track_custom_properties(track_id)?
+    );
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::num::NonZeroUsize;
+
+use diesel::{
+    result::{DatabaseErrorKind, Error as DieselError},
+    Connection as _,
+};
+
+use aoide_repo::RepoError;
+
+use crate::DbConnection;
+
+/// Errors of transactions that could be retried.
+pub trait TransactionError: From<DieselError> {
+    /// Check if the failed transaction might succeed when retried.
+    fn is_retryable(&self) -> bool;
+}
+
+/// Check if the database has been busy or locked.
+///
+/// SQLite reports both `SQLITE_BUSY` and `SQLITE_LOCKED` with an unknown
+/// error kind and only the error message allows to distinguish them.
+#[must_use]
+pub fn is_retryable_diesel_error(err: &DieselError) -> bool {
+    let DieselError::DatabaseError(kind, info) = err else {
+        return false;
+    };
+    match kind {
+        DatabaseErrorKind::SerializationFailure => true,
+        DatabaseErrorKind::Unknown => {
+            let message = info.message();
+            message.contains("database is locked")
+                || message.contains("database table is locked")
+                || message.contains("database is busy")
+        }
+        _ => false,
+    }
+}
+
+/// Check if the repository operation failed because the database
+/// has been busy or locked.
+///
+/// Revision conflicts are not retryable. Replaying the operation with
+/// the same outdated entity header would fail again.
+#[must_use]
+pub fn is_retryable_repo_error(err: &RepoError) -> bool {
+    match err {
+        RepoError::Other(err) => err
+            .downcast_ref::<DieselError>()
+            .is_some_and(is_retryable_diesel_error),
+        RepoError::Conflict { .. } | RepoError::NotFound { .. } | RepoError::Aborted => false,
+    }
+}
+
+impl TransactionError for DieselError {
+    fn is_retryable(&self) -> bool {
+        is_retryable_diesel_error(self)
+    }
+}
+
+/// Run a read-modify-write cycle in a transaction that is retried if the
+/// database has been busy or locked.
+///
+/// The transaction is retried up to `max_attempts` in total if it failed with
+/// a retryable error. The closure must (re-)load all data that it modifies
+/// on each invocation.
+///
+/// Retrying is only effective for the outermost transaction. Nested
+/// transactions are executed as save points and inherit all locks from
+/// the enclosing transaction.
+pub fn with_retrying_transaction<T, E, F>(
+    connection: &mut DbConnection,
+    max_attempts: NonZeroUsize,
+    mut f: F,
+) -> Result<T, E>
+where
+    E: TransactionError,
+    F: FnMut(&mut DbConnection) -> Result<T, E>,
+{
+    let mut attempt = 1;
+    loop {
+        match connection.transaction(&mut f) {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                if attempt >= max_attempts.get() || !err.is_retryable() {
+                    return Err(err);
+                }
+                log::debug!("Retrying failed transaction after attempt #{attempt}");
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
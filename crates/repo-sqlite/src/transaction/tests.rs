@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::num::NonZeroUsize;
+
+use test_log::test;
+
+use aoide_core::{util::clock::OffsetDateTimeMs, Collection, CollectionEntity, CollectionHeader};
+use aoide_repo::{collection::EntityRepo as _, RepoError};
+
+use super::*;
+use crate::{repo::tests::vfs_media_source_config, tests::*};
+
+#[derive(Debug)]
+enum TestError {
+    Repo(RepoError),
+    Diesel(DieselError),
+}
+
+impl From<RepoError> for TestError {
+    fn from(err: RepoError) -> Self {
+        Self::Repo(err)
+    }
+}
+
+impl From<DieselError> for TestError {
+    fn from(err: DieselError) -> Self {
+        Self::Diesel(err)
+    }
+}
+
+impl TransactionError for TestError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Repo(err) => is_retryable_repo_error(err),
+            Self::Diesel(err) => is_retryable_diesel_error(err),
+        }
+    }
+}
+
+fn create_collection_with_outdated_header(
+    connection: &mut DbConnection,
+) -> TestResult<CollectionHeader> {
+    let mut repo = crate::Connection::new(connection);
+    let entity = CollectionEntity::new(
+        CollectionHeader::initial_random(),
+        Collection {
+            title: "Test Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: vfs_media_source_config(),
+        },
+    );
+    repo.insert_collection_entity(&OffsetDateTimeMs::now_utc(), &entity)?;
+    // Concurrent modification by some other writer
+    repo.touch_collection_entity_revision(&entity.hdr, &OffsetDateTimeMs::now_utc())?;
+    Ok(entity.hdr.clone())
+}
+
+fn database_locked_error() -> DieselError {
+    DieselError::DatabaseError(
+        DatabaseErrorKind::Unknown,
+        Box::new("database is locked".to_owned()),
+    )
+}
+
+#[test]
+fn retry_after_database_locked_on_first_attempt() -> TestResult<()> {
+    let mut connection = establish_connection()?;
+
+    let mut attempts = 0;
+    let result = with_retrying_transaction::<_, TestError, _>(
+        &mut connection,
+        NonZeroUsize::new(3).unwrap(),
+        |_| {
+            attempts += 1;
+            if attempts == 1 {
+                return Err(database_locked_error().into());
+            }
+            Ok(attempts)
+        },
+    );
+
+    assert!(matches!(result, Ok(2)));
+    Ok(())
+}
+
+#[test]
+fn give_up_after_max_attempts() -> TestResult<()> {
+    let mut connection = establish_connection()?;
+
+    let mut attempts = 0;
+    let result = with_retrying_transaction::<(), TestError, _>(
+        &mut connection,
+        NonZeroUsize::new(3).unwrap(),
+        |_| {
+            attempts += 1;
+            Err(RepoError::Other(database_locked_error().into()).into())
+        },
+    );
+
+    assert!(matches!(result, Err(TestError::Repo(RepoError::Other(_)))));
+    assert_eq!(3, attempts);
+    Ok(())
+}
+
+#[test]
+fn no_retry_after_conflict() -> TestResult<()> {
+    let mut connection = establish_connection()?;
+    let outdated_header = create_collection_with_outdated_header(&mut connection)?;
+
+    let mut attempts = 0;
+    let result = with_retrying_transaction::<_, TestError, _>(
+        &mut connection,
+        NonZeroUsize::new(3).unwrap(),
+        |connection| {
+            attempts += 1;
+            crate::Connection::new(connection)
+                .touch_collection_entity_revision(&outdated_header, &OffsetDateTimeMs::now_utc())
+                .map_err(Into::into)
+        },
+    );
+
+    assert!(matches!(
+        result,
+        Err(TestError::Repo(RepoError::Conflict { .. }))
+    ));
+    assert_eq!(1, attempts);
+    Ok(())
+}
+
+#[test]
+fn no_retry_for_other_errors() -> TestResult<()> {
+    let mut connection = establish_connection()?;
+
+    let mut attempts = 0;
+    let result = with_retrying_transaction::<(), TestError, _>(
+        &mut connection,
+        NonZeroUsize::new(3).unwrap(),
+        |_| {
+            attempts += 1;
+            Err(RepoError::NotFound { entity: None }.into())
+        },
+    );
+
+    assert!(matches!(
+        result,
+        Err(TestError::Repo(RepoError::NotFound { .. }))
+    ));
+    assert_eq!(1, attempts);
+    Ok(())
+}
@@ -32,6 +32,12 @@ pub enum KindFilter<'a> {
     IsNone,
     Equal(Cow<'a, str>),
     NotEqual(Cow<'a, str>),
+    /// Matches any of the given kinds and optionally also
+    /// collections without a kind.
+    AnyOf {
+        kinds: Vec<Cow<'a, str>>,
+        include_none: bool,
+    },
 }
 
 pub trait EntityRepo {
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{io::Write, marker::PhantomData};
+
+use serde::Serialize;
+
+use crate::{RecordCollector, ReservableRecordCollector};
+
+/// Streams collected records into a writer
+///
+/// Each record is serialized as a single line of JSON ([JSON Lines](https://jsonlines.org)
+/// or NDJSON) immediately when it is collected. Records are not accumulated
+/// in memory. The headers are discarded.
+///
+/// Errors cannot be reported while collecting. Writing stops after the
+/// first error, which is returned when finishing.
+#[derive(Debug)]
+pub struct JsonLinesCollector<W, H, R> {
+    writer: W,
+    count: usize,
+    error: Option<serde_json::Error>,
+    _phantom: PhantomData<fn(H, R)>,
+}
+
+impl<W, H, R> JsonLinesCollector<W, H, R>
+where
+    W: Write,
+{
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            count: 0,
+            error: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The number of records that have been written successfully.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The error that occurred while writing, if any.
+    ///
+    /// All subsequently collected records have been discarded.
+    #[must_use]
+    pub const fn error(&self) -> Option<&serde_json::Error> {
+        self.error.as_ref()
+    }
+
+    /// Flush and return the writer.
+    ///
+    /// Fails with the first error that occurred while collecting.
+    pub fn finish(self) -> serde_json::Result<W> {
+        let Self {
+            mut writer,
+            count: _,
+            error,
+            _phantom,
+        } = self;
+        if let Some(err) = error {
+            return Err(err);
+        }
+        writer.flush().map_err(serde_json::Error::io)?;
+        Ok(writer)
+    }
+
+    fn write_line(&mut self, record: &R) -> serde_json::Result<()>
+    where
+        R: Serialize,
+    {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n").map_err(serde_json::Error::io)
+    }
+}
+
+impl<W, H, R> RecordCollector for JsonLinesCollector<W, H, R>
+where
+    W: Write,
+    R: Serialize,
+{
+    type Header = H;
+    type Record = R;
+
+    fn collect(&mut self, _header: H, record: R) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.write_line(&record) {
+            Ok(()) => {
+                self.count += 1;
+            }
+            Err(err) => {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+impl<W, H, R> ReservableRecordCollector for JsonLinesCollector<W, H, R>
+where
+    W: Write,
+    R: Serialize,
+{
+    fn reserve(&mut self, _additional: usize) {
+        // Nothing is buffered
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io;
+
+use serde::ser::Error as _;
+use serde_json::{json, Value};
+
+use super::*;
+
+#[test]
+fn collect_records_as_json_lines() {
+    let records = vec![
+        json!({ "title": "First", "position": 1 }),
+        json!({ "title": "Second", "tags": ["a", "b"] }),
+        json!(null),
+        json!("multi\nline"),
+    ];
+    let mut collector = JsonLinesCollector::new(Vec::new());
+    collector.reserve(records.len());
+    for (header, record) in records.iter().cloned().enumerate() {
+        collector.collect(header, record);
+    }
+    assert_eq!(records.len(), collector.count());
+    assert!(collector.error().is_none());
+    let output = String::from_utf8(collector.finish().unwrap()).unwrap();
+    assert!(output.ends_with('\n'));
+    let collected = output
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(records, collected);
+}
+
+struct FailingRecord;
+
+impl Serialize for FailingRecord {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(S::Error::custom("failing record"))
+    }
+}
+
+#[derive(Debug, Default)]
+struct FailAfterWriter {
+    written: Vec<u8>,
+    remaining: usize,
+}
+
+impl Write for FailAfterWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.remaining < buf.len() {
+            return Err(io::Error::other("writer is full"));
+        }
+        self.remaining -= buf.len();
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn serialization_error_is_returned_when_finishing() {
+    let mut collector = JsonLinesCollector::<_, (), Option<FailingRecord>>::new(Vec::new());
+    collector.collect((), None);
+    collector.collect((), Some(FailingRecord));
+    // Discarded after the first error
+    collector.collect((), None);
+    assert_eq!(1, collector.count());
+    assert!(collector.error().is_some());
+    assert!(collector.finish().is_err());
+}
+
+#[test]
+fn write_error_is_returned_when_finishing() {
+    let writer = FailAfterWriter {
+        remaining: 5,
+        ..Default::default()
+    };
+    let mut collector = JsonLinesCollector::new(writer);
+    collector.collect((), json!(1));
+    collector.collect((), json!("too long"));
+    assert_eq!(1, collector.count());
+    let err = collector.finish().unwrap_err();
+    assert!(err.is_io());
+}
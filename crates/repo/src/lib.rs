@@ -3,7 +3,7 @@
 
 use derive_more::derive::{Display, Error};
 
-use aoide_core::util::clock::OffsetDateTimeMs;
+use aoide_core::{util::clock::OffsetDateTimeMs, EntityRevision, EntityUid, EntityUidTyped};
 use aoide_core_api::{Pagination, PaginationOffset};
 
 #[macro_use]
@@ -23,6 +23,11 @@ pub mod tag;
 pub mod track;
 pub use self::track::RecordId as TrackId;
 
+#[cfg(feature = "serde")]
+pub mod json_lines;
+#[cfg(feature = "serde")]
+pub use self::json_lines::JsonLinesCollector;
+
 pub type RecordId = i64;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -111,13 +116,61 @@ where
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum EntityKind {
+    #[display("collection")]
+    Collection,
+
+    #[display("playlist")]
+    Playlist,
+
+    #[display("track")]
+    Track,
+}
+
+/// Identifies the entity that an error refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[display("{kind} {uid}")]
+pub struct EntityContext {
+    pub kind: EntityKind,
+    pub uid: EntityUid,
+}
+
+impl EntityContext {
+    #[must_use]
+    pub fn new<T>(kind: EntityKind, uid: &EntityUidTyped<T>) -> Self {
+        Self {
+            kind,
+            uid: uid.as_ref().clone(),
+        }
+    }
+}
+
+fn display_with_entity_context(message: &str, entity: Option<&EntityContext>) -> String {
+    if let Some(entity) = entity {
+        format!("{entity} {message}")
+    } else {
+        message.to_owned()
+    }
+}
+
 #[derive(Debug, Display, Error)]
 pub enum RepoError {
-    #[display("not found")]
-    NotFound,
-
-    #[display("conflict")]
-    Conflict,
+    #[display("{}", display_with_entity_context("not found", entity.as_ref()))]
+    NotFound {
+        /// The entity that has not been found, if known.
+        entity: Option<EntityContext>,
+    },
+
+    /// The revision of an entity doesn't match the stored revision.
+    #[display("{}", display_with_entity_context("conflict", entity.as_ref()))]
+    Conflict {
+        /// The conflicting entity, if known.
+        entity: Option<EntityContext>,
+
+        /// The current revision of the stored entity.
+        current_rev: EntityRevision,
+    },
 
     #[display("aborted")]
     Aborted,
@@ -125,6 +178,28 @@ pub enum RepoError {
     Other(anyhow::Error),
 }
 
+impl RepoError {
+    /// Attach the affected entity to not found and conflict errors.
+    ///
+    /// Errors of other kinds or with an entity are returned unmodified.
+    #[must_use]
+    pub fn with_entity_context(self, context: EntityContext) -> Self {
+        match self {
+            Self::NotFound { entity: None } => Self::NotFound {
+                entity: Some(context),
+            },
+            Self::Conflict {
+                entity: None,
+                current_rev,
+            } => Self::Conflict {
+                entity: Some(context),
+                current_rev,
+            },
+            err => err,
+        }
+    }
+}
+
 pub type RepoResult<T> = Result<T, RepoError>;
 
 pub trait OptionalRepoResult<T> {
@@ -135,7 +210,7 @@ impl<T> OptionalRepoResult<T> for Result<T, RepoError> {
     fn optional(self) -> RepoResult<Option<T>> {
         self.map_or_else(
             |err| {
-                if matches!(err, RepoError::NotFound) {
+                if matches!(err, RepoError::NotFound { .. }) {
                     Ok(None)
                 } else {
                     Err(err)
@@ -59,7 +59,13 @@ macro_rules! entity_repo_trait_common_functions {
                 let (id, rev) =
                     self.[<resolve_ $entity_type_name:lower _entity_revision>](&updated_entity.hdr.uid).map(|(hdr, rev)| (hdr.id, rev))?;
                 if updated_entity.hdr.rev.prev() != Some(rev) {
-                    return Err($crate::RepoError::Conflict);
+                    return Err($crate::RepoError::Conflict {
+                        entity: Some($crate::EntityContext::new(
+                            $crate::EntityKind::$entity_type_name,
+                            &updated_entity.hdr.uid,
+                        )),
+                        current_rev: rev,
+                    });
                 }
                 self.[<update_ $entity_type_name:lower _entity>](id, updated_at, updated_entity)
             }
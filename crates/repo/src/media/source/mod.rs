@@ -1,7 +1,11 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core::{media::content::ContentPath, util::clock::OffsetDateTimeMs, MediaSource};
+use aoide_core::{
+    media::content::{ContentPath, ContentRevision},
+    util::clock::OffsetDateTimeMs,
+    MediaSource,
+};
 use aoide_core_api::filtering::StringPredicate;
 
 use crate::{CollectionId, RepoResult};
@@ -23,6 +27,18 @@ pub trait Repo {
     fn load_media_source(&mut self, id: RecordId) -> RepoResult<(RecordHeader, MediaSource)>;
 }
 
+/// The stored content digest of a media source.
+///
+/// The revision and size of the content at the time when the
+/// digest has been computed allow to detect modifications.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDigestRecord {
+    pub content_path: ContentPath<'static>,
+    pub content_rev: ContentRevision,
+    pub content_size: u64,
+    pub content_digest: Vec<u8>,
+}
+
 pub trait CollectionRepo {
     fn resolve_media_source_id_synchronized_at_by_content_path(
         &mut self,
@@ -36,6 +52,22 @@ pub trait CollectionRepo {
         content_path_predicate: StringPredicate<'_>,
     ) -> RepoResult<Vec<RecordId>>;
 
+    /// Load the content paths of all media sources in lexicographical order.
+    fn load_media_source_content_paths(
+        &mut self,
+        collection_id: CollectionId,
+    ) -> RepoResult<Vec<ContentPath<'static>>>;
+
+    /// Load the stored content digests of the given media sources.
+    ///
+    /// Media sources without a content digest, revision, or size
+    /// are omitted.
+    fn load_media_source_content_digests(
+        &mut self,
+        collection_id: CollectionId,
+        content_paths: &[&ContentPath<'_>],
+    ) -> RepoResult<Vec<ContentDigestRecord>>;
+
     fn insert_media_source(
         &mut self,
         collection_id: CollectionId,
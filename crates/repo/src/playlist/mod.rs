@@ -11,7 +11,11 @@ use aoide_core::{
     },
     util::{clock::OffsetDateTimeMs, random::adhoc_rng},
 };
-use aoide_core_api::{playlist::EntityWithEntriesSummary, Pagination};
+use aoide_core_api::{
+    playlist::EntityWithEntriesSummary,
+    track::search::{Filter, SortOrder},
+    Pagination,
+};
 
 use crate::{CollectionId, RecordCollector, RepoResult, ReservableRecordCollector, TrackId};
 
@@ -31,6 +35,13 @@ pub enum KindFilter<'a> {
     NotEqual(Cow<'a, str>),
 }
 
+/// The stored track search of a dynamic playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicQuery {
+    pub filter: Option<Filter>,
+    pub ordering: Vec<SortOrder>,
+}
+
 pub trait EntityRepo: EntryRepo {
     entity_repo_trait_common_functions!(RecordId, Entity, EntityUid, EntityHeader, Playlist);
 
@@ -65,6 +76,23 @@ pub trait EntityRepo: EntryRepo {
             Record = EntityWithEntriesSummary,
         >,
     ) -> RepoResult<()>;
+
+    /// Load the collection of a playlist.
+    ///
+    /// Returns `None` if the playlist does not belong to any collection.
+    fn load_playlist_collection_id(&mut self, id: RecordId) -> RepoResult<Option<CollectionId>>;
+
+    /// Load the stored track search of a dynamic playlist.
+    ///
+    /// Returns `None` if the playlist is not dynamic.
+    fn load_playlist_dynamic_query(&mut self, id: RecordId) -> RepoResult<Option<DynamicQuery>>;
+
+    /// Store or remove the track search of a dynamic playlist.
+    fn store_playlist_dynamic_query(
+        &mut self,
+        id: RecordId,
+        query: Option<&DynamicQuery>,
+    ) -> RepoResult<()>;
 }
 
 /// Prepend playlist entries by insertion
@@ -3,17 +3,22 @@
 
 use aoide_core::{
     media::content::{ContentLink, ContentPath},
+    tag::{FacetId, Label},
     track::{actor::ActorNamesSummarySplitter, EntityHeader},
+    util::clock::OffsetDateTimeMs,
     EntityRevision, Track, TrackEntity, TrackUid,
 };
 use aoide_core_api::{
     filtering::StringPredicate,
-    track::search::{Filter, SortOrder, StringField},
+    track::search::{FieldGroups, Filter, SortOrder, StringField},
     Pagination,
 };
 
 use crate::{CollectionId, MediaSourceId, RepoResult, ReservableRecordCollector, StringCount};
 
+#[cfg(feature = "serde")]
+pub mod search;
+
 record_id_newtype!(RecordId);
 
 pub type RecordHeader = crate::RecordHeader<RecordId>;
@@ -40,6 +45,15 @@ pub enum ReplaceOutcome {
     NotUpdated(MediaSourceId, RecordId, Track),
 }
 
+/// Per-track outcome of deleting multiple tracks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeleteOutcome {
+    Deleted,
+    NotFound,
+    /// Still referenced, e.g. by playlist entries.
+    Blocked,
+}
+
 /// Essential properties that allow to trace down a track from all
 /// directions, i.e. database relations, source path, and source
 /// synchronization.
@@ -51,6 +65,26 @@ pub struct RecordTrail {
     pub last_synchronized_rev: Option<EntityRevision>,
 }
 
+/// The position up to which the tracks of a collection have been indexed.
+///
+/// Tracks are ordered by the time of their last update and then by
+/// their id. The derived ordering of this type reflects this order.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IndexWatermark {
+    pub updated_at: OffsetDateTimeMs,
+    pub id: RecordId,
+}
+
+impl From<&RecordHeader> for IndexWatermark {
+    fn from(from: &RecordHeader) -> Self {
+        let RecordHeader { id, updated_at, .. } = from;
+        Self {
+            updated_at: updated_at.clone(),
+            id: *id,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ReplaceParams {
     pub mode: ReplaceMode,
@@ -104,6 +138,29 @@ pub trait CollectionRepo {
         track: Track,
     ) -> RepoResult<ReplaceOutcome>;
 
+    /// Load multiple tracks of a collection by their UIDs.
+    ///
+    /// Found tracks are collected in the requested order. The UIDs
+    /// of all tracks that could not be found are returned.
+    fn load_tracks_by_uids(
+        &mut self,
+        collection_id: CollectionId,
+        uids: &[TrackUid],
+        collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+    ) -> RepoResult<Vec<TrackUid>>;
+
+    /// Delete multiple tracks of a collection by their UIDs.
+    ///
+    /// Tracks that are still referenced by playlist entries are not
+    /// deleted. Deleting the remaining tracks proceeds regardless.
+    /// The media sources of deleted tracks are deleted as well.
+    /// The outcomes are returned in the requested order.
+    fn delete_tracks_by_uids(
+        &mut self,
+        collection_id: CollectionId,
+        uids: &[TrackUid],
+    ) -> RepoResult<Vec<(TrackUid, DeleteOutcome)>>;
+
     fn search_tracks(
         &mut self,
         collection_id: CollectionId,
@@ -111,6 +168,28 @@ pub trait CollectionRepo {
         filter: Option<&Filter>,
         ordering: &[SortOrder],
         collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+    ) -> RepoResult<usize> {
+        self.search_tracks_with_field_groups(
+            collection_id,
+            pagination,
+            filter,
+            ordering,
+            FieldGroups::all(),
+            collector,
+        )
+    }
+
+    /// Search tracks and only load the requested field groups.
+    ///
+    /// The fields of unrequested groups are empty.
+    fn search_tracks_with_field_groups(
+        &mut self,
+        collection_id: CollectionId,
+        pagination: &Pagination,
+        filter: Option<&Filter>,
+        ordering: &[SortOrder],
+        field_groups: FieldGroups,
+        collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
     ) -> RepoResult<usize>;
 
     fn count_tracks(&mut self, collection_id: CollectionId) -> RepoResult<u64>;
@@ -127,6 +206,32 @@ pub trait CollectionRepo {
         pagination: &Pagination,
         content_path_predicate: Option<StringPredicate<'_>>,
     ) -> RepoResult<Vec<(EntityHeader, RecordHeader, RecordTrail)>>;
+
+    /// Load tracks that have been updated after the watermark.
+    ///
+    /// Loads all tracks if no watermark is given. The tracks are
+    /// collected in ascending order, i.e. the header of the last
+    /// track could be stored as the new watermark. Deleted tracks
+    /// are not detected.
+    fn load_tracks_updated_after(
+        &mut self,
+        collection_id: CollectionId,
+        watermark: Option<&IndexWatermark>,
+        limit: u64,
+        collector: &mut dyn ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+    ) -> RepoResult<usize>;
+
+    fn load_track_index_watermark(
+        &mut self,
+        collection_id: CollectionId,
+    ) -> RepoResult<Option<IndexWatermark>>;
+
+    /// Store or reset the watermark of a collection.
+    fn store_track_index_watermark(
+        &mut self,
+        collection_id: CollectionId,
+        watermark: Option<&IndexWatermark>,
+    ) -> RepoResult<()>;
 }
 
 pub trait ActorRepo {
@@ -136,3 +241,39 @@ pub trait ActorRepo {
         summary_splitter: &ActorNamesSummarySplitter,
     ) -> RepoResult<Vec<String>>;
 }
+
+pub trait TagRepo {
+    /// Find labels that co-occur with the given tag on the same tracks.
+    ///
+    /// Only considers labels of the same facet, excluding the given
+    /// label itself. Returns at most `limit` labels together with the
+    /// number of tracks they share with the given tag, ordered by this
+    /// number in descending order.
+    fn related_tags(
+        &mut self,
+        collection_id: CollectionId,
+        facet_id: Option<&FacetId<'_>>,
+        label: &Label<'_>,
+        limit: u64,
+    ) -> RepoResult<Vec<(String, usize)>>;
+}
+
+/// Custom properties of tracks that are not covered by the schema.
+///
+/// Custom properties are not loaded together with tracks.
+pub trait CustomPropertyRepo {
+    /// Load all custom properties of a track ordered by key.
+    fn load_track_custom_properties(&mut self, id: RecordId) -> RepoResult<Vec<(String, String)>>;
+
+    fn load_track_custom_property(&mut self, id: RecordId, key: &str) -> RepoResult<String>;
+
+    /// Insert or replace the value of a custom property.
+    fn store_track_custom_property(
+        &mut self,
+        id: RecordId,
+        key: &str,
+        value: &str,
+    ) -> RepoResult<()>;
+
+    fn delete_track_custom_property(&mut self, id: RecordId, key: &str) -> RepoResult<()>;
+}
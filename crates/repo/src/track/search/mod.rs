@@ -0,0 +1,684 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Persistent representation of track search parameters.
+//!
+//! Mirrors the search types of `aoide-core-api` that are not serializable.
+//! The stored format must not change implicitly when the wire format of
+//! the web API evolves, i.e. it is owned by the repository.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use aoide_core::{
+    tag::{FacetId, FacetKey, Label, ScoreValue},
+    track::{
+        actor::{Kind as ActorKind, Role as ActorRole},
+        title::Kind as TitleKind,
+    },
+    util::clock::OffsetDateTimeMs,
+    EntityUid, PlaylistUid, TrackUid,
+};
+use aoide_core_api::{
+    filtering::{
+        CustomPropertyEquals, FacetLabelsPredicate, FacetScoreAtLeast, FacetedTagsPredicate,
+        FilterModifier, ScalarFieldFilter, ScalarPredicate, StringFilter, StringPredicate,
+    },
+    tag::search::{FacetsFilter, Filter as TagFilter},
+    track::search::{
+        ActorPhraseFilter, ConditionFilter, DateTimeField, Filter, NumericField, PhraseFieldFilter,
+        Scope, SortField, SortOrder, StringField, TitlePhraseFilter,
+    },
+    SortDirection,
+};
+
+/// Maps a persistent enum onto its counterpart and back.
+macro_rules! persisted_enum {
+    ($persisted:ident, $origin:ty, { $($variant:ident),+ $(,)? }) => {
+        #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+        #[allow(clippy::enum_variant_names)] // Mirrors the variants of the origin
+        enum $persisted {
+            $($variant,)+
+        }
+
+        impl From<$origin> for $persisted {
+            fn from(from: $origin) -> Self {
+                match from {
+                    $(<$origin>::$variant => Self::$variant,)+
+                }
+            }
+        }
+
+        impl From<$persisted> for $origin {
+            fn from(from: $persisted) -> Self {
+                match from {
+                    $($persisted::$variant => Self::$variant,)+
+                }
+            }
+        }
+    };
+}
+
+persisted_enum!(PersistedSortDirection, SortDirection, {
+    Ascending,
+    Descending,
+});
+
+persisted_enum!(PersistedSortField, SortField, {
+    AlbumTitle,
+    AudioBitrateBps,
+    AudioChannelCount,
+    AudioChannelMask,
+    AudioDurationMs,
+    AudioLoudnessLufs,
+    AudioSampleRateHz,
+    CollectedAt,
+    ContentPath,
+    ContentType,
+    Copyright,
+    CreatedAt,
+    DiscNumber,
+    DiscTotal,
+    MusicTempoBpm,
+    MusicKeyCode,
+    Publisher,
+    RecordedAtDate,
+    ReleasedAtDate,
+    ReleasedOrigAtDate,
+    TrackNumber,
+    TrackTotal,
+    UpdatedAt,
+});
+
+persisted_enum!(PersistedStringField, StringField, {
+    ContentPath,
+    ContentType,
+    Copyright,
+    Publisher,
+});
+
+persisted_enum!(PersistedNumericField, NumericField, {
+    AdvisoryRating,
+    ArtworkDataSize,
+    ArtworkImageHeight,
+    ArtworkImageWidth,
+    AudioBitrateBps,
+    AudioChannelCount,
+    AudioChannelMask,
+    AudioDurationMs,
+    AudioLoudnessLufs,
+    AudioSampleRateHz,
+    DiscNumber,
+    DiscTotal,
+    MusicTempoBpm,
+    MusicKeyCode,
+    RecordedAtDate,
+    ReleasedAtDate,
+    ReleasedOrigAtDate,
+    TrackNumber,
+    TrackTotal,
+});
+
+persisted_enum!(PersistedDateTimeField, DateTimeField, {
+    CollectedAt,
+    RecordedAt,
+    ReleasedAt,
+    ReleasedOrigAt,
+});
+
+persisted_enum!(PersistedConditionFilter, ConditionFilter, {
+    SourceTracked,
+    SourceUntracked,
+    SourceNeverSynchronized,
+    TrackTitleBlank,
+    AlbumTitleBlank,
+});
+
+persisted_enum!(PersistedFilterModifier, FilterModifier, { Complement });
+
+persisted_enum!(PersistedScope, Scope, { Track, Album });
+
+persisted_enum!(PersistedActorRole, ActorRole, {
+    Artist,
+    Arranger,
+    Composer,
+    Conductor,
+    MixDj,
+    Engineer,
+    Lyricist,
+    MixEngineer,
+    Performer,
+    Producer,
+    Director,
+    Remixer,
+    Writer,
+});
+
+persisted_enum!(PersistedActorKind, ActorKind, {
+    Summary,
+    Individual,
+    Sorting,
+});
+
+persisted_enum!(PersistedTitleKind, TitleKind, {
+    Main,
+    Sub,
+    Sorting,
+    Work,
+    Movement,
+});
+
+/// Persistent representation of [`SortOrder`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersistedSortOrder {
+    field: PersistedSortField,
+    direction: PersistedSortDirection,
+}
+
+impl From<SortOrder> for PersistedSortOrder {
+    fn from(from: SortOrder) -> Self {
+        let SortOrder { field, direction } = from;
+        Self {
+            field: field.into(),
+            direction: direction.into(),
+        }
+    }
+}
+
+impl From<PersistedSortOrder> for SortOrder {
+    fn from(from: PersistedSortOrder) -> Self {
+        let PersistedSortOrder { field, direction } = from;
+        Self {
+            field: field.into(),
+            direction: direction.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum PersistedStringPredicate {
+    StartsWith(String),
+    StartsNotWith(String),
+    EndsWith(String),
+    EndsNotWith(String),
+    Contains(String),
+    ContainsNot(String),
+    Matches(String),
+    MatchesNot(String),
+    Equals(String),
+    EqualsNot(String),
+    Prefix(String),
+}
+
+impl From<StringPredicate<'static>> for PersistedStringPredicate {
+    fn from(from: StringPredicate<'static>) -> Self {
+        match from {
+            StringPredicate::StartsWith(s) => Self::StartsWith(s.into_owned()),
+            StringPredicate::StartsNotWith(s) => Self::StartsNotWith(s.into_owned()),
+            StringPredicate::EndsWith(s) => Self::EndsWith(s.into_owned()),
+            StringPredicate::EndsNotWith(s) => Self::EndsNotWith(s.into_owned()),
+            StringPredicate::Contains(s) => Self::Contains(s.into_owned()),
+            StringPredicate::ContainsNot(s) => Self::ContainsNot(s.into_owned()),
+            StringPredicate::Matches(s) => Self::Matches(s.into_owned()),
+            StringPredicate::MatchesNot(s) => Self::MatchesNot(s.into_owned()),
+            StringPredicate::Equals(s) => Self::Equals(s.into_owned()),
+            StringPredicate::EqualsNot(s) => Self::EqualsNot(s.into_owned()),
+            StringPredicate::Prefix(s) => Self::Prefix(s.into_owned()),
+        }
+    }
+}
+
+impl From<PersistedStringPredicate> for StringPredicate<'static> {
+    fn from(from: PersistedStringPredicate) -> Self {
+        match from {
+            PersistedStringPredicate::StartsWith(s) => Self::StartsWith(Cow::Owned(s)),
+            PersistedStringPredicate::StartsNotWith(s) => Self::StartsNotWith(Cow::Owned(s)),
+            PersistedStringPredicate::EndsWith(s) => Self::EndsWith(Cow::Owned(s)),
+            PersistedStringPredicate::EndsNotWith(s) => Self::EndsNotWith(Cow::Owned(s)),
+            PersistedStringPredicate::Contains(s) => Self::Contains(Cow::Owned(s)),
+            PersistedStringPredicate::ContainsNot(s) => Self::ContainsNot(Cow::Owned(s)),
+            PersistedStringPredicate::Matches(s) => Self::Matches(Cow::Owned(s)),
+            PersistedStringPredicate::MatchesNot(s) => Self::MatchesNot(Cow::Owned(s)),
+            PersistedStringPredicate::Equals(s) => Self::Equals(Cow::Owned(s)),
+            PersistedStringPredicate::EqualsNot(s) => Self::EqualsNot(Cow::Owned(s)),
+            PersistedStringPredicate::Prefix(s) => Self::Prefix(Cow::Owned(s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedStringFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modifier: Option<PersistedFilterModifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<PersistedStringPredicate>,
+}
+
+impl From<StringFilter<'static>> for PersistedStringFilter {
+    fn from(from: StringFilter<'static>) -> Self {
+        let StringFilter { modifier, value } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            value: value.map(Into::into),
+        }
+    }
+}
+
+impl From<PersistedStringFilter> for StringFilter<'static> {
+    fn from(from: PersistedStringFilter) -> Self {
+        let PersistedStringFilter { modifier, value } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            value: value.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum PersistedScalarPredicate<V> {
+    LessThan(V),
+    LessOrEqual(V),
+    GreaterThan(V),
+    GreaterOrEqual(V),
+    Equal(Option<V>),
+    NotEqual(Option<V>),
+}
+
+impl<V> From<ScalarPredicate<V>> for PersistedScalarPredicate<V> {
+    fn from(from: ScalarPredicate<V>) -> Self {
+        match from {
+            ScalarPredicate::LessThan(v) => Self::LessThan(v),
+            ScalarPredicate::LessOrEqual(v) => Self::LessOrEqual(v),
+            ScalarPredicate::GreaterThan(v) => Self::GreaterThan(v),
+            ScalarPredicate::GreaterOrEqual(v) => Self::GreaterOrEqual(v),
+            ScalarPredicate::Equal(v) => Self::Equal(v),
+            ScalarPredicate::NotEqual(v) => Self::NotEqual(v),
+        }
+    }
+}
+
+impl<V> From<PersistedScalarPredicate<V>> for ScalarPredicate<V> {
+    fn from(from: PersistedScalarPredicate<V>) -> Self {
+        match from {
+            PersistedScalarPredicate::LessThan(v) => Self::LessThan(v),
+            PersistedScalarPredicate::LessOrEqual(v) => Self::LessOrEqual(v),
+            PersistedScalarPredicate::GreaterThan(v) => Self::GreaterThan(v),
+            PersistedScalarPredicate::GreaterOrEqual(v) => Self::GreaterOrEqual(v),
+            PersistedScalarPredicate::Equal(v) => Self::Equal(v),
+            PersistedScalarPredicate::NotEqual(v) => Self::NotEqual(v),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedScalarFieldFilter<F, V> {
+    field: F,
+    predicate: PersistedScalarPredicate<V>,
+}
+
+impl<F, V, G> From<ScalarFieldFilter<F, V>> for PersistedScalarFieldFilter<G, V>
+where
+    G: From<F>,
+{
+    fn from(from: ScalarFieldFilter<F, V>) -> Self {
+        let ScalarFieldFilter { field, predicate } = from;
+        Self {
+            field: field.into(),
+            predicate: predicate.into(),
+        }
+    }
+}
+
+impl<F, V, G> From<PersistedScalarFieldFilter<G, V>> for ScalarFieldFilter<F, V>
+where
+    F: From<G>,
+{
+    fn from(from: PersistedScalarFieldFilter<G, V>) -> Self {
+        let PersistedScalarFieldFilter { field, predicate } = from;
+        Self {
+            field: field.into(),
+            predicate: predicate.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum PersistedFacetsFilter {
+    Prefix(Option<FacetId<'static>>),
+    AnyOf(Vec<Option<FacetId<'static>>>),
+    NoneOf(Vec<Option<FacetId<'static>>>),
+}
+
+impl From<FacetsFilter<'static>> for PersistedFacetsFilter {
+    fn from(from: FacetsFilter<'static>) -> Self {
+        match from {
+            FacetsFilter::Prefix(key) => Self::Prefix(key.into_inner()),
+            FacetsFilter::AnyOf(keys) => {
+                Self::AnyOf(keys.into_iter().map(FacetKey::into_inner).collect())
+            }
+            FacetsFilter::NoneOf(keys) => {
+                Self::NoneOf(keys.into_iter().map(FacetKey::into_inner).collect())
+            }
+        }
+    }
+}
+
+impl From<PersistedFacetsFilter> for FacetsFilter<'static> {
+    fn from(from: PersistedFacetsFilter) -> Self {
+        match from {
+            PersistedFacetsFilter::Prefix(key) => Self::Prefix(key.into()),
+            PersistedFacetsFilter::AnyOf(keys) => {
+                Self::AnyOf(keys.into_iter().map(Into::into).collect())
+            }
+            PersistedFacetsFilter::NoneOf(keys) => {
+                Self::NoneOf(keys.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedTagFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modifier: Option<PersistedFilterModifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    facets: Option<PersistedFacetsFilter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<PersistedStringPredicate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    score: Option<PersistedScalarPredicate<f64>>,
+}
+
+impl From<TagFilter> for PersistedTagFilter {
+    fn from(from: TagFilter) -> Self {
+        let TagFilter {
+            modifier,
+            facets,
+            label,
+            score,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            facets: facets.map(Into::into),
+            label: label.map(Into::into),
+            score: score.map(Into::into),
+        }
+    }
+}
+
+impl From<PersistedTagFilter> for TagFilter {
+    fn from(from: PersistedTagFilter) -> Self {
+        let PersistedTagFilter {
+            modifier,
+            facets,
+            label,
+            score,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            facets: facets.map(Into::into),
+            label: label.map(Into::into),
+            score: score.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedFacetLabelsPredicate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modifier: Option<PersistedFilterModifier>,
+    facet: Option<FacetId<'static>>,
+    any_of: Vec<Label<'static>>,
+}
+
+impl From<FacetLabelsPredicate> for PersistedFacetLabelsPredicate {
+    fn from(from: FacetLabelsPredicate) -> Self {
+        let FacetLabelsPredicate {
+            modifier,
+            facet,
+            any_of,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            facet: facet.into_inner(),
+            any_of,
+        }
+    }
+}
+
+impl From<PersistedFacetLabelsPredicate> for FacetLabelsPredicate {
+    fn from(from: PersistedFacetLabelsPredicate) -> Self {
+        let PersistedFacetLabelsPredicate {
+            modifier,
+            facet,
+            any_of,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            facet: facet.into(),
+            any_of,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedActorPhraseFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modifier: Option<PersistedFilterModifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<PersistedScope>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    roles: Vec<PersistedActorRole>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    kinds: Vec<PersistedActorKind>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    name_terms: Vec<String>,
+}
+
+impl From<ActorPhraseFilter> for PersistedActorPhraseFilter {
+    fn from(from: ActorPhraseFilter) -> Self {
+        let ActorPhraseFilter {
+            modifier,
+            scope,
+            roles,
+            kinds,
+            name_terms,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            scope: scope.map(Into::into),
+            roles: roles.into_iter().map(Into::into).collect(),
+            kinds: kinds.into_iter().map(Into::into).collect(),
+            name_terms,
+        }
+    }
+}
+
+impl From<PersistedActorPhraseFilter> for ActorPhraseFilter {
+    fn from(from: PersistedActorPhraseFilter) -> Self {
+        let PersistedActorPhraseFilter {
+            modifier,
+            scope,
+            roles,
+            kinds,
+            name_terms,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            scope: scope.map(Into::into),
+            roles: roles.into_iter().map(Into::into).collect(),
+            kinds: kinds.into_iter().map(Into::into).collect(),
+            name_terms,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedTitlePhraseFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modifier: Option<PersistedFilterModifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<PersistedScope>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    kinds: Vec<PersistedTitleKind>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    name_terms: Vec<String>,
+}
+
+impl From<TitlePhraseFilter> for PersistedTitlePhraseFilter {
+    fn from(from: TitlePhraseFilter) -> Self {
+        let TitlePhraseFilter {
+            modifier,
+            scope,
+            kinds,
+            name_terms,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            scope: scope.map(Into::into),
+            kinds: kinds.into_iter().map(Into::into).collect(),
+            name_terms,
+        }
+    }
+}
+
+impl From<PersistedTitlePhraseFilter> for TitlePhraseFilter {
+    fn from(from: PersistedTitlePhraseFilter) -> Self {
+        let PersistedTitlePhraseFilter {
+            modifier,
+            scope,
+            kinds,
+            name_terms,
+        } = from;
+        Self {
+            modifier: modifier.map(Into::into),
+            scope: scope.map(Into::into),
+            kinds: kinds.into_iter().map(Into::into).collect(),
+            name_terms,
+        }
+    }
+}
+
+/// Persistent representation of [`Filter`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PersistedFilter(PersistedFilterInner);
+
+impl From<Filter> for PersistedFilter {
+    fn from(from: Filter) -> Self {
+        Self(from.into())
+    }
+}
+
+impl From<PersistedFilter> for Filter {
+    fn from(from: PersistedFilter) -> Self {
+        let PersistedFilter(inner) = from;
+        inner.into()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum PersistedFilterInner {
+    Phrase {
+        fields: Vec<PersistedStringField>,
+        terms: Vec<String>,
+    },
+    ActorPhrase(PersistedActorPhraseFilter),
+    TitlePhrase(PersistedTitlePhraseFilter),
+    Numeric(PersistedScalarFieldFilter<PersistedNumericField, f64>),
+    DateTime(PersistedScalarFieldFilter<PersistedDateTimeField, OffsetDateTimeMs>),
+    Condition(PersistedConditionFilter),
+    Tag(PersistedTagFilter),
+    FacetedTags(Vec<PersistedFacetLabelsPredicate>),
+    FacetScoreAtLeast {
+        facet_id: FacetId<'static>,
+        min: ScoreValue,
+    },
+    CustomPropertyEquals {
+        key: String,
+        value: String,
+    },
+    CueLabel(PersistedStringFilter),
+    AnyTrackUid(Vec<EntityUid>),
+    AnyPlaylistUid(Vec<EntityUid>),
+    All(Vec<PersistedFilterInner>),
+    Any(Vec<PersistedFilterInner>),
+    Not(Box<PersistedFilterInner>),
+}
+
+impl From<Filter> for PersistedFilterInner {
+    fn from(from: Filter) -> Self {
+        match from {
+            Filter::Phrase(PhraseFieldFilter { fields, terms }) => Self::Phrase {
+                fields: fields.into_iter().map(Into::into).collect(),
+                terms,
+            },
+            Filter::ActorPhrase(filter) => Self::ActorPhrase(filter.into()),
+            Filter::TitlePhrase(filter) => Self::TitlePhrase(filter.into()),
+            Filter::Numeric(filter) => Self::Numeric(filter.into()),
+            Filter::DateTime(filter) => Self::DateTime(filter.into()),
+            Filter::Condition(filter) => Self::Condition(filter.into()),
+            Filter::Tag(filter) => Self::Tag(filter.into()),
+            Filter::FacetedTags(FacetedTagsPredicate { all_of }) => {
+                Self::FacetedTags(all_of.into_iter().map(Into::into).collect())
+            }
+            Filter::FacetScoreAtLeast(FacetScoreAtLeast { facet_id, min }) => {
+                Self::FacetScoreAtLeast { facet_id, min }
+            }
+            Filter::CustomPropertyEquals(CustomPropertyEquals { key, value }) => {
+                Self::CustomPropertyEquals { key, value }
+            }
+            Filter::CueLabel(filter) => Self::CueLabel(filter.into()),
+            Filter::AnyTrackUid(uids) => {
+                Self::AnyTrackUid(uids.into_iter().map(TrackUid::into_untyped).collect())
+            }
+            Filter::AnyPlaylistUid(uids) => {
+                Self::AnyPlaylistUid(uids.into_iter().map(PlaylistUid::into_untyped).collect())
+            }
+            Filter::All(filters) => Self::All(filters.into_iter().map(Into::into).collect()),
+            Filter::Any(filters) => Self::Any(filters.into_iter().map(Into::into).collect()),
+            Filter::Not(filter) => Self::Not(Box::new((*filter).into())),
+        }
+    }
+}
+
+impl From<PersistedFilterInner> for Filter {
+    fn from(from: PersistedFilterInner) -> Self {
+        match from {
+            PersistedFilterInner::Phrase { fields, terms } => Self::Phrase(PhraseFieldFilter {
+                fields: fields.into_iter().map(Into::into).collect(),
+                terms,
+            }),
+            PersistedFilterInner::ActorPhrase(filter) => Self::ActorPhrase(filter.into()),
+            PersistedFilterInner::TitlePhrase(filter) => Self::TitlePhrase(filter.into()),
+            PersistedFilterInner::Numeric(filter) => Self::Numeric(filter.into()),
+            PersistedFilterInner::DateTime(filter) => Self::DateTime(filter.into()),
+            PersistedFilterInner::Condition(filter) => Self::Condition(filter.into()),
+            PersistedFilterInner::Tag(filter) => Self::Tag(filter.into()),
+            PersistedFilterInner::FacetedTags(all_of) => Self::FacetedTags(FacetedTagsPredicate {
+                all_of: all_of.into_iter().map(Into::into).collect(),
+            }),
+            PersistedFilterInner::FacetScoreAtLeast { facet_id, min } => {
+                Self::FacetScoreAtLeast(FacetScoreAtLeast { facet_id, min })
+            }
+            PersistedFilterInner::CustomPropertyEquals { key, value } => {
+                Self::CustomPropertyEquals(CustomPropertyEquals { key, value })
+            }
+            PersistedFilterInner::CueLabel(filter) => Self::CueLabel(filter.into()),
+            PersistedFilterInner::AnyTrackUid(uids) => {
+                Self::AnyTrackUid(uids.into_iter().map(TrackUid::from_untyped).collect())
+            }
+            PersistedFilterInner::AnyPlaylistUid(uids) => {
+                Self::AnyPlaylistUid(uids.into_iter().map(PlaylistUid::from_untyped).collect())
+            }
+            PersistedFilterInner::All(filters) => {
+                Self::All(filters.into_iter().map(Into::into).collect())
+            }
+            PersistedFilterInner::Any(filters) => {
+                Self::Any(filters.into_iter().map(Into::into).collect())
+            }
+            PersistedFilterInner::Not(filter) => Self::Not(Box::new((*filter).into())),
+        }
+    }
+}
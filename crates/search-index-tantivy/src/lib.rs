@@ -1,30 +1,49 @@
 // aoide.org - Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{borrow::Cow, fs, path::Path};
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    fs,
+    io::{self, Write as _},
+    ops::Bound,
+    path::{Path, PathBuf},
+};
 
 use aoide_core::{
+    audio::signal::LoudnessLufs,
     media::content::ContentMetadata,
-    tag::{FacetId as TagFacetId, FacetedTags, PlainTag},
+    tag::{FacetId as TagFacetId, FacetedTags, Label, PlainTag, Score},
     track::{
-        actor::Actors,
+        actor::{Actors, Role as ActorRole, DEFAULT_ARTIST_ROLES},
         tag::{
             FACET_ACOUSTICNESS, FACET_AROUSAL, FACET_COMMENT, FACET_DANCEABILITY, FACET_ENERGY,
             FACET_GENRE, FACET_GROUPING, FACET_INSTRUMENTALNESS, FACET_LIVENESS, FACET_MOOD,
             FACET_POPULARITY, FACET_SPEECHINESS, FACET_VALENCE,
         },
+        title::{Kind as TitleKind, Titles},
         PlayCounter,
     },
     util::clock::{OffsetDateTimeMs, YyyyMmDdDate},
     CollectionUid, EncodedEntityUid, EntityRevision, EntityUid, TrackEntity, TrackUid,
 };
 use tantivy::{
+    aggregation::{
+        agg_req::Aggregations,
+        agg_result::{AggregationResult, AggregationResults, BucketEntries, BucketResult},
+        AggregationCollector, AggregationLimits, Key,
+    },
     collector::TopDocs,
-    directory::MmapDirectory,
-    query::{AllQuery, Query as _, TermQuery},
-    schema::{Field, IndexRecordOption, Schema, Value, INDEXED, STORED, STRING, TEXT},
-    Index, Searcher, TantivyDocument, TantivyError, Term,
+    directory::{error::OpenReadError, Directory as _, MmapDirectory},
+    query::{AllQuery, BooleanQuery, BoostQuery, Occur, Query, RangeQuery, TermQuery},
+    schema::{
+        Facet, FacetOptions, Field, IndexRecordOption, Schema, Value, FAST, INDEXED, STORED,
+        STRING, TEXT,
+    },
+    tokenizer::TokenStream as _,
+    Index, IndexWriter, ReloadPolicy, Searcher, TantivyDocument, TantivyError, Term,
 };
+use thiserror::Error;
 
 const COLLECTION_UID: &str = "collection_uid";
 const UID: &str = "uid";
@@ -33,10 +52,17 @@ const CONTENT_PATH: &str = "content_path";
 const CONTENT_TYPE: &str = "content_type";
 const COLLECTED_AT: &str = "collected_at";
 const DURATION_MS: &str = "duration_ms";
+const LOUDNESS_LUFS: &str = "loudness_lufs";
 const TRACK_TITLE: &str = "track_title";
 const TRACK_ARTIST: &str = "track_artist";
 const ALBUM_TITLE: &str = "album_title";
 const ALBUM_ARTIST: &str = "album_artist";
+const COMPOSER: &str = "composer";
+const REMIXER: &str = "remixer";
+const PRODUCER: &str = "producer";
+const CONDUCTOR: &str = "conductor";
+const DISC_NUMBER: &str = "disc_number";
+const TRACK_NUMBER: &str = "track_number";
 const RECORDED_AT_YYYYMMDD: &str = "recorded_at_yyyymmdd";
 const RELEASED_AT_YYYYMMDD: &str = "released_at_yyyymmdd";
 const RELEASED_ORIG_AT_YYYYMMDD: &str = "released_orig_at_yyyymmdd";
@@ -49,6 +75,8 @@ const MOOD: &str = "mood";
 const COMMENT: &str = "comment";
 const GROUPING: &str = "grouping";
 const TAG: &str = "tag";
+const TAG_EXACT: &str = "tag_exact";
+const FACETED_TAG: &str = "faceted_tag";
 const ACOUSTICNESS: &str = "acousticness";
 const AROUSAL: &str = "arousal";
 const DANCEABILITY: &str = "danceability";
@@ -59,6 +87,65 @@ const POPULARITY: &str = "popularity";
 const SPEECHINESS: &str = "speechiness";
 const VALENCE: &str = "valence";
 
+/// The version of how documents are populated from tracks.
+///
+/// Must be incremented whenever the contents of existing fields change
+/// in an incompatible way that cannot be detected by comparing the
+/// schemas, e.g. when switching tokenizers. A version mismatch
+/// recreates the index.
+const SCHEMA_VERSION: u32 = 2;
+
+/// The file in the index directory that stores the [`SCHEMA_VERSION`].
+///
+/// Existing indexes without this file are considered as outdated.
+const SCHEMA_VERSION_FILE_NAME: &str = "aoide-schema-version";
+
+/// The file in the index directory that marks a pending re-index.
+///
+/// Created when migrating the schema and only removed after all tracks
+/// have been re-indexed. Survives restarts of the application.
+const REINDEX_MARKER_FILE_NAME: &str = "aoide-reindex-required";
+
+/// The file in the index directory that stores the metadata and schema.
+const META_FILE_NAME: &str = "meta.json";
+
+/// Prefix of the field names for custom scored facets.
+const SCORED_FACET_FIELD_NAME_PREFIX: &str = "scored_facet_";
+
+/// The minimum memory budget of tantivy for a single writer thread.
+const OPTIMIZE_WRITER_MEMORY_BUDGET_BYTES: usize = 15_000_000;
+
+/// Boost factors of the seed terms for finding similar tracks.
+const MORE_LIKE_THIS_GENRE_BOOST: f32 = 3.0;
+const MORE_LIKE_THIS_ARTIST_BOOST: f32 = 2.0;
+const MORE_LIKE_THIS_MOOD_BOOST: f32 = 1.5;
+const MORE_LIKE_THIS_TEMPO_BPM_BOOST: f32 = 2.0;
+
+/// Maximum distance from the tempo of the seed for finding similar tracks.
+const MORE_LIKE_THIS_TEMPO_BPM_DELTA: f64 = 5.0;
+
+/// The name of the histogram aggregation for counting tracks by tempo.
+const TEMPO_BPM_HISTOGRAM: &str = "tempo_bpm_histogram";
+
+/// A track found in the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackSearchHit {
+    pub uid: TrackUid,
+    pub score: f32,
+}
+
+/// The number of tracks within a tempo range
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoBpmBucket {
+    /// Inclusive lower bound
+    pub min_bpm: f64,
+
+    /// Exclusive upper bound
+    pub max_bpm: f64,
+
+    pub count: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct TrackFields {
     pub collection_uid: Field,
@@ -68,10 +155,17 @@ pub struct TrackFields {
     pub content_type: Field,
     pub collected_at: Field,
     pub duration_ms: Field,
+    pub loudness_lufs: Field,
     pub track_title: Field,
     pub track_artist: Field,
     pub album_title: Field,
     pub album_artist: Field,
+    pub composer: Field,
+    pub remixer: Field,
+    pub producer: Field,
+    pub conductor: Field,
+    pub disc_number: Field,
+    pub track_number: Field,
     pub recorded_at_yyyymmdd: Field,
     pub released_at_yyyymmdd: Field,
     pub released_orig_at_yyyymmdd: Field,
@@ -84,6 +178,8 @@ pub struct TrackFields {
     pub comment: Field,
     pub grouping: Field,
     pub tag: Field,
+    pub tag_exact: Field,
+    pub faceted_tag: Field,
     pub acousticness: Field,
     pub arousal: Field,
     pub danceability: Field,
@@ -93,6 +189,58 @@ pub struct TrackFields {
     pub popularity: Field,
     pub speechiness: Field,
     pub valence: Field,
+
+    /// Custom facets that are indexed by score.
+    ///
+    /// Registered when building the schema. Tags of these facets are
+    /// indexed by their score instead of their label, like the tags
+    /// of predefined facets, e.g. [`FACET_ENERGY`].
+    pub scored_facets: Vec<ScoredFacetField>,
+
+    /// Roles of the track actors that are indexed as `track_artist`.
+    ///
+    /// Initialized with [`DEFAULT_ARTIST_ROLES`].
+    pub track_artist_roles: Vec<ActorRole>,
+}
+
+/// A custom facet with a dedicated score field
+#[derive(Debug, Clone)]
+pub struct ScoredFacetField {
+    pub facet_id: TagFacetId<'static>,
+    pub field_name: String,
+    pub field: Field,
+}
+
+/// Map predefined scored facets onto the name of their dedicated field.
+fn predefined_scored_facet_field_name(facet_id: &str) -> Option<&'static str> {
+    let field_name = match facet_id {
+        FACET_ACOUSTICNESS => ACOUSTICNESS,
+        FACET_AROUSAL => AROUSAL,
+        FACET_DANCEABILITY => DANCEABILITY,
+        FACET_ENERGY => ENERGY,
+        FACET_INSTRUMENTALNESS => INSTRUMENTALNESS,
+        FACET_LIVENESS => LIVENESS,
+        FACET_POPULARITY => POPULARITY,
+        FACET_SPEECHINESS => SPEECHINESS,
+        FACET_VALENCE => VALENCE,
+        _ => return None,
+    };
+    Some(field_name)
+}
+
+/// Derive the field name of a custom scored facet, e.g. `mood/intensity`
+/// is mapped onto `scored_facet_mood_intensity`.
+///
+/// Special characters are replaced by `_`. The field names of different
+/// facets may collide.
+fn custom_scored_facet_field_name(facet_id: &TagFacetId<'_>) -> String {
+    facet_id
+        .as_str()
+        .chars()
+        .fold(SCORED_FACET_FIELD_NAME_PREFIX.to_owned(), |mut name, c| {
+            name.push(if c.is_ascii_alphanumeric() { c } else { '_' });
+            name
+        })
 }
 
 fn add_date_field(doc: &mut TantivyDocument, field: Field, date_time: &OffsetDateTimeMs) {
@@ -101,7 +249,52 @@ fn add_date_field(doc: &mut TantivyDocument, field: Field, date_time: &OffsetDat
 
 const TAG_LABEL_PREFIX: char = '#';
 
+/// Separates the path components of hierarchical tag facets, e.g. `mood/calm`.
+const TAG_FACET_PATH_SEPARATOR: char = '/';
+
+/// Map a (hierarchical) tag facet identifier onto a facet path.
+///
+/// Returns `None` if the identifier doesn't contain any path components.
+fn facet_from_tag_facet_path(facet_path: &str) -> Option<Facet> {
+    let path_components = facet_path
+        .split(TAG_FACET_PATH_SEPARATOR)
+        .filter(|path_component| !path_component.is_empty())
+        .collect::<Vec<_>>();
+    if path_components.is_empty() {
+        return None;
+    }
+    Some(Facet::from_path(path_components))
+}
+
+/// Format the text of generic tags, i.e. `#label` or `facet#label`.
+fn format_tag_text<'a>(facet_id: Option<&TagFacetId<'_>>, label: &'a Label<'a>) -> Cow<'a, str> {
+    let facet_prefix = facet_id
+        .map(|facet_id| {
+            debug_assert!(!facet_id.is_empty());
+            debug_assert!(!facet_id.as_str().contains(TAG_LABEL_PREFIX));
+            facet_id.as_str()
+        })
+        .unwrap_or_default();
+    if label.as_str().starts_with(TAG_LABEL_PREFIX) {
+        // Omit the redundant prefix
+        if facet_prefix.is_empty() {
+            return Cow::Borrowed(label.as_str());
+        }
+        Cow::Owned(format!("{facet_prefix}{label}"))
+    } else {
+        Cow::Owned(format!("{facet_prefix}{TAG_LABEL_PREFIX}{label}"))
+    }
+}
+
 impl TrackFields {
+    /// The dedicated score field of a custom scored facet.
+    #[must_use]
+    pub fn scored_facet_field(&self, facet_id: &TagFacetId<'_>) -> Option<&ScoredFacetField> {
+        self.scored_facets
+            .iter()
+            .find(|scored_facet| scored_facet.facet_id == *facet_id)
+    }
+
     fn format_tag_field_text<'a>(
         &self,
         facet_id: Option<&TagFacetId<'_>>,
@@ -126,27 +319,19 @@ impl TrackFields {
             Some(FACET_MOOD) => Some((self.mood, Cow::Borrowed(label.as_str()))),
             _ => {
                 // Generic tag field
-                let facet_prefix = facet_id
-                    .map(|facet_id| {
-                        debug_assert!(!facet_id.is_empty());
-                        debug_assert!(!facet_id.as_str().contains(TAG_LABEL_PREFIX));
-                        facet_id.as_str()
-                    })
-                    .unwrap_or_default();
-                let text = if label.as_str().starts_with(TAG_LABEL_PREFIX) {
-                    // Omit the redundant prefix
-                    if facet_prefix.is_empty() {
-                        return Some((self.tag, Cow::Borrowed(label.as_str())));
-                    }
-                    format!("{facet_prefix}{label}")
-                } else {
-                    format!("{facet_prefix}{TAG_LABEL_PREFIX}{label}")
-                };
-                Some((self.tag, Cow::Owned(text)))
+                Some((self.tag, format_tag_text(facet_id, label)))
             }
         }
     }
 
+    fn add_tag_field_text(&self, doc: &mut TantivyDocument, field: Field, text: &str) {
+        if field == self.tag {
+            // Generic tags are indexed both tokenized and verbatim
+            doc.add_text(self.tag_exact, text);
+        }
+        doc.add_text(field, text);
+    }
+
     /// Create a new document from a track entity
     ///
     /// When storing tracks from multiple collections in a single index
@@ -180,12 +365,23 @@ impl TrackFields {
         if let Some(duration) = audio_metadata.duration {
             doc.add_f64(self.duration_ms, duration.value());
         }
+        if let Some(loudness) = audio_metadata.loudness {
+            doc.add_f64(self.loudness_lufs, loudness.value());
+        }
         if let Some(track_title) = entity.body.track.track_title() {
             doc.add_text(self.track_title, track_title);
         }
-        // Index all track actors as `track_artist` by name, independent of their role
-        for track_artist in &Actors::collect_all_unique_actor_names(entity.body.track.actors.iter())
-        {
+        // Index work titles as `grouping` in addition to the grouping tags.
+        // Depending on the file format and tagger the work is stored in the
+        // grouping field and vice versa, e.g. in TIT1 of ID3v2.
+        for work_title in Titles::filter_kind(entity.body.track.titles.iter(), TitleKind::Work) {
+            doc.add_text(self.grouping, &work_title.name);
+        }
+        // Index track actors of the configured roles as `track_artist` by name
+        for track_artist in &Actors::collect_names_for_roles(
+            entity.body.track.actors.iter(),
+            &self.track_artist_roles,
+        ) {
             doc.add_text(self.track_artist, track_artist);
         }
         if let Some(album_title) = entity.body.track.album_title() {
@@ -196,6 +392,30 @@ impl TrackFields {
         {
             doc.add_text(self.album_artist, album_artist);
         }
+        // Index track actors of selected roles additionally in role-specific fields
+        for (role, field) in [
+            (ActorRole::Composer, self.composer),
+            (ActorRole::Remixer, self.remixer),
+            (ActorRole::Producer, self.producer),
+            (ActorRole::Conductor, self.conductor),
+        ] {
+            for name in &Actors::collect_all_unique_actor_names(
+                entity
+                    .body
+                    .track
+                    .actors
+                    .iter()
+                    .filter(|actor| actor.role == role),
+            ) {
+                doc.add_text(field, name);
+            }
+        }
+        if let Some(disc_number) = entity.body.track.indexes.disc.number {
+            doc.add_u64(self.disc_number, disc_number.into());
+        }
+        if let Some(track_number) = entity.body.track.indexes.track.number {
+            doc.add_u64(self.track_number, track_number.into());
+        }
         if let Some(recorded_at_yyyymmdd) = entity
             .body
             .track
@@ -203,7 +423,10 @@ impl TrackFields {
             .as_ref()
             .map(YyyyMmDdDate::from)
         {
-            doc.add_i64(self.album_artist, recorded_at_yyyymmdd.value().into());
+            doc.add_i64(
+                self.recorded_at_yyyymmdd,
+                recorded_at_yyyymmdd.value().into(),
+            );
         }
         if let Some(released_at_yyyymmdd) = entity
             .body
@@ -212,7 +435,10 @@ impl TrackFields {
             .as_ref()
             .map(YyyyMmDdDate::from)
         {
-            doc.add_i64(self.album_artist, released_at_yyyymmdd.value().into());
+            doc.add_i64(
+                self.released_at_yyyymmdd,
+                released_at_yyyymmdd.value().into(),
+            );
         }
         if let Some(released_orig_at_yyyymmdd) = entity
             .body
@@ -221,7 +447,10 @@ impl TrackFields {
             .as_ref()
             .map(YyyyMmDdDate::from)
         {
-            doc.add_i64(self.album_artist, released_orig_at_yyyymmdd.value().into());
+            doc.add_i64(
+                self.released_orig_at_yyyymmdd,
+                released_orig_at_yyyymmdd.value().into(),
+            );
         }
         if let Some(tempo_bpm) = entity.body.track.metrics.tempo_bpm {
             doc.add_f64(self.tempo_bpm, tempo_bpm.value());
@@ -244,12 +473,15 @@ impl TrackFields {
         for tag in &entity.body.track.tags.plain {
             if let Some((field, text)) = self.format_tag_field_text(None, tag) {
                 debug_assert_eq!(self.tag, field);
-                doc.add_text(field, text);
+                self.add_tag_field_text(&mut doc, field, &text);
             }
         }
         for faceted_tags in &entity.body.track.tags.facets {
             let FacetedTags { facet_id, tags } = faceted_tags;
             debug_assert!(!facet_id.is_empty());
+            if let Some(facet) = facet_from_tag_facet_path(facet_id.as_str()) {
+                doc.add_facet(self.faceted_tag, facet);
+            }
             let score_field = match facet_id.as_str() {
                 FACET_ACOUSTICNESS => self.acousticness,
                 FACET_AROUSAL => self.arousal,
@@ -261,15 +493,20 @@ impl TrackFields {
                 FACET_SPEECHINESS => self.speechiness,
                 FACET_VALENCE => self.valence,
                 _ => {
-                    for tag in tags {
-                        if let Some((field, text)) = self.format_tag_field_text(Some(facet_id), tag)
-                        {
-                            doc.add_text(field, text);
-                        } else {
-                            log::debug!("Ignoring faceted tag \"{facet_id}\": {tag:?}");
+                    if let Some(scored_facet) = self.scored_facet_field(facet_id) {
+                        scored_facet.field
+                    } else {
+                        for tag in tags {
+                            if let Some((field, text)) =
+                                self.format_tag_field_text(Some(facet_id), tag)
+                            {
+                                self.add_tag_field_text(&mut doc, field, &text);
+                            } else {
+                                log::debug!("Ignoring faceted tag \"{facet_id}\": {tag:?}");
+                            }
                         }
+                        continue;
                     }
-                    continue;
                 }
             };
             for tag in tags {
@@ -277,12 +514,119 @@ impl TrackFields {
                 if label.is_some() {
                     log::debug!("Ignoring label of \"{facet_id}\" tag: {tag:?}");
                 }
+                // Prevent invalid scores from corrupting the index
+                let Some(score) = Score::from_value_clamped(score.value()) else {
+                    log::debug!("Ignoring invalid score of \"{facet_id}\" tag: {tag:?}");
+                    continue;
+                };
                 doc.add_f64(score_field, score.value());
             }
         }
         doc
     }
 
+    /// Query all tracks with faceted tags at or below the given path.
+    ///
+    /// The query for `mood` (or `mood/`) matches tags with the facets
+    /// `mood`, `mood/calm`, and `mood/energetic`, but not `moody`.
+    ///
+    /// Returns `None` if the path doesn't contain any path components.
+    #[must_use]
+    pub fn faceted_tag_prefix_query(&self, facet_path_prefix: &str) -> Option<TermQuery> {
+        let facet = facet_from_tag_facet_path(facet_path_prefix)?;
+        Some(TermQuery::new(
+            Term::from_facet(self.faceted_tag, &facet),
+            IndexRecordOption::Basic,
+        ))
+    }
+
+    /// Query all tracks with a loudness within the given bounds.
+    ///
+    /// The loudness determines the gain that is needed for normalizing
+    /// the playback volume. Tracks without a loudness are never matched.
+    #[must_use]
+    pub fn loudness_range_query(
+        &self,
+        lower_bound: Bound<LoudnessLufs>,
+        upper_bound: Bound<LoudnessLufs>,
+    ) -> RangeQuery {
+        RangeQuery::new_f64_bounds(
+            LOUDNESS_LUFS.to_owned(),
+            lower_bound.map(LoudnessLufs::value),
+            upper_bound.map(LoudnessLufs::value),
+        )
+    }
+
+    /// Query all tracks with a score of the given facet within the given bounds.
+    ///
+    /// Supports both predefined and registered custom scored facets.
+    /// Returns `None` if the facet is not indexed by score.
+    #[must_use]
+    pub fn scored_facet_range_query(
+        &self,
+        facet_id: &TagFacetId<'_>,
+        lower_bound: Bound<Score>,
+        upper_bound: Bound<Score>,
+    ) -> Option<RangeQuery> {
+        let field_name = predefined_scored_facet_field_name(facet_id.as_str())
+            .map(ToOwned::to_owned)
+            .or_else(|| {
+                self.scored_facet_field(facet_id)
+                    .map(|scored_facet| scored_facet.field_name.clone())
+            })?;
+        Some(RangeQuery::new_f64_bounds(
+            field_name,
+            lower_bound.map(Score::value),
+            upper_bound.map(Score::value),
+        ))
+    }
+
+    /// Query all tracks without a loudness, i.e. that have not been analyzed yet.
+    #[must_use]
+    pub fn missing_loudness_query(&self) -> BooleanQuery {
+        BooleanQuery::new(vec![
+            (Occur::Must, Box::new(AllQuery)),
+            (
+                Occur::MustNot,
+                Box::new(self.loudness_range_query(Bound::Unbounded, Bound::Unbounded)),
+            ),
+        ])
+    }
+
+    /// Term for matching a generic tag exactly.
+    ///
+    /// Only matches tags with the identical facet and label.
+    #[must_use]
+    pub fn tag_term_exact(&self, facet_id: Option<&TagFacetId<'_>>, label: &Label<'_>) -> Term {
+        Term::from_field_text(self.tag_exact, &format_tag_text(facet_id, label))
+    }
+
+    /// Query generic tags by the tokens of their facet and label.
+    ///
+    /// Matches all documents with tags that contain all tokens, e.g. the
+    /// label `drum` matches both `drum` and `drum and bass`. The tokens are
+    /// matched per document and not per tag, i.e. `drum and bass` also
+    /// matches a document with the two separate tags `drum` and `bass`.
+    pub fn tag_query_tokenized(
+        &self,
+        index: &Index,
+        facet_id: Option<&TagFacetId<'_>>,
+        label: &Label<'_>,
+    ) -> tantivy::Result<BooleanQuery> {
+        let mut tokenizer = index.tokenizer_for_field(self.tag)?;
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        tokenizer
+            .token_stream(&format_tag_text(facet_id, label))
+            .process(&mut |token| {
+                let term = Term::from_field_text(self.tag, &token.text);
+                subqueries.push((
+                    Occur::Must,
+                    Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+                ));
+            });
+        Ok(BooleanQuery::new(subqueries))
+    }
+
     #[must_use]
     pub fn collection_uid_term(&self, collection_uid: &CollectionUid) -> Term {
         Term::from_field_text(
@@ -363,6 +707,158 @@ impl TrackFields {
             Ok(None)
         }
     }
+
+    fn collect_seed_terms(
+        searcher: &Searcher,
+        seed: &TantivyDocument,
+        field: Field,
+    ) -> tantivy::Result<BTreeSet<Term>> {
+        let mut tokenizer = searcher.index().tokenizer_for_field(field)?;
+        let mut terms = BTreeSet::new();
+        for value in seed.get_all(field) {
+            let Some(text) = value.as_str() else {
+                continue;
+            };
+            tokenizer.token_stream(text).process(&mut |token| {
+                terms.insert(Term::from_field_text(field, &token.text));
+            });
+        }
+        Ok(terms)
+    }
+
+    /// Find tracks that are similar to a seed document
+    ///
+    /// The similarity is based on the indexed terms of the genre, track
+    /// artist, and mood fields of the seed, weighted by their relevance,
+    /// and on the proximity of the tempo. The seed itself is excluded
+    /// from the results. If the seed belongs to a collection then only
+    /// tracks of this collection are considered.
+    ///
+    /// The results are ordered by descending similarity.
+    pub fn more_like_this(
+        &self,
+        searcher: &Searcher,
+        seed: &TantivyDocument,
+        limit: usize,
+    ) -> tantivy::Result<Vec<TrackSearchHit>> {
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for (field, boost) in [
+            (self.genre, MORE_LIKE_THIS_GENRE_BOOST),
+            (self.track_artist, MORE_LIKE_THIS_ARTIST_BOOST),
+            (self.mood, MORE_LIKE_THIS_MOOD_BOOST),
+        ] {
+            for term in Self::collect_seed_terms(searcher, seed, field)? {
+                let query = TermQuery::new(term, IndexRecordOption::WithFreqs);
+                subqueries.push((
+                    Occur::Should,
+                    Box::new(BoostQuery::new(Box::new(query), boost)),
+                ));
+            }
+        }
+        if let Some(tempo_bpm) = seed
+            .get_first(self.tempo_bpm)
+            .as_ref()
+            .and_then(Value::as_f64)
+        {
+            let query = RangeQuery::new_f64_bounds(
+                TEMPO_BPM.to_owned(),
+                Bound::Included(tempo_bpm - MORE_LIKE_THIS_TEMPO_BPM_DELTA),
+                Bound::Included(tempo_bpm + MORE_LIKE_THIS_TEMPO_BPM_DELTA),
+            );
+            subqueries.push((
+                Occur::Should,
+                Box::new(BoostQuery::new(
+                    Box::new(query),
+                    MORE_LIKE_THIS_TEMPO_BPM_BOOST,
+                )),
+            ));
+        }
+        if subqueries.is_empty() || limit == 0 {
+            // Nothing to compare with
+            return Ok(Vec::new());
+        }
+        if let Some(uid) = self.read_uid(seed) {
+            subqueries.push((Occur::MustNot, Box::new(self.uid_query(&uid))));
+        }
+        if let Some(collection_uid) = self.read_collection_uid(seed) {
+            subqueries.push((
+                Occur::Must,
+                Box::new(self.collection_uid_query(&collection_uid)),
+            ));
+        }
+        let query = BooleanQuery::new(subqueries);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, doc_addr) in top_docs {
+            let doc = searcher.doc(doc_addr)?;
+            let Some(uid) = self.read_uid(&doc) else {
+                log::warn!("Missing uid in document {doc_addr:?}");
+                continue;
+            };
+            hits.push(TrackSearchHit { uid, score });
+        }
+        Ok(hits)
+    }
+
+    /// Count the matching tracks in buckets of equal tempo ranges
+    ///
+    /// The buckets are aligned to multiples of `bucket_width_bpm`, e.g.
+    /// for a width of 10 BPM a track with 128 BPM is counted in the
+    /// bucket from 120 BPM (inclusive) to 130 BPM (exclusive). Empty
+    /// buckets between the lowest and the highest tempo are included.
+    /// Tracks without a tempo are not counted.
+    ///
+    /// The buckets are ordered by ascending tempo.
+    pub fn tempo_bpm_histogram(
+        &self,
+        searcher: &Searcher,
+        query: &dyn Query,
+        bucket_width_bpm: f64,
+    ) -> tantivy::Result<Vec<TempoBpmBucket>> {
+        if bucket_width_bpm.is_nan() || bucket_width_bpm <= 0.0 {
+            return Err(TantivyError::InvalidArgument(format!(
+                "invalid tempo bucket width: {bucket_width_bpm}"
+            )));
+        }
+        let aggregations: Aggregations = serde_json::from_value(serde_json::json!({
+            TEMPO_BPM_HISTOGRAM: {
+                "histogram": {
+                    "field": TEMPO_BPM,
+                    "interval": bucket_width_bpm,
+                },
+            },
+        }))
+        .map_err(|err| TantivyError::InvalidArgument(err.to_string()))?;
+        let collector = AggregationCollector::from_aggs(aggregations, AggregationLimits::default());
+        let AggregationResults(mut results) = searcher.search(query, &collector)?;
+        let Some(AggregationResult::BucketResult(BucketResult::Histogram { buckets })) =
+            results.remove(TEMPO_BPM_HISTOGRAM)
+        else {
+            return Err(TantivyError::InternalError(
+                "missing result of tempo histogram aggregation".to_owned(),
+            ));
+        };
+        let bucket_entries = match buckets {
+            BucketEntries::Vec(bucket_entries) => bucket_entries,
+            BucketEntries::HashMap(bucket_entries) => bucket_entries.into_values().collect(),
+        };
+        let mut buckets = bucket_entries
+            .into_iter()
+            .filter_map(|bucket_entry| {
+                let Key::F64(min_bpm) = bucket_entry.key else {
+                    log::warn!("Unexpected key of tempo bucket: {:?}", bucket_entry.key);
+                    return None;
+                };
+                Some(TempoBpmBucket {
+                    min_bpm,
+                    max_bpm: min_bpm + bucket_width_bpm,
+                    count: bucket_entry.doc_count,
+                })
+            })
+            .collect::<Vec<_>>();
+        buckets.sort_by(|lhs, rhs| lhs.min_bpm.total_cmp(&rhs.min_bpm));
+        Ok(buckets)
+    }
 }
 
 /// Create the schema for indexing tracks
@@ -370,6 +866,19 @@ impl TrackFields {
 /// Supports to index tracks from different collections.
 #[must_use]
 pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
+    build_schema_for_tracks_with_scored_facets(&[])
+}
+
+/// Create the schema for indexing tracks with custom scored facets
+///
+/// Each custom scored facet is indexed in a dedicated numeric field.
+/// Predefined scored facets and facets with colliding field names
+/// are ignored.
+#[allow(clippy::too_many_lines)] // TODO
+#[must_use]
+pub fn build_schema_for_tracks_with_scored_facets(
+    scored_facet_ids: &[TagFacetId<'_>],
+) -> (Schema, TrackFields) {
     let mut schema_builder = Schema::builder();
     let collection_uid = schema_builder.add_text_field(COLLECTION_UID, STRING | STORED);
     let uid = schema_builder.add_text_field(UID, STRING | STORED);
@@ -378,15 +887,22 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
     let content_type = schema_builder.add_text_field(CONTENT_TYPE, STRING);
     let collected_at = schema_builder.add_date_field(COLLECTED_AT, INDEXED);
     let duration_ms = schema_builder.add_f64_field(DURATION_MS, INDEXED);
+    let loudness_lufs = schema_builder.add_f64_field(LOUDNESS_LUFS, INDEXED);
     let track_title = schema_builder.add_text_field(TRACK_TITLE, TEXT);
     let track_artist = schema_builder.add_text_field(TRACK_ARTIST, TEXT);
     let album_title = schema_builder.add_text_field(ALBUM_TITLE, TEXT);
     let album_artist = schema_builder.add_text_field(ALBUM_ARTIST, TEXT);
+    let composer = schema_builder.add_text_field(COMPOSER, TEXT);
+    let remixer = schema_builder.add_text_field(REMIXER, TEXT);
+    let producer = schema_builder.add_text_field(PRODUCER, TEXT);
+    let conductor = schema_builder.add_text_field(CONDUCTOR, TEXT);
+    let disc_number = schema_builder.add_u64_field(DISC_NUMBER, INDEXED | FAST);
+    let track_number = schema_builder.add_u64_field(TRACK_NUMBER, INDEXED | FAST);
     let recorded_at_yyyymmdd = schema_builder.add_i64_field(RECORDED_AT_YYYYMMDD, INDEXED);
     let released_at_yyyymmdd = schema_builder.add_i64_field(RELEASED_AT_YYYYMMDD, INDEXED);
     let released_orig_at_yyyymmdd =
         schema_builder.add_i64_field(RELEASED_ORIG_AT_YYYYMMDD, INDEXED);
-    let tempo_bpm = schema_builder.add_f64_field(TEMPO_BPM, INDEXED);
+    let tempo_bpm = schema_builder.add_f64_field(TEMPO_BPM, INDEXED | FAST);
     let key_code = schema_builder.add_u64_field(KEY_CODE, INDEXED);
     let times_played = schema_builder.add_u64_field(TIMES_PLAYED, INDEXED);
     let last_played_at = schema_builder.add_date_field(LAST_PLAYED_AT, INDEXED);
@@ -395,6 +911,8 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
     let comment = schema_builder.add_text_field(COMMENT, TEXT);
     let grouping = schema_builder.add_text_field(GROUPING, TEXT);
     let tag = schema_builder.add_text_field(TAG, TEXT);
+    let tag_exact = schema_builder.add_text_field(TAG_EXACT, STRING);
+    let faceted_tag = schema_builder.add_facet_field(FACETED_TAG, FacetOptions::default());
     let acousticness = schema_builder.add_f64_field(ACOUSTICNESS, INDEXED);
     let arousal = schema_builder.add_f64_field(AROUSAL, INDEXED);
     let danceability = schema_builder.add_f64_field(DANCEABILITY, INDEXED);
@@ -404,6 +922,30 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
     let popularity = schema_builder.add_f64_field(POPULARITY, INDEXED);
     let speechiness = schema_builder.add_f64_field(SPEECHINESS, INDEXED);
     let valence = schema_builder.add_f64_field(VALENCE, INDEXED);
+    let mut scored_facets = Vec::<ScoredFacetField>::with_capacity(scored_facet_ids.len());
+    for facet_id in scored_facet_ids {
+        if predefined_scored_facet_field_name(facet_id.as_str()).is_some() {
+            log::debug!("Ignoring predefined scored facet \"{facet_id}\"");
+            continue;
+        }
+        let field_name = custom_scored_facet_field_name(facet_id);
+        if let Some(scored_facet) = scored_facets
+            .iter()
+            .find(|scored_facet| scored_facet.field_name == field_name)
+        {
+            log::warn!(
+                "Ignoring scored facet \"{facet_id}\" that collides with \"{other_facet_id}\"",
+                other_facet_id = scored_facet.facet_id
+            );
+            continue;
+        }
+        let field = schema_builder.add_f64_field(&field_name, INDEXED);
+        scored_facets.push(ScoredFacetField {
+            facet_id: facet_id.clone_owned(),
+            field_name,
+            field,
+        });
+    }
     let schema = schema_builder.build();
     let fields = TrackFields {
         collection_uid,
@@ -413,10 +955,17 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
         content_type,
         collected_at,
         duration_ms,
+        loudness_lufs,
         track_title,
         track_artist,
         album_title,
         album_artist,
+        composer,
+        remixer,
+        producer,
+        conductor,
+        disc_number,
+        track_number,
         recorded_at_yyyymmdd,
         released_at_yyyymmdd,
         released_orig_at_yyyymmdd,
@@ -429,6 +978,8 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
         comment,
         grouping,
         tag,
+        tag_exact,
+        faceted_tag,
         acousticness,
         arousal,
         danceability,
@@ -438,6 +989,8 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
         popularity,
         speechiness,
         valence,
+        scored_facets,
+        track_artist_roles: DEFAULT_ARTIST_ROLES.to_vec(),
     };
     (schema, fields)
 }
@@ -446,6 +999,62 @@ pub fn build_schema_for_tracks() -> (Schema, TrackFields) {
 pub struct TrackIndex {
     pub fields: TrackFields,
     pub index: Index,
+
+    /// The marker file of a pending re-index in the index directory.
+    ///
+    /// `None` for indexes that are not stored in a directory.
+    reindex_marker_path: Option<PathBuf>,
+
+    reindex_required: bool,
+}
+
+/// Compatibility of an existing index with the current schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaCompatibility {
+    /// The schemas are equal.
+    Identical,
+
+    /// New fields have been appended.
+    ///
+    /// The existing index could be migrated and is kept, but all tracks
+    /// need to be re-indexed.
+    Additive,
+
+    /// Existing fields have been modified, removed, or reordered.
+    ///
+    /// The index needs to be recreated and rebuilt.
+    Incompatible,
+}
+
+/// Check if an index with the stored schema could be migrated to the
+/// current schema.
+///
+/// Only appending new fields that are not fast fields is considered as
+/// an additive change. Fast fields are not supported, because existing
+/// segments lack the corresponding columns.
+#[must_use]
+pub fn check_schema_compatibility(stored: &Schema, current: &Schema) -> SchemaCompatibility {
+    let stored_count = stored.fields().count();
+    let current_count = current.fields().count();
+    if current_count < stored_count
+        || stored
+            .fields()
+            .zip(current.fields())
+            .any(|((_, stored_entry), (_, current_entry))| stored_entry != current_entry)
+    {
+        return SchemaCompatibility::Incompatible;
+    }
+    if current_count == stored_count {
+        return SchemaCompatibility::Identical;
+    }
+    if current
+        .fields()
+        .skip(stored_count)
+        .any(|(_, added_entry)| added_entry.is_fast())
+    {
+        return SchemaCompatibility::Incompatible;
+    }
+    SchemaCompatibility::Additive
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -455,9 +1064,136 @@ pub enum IndexStorage<'p> {
     FileDir { dir_path: &'p Path },
 }
 
+/// How to proceed when opening a corrupt index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CorruptionPolicy {
+    /// Fail with [`OpenError::Corrupt`].
+    #[default]
+    FailOnCorruption,
+
+    /// Delete all index data and create a new, empty index.
+    ///
+    /// The index needs to be rebuilt afterwards.
+    RecreateOnCorruption,
+}
+
+#[derive(Debug, Error)]
+pub enum OpenError {
+    /// The index data is corrupt and could not be opened.
+    #[error("corrupt index: {0}")]
+    Corrupt(TantivyError),
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Check if an error indicates that the index data is corrupt.
+///
+/// Only corrupt data and checksum or format errors are considered as
+/// corruption. Other I/O errors might be transient and must not result
+/// in deleting the index.
+#[must_use]
+pub fn is_index_corruption_error(err: &TantivyError) -> bool {
+    match err {
+        TantivyError::DataCorruption(_)
+        | TantivyError::IncompatibleIndex(_)
+        | TantivyError::OpenReadError(OpenReadError::IncompatibleIndex(_)) => true,
+        TantivyError::OpenReadError(OpenReadError::IoError { io_error, .. }) => {
+            // Invalid or truncated file footers, see `tantivy::directory::Footer`
+            matches!(
+                io_error.kind(),
+                io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+            )
+        }
+        _ => false,
+    }
+}
+
+fn is_schema_version_outdated(dir_path: &Path) -> io::Result<bool> {
+    match fs::read_to_string(dir_path.join(SCHEMA_VERSION_FILE_NAME)) {
+        Ok(content) => Ok(content.trim().parse::<u32>().ok() != Some(SCHEMA_VERSION)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            // Only an existing index could be outdated
+            Ok(dir_path.join(META_FILE_NAME).is_file())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn write_schema_version(dir_path: &Path) -> io::Result<()> {
+    fs::write(
+        dir_path.join(SCHEMA_VERSION_FILE_NAME),
+        SCHEMA_VERSION.to_string(),
+    )
+}
+
+/// Replace the stored schema of an existing index.
+///
+/// Existing segments are kept as is. Only safe for changes that have
+/// been classified as [`SchemaCompatibility::Additive`].
+fn migrate_additive_schema(dir_path: &Path, schema: Schema) -> anyhow::Result<()> {
+    let index = Index::open_in_dir(dir_path)?;
+    let mut metas = index.load_metas()?;
+    metas.schema = schema;
+    let mut buffer = serde_json::to_vec_pretty(&metas)?;
+    writeln!(&mut buffer)?;
+    index
+        .directory()
+        .atomic_write(Path::new(META_FILE_NAME), &buffer)?;
+    Ok(())
+}
+
+fn open_or_create_in_dir(dir_path: &Path, schema: Schema) -> tantivy::Result<Index> {
+    let index_dir = MmapDirectory::open(dir_path)?;
+    Index::open_or_create(index_dir, schema)
+        .and_then(|index| verify_index_segments(&index).map(|()| index))
+}
+
+/// Verify that all segments of the index could be opened.
+fn verify_index_segments(index: &Index) -> tantivy::Result<()> {
+    index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()
+        .map(drop)
+}
+
 impl TrackIndex {
-    pub fn open_or_recreate(index_storage: IndexStorage<'_>) -> anyhow::Result<TrackIndex> {
-        let (schema, fields) = build_schema_for_tracks();
+    /// Open an existing or create a new index.
+    ///
+    /// Indexes with an incompatible schema are always recreated. Corrupt
+    /// indexes are handled according to [`CorruptionPolicy::default()`].
+    pub fn open_or_recreate(index_storage: IndexStorage<'_>) -> Result<TrackIndex, OpenError> {
+        Self::open_or_recreate_with_policy(index_storage, CorruptionPolicy::default())
+    }
+
+    /// Open an existing or create a new index.
+    ///
+    /// Indexes with an incompatible schema are always recreated. Corrupt
+    /// indexes are handled according to the given `corruption_policy`.
+    pub fn open_or_recreate_with_policy(
+        index_storage: IndexStorage<'_>,
+        corruption_policy: CorruptionPolicy,
+    ) -> Result<TrackIndex, OpenError> {
+        Self::open_or_recreate_with_scored_facets(index_storage, corruption_policy, &[])
+    }
+
+    /// Open an existing or create a new index with custom scored facets.
+    ///
+    /// See also: [`build_schema_for_tracks_with_scored_facets()`]
+    ///
+    /// Changing the scored facets changes the schema. Adding new scored
+    /// facets migrates the existing index and [`TrackIndex::reindex_required()`]
+    /// is set until the re-index has been finished. All other changes
+    /// recreate the index, which needs to be rebuilt afterwards.
+    /// See also: [`check_schema_compatibility()`]
+    pub fn open_or_recreate_with_scored_facets(
+        index_storage: IndexStorage<'_>,
+        corruption_policy: CorruptionPolicy,
+        scored_facet_ids: &[TagFacetId<'_>],
+    ) -> Result<TrackIndex, OpenError> {
+        let (schema, fields) = build_schema_for_tracks_with_scored_facets(scored_facet_ids);
+        let mut reindex_marker_path = None;
         let index = match index_storage {
             IndexStorage::InMemory => {
                 log::info!("Creating temporary track index in RAM");
@@ -465,27 +1201,102 @@ impl TrackIndex {
             }
             IndexStorage::TempDir => {
                 log::info!("Creating temporary track index");
-                Index::create_from_tempdir(schema)?
+                Index::create_from_tempdir(schema).map_err(anyhow::Error::from)?
             }
             IndexStorage::FileDir { dir_path } => {
-                fs::create_dir_all(dir_path)?;
-                let index_dir = MmapDirectory::open(dir_path)?;
-                match Index::open_or_create(index_dir, schema) {
+                fs::create_dir_all(dir_path).map_err(anyhow::Error::from)?;
+                if is_schema_version_outdated(dir_path).map_err(anyhow::Error::from)? {
+                    log::warn!("Deleting track index with outdated schema version");
+                    // Delete existing index data
+                    fs::remove_dir_all(dir_path).map_err(anyhow::Error::from)?;
+                    // ...and retry.
+                    return Self::open_or_recreate_with_scored_facets(
+                        index_storage,
+                        corruption_policy,
+                        scored_facet_ids,
+                    );
+                }
+                let opened = open_or_create_in_dir(dir_path, schema.clone());
+                let index = match opened {
                     Ok(index) => index,
                     Err(TantivyError::SchemaError(err)) => {
-                        log::warn!("Deleting track index with incompatible schema: {err}");
-                        // Delete existing index data
-                        fs::remove_dir_all(dir_path)?;
-                        // ...and retry.
-                        return Self::open_or_recreate(index_storage);
+                        let compatibility = Index::open_in_dir(dir_path)
+                            .map_or(SchemaCompatibility::Incompatible, |index| {
+                                check_schema_compatibility(&index.schema(), &schema)
+                            });
+                        if compatibility != SchemaCompatibility::Additive {
+                            log::warn!("Deleting track index with incompatible schema: {err}");
+                            // Delete existing index data
+                            fs::remove_dir_all(dir_path).map_err(anyhow::Error::from)?;
+                            // ...and retry.
+                            return Self::open_or_recreate_with_scored_facets(
+                                index_storage,
+                                corruption_policy,
+                                scored_facet_ids,
+                            );
+                        }
+                        log::info!("Migrating track index with additive schema changes");
+                        // Persist the pending re-index before migrating the schema
+                        fs::write(dir_path.join(REINDEX_MARKER_FILE_NAME), [])
+                            .map_err(anyhow::Error::from)?;
+                        migrate_additive_schema(dir_path, schema.clone())?;
+                        open_or_create_in_dir(dir_path, schema).map_err(anyhow::Error::from)?
                     }
+                    Err(err) if is_index_corruption_error(&err) => match corruption_policy {
+                        CorruptionPolicy::FailOnCorruption => {
+                            return Err(OpenError::Corrupt(err));
+                        }
+                        CorruptionPolicy::RecreateOnCorruption => {
+                            log::warn!("Deleting corrupt track index: {err}");
+                            // Delete existing index data
+                            fs::remove_dir_all(dir_path).map_err(anyhow::Error::from)?;
+                            // ...and retry once, failing if the index is still corrupt.
+                            return Self::open_or_recreate_with_scored_facets(
+                                index_storage,
+                                CorruptionPolicy::FailOnCorruption,
+                                scored_facet_ids,
+                            );
+                        }
+                    },
                     Err(err) => {
-                        return Err(err.into());
+                        return Err(anyhow::Error::from(err).into());
                     }
-                }
+                };
+                write_schema_version(dir_path).map_err(anyhow::Error::from)?;
+                reindex_marker_path = Some(dir_path.join(REINDEX_MARKER_FILE_NAME));
+                index
             }
         };
-        Ok(Self { fields, index })
+        let reindex_required = reindex_marker_path.as_deref().is_some_and(Path::is_file);
+        Ok(Self {
+            fields,
+            index,
+            reindex_marker_path,
+            reindex_required,
+        })
+    }
+
+    /// All tracks need to be re-indexed after the schema has been migrated.
+    ///
+    /// Documents that have been indexed before the migration are kept
+    /// but lack values for the new fields. The pending re-index is
+    /// persisted until [`TrackIndex::finish_reindex()`] is invoked.
+    #[must_use]
+    pub const fn reindex_required(&self) -> bool {
+        self.reindex_required
+    }
+
+    /// Confirm that all tracks have been re-indexed.
+    pub fn finish_reindex(&mut self) -> io::Result<()> {
+        if let Some(reindex_marker_path) = &self.reindex_marker_path {
+            match fs::remove_file(reindex_marker_path) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => return Err(err),
+            }
+        }
+        self.reindex_required = false;
+        Ok(())
     }
 
     pub fn count_all(&self) -> anyhow::Result<usize> {
@@ -493,6 +1304,31 @@ impl TrackIndex {
         let count_all = AllQuery.count(&searcher)?;
         Ok(count_all)
     }
+
+    /// Merge all searchable segments into a single segment.
+    ///
+    /// Consolidates the many small segments that are left behind by
+    /// incremental indexing and reclaims the space of deleted documents.
+    ///
+    /// This is an expensive maintenance operation that blocks until
+    /// finished and should only be invoked occasionally, e.g. after
+    /// re-indexing. It fails if another index writer is currently active.
+    pub fn optimize(&self) -> anyhow::Result<()> {
+        let mut writer: IndexWriter = self
+            .index
+            .writer_with_num_threads(1, OPTIMIZE_WRITER_MEMORY_BUDGET_BYTES)?;
+        let segment_ids = self.index.searchable_segment_ids()?;
+        if !segment_ids.is_empty() {
+            log::info!(
+                "Merging {num_segments} segment(s) of track index",
+                num_segments = segment_ids.len()
+            );
+            writer.merge(&segment_ids).wait()?;
+        }
+        writer.garbage_collect_files().wait()?;
+        writer.wait_merging_threads()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,8 @@
 // aoide.org - Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::ops::Bound;
+
 use aoide_core::{
     audio::{BitrateBps, ChannelCount, Channels, DurationMs, LoudnessLufs, SampleRateHz},
     collection,
@@ -10,11 +12,32 @@ use aoide_core::{
         },
         Content, Source as MediaSource,
     },
-    track::{Entity, EntityBody, EntityHeader, Track},
-    util::clock::OffsetDateTimeMs,
+    music::tempo::TempoBpm,
+    tag::{FacetId, FacetedTags, Label, PlainTag, Score, ScoreValue, Tags},
+    track::{
+        actor::{Actor, Kind as ActorKind, Role as ActorRole},
+        tag::{FACET_ID_ENERGY, FACET_ID_GENRE, FACET_ID_GROUPING, FACET_ID_VALENCE},
+        title::{Kind as TitleKind, Title},
+        Entity, EntityBody, EntityHeader, Track,
+    },
+    util::clock::{OffsetDateTimeMs, YyyyMmDdDate},
 };
+use nonicle::CanonicalizeInto as _;
 
-use crate::{IndexStorage, TrackIndex};
+use tantivy::{
+    collector::{Count, TopDocs},
+    indexer::NoMergePolicy,
+    query::{AllQuery, Query, RangeQuery, TermQuery},
+    schema::{Field, IndexRecordOption},
+    IndexWriter, Searcher, TantivyDocument, Term,
+};
+
+use crate::{
+    build_schema_for_tracks, build_schema_for_tracks_with_scored_facets,
+    check_schema_compatibility, CorruptionPolicy, IndexStorage, OpenError, SchemaCompatibility,
+    TempoBpmBucket, TrackIndex, ENERGY, RELEASED_AT_YYYYMMDD, SCHEMA_VERSION,
+    SCHEMA_VERSION_FILE_NAME, VALENCE,
+};
 
 #[test]
 fn track_index_smoke_test_to_verify_dynamic_schema_against_static_types() {
@@ -26,6 +49,7 @@ fn track_index_smoke_test_to_verify_dynamic_schema_against_static_types() {
         encoder: Some("encoder".to_owned()),
         loudness: Some(LoudnessLufs::new(1.234)),
         sample_rate: Some(SampleRateHz::new(44_100.0)),
+        waveform_overview: None,
     };
     let media_source = MediaSource {
         collected_at: OffsetDateTimeMs::now_utc(),
@@ -37,6 +61,7 @@ fn track_index_smoke_test_to_verify_dynamic_schema_against_static_types() {
             },
             r#type: "audio/mpeg".parse().unwrap(),
             digest: Some(b"jsdf09w8092r2oijwlfksdf".to_vec()),
+            size: None,
             metadata: ContentMetadata::Audio(audio_metadata),
             metadata_flags: Default::default(),
         },
@@ -73,3 +98,898 @@ fn track_index_smoke_test_to_verify_dynamic_schema_against_static_types() {
     let writer = track_index.index.writer(15_000_000).unwrap();
     let _doc_id = writer.add_document(document).unwrap();
 }
+
+fn new_track_entity(content_path: String) -> Entity {
+    let media_source = MediaSource {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        artwork: None,
+        content: Content {
+            link: ContentLink {
+                path: ContentPath::new(content_path.into()),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            digest: None,
+            size: None,
+            metadata: ContentMetadata::Audio(Default::default()),
+            metadata_flags: Default::default(),
+        },
+    };
+    let entity_body = EntityBody {
+        updated_at: OffsetDateTimeMs::now_utc(),
+        track: Track::new_from_media_source(media_source),
+        content_url: None,
+        last_synchronized_rev: None,
+    };
+    Entity::new(EntityHeader::initial_random(), entity_body)
+}
+
+/// Create a single-threaded writer with a small memory budget.
+fn new_index_writer(track_index: &TrackIndex) -> IndexWriter {
+    track_index
+        .index
+        .writer_with_num_threads(1, 15_000_000)
+        .unwrap()
+}
+
+/// Index the tracks without a collection in a single commit.
+///
+/// Returns a searcher for the committed index.
+fn index_tracks<'a>(
+    track_index: &TrackIndex,
+    entities: impl IntoIterator<Item = &'a Entity>,
+) -> Searcher {
+    let mut writer = new_index_writer(track_index);
+    for entity in entities {
+        writer
+            .add_document(track_index.fields.create_document(None, entity, None))
+            .unwrap();
+    }
+    writer.commit().unwrap();
+    writer.wait_merging_threads().unwrap();
+    track_index.index.reader().unwrap().searcher()
+}
+
+#[test]
+fn optimize_merges_segments_and_preserves_documents() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let collection_uid = collection::EntityHeader::initial_random().uid;
+    let mut writer = new_index_writer(&track_index);
+    // Prevent automatic merges to accumulate segments
+    writer.set_merge_policy(Box::new(NoMergePolicy));
+    for i in 0..10 {
+        // Each commit creates a new segment
+        let kept_entity = new_track_entity(format!("kept{i}.mp3"));
+        let deleted_entity = new_track_entity(format!("deleted{i}.mp3"));
+        writer
+            .add_document(track_index.fields.create_document(
+                Some(&collection_uid),
+                &kept_entity,
+                None,
+            ))
+            .unwrap();
+        writer
+            .add_document(track_index.fields.create_document(
+                Some(&collection_uid),
+                &deleted_entity,
+                None,
+            ))
+            .unwrap();
+        writer.commit().unwrap();
+        writer.delete_term(track_index.fields.uid_term(&deleted_entity.hdr.uid));
+        writer.commit().unwrap();
+    }
+    writer.wait_merging_threads().unwrap();
+
+    let segment_count_before = track_index.index.searchable_segment_ids().unwrap().len();
+    assert!(segment_count_before > 1);
+    assert_eq!(10, track_index.count_all().unwrap());
+
+    track_index.optimize().unwrap();
+
+    let segment_count_after = track_index.index.searchable_segment_ids().unwrap().len();
+    assert!(segment_count_after < segment_count_before);
+    assert_eq!(1, segment_count_after);
+    assert_eq!(10, track_index.count_all().unwrap());
+}
+
+#[test]
+fn index_actors_by_role() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let mut entity = new_track_entity("file.mp3".to_owned());
+    let new_actor = |role, name: &str| Actor {
+        role,
+        kind: ActorKind::Summary,
+        name: name.to_owned(),
+        role_notes: None,
+    };
+    entity.body.track.actors = vec![
+        new_actor(ActorRole::Artist, "Performer"),
+        new_actor(ActorRole::Composer, "Composer"),
+        new_actor(ActorRole::Remixer, "Remixer"),
+    ]
+    .canonicalize_into();
+    let searcher = index_tracks(&track_index, [&entity]);
+    let count_matches = |field: Field, text: &str| {
+        let query = TermQuery::new(Term::from_field_text(field, text), IndexRecordOption::Basic);
+        searcher.search(&query, &Count).unwrap()
+    };
+    let fields = &track_index.fields;
+    assert_eq!(1, count_matches(fields.composer, "composer"));
+    assert_eq!(0, count_matches(fields.composer, "remixer"));
+    assert_eq!(1, count_matches(fields.remixer, "remixer"));
+    assert_eq!(0, count_matches(fields.remixer, "composer"));
+    assert_eq!(0, count_matches(fields.producer, "composer"));
+    assert_eq!(0, count_matches(fields.conductor, "remixer"));
+    // Only artists and performers are indexed as track artists by default
+    assert_eq!(1, count_matches(fields.track_artist, "performer"));
+    assert_eq!(0, count_matches(fields.track_artist, "composer"));
+    assert_eq!(0, count_matches(fields.track_artist, "remixer"));
+}
+
+#[test]
+fn index_track_artists_of_configured_roles() {
+    let mut track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    track_index.fields.track_artist_roles = vec![ActorRole::Composer, ActorRole::Producer];
+    let mut entity = new_track_entity("file.mp3".to_owned());
+    let new_actor = |role, name: &str| Actor {
+        role,
+        kind: ActorKind::Summary,
+        name: name.to_owned(),
+        role_notes: None,
+    };
+    entity.body.track.actors = vec![
+        new_actor(ActorRole::Artist, "Artist"),
+        new_actor(ActorRole::Performer, "Performer"),
+        new_actor(ActorRole::Composer, "Composer"),
+        new_actor(ActorRole::Producer, "Producer"),
+    ]
+    .canonicalize_into();
+    let searcher = index_tracks(&track_index, [&entity]);
+    let count_matches = |text: &str| {
+        let query = TermQuery::new(
+            Term::from_field_text(track_index.fields.track_artist, text),
+            IndexRecordOption::Basic,
+        );
+        searcher.search(&query, &Count).unwrap()
+    };
+    assert_eq!(0, count_matches("artist"));
+    assert_eq!(0, count_matches("performer"));
+    assert_eq!(1, count_matches("composer"));
+    assert_eq!(1, count_matches("producer"));
+}
+
+#[test]
+fn recreate_corrupt_index_on_open() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let index_storage = IndexStorage::FileDir {
+        dir_path: temp_dir.path(),
+    };
+    {
+        let track_index = TrackIndex::open_or_recreate(index_storage).unwrap();
+        index_tracks(&track_index, [&new_track_entity("file.mp3".to_owned())]);
+        assert_eq!(1, track_index.count_all().unwrap());
+    }
+
+    // Overwrite the index metadata with garbage
+    std::fs::write(temp_dir.path().join("meta.json"), b"\x00garbage").unwrap();
+
+    assert!(matches!(
+        TrackIndex::open_or_recreate_with_policy(index_storage, CorruptionPolicy::FailOnCorruption),
+        Err(OpenError::Corrupt(_))
+    ));
+
+    let track_index = TrackIndex::open_or_recreate_with_policy(
+        index_storage,
+        CorruptionPolicy::RecreateOnCorruption,
+    )
+    .unwrap();
+    assert_eq!(0, track_index.count_all().unwrap());
+
+    // The recreated index is usable
+    index_tracks(&track_index, [&new_track_entity("file.mp3".to_owned())]);
+    assert_eq!(1, track_index.count_all().unwrap());
+}
+
+#[test]
+fn keep_index_on_io_error_when_opening() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let index_storage = IndexStorage::FileDir {
+        dir_path: temp_dir.path(),
+    };
+    {
+        let track_index = TrackIndex::open_or_recreate(index_storage).unwrap();
+        index_tracks(&track_index, [&new_track_entity("file.mp3".to_owned())]);
+        assert_eq!(1, track_index.count_all().unwrap());
+    }
+
+    // Reading the index metadata fails with an I/O error
+    let meta_path = temp_dir.path().join("meta.json");
+    let moved_meta_path = temp_dir.path().join("meta.json.moved");
+    std::fs::rename(&meta_path, &moved_meta_path).unwrap();
+    std::fs::create_dir(&meta_path).unwrap();
+
+    assert!(matches!(
+        TrackIndex::open_or_recreate_with_policy(
+            index_storage,
+            CorruptionPolicy::RecreateOnCorruption
+        ),
+        Err(OpenError::Other(_))
+    ));
+
+    // The index has not been deleted
+    std::fs::remove_dir(&meta_path).unwrap();
+    std::fs::rename(&moved_meta_path, &meta_path).unwrap();
+    let track_index = TrackIndex::open_or_recreate_with_policy(
+        index_storage,
+        CorruptionPolicy::RecreateOnCorruption,
+    )
+    .unwrap();
+    assert_eq!(1, track_index.count_all().unwrap());
+}
+
+fn open_in_dir_and_add_track(
+    dir_path: &std::path::Path,
+    scored_facet_ids: &[FacetId<'_>],
+) -> TrackIndex {
+    let track_index = TrackIndex::open_or_recreate_with_scored_facets(
+        IndexStorage::FileDir { dir_path },
+        CorruptionPolicy::default(),
+        scored_facet_ids,
+    )
+    .unwrap();
+    index_tracks(&track_index, [&new_track_entity("file.mp3".to_owned())]);
+    track_index
+}
+
+#[test]
+fn check_schema_compatibility_of_scored_facets() {
+    let (schema, _) = build_schema_for_tracks();
+    let (schema_with_foo, _) =
+        build_schema_for_tracks_with_scored_facets(&[FacetId::from_unchecked("foo")]);
+    let (schema_with_bar, _) =
+        build_schema_for_tracks_with_scored_facets(&[FacetId::from_unchecked("bar")]);
+    assert_eq!(
+        SchemaCompatibility::Identical,
+        check_schema_compatibility(&schema, &schema)
+    );
+    assert_eq!(
+        SchemaCompatibility::Additive,
+        check_schema_compatibility(&schema, &schema_with_foo)
+    );
+    assert_eq!(
+        SchemaCompatibility::Incompatible,
+        check_schema_compatibility(&schema_with_foo, &schema)
+    );
+    assert_eq!(
+        SchemaCompatibility::Incompatible,
+        check_schema_compatibility(&schema_with_foo, &schema_with_bar)
+    );
+}
+
+#[test]
+fn migrate_index_with_additive_schema_change() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let track_index = open_in_dir_and_add_track(temp_dir.path(), &[]);
+    assert!(!track_index.reindex_required());
+    drop(track_index);
+
+    let custom_facet_id = FacetId::from_unchecked("mood/intensity");
+    let track_index =
+        open_in_dir_and_add_track(temp_dir.path(), std::slice::from_ref(&custom_facet_id));
+    // Existing documents are kept
+    assert!(track_index.reindex_required());
+    assert_eq!(2, track_index.count_all().unwrap());
+    drop(track_index);
+
+    // The pending re-index survives reopening the migrated index
+    let reopen = || {
+        TrackIndex::open_or_recreate_with_scored_facets(
+            IndexStorage::FileDir {
+                dir_path: temp_dir.path(),
+            },
+            CorruptionPolicy::default(),
+            std::slice::from_ref(&custom_facet_id),
+        )
+        .unwrap()
+    };
+    let mut track_index = reopen();
+    assert!(track_index.reindex_required());
+    assert_eq!(2, track_index.count_all().unwrap());
+    track_index.finish_reindex().unwrap();
+    assert!(!track_index.reindex_required());
+    drop(track_index);
+
+    let track_index = reopen();
+    assert!(!track_index.reindex_required());
+    assert_eq!(2, track_index.count_all().unwrap());
+}
+
+#[test]
+fn recreate_index_with_incompatible_schema_change() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    drop(open_in_dir_and_add_track(
+        temp_dir.path(),
+        &[FacetId::from_unchecked("foo")],
+    ));
+
+    // Replacing a field is incompatible
+    let track_index = TrackIndex::open_or_recreate_with_scored_facets(
+        IndexStorage::FileDir {
+            dir_path: temp_dir.path(),
+        },
+        CorruptionPolicy::default(),
+        &[FacetId::from_unchecked("bar")],
+    )
+    .unwrap();
+    assert!(!track_index.reindex_required());
+    assert_eq!(0, track_index.count_all().unwrap());
+}
+
+#[test]
+fn recreate_index_with_outdated_schema_version() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    drop(open_in_dir_and_add_track(temp_dir.path(), &[]));
+
+    std::fs::write(temp_dir.path().join(SCHEMA_VERSION_FILE_NAME), "0").unwrap();
+
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::FileDir {
+        dir_path: temp_dir.path(),
+    })
+    .unwrap();
+    assert_eq!(0, track_index.count_all().unwrap());
+}
+
+#[test]
+fn recreate_index_without_schema_version() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    drop(open_in_dir_and_add_track(temp_dir.path(), &[]));
+
+    std::fs::remove_file(temp_dir.path().join(SCHEMA_VERSION_FILE_NAME)).unwrap();
+
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::FileDir {
+        dir_path: temp_dir.path(),
+    })
+    .unwrap();
+    assert_eq!(0, track_index.count_all().unwrap());
+}
+
+#[test]
+fn keep_index_if_schema_version_is_unreadable() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    drop(open_in_dir_and_add_track(temp_dir.path(), &[]));
+
+    // Reading a directory instead of a file fails
+    let schema_version_path = temp_dir.path().join(SCHEMA_VERSION_FILE_NAME);
+    std::fs::remove_file(&schema_version_path).unwrap();
+    std::fs::create_dir(&schema_version_path).unwrap();
+    assert!(TrackIndex::open_or_recreate(IndexStorage::FileDir {
+        dir_path: temp_dir.path(),
+    })
+    .is_err());
+
+    std::fs::remove_dir(&schema_version_path).unwrap();
+    std::fs::write(&schema_version_path, SCHEMA_VERSION.to_string()).unwrap();
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::FileDir {
+        dir_path: temp_dir.path(),
+    })
+    .unwrap();
+    assert_eq!(1, track_index.count_all().unwrap());
+}
+
+#[test]
+fn range_query_partial_release_dates() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let entities = [
+        YyyyMmDdDate::from_year(2021),
+        YyyyMmDdDate::from_year_month(2021, 6),
+        YyyyMmDdDate::from_year_month_day(2021, 6, 15),
+        YyyyMmDdDate::from_year(2022),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, released_at)| {
+        let mut entity = new_track_entity(format!("file{i}.mp3"));
+        entity.body.track.released_at = Some(released_at.into());
+        entity
+    })
+    .collect::<Vec<_>>();
+    let searcher = index_tracks(&track_index, &entities);
+    let count_released_between = |from: YyyyMmDdDate, to: YyyyMmDdDate| {
+        let query = RangeQuery::new_i64(
+            RELEASED_AT_YYYYMMDD.to_owned(),
+            from.value().into()..to.value().into(),
+        );
+        searcher.search(&query, &Count).unwrap()
+    };
+    assert_eq!(
+        3,
+        count_released_between(YyyyMmDdDate::from_year(2021), YyyyMmDdDate::from_year(2022))
+    );
+    assert_eq!(
+        2,
+        count_released_between(
+            YyyyMmDdDate::from_year_month(2021, 6),
+            YyyyMmDdDate::from_year_month(2021, 7)
+        )
+    );
+    assert_eq!(
+        1,
+        count_released_between(YyyyMmDdDate::from_year(2022), YyyyMmDdDate::from_year(2023))
+    );
+}
+
+#[test]
+fn skip_or_clamp_invalid_scores() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let mut entity = new_track_entity("file.mp3".to_owned());
+    let new_faceted_tags = |facet_id, score_value| FacetedTags {
+        facet_id,
+        tags: vec![PlainTag {
+            label: None,
+            score: Score::new_unchecked(score_value),
+        }],
+    };
+    entity.body.track.tags = Tags {
+        plain: vec![],
+        facets: vec![
+            new_faceted_tags(FACET_ID_ENERGY.clone(), ScoreValue::NAN),
+            new_faceted_tags(FACET_ID_VALENCE.clone(), 1.5),
+        ],
+    }
+    .canonicalize_into();
+    let searcher = index_tracks(&track_index, [&entity]);
+    let count_scores_between = |field_name: &str, min: ScoreValue, max: ScoreValue| {
+        let query = RangeQuery::new_f64_bounds(
+            field_name.to_owned(),
+            Bound::Included(min),
+            Bound::Included(max),
+        );
+        searcher.search(&query, &Count).unwrap()
+    };
+    // NaN is not indexed
+    assert_eq!(
+        0,
+        count_scores_between(ENERGY, ScoreValue::MIN, ScoreValue::MAX)
+    );
+    // Out-of-range values are clamped
+    assert_eq!(
+        1,
+        count_scores_between(VALENCE, Score::MAX_VALUE, Score::MAX_VALUE)
+    );
+}
+
+#[test]
+fn index_and_query_custom_scored_facets() {
+    let custom_facet_id = FacetId::from_unchecked("mood/intensity");
+    let track_index = TrackIndex::open_or_recreate_with_scored_facets(
+        IndexStorage::InMemory,
+        CorruptionPolicy::default(),
+        &[custom_facet_id.clone(), FACET_ID_ENERGY.clone()],
+    )
+    .unwrap();
+    // Predefined scored facets are not registered again
+    assert_eq!(1, track_index.fields.scored_facets.len());
+    let new_entity = |name: &str, score_value| {
+        let mut entity = new_track_entity(format!("{name}.mp3"));
+        entity.body.track.tags = Tags {
+            plain: vec![],
+            facets: vec![FacetedTags {
+                facet_id: custom_facet_id.clone(),
+                tags: vec![PlainTag {
+                    label: None,
+                    score: Score::new_unchecked(score_value),
+                }],
+            }],
+        }
+        .canonicalize_into();
+        entity
+    };
+    let searcher = index_tracks(
+        &track_index,
+        &[
+            new_entity("low", 0.2),
+            new_entity("medium", 0.5),
+            new_entity("high", 0.9),
+        ],
+    );
+    let count_scores_between = |facet_id: &FacetId<'_>, min: ScoreValue, max: ScoreValue| {
+        let query = track_index
+            .fields
+            .scored_facet_range_query(
+                facet_id,
+                Bound::Included(Score::new_unchecked(min)),
+                Bound::Excluded(Score::new_unchecked(max)),
+            )
+            .unwrap();
+        searcher.search(&query, &Count).unwrap()
+    };
+    assert_eq!(3, count_scores_between(&custom_facet_id, 0.0, 1.0));
+    assert_eq!(2, count_scores_between(&custom_facet_id, 0.5, 1.0));
+    assert_eq!(1, count_scores_between(&custom_facet_id, 0.0, 0.5));
+    // Predefined scored facets are queried by their dedicated field
+    assert_eq!(0, count_scores_between(FACET_ID_ENERGY, 0.0, 1.0));
+    // Unregistered facets are not indexed by score
+    assert!(track_index
+        .fields
+        .scored_facet_range_query(
+            &FacetId::from_unchecked("mood/unknown"),
+            Bound::Unbounded,
+            Bound::Unbounded,
+        )
+        .is_none());
+}
+
+#[test]
+fn more_like_this_prefers_same_genre_and_similar_tempo() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let new_entity = |name: &str, genre: &str, tempo_bpm| {
+        let mut entity = new_track_entity(format!("{name}.mp3"));
+        entity.body.track.tags = Tags {
+            plain: vec![],
+            facets: vec![FacetedTags {
+                facet_id: FACET_ID_GENRE.clone(),
+                tags: vec![PlainTag {
+                    label: Some(Label::from_unchecked(genre.to_owned())),
+                    score: Default::default(),
+                }],
+            }],
+        }
+        .canonicalize_into();
+        entity.body.track.metrics.tempo_bpm = Some(TempoBpm::new(tempo_bpm));
+        entity
+    };
+    let seed_entity = new_entity("seed", "Techno", 130.0);
+    let same_genre_similar_tempo = [
+        new_entity("techno131", "Techno", 131.0),
+        new_entity("techno128", "Techno", 128.0),
+    ];
+    let other_entities = [
+        new_entity("house130", "House", 130.0),
+        new_entity("techno90", "Techno", 90.0),
+        new_entity("jazz90", "Jazz", 90.0),
+    ];
+    let searcher = index_tracks(
+        &track_index,
+        std::iter::once(&seed_entity)
+            .chain(&same_genre_similar_tempo)
+            .chain(&other_entities),
+    );
+    let seed = track_index.fields.create_document(None, &seed_entity, None);
+    let hits = track_index
+        .fields
+        .more_like_this(&searcher, &seed, 10)
+        .unwrap();
+
+    // The seed itself and tracks without anything in common are excluded
+    assert_eq!(4, hits.len());
+    assert!(hits.iter().all(|hit| hit.uid != seed_entity.hdr.uid));
+    assert!(hits.iter().all(|hit| hit.uid != other_entities[2].hdr.uid));
+    // The most similar tracks share the genre and are close in tempo
+    let mut most_similar = hits[..2].iter().map(|hit| &hit.uid).collect::<Vec<_>>();
+    most_similar.sort();
+    let mut expected = same_genre_similar_tempo
+        .iter()
+        .map(|entity| &entity.hdr.uid)
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(expected, most_similar);
+    assert!(hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+    // The number of results is limited
+    assert_eq!(
+        1,
+        track_index
+            .fields
+            .more_like_this(&searcher, &seed, 1)
+            .unwrap()
+            .len()
+    );
+}
+
+#[test]
+fn query_faceted_tags_by_path_prefix() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let new_entity = |facet_id: &str| {
+        let mut entity = new_track_entity(format!("{facet_id}.mp3"));
+        entity.body.track.tags = Tags {
+            plain: vec![],
+            facets: vec![FacetedTags {
+                facet_id: FacetId::new_unchecked(facet_id.to_owned().into()),
+                tags: vec![PlainTag {
+                    label: Some(Label::from_unchecked("label".to_owned())),
+                    score: Default::default(),
+                }],
+            }],
+        }
+        .canonicalize_into();
+        entity
+    };
+    let mood_energetic = new_entity("mood/energetic");
+    let mood_calm = new_entity("mood/calm");
+    let genre_rock = new_entity("genre/rock");
+    let moody = new_entity("moody");
+    let searcher = index_tracks(
+        &track_index,
+        [&mood_energetic, &mood_calm, &genre_rock, &moody],
+    );
+    let search_uids = |facet_path_prefix: &str| {
+        let query = track_index
+            .fields
+            .faceted_tag_prefix_query(facet_path_prefix)
+            .unwrap();
+        let mut uids = searcher
+            .search(&query, &TopDocs::with_limit(10))
+            .unwrap()
+            .into_iter()
+            .map(|(_, doc_address)| {
+                let doc = searcher.doc::<TantivyDocument>(doc_address).unwrap();
+                track_index.fields.read_uid(&doc).unwrap()
+            })
+            .collect::<Vec<_>>();
+        uids.sort();
+        uids
+    };
+
+    let mut expected_mood_uids = vec![mood_energetic.hdr.uid.clone(), mood_calm.hdr.uid.clone()];
+    expected_mood_uids.sort();
+    assert_eq!(expected_mood_uids, search_uids("mood/"));
+    assert_eq!(expected_mood_uids, search_uids("mood"));
+    assert_eq!(vec![mood_calm.hdr.uid.clone()], search_uids("mood/calm"));
+    assert_eq!(vec![genre_rock.hdr.uid.clone()], search_uids("genre"));
+    assert!(search_uids("mood/calm/quiet").is_empty());
+    assert!(track_index.fields.faceted_tag_prefix_query("/").is_none());
+}
+
+#[test]
+fn query_tags_exact_or_tokenized() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let new_entity = |label: &str| {
+        let mut entity = new_track_entity(format!("{label}.mp3"));
+        entity.body.track.tags = Tags {
+            plain: vec![PlainTag {
+                label: Some(Label::from_unchecked(label.to_owned())),
+                score: Default::default(),
+            }],
+            facets: vec![],
+        }
+        .canonicalize_into();
+        entity
+    };
+    let drum_and_bass = new_entity("drum and bass");
+    let drum = new_entity("drum");
+    let bass_and_drum = new_entity("bass and drum");
+    let searcher = index_tracks(&track_index, [&drum_and_bass, &drum, &bass_and_drum]);
+    let search_uids = |query: &dyn Query| {
+        let mut uids = searcher
+            .search(query, &TopDocs::with_limit(10))
+            .unwrap()
+            .into_iter()
+            .map(|(_, doc_address)| {
+                let doc = searcher.doc::<TantivyDocument>(doc_address).unwrap();
+                track_index.fields.read_uid(&doc).unwrap()
+            })
+            .collect::<Vec<_>>();
+        uids.sort();
+        uids
+    };
+    let exact_query = |label: &str| {
+        TermQuery::new(
+            track_index
+                .fields
+                .tag_term_exact(None, &Label::from_unchecked(label)),
+            IndexRecordOption::Basic,
+        )
+    };
+    let tokenized_query = |label: &str| {
+        track_index
+            .fields
+            .tag_query_tokenized(&track_index.index, None, &Label::from_unchecked(label))
+            .unwrap()
+    };
+
+    assert_eq!(
+        vec![drum_and_bass.hdr.uid.clone()],
+        search_uids(&exact_query("drum and bass"))
+    );
+    assert_eq!(
+        vec![drum.hdr.uid.clone()],
+        search_uids(&exact_query("drum"))
+    );
+    assert!(search_uids(&exact_query("Drum and Bass")).is_empty());
+
+    let mut expected_uids = vec![
+        drum_and_bass.hdr.uid.clone(),
+        drum.hdr.uid.clone(),
+        bass_and_drum.hdr.uid.clone(),
+    ];
+    expected_uids.sort();
+    assert_eq!(expected_uids, search_uids(&tokenized_query("drum")));
+    let mut expected_uids = vec![drum_and_bass.hdr.uid.clone(), bass_and_drum.hdr.uid.clone()];
+    expected_uids.sort();
+    assert_eq!(
+        expected_uids,
+        search_uids(&tokenized_query("Drum and Bass"))
+    );
+}
+
+#[test]
+fn query_tracks_by_loudness() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let entities = [None, Some(-14.0), Some(-9.5), Some(-7.0), None]
+        .into_iter()
+        .enumerate()
+        .map(|(i, loudness)| {
+            let mut entity = new_track_entity(format!("file{i}.mp3"));
+            let ContentMetadata::Audio(audio_metadata) =
+                &mut entity.body.track.media_source.content.metadata;
+            audio_metadata.loudness = loudness.map(LoudnessLufs::new);
+            entity
+        })
+        .collect::<Vec<_>>();
+    let searcher = index_tracks(&track_index, &entities);
+    let fields = &track_index.fields;
+    let count_loudness_within = |lower_bound, upper_bound| {
+        let query = fields.loudness_range_query(lower_bound, upper_bound);
+        searcher.search(&query, &Count).unwrap()
+    };
+    assert_eq!(3, count_loudness_within(Bound::Unbounded, Bound::Unbounded));
+    assert_eq!(
+        2,
+        count_loudness_within(
+            Bound::Included(LoudnessLufs::new(-10.0)),
+            Bound::Included(LoudnessLufs::new(-7.0))
+        )
+    );
+    assert_eq!(
+        1,
+        count_loudness_within(
+            Bound::Included(LoudnessLufs::new(-10.0)),
+            Bound::Excluded(LoudnessLufs::new(-7.0))
+        )
+    );
+    assert_eq!(
+        1,
+        count_loudness_within(Bound::Unbounded, Bound::Excluded(LoudnessLufs::new(-9.5)))
+    );
+    assert_eq!(
+        0,
+        count_loudness_within(Bound::Excluded(LoudnessLufs::new(-7.0)), Bound::Unbounded)
+    );
+    assert_eq!(
+        2,
+        searcher
+            .search(&fields.missing_loudness_query(), &Count)
+            .unwrap()
+    );
+}
+
+#[test]
+fn count_tracks_in_tempo_buckets() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let collection_uid = collection::EntityHeader::initial_random().uid;
+    let other_collection_uid = collection::EntityHeader::initial_random().uid;
+    let mut writer = new_index_writer(&track_index);
+    for (i, tempo_bpm) in [
+        None,
+        Some(85.0),
+        Some(121.0),
+        Some(124.5),
+        Some(129.9),
+        Some(130.0),
+        Some(140.0),
+        Some(142.0),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut entity = new_track_entity(format!("file{i}.mp3"));
+        entity.body.track.metrics.tempo_bpm = tempo_bpm.map(TempoBpm::new);
+        let collection_uid = if i % 2 == 0 {
+            &collection_uid
+        } else {
+            &other_collection_uid
+        };
+        writer
+            .add_document(
+                track_index
+                    .fields
+                    .create_document(Some(collection_uid), &entity, None),
+            )
+            .unwrap();
+    }
+    writer.commit().unwrap();
+
+    let searcher = track_index.index.reader().unwrap().searcher();
+    let fields = &track_index.fields;
+    let bucket = |min_bpm, count| TempoBpmBucket {
+        min_bpm,
+        max_bpm: min_bpm + 10.0,
+        count,
+    };
+    assert_eq!(
+        vec![
+            bucket(80.0, 1),
+            bucket(90.0, 0),
+            bucket(100.0, 0),
+            bucket(110.0, 0),
+            bucket(120.0, 3),
+            bucket(130.0, 1),
+            bucket(140.0, 2),
+        ],
+        fields
+            .tempo_bpm_histogram(&searcher, &AllQuery, 10.0)
+            .unwrap()
+    );
+
+    // Only tracks that match the query are counted
+    assert_eq!(
+        vec![bucket(120.0, 2), bucket(130.0, 0), bucket(140.0, 1)],
+        fields
+            .tempo_bpm_histogram(
+                &searcher,
+                &fields.collection_uid_query(&collection_uid),
+                10.0
+            )
+            .unwrap()
+    );
+
+    assert!(fields
+        .tempo_bpm_histogram(&searcher, &AllQuery, 0.0)
+        .is_err());
+}
+
+#[test]
+fn index_grouping_tags_and_work_titles_as_grouping() {
+    let track_index = TrackIndex::open_or_recreate(IndexStorage::InMemory).unwrap();
+    let mut grouping = new_track_entity("grouping.mp3".to_owned());
+    grouping.body.track.tags = Tags {
+        plain: vec![],
+        facets: vec![FacetedTags {
+            facet_id: FACET_ID_GROUPING.clone(),
+            tags: vec![PlainTag {
+                label: Some(Label::from_unchecked("Piano Concertos")),
+                score: Default::default(),
+            }],
+        }],
+    }
+    .canonicalize_into();
+    let mut work = new_track_entity("work.mp3".to_owned());
+    work.body.track.titles = vec![
+        Title {
+            kind: TitleKind::Main,
+            name: "Allegro".to_owned(),
+        },
+        Title {
+            kind: TitleKind::Work,
+            name: "Piano Concerto No. 21".to_owned(),
+        },
+    ]
+    .canonicalize_into();
+    let other = new_track_entity("other.mp3".to_owned());
+    let searcher = index_tracks(&track_index, [&grouping, &work, &other]);
+    let search_grouping = |text: &str| {
+        let query = TermQuery::new(
+            Term::from_field_text(track_index.fields.grouping, text),
+            IndexRecordOption::Basic,
+        );
+        let mut uids = searcher
+            .search(&query, &TopDocs::with_limit(10))
+            .unwrap()
+            .into_iter()
+            .map(|(_, doc_address)| {
+                let doc = searcher.doc::<TantivyDocument>(doc_address).unwrap();
+                track_index.fields.read_uid(&doc).unwrap()
+            })
+            .collect::<Vec<_>>();
+        uids.sort();
+        uids
+    };
+    assert_eq!(vec![grouping.hdr.uid.clone()], search_grouping("concertos"));
+    assert_eq!(vec![work.hdr.uid.clone()], search_grouping("concerto"));
+    let mut expected_uids = vec![grouping.hdr.uid.clone(), work.hdr.uid.clone()];
+    expected_uids.sort();
+    assert_eq!(expected_uids, search_grouping("piano"));
+    // The main title is not indexed as grouping.
+    assert!(search_grouping("allegro").is_empty());
+}
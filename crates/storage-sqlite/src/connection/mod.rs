@@ -7,10 +7,14 @@ use std::{
     str::FromStr,
 };
 
+use diesel::{Connection as _, SqliteConnection};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::Result;
+
 pub mod pool;
+pub mod pragma;
 
 pub const IN_MEMORY_STORAGE: &str = ":memory:";
 
@@ -49,7 +53,7 @@ impl fmt::Display for Storage {
 impl FromStr for Storage {
     type Err = <PathBuf as FromStr>::Err;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         if s.to_lowercase().trim() == IN_MEMORY_STORAGE {
             return Ok(Self::InMemory);
         }
@@ -64,6 +68,9 @@ pub struct Config {
     pub storage: Storage,
 
     pub pool: self::pool::Config,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pragma: self::pragma::Config,
 }
 
 impl Config {
@@ -72,3 +79,16 @@ impl Config {
         self.storage.parent_dir()
     }
 }
+
+/// Establish a single connection
+///
+/// The pragma parameters are validated and applied to the new connection.
+pub fn establish_connection(
+    storage: &Storage,
+    pragma_config: &self::pragma::Config,
+) -> Result<SqliteConnection> {
+    pragma_config.validate()?;
+    let mut connection = SqliteConnection::establish(storage.as_ref())?;
+    pragma_config.apply(&mut connection)?;
+    Ok(connection)
+}
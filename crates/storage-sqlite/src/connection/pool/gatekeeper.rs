@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::{
-    num::NonZeroU64,
+    num::{NonZeroU32, NonZeroU64},
     pin::pin,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -13,7 +13,11 @@ use std::{
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use tokio::{sync::RwLock, task::spawn_blocking, time::sleep};
+use tokio::{
+    sync::{Notify, RwLock},
+    task::spawn_blocking,
+    time::sleep,
+};
 
 use super::{get_pooled_connection, ConnectionPool, PooledConnection};
 use crate::{Error, Result};
@@ -25,13 +29,97 @@ use crate::{Error, Result};
 /// locking when obtaining a connection and timeouts when concurrently
 /// trying to execute write operations on a shared `SQLite` database
 /// instance.
+///
+/// The order in which concurrent read and write requests are granted
+/// access is controlled by the [`FairnessPolicy`].
 #[allow(missing_debug_implementations)]
 pub struct Gatekeeper {
     connection_pool: Arc<RwLock<ConnectionPool>>,
     acquire_read_timeout: Duration,
     acquire_write_timeout: Duration,
+    fairness_policy: FairnessPolicy,
     request_counter_state: Arc<RequestCounterState>,
+    admission_state: AdmissionState,
     decommisioned: AtomicBool,
+    read_only: bool,
+}
+
+/// Bookkeeping for admitting pending write requests
+/// according to the [`FairnessPolicy`].
+#[derive(Debug, Default)]
+struct AdmissionState {
+    /// Read requests that are waiting for access.
+    waiting_read_count: AtomicUsize,
+
+    /// Read requests that have been granted access since the last write request.
+    consecutive_read_count: AtomicUsize,
+
+    /// Notifies writers about changes of the read counters.
+    read_notify: Notify,
+}
+
+impl AdmissionState {
+    fn is_write_admitted(&self, max_consecutive_reads: NonZeroU32) -> bool {
+        self.waiting_read_count.load(Ordering::Acquire) == 0
+            || self.consecutive_read_count.load(Ordering::Acquire)
+                >= usize::try_from(max_consecutive_reads.get()).unwrap_or(usize::MAX)
+    }
+
+    /// Wait until a write request is admitted.
+    async fn admit_write(&self, fairness_policy: FairnessPolicy) {
+        let FairnessPolicy::ReadBatch {
+            max_consecutive_reads,
+        } = fairness_policy
+        else {
+            return;
+        };
+        loop {
+            let mut notified = pin!(self.read_notify.notified());
+            // Register for notifications before checking the condition
+            // to not miss any concurrent updates.
+            notified.as_mut().enable();
+            if self.is_write_admitted(max_consecutive_reads) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn write_granted(&self) {
+        self.consecutive_read_count.store(0, Ordering::Release);
+    }
+}
+
+/// Counts a read request as waiting until access has been granted.
+struct WaitingReadScope<'a> {
+    admission_state: &'a AdmissionState,
+}
+
+impl<'a> WaitingReadScope<'a> {
+    fn new(admission_state: &'a AdmissionState) -> Self {
+        admission_state
+            .waiting_read_count
+            .fetch_add(1, Ordering::AcqRel);
+        Self { admission_state }
+    }
+
+    fn granted(self) {
+        self.admission_state
+            .consecutive_read_count
+            .fetch_add(1, Ordering::AcqRel);
+        // The waiting count is decremented on drop
+    }
+}
+
+impl Drop for WaitingReadScope<'_> {
+    fn drop(&mut self) {
+        let waiting_read_count_before = self
+            .admission_state
+            .waiting_read_count
+            .fetch_sub(1, Ordering::AcqRel);
+        debug_assert!(waiting_read_count_before > 0);
+        self.admission_state.read_notify.notify_waiters();
+    }
 }
 
 #[derive(Debug, Default)]
@@ -115,6 +203,7 @@ impl Gatekeeper {
         let Config {
             acquire_read_timeout_millis,
             acquire_write_timeout_millis,
+            fairness_policy,
         } = config;
         let acquire_read_timeout = Duration::from_millis(acquire_read_timeout_millis.get());
         let acquire_write_timeout = Duration::from_millis(acquire_write_timeout_millis.get());
@@ -122,11 +211,31 @@ impl Gatekeeper {
             connection_pool: Arc::new(RwLock::new(connection_pool)),
             acquire_read_timeout,
             acquire_write_timeout,
+            fairness_policy,
             request_counter_state: Default::default(),
+            admission_state: Default::default(),
             decommisioned: AtomicBool::new(false),
+            read_only: false,
         }
     }
 
+    /// Reject all write requests
+    ///
+    /// Write tasks fail early with [`Error::ReadOnly`] without
+    /// acquiring a connection.
+    #[must_use]
+    pub fn new_read_only(connection_pool: ConnectionPool, config: Config) -> Self {
+        Self {
+            read_only: true,
+            ..Self::new(connection_pool, config)
+        }
+    }
+
+    #[must_use]
+    pub const fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     pub fn decommission(&self) {
         self.decommisioned.store(true, Ordering::Relaxed);
     }
@@ -140,6 +249,13 @@ impl Gatekeeper {
         Ok(())
     }
 
+    const fn check_not_read_only(&self) -> Result<()> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+        Ok(())
+    }
+
     pub async fn spawn_blocking_read_task_with_timeout<H, R>(
         &self,
         connection_handler: H,
@@ -157,7 +273,12 @@ impl Gatekeeper {
         let mut timeout = pin!(sleep(acquire_read_timeout));
         tokio::select! {
             () = &mut timeout => Err(Error::TaskTimeout {reason: "database is locked".to_string() }),
-            guard = self.connection_pool.read() => {
+            guard = async {
+                let waiting_read_scope = WaitingReadScope::new(&self.admission_state);
+                let guard = self.connection_pool.read().await;
+                waiting_read_scope.granted();
+                guard
+            } => {
                 self.check_not_decommissioned()?;
                 let connection = get_pooled_connection(&guard)?;
                 self.check_not_decommissioned()?;
@@ -187,6 +308,7 @@ impl Gatekeeper {
         R: Send + 'static,
     {
         self.check_not_decommissioned()?;
+        self.check_not_read_only()?;
         let _request_counter_scope = RequestCounterScope::new(
             Arc::clone(&self.request_counter_state),
             RequestCounterMode::Write,
@@ -194,7 +316,12 @@ impl Gatekeeper {
         let mut timeout = pin!(sleep(acquire_write_timeout));
         tokio::select! {
             () = &mut timeout => Err(Error::TaskTimeout {reason: "database is locked".to_string() }),
-            guard = self.connection_pool.write() => {
+            guard = async {
+                self.admission_state.admit_write(self.fairness_policy).await;
+                let guard = self.connection_pool.write().await;
+                self.admission_state.write_granted();
+                guard
+            } => {
                 self.check_not_decommissioned()?;
                 let connection = get_pooled_connection(&guard)?;
                 self.check_not_decommissioned()?;
@@ -228,9 +355,35 @@ impl Gatekeeper {
     }
 }
 
+/// Controls the order in which read and write requests are granted access
+///
+/// Both policies guarantee that neither read nor write requests
+/// starve under sustained load of the other kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FairnessPolicy {
+    /// Grant access in the order of the requests.
+    ///
+    /// Read requests that arrive after a pending write request
+    /// have to wait until this write request has finished.
+    #[default]
+    Fifo,
+
+    /// Grant access to batches of concurrent read requests.
+    ///
+    /// Pending write requests are deferred while read requests are waiting
+    /// until `max_consecutive_reads` read requests have been granted access
+    /// since the last write request. Increases the read throughput at the
+    /// expense of the latency of write requests.
+    ReadBatch { max_consecutive_reads: NonZeroU32 },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Config {
     pub acquire_read_timeout_millis: NonZeroU64,
     pub acquire_write_timeout_millis: NonZeroU64,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fairness_policy: FairnessPolicy,
 }
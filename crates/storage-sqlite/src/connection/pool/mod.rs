@@ -3,11 +3,11 @@
 
 use std::num::NonZeroU32;
 
-use diesel::{r2d2, Connection as _};
+use diesel::r2d2;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use super::Storage;
+use super::{establish_connection, Storage};
 use crate::Result;
 
 pub type ConnectionManager = r2d2::ConnectionManager<diesel::SqliteConnection>;
@@ -19,19 +19,26 @@ pub type PooledConnection = r2d2::PooledConnection<ConnectionManager>;
 #[cfg(feature = "tokio")]
 pub mod gatekeeper;
 
-pub fn create_connection_pool(storage: &Storage, max_size: NonZeroU32) -> Result<ConnectionPool> {
-    let storage = storage.as_ref();
+/// Create a connection pool
+///
+/// The pragma parameters are applied to every connection in the pool.
+pub fn create_connection_pool(
+    storage: &Storage,
+    max_size: NonZeroU32,
+    pragma_config: super::pragma::Config,
+) -> Result<ConnectionPool> {
     // Establish a test connection before creating the connection pool to fail early.
     // If the given file is inaccessible r2d2 (Diesel 1.4.8) seems to do multiple retries
     // and logs errors instead of simply failing and returning and error immediately.
     // Example file name: connection = ":/tmp/aoide.sqlite"
-    std::mem::drop(diesel::SqliteConnection::establish(storage)?);
+    std::mem::drop(establish_connection(storage, &pragma_config)?);
     // The test connection is dropped immediately without using it
     // and missing files should have been created after reaching
     // this point.
-    let manager = ConnectionManager::new(storage);
+    let manager = ConnectionManager::new(storage.as_ref());
     let pool = ConnectionPool::builder()
         .max_size(max_size.get())
+        .connection_customizer(Box::new(pragma_config))
         .build(manager)?;
     Ok(pool)
 }
@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt::Write as _;
+
+use anyhow::anyhow;
+use diesel::{connection::SimpleConnection as _, r2d2, QueryResult, SqliteConnection};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Upper limit for the size of the page cache (1 gibibyte).
+pub const MAX_CACHE_SIZE_KIB: u32 = 1 << 20;
+
+/// Upper limit for the size of memory-mapped I/O (16 gibibytes).
+///
+/// SQLite silently reduces larger values to the compile-time
+/// limit `SQLITE_MAX_MMAP_SIZE`.
+pub const MAX_MMAP_SIZE_BYTES: u64 = 1 << 34;
+
+/// Synchronization mode of the database file
+///
+/// See also: <https://www.sqlite.org/pragma.html#pragma_synchronous>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Synchronous {
+    /// Don't sync at all
+    ///
+    /// The database might become corrupted if the operating system
+    /// crashes or the computer loses power.
+    Off,
+
+    /// Only sync at the most critical moments
+    ///
+    /// With `journal_mode = WAL` the database stays consistent even
+    /// after a power loss or operating system crash. But the most
+    /// recently committed transactions might be rolled back, i.e.
+    /// durability is traded for faster commits.
+    Normal,
+
+    /// Sync on every commit
+    ///
+    /// Recommended when not using `journal_mode = WAL`.
+    Full,
+
+    /// Like `Full`, but additionally sync the directory of the journal
+    Extra,
+}
+
+impl Synchronous {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "OFF",
+            Self::Normal => "NORMAL",
+            Self::Full => "FULL",
+            Self::Extra => "EXTRA",
+        }
+    }
+}
+
+/// Storage location of temporary tables and indices
+///
+/// See also: <https://www.sqlite.org/pragma.html#pragma_temp_store>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TempStore {
+    /// Use the compile-time default
+    Default,
+    File,
+    Memory,
+}
+
+impl TempStore {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "DEFAULT",
+            Self::File => "FILE",
+            Self::Memory => "MEMORY",
+        }
+    }
+}
+
+/// Per-connection tuning parameters
+///
+/// The parameters are applied to every newly established connection.
+/// Unset parameters keep the defaults of SQLite.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Config {
+    /// Maximum size of the page cache in kibibytes
+    ///
+    /// Valid range: 1..=[`MAX_CACHE_SIZE_KIB`]
+    ///
+    /// See also: <https://www.sqlite.org/pragma.html#pragma_cache_size>
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub cache_size_kib: Option<u32>,
+
+    /// Maximum size of memory-mapped I/O in bytes
+    ///
+    /// Valid range: 0..=[`MAX_MMAP_SIZE_BYTES`]. Memory-mapped
+    /// I/O is disabled for 0.
+    ///
+    /// See also: <https://www.sqlite.org/pragma.html#pragma_mmap_size>
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub mmap_size_bytes: Option<u64>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub synchronous: Option<Synchronous>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub temp_store: Option<TempStore>,
+
+    /// Reject all changes of the database
+    ///
+    /// See also: <https://www.sqlite.org/pragma.html#pragma_query_only>
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub query_only: Option<bool>,
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<()> {
+        let Self {
+            cache_size_kib,
+            mmap_size_bytes,
+            synchronous: _,
+            temp_store: _,
+            query_only: _,
+        } = self;
+        if let Some(cache_size_kib) = cache_size_kib {
+            if !(1..=MAX_CACHE_SIZE_KIB).contains(cache_size_kib) {
+                return Err(Error::Other(anyhow!(
+                    "invalid cache size: {cache_size_kib} KiB"
+                )));
+            }
+        }
+        if let Some(mmap_size_bytes) = mmap_size_bytes {
+            if *mmap_size_bytes > MAX_MMAP_SIZE_BYTES {
+                return Err(Error::Other(anyhow!(
+                    "invalid mmap size: {mmap_size_bytes} bytes"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the parameters to a connection
+    ///
+    /// Foreign key constraints are always enforced, independent of
+    /// the parameters. The setting is per connection and the schema
+    /// relies on cascading deletes.
+    ///
+    /// The parameters must have been validated before.
+    pub fn apply(&self, connection: &mut SqliteConnection) -> QueryResult<()> {
+        debug_assert!(self.validate().is_ok());
+        let Self {
+            cache_size_kib,
+            mmap_size_bytes,
+            synchronous,
+            temp_store,
+            query_only,
+        } = self;
+        let mut sql = String::from("PRAGMA foreign_keys = 1;");
+        if let Some(cache_size_kib) = cache_size_kib {
+            // Negative values denote the size in KiB instead of pages
+            write!(sql, "PRAGMA cache_size = -{cache_size_kib};").expect("infallible");
+        }
+        if let Some(mmap_size_bytes) = mmap_size_bytes {
+            write!(sql, "PRAGMA mmap_size = {mmap_size_bytes};").expect("infallible");
+        }
+        if let Some(synchronous) = synchronous {
+            write!(sql, "PRAGMA synchronous = {};", synchronous.as_str()).expect("infallible");
+        }
+        if let Some(temp_store) = temp_store {
+            write!(sql, "PRAGMA temp_store = {};", temp_store.as_str()).expect("infallible");
+        }
+        if let Some(query_only) = query_only {
+            write!(sql, "PRAGMA query_only = {};", i32::from(*query_only)).expect("infallible");
+        }
+        connection.batch_execute(&sql)
+    }
+}
+
+impl r2d2::CustomizeConnection<SqliteConnection, r2d2::Error> for Config {
+    fn on_acquire(
+        &self,
+        connection: &mut SqliteConnection,
+    ) -> std::result::Result<(), r2d2::Error> {
+        self.apply(connection).map_err(r2d2::Error::QueryError)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,106 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use diesel::{
+    connection::SimpleConnection as _, sql_types::BigInt, QueryableByName, RunQueryDsl as _,
+};
+
+use super::*;
+use crate::connection::{
+    establish_connection,
+    pool::{create_connection_pool, get_pooled_connection},
+    Storage,
+};
+
+#[derive(QueryableByName)]
+struct CacheSize {
+    #[diesel(sql_type = BigInt)]
+    cache_size: i64,
+}
+
+fn query_cache_size(connection: &mut SqliteConnection) -> i64 {
+    diesel::dsl::sql_query("PRAGMA cache_size")
+        .get_result::<CacheSize>(connection)
+        .unwrap()
+        .cache_size
+}
+
+#[test]
+fn establish_connection_with_custom_cache_size() {
+    let config = Config {
+        cache_size_kib: Some(4096),
+        ..Default::default()
+    };
+    let mut connection = establish_connection(&Storage::InMemory, &config).unwrap();
+    assert_eq!(-4096, query_cache_size(&mut connection));
+}
+
+#[test]
+fn pooled_connections_with_custom_cache_size() {
+    let config = Config {
+        cache_size_kib: Some(8192),
+        mmap_size_bytes: Some(0),
+        synchronous: Some(Synchronous::Normal),
+        temp_store: Some(TempStore::Memory),
+        query_only: None,
+    };
+    let pool = create_connection_pool(&Storage::InMemory, 2.try_into().unwrap(), config).unwrap();
+    let mut connection = get_pooled_connection(&pool).unwrap();
+    assert_eq!(-8192, query_cache_size(&mut connection));
+}
+
+#[derive(QueryableByName)]
+struct ForeignKeys {
+    #[diesel(sql_type = BigInt)]
+    foreign_keys: i64,
+}
+
+fn query_foreign_keys(connection: &mut SqliteConnection) -> i64 {
+    diesel::dsl::sql_query("PRAGMA foreign_keys")
+        .get_result::<ForeignKeys>(connection)
+        .unwrap()
+        .foreign_keys
+}
+
+#[test]
+fn pooled_connections_enforce_foreign_keys() {
+    let pool = create_connection_pool(&Storage::InMemory, 2.try_into().unwrap(), Config::default())
+        .unwrap();
+    let mut connection = get_pooled_connection(&pool).unwrap();
+    assert_eq!(1, query_foreign_keys(&mut connection));
+}
+
+#[test]
+fn reject_invalid_sizes() {
+    assert!(Config::default().validate().is_ok());
+    for config in [
+        Config {
+            cache_size_kib: Some(0),
+            ..Default::default()
+        },
+        Config {
+            cache_size_kib: Some(MAX_CACHE_SIZE_KIB + 1),
+            ..Default::default()
+        },
+        Config {
+            mmap_size_bytes: Some(MAX_MMAP_SIZE_BYTES + 1),
+            ..Default::default()
+        },
+    ] {
+        assert!(config.validate().is_err());
+        assert!(establish_connection(&Storage::InMemory, &config).is_err());
+    }
+}
+
+#[test]
+fn query_only_rejects_changes() {
+    let config = Config {
+        query_only: Some(true),
+        ..Default::default()
+    };
+    let mut connection = establish_connection(&Storage::InMemory, &config).unwrap();
+    assert!(connection.batch_execute("SELECT 1").is_ok());
+    assert!(connection
+        .batch_execute("CREATE TABLE test (id INTEGER PRIMARY KEY)")
+        .is_err());
+}
@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+
+use diesel::{
+    sql_types::{BigInt, Nullable, Text},
+    QueryableByName, RunQueryDsl as _, SqliteConnection,
+};
+
+use crate::Result;
+
+/// The result message of `PRAGMA integrity_check` if no problems have been found.
+const INTEGRITY_CHECK_OK: &str = "ok";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityProblem {
+    /// A problem reported by `PRAGMA integrity_check`.
+    Corruption { message: String },
+
+    /// A row reported by `PRAGMA foreign_key_check`.
+    ForeignKeyViolation {
+        table: String,
+        row_id: Option<i64>,
+        parent_table: String,
+    },
+}
+
+impl fmt::Display for IntegrityProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Corruption { message } => f.write_str(message),
+            Self::ForeignKeyViolation {
+                table,
+                row_id,
+                parent_table,
+            } => {
+                if let Some(row_id) = row_id {
+                    write!(
+                        f,
+                        "row {row_id} in table {table} violates foreign key constraint of table {parent_table}"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "row in table {table} violates foreign key constraint of table {parent_table}"
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub problems: Vec<IntegrityProblem>,
+}
+
+impl IntegrityReport {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[derive(QueryableByName)]
+struct IntegrityCheckRow {
+    #[diesel(sql_type = Text)]
+    integrity_check: String,
+}
+
+#[derive(QueryableByName)]
+struct ForeignKeyCheckRow {
+    #[diesel(sql_type = Text)]
+    table: String,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    rowid: Option<i64>,
+    #[diesel(sql_type = Text)]
+    parent: String,
+}
+
+/// Check the integrity of the database.
+///
+/// Runs both `PRAGMA integrity_check` and `PRAGMA foreign_key_check`
+/// and collects all reported problems. The database is not modified.
+///
+/// See also: <https://www.sqlite.org/pragma.html#pragma_integrity_check>
+pub fn check_integrity(connection: &mut SqliteConnection) -> Result<IntegrityReport> {
+    let mut problems = diesel::dsl::sql_query("PRAGMA integrity_check")
+        .load::<IntegrityCheckRow>(connection)?
+        .into_iter()
+        .filter_map(|IntegrityCheckRow { integrity_check }| {
+            (integrity_check != INTEGRITY_CHECK_OK).then_some(IntegrityProblem::Corruption {
+                message: integrity_check,
+            })
+        })
+        .collect::<Vec<_>>();
+    problems.extend(
+        diesel::dsl::sql_query("PRAGMA foreign_key_check")
+            .load::<ForeignKeyCheckRow>(connection)?
+            .into_iter()
+            .map(
+                |ForeignKeyCheckRow {
+                     table,
+                     rowid,
+                     parent,
+                 }| IntegrityProblem::ForeignKeyViolation {
+                    table,
+                    row_id: rowid,
+                    parent_table: parent,
+                },
+            ),
+    );
+    Ok(IntegrityReport { problems })
+}
+
+#[cfg(test)]
+mod tests;
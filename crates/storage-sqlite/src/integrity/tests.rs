@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use diesel::connection::SimpleConnection as _;
+
+use super::*;
+use crate::connection::{establish_connection, pragma, Storage};
+
+fn establish_connection_with_schema() -> SqliteConnection {
+    let mut connection =
+        establish_connection(&Storage::InMemory, &pragma::Config::default()).unwrap();
+    connection
+        .batch_execute(
+            "CREATE TABLE parent (id INTEGER PRIMARY KEY);
+            CREATE TABLE child (id INTEGER PRIMARY KEY, parent_id INTEGER NOT NULL REFERENCES parent(id));
+            INSERT INTO parent (id) VALUES (1);
+            INSERT INTO child (id, parent_id) VALUES (1, 1);",
+        )
+        .unwrap();
+    connection
+}
+
+#[test]
+fn healthy_database_is_ok() {
+    let mut connection = establish_connection_with_schema();
+    let report = check_integrity(&mut connection).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(IntegrityReport::default(), report);
+}
+
+#[test]
+fn foreign_key_violations_are_reported() {
+    let mut connection = establish_connection_with_schema();
+    // Temporarily disable enforcement to be able to insert an orphaned row.
+    connection
+        .batch_execute(
+            "PRAGMA foreign_keys = 0;
+            INSERT INTO child (id, parent_id) VALUES (2, 2);
+            PRAGMA foreign_keys = 1;",
+        )
+        .unwrap();
+    let report = check_integrity(&mut connection).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(
+        vec![IntegrityProblem::ForeignKeyViolation {
+            table: "child".to_owned(),
+            row_id: Some(2),
+            parent_table: "parent".to_owned(),
+        }],
+        report.problems
+    );
+}
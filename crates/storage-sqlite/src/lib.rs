@@ -5,6 +5,9 @@ use diesel::{RunQueryDsl as _, SqliteConnection};
 use thiserror::Error;
 
 pub mod connection;
+pub mod integrity;
+
+pub use self::integrity::{check_integrity, IntegrityProblem, IntegrityReport};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -20,6 +23,9 @@ pub enum Error {
     #[error(transparent)]
     Other(anyhow::Error),
 
+    #[error("database is read-only")]
+    ReadOnly,
+
     #[cfg(feature = "tokio")]
     #[error("timeout: {reason}")]
     TaskTimeout { reason: String },
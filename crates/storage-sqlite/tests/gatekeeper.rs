@@ -0,0 +1,150 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    num::{NonZeroU32, NonZeroU64},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use aoide_storage_sqlite::connection::{
+    pool::{
+        create_connection_pool,
+        gatekeeper::{Config, FairnessPolicy, Gatekeeper},
+    },
+    Storage,
+};
+
+const READ_TASK_COUNT: usize = 16;
+
+const WRITE_TASK_COUNT: usize = 4;
+
+const LOAD_DURATION: Duration = Duration::from_millis(500);
+
+const TASK_DURATION: Duration = Duration::from_millis(2);
+
+/// The maximum time for acquiring access that is considered as starvation.
+const MAX_ACQUIRE_DURATION: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default)]
+struct Progress {
+    finished_count: AtomicUsize,
+    max_acquire_duration_micros: AtomicU64,
+}
+
+impl Progress {
+    fn finished(&self, acquire_duration: Duration) {
+        self.finished_count.fetch_add(1, Ordering::Relaxed);
+        self.max_acquire_duration_micros.fetch_max(
+            acquire_duration.as_micros().try_into().unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn max_acquire_duration(&self) -> Duration {
+        Duration::from_micros(self.max_acquire_duration_micros.load(Ordering::Relaxed))
+    }
+}
+
+fn new_gatekeeper(fairness_policy: FairnessPolicy) -> Gatekeeper {
+    let connection_pool = create_connection_pool(
+        &Storage::InMemory,
+        NonZeroU32::new(READ_TASK_COUNT.try_into().unwrap()).unwrap(),
+        Default::default(),
+    )
+    .unwrap();
+    Gatekeeper::new(
+        connection_pool,
+        Config {
+            acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+            acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+            fairness_policy,
+        },
+    )
+}
+
+/// Spawn concurrent read and write tasks that continuously send
+/// requests and record the progress of each task.
+async fn run_sustained_load(fairness_policy: FairnessPolicy) -> (Vec<Progress>, Vec<Progress>) {
+    let gatekeeper = Arc::new(new_gatekeeper(fairness_policy));
+    let deadline = Instant::now() + LOAD_DURATION;
+    let read_progress = (0..READ_TASK_COUNT)
+        .map(|_| Arc::new(Progress::default()))
+        .collect::<Vec<_>>();
+    let write_progress = (0..WRITE_TASK_COUNT)
+        .map(|_| Arc::new(Progress::default()))
+        .collect::<Vec<_>>();
+    let mut tasks = Vec::new();
+    for (progress, is_write) in read_progress
+        .iter()
+        .map(|progress| (progress, false))
+        .chain(write_progress.iter().map(|progress| (progress, true)))
+    {
+        let gatekeeper = Arc::clone(&gatekeeper);
+        let progress = Arc::clone(progress);
+        tasks.push(tokio::spawn(async move {
+            while Instant::now() < deadline {
+                let requested = Instant::now();
+                let acquire_duration = if is_write {
+                    gatekeeper
+                        .spawn_blocking_write_task(move |_connection| {
+                            let acquire_duration = requested.elapsed();
+                            std::thread::sleep(TASK_DURATION);
+                            acquire_duration
+                        })
+                        .await
+                } else {
+                    gatekeeper
+                        .spawn_blocking_read_task(move |_connection| {
+                            let acquire_duration = requested.elapsed();
+                            std::thread::sleep(TASK_DURATION);
+                            acquire_duration
+                        })
+                        .await
+                }
+                .unwrap();
+                progress.finished(acquire_duration);
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+    let unwrap_progress = |progress: Vec<Arc<Progress>>| {
+        progress
+            .into_iter()
+            .map(|progress| Arc::into_inner(progress).unwrap())
+            .collect()
+    };
+    (
+        unwrap_progress(read_progress),
+        unwrap_progress(write_progress),
+    )
+}
+
+fn assert_forward_progress(progress: &[Progress]) {
+    for progress in progress {
+        assert!(progress.finished_count.load(Ordering::Relaxed) > 0);
+        assert!(progress.max_acquire_duration() < MAX_ACQUIRE_DURATION);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fifo_neither_reads_nor_writes_starve() {
+    let (read_progress, write_progress) = run_sustained_load(FairnessPolicy::Fifo).await;
+    assert_forward_progress(&read_progress);
+    assert_forward_progress(&write_progress);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn read_batch_neither_reads_nor_writes_starve() {
+    let (read_progress, write_progress) = run_sustained_load(FairnessPolicy::ReadBatch {
+        max_consecutive_reads: NonZeroU32::new(8).unwrap(),
+    })
+    .await;
+    assert_forward_progress(&read_progress);
+    assert_forward_progress(&write_progress);
+}
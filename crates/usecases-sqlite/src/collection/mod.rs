@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use diesel::Connection as _;
 use url::Url;
 
 use aoide_core::{
@@ -9,14 +10,17 @@ use aoide_core::{
     Collection, CollectionEntity, CollectionHeader, CollectionUid,
 };
 use aoide_core_api::{
-    collection::{EntityWithSummary, LoadScope},
+    collection::{EntityWithSummary, LoadScope, Patch},
     Pagination,
 };
 use aoide_repo::{
     collection::{EntityRepo as _, KindFilter, MediaSourceRootUrlFilter, RecordHeader},
     ReservableRecordCollector,
 };
-use aoide_usecases::collection::{self as uc, vfs::RepoContext};
+use aoide_usecases::collection::{
+    self as uc,
+    vfs::{RebaseRootUrlOutcome, RebaseRootUrlParams, RepoContext},
+};
 
 use crate::{DbConnection, Error, RepoConnection, Result};
 
@@ -41,6 +45,15 @@ pub fn update(
     Ok(updated_entity)
 }
 
+pub fn patch(
+    connection: &mut DbConnection,
+    entity_header: CollectionHeader,
+    patch: Patch,
+) -> Result<CollectionEntity> {
+    let mut repo = RepoConnection::new(connection);
+    uc::patch(&mut repo, entity_header, patch).map_err(Into::into)
+}
+
 pub fn purge(connection: &mut DbConnection, entity_uid: &CollectionUid) -> Result<()> {
     let mut repo = RepoConnection::new(connection);
     uc::purge(&mut repo, entity_uid).map_err(Into::into)
@@ -113,5 +126,22 @@ pub fn resolve_url_from_content_path(
         .map_err(Error::Other)
 }
 
+/// Move all media sources of a collection with virtual file paths
+/// to a new root URL.
+///
+/// Runs in a transaction, i.e. either succeeds or leaves the
+/// collection unmodified.
+pub fn rebase_collection_root(
+    connection: &mut DbConnection,
+    entity_uid: &CollectionUid,
+    new_root_url: BaseUrl,
+    params: &RebaseRootUrlParams,
+) -> Result<RebaseRootUrlOutcome> {
+    connection.transaction::<_, Error, _>(|connection| {
+        let mut repo = RepoConnection::new(connection);
+        uc::vfs::rebase_root_url(&mut repo, entity_uid, new_root_url, params).map_err(Into::into)
+    })
+}
+
 #[cfg(test)]
 mod tests;
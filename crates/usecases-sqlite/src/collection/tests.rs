@@ -4,10 +4,23 @@
 use anyhow::{anyhow, Result};
 use aoide_core::{
     collection::MediaSourceConfig,
-    media::content::{ContentPath, ContentPathConfig, VirtualFilePathConfig},
-    util::url::BaseUrl,
-    Collection,
+    media::{
+        self,
+        content::{
+            AudioContentMetadata, ContentLink, ContentPath, ContentPathConfig,
+            VirtualFilePathConfig,
+        },
+    },
+    util::{
+        clock::OffsetDateTimeMs,
+        color::{Color, RgbColor},
+        url::BaseUrl,
+    },
+    Collection, CollectionUid,
 };
+use aoide_core_api::collection::Patch;
+use aoide_repo::{collection::EntityRepo as _, media::source::CollectionRepo as _};
+use aoide_usecases::collection::vfs::RebaseRootUrlParams;
 use diesel::Connection;
 
 use aoide_repo_sqlite::{initialize_database, run_migrations, DbConnection};
@@ -118,3 +131,192 @@ fn resolve_content_path_from_url() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+fn new_collection() -> anyhow::Result<Collection> {
+    Ok(Collection {
+        title: "Test Collection".into(),
+        notes: Some("Some personal notes".into()),
+        kind: Some("test".into()),
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::parse_strict(&format!("{FILE_URL_PREFIX}/a/b/"))?,
+                excluded_paths: vec![],
+            }),
+        },
+    })
+}
+
+#[test]
+fn patch_color_only() -> anyhow::Result<()> {
+    let mut fixture = DbFixture::new()?;
+    let collection = new_collection()?;
+    let created_entity = super::create(&mut fixture.connection, collection.clone())?;
+    let color = Color::Rgb(RgbColor::rgb(0x12, 0x34, 0x56));
+    let patch = Patch {
+        color: Some(Some(color)),
+        ..Default::default()
+    };
+    let patched_entity = super::patch(&mut fixture.connection, created_entity.hdr.clone(), patch)?;
+    assert_eq!(created_entity.hdr.uid, patched_entity.hdr.uid);
+    assert_eq!(created_entity.hdr.rev.next(), Some(patched_entity.hdr.rev));
+    assert_eq!(
+        Collection {
+            color: Some(color),
+            ..collection
+        },
+        patched_entity.body
+    );
+    Ok(())
+}
+
+#[test]
+fn patch_empty_is_noop() -> anyhow::Result<()> {
+    let mut fixture = DbFixture::new()?;
+    let created_entity = super::create(&mut fixture.connection, new_collection()?)?;
+    let patch = Patch::default();
+    assert!(patch.is_empty());
+    let patched_entity = super::patch(&mut fixture.connection, created_entity.hdr.clone(), patch)?;
+    assert_eq!(created_entity.hdr, patched_entity.hdr);
+    assert_eq!(created_entity.body, patched_entity.body);
+    Ok(())
+}
+
+fn root_url_from_dir(dir: &tempfile::TempDir) -> anyhow::Result<BaseUrl> {
+    let root_url =
+        Url::from_directory_path(dir.path()).map_err(|()| anyhow!("invalid root directory"))?;
+    BaseUrl::parse_strict(root_url.as_str()).map_err(Into::into)
+}
+
+fn create_files(dir: &tempfile::TempDir, content_paths: &[&str]) -> anyhow::Result<()> {
+    for content_path in content_paths {
+        std::fs::write(dir.path().join(content_path), [])?;
+    }
+    Ok(())
+}
+
+/// Create a collection with a media source for each content path.
+fn create_collection_with_media_sources(
+    connection: &mut DbConnection,
+    root_url: BaseUrl,
+    content_paths: &[&'static str],
+) -> anyhow::Result<CollectionUid> {
+    let collection = Collection {
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url,
+                excluded_paths: vec![],
+            }),
+        },
+        ..new_collection()?
+    };
+    let collection_uid = super::create(connection, collection)?.hdr.uid.clone();
+    let mut repo = crate::RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(&collection_uid)?;
+    for content_path in content_paths {
+        let media_source = media::Source {
+            collected_at: OffsetDateTimeMs::now_utc(),
+            content: media::Content {
+                link: ContentLink {
+                    path: ContentPath::from(*content_path),
+                    rev: None,
+                },
+                r#type: "audio/mpeg".parse()?,
+                digest: None,
+                size: None,
+                metadata_flags: Default::default(),
+                metadata: AudioContentMetadata::default().into(),
+            },
+            artwork: None,
+        };
+        repo.insert_media_source(collection_id, OffsetDateTimeMs::now_utc(), &media_source)?;
+    }
+    Ok(collection_uid)
+}
+
+#[test]
+fn rebase_collection_root() -> anyhow::Result<()> {
+    let mut fixture = DbFixture::new()?;
+    let content_paths = ["a.mp3", "b.mp3"];
+    let old_root_dir = tempfile::tempdir()?;
+    let collection_uid = create_collection_with_media_sources(
+        &mut fixture.connection,
+        root_url_from_dir(&old_root_dir)?,
+        &content_paths,
+    )?;
+    // Move the whole library into a new directory
+    let new_root_dir = tempfile::tempdir()?;
+    create_files(&new_root_dir, &content_paths)?;
+    let new_root_url = root_url_from_dir(&new_root_dir)?;
+
+    let outcome = super::rebase_collection_root(
+        &mut fixture.connection,
+        &collection_uid,
+        new_root_url.clone(),
+        &RebaseRootUrlParams {
+            check_file_exists: true,
+            dry_run: false,
+        },
+    )?;
+    assert_eq!(2, outcome.resolved_count);
+    assert!(outcome.unresolved_paths.is_empty());
+    let updated_entity = outcome.updated_entity.unwrap();
+    assert_eq!(
+        ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+            root_url: new_root_url.clone(),
+            excluded_paths: vec![],
+        }),
+        updated_entity.body.media_source_config.content_path
+    );
+    for content_path in content_paths {
+        assert_eq!(
+            Some(new_root_url.join(content_path)?),
+            super::resolve_url_from_content_path(
+                &mut fixture.connection,
+                &collection_uid,
+                &ContentPath::from(content_path),
+                None,
+            )?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn rebase_collection_root_dry_run_reports_unresolved_paths() -> anyhow::Result<()> {
+    let mut fixture = DbFixture::new()?;
+    let old_root_dir = tempfile::tempdir()?;
+    let old_root_url = root_url_from_dir(&old_root_dir)?;
+    let collection_uid = create_collection_with_media_sources(
+        &mut fixture.connection,
+        old_root_url.clone(),
+        &["a.mp3", "b.mp3"],
+    )?;
+    // Only a single file has been moved
+    let new_root_dir = tempfile::tempdir()?;
+    create_files(&new_root_dir, &["b.mp3"])?;
+
+    let outcome = super::rebase_collection_root(
+        &mut fixture.connection,
+        &collection_uid,
+        root_url_from_dir(&new_root_dir)?,
+        &RebaseRootUrlParams {
+            check_file_exists: true,
+            dry_run: true,
+        },
+    )?;
+    assert_eq!(1, outcome.resolved_count);
+    assert_eq!(vec![ContentPath::from("a.mp3")], outcome.unresolved_paths);
+    assert!(outcome.updated_entity.is_none());
+    // The collection has not been modified
+    assert_eq!(
+        Some(old_root_url.join("a.mp3")?),
+        super::resolve_url_from_content_path(
+            &mut fixture.connection,
+            &collection_uid,
+            &ContentPath::from("a.mp3"),
+            None,
+        )?
+    );
+    Ok(())
+}
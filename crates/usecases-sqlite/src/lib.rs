@@ -1,11 +1,16 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Instant;
+
 use thiserror::Error;
 
 use aoide_media_file::Error as MediaFileError;
 use aoide_repo::RepoError;
-use aoide_repo_sqlite::{Connection as RepoConnection, DbConnection};
+use aoide_repo_sqlite::{
+    transaction::{is_retryable_diesel_error, is_retryable_repo_error, TransactionError},
+    Connection as RepoConnection, DbConnection,
+};
 use aoide_storage_sqlite::Error as StorageError;
 use aoide_usecases as uc;
 
@@ -45,6 +50,16 @@ impl From<diesel::result::Error> for Error {
     }
 }
 
+impl TransactionError for Error {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Repository(err) => is_retryable_repo_error(err),
+            Self::Storage(StorageError::Database(err)) => is_retryable_diesel_error(err),
+            _ => false,
+        }
+    }
+}
+
 impl From<uc::Error> for Error {
     fn from(err: uc::Error) -> Self {
         use uc::Error as From;
@@ -59,3 +74,8 @@ impl From<uc::Error> for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+/// Elapsed time for recording as a `duration_ms` field of tracing spans.
+fn elapsed_millis(started: Instant) -> u64 {
+    started.elapsed().as_millis().try_into().unwrap_or(u64::MAX)
+}
@@ -4,7 +4,7 @@
 use std::sync::atomic::AtomicBool;
 
 use aoide_core::CollectionUid;
-use aoide_core_api::media::tracker::{find_untracked_files::Outcome, FsTraversalParams};
+use aoide_core_api::media::tracker::find_untracked_files::{Outcome, Params};
 use aoide_repo_sqlite::DbConnection;
 
 use crate::{RepoConnection, Result};
@@ -16,7 +16,7 @@ mod uc {
 pub fn visit_directories<ReportProgressFn: FnMut(uc::ProgressEvent)>(
     connection: &mut DbConnection,
     collection_uid: &CollectionUid,
-    params: &FsTraversalParams,
+    params: &Params,
     report_progress_fn: &mut ReportProgressFn,
     abort_flag: &AtomicBool,
 ) -> Result<Outcome> {
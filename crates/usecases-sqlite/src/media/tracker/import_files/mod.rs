@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::sync::atomic::AtomicBool;
+use std::{sync::atomic::AtomicBool, time::Instant};
 
 use aoide_core::{track::Track, CollectionUid};
 use aoide_core_api::media::tracker::import_files::Params;
@@ -28,6 +28,20 @@ where
     InterceptImportedTrackFn: Fn(Track) -> Track + Send,
     ReportProgressFn: FnMut(uc::ProgressEvent),
 {
+    let span = tracing::info_span!(
+        "import_files",
+        %collection_uid,
+        created = tracing::field::Empty,
+        updated = tracing::field::Empty,
+        unchanged = tracing::field::Empty,
+        skipped = tracing::field::Empty,
+        failed = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+    );
+    let _entered = span.enter();
+    // The root URL may reveal private file system paths.
+    tracing::debug!(root_url = ?params.root_url, "Importing files");
+    let started = Instant::now();
     let mut repo = RepoConnection::new(connection);
     let outcome = uc::import_files(
         &mut repo,
@@ -38,5 +52,15 @@ where
         report_progress_fn,
         abort_flag,
     )?;
+    let tracks = &outcome.summary.tracks;
+    span.record("created", tracks.created);
+    span.record("updated", tracks.updated);
+    span.record("unchanged", tracks.unchanged);
+    span.record("skipped", tracks.skipped);
+    span.record("failed", tracks.failed);
+    span.record("duration_ms", crate::elapsed_millis(started));
     Ok(outcome)
 }
+
+#[cfg(test)]
+mod tests;
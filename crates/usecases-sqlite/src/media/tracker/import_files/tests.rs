@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    fmt,
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
+
+use anyhow::anyhow;
+use diesel::Connection as _;
+use tracing::{
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer, Registry};
+
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPathConfig, VirtualFilePathConfig},
+    util::url::BaseUrl,
+    Collection,
+};
+use aoide_core_api::media::{tracker::import_files::Params, SyncMode};
+use aoide_media_file::io::import::ImportTrackConfig;
+use aoide_repo_sqlite::{initialize_database, run_migrations, DbConnection};
+
+#[derive(Debug, Default)]
+struct CapturedSpan {
+    name: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Visit for CapturedSpan {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.push((field.name(), format!("{value:?}")));
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CaptureSpansLayer {
+    spans: Arc<Mutex<Vec<CapturedSpan>>>,
+}
+
+impl<S> Layer<S> for CaptureSpansLayer
+where
+    S: tracing::Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut captured = CapturedSpan {
+            name: attrs.metadata().name(),
+            ..Default::default()
+        };
+        attrs.record(&mut captured);
+        let span = ctx.span(id).expect("new span");
+        span.extensions_mut()
+            .insert(self.spans.lock().unwrap().len());
+        self.spans.lock().unwrap().push(captured);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("recorded span");
+        let index = *span.extensions().get::<usize>().expect("span index");
+        values.record(&mut self.spans.lock().unwrap()[index]);
+    }
+}
+
+#[test]
+fn import_files_emits_span_with_fields() -> anyhow::Result<()> {
+    let mut connection =
+        DbConnection::establish(":memory:").expect("in-memory database connection");
+    initialize_database(&mut connection)?;
+    run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+    let collection = Collection {
+        title: "Test Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::parse_strict("file:///home/secret/music/")?,
+                excluded_paths: vec![],
+            }),
+        },
+    };
+    let collection_uid = crate::collection::create(&mut connection, collection)?
+        .hdr
+        .uid
+        .clone();
+
+    let layer = CaptureSpansLayer::default();
+    let spans = Arc::clone(&layer.spans);
+    let subscriber = Registry::default().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        super::import_files(
+            &mut connection,
+            &collection_uid,
+            &Params {
+                root_url: None,
+                sync_mode: SyncMode::Modified,
+                content_digest_workers: None,
+            },
+            ImportTrackConfig::default(),
+            &std::convert::identity,
+            &mut |_| {},
+            &AtomicBool::new(false),
+        )
+    })?;
+
+    let spans = spans.lock().unwrap();
+    let span = spans
+        .iter()
+        .find(|span| span.name == "import_files")
+        .expect("import span");
+    let field_value = |name| {
+        span.fields
+            .iter()
+            .find_map(|(field_name, value)| (*field_name == name).then_some(value.as_str()))
+    };
+    assert_eq!(
+        Some(collection_uid.to_string().as_str()),
+        field_value("collection_uid")
+    );
+    assert_eq!(Some("0"), field_value("created"));
+    assert_eq!(Some("0"), field_value("updated"));
+    assert_eq!(Some("0"), field_value("failed"));
+    assert!(field_value("duration_ms").is_some());
+    // File system paths must not be exposed as span fields.
+    assert!(span
+        .fields
+        .iter()
+        .all(|(_, value)| !value.contains("/home/secret")));
+    Ok(())
+}
@@ -8,3 +8,6 @@ pub mod query_status;
 pub mod relink;
 pub mod scan_directories;
 pub mod untrack_directories;
+
+#[cfg(test)]
+mod tests;
@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::sync::atomic::AtomicBool;
+use std::{sync::atomic::AtomicBool, time::Instant};
 
 use aoide_core::CollectionUid;
 use aoide_core_api::media::tracker::{scan_directories::Outcome, FsTraversalParams};
@@ -20,13 +20,31 @@ pub fn scan_directories<ReportProgressFn: FnMut(uc::ProgressEvent)>(
     report_progress_fn: &mut ReportProgressFn,
     abort_flag: &AtomicBool,
 ) -> Result<Outcome> {
+    let span = tracing::info_span!(
+        "scan_directories",
+        %collection_uid,
+        completion = tracing::field::Empty,
+        added = tracing::field::Empty,
+        modified = tracing::field::Empty,
+        orphaned = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+    );
+    let _entered = span.enter();
+    // The root URL may reveal private file system paths.
+    tracing::debug!(root_url = ?params.root_url, "Scanning directories");
+    let started = Instant::now();
     let mut repo = RepoConnection::new(connection);
-    uc::scan_directories(
+    let outcome = uc::scan_directories(
         &mut repo,
         collection_uid,
         params,
         report_progress_fn,
         abort_flag,
-    )
-    .map_err(Into::into)
+    )?;
+    span.record("completion", tracing::field::debug(outcome.completion));
+    span.record("added", outcome.summary.added);
+    span.record("modified", outcome.summary.modified);
+    span.record("orphaned", outcome.summary.orphaned);
+    span.record("duration_ms", crate::elapsed_millis(started));
+    Ok(outcome)
 }
@@ -0,0 +1,474 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use diesel::Connection as _;
+use url::Url;
+
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPath, ContentPathConfig, VirtualFilePathConfig},
+    util::url::BaseUrl,
+    Collection, CollectionUid, Track,
+};
+use aoide_core_api::{
+    media::{
+        tracker::{
+            find_untracked_files,
+            import_files::{Outcome, Params},
+            Completion, FsTraversalParams, Progress, ProgressPhase,
+        },
+        SyncMode,
+    },
+    Pagination,
+};
+use aoide_media_file::{io::import::ImportTrackConfig, util::audio_stream::audio_stream_digest};
+use aoide_repo_sqlite::{initialize_database, run_migrations, DbConnection};
+
+fn establish_connection_with_collection(
+    root_dir_path: &Path,
+) -> anyhow::Result<(DbConnection, CollectionUid)> {
+    let mut connection =
+        DbConnection::establish(":memory:").expect("in-memory database connection");
+    initialize_database(&mut connection)?;
+    run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+    let root_url =
+        Url::from_directory_path(root_dir_path).map_err(|()| anyhow!("invalid root directory"))?;
+    let collection = Collection {
+        title: "Test Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::parse_strict(root_url.as_str())?,
+                excluded_paths: vec![],
+            }),
+        },
+    };
+    let collection_uid = crate::collection::create(&mut connection, collection)?
+        .hdr
+        .uid
+        .clone();
+    Ok((connection, collection_uid))
+}
+
+#[test]
+fn scan_and_import_report_phase_transitions_in_order() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(root_dir.path().join("a").join("b"))?;
+    std::fs::create_dir_all(root_dir.path().join("c"))?;
+    // The root directory and all its subdirectories
+    let directory_count = 4;
+
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+
+    let mut scanning_progress = Vec::new();
+    super::scan_directories::scan_directories(
+        &mut connection,
+        &collection_uid,
+        &FsTraversalParams::default(),
+        &mut |event| scanning_progress.push(Progress::Scanning(event.progress)),
+        &AtomicBool::new(false),
+    )?;
+    let mut importing_progress = Vec::new();
+    super::import_files::import_files(
+        &mut connection,
+        &collection_uid,
+        &Params {
+            root_url: None,
+            sync_mode: SyncMode::Modified,
+            content_digest_workers: None,
+        },
+        ImportTrackConfig::default(),
+        &std::convert::identity,
+        &mut |event| importing_progress.push(Progress::Importing(event.progress)),
+        &AtomicBool::new(false),
+    )?;
+
+    let mut phases = scanning_progress
+        .iter()
+        .chain(&importing_progress)
+        .filter_map(Progress::phase)
+        .collect::<Vec<_>>();
+    phases.dedup();
+    assert_eq!(Some(&ProgressPhase::ScanningDirectories), phases.first());
+    assert_eq!(Some(&ProgressPhase::ImportingFiles), phases.last());
+    // All directories are digested while scanning and before importing
+    let importing_started = phases
+        .iter()
+        .position(|phase| *phase == ProgressPhase::ImportingFiles)
+        .unwrap();
+    assert!(phases[..importing_started].contains(&ProgressPhase::DigestingDirectories));
+    assert!(!phases[importing_started..].contains(&ProgressPhase::ScanningDirectories));
+    assert!(!phases[importing_started..].contains(&ProgressPhase::DigestingDirectories));
+
+    let digested_paths = scanning_progress
+        .iter()
+        .filter_map(|progress| match progress {
+            Progress::Scanning(progress)
+                if progress.phase == ProgressPhase::DigestingDirectories =>
+            {
+                Some(progress.current_path.as_ref().expect("current path"))
+            }
+            _ => None,
+        })
+        .count();
+    assert_eq!(directory_count, digested_paths);
+
+    let imported_paths = importing_progress
+        .iter()
+        .filter(|progress| {
+            matches!(progress, Progress::Importing(progress) if progress.current_path.is_some())
+        })
+        .count();
+    assert_eq!(directory_count, imported_paths);
+    let Some(Progress::Importing(first)) = importing_progress.first() else {
+        unreachable!();
+    };
+    assert_eq!(None, first.estimated_remaining);
+    let Some(Progress::Importing(last)) = importing_progress.last() else {
+        unreachable!();
+    };
+    assert_eq!(Some(Duration::ZERO), last.estimated_remaining);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_directories_is_incomplete_only_with_inaccessible_entries() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(root_dir.path().join("a").join("b"))?;
+
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+
+    let outcome = super::scan_directories::scan_directories(
+        &mut connection,
+        &collection_uid,
+        &FsTraversalParams::default(),
+        &mut |_| {},
+        &AtomicBool::new(false),
+    )?;
+    assert_eq!(Completion::Finished, outcome.completion);
+    assert!(outcome.skipped_paths.is_empty());
+
+    // Symlink loop
+    std::os::unix::fs::symlink(root_dir.path(), root_dir.path().join("a").join("loop"))?;
+    let outcome = super::scan_directories::scan_directories(
+        &mut connection,
+        &collection_uid,
+        &FsTraversalParams::default(),
+        &mut |_| {},
+        &AtomicBool::new(false),
+    )?;
+    assert_eq!(Completion::Finished, outcome.completion);
+    assert!(!outcome.skipped_paths.is_empty());
+
+    // Broken symlink
+    std::fs::remove_dir(root_dir.path().join("a").join("b"))?;
+    std::os::unix::fs::symlink(
+        root_dir.path().join("missing"),
+        root_dir.path().join("a").join("broken"),
+    )?;
+    let outcome = super::scan_directories::scan_directories(
+        &mut connection,
+        &collection_uid,
+        &FsTraversalParams::default(),
+        &mut |_| {},
+        &AtomicBool::new(false),
+    )?;
+    assert_eq!(Completion::Aborted, outcome.completion);
+    assert!(!outcome.skipped_paths.is_empty());
+    // The removed directory is not marked as orphaned
+    assert_eq!(0, outcome.summary.orphaned);
+    Ok(())
+}
+
+#[test]
+fn find_untracked_files_skips_ignored_paths() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(root_dir.path().join(".hidden"))?;
+    std::fs::create_dir_all(root_dir.path().join("sub"))?;
+    for file_path in [
+        "track.mp3",
+        "track.mp3.part",
+        ".DS_Store",
+        "THUMBS.DB",
+        ".hidden/inner.mp3",
+        "sub/cover.jpg",
+    ] {
+        std::fs::write(root_dir.path().join(file_path), [])?;
+    }
+
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+
+    let mut find_untracked_content_paths = |ignore_patterns: Option<Vec<String>>| {
+        let params = find_untracked_files::Params {
+            ignore_patterns,
+            ..Default::default()
+        };
+        super::find_untracked_files::visit_directories(
+            &mut connection,
+            &collection_uid,
+            &params,
+            &mut |_| {},
+            &AtomicBool::new(false),
+        )
+        .map(|outcome| {
+            let mut content_paths = outcome.content_paths;
+            content_paths.sort_unstable();
+            content_paths
+        })
+    };
+
+    // Hidden and junk files are ignored by default.
+    assert_eq!(
+        vec![
+            ContentPath::from("sub/cover.jpg"),
+            ContentPath::from("track.mp3"),
+        ],
+        find_untracked_content_paths(None)?
+    );
+    // Custom patterns replace the defaults.
+    assert_eq!(
+        vec![
+            ContentPath::from(".DS_Store"),
+            ContentPath::from(".hidden/inner.mp3"),
+            ContentPath::from("THUMBS.DB"),
+            ContentPath::from("track.mp3"),
+        ],
+        find_untracked_content_paths(Some(vec!["*.JPG".to_owned(), "*.part".to_owned()]))?
+    );
+    // Nothing is ignored without any patterns.
+    assert_eq!(6, find_untracked_content_paths(Some(vec![]))?.len());
+    Ok(())
+}
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+/// Create files with distinct audio content in the given subdirectories.
+fn create_distinct_mp3_files(
+    root_dir_path: &Path,
+    dir_names: &[&str],
+    files_per_dir: usize,
+) -> anyhow::Result<()> {
+    let fixture_data = std::fs::read(FIXTURE_FILE_PATH)?;
+    for dir_name in dir_names {
+        let dir_path = root_dir_path.join(dir_name);
+        std::fs::create_dir_all(&dir_path)?;
+        for i in 0..files_per_dir {
+            // Trailing data is considered as part of the audio stream
+            let mut data = fixture_data.clone();
+            data.extend(format!("{dir_name}{i}").as_bytes());
+            std::fs::write(dir_path.join(format!("{i:02}.mp3")), data)?;
+        }
+    }
+    Ok(())
+}
+
+fn scan_and_import_files(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    content_digest_workers: usize,
+    intercept_imported_track_fn: &(impl Fn(Track) -> Track + Send),
+    abort_flag: &AtomicBool,
+) -> anyhow::Result<Outcome> {
+    super::scan_directories::scan_directories(
+        connection,
+        collection_uid,
+        &FsTraversalParams::default(),
+        &mut |_| {},
+        &AtomicBool::new(false),
+    )?;
+    let outcome = super::import_files::import_files(
+        connection,
+        collection_uid,
+        &Params {
+            root_url: None,
+            sync_mode: SyncMode::Modified,
+            content_digest_workers: NonZeroUsize::new(content_digest_workers),
+        },
+        ImportTrackConfig::default(),
+        intercept_imported_track_fn,
+        &mut |_| {},
+        abort_flag,
+    )?;
+    Ok(outcome)
+}
+
+fn load_content_digests(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+) -> anyhow::Result<HashMap<String, Option<Vec<u8>>>> {
+    let mut tracks = Vec::new();
+    crate::track::search::search(
+        connection,
+        collection_uid,
+        &Default::default(),
+        &Pagination::default(),
+        &mut tracks,
+    )?;
+    Ok(tracks
+        .into_iter()
+        .map(|(_, entity)| {
+            let content = entity.raw.body.track.media_source.content;
+            (content.link.path.as_str().to_owned(), content.digest)
+        })
+        .collect())
+}
+
+#[test]
+fn import_files_computes_content_digests_in_parallel() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    let dir_names = ["a", "b", "c"];
+    let files_per_dir = 8;
+    create_distinct_mp3_files(root_dir.path(), &dir_names, files_per_dir)?;
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+
+    let outcome = scan_and_import_files(
+        &mut connection,
+        &collection_uid,
+        4,
+        &std::convert::identity,
+        &AtomicBool::new(false),
+    )?;
+    assert_eq!(Completion::Finished, outcome.completion);
+    assert_eq!(
+        dir_names.len() * files_per_dir,
+        outcome.summary.tracks.created
+    );
+
+    let content_digests = load_content_digests(&mut connection, &collection_uid)?;
+    assert_eq!(dir_names.len() * files_per_dir, content_digests.len());
+    for (content_path, content_digest) in content_digests {
+        let mut file = std::fs::File::open(root_dir.path().join(&content_path))?;
+        let expected_digest = audio_stream_digest(&mut file)?;
+        assert_eq!(
+            Some(expected_digest.as_slice()),
+            content_digest.as_deref(),
+            "{content_path}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn import_files_without_content_digest_workers() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    create_distinct_mp3_files(root_dir.path(), &["a"], 2)?;
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+
+    scan_and_import_files(
+        &mut connection,
+        &collection_uid,
+        0,
+        &std::convert::identity,
+        &AtomicBool::new(false),
+    )?;
+    let content_digests = load_content_digests(&mut connection, &collection_uid)?;
+    assert_eq!(2, content_digests.len());
+    assert!(content_digests.values().all(Option::is_none));
+    Ok(())
+}
+
+#[test]
+fn abort_import_files_while_computing_content_digests() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    let files_per_dir = 32;
+    create_distinct_mp3_files(root_dir.path(), &["a"], files_per_dir)?;
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+
+    let abort_after_count = 3;
+    let abort_flag = AtomicBool::new(false);
+    let imported_count = AtomicUsize::new(0);
+    let abort_after_imported = |track| {
+        if imported_count.fetch_add(1, Ordering::Relaxed) + 1 >= abort_after_count {
+            abort_flag.store(true, Ordering::Relaxed);
+        }
+        track
+    };
+    let outcome = scan_and_import_files(
+        &mut connection,
+        &collection_uid,
+        2,
+        &abort_after_imported,
+        &abort_flag,
+    )?;
+    assert_eq!(Completion::Aborted, outcome.completion);
+    assert_eq!(abort_after_count, outcome.summary.tracks.created);
+    assert_eq!(abort_after_count, imported_count.load(Ordering::Relaxed));
+
+    let content_digests = load_content_digests(&mut connection, &collection_uid)?;
+    assert_eq!(abort_after_count, content_digests.len());
+    assert!(content_digests.values().all(Option::is_some));
+    Ok(())
+}
+
+#[test]
+fn abort_import_files_after_first_file() -> anyhow::Result<()> {
+    let root_dir = tempfile::tempdir()?;
+    let files_per_dir = 4;
+    create_distinct_mp3_files(root_dir.path(), &["a"], files_per_dir)?;
+    let (mut connection, collection_uid) = establish_connection_with_collection(root_dir.path())?;
+    super::scan_directories::scan_directories(
+        &mut connection,
+        &collection_uid,
+        &FsTraversalParams::default(),
+        &mut |_| {},
+        &AtomicBool::new(false),
+    )?;
+
+    let abort_flag = AtomicBool::new(false);
+    let mut imported_file_paths = Vec::new();
+    let mut last_progress = None;
+    let outcome = super::import_files::import_files(
+        &mut connection,
+        &collection_uid,
+        &Params {
+            root_url: None,
+            sync_mode: SyncMode::Modified,
+            content_digest_workers: None,
+        },
+        ImportTrackConfig::default(),
+        &std::convert::identity,
+        &mut |event| {
+            if let Some(current_path) = &event.progress.current_path {
+                if Path::new(current_path.as_str())
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"))
+                {
+                    // Abort as soon as the first file has been imported
+                    imported_file_paths.push(current_path.clone());
+                    abort_flag.store(true, Ordering::Relaxed);
+                }
+            }
+            last_progress = Some(event.progress);
+        },
+        &abort_flag,
+    )?;
+    assert_eq!(Completion::Aborted, outcome.completion);
+    assert_eq!(1, imported_file_paths.len());
+    assert_eq!(1, outcome.summary.tracks.created);
+    // The final progress event reflects the partial outcome.
+    let last_progress = last_progress.expect("final progress");
+    assert_eq!(None, last_progress.current_path);
+    assert_eq!(outcome.summary, last_progress.summary);
+
+    let content_digests = load_content_digests(&mut connection, &collection_uid)?;
+    assert_eq!(
+        vec![imported_file_paths[0].as_str()],
+        content_digests
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
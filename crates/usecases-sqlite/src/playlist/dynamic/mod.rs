@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Dynamic playlists with contents that are resolved from a stored track search.
+
+use anyhow::anyhow;
+use diesel::Connection as _;
+
+use aoide_core::{CollectionUid, PlaylistUid, TrackEntity};
+use aoide_core_api::{
+    track::search::{FieldGroups, Filter, SortOrder},
+    Pagination,
+};
+use aoide_repo::{
+    collection::EntityRepo as _,
+    playlist::{DynamicQuery, EntityRepo as _},
+};
+use aoide_repo_sqlite::DbConnection;
+use aoide_usecases as uc;
+
+use crate::{Error, RepoConnection, Result};
+
+/// Store the track search of a dynamic playlist.
+///
+/// Turns a dynamic playlist back into a static playlist if both
+/// `filter` and `ordering` are empty.
+pub fn store_query(
+    connection: &mut DbConnection,
+    playlist_uid: &PlaylistUid,
+    filter: Option<Filter>,
+    ordering: Vec<SortOrder>,
+) -> Result<()> {
+    if let Some(filter) = &filter {
+        uc::track::search::validate_filter(filter)
+            .map_err(|uc::InputError(err)| Error::Input(err))?;
+    }
+    let query =
+        (filter.is_some() || !ordering.is_empty()).then_some(DynamicQuery { filter, ordering });
+    let mut repo = RepoConnection::new(connection);
+    let id = repo.resolve_playlist_id(playlist_uid)?;
+    repo.store_playlist_dynamic_query(id, query.as_ref())
+        .map_err(Into::into)
+}
+
+/// Load the track search of a dynamic playlist.
+///
+/// Returns `None` if the playlist is not dynamic.
+pub fn load_query(
+    connection: &mut DbConnection,
+    playlist_uid: &PlaylistUid,
+) -> Result<Option<(Option<Filter>, Vec<SortOrder>)>> {
+    let mut repo = RepoConnection::new(connection);
+    let id = repo.resolve_playlist_id(playlist_uid)?;
+    let query = repo.load_playlist_dynamic_query(id)?;
+    Ok(query.map(|DynamicQuery { filter, ordering }| (filter, ordering)))
+}
+
+/// Resolve the current contents of a dynamic playlist.
+///
+/// The stored track search is executed within the given collection,
+/// i.e. the result reflects all tracks that have been added, modified,
+/// or removed since the query has been stored. The playlist must
+/// belong to this collection.
+///
+/// Runs in a transaction, i.e. the stored query and the tracks are
+/// loaded consistently.
+pub fn resolve_dynamic_playlist(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    playlist_uid: &PlaylistUid,
+) -> Result<Vec<TrackEntity>> {
+    connection.transaction::<_, Error, _>(|connection| {
+        let mut repo = RepoConnection::new(connection);
+        let collection_id = repo.resolve_collection_id(collection_uid)?;
+        let playlist_id = repo.resolve_playlist_id(playlist_uid)?;
+        if repo.load_playlist_collection_id(playlist_id)? != Some(collection_id) {
+            return Err(Error::Input(anyhow!(
+                "playlist {playlist_uid} does not belong to collection {collection_uid}"
+            )));
+        }
+        let Some(DynamicQuery { filter, ordering }) =
+            repo.load_playlist_dynamic_query(playlist_id)?
+        else {
+            return Err(Error::Input(anyhow!(
+                "playlist {playlist_uid} is not dynamic"
+            )));
+        };
+        let mut collector = Vec::new();
+        uc::track::search::search(
+            &mut repo,
+            collection_id,
+            &Pagination::new(),
+            filter.as_ref(),
+            &ordering,
+            FieldGroups::all(),
+            &mut collector,
+        )?;
+        Ok(collector.into_iter().map(|(_, entity)| entity).collect())
+    })
+}
+
+#[cfg(test)]
+mod tests;
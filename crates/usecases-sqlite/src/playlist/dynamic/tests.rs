@@ -0,0 +1,310 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::{anyhow, Result};
+use diesel::Connection as _;
+use nonicle::CanonicalizeInto as _;
+
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::{
+        self,
+        content::{AudioContentMetadata, ContentLink, ContentPathConfig, VirtualFilePathConfig},
+    },
+    tag::{FacetKey, Label, PlainTag, TagsMap},
+    track::tag::FACET_ID_GENRE,
+    util::{clock::OffsetDateTimeMs, url::BaseUrl},
+    Collection, CollectionUid, Playlist, PlaylistUid, Track,
+};
+use aoide_core_api::{
+    filtering::{FacetLabelsPredicate, FacetedTagsPredicate, StringPredicate, MAX_NESTING_DEPTH},
+    track::search::{Filter, Params as SearchParams, SortField, SortOrder},
+    SortDirection,
+};
+use aoide_repo::track::ReplaceMode;
+use aoide_repo_sqlite::{initialize_database, run_migrations, DbConnection};
+use aoide_usecases::track::{replace::Params as ReplaceParams, validate_input};
+
+use crate::Error;
+
+struct Fixture {
+    connection: DbConnection,
+    collection_uid: CollectionUid,
+    playlist_uid: PlaylistUid,
+}
+
+impl Fixture {
+    fn new() -> Result<Self> {
+        let mut connection =
+            DbConnection::establish(":memory:").expect("in-memory database connection");
+        initialize_database(&mut connection)?;
+        run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+        let collection = Collection {
+            title: "Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url: BaseUrl::parse_strict("file:///music/")?,
+                    excluded_paths: vec![],
+                }),
+            },
+        };
+        let collection_uid = crate::collection::create(&mut connection, collection)?
+            .hdr
+            .uid
+            .clone();
+        let playlist = Playlist {
+            title: "Rock".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            flags: Default::default(),
+        };
+        let playlist_uid =
+            crate::playlist::create(&mut connection, Some(&collection_uid), playlist)?
+                .hdr
+                .uid
+                .clone();
+        Ok(Self {
+            connection,
+            collection_uid,
+            playlist_uid,
+        })
+    }
+
+    fn add_track(&mut self, path: &str, genre: &str) -> Result<()> {
+        let media_source = media::Source {
+            collected_at: OffsetDateTimeMs::now_utc(),
+            content: media::Content {
+                link: ContentLink {
+                    path: path.to_owned().into(),
+                    rev: None,
+                },
+                r#type: "audio/mpeg".parse().unwrap(),
+                metadata_flags: Default::default(),
+                metadata: AudioContentMetadata::default().into(),
+                digest: None,
+                size: None,
+            },
+            artwork: Default::default(),
+        };
+        let mut track = Track::new_from_media_source(media_source);
+        let mut tags = TagsMap::<'static>::default();
+        tags.insert(
+            FACET_ID_GENRE.clone_owned(),
+            PlainTag {
+                label: Some(Label::from_unchecked(genre.to_owned())),
+                score: Default::default(),
+            },
+        );
+        track.tags = tags.canonicalize_into();
+        let (validated_input, _invalidities) = validate_input(track)?;
+        let params = ReplaceParams {
+            mode: ReplaceMode::UpdateOrCreate,
+            resolve_path_from_url: false,
+            preserve_collected_at: false,
+            update_last_synchronized_rev: false,
+            decode_gigtags: false,
+        };
+        crate::track::replace::replace_many_by_media_source_content_path(
+            &mut self.connection,
+            &self.collection_uid,
+            &params,
+            [validated_input],
+        )?;
+        Ok(())
+    }
+
+    fn remove_track(&mut self, path: &str) -> Result<()> {
+        crate::track::purge::purge_by_media_source_content_path_predicates(
+            &mut self.connection,
+            &self.collection_uid,
+            vec![StringPredicate::Equals(path.into())],
+        )?;
+        Ok(())
+    }
+
+    fn resolve_paths(&mut self) -> Result<Vec<String>> {
+        let tracks = super::resolve_dynamic_playlist(
+            &mut self.connection,
+            &self.collection_uid,
+            &self.playlist_uid,
+        )?;
+        Ok(tracks
+            .into_iter()
+            .map(|entity| {
+                entity
+                    .raw
+                    .body
+                    .track
+                    .media_source
+                    .content
+                    .link
+                    .path
+                    .to_string()
+            })
+            .collect())
+    }
+}
+
+fn genre_filter(genre: &str) -> Filter {
+    Filter::FacetedTags(FacetedTagsPredicate {
+        all_of: vec![FacetLabelsPredicate {
+            modifier: None,
+            facet: FacetKey::from(FACET_ID_GENRE.clone_owned()),
+            any_of: vec![Label::from_unchecked(genre.to_owned())],
+        }],
+    })
+}
+
+fn ordering_by_path() -> Vec<SortOrder> {
+    vec![SortOrder {
+        field: SortField::ContentPath,
+        direction: SortDirection::Ascending,
+    }]
+}
+
+#[test]
+fn resolve_genre_filtered_playlist() -> Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.add_track("rock1.mp3", "Rock")?;
+    fixture.add_track("jazz1.mp3", "Jazz")?;
+    super::store_query(
+        &mut fixture.connection,
+        &fixture.playlist_uid,
+        Some(genre_filter("Rock")),
+        ordering_by_path(),
+    )?;
+    assert_eq!(vec!["rock1.mp3"], fixture.resolve_paths()?);
+
+    // Added tracks are reflected in the contents
+    fixture.add_track("rock2.mp3", "Rock")?;
+    fixture.add_track("jazz2.mp3", "Jazz")?;
+    assert_eq!(vec!["rock1.mp3", "rock2.mp3"], fixture.resolve_paths()?);
+
+    // Removed tracks are reflected in the contents
+    fixture.remove_track("rock1.mp3")?;
+    assert_eq!(vec!["rock2.mp3"], fixture.resolve_paths()?);
+
+    // Modified tracks are reflected in the contents
+    fixture.add_track("jazz1.mp3", "Rock")?;
+    assert_eq!(vec!["jazz1.mp3", "rock2.mp3"], fixture.resolve_paths()?);
+
+    Ok(())
+}
+
+#[test]
+fn store_and_remove_query() -> Result<()> {
+    let mut fixture = Fixture::new()?;
+    assert!(super::load_query(&mut fixture.connection, &fixture.playlist_uid)?.is_none());
+    assert!(fixture.resolve_paths().is_err());
+
+    super::store_query(
+        &mut fixture.connection,
+        &fixture.playlist_uid,
+        Some(genre_filter("Rock")),
+        ordering_by_path(),
+    )?;
+    let (filter, ordering) = super::load_query(&mut fixture.connection, &fixture.playlist_uid)?
+        .expect("dynamic playlist");
+    assert!(matches!(filter, Some(Filter::FacetedTags(_))));
+    assert_eq!(ordering_by_path(), ordering);
+
+    super::store_query(&mut fixture.connection, &fixture.playlist_uid, None, vec![])?;
+    assert!(super::load_query(&mut fixture.connection, &fixture.playlist_uid)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn reject_resolving_playlist_of_another_collection() -> Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.add_track("rock1.mp3", "Rock")?;
+    super::store_query(
+        &mut fixture.connection,
+        &fixture.playlist_uid,
+        Some(genre_filter("Rock")),
+        ordering_by_path(),
+    )?;
+    let other_collection = Collection {
+        title: "Other collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::parse_strict("file:///other/")?,
+                excluded_paths: vec![],
+            }),
+        },
+    };
+    let other_collection_uid =
+        crate::collection::create(&mut fixture.connection, other_collection)?
+            .hdr
+            .uid
+            .clone();
+    assert!(super::resolve_dynamic_playlist(
+        &mut fixture.connection,
+        &other_collection_uid,
+        &fixture.playlist_uid,
+    )
+    .is_err());
+    assert_eq!(vec!["rock1.mp3"], fixture.resolve_paths()?);
+    Ok(())
+}
+
+#[test]
+fn reject_too_deeply_nested_filters_as_invalid_input() -> Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.add_track("rock1.mp3", "Rock")?;
+    let nested_filter = |depth| {
+        (0..depth).fold(genre_filter("Rock"), |filter, _| {
+            Filter::Not(Box::new(filter))
+        })
+    };
+
+    let params = SearchParams {
+        filter: Some(nested_filter(MAX_NESTING_DEPTH)),
+        ..Default::default()
+    };
+    let mut collector = Vec::new();
+    // An even number of negations
+    assert_eq!(
+        1,
+        crate::track::search::search(
+            &mut fixture.connection,
+            &fixture.collection_uid,
+            &params,
+            &Default::default(),
+            &mut collector,
+        )?
+    );
+
+    let params = SearchParams {
+        filter: Some(nested_filter(MAX_NESTING_DEPTH + 1)),
+        ..Default::default()
+    };
+    assert!(matches!(
+        crate::track::search::search(
+            &mut fixture.connection,
+            &fixture.collection_uid,
+            &params,
+            &Default::default(),
+            &mut collector,
+        ),
+        Err(Error::Input(_))
+    ));
+    assert!(matches!(
+        super::store_query(
+            &mut fixture.connection,
+            &fixture.playlist_uid,
+            Some(nested_filter(MAX_NESTING_DEPTH + 1)),
+            ordering_by_path(),
+        ),
+        Err(Error::Input(_))
+    ));
+    assert!(super::load_query(&mut fixture.connection, &fixture.playlist_uid)?.is_none());
+    Ok(())
+}
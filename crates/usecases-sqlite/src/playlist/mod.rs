@@ -15,6 +15,7 @@ use aoide_usecases as uc;
 
 use crate::{RepoConnection, Result};
 
+pub mod dynamic;
 pub mod entries;
 
 pub fn create(
@@ -82,3 +83,6 @@ pub fn patch_entries(
     let mut repo = RepoConnection::new(connection);
     uc::playlist::entries::patch(&mut repo, entity_header, operations).map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::{anyhow, Result};
+use diesel::Connection as _;
+
+use aoide_core::{
+    audio::{DurationMs, PositionMs},
+    collection::MediaSourceConfig,
+    media::{
+        self,
+        content::{AudioContentMetadata, ContentLink, ContentPathConfig, VirtualFilePathConfig},
+    },
+    playlist::{Entry, Item, TrackItem},
+    util::{clock::OffsetDateTimeMs, url::BaseUrl},
+    Collection, Playlist, PlaylistHeader, Track, TrackUid,
+};
+use aoide_repo::track::ReplaceMode;
+use aoide_repo_sqlite::{initialize_database, run_migrations, DbConnection};
+use aoide_usecases::{
+    playlist::entries::PatchOperation,
+    track::{replace::Params as ReplaceParams, validate_input},
+};
+
+struct Fixture {
+    connection: DbConnection,
+    playlist_header: PlaylistHeader,
+    track_uid: TrackUid,
+}
+
+impl Fixture {
+    fn new(track_duration: DurationMs) -> Result<Self> {
+        let mut connection =
+            DbConnection::establish(":memory:").expect("in-memory database connection");
+        initialize_database(&mut connection)?;
+        run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+        let collection = Collection {
+            title: "Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url: BaseUrl::parse_strict("file:///music/")?,
+                    excluded_paths: vec![],
+                }),
+            },
+        };
+        let collection_uid = crate::collection::create(&mut connection, collection)?
+            .hdr
+            .uid
+            .clone();
+        let media_source = media::Source {
+            collected_at: OffsetDateTimeMs::now_utc(),
+            content: media::Content {
+                link: ContentLink {
+                    path: "track.mp3".to_owned().into(),
+                    rev: None,
+                },
+                r#type: "audio/mpeg".parse().unwrap(),
+                metadata_flags: Default::default(),
+                metadata: AudioContentMetadata {
+                    duration: Some(track_duration),
+                    ..Default::default()
+                }
+                .into(),
+                digest: None,
+                size: None,
+            },
+            artwork: Default::default(),
+        };
+        let (validated_input, _invalidities) =
+            validate_input(Track::new_from_media_source(media_source))?;
+        let params = ReplaceParams {
+            mode: ReplaceMode::CreateOnly,
+            resolve_path_from_url: false,
+            preserve_collected_at: false,
+            update_last_synchronized_rev: false,
+            decode_gigtags: false,
+        };
+        let summary = crate::track::replace::replace_many_by_media_source_content_path(
+            &mut connection,
+            &collection_uid,
+            &params,
+            [validated_input],
+        )?;
+        let track_uid = summary.created[0].hdr.uid.clone();
+        let playlist = Playlist {
+            title: "Set".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            flags: Default::default(),
+        };
+        let playlist_header = super::create(&mut connection, Some(&collection_uid), playlist)?
+            .hdr
+            .clone();
+        Ok(Self {
+            connection,
+            playlist_header,
+            track_uid,
+        })
+    }
+
+    fn append_track_item(&mut self, cue_in: f64, cue_out: f64) -> crate::Result<()> {
+        let entry = Entry {
+            added_at: OffsetDateTimeMs::now_utc(),
+            title: None,
+            notes: None,
+            item: Item::Track(TrackItem {
+                cue_in: Some(PositionMs::new(cue_in)),
+                cue_out: Some(PositionMs::new(cue_out)),
+                ..TrackItem::new(self.track_uid.clone())
+            }),
+        };
+        let (_, entity_with_entries_summary) = super::patch_entries(
+            &mut self.connection,
+            &self.playlist_header,
+            [PatchOperation::Append {
+                entries: vec![entry],
+            }],
+        )?;
+        self.playlist_header = entity_with_entries_summary.entity.hdr.clone();
+        Ok(())
+    }
+}
+
+#[test]
+fn append_track_item_with_cues_within_track_duration() -> Result<()> {
+    let mut fixture = Fixture::new(DurationMs::new(180_000.0))?;
+    fixture.append_track_item(10_000.0, 170_000.0)?;
+    Ok(())
+}
+
+#[test]
+fn reject_track_item_with_cues_beyond_track_duration() -> Result<()> {
+    let mut fixture = Fixture::new(DurationMs::new(180_000.0))?;
+    assert!(matches!(
+        fixture.append_track_item(10_000.0, 190_000.0),
+        Err(crate::Error::Input(_))
+    ));
+    Ok(())
+}
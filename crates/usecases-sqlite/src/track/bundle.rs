@@ -0,0 +1,46 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aoide_core::{CollectionUid, TrackUid};
+use aoide_media_file::io::import::LoadedArtworkImageData;
+use aoide_repo::collection::EntityRepo as _;
+use aoide_repo_sqlite::DbConnection;
+
+use crate::{RepoConnection, Result};
+
+mod uc {
+    pub(super) use aoide_usecases::track::{
+        bundle::{import_bundle, load_exportable_track},
+        replace::ReplaceByContentPathOutcome,
+    };
+}
+
+pub use aoide_usecases::track::bundle::{ExportableTrack, TrackBundle};
+
+/// Load a track of a collection for exporting it as a self-contained bundle.
+///
+/// See also: [`aoide_usecases::track::bundle::load_exportable_track()`]
+pub fn load_exportable_track(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    track_uid: &TrackUid,
+) -> Result<ExportableTrack> {
+    let mut repo = RepoConnection::new(connection);
+    uc::load_exportable_track(&mut repo, collection_uid, track_uid).map_err(Into::into)
+}
+
+/// Import a self-contained bundle into a collection.
+///
+/// See also: [`aoide_usecases::track::bundle::import_bundle()`]
+pub fn import_bundle(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    bundle: TrackBundle,
+) -> Result<(
+    uc::ReplaceByContentPathOutcome,
+    Option<LoadedArtworkImageData>,
+)> {
+    let mut repo = RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(collection_uid)?;
+    uc::import_bundle(&mut repo, collection_id, bundle).map_err(Into::into)
+}
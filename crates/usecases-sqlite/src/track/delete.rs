@@ -0,0 +1,31 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use diesel::Connection as _;
+
+use aoide_core::{CollectionUid, TrackUid};
+use aoide_repo::{
+    collection::EntityRepo as _,
+    track::{CollectionRepo as _, DeleteOutcome},
+};
+use aoide_repo_sqlite::DbConnection;
+
+use crate::{Error, RepoConnection, Result};
+
+/// Delete multiple tracks of a collection by their UIDs
+///
+/// Tracks that are still referenced by playlist entries are not deleted.
+/// All other tracks are deleted atomically in a single transaction.
+/// The outcomes are returned in the requested order.
+pub fn delete_many_in_collection(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    track_uids: &[TrackUid],
+) -> Result<Vec<(TrackUid, DeleteOutcome)>> {
+    connection.transaction::<_, Error, _>(|connection| {
+        let mut repo = RepoConnection::new(connection);
+        let collection_id = repo.resolve_collection_id(collection_uid)?;
+        repo.delete_tracks_by_uids(collection_id, track_uids)
+            .map_err(Into::into)
+    })
+}
@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use diesel::Connection as _;
+
+use aoide_core::{CollectionUid, TrackUid};
+use aoide_core_api::track::edit_tags::{Summary, TagEdit};
+use aoide_repo::collection::EntityRepo as _;
+use aoide_repo_sqlite::DbConnection;
+
+use crate::{Error, RepoConnection, Result};
+
+mod uc {
+    pub(super) use aoide_usecases::track::edit_tags::*;
+}
+
+/// Apply a tag edit to multiple tracks of a collection
+///
+/// Each track is edited atomically in a nested transaction.
+/// Failing to edit a track doesn't affect the other tracks.
+pub fn edit_tags(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    track_uids: &[TrackUid],
+    edit: &TagEdit,
+) -> Result<Summary> {
+    let collection_id = RepoConnection::new(connection).resolve_collection_id(collection_uid)?;
+    let mut summary = Summary::default();
+    for track_uid in track_uids {
+        let edited = connection.transaction::<_, Error, _>(|connection| {
+            let mut repo = RepoConnection::new(connection);
+            uc::edit_track_tags(&mut repo, collection_id, track_uid, edit).map_err(Into::into)
+        });
+        match edited {
+            Ok(Some(entity)) => {
+                summary.updated.push(entity);
+            }
+            Ok(None) => {
+                summary.unchanged.push(track_uid.clone());
+            }
+            Err(err) => {
+                log::warn!("Failed to edit tags of track {track_uid}: {err}");
+                summary.failed.push(track_uid.clone());
+            }
+        }
+    }
+    Ok(summary)
+}
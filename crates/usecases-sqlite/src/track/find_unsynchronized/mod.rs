@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::sync::atomic::AtomicBool;
+
 use aoide_core::CollectionUid;
 use aoide_core_api::{
     track::find_unsynchronized::{Params, UnsynchronizedTrackEntity},
@@ -16,8 +18,12 @@ pub fn find_unsynchronized(
     collection_uid: &CollectionUid,
     params: Params,
     pagination: &Pagination,
+    abort_flag: &AtomicBool,
 ) -> Result<Vec<UnsynchronizedTrackEntity>> {
     let mut repo = RepoConnection::new(connection);
-    uc::find_unsynchronized_with_params(&mut repo, collection_uid, params, pagination)
+    uc::find_unsynchronized_with_params(&mut repo, collection_uid, params, pagination, abort_flag)
         .map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,191 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::anyhow;
+use diesel::Connection as _;
+use url::Url;
+
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPath, ContentPathConfig, VirtualFilePathConfig},
+    util::url::BaseUrl,
+    Collection,
+};
+use aoide_core_api::{media::SyncMode, track::find_unsynchronized::FileStatus};
+use aoide_media_file::io::import::ImportTrackConfig;
+use aoide_repo::track::ReplaceMode;
+use aoide_repo_sqlite::{initialize_database, run_migrations};
+use aoide_usecases::track::{
+    import_and_replace::Params as ImportAndReplaceParams, replace::Params as ReplaceParams,
+    validate_input,
+};
+
+use super::*;
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+const PRESENT_CONTENT_PATH: &str = "present.mp3";
+
+const MISSING_CONTENT_PATH: &str = "missing.mp3";
+
+struct Fixture {
+    root_dir: tempfile::TempDir,
+    connection: DbConnection,
+    collection_uid: CollectionUid,
+}
+
+impl Fixture {
+    fn new() -> anyhow::Result<Self> {
+        let root_dir = tempfile::tempdir()?;
+        for content_path in [PRESENT_CONTENT_PATH, MISSING_CONTENT_PATH] {
+            std::fs::copy(FIXTURE_FILE_PATH, root_dir.path().join(content_path))?;
+        }
+        let mut connection =
+            DbConnection::establish(":memory:").expect("in-memory database connection");
+        initialize_database(&mut connection)?;
+        run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+        let root_url = Url::from_directory_path(root_dir.path())
+            .map_err(|()| anyhow!("invalid root directory"))?;
+        let collection = Collection {
+            title: "Test Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url: BaseUrl::parse_strict(root_url.as_str())?,
+                    excluded_paths: vec![],
+                }),
+            },
+        };
+        let collection_uid = crate::collection::create(&mut connection, collection)?
+            .hdr
+            .uid
+            .clone();
+        Ok(Self {
+            root_dir,
+            connection,
+            collection_uid,
+        })
+    }
+
+    fn import_tracks(&mut self) -> anyhow::Result<()> {
+        let params = ImportAndReplaceParams {
+            sync_mode: SyncMode::Always,
+            import_config: ImportTrackConfig::default(),
+            replace_mode: ReplaceMode::UpdateOrCreate,
+            content_digest_workers: None,
+            force: false,
+        };
+        let outcome = crate::track::import_and_replace::import_and_replace_many_by_local_file_path(
+            &mut self.connection,
+            &self.collection_uid,
+            [
+                ContentPath::from(PRESENT_CONTENT_PATH),
+                ContentPath::from(MISSING_CONTENT_PATH),
+            ],
+            2,
+            &params,
+            &std::convert::identity,
+            &AtomicBool::new(false),
+        )?;
+        assert_eq!(2, outcome.summary.created.len());
+        Ok(())
+    }
+
+    /// Modify all tracks without synchronizing them with their files.
+    fn modify_tracks(&mut self) -> anyhow::Result<()> {
+        let mut tracks = Vec::new();
+        crate::track::search::search(
+            &mut self.connection,
+            &self.collection_uid,
+            &Default::default(),
+            &Pagination::default(),
+            &mut tracks,
+        )?;
+        let mut validated_inputs = Vec::with_capacity(tracks.len());
+        for (_, entity) in tracks {
+            let mut track = entity.raw.body.track;
+            track.set_track_title("Modified");
+            let (validated_input, _invalidities) = validate_input(track)?;
+            validated_inputs.push(validated_input);
+        }
+        let params = ReplaceParams {
+            mode: ReplaceMode::UpdateOnly,
+            resolve_path_from_url: false,
+            preserve_collected_at: true,
+            update_last_synchronized_rev: false,
+            decode_gigtags: false,
+        };
+        let summary = crate::track::replace::replace_many_by_media_source_content_path(
+            &mut self.connection,
+            &self.collection_uid,
+            &params,
+            validated_inputs,
+        )?;
+        assert_eq!(2, summary.updated.len());
+        Ok(())
+    }
+
+    fn find_unsynchronized(
+        &mut self,
+        check_file_exists: bool,
+    ) -> anyhow::Result<Vec<(String, Option<FileStatus>)>> {
+        let params = Params {
+            resolve_url_from_content_path: None,
+            content_path_predicate: None,
+            check_file_exists,
+        };
+        let mut unsynchronized = find_unsynchronized(
+            &mut self.connection,
+            &self.collection_uid,
+            params,
+            &Pagination::default(),
+            &AtomicBool::new(false),
+        )?
+        .into_iter()
+        .map(|entity| {
+            let track = entity.raw.body;
+            (track.content_link.path.into(), track.file_status)
+        })
+        .collect::<Vec<(String, _)>>();
+        unsynchronized.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        Ok(unsynchronized)
+    }
+}
+
+#[test]
+fn check_if_files_of_unsynchronized_tracks_exist() -> anyhow::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.import_tracks()?;
+    assert!(fixture.find_unsynchronized(true)?.is_empty());
+
+    fixture.modify_tracks()?;
+    std::fs::remove_file(fixture.root_dir.path().join(MISSING_CONTENT_PATH))?;
+
+    assert_eq!(
+        vec![
+            (MISSING_CONTENT_PATH.to_owned(), Some(FileStatus::Missing)),
+            (PRESENT_CONTENT_PATH.to_owned(), Some(FileStatus::Present)),
+        ],
+        fixture.find_unsynchronized(true)?
+    );
+    Ok(())
+}
+
+#[test]
+fn skip_checking_if_files_exist_unless_requested() -> anyhow::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.import_tracks()?;
+    fixture.modify_tracks()?;
+    std::fs::remove_file(fixture.root_dir.path().join(MISSING_CONTENT_PATH))?;
+
+    assert_eq!(
+        vec![
+            (MISSING_CONTENT_PATH.to_owned(), None),
+            (PRESENT_CONTENT_PATH.to_owned(), None),
+        ],
+        fixture.find_unsynchronized(false)?
+    );
+    Ok(())
+}
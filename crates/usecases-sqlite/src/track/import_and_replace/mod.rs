@@ -63,3 +63,6 @@ where
     )
     .map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,300 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{num::NonZeroUsize, sync::atomic::AtomicBool, time::Duration};
+
+use anyhow::anyhow;
+use diesel::{Connection as _, RunQueryDsl as _};
+use url::Url;
+
+use aoide_core::{
+    audio::DurationMs,
+    collection::MediaSourceConfig,
+    media::{
+        content::{
+            AudioContentMetadata, ContentLink, ContentMetadata, ContentMetadataFlags,
+            ContentPathConfig, VirtualFilePathConfig,
+        },
+        Content, Source,
+    },
+    util::{clock::OffsetDateTimeMs, url::BaseUrl},
+    Collection,
+};
+use aoide_core_api::{media::SyncMode, Pagination};
+use aoide_media_file::{
+    io::{
+        export::{export_track_to_file, ExportTrackConfig},
+        import::ImportTrackConfig,
+    },
+    util::audio_stream::audio_stream_digest,
+};
+use aoide_repo::track::ReplaceMode;
+use aoide_repo_sqlite::{initialize_database, run_migrations};
+use aoide_usecases::track::{replace::Params as ReplaceParams, validate_input};
+
+use super::*;
+
+const FIXTURE_FILE_PATH: &str = "../media-file/tests/assets/empty.mp3";
+
+const CONTENT_PATH: &str = "track.mp3";
+
+const IMPORTED_TITLE: &str = "Imported";
+
+/// Write a file tag with a title into the fixture file.
+///
+/// Content metadata is only imported from tagged files.
+fn write_file_tag(file_path: &std::path::Path) -> anyhow::Result<()> {
+    let mut track = Track::new_from_media_source(Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: Content {
+            link: ContentLink {
+                path: ContentPath::from(CONTENT_PATH),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse()?,
+            metadata_flags: Default::default(),
+            metadata: AudioContentMetadata::default().into(),
+            digest: None,
+            size: None,
+        },
+        artwork: None,
+    });
+    track.set_track_title(IMPORTED_TITLE);
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(file_path)?;
+    export_track_to_file(
+        &mut file,
+        None,
+        &ExportTrackConfig::default(),
+        &mut track,
+        None,
+    )?;
+    Ok(())
+}
+
+struct Fixture {
+    root_dir: tempfile::TempDir,
+    connection: DbConnection,
+    collection_uid: CollectionUid,
+}
+
+impl Fixture {
+    fn new() -> anyhow::Result<Self> {
+        let root_dir = tempfile::tempdir()?;
+        let file_path = root_dir.path().join(CONTENT_PATH);
+        std::fs::copy(FIXTURE_FILE_PATH, &file_path)?;
+        write_file_tag(&file_path)?;
+        let mut connection =
+            DbConnection::establish(":memory:").expect("in-memory database connection");
+        initialize_database(&mut connection)?;
+        run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+        let root_url = Url::from_directory_path(root_dir.path())
+            .map_err(|()| anyhow!("invalid root directory"))?;
+        let collection = Collection {
+            title: "Test Collection".into(),
+            notes: None,
+            kind: None,
+            color: None,
+            media_source_config: MediaSourceConfig {
+                content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                    root_url: BaseUrl::parse_strict(root_url.as_str())?,
+                    excluded_paths: vec![],
+                }),
+            },
+        };
+        let collection_uid = crate::collection::create(&mut connection, collection)?
+            .hdr
+            .uid
+            .clone();
+        Ok(Self {
+            root_dir,
+            connection,
+            collection_uid,
+        })
+    }
+
+    fn reimport(&mut self, force: bool) -> anyhow::Result<uc::Outcome> {
+        self.reimport_with_content_digest_workers(force, None)
+    }
+
+    fn reimport_with_content_digest_workers(
+        &mut self,
+        force: bool,
+        content_digest_workers: Option<NonZeroUsize>,
+    ) -> anyhow::Result<uc::Outcome> {
+        let params = uc::Params {
+            sync_mode: SyncMode::Always,
+            import_config: ImportTrackConfig::default(),
+            replace_mode: ReplaceMode::UpdateOrCreate,
+            content_digest_workers,
+            force,
+        };
+        let outcome = import_and_replace_many_by_local_file_path(
+            &mut self.connection,
+            &self.collection_uid,
+            [ContentPath::from(CONTENT_PATH)],
+            1,
+            &params,
+            &std::convert::identity,
+            &AtomicBool::new(false),
+        )?;
+        Ok(outcome)
+    }
+
+    fn load_track(&mut self) -> anyhow::Result<Track> {
+        let mut tracks = Vec::new();
+        crate::track::search::search(
+            &mut self.connection,
+            &self.collection_uid,
+            &Default::default(),
+            &Pagination::default(),
+            &mut tracks,
+        )?;
+        assert_eq!(1, tracks.len());
+        let (_, entity) = tracks.pop().unwrap();
+        Ok(entity.raw.body.track)
+    }
+
+    fn curate_track(&mut self, modify_track: impl FnOnce(&mut Track)) -> anyhow::Result<()> {
+        let mut track = self.load_track()?;
+        modify_track(&mut track);
+        let (validated_input, _invalidities) = validate_input(track)?;
+        let params = ReplaceParams {
+            mode: ReplaceMode::UpdateOnly,
+            resolve_path_from_url: false,
+            preserve_collected_at: true,
+            update_last_synchronized_rev: false,
+            decode_gigtags: false,
+        };
+        let summary = crate::track::replace::replace_many_by_media_source_content_path(
+            &mut self.connection,
+            &self.collection_uid,
+            &params,
+            [validated_input],
+        )?;
+        assert_eq!(1, summary.updated.len());
+        Ok(())
+    }
+}
+
+fn content_duration(track: &Track) -> Option<DurationMs> {
+    let ContentMetadata::Audio(audio) = &track.media_source.content.metadata;
+    audio.duration
+}
+
+#[test]
+fn preserve_locked_metadata_unless_forced() -> anyhow::Result<()> {
+    let mut fixture = Fixture::new()?;
+    let outcome = fixture.reimport(false)?;
+    assert_eq!(1, outcome.summary.created.len());
+    assert_eq!(Some(IMPORTED_TITLE), fixture.load_track()?.track_title());
+
+    fixture.curate_track(|track| {
+        track.set_track_title("Curated");
+        track.media_source.content.metadata_flags = ContentMetadataFlags::LOCKED;
+    })?;
+
+    let outcome = fixture.reimport(false)?;
+    assert_eq!(
+        vec![ContentPath::from(CONTENT_PATH)],
+        outcome.summary.not_imported
+    );
+    let track = fixture.load_track()?;
+    assert_eq!(Some("Curated"), track.track_title());
+    assert!(track.media_source.content.metadata_flags.is_locked());
+
+    let outcome = fixture.reimport(true)?;
+    assert_eq!(1, outcome.summary.updated.len());
+    let track = fixture.load_track()?;
+    assert_eq!(Some(IMPORTED_TITLE), track.track_title());
+    assert!(!track.media_source.content.metadata_flags.is_locked());
+
+    Ok(())
+}
+
+#[test]
+fn preserve_reliable_metadata_unless_forced() -> anyhow::Result<()> {
+    let mut fixture = Fixture::new()?;
+    fixture.reimport(false)?;
+    let imported_duration = content_duration(&fixture.load_track()?);
+    let reliable_duration = DurationMs::new(123_456.0);
+    assert_ne!(Some(reliable_duration), imported_duration);
+
+    fixture.curate_track(|track| {
+        let ContentMetadata::Audio(audio) = &mut track.media_source.content.metadata;
+        audio.duration = Some(reliable_duration);
+        track.media_source.content.metadata_flags = ContentMetadataFlags::RELIABLE;
+    })?;
+
+    fixture.reimport(false)?;
+    let track = fixture.load_track()?;
+    assert_eq!(Some(reliable_duration), content_duration(&track));
+    let metadata_flags = track.media_source.content.metadata_flags;
+    assert!(metadata_flags.is_reliable());
+    // An update from a reliable source should be considered
+    assert!(metadata_flags.is_stale());
+
+    fixture.reimport(true)?;
+    let track = fixture.load_track()?;
+    assert_eq!(imported_duration, content_duration(&track));
+    assert!(track.media_source.content.metadata_flags.is_unreliable());
+
+    Ok(())
+}
+
+#[test]
+fn reuse_content_digest_of_unmodified_file() -> anyhow::Result<()> {
+    let mut fixture = Fixture::new()?;
+    let content_digest_workers = NonZeroUsize::new(1);
+    fixture.reimport_with_content_digest_workers(false, content_digest_workers)?;
+    let file_path = fixture.root_dir.path().join(CONTENT_PATH);
+    let expected_digest = audio_stream_digest(&mut std::fs::File::open(&file_path)?)?;
+    assert_eq!(
+        Some(expected_digest.as_slice()),
+        fixture.load_track()?.media_source.content.digest.as_deref()
+    );
+
+    // Replace the stored digest with a bogus value that would be
+    // overwritten if the digest was recomputed.
+    let bogus_digest = vec![0xde, 0xad, 0xbe, 0xef];
+    diesel::sql_query("UPDATE media_source SET content_digest=x'deadbeef'")
+        .execute(&mut fixture.connection)?;
+    fixture.reimport_with_content_digest_workers(false, content_digest_workers)?;
+    assert_eq!(
+        Some(bogus_digest.as_slice()),
+        fixture.load_track()?.media_source.content.digest.as_deref()
+    );
+
+    // Rewriting the file with the same size but a more recent modification
+    // time also invalidates the cached digest.
+    let data = std::fs::read(&file_path)?;
+    std::fs::write(&file_path, &data)?;
+    let modified = std::fs::metadata(&file_path)?.modified()?;
+    std::fs::File::options()
+        .write(true)
+        .open(&file_path)?
+        .set_modified(modified + Duration::from_secs(60))?;
+    fixture.reimport_with_content_digest_workers(false, content_digest_workers)?;
+    assert_eq!(
+        Some(expected_digest.as_slice()),
+        fixture.load_track()?.media_source.content.digest.as_deref()
+    );
+
+    // Modifying the file changes its size and invalidates the cached digest.
+    diesel::sql_query("UPDATE media_source SET content_digest=x'deadbeef'")
+        .execute(&mut fixture.connection)?;
+    let mut data = std::fs::read(&file_path)?;
+    data.extend(b"modified");
+    std::fs::write(&file_path, data)?;
+    let expected_digest = audio_stream_digest(&mut std::fs::File::open(&file_path)?)?;
+    assert_ne!(bogus_digest.as_slice(), expected_digest.as_slice());
+    fixture.reimport_with_content_digest_workers(false, content_digest_workers)?;
+    assert_eq!(
+        Some(expected_digest.as_slice()),
+        fixture.load_track()?.media_source.content.digest.as_deref()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Incremental indexing of tracks, e.g. for a full-text search index.
+
+use aoide_core::{CollectionUid, TrackEntity};
+use aoide_repo::{
+    collection::EntityRepo as _,
+    track::{CollectionRepo as _, IndexWatermark, RecordHeader},
+    ReservableRecordCollector,
+};
+use aoide_repo_sqlite::DbConnection;
+
+use crate::{RepoConnection, Result};
+
+/// Load the position up to which the tracks of a collection have been indexed.
+///
+/// Returns `None` if the collection has not been indexed yet.
+pub fn load_index_watermark(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+) -> Result<Option<IndexWatermark>> {
+    let mut repo = RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(collection_uid)?;
+    repo.load_track_index_watermark(collection_id)
+        .map_err(Into::into)
+}
+
+/// Load the next batch of tracks that have been updated after the watermark.
+///
+/// The header of the last collected track is the watermark for loading
+/// the next batch.
+pub fn load_updated_after_index_watermark(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    watermark: Option<&IndexWatermark>,
+    limit: u64,
+    collector: &mut impl ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+) -> Result<usize> {
+    let mut repo = RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(collection_uid)?;
+    repo.load_tracks_updated_after(collection_id, watermark, limit, collector)
+        .map_err(Into::into)
+}
+
+/// Store or reset the position up to which the tracks of a collection have been indexed.
+///
+/// Should be invoked within the same transaction that loaded the indexed
+/// tracks and right before committing the index.
+pub fn store_index_watermark(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    watermark: Option<&IndexWatermark>,
+) -> Result<()> {
+    let mut repo = RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(collection_uid)?;
+    repo.store_track_index_watermark(collection_id, watermark)
+        .map_err(Into::into)
+}
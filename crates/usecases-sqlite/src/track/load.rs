@@ -4,8 +4,8 @@
 use aoide_core::{track::actor::ActorNamesSummarySplitter, CollectionUid, TrackEntity, TrackUid};
 use aoide_repo::{
     collection::EntityRepo as _,
-    track::{ActorRepo as _, EntityRepo as _, RecordHeader},
-    RecordCollector, RepoError,
+    track::{ActorRepo as _, CollectionRepo as _, EntityRepo as _, RecordHeader},
+    RecordCollector, RepoError, ReservableRecordCollector,
 };
 use aoide_repo_sqlite::DbConnection;
 
@@ -28,7 +28,7 @@ pub fn load_many(
             Ok((record_header, entity)) => {
                 collector.collect(record_header, entity);
             }
-            Err(RepoError::NotFound) => {
+            Err(RepoError::NotFound { .. }) => {
                 log::debug!("Track with UID '{entity_uid}' not found");
                 continue;
             }
@@ -40,6 +40,21 @@ pub fn load_many(
     Ok(())
 }
 
+/// Load multiple tracks of a collection in the requested order.
+///
+/// Returns the UIDs of all tracks that could not be found.
+pub fn load_many_in_collection(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    entity_uids: &[TrackUid],
+    collector: &mut impl ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
+) -> Result<Vec<TrackUid>> {
+    let mut repo = RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(collection_uid)?;
+    repo.load_tracks_by_uids(collection_id, entity_uids, collector)
+        .map_err(Into::into)
+}
+
 pub fn load_all_actor_names(
     connection: &mut DbConnection,
     collection_uid: Option<&CollectionUid>,
@@ -1,9 +1,13 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub mod bundle;
+pub mod delete;
+pub mod edit_tags;
 pub mod export_metadata;
 pub mod find_unsynchronized;
 pub mod import_and_replace;
+pub mod index_watermark;
 pub mod load;
 pub mod purge;
 pub mod replace;
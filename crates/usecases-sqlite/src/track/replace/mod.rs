@@ -1,8 +1,9 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core::CollectionUid;
+use aoide_core::{media::content::ContentPath, CollectionUid, Track};
 use aoide_core_api::track::replace::Summary;
+use aoide_repo::collection::EntityRepo as _;
 use aoide_repo_sqlite::DbConnection;
 use aoide_usecases::track::ValidatedInput;
 
@@ -10,7 +11,8 @@ use crate::{RepoConnection, Result};
 
 mod uc {
     pub(super) use aoide_usecases::track::replace::{
-        replace_many_by_media_source_content_path, Params,
+        replace_by_content_path, replace_many_by_media_source_content_path, Params,
+        ReplaceByContentPathOutcome,
     };
 }
 
@@ -29,3 +31,20 @@ pub fn replace_many_by_media_source_content_path(
     )
     .map_err(Into::into)
 }
+
+/// Update the track at the given content path or create it.
+///
+/// See also: [`aoide_usecases::track::replace::replace_by_content_path()`]
+pub fn replace_track_by_content_path(
+    connection: &mut DbConnection,
+    collection_uid: &CollectionUid,
+    content_path: ContentPath<'static>,
+    track: Track,
+) -> Result<uc::ReplaceByContentPathOutcome> {
+    let mut repo = RepoConnection::new(connection);
+    let collection_id = repo.resolve_collection_id(collection_uid)?;
+    uc::replace_by_content_path(&mut repo, collection_id, content_path, track).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::anyhow;
+use diesel::Connection as _;
+
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::{
+        content::{AudioContentMetadata, ContentLink, ContentPathConfig, VirtualFilePathConfig},
+        Content, Source,
+    },
+    util::{clock::OffsetDateTimeMs, url::BaseUrl},
+    Collection,
+};
+use aoide_repo_sqlite::{initialize_database, run_migrations};
+use aoide_usecases::track::replace::ReplaceAction;
+
+use super::*;
+
+const CONTENT_PATH: &str = "artist/track.mp3";
+
+fn establish_connection_with_collection() -> anyhow::Result<(DbConnection, CollectionUid)> {
+    let mut connection =
+        DbConnection::establish(":memory:").expect("in-memory database connection");
+    initialize_database(&mut connection)?;
+    run_migrations(&mut connection).map_err(|err| anyhow!(err))?;
+    let collection = Collection {
+        title: "Test Collection".into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::parse_strict("file:///home/test/music/")?,
+                excluded_paths: vec![],
+            }),
+        },
+    };
+    let collection_uid = crate::collection::create(&mut connection, collection)?
+        .hdr
+        .uid
+        .clone();
+    Ok((connection, collection_uid))
+}
+
+fn new_track(title: &str) -> Track {
+    let mut track = Track::new_from_media_source(Source {
+        collected_at: OffsetDateTimeMs::now_utc(),
+        content: Content {
+            link: ContentLink {
+                // Replaced by the content path argument
+                path: ContentPath::from("ignored.mp3"),
+                rev: None,
+            },
+            r#type: "audio/mpeg".parse().unwrap(),
+            metadata_flags: Default::default(),
+            metadata: AudioContentMetadata::default().into(),
+            digest: None,
+            size: None,
+        },
+        artwork: None,
+    });
+    track.set_track_title(title);
+    track
+}
+
+#[test]
+fn replace_track_by_content_path_creates_updates_or_skips() -> anyhow::Result<()> {
+    let (mut connection, collection_uid) = establish_connection_with_collection()?;
+
+    // Create
+    let created = replace_track_by_content_path(
+        &mut connection,
+        &collection_uid,
+        ContentPath::from(CONTENT_PATH),
+        new_track("Title"),
+    )?;
+    assert_eq!(ReplaceAction::Created, created.action);
+    let created_track = &created.entity.body.track;
+    assert_eq!(
+        CONTENT_PATH,
+        created_track.media_source.content.link.path.as_str()
+    );
+    assert_eq!(Some("Title"), created_track.track_title());
+
+    // No-op if the contents are equivalent
+    let mut equivalent_track = new_track("Title");
+    equivalent_track.media_source.collected_at = OffsetDateTimeMs::from_timestamp_millis(
+        created_track.media_source.collected_at.timestamp_millis() + 1_000,
+    );
+    let unchanged = replace_track_by_content_path(
+        &mut connection,
+        &collection_uid,
+        ContentPath::from(CONTENT_PATH),
+        equivalent_track,
+    )?;
+    assert_eq!(ReplaceAction::Unchanged, unchanged.action);
+    assert_eq!(created.entity.hdr, unchanged.entity.hdr);
+
+    // Update
+    let updated = replace_track_by_content_path(
+        &mut connection,
+        &collection_uid,
+        ContentPath::from(CONTENT_PATH),
+        new_track("New title"),
+    )?;
+    assert_eq!(ReplaceAction::Updated, updated.action);
+    assert_eq!(created.entity.hdr.uid, updated.entity.hdr.uid);
+    assert_eq!(created.entity.hdr.rev.next(), Some(updated.entity.hdr.rev));
+    let updated_track = &updated.entity.body.track;
+    assert_eq!(Some("New title"), updated_track.track_title());
+    // The original collection time stamp is preserved
+    assert_eq!(
+        created_track.media_source.collected_at,
+        updated_track.media_source.collected_at
+    );
+
+    let loaded = crate::track::load::load_one(&mut connection, &updated.entity.hdr.uid)?;
+    assert_eq!(updated.entity.hdr, loaded.hdr);
+    Ok(())
+}
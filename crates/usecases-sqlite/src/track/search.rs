@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Instant;
+
 use aoide_core::{CollectionUid, TrackEntity};
 use aoide_core_api::Pagination;
 use aoide_repo::{track::RecordHeader, ReservableRecordCollector};
@@ -20,7 +22,17 @@ pub fn search(
     pagination: &Pagination,
     collector: &mut impl ReservableRecordCollector<Header = RecordHeader, Record = TrackEntity>,
 ) -> Result<usize> {
+    let span = tracing::info_span!(
+        "search_tracks",
+        %collection_uid,
+        count = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+    );
+    let _entered = span.enter();
+    let started = Instant::now();
     let mut repo = RepoConnection::new(connection);
-    uc::search_with_params(&mut repo, collection_uid, params, pagination, collector)
-        .map_err(Into::into)
+    let count = uc::search_with_params(&mut repo, collection_uid, params, pagination, collector)?;
+    span.record("count", count);
+    span.record("duration_ms", crate::elapsed_millis(started));
+    Ok(count)
 }
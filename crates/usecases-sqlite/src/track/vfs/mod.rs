@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::path::Path;
+use std::{path::Path, time::Instant};
 
 use aoide_core::CollectionUid;
 use aoide_core_api::track::search::Filter;
@@ -19,8 +19,20 @@ pub fn export_files(
     match_files: uc::MatchFiles,
     purge_other_files: bool,
 ) -> Result<uc::ExportTrackFilesOutcome> {
+    let span = tracing::info_span!(
+        "export_files",
+        %collection_uid,
+        exported = tracing::field::Empty,
+        skipped = tracing::field::Empty,
+        failed = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+    );
+    let _entered = span.enter();
+    // The target path may reveal private file system paths.
+    tracing::debug!(target_root_path = %target_root_path.display(), "Exporting files");
+    let started = Instant::now();
     let mut repo = RepoConnection::new(connection);
-    uc::export_files(
+    let outcome = uc::export_files(
         &mut repo,
         collection_uid,
         filter,
@@ -28,6 +40,10 @@ pub fn export_files(
         target_root_path,
         match_files,
         purge_other_files,
-    )
-    .map_err(Into::into)
+    )?;
+    span.record("exported", outcome.exported);
+    span.record("skipped", outcome.skipped);
+    span.record("failed", outcome.failed.len());
+    span.record("duration_ms", crate::elapsed_millis(started));
+    Ok(outcome)
 }
@@ -8,8 +8,11 @@ use aoide_core::{
     collection::EntityHeader as CollectionEntityHeader, util::clock::OffsetDateTimeMs, Collection,
     CollectionEntity, CollectionUid,
 };
-use aoide_core_api::collection::{EntityWithSummary, LoadScope};
-use aoide_repo::collection::{EntityRepo, RecordHeader};
+use aoide_core_api::collection::{EntityWithSummary, LoadScope, Patch};
+use aoide_repo::{
+    collection::{EntityRepo, RecordHeader},
+    EntityContext, EntityKind, RepoError,
+};
 
 use crate::{Error, InputResult, Result};
 
@@ -57,6 +60,33 @@ pub fn store_updated_entity(repo: &mut impl EntityRepo, entity: &CollectionEntit
     Ok(())
 }
 
+/// Apply a partial update
+///
+/// The revision is only bumped if the patch actually modifies
+/// the collection. Otherwise the current entity is returned as is.
+pub fn patch(
+    repo: &mut impl EntityRepo,
+    hdr: CollectionEntityHeader,
+    patch: Patch,
+) -> Result<CollectionEntity> {
+    let id = repo.resolve_collection_id(&hdr.uid)?;
+    let (_, entity) = repo.load_collection_entity(id)?;
+    if entity.hdr.rev != hdr.rev {
+        return Err(RepoError::Conflict {
+            entity: Some(EntityContext::new(EntityKind::Collection, &hdr.uid)),
+            current_rev: entity.hdr.rev,
+        }
+        .into());
+    }
+    let (_, mut collection) = entity.into();
+    if !patch.apply(&mut collection) {
+        return Ok(CollectionEntity::new(hdr, collection));
+    }
+    let updated_entity = update_entity(hdr, collection)?;
+    store_updated_entity(repo, &updated_entity)?;
+    Ok(updated_entity)
+}
+
 pub fn load_one(
     repo: &mut impl EntityRepo,
     collection_uid: &CollectionUid,
@@ -6,13 +6,22 @@ use std::ops::Not as _;
 use anyhow::anyhow;
 
 use aoide_core::{
-    media::content::{resolver::vfs::RemappingVfsResolver, ContentPath, ContentPathKind},
+    media::content::{
+        resolver::{
+            vfs::{RemappingVfsResolver, VfsResolver},
+            ContentPathResolver as _,
+        },
+        ContentPath, ContentPathConfig, ContentPathKind,
+    },
     util::url::BaseUrl,
-    CollectionUid,
+    CollectionEntity, CollectionUid,
+};
+use aoide_repo::{
+    collection::{EntityRepo, RecordId},
+    media::source::CollectionRepo as MediaSourceCollectionRepo,
 };
-use aoide_repo::collection::{EntityRepo, RecordId};
 
-use crate::{Error, Result};
+use crate::{Error, InputError, Result};
 
 #[derive(Debug, Clone)]
 struct RepoContextProps {
@@ -142,3 +151,87 @@ impl ContentPathContext {
         })
     }
 }
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RebaseRootUrlParams {
+    /// Check if the files exist at their new location.
+    pub check_file_exists: bool,
+
+    /// Only report unresolved content paths without modifying
+    /// the collection.
+    pub dry_run: bool,
+}
+
+#[derive(Debug)]
+pub struct RebaseRootUrlOutcome {
+    /// The updated collection.
+    ///
+    /// `None` for a dry run or if the root URL didn't change.
+    pub updated_entity: Option<CollectionEntity>,
+
+    /// The number of content paths that resolve under the new root URL.
+    pub resolved_count: usize,
+
+    /// Content paths that could not be resolved under the new root URL.
+    pub unresolved_paths: Vec<ContentPath<'static>>,
+}
+
+/// Change the root URL of a collection with virtual file paths
+///
+/// The relative content paths of all media sources are preserved,
+/// i.e. they are implicitly rebased onto the new root URL. Content
+/// paths that don't resolve under the new root URL are reported.
+pub fn rebase_root_url<Repo>(
+    repo: &mut Repo,
+    collection_uid: &CollectionUid,
+    new_root_url: BaseUrl,
+    params: &RebaseRootUrlParams,
+) -> Result<RebaseRootUrlOutcome>
+where
+    Repo: EntityRepo + MediaSourceCollectionRepo,
+{
+    let RebaseRootUrlParams {
+        check_file_exists,
+        dry_run,
+    } = *params;
+    if !VfsResolver::is_valid_root_url(&new_root_url) {
+        return Err(InputError(anyhow!("invalid root URL: {new_root_url}")).into());
+    }
+    let record_id = repo.resolve_collection_id(collection_uid)?;
+    let (_, entity) = repo.load_collection_entity(record_id)?;
+    let (hdr, mut collection) = entity.into();
+    let ContentPathConfig::VirtualFilePath(vfs_config) =
+        &mut collection.media_source_config.content_path
+    else {
+        let kind = collection.media_source_config.content_path.kind();
+        return Err(Error::Other(anyhow!(
+            "unsupported content path kind: {kind:?}"
+        )));
+    };
+    let resolver = VfsResolver::with_root_url(new_root_url.clone());
+    let mut resolved_count = 0;
+    let mut unresolved_paths = Vec::new();
+    for content_path in repo.load_media_source_content_paths(record_id)? {
+        let resolved = resolver.resolve_url_from_path(&content_path).is_ok()
+            && (!check_file_exists || resolver.build_file_path(&content_path).is_file());
+        if resolved {
+            resolved_count += 1;
+        } else {
+            log::debug!("Unresolved content path under {new_root_url}: {content_path}");
+            unresolved_paths.push(content_path);
+        }
+    }
+    let updated_entity = if dry_run || vfs_config.root_url == new_root_url {
+        None
+    } else {
+        vfs_config.root_url = new_root_url;
+        let updated_entity = super::update_entity(hdr, collection)?;
+        super::store_updated_entity(repo, &updated_entity)?;
+        Some(updated_entity)
+    };
+    Ok(RebaseRootUrlOutcome {
+        updated_entity,
+        resolved_count,
+        unresolved_paths,
+    })
+}
@@ -11,6 +11,8 @@ pub mod collection;
 pub mod playlist;
 pub mod track;
 
+mod worker_pool;
+
 #[cfg(feature = "media-file")]
 use aoide_media_file::Error as MediaFileError;
 
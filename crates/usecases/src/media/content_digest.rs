@@ -0,0 +1,215 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    num::NonZeroUsize,
+    path::Path,
+    sync::{atomic::AtomicBool, mpsc},
+};
+
+use aoide_core::media::content::{ContentPath, ContentRevision};
+use aoide_media_file::util::audio_stream::audio_stream_digest;
+use aoide_repo::{
+    media::source::{CollectionRepo as MediaSourceCollectionRepo, ContentDigestRecord},
+    CollectionId, RepoResult,
+};
+
+use crate::worker_pool::with_worker_pool;
+
+/// Compute the digest of the audio content of a file.
+///
+/// Returns `None` if the file is not a regular file or could not be read.
+#[must_use]
+pub fn content_digest_from_file_path(file_path: &Path) -> Option<Vec<u8>> {
+    if !file_path.is_file() {
+        return None;
+    }
+    let digest = File::open(file_path)
+        .map_err(Into::into)
+        .and_then(|mut file| audio_stream_digest(&mut file));
+    match digest {
+        Ok(digest) => Some(digest.to_vec()),
+        Err(err) => {
+            log::warn!(
+                "Failed to compute content digest of {file_path}: {err}",
+                file_path = file_path.display()
+            );
+            None
+        }
+    }
+}
+
+/// The modification time and size of a file.
+///
+/// The content digest of a file is assumed to be unchanged as long as
+/// its fingerprint is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileFingerprint {
+    pub rev: ContentRevision,
+    pub size: u64,
+}
+
+impl FileFingerprint {
+    /// Read the fingerprint from the metadata of a file.
+    ///
+    /// Returns `None` if the metadata is not available or if the
+    /// file has no valid modification time.
+    #[must_use]
+    pub fn from_file_path(file_path: &Path) -> Option<Self> {
+        let metadata = file_path.metadata().ok()?;
+        let rev = ContentRevision::try_from_file_time(metadata.modified().ok()?).ok()??;
+        let size = metadata.len();
+        Some(Self { rev, size })
+    }
+}
+
+/// Previously computed content digests of files, keyed by content path.
+///
+/// A cached digest is only reused if the fingerprint of the file
+/// still matches.
+#[derive(Debug, Default)]
+pub struct ContentDigestCache {
+    entries: HashMap<String, (FileFingerprint, Vec<u8>)>,
+}
+
+impl ContentDigestCache {
+    /// Load the stored content digests of media sources.
+    pub fn load_from_repo<Repo>(
+        repo: &mut Repo,
+        collection_id: CollectionId,
+        content_paths: &[&ContentPath<'_>],
+    ) -> RepoResult<Self>
+    where
+        Repo: MediaSourceCollectionRepo,
+    {
+        let records = repo.load_media_source_content_digests(collection_id, content_paths)?;
+        Ok(records.into_iter().collect())
+    }
+
+    pub fn insert(
+        &mut self,
+        content_path: &ContentPath<'_>,
+        fingerprint: FileFingerprint,
+        digest: Vec<u8>,
+    ) {
+        self.entries
+            .insert(content_path.as_str().to_owned(), (fingerprint, digest));
+    }
+
+    /// Get the cached digest of a file if its fingerprint matches.
+    #[must_use]
+    pub fn get(
+        &self,
+        content_path: &ContentPath<'_>,
+        fingerprint: &FileFingerprint,
+    ) -> Option<&[u8]> {
+        self.entries
+            .get(content_path.as_str())
+            .filter(|(cached_fingerprint, _)| cached_fingerprint == fingerprint)
+            .map(|(_, digest)| digest.as_slice())
+    }
+
+    /// Get the cached digest of a file or compute it if the file
+    /// has been modified since.
+    #[must_use]
+    pub fn get_or_compute(
+        &self,
+        content_path: &ContentPath<'_>,
+        file_path: &Path,
+    ) -> Option<Vec<u8>> {
+        if let Some(digest) = FileFingerprint::from_file_path(file_path)
+            .and_then(|fingerprint| self.get(content_path, &fingerprint))
+        {
+            log::debug!("Reusing cached content digest of {content_path}");
+            return Some(digest.to_vec());
+        }
+        content_digest_from_file_path(file_path)
+    }
+}
+
+impl FromIterator<ContentDigestRecord> for ContentDigestCache {
+    fn from_iter<I: IntoIterator<Item = ContentDigestRecord>>(iter: I) -> Self {
+        let mut cache = Self::default();
+        for record in iter {
+            let ContentDigestRecord {
+                content_path,
+                content_rev,
+                content_size,
+                content_digest,
+            } = record;
+            let fingerprint = FileFingerprint {
+                rev: content_rev,
+                size: content_size,
+            };
+            cache.insert(&content_path, fingerprint, content_digest);
+        }
+        cache
+    }
+}
+
+/// Content digests of files that are computed in the background.
+#[derive(Debug)]
+pub struct ContentDigests {
+    receiver: mpsc::Receiver<(usize, Option<Vec<u8>>)>,
+    received: HashMap<usize, Option<Vec<u8>>>,
+    next_index: usize,
+}
+
+impl ContentDigests {
+    /// Wait for the digest of the next file.
+    ///
+    /// The digests are returned in the order of the file paths.
+    /// Returns `None` if the digest could not be computed or
+    /// if the computation has been aborted.
+    pub fn next_digest(&mut self) -> Option<Vec<u8>> {
+        let index = self.next_index;
+        self.next_index += 1;
+        if let Some(digest) = self.received.remove(&index) {
+            return digest;
+        }
+        // Fails after all workers have finished or stopped
+        while let Ok((received_index, digest)) = self.receiver.recv() {
+            if received_index == index {
+                return digest;
+            }
+            self.received.insert(received_index, digest);
+        }
+        None
+    }
+}
+
+/// Compute the content digests of files on a bounded number of worker threads.
+///
+/// The digests are consumed in order by `consume_fn` on the current thread while
+/// the workers continue with subsequent files. All workers stop when `abort_flag`
+/// is set or when `consume_fn` returns.
+///
+/// Digests of unmodified files are taken from `cache` instead of
+/// recomputing them.
+///
+/// No digests are computed if `worker_count` is `None`.
+pub fn with_content_digests<T>(
+    files: &[(&ContentPath<'_>, &Path)],
+    cache: &ContentDigestCache,
+    worker_count: Option<NonZeroUsize>,
+    abort_flag: &AtomicBool,
+    consume_fn: impl FnOnce(&mut ContentDigests) -> T,
+) -> T {
+    let worker_count = worker_count.map_or(0, NonZeroUsize::get);
+    with_worker_pool(
+        files,
+        worker_count,
+        abort_flag,
+        |(content_path, file_path)| Some(cache.get_or_compute(content_path, file_path)),
+        |receiver| {
+            let mut content_digests = ContentDigests {
+                receiver,
+                received: HashMap::new(),
+                next_index: 0,
+            };
+            consume_fn(&mut content_digests)
+        },
+    )
+}
@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::io::BufReader;
+use std::{io::BufReader, path::Path};
 
 use aoide_core::{
     media::content::{resolver::vfs::VfsResolver, ContentLink, ContentPath, ContentRevision},
@@ -9,7 +9,7 @@ use aoide_core::{
 };
 use aoide_core_api::media::SyncMode;
 use aoide_media_file::{
-    fs::open_file_for_reading,
+    fs::{artwork::import_folder_artwork_into_track, open_file_for_reading},
     io::{
         export::{export_track_to_file_path, ExportTrackConfig},
         import::{import_into_track, ImportTrack, ImportTrackConfig, Issues, Reader},
@@ -19,6 +19,7 @@ use aoide_media_file::{
 
 use crate::Result;
 
+pub mod content_digest;
 pub mod source;
 pub mod tracker;
 
@@ -162,8 +163,10 @@ pub fn import_track_from_file_path(
         rev: new_content_rev,
     };
     let mut track = import_track.with_content(content_link, content_type);
+    track.media_source.content.size = file.metadata().ok().map(|metadata| metadata.len());
     let mut reader: Box<dyn Reader> = Box::new(BufReader::new(file));
-    let issues = import_into_track(&mut reader, config, &mut track)?;
+    let mut issues = import_into_track(&mut reader, config, &mut track)?;
+    import_optional_file_metadata_into_track(&canonical_path, config, &mut track, &mut issues);
     if issues.is_empty() {
         log::debug!(
             "Finished import of file \"{canonical_path}\" without issues",
@@ -179,6 +182,27 @@ pub fn import_track_from_file_path(
     Ok(ImportTrackFromFileOutcome::Imported { track, issues })
 }
 
+/// Import the optional metadata that is not stored in the file tags.
+///
+/// Each step is only performed if enabled by the corresponding flag
+/// of the `config`.
+fn import_optional_file_metadata_into_track(
+    file_path: &Path,
+    config: &ImportTrackConfig,
+    track: &mut Track,
+    issues: &mut Issues,
+) {
+    import_folder_artwork_into_track(file_path, config, track, issues);
+    #[cfg(feature = "decoded-duration")]
+    aoide_media_file::fs::duration::import_decoded_duration_into_track(
+        file_path, config, track, issues,
+    );
+    #[cfg(feature = "waveform")]
+    aoide_media_file::fs::waveform::import_waveform_overview_into_track(
+        file_path, config, track, issues,
+    );
+}
+
 /// Export track metadata into file tags.
 pub fn export_track_metadata_into_file(
     track: &mut Track,
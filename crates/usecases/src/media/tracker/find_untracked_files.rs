@@ -16,8 +16,9 @@ use aoide_core::{
     CollectionUid,
 };
 use aoide_core_api::media::tracker::{
-    find_untracked_files::Outcome, Completion, FsTraversalDirectoriesProgress,
-    FsTraversalEntriesProgress, FsTraversalParams, FsTraversalProgress,
+    find_untracked_files::{Outcome, Params},
+    Completion, FsTraversalDirectoriesProgress, FsTraversalEntriesProgress, FsTraversalProgress,
+    ProgressPhase,
 };
 use aoide_media_file::fs::visit::{self, url_from_walkdir_entry};
 use aoide_repo::{
@@ -34,8 +35,8 @@ pub struct ProgressEvent {
     pub progress: FsTraversalProgress,
 }
 
-impl From<visit::ProgressEvent> for ProgressEvent {
-    fn from(from: visit::ProgressEvent) -> Self {
+impl From<&visit::ProgressEvent> for ProgressEvent {
+    fn from(from: &visit::ProgressEvent) -> Self {
         let visit::ProgressEvent {
             started_at,
             status,
@@ -51,36 +52,93 @@ impl From<visit::ProgressEvent> for ProgressEvent {
                             finished: entries_finished,
                         },
                 },
+            skipped_entries: _,
         } = from;
         Self {
             elapsed: started_at.elapsed(),
-            status,
+            status: *status,
             progress: FsTraversalProgress {
+                phase: ProgressPhase::ScanningDirectories,
+                current_path: None,
                 directories: FsTraversalDirectoriesProgress {
-                    finished: directories_finished,
+                    finished: *directories_finished,
                 },
                 entries: FsTraversalEntriesProgress {
-                    skipped: entries_skipped,
-                    finished: entries_finished,
+                    skipped: *entries_skipped,
+                    finished: *entries_finished,
                 },
             },
         }
     }
 }
 
+/// Match a name against a pattern with wildcards, ignoring ASCII case.
+///
+/// The wildcard `*` matches any sequence of characters and `?`
+/// matches a single character.
+fn matches_wildcard_pattern(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let mut pattern_pos = 0;
+    let mut name_pos = 0;
+    // Positions for resuming after the last `*` has been visited
+    let mut resume_pos = None;
+    while name_pos < name.len() {
+        match pattern.get(pattern_pos) {
+            Some('*') => {
+                resume_pos = Some((pattern_pos, name_pos));
+                pattern_pos += 1;
+            }
+            Some(next) if *next == '?' || next.eq_ignore_ascii_case(&name[name_pos]) => {
+                pattern_pos += 1;
+                name_pos += 1;
+            }
+            _ => {
+                let Some((star_pattern_pos, star_name_pos)) = resume_pos else {
+                    return false;
+                };
+                // Let the last `*` consume one more character
+                pattern_pos = star_pattern_pos + 1;
+                name_pos = star_name_pos + 1;
+                resume_pos = Some((star_pattern_pos, name_pos));
+            }
+        }
+    }
+    pattern[pattern_pos..].iter().all(|next| *next == '*')
+}
+
+/// Check if any component of the path matches any of the ignore patterns.
+fn is_ignored_content_path(ignore_patterns: &[&str], content_path: &ContentPath<'_>) -> bool {
+    content_path
+        .as_str()
+        .split('/')
+        .filter(|name| !name.is_empty())
+        .any(|name| {
+            ignore_patterns
+                .iter()
+                .any(|pattern| matches_wildcard_pattern(pattern, name))
+        })
+}
+
 struct AncestorVisitor<'r, Repo> {
     collection_id: CollectionId,
     content_path_resolver: &'r VfsResolver,
+    ignore_patterns: &'r [&'r str],
     content_paths: Vec<ContentPath<'static>>,
     _repo_marker: PhantomData<Repo>,
 }
 
 impl<'r, Repo> AncestorVisitor<'r, Repo> {
     #[must_use]
-    const fn new(collection_id: CollectionId, content_path_resolver: &'r VfsResolver) -> Self {
+    const fn new(
+        collection_id: CollectionId,
+        content_path_resolver: &'r VfsResolver,
+        ignore_patterns: &'r [&'r str],
+    ) -> Self {
         Self {
             collection_id,
             content_path_resolver,
+            ignore_patterns,
             content_paths: Vec::new(),
             _repo_marker: PhantomData,
         }
@@ -106,12 +164,16 @@ where
             // Skip directories.
             return Ok(());
         }
+        if is_ignored_content_path(self.ignore_patterns, &content_path) {
+            log::debug!("Ignoring {content_path}");
+            return Ok(());
+        }
         match repo.media_tracker_resolve_source_id_synchronized_at_by_content_path(
             self.collection_id,
             &content_path,
         ) {
             Ok(_) => Ok(()),
-            Err(RepoError::NotFound) => {
+            Err(RepoError::NotFound { .. }) => {
                 self.content_paths.push(content_path);
                 Ok(())
             }
@@ -127,15 +189,21 @@ where
 struct DirectoryVisitor<'a, Repo> {
     collection_id: CollectionId,
     resolver: &'a RemappingVfsResolver,
+    ignore_patterns: &'a [&'a str],
     content_paths: Vec<ContentPath<'static>>,
     repo: PhantomData<Repo>,
 }
 
 impl<'a, Repo> DirectoryVisitor<'a, Repo> {
-    fn new(collection_id: CollectionId, resolver: &'a RemappingVfsResolver) -> Self {
+    fn new(
+        collection_id: CollectionId,
+        resolver: &'a RemappingVfsResolver,
+        ignore_patterns: &'a [&'a str],
+    ) -> Self {
         Self {
             collection_id,
             resolver,
+            ignore_patterns,
             content_paths: Default::default(),
             repo: PhantomData,
         }
@@ -152,7 +220,11 @@ impl<'a, Repo> aoide_media_file::fs::visit::DirectoryVisitor for DirectoryVisito
     type AfterAncestorFinishedError = anyhow::Error;
 
     fn new_ancestor_visitor(&mut self, _dir_entry: &walkdir::DirEntry) -> Self::AncestorVisitor {
-        AncestorVisitor::new(self.collection_id, self.resolver.canonical_resolver())
+        AncestorVisitor::new(
+            self.collection_id,
+            self.resolver.canonical_resolver(),
+            self.ignore_patterns,
+        )
     }
 
     fn after_ancestor_finished(
@@ -172,14 +244,16 @@ pub fn visit_directories<
 >(
     repo: &mut Repo,
     collection_uid: &CollectionUid,
-    params: &FsTraversalParams,
+    params: &Params,
     report_progress_fn: &mut ReportProgressFn,
     abort_flag: &AtomicBool,
 ) -> Result<Outcome> {
-    let FsTraversalParams {
+    let ignore_patterns = params.ignore_patterns();
+    let Params {
         root_url,
         excluded_paths,
         max_depth,
+        ignore_patterns: _,
     } = params;
     let collection_ctx = RepoContext::resolve(repo, collection_uid, root_url.as_ref())?;
     let Some(resolver) = &collection_ctx.content_path.resolver else {
@@ -188,7 +262,8 @@ pub fn visit_directories<
             "unsupported path kind: {path_kind:?}"
         )));
     };
-    let mut directory_visitor = DirectoryVisitor::new(collection_ctx.record_id, resolver);
+    let mut directory_visitor =
+        DirectoryVisitor::new(collection_ctx.record_id, resolver, &ignore_patterns);
     let root_file_path = resolver.build_file_path(resolver.root_path());
     let excluded_paths = excluded_paths
         .iter()
@@ -203,12 +278,12 @@ pub fn visit_directories<
         &mut directory_visitor,
         &mut |progress_event| {
             log::trace!("{progress_event:?}");
-            report_progress_fn(progress_event.clone().into());
+            report_progress_fn(progress_event.into());
         },
     )
     .map(|mut progress_event| {
         progress_event.try_finish();
-        report_progress_fn(progress_event.clone().into());
+        report_progress_fn((&progress_event).into());
         let elapsed = progress_event.elapsed_since_started();
         let outcome = progress_event.finalize();
         log::info!(
@@ -224,6 +299,7 @@ pub fn visit_directories<
         let visit::Outcome {
             completion,
             progress: _,
+            skipped_entries: _,
         } = outcome;
         match completion {
             visit::Completion::Finished => Completion::Finished,
@@ -10,21 +10,27 @@ use std::{
 use anyhow::anyhow;
 
 use aoide_core::{
-    media::content::resolver::vfs::RemappingVfsResolver, util::clock::OffsetDateTimeMs,
+    media::content::{resolver::vfs::RemappingVfsResolver, ContentPath},
+    util::clock::OffsetDateTimeMs,
     CollectionUid, Track,
 };
 use aoide_core_api::{
     media::tracker::{
-        import_files::{ImportedSourceWithIssues, Outcome, Params, Summary},
-        Completion,
+        import_files::{
+            ImportedSourceWithIssues, IssueKind, IssueSummary, Outcome, Params, Summary,
+        },
+        Completion, ImportingProgress,
     },
     track::replace::Summary as TracksSummary,
     Pagination, PaginationOffset,
 };
-use aoide_media_file::io::import::ImportTrackConfig;
+use aoide_media_file::io::import::{ImportTrackConfig, IssueKind as ImportIssueKind};
 use aoide_repo::{
     collection::EntityRepo as CollectionRepo,
-    media::tracker::{Repo as MediaTrackerRepo, TrackedDirectory},
+    media::{
+        source::CollectionRepo as MediaSourceCollectionRepo,
+        tracker::{Repo as MediaTrackerRepo, TrackedDirectory},
+    },
     track::{CollectionRepo as TrackCollectionRepo, ReplaceMode},
     CollectionId,
 };
@@ -33,7 +39,7 @@ use crate::{
     collection::vfs::RepoContext,
     track::import_and_replace::{
         self, import_and_replace_by_local_file_path_from_directory_with_content_path_resolver,
-        Outcome as ImportAndReplaceOutcome,
+        DirectoryImportHandlers, Outcome as ImportAndReplaceOutcome,
     },
     Error, Result,
 };
@@ -41,7 +47,46 @@ use crate::{
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProgressEvent {
     pub elapsed: Duration,
-    pub summary: Summary,
+    pub progress: ImportingProgress,
+}
+
+/// Extrapolate the remaining time from the throughput so far.
+fn estimate_remaining(elapsed: Duration, processed: usize, total: usize) -> Option<Duration> {
+    if processed == 0 {
+        return None;
+    }
+    let remaining = total.saturating_sub(processed);
+    let remaining = u32::try_from(remaining).unwrap_or(u32::MAX);
+    let processed = u32::try_from(processed).unwrap_or(u32::MAX);
+    Some(elapsed / processed * remaining)
+}
+
+fn new_progress_event(
+    started_at: Instant,
+    pending_count: usize,
+    summary: &Summary,
+    current_path: Option<ContentPath<'static>>,
+) -> ProgressEvent {
+    let elapsed = started_at.elapsed();
+    let processed =
+        summary.directories.confirmed + summary.directories.skipped + summary.directories.untracked;
+    ProgressEvent {
+        elapsed,
+        progress: ImportingProgress {
+            current_path,
+            estimated_remaining: estimate_remaining(elapsed, processed, pending_count),
+            summary: summary.clone(),
+        },
+    }
+}
+
+const fn issue_kind(kind: ImportIssueKind) -> IssueKind {
+    match kind {
+        ImportIssueKind::MissingArtwork => IssueKind::MissingArtwork,
+        ImportIssueKind::MalformedDate => IssueKind::MalformedDate,
+        ImportIssueKind::UnsupportedTag => IssueKind::UnsupportedTag,
+        ImportIssueKind::Other => IssueKind::Other,
+    }
 }
 
 #[allow(clippy::too_many_lines)] // TODO
@@ -56,13 +101,14 @@ pub fn import_files<Repo, InterceptImportedTrackFn, ReportProgressFn>(
     abort_flag: &AtomicBool,
 ) -> Result<Outcome>
 where
-    Repo: CollectionRepo + MediaTrackerRepo + TrackCollectionRepo,
+    Repo: CollectionRepo + MediaSourceCollectionRepo + MediaTrackerRepo + TrackCollectionRepo,
     InterceptImportedTrackFn: Fn(Track) -> Track + Send,
     ReportProgressFn: FnMut(ProgressEvent),
 {
     let Params {
         root_url,
         sync_mode,
+        content_digest_workers,
     } = params;
     let collection_ctx = RepoContext::resolve(repo, collection_uid, root_url.as_ref())?;
     let Some(resolver) = &collection_ctx.content_path.resolver else {
@@ -75,18 +121,26 @@ where
         sync_mode: *sync_mode,
         import_config,
         replace_mode: ReplaceMode::UpdateOrCreate,
+        content_digest_workers: *content_digest_workers,
+        force: false,
     };
     let collection_id = collection_ctx.record_id;
 
     let started_at = Instant::now();
+    // The number of pending directories is needed for estimating the remaining time.
+    let directories_status = repo
+        .media_tracker_aggregate_directories_tracking_status(collection_id, resolver.root_path())?;
+    let pending_count = directories_status.added + directories_status.modified;
     let mut summary = Summary::default();
     let mut imported_sources_with_issues = Vec::new();
+    let mut issue_summary = IssueSummary::default();
+    report_progress_fn(new_progress_event(
+        started_at,
+        pending_count,
+        &summary,
+        None,
+    ));
     let outcome = 'outcome: loop {
-        report_progress_fn(ProgressEvent {
-            elapsed: started_at.elapsed(),
-            summary: summary.clone(),
-        });
-
         let pending_directories = repo.media_tracker_load_directories_requiring_confirmation(
             collection_id,
             resolver.root_path(),
@@ -109,6 +163,7 @@ where
                 completion: Completion::Finished,
                 summary,
                 imported_sources_with_issues,
+                issue_summary,
             };
             break 'outcome outcome;
         }
@@ -127,18 +182,42 @@ where
                     completion: Completion::Aborted,
                     summary,
                     imported_sources_with_issues,
+                    issue_summary,
                 };
                 break 'outcome outcome;
             }
 
+            report_progress_fn(new_progress_event(
+                started_at,
+                pending_count,
+                &summary,
+                Some(pending_directory.content_path.clone()),
+            ));
+            // Report progress after each imported file to keep the feedback responsive
+            // even for directories that contain many files.
+            let mut report_file_progress_fn =
+                |content_path: &ContentPath<'_>, tracks_summary: &TracksSummary| {
+                    let mut progress_summary = summary.clone();
+                    progress_summary.tracks += tracks_summary;
+                    report_progress_fn(new_progress_event(
+                        started_at,
+                        pending_count,
+                        &progress_summary,
+                        Some(content_path.clone_owned()),
+                    ));
+                };
             let import_pending_directory_res = import_pending_directory(
                 repo,
                 collection_id,
                 resolver,
                 &import_and_replace_params,
-                intercept_imported_track_fn,
-                abort_flag,
+                &mut DirectoryImportHandlers {
+                    intercept_imported_track_fn,
+                    report_file_progress_fn: &mut report_file_progress_fn,
+                    abort_flag,
+                },
                 &pending_directory,
+                &mut issue_summary,
             );
             let TrackedDirectory {
                 content_path: content_dir_path,
@@ -171,6 +250,7 @@ where
                                     completion: Completion::Aborted,
                                     summary,
                                     imported_sources_with_issues,
+                                    issue_summary,
                                 };
                                 break 'outcome outcome;
                             }
@@ -205,10 +285,12 @@ where
     };
 
     // Report final progress.
-    report_progress_fn(ProgressEvent {
-        elapsed: started_at.elapsed(),
-        summary: outcome.summary.clone(),
-    });
+    report_progress_fn(new_progress_event(
+        started_at,
+        pending_count,
+        &outcome.summary,
+        None,
+    ));
 
     Ok(outcome)
 }
@@ -231,18 +313,19 @@ enum ImportPendingDirectoryCompletion {
     NotConfirmed,
 }
 
-fn import_pending_directory<Repo, InterceptImportedTrackFn>(
+fn import_pending_directory<Repo, InterceptImportedTrackFn, ReportFileProgressFn>(
     repo: &mut Repo,
     collection_id: CollectionId,
     resolver: &RemappingVfsResolver,
     import_and_replace_params: &import_and_replace::Params,
-    intercept_imported_track_fn: &InterceptImportedTrackFn,
-    abort_flag: &AtomicBool,
+    handlers: &mut DirectoryImportHandlers<'_, InterceptImportedTrackFn, ReportFileProgressFn>,
     pending_directory: &TrackedDirectory,
+    issue_summary: &mut IssueSummary,
 ) -> Result<ImportPendingDirectoryOutcome>
 where
-    Repo: CollectionRepo + MediaTrackerRepo + TrackCollectionRepo,
+    Repo: CollectionRepo + MediaSourceCollectionRepo + MediaTrackerRepo + TrackCollectionRepo,
     InterceptImportedTrackFn: Fn(Track) -> Track + Send,
+    ReportFileProgressFn: FnMut(&ContentPath<'_>, &TracksSummary),
 {
     let TrackedDirectory {
         content_path,
@@ -257,8 +340,7 @@ where
             resolver.canonical_resolver(),
             content_path,
             import_and_replace_params,
-            intercept_imported_track_fn,
-            abort_flag,
+            handlers,
         ) {
             Ok(outcome) => outcome,
             Err(err) => {
@@ -289,9 +371,12 @@ where
     } = outcome;
     let imported_sources_with_issues = imported_media_sources_with_issues
         .into_iter()
-        .map(|(_, path, issues)| ImportedSourceWithIssues {
-            path,
-            messages: issues.into_messages(),
+        .map(|(_, path, issues)| {
+            issue_summary.add_source(&path, issues.kinds().map(issue_kind));
+            ImportedSourceWithIssues {
+                path,
+                messages: issues.into_messages(),
+            }
         })
         .collect();
     match completion {
@@ -66,7 +66,7 @@ where
     // the associated track!
     debug_assert!(matches!(
         repo.load_track_entity(new_header.id),
-        Err(RepoError::NotFound)
+        Err(RepoError::NotFound { .. })
     ));
     // Finish with updating the old track
     if updated_track != old_entity.body.track {
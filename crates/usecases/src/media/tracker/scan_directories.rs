@@ -1,7 +1,12 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{path::Path, sync::atomic::AtomicBool, time::Duration};
+use std::{
+    cell::RefCell,
+    path::Path,
+    sync::atomic::AtomicBool,
+    time::{Duration, Instant},
+};
 
 use anyhow::anyhow;
 use url::Url;
@@ -14,7 +19,7 @@ use aoide_core::{
 use aoide_core_api::media::tracker::{
     scan_directories::{Outcome, Summary},
     Completion, FsTraversalDirectoriesProgress, FsTraversalEntriesProgress, FsTraversalParams,
-    FsTraversalProgress,
+    FsTraversalProgress, ProgressPhase,
 };
 use aoide_media_file::fs::{
     digest::{hash_directories, HashDirectoryVisitor},
@@ -35,8 +40,8 @@ pub struct ProgressEvent {
     pub progress: FsTraversalProgress,
 }
 
-impl From<visit::ProgressEvent> for ProgressEvent {
-    fn from(from: visit::ProgressEvent) -> Self {
+impl From<&visit::ProgressEvent> for ProgressEvent {
+    fn from(from: &visit::ProgressEvent) -> Self {
         let visit::ProgressEvent {
             started_at,
             status,
@@ -52,17 +57,20 @@ impl From<visit::ProgressEvent> for ProgressEvent {
                             finished: entries_finished,
                         },
                 },
+            skipped_entries: _,
         } = from;
         Self {
             elapsed: started_at.elapsed(),
-            status,
+            status: *status,
             progress: FsTraversalProgress {
+                phase: ProgressPhase::ScanningDirectories,
+                current_path: None,
                 directories: FsTraversalDirectoriesProgress {
-                    finished: directories_finished,
+                    finished: *directories_finished,
                 },
                 entries: FsTraversalEntriesProgress {
-                    skipped: entries_skipped,
-                    finished: entries_finished,
+                    skipped: *entries_skipped,
+                    finished: *entries_finished,
                 },
             },
         }
@@ -106,6 +114,11 @@ pub fn scan_directories<
     )?;
     log::debug!("Marked {outdated_count} current cache entries as outdated");
     let mut summary = Summary::default();
+    // Progress is reported both while traversing the directory tree and
+    // when storing the digest of a finished directory.
+    let started_at = Instant::now();
+    let last_progress = RefCell::new(FsTraversalProgress::default());
+    let report_progress_fn = RefCell::new(report_progress_fn);
     let digest_finished_fn = |dir_path: &Path, digest: digest::Output<blake3::Hasher>| {
         log::debug!(
             "Finishing directory: {dir_path}",
@@ -122,6 +135,16 @@ pub fn scan_directories<
             .resolve_path_from_url(&url)?
             .ok_or_else(|| anyhow!("unresolved URL: {url}"))?;
         log::debug!("Updating digest of content path: {content_path}");
+        let progress = FsTraversalProgress {
+            phase: ProgressPhase::DigestingDirectories,
+            current_path: Some(content_path.clone()),
+            ..last_progress.borrow().clone()
+        };
+        (*report_progress_fn.borrow_mut())(ProgressEvent {
+            elapsed: started_at.elapsed(),
+            status: visit::Status::InProgress,
+            progress,
+        });
         let updated_at = OffsetDateTimeMs::now_utc();
         match repo
             .media_tracker_update_directory_digest(
@@ -155,17 +178,20 @@ pub fn scan_directories<
         Ok(visit::AfterAncestorFinished::Continue)
     };
     let mut directory_visitor = HashDirectoryVisitor::new(blake3::Hasher::new, digest_finished_fn);
-    let mut report_progress_fn = |progress_event: &visit::ProgressEvent| {
+    let mut report_visit_progress_fn = |progress_event: &visit::ProgressEvent| {
         log::trace!("{progress_event:?}");
-        report_progress_fn(progress_event.clone().into());
+        // Only the number of skipped entries is reported
+        let progress_event = ProgressEvent::from(progress_event);
+        *last_progress.borrow_mut() = progress_event.progress.clone();
+        (*report_progress_fn.borrow_mut())(progress_event);
     };
-    let completion = hash_directories::<_, anyhow::Error, _, _, _>(
+    let (completion, skipped_entries) = hash_directories::<_, anyhow::Error, _, _, _>(
         &root_file_path.as_path().into(),
         &excluded_paths,
         *max_depth,
         abort_flag,
         &mut directory_visitor,
-        &mut report_progress_fn,
+        &mut report_visit_progress_fn,
     )
     .map_err(Into::into)
     .map_err(RepoError::Other)
@@ -173,8 +199,26 @@ pub fn scan_directories<
         let visit::Outcome {
             completion,
             progress: _,
+            skipped_entries,
         } = outcome;
-        match completion {
+        // Directories that have already been visited, e.g. through a symlink,
+        // don't affect the completeness of the scan.
+        let num_inaccessible_entries = skipped_entries
+            .iter()
+            .filter(|skipped_entry| {
+                matches!(skipped_entry.reason, visit::SkipReason::Inaccessible(_))
+            })
+            .count();
+        let completion = match completion {
+            visit::Completion::Finished if num_inaccessible_entries > 0 => {
+                // The contents of inaccessible entries are unknown and must
+                // not be marked as orphaned.
+                log::warn!(
+                    "Skipped {num_inaccessible_entries} inaccessible entries while scanning \
+                     directories"
+                );
+                Completion::Aborted
+            }
             visit::Completion::Finished => {
                 // Mark all remaining entries that are unreachable and
                 // have not been visited as orphaned.
@@ -185,14 +229,37 @@ pub fn scan_directories<
                     resolver.root_path(),
                 )?;
                 debug_assert!(summary.orphaned <= outdated_count);
-                Ok(Completion::Finished)
+                Completion::Finished
             }
             visit::Completion::Aborted => {
                 // All partial results up to now can safely be committed.
-                Ok(Completion::Aborted)
+                Completion::Aborted
             }
-        }
+        };
+        Ok((completion, skipped_entries))
     })?;
+    let skipped_paths = skipped_entries
+        .into_iter()
+        .filter_map(|visit::SkippedEntry { path, reason }| {
+            let url = if path.is_dir() {
+                Url::from_directory_path(&path)
+            } else {
+                Url::from_file_path(&path)
+            };
+            let Some(content_path) = url
+                .ok()
+                .and_then(|url| resolver.resolve_path_from_url(&url).ok().flatten())
+            else {
+                log::warn!(
+                    "Failed to resolve content path of skipped entry: {path}",
+                    path = path.display()
+                );
+                return None;
+            };
+            log::debug!("Skipped entry {content_path}: {reason:?}");
+            Some(content_path)
+        })
+        .collect();
     let (root_url, root_path) = collection_ctx
         .content_path
         .resolver
@@ -203,5 +270,6 @@ pub fn scan_directories<
         root_path,
         completion,
         summary,
+        skipped_paths,
     })
 }
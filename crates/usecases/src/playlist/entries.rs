@@ -3,17 +3,23 @@
 
 use std::ops::Range;
 
+use anyhow::anyhow;
+
 use aoide_core::{
-    playlist::{EntityHeader, EntityUid, Entry},
+    media::content::{AudioContentMetadata, ContentMetadata},
+    playlist::{EntityHeader, EntityUid, Entry, Item, TrackItem},
     util::clock::OffsetDateTimeMs,
 };
 use aoide_core_api::playlist::EntityWithEntriesSummary;
 use aoide_repo::{
     playlist::{EntityRepo, EntryRepo, RecordHeader},
-    RepoResult,
+    track::EntityRepo as TrackRepo,
 };
+use semval::prelude::*;
+
+use crate::{InputError, Result};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PatchOperation {
     Append { entries: Vec<Entry> },
     Prepend { entries: Vec<Entry> },
@@ -26,13 +32,55 @@ pub enum PatchOperation {
     ShuffleAll,
 }
 
+/// Validate a track item against the referenced track.
+///
+/// The track is only loaded if the item has cue positions that need
+/// to be checked against the duration of the track.
+fn validate_track_item<Repo>(repo: &mut Repo, track_item: &TrackItem) -> Result<()>
+where
+    Repo: TrackRepo,
+{
+    let validated = if track_item.cue_in.is_some() || track_item.cue_out.is_some() {
+        let (_, track_entity) = repo.load_track_entity_by_uid(&track_item.uid)?;
+        let ContentMetadata::Audio(AudioContentMetadata { duration, .. }) =
+            track_entity.body.track.media_source.content.metadata;
+        track_item.validate_with_duration(duration)
+    } else {
+        track_item.validate()
+    };
+    validated.map_err(|err| {
+        InputError(anyhow!(
+            "invalid playlist entry for track {uid}: {err:?}",
+            uid = track_item.uid
+        ))
+        .into()
+    })
+}
+
+fn validate_entries<Repo>(repo: &mut Repo, entries: &[Entry]) -> Result<()>
+where
+    Repo: TrackRepo,
+{
+    for entry in entries {
+        match &entry.item {
+            Item::Separator(_) => (),
+            Item::Track(track_item) => validate_track_item(repo, track_item)?,
+        }
+    }
+    Ok(())
+}
+
+/// Patch the entries of a playlist.
+///
+/// Added track items are validated against the referenced tracks,
+/// i.e. their cue positions must fall within the track duration.
 pub fn patch<Repo>(
     repo: &mut Repo,
     entity_header: &EntityHeader,
     operations: impl IntoIterator<Item = PatchOperation>,
-) -> RepoResult<(RecordHeader, EntityWithEntriesSummary)>
+) -> Result<(RecordHeader, EntityWithEntriesSummary)>
 where
-    Repo: EntityRepo + EntryRepo,
+    Repo: EntityRepo + EntryRepo + TrackRepo,
 {
     let updated_at = OffsetDateTimeMs::now_utc();
     let (record_header, next_rev) =
@@ -47,18 +95,21 @@ where
                 if entries.is_empty() {
                     continue;
                 }
+                validate_entries(repo, entries)?;
                 repo.append_playlist_entries(record_header.id, entries)?;
             }
             Prepend { entries } => {
                 if entries.is_empty() {
                     continue;
                 }
+                validate_entries(repo, entries)?;
                 repo.prepend_playlist_entries(record_header.id, entries)?;
             }
             Insert { before, entries } => {
                 if entries.is_empty() {
                     continue;
                 }
+                validate_entries(repo, entries)?;
                 repo.insert_playlist_entries(record_header.id, *before, entries)?;
             }
             CopyAll {
@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::anyhow;
+
+use aoide_core::{
+    collection::MediaSourceConfig, media::content::resolver::vfs::VfsResolver, CollectionUid,
+    Track, TrackUid,
+};
+use aoide_core_api::collection::LoadScope;
+use aoide_media_file::io::import::{load_artwork_image_data, LoadedArtworkImageData};
+use aoide_repo::{
+    collection::EntityRepo as CollectionRepo, track::CollectionRepo as TrackCollectionRepo,
+    CollectionId, EntityContext, EntityKind, RepoError,
+};
+
+use super::replace::{replace_by_content_path, ReplaceByContentPathOutcome};
+use crate::{Error, Result};
+
+/// A self-contained snapshot of a single track
+///
+/// Contains the track metadata together with the image data of its
+/// artwork, independent of the media file.
+#[derive(Debug, Clone)]
+pub struct TrackBundle {
+    pub track: Track,
+
+    /// The image data of the artwork
+    ///
+    /// `None` if the track has no artwork or if the image data
+    /// is not available.
+    pub artwork_image: Option<LoadedArtworkImageData>,
+}
+
+/// A track that has been loaded for exporting it as a [`TrackBundle`].
+#[derive(Debug)]
+pub struct ExportableTrack {
+    pub content_path_resolver: VfsResolver,
+    pub track: Track,
+}
+
+impl ExportableTrack {
+    /// Load the artwork image and assemble the bundle.
+    ///
+    /// The artwork image is loaded from the media file or the linked
+    /// image file. This should be done outside of any database
+    /// transaction.
+    pub fn into_bundle(self) -> Result<TrackBundle> {
+        let Self {
+            content_path_resolver,
+            track,
+        } = self;
+        let artwork_image = if let Some(artwork) = &track.media_source.artwork {
+            let file_path =
+                content_path_resolver.build_file_path(&track.media_source.content.link.path);
+            load_artwork_image_data(&file_path, artwork)?
+        } else {
+            None
+        };
+        Ok(TrackBundle {
+            track,
+            artwork_image,
+        })
+    }
+}
+
+/// Load a track of a collection for exporting it as a [`TrackBundle`].
+///
+/// See also: [`ExportableTrack::into_bundle()`]
+pub fn load_exportable_track<Repo>(
+    repo: &mut Repo,
+    collection_uid: &CollectionUid,
+    track_uid: &TrackUid,
+) -> Result<ExportableTrack>
+where
+    Repo: CollectionRepo + TrackCollectionRepo,
+{
+    let (collection_hdr, collection_entity_with_summary) =
+        crate::collection::load_one(repo, collection_uid, LoadScope::Entity)?;
+    let MediaSourceConfig { content_path } = &collection_entity_with_summary
+        .entity
+        .body
+        .media_source_config;
+    let Some(root_url) = content_path.root_url() else {
+        return Err(Error::Other(anyhow!(
+            "unsupported content path config: {content_path:?}"
+        )));
+    };
+    let content_path_resolver = VfsResolver::with_root_url(root_url.clone());
+    let mut loaded = Vec::with_capacity(1);
+    repo.load_tracks_by_uids(
+        collection_hdr.id,
+        std::slice::from_ref(track_uid),
+        &mut loaded,
+    )?;
+    let Some((_, entity)) = loaded.pop() else {
+        return Err(RepoError::NotFound {
+            entity: Some(EntityContext::new(EntityKind::Track, track_uid)),
+        }
+        .into());
+    };
+    Ok(ExportableTrack {
+        content_path_resolver,
+        track: entity.raw.body.track,
+    })
+}
+
+/// Import a [`TrackBundle`] into a collection.
+///
+/// The track is created or updated at its content path, see
+/// [`replace_by_content_path()`]. The artwork image is not stored
+/// and only returned to the caller.
+///
+/// The URI of linked artwork is not modified, i.e. it still refers
+/// to the image file of the exported track.
+pub fn import_bundle<Repo>(
+    repo: &mut Repo,
+    collection_id: CollectionId,
+    bundle: TrackBundle,
+) -> Result<(ReplaceByContentPathOutcome, Option<LoadedArtworkImageData>)>
+where
+    Repo: TrackCollectionRepo,
+{
+    let TrackBundle {
+        track,
+        artwork_image,
+    } = bundle;
+    let content_path = track.media_source.content.link.path.clone();
+    let outcome = replace_by_content_path(repo, collection_id, content_path, track)?;
+    Ok((outcome, artwork_image))
+}
@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::anyhow;
+use nonicle::{Canonical, CanonicalizeInto as _};
+
+use aoide_core::{
+    tag::{FacetKey, Tags, TagsMap},
+    track::Entity,
+    TrackUid,
+};
+use aoide_core_api::track::edit_tags::TagEdit;
+use aoide_repo::{
+    track::{CollectionRepo, ReplaceMode, ReplaceOutcome, ReplaceParams},
+    CollectionId, EntityContext, EntityKind, RepoError,
+};
+
+use crate::{Error, Result};
+
+/// Apply a tag edit
+///
+/// Returns `true` if the tags have been modified.
+pub fn apply_tag_edit(tags: &mut Canonical<Tags<'static>>, edit: &TagEdit) -> bool {
+    let mut tags_map = TagsMap::from(tags.clone().untie()).into_inner();
+    match edit {
+        TagEdit::Add {
+            facet_id,
+            tags: added_tags,
+        } => {
+            let facet_tags = tags_map.entry(FacetKey::new(facet_id.clone())).or_default();
+            for added_tag in added_tags {
+                if facet_tags.iter().all(|tag| tag.label != added_tag.label) {
+                    facet_tags.push(added_tag.clone());
+                }
+            }
+        }
+        TagEdit::Remove { facet_id, labels } => {
+            if let Some(facet_tags) = tags_map.get_mut(&FacetKey::new(facet_id.clone())) {
+                facet_tags.retain(|tag| {
+                    !tag.label
+                        .as_ref()
+                        .is_some_and(|label| labels.contains(label))
+                });
+            }
+        }
+        TagEdit::Replace {
+            facet_id,
+            tags: replaced_tags,
+        } => {
+            tags_map.insert(FacetKey::new(facet_id.clone()), replaced_tags.clone());
+        }
+    }
+    let edited_tags = TagsMap::new(tags_map).canonicalize_into();
+    if edited_tags == *tags {
+        return false;
+    }
+    *tags = edited_tags;
+    true
+}
+
+/// Apply a tag edit to a single track of a collection
+///
+/// Returns the updated entity with a bumped revision or `None`
+/// if the tags of the track are unchanged.
+pub fn edit_track_tags<Repo>(
+    repo: &mut Repo,
+    collection_id: CollectionId,
+    track_uid: &TrackUid,
+    edit: &TagEdit,
+) -> Result<Option<Entity>>
+where
+    Repo: CollectionRepo,
+{
+    let mut loaded = Vec::with_capacity(1);
+    repo.load_tracks_by_uids(collection_id, std::slice::from_ref(track_uid), &mut loaded)?;
+    let Some((_, entity)) = loaded.pop() else {
+        return Err(RepoError::NotFound {
+            entity: Some(EntityContext::new(EntityKind::Track, track_uid)),
+        }
+        .into());
+    };
+    let mut track = entity.raw.body.track;
+    if !apply_tag_edit(&mut track.tags, edit) {
+        return Ok(None);
+    }
+    let params = ReplaceParams {
+        mode: ReplaceMode::UpdateOnly,
+        preserve_collected_at: true,
+        update_last_synchronized_rev: false,
+    };
+    match repo.replace_track_by_media_source_content_path(collection_id, params, track)? {
+        ReplaceOutcome::Updated(_, _, entity) => Ok(Some(entity)),
+        ReplaceOutcome::Unchanged(..) => Ok(None),
+        ReplaceOutcome::Created(..)
+        | ReplaceOutcome::NotCreated(_)
+        | ReplaceOutcome::NotUpdated(..) => {
+            Err(Error::Other(anyhow!("failed to update track {track_uid}")))
+        }
+    }
+}
@@ -1,13 +1,29 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::{
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
 use anyhow::anyhow;
+use url::Url;
 
-use aoide_core::{media::content::resolver::ContentPathResolver, CollectionUid};
+use aoide_core::{
+    media::content::{
+        resolver::{vfs::VfsResolver, ContentPathResolver},
+        ContentPath, ContentPathKind,
+    },
+    CollectionUid,
+};
 use aoide_core_api::{
     filtering::StringPredicate,
     media::source::ResolveUrlFromContentPath,
-    track::find_unsynchronized::{Params, UnsynchronizedTrack, UnsynchronizedTrackEntity},
+    track::find_unsynchronized::{
+        FileStatus, Params, UnsynchronizedTrack, UnsynchronizedTrackEntity,
+    },
     Pagination,
 };
 use aoide_repo::{
@@ -16,7 +32,89 @@ use aoide_repo::{
     CollectionId, RepoResult,
 };
 
-use crate::{collection::vfs::RepoContext, Error, Result};
+use crate::{
+    collection::vfs::{ContentPathContext, RepoContext},
+    worker_pool::with_worker_pool,
+    Error, Result,
+};
+
+/// Upper bound for the number of threads that check if files exist.
+const MAX_FILE_STATUS_WORKER_COUNT: usize = 8;
+
+/// Resolves the local file paths of media sources for checking if
+/// they exist.
+#[derive(Debug)]
+pub struct FileStatusResolver {
+    /// `None` for file URLs.
+    vfs_resolver: Option<VfsResolver>,
+}
+
+impl FileStatusResolver {
+    /// Create a new resolver for the given collection context.
+    ///
+    /// Returns `None` if the collection doesn't contain local files.
+    #[must_use]
+    pub fn new(content_path: &ContentPathContext) -> Option<Self> {
+        let vfs_resolver = match content_path.kind {
+            ContentPathKind::FileUrl => None,
+            ContentPathKind::VirtualFilePath => {
+                // Files are always checked at their canonical location,
+                // independent of an override root URL.
+                let canonical_root_url = content_path.resolver.as_ref()?.canonical_root_url();
+                Some(VfsResolver::with_root_url(canonical_root_url.clone()))
+            }
+            ContentPathKind::Uri | ContentPathKind::Url => {
+                return None;
+            }
+        };
+        Some(Self { vfs_resolver })
+    }
+
+    fn resolve_file_path(&self, content_path: &ContentPath<'_>) -> Option<PathBuf> {
+        if let Some(vfs_resolver) = &self.vfs_resolver {
+            return Some(vfs_resolver.build_file_path(content_path));
+        }
+        Url::parse(content_path.as_str()).ok()?.to_file_path().ok()
+    }
+}
+
+/// Check if files exist on a bounded number of worker threads.
+///
+/// The status of files that have not been checked before `abort_flag`
+/// has been set remains `None`. Same for file paths that are `None`.
+fn check_file_status(
+    file_paths: &[Option<PathBuf>],
+    abort_flag: &AtomicBool,
+) -> Vec<Option<FileStatus>> {
+    let worker_count = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(MAX_FILE_STATUS_WORKER_COUNT);
+    let file_status = with_worker_pool(
+        file_paths,
+        worker_count,
+        abort_flag,
+        |file_path| {
+            let file_path = file_path.as_ref()?;
+            let file_status = if file_path.is_file() {
+                FileStatus::Present
+            } else {
+                FileStatus::Missing
+            };
+            Some(file_status)
+        },
+        |receiver| {
+            let mut file_status = vec![None; file_paths.len()];
+            for (index, status) in receiver {
+                file_status[index] = Some(status);
+            }
+            file_status
+        },
+    );
+    if abort_flag.load(Ordering::Relaxed) {
+        log::info!("Aborted checking if files of unsynchronized tracks exist");
+    }
+    file_status
+}
 
 #[allow(clippy::missing_panics_doc)] // Never panics
 pub fn find_unsynchronized<Repo, Resolver>(
@@ -25,37 +123,55 @@ pub fn find_unsynchronized<Repo, Resolver>(
     pagination: &Pagination,
     content_path_predicate: Option<StringPredicate<'_>>,
     content_path_resolver: Option<&Resolver>,
+    file_status_resolver: Option<&FileStatusResolver>,
+    abort_flag: &AtomicBool,
 ) -> RepoResult<Vec<UnsynchronizedTrackEntity>>
 where
     Repo: TrackCollectionRepo,
     Resolver: ContentPathResolver,
 {
-    repo.find_unsynchronized_tracks(collection_id, pagination, content_path_predicate)
-        .map(|v| {
-            v.into_iter()
-                .map(|(entity_header, _record_id, record_trail)| {
-                    let RecordTrail {
-                        collection_id: _,
-                        media_source_id: _,
-                        content_link,
-                        last_synchronized_rev,
-                    } = record_trail;
-                    let mut content_link = content_link;
-                    if let Some(content_path_resolver) = content_path_resolver {
-                        // FIXME: Handle errors
-                        let url = content_path_resolver
-                            .resolve_url_from_path(&content_link.path)
-                            .expect("valid content path");
-                        content_link.path = url.to_string().into();
-                    }
-                    let track = UnsynchronizedTrack {
-                        content_link,
-                        last_synchronized_rev,
-                    };
-                    UnsynchronizedTrackEntity::new(entity_header, track)
-                })
-                .collect()
+    let records =
+        repo.find_unsynchronized_tracks(collection_id, pagination, content_path_predicate)?;
+    // The file paths must be resolved before content paths are rewritten into URLs
+    let file_status = if let Some(file_status_resolver) = file_status_resolver {
+        let file_paths = records
+            .iter()
+            .map(|(_, _, record_trail)| {
+                file_status_resolver.resolve_file_path(&record_trail.content_link.path)
+            })
+            .collect::<Vec<_>>();
+        check_file_status(&file_paths, abort_flag)
+    } else {
+        vec![None; records.len()]
+    };
+    debug_assert_eq!(records.len(), file_status.len());
+    let entities = records
+        .into_iter()
+        .zip(file_status)
+        .map(|((entity_header, _record_id, record_trail), file_status)| {
+            let RecordTrail {
+                collection_id: _,
+                media_source_id: _,
+                content_link,
+                last_synchronized_rev,
+            } = record_trail;
+            let mut content_link = content_link;
+            if let Some(content_path_resolver) = content_path_resolver {
+                // FIXME: Handle errors
+                let url = content_path_resolver
+                    .resolve_url_from_path(&content_link.path)
+                    .expect("valid content path");
+                content_link.path = url.to_string().into();
+            }
+            let track = UnsynchronizedTrack {
+                content_link,
+                last_synchronized_rev,
+                file_status,
+            };
+            UnsynchronizedTrackEntity::new(entity_header, track)
         })
+        .collect();
+    Ok(entities)
 }
 
 pub fn find_unsynchronized_with_params<Repo>(
@@ -63,6 +179,7 @@ pub fn find_unsynchronized_with_params<Repo>(
     collection_uid: &CollectionUid,
     params: Params,
     pagination: &Pagination,
+    abort_flag: &AtomicBool,
 ) -> Result<Vec<UnsynchronizedTrackEntity>>
 where
     Repo: CollectionRepo + TrackCollectionRepo,
@@ -70,6 +187,7 @@ where
     let Params {
         resolve_url_from_content_path,
         content_path_predicate,
+        check_file_exists,
     } = params;
     let collection_ctx = RepoContext::resolve_override(
         repo,
@@ -81,6 +199,16 @@ where
             .map(ToOwned::to_owned),
     )?;
     let collection_id = collection_ctx.record_id;
+    let file_status_resolver = if check_file_exists {
+        let file_status_resolver = FileStatusResolver::new(&collection_ctx.content_path);
+        if file_status_resolver.is_none() {
+            let path_kind = collection_ctx.content_path.kind;
+            log::debug!("Not checking if files exist for path kind {path_kind:?}");
+        }
+        file_status_resolver
+    } else {
+        None
+    };
     let content_path_resolver = if resolve_url_from_content_path.is_some() {
         if let Some(resolver) = collection_ctx.content_path.resolver {
             Some(resolver)
@@ -99,6 +227,8 @@ where
         pagination,
         content_path_predicate,
         content_path_resolver.as_ref(),
+        file_status_resolver.as_ref(),
+        abort_flag,
     )
     .map_err(Into::into)
 }
@@ -3,6 +3,9 @@
 
 use std::{
     fs::read_dir,
+    io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicBool, Ordering},
 };
 
@@ -12,7 +15,7 @@ use url::Url;
 use aoide_core::{
     media::content::{
         resolver::{vfs::VfsResolver, ContentPathResolver as _},
-        ContentPath, ContentRevision,
+        ContentMetadataFlags, ContentPath, ContentRevision,
     },
     util::clock::OffsetDateTimeMs,
     CollectionUid, Track,
@@ -24,13 +27,17 @@ use aoide_core_api::{
 use aoide_media_file::io::import::{ImportTrack, ImportTrackConfig, Issues};
 use aoide_repo::{
     collection::EntityRepo as CollectionRepo,
+    media::source::CollectionRepo as MediaSourceCollectionRepo,
     track::{CollectionRepo as TrackCollectionRepo, ReplaceMode, ReplaceParams},
     CollectionId, MediaSourceId, OptionalRepoResult as _, RepoResult,
 };
 
 use crate::{
     collection::vfs::RepoContext,
-    media::{import_track_from_file_path, ImportTrackFromFileOutcome, SyncModeParams},
+    media::{
+        content_digest::{with_content_digests, ContentDigestCache},
+        import_track_from_file_path, ImportTrackFromFileOutcome, SyncModeParams,
+    },
     Error, MediaFileError, Result,
 };
 
@@ -120,6 +127,7 @@ fn import_replacement_from_file_path<InterceptImportedTrackFn>(
     context: ImportReplacementFromFilePathContext,
     content_path_resolver: &VfsResolver,
     params: &Params,
+    content_digest: Option<Vec<u8>>,
     intercept_imported_track_fn: &InterceptImportedTrackFn,
 ) -> Result<ImportReplacementFromFilePathOutcome>
 where
@@ -137,7 +145,28 @@ where
         sync_mode,
         import_config,
         replace_mode,
+        content_digest_workers: _,
+        force,
     } = params;
+    let mut import_track = import_track;
+    if let ImportTrack::UpdateTrack(track) = &mut import_track {
+        let metadata_flags = &mut track.media_source.content.metadata_flags;
+        if *force {
+            // Reset the flags to overwrite all reliable or locked metadata
+            *metadata_flags = ContentMetadataFlags::default();
+        } else if metadata_flags.is_locked() {
+            // Don't even parse the file if the result would be discarded
+            debug_assert!(media_source_id.is_some());
+            log::debug!("Skipping reimport of track {content_path} with locked metadata");
+            return Ok(ImportReplacementFromFilePathOutcome {
+                collection_id,
+                content_path,
+                import_issues: Default::default(),
+                media_source_id,
+                replacement: Replacement::NotImported,
+            });
+        }
+    }
     let mut import_issues = Default::default();
     let replacement = match import_track_from_file_path(
         import_track,
@@ -146,8 +175,11 @@ where
         &SyncModeParams::new(*sync_mode, external_rev, synchronized_rev),
         import_config,
     ) {
-        Ok(ImportTrackFromFileOutcome::Imported { track, issues }) => {
+        Ok(ImportTrackFromFileOutcome::Imported { mut track, issues }) => {
             debug_assert_eq!(track.media_source.content.link.path, content_path);
+            if content_digest.is_some() {
+                track.media_source.content.digest = content_digest;
+            }
             import_issues = issues;
             let track = intercept_imported_track_fn(track);
             let (validated_input, invalidities) = validate_input(track)?;
@@ -283,6 +315,19 @@ pub struct Params {
     pub sync_mode: SyncMode,
     pub import_config: ImportTrackConfig,
     pub replace_mode: ReplaceMode,
+
+    /// Compute the digests of the audio content of imported files
+    /// with the given number of worker threads.
+    ///
+    /// No digests are computed if `None`.
+    pub content_digest_workers: Option<NonZeroUsize>,
+
+    /// Re-import and overwrite metadata regardless of the
+    /// [`ContentMetadataFlags`] of existing tracks.
+    ///
+    /// Otherwise tracks with locked metadata are not re-imported
+    /// and reliable metadata is only replaced by reliable metadata.
+    pub force: bool,
 }
 
 pub fn import_and_replace_many_by_local_file_path<Repo, InterceptImportedTrackFn>(
@@ -295,7 +340,7 @@ pub fn import_and_replace_many_by_local_file_path<Repo, InterceptImportedTrackFn
     abort_flag: &AtomicBool,
 ) -> Result<Outcome>
 where
-    Repo: CollectionRepo + TrackCollectionRepo,
+    Repo: CollectionRepo + MediaSourceCollectionRepo + TrackCollectionRepo,
     InterceptImportedTrackFn: Fn(Track) -> Track,
 {
     let collection_ctx = RepoContext::resolve(repo, collection_uid, None)?;
@@ -306,64 +351,95 @@ where
         )));
     };
     let collection_id = collection_ctx.record_id;
-    let mut summary = Summary::default();
-    let mut visited_media_source_ids =
-        Vec::with_capacity(expected_content_path_count.unwrap_or(DEFAULT_MEDIA_SOURCE_COUNT));
-    let mut imported_media_sources_with_issues =
-        Vec::with_capacity(expected_content_path_count.unwrap_or(DEFAULT_MEDIA_SOURCE_COUNT) / 4);
-    for content_path in content_paths {
-        if abort_flag.load(Ordering::Relaxed) {
-            log::debug!("Aborting import of {content_path}");
-            return Ok(Outcome {
-                completion: Completion::Aborted,
-                summary,
-                visited_media_source_ids,
-                imported_media_sources_with_issues,
-            });
-        }
-
-        let context = ImportReplacementFromFilePathContext::load_from_repo(
+    let content_path_resolver = resolver.canonical_resolver();
+    let content_paths = content_paths.into_iter().collect::<Vec<_>>();
+    let (file_paths, content_digest_cache) = if params.content_digest_workers.is_some() {
+        let file_paths = content_paths
+            .iter()
+            .map(|content_path| content_path_resolver.build_file_path(content_path))
+            .collect();
+        let content_digest_cache = ContentDigestCache::load_from_repo(
             repo,
             collection_id,
-            content_path,
+            &content_paths.iter().collect::<Vec<_>>(),
         )?;
-        let content_path_resolver = resolver.canonical_resolver();
+        (file_paths, content_digest_cache)
+    } else {
+        (Vec::new(), ContentDigestCache::default())
+    };
+    let files = content_paths
+        .iter()
+        .zip(file_paths.iter().map(PathBuf::as_path))
+        .collect::<Vec<_>>();
+    with_content_digests(
+        &files,
+        &content_digest_cache,
+        params.content_digest_workers,
+        abort_flag,
+        |content_digests| {
+            let mut summary = Summary::default();
+            let mut visited_media_source_ids = Vec::with_capacity(
+                expected_content_path_count.unwrap_or(DEFAULT_MEDIA_SOURCE_COUNT),
+            );
+            let mut imported_media_sources_with_issues = Vec::with_capacity(
+                expected_content_path_count.unwrap_or(DEFAULT_MEDIA_SOURCE_COUNT) / 4,
+            );
+            for content_path in &content_paths {
+                if abort_flag.load(Ordering::Relaxed) {
+                    log::debug!("Aborting import of {content_path}");
+                    return Ok(Outcome {
+                        completion: Completion::Aborted,
+                        summary,
+                        visited_media_source_ids,
+                        imported_media_sources_with_issues,
+                    });
+                }
 
-        // TODO: Import multiple tracks from different files in parallel.
-        let outcome = import_replacement_from_file_path(
-            context,
-            content_path_resolver,
-            params,
-            intercept_imported_track_fn,
-        )?;
+                let context = ImportReplacementFromFilePathContext::load_from_repo(
+                    repo,
+                    collection_id,
+                    content_path.clone(),
+                )?;
+                let content_digest = content_digests.next_digest();
 
-        let ImportAndReplaceFromFilePathOutcome {
-            content_path,
-            import_issues,
-            media_source_id,
-        } = replace_after_imported_from_file_path(
-            outcome,
-            &mut summary,
-            repo,
-            content_path_resolver,
-        )?;
-        if let Some(media_source_id) = media_source_id {
-            visited_media_source_ids.push(media_source_id);
-            if !import_issues.is_empty() {
-                imported_media_sources_with_issues.push((
-                    media_source_id,
+                // TODO: Import multiple tracks from different files in parallel.
+                let outcome = import_replacement_from_file_path(
+                    context,
+                    content_path_resolver,
+                    params,
+                    content_digest,
+                    intercept_imported_track_fn,
+                )?;
+
+                let ImportAndReplaceFromFilePathOutcome {
                     content_path,
                     import_issues,
-                ));
+                    media_source_id,
+                } = replace_after_imported_from_file_path(
+                    outcome,
+                    &mut summary,
+                    repo,
+                    content_path_resolver,
+                )?;
+                if let Some(media_source_id) = media_source_id {
+                    visited_media_source_ids.push(media_source_id);
+                    if !import_issues.is_empty() {
+                        imported_media_sources_with_issues.push((
+                            media_source_id,
+                            content_path,
+                            import_issues,
+                        ));
+                    }
+                }
             }
-        }
-    }
-    Ok(Outcome {
-        completion: Completion::Finished,
-        summary,
-        visited_media_source_ids,
-        imported_media_sources_with_issues,
-    })
+            Ok(Outcome {
+                completion: Completion::Finished,
+                summary,
+                visited_media_source_ids,
+                imported_media_sources_with_issues,
+            })
+        },
+    )
 }
 
 const EXPECTED_NUMBER_OF_DIR_ENTRIES: usize = 1024;
@@ -377,7 +453,7 @@ pub fn import_and_replace_by_local_file_path_from_directory<Repo, InterceptImpor
     abort_flag: &AtomicBool,
 ) -> Result<Outcome>
 where
-    Repo: CollectionRepo + TrackCollectionRepo,
+    Repo: CollectionRepo + MediaSourceCollectionRepo + TrackCollectionRepo,
     InterceptImportedTrackFn: Fn(Track) -> Track + Send,
 {
     let collection_ctx = RepoContext::resolve(repo, collection_uid, None)?;
@@ -394,36 +470,23 @@ where
         resolver.canonical_resolver(),
         source_dir_path,
         params,
-        intercept_imported_track_fn,
-        abort_flag,
+        &mut DirectoryImportHandlers {
+            intercept_imported_track_fn,
+            report_file_progress_fn: &mut |_: &ContentPath<'_>, _: &Summary| {},
+            abort_flag,
+        },
     )
 }
 
-pub fn import_and_replace_by_local_file_path_from_directory_with_content_path_resolver<
-    InterceptImportedTrackFn,
->(
-    repo: &mut impl TrackCollectionRepo,
-    collection_id: CollectionId,
+/// Read the entries of a directory that resolve to content paths.
+///
+/// Inaccessible and unsupported entries are skipped.
+fn read_dir_entries(
     content_path_resolver: &VfsResolver,
-    source_dir_path: &ContentPath<'_>,
-    params: &Params,
-    intercept_imported_track_fn: &InterceptImportedTrackFn,
-    abort_flag: &AtomicBool,
-) -> Result<Outcome>
-where
-    InterceptImportedTrackFn: Fn(Track) -> Track + Send,
-{
-    let dir_path = content_path_resolver.build_file_path(source_dir_path);
-    log::debug!(
-        "Importing files from directory: {dir_path}",
-        dir_path = dir_path.display()
-    );
+    dir_path: &Path,
+) -> io::Result<Vec<(ContentPath<'static>, PathBuf)>> {
     let dir_entries = read_dir(dir_path)?;
-    let mut summary = Summary::default();
-    let mut visited_media_source_ids = Vec::with_capacity(EXPECTED_NUMBER_OF_DIR_ENTRIES);
-    let mut imported_media_sources_with_issues =
-        Vec::with_capacity(EXPECTED_NUMBER_OF_DIR_ENTRIES / 4);
-    // TODO: Import multiple directory entries in parallel.
+    let mut entries = Vec::with_capacity(EXPECTED_NUMBER_OF_DIR_ENTRIES);
     for dir_entry in dir_entries {
         let dir_entry = match dir_entry {
             Ok(dir_entry) => dir_entry,
@@ -433,20 +496,9 @@ where
                 continue;
             }
         };
-        if abort_flag.load(Ordering::Relaxed) {
-            log::debug!(
-                "Aborting import before visiting {path}",
-                path = dir_entry.path().display()
-            );
-            return Ok(Outcome {
-                completion: Completion::Aborted,
-                summary,
-                visited_media_source_ids,
-                imported_media_sources_with_issues,
-            });
-        }
+        let file_path = dir_entry.path();
         let content_path = if let Some(content_path) =
-            Url::from_file_path(dir_entry.path()).ok().and_then(|url| {
+            Url::from_file_path(&file_path).ok().and_then(|url| {
                 content_path_resolver
                     .resolve_path_from_url(&url)
                     .ok()
@@ -456,47 +508,140 @@ where
         } else {
             log::warn!(
                 "Skipping invalid/unsupported directory entry: {path}",
-                path = dir_entry.path().display()
+                path = file_path.display()
             );
             // Skip entry and keep going
             continue;
         };
-        let context = ImportReplacementFromFilePathContext::load_from_repo(
-            repo,
-            collection_id,
-            content_path,
-        )?;
-        let outcome = import_replacement_from_file_path(
-            context,
-            content_path_resolver,
-            params,
-            intercept_imported_track_fn,
-        )?;
-        let ImportAndReplaceFromFilePathOutcome {
-            content_path,
-            import_issues,
-            media_source_id,
-        } = replace_after_imported_from_file_path(
-            outcome,
-            &mut summary,
-            repo,
-            content_path_resolver,
-        )?;
-        if let Some(media_source_id) = media_source_id {
-            visited_media_source_ids.push(media_source_id);
-            if !import_issues.is_empty() {
-                imported_media_sources_with_issues.push((
-                    media_source_id,
+        entries.push((content_path, file_path));
+    }
+    Ok(entries)
+}
+
+/// Callbacks for importing files from a directory.
+#[allow(missing_debug_implementations)]
+pub struct DirectoryImportHandlers<'a, InterceptImportedTrackFn, ReportFileProgressFn> {
+    pub intercept_imported_track_fn: &'a InterceptImportedTrackFn,
+
+    /// Receives the accumulated summary after each file has been imported.
+    pub report_file_progress_fn: &'a mut ReportFileProgressFn,
+
+    /// Checked before visiting each file.
+    pub abort_flag: &'a AtomicBool,
+}
+
+/// Import all files from a single directory.
+pub fn import_and_replace_by_local_file_path_from_directory_with_content_path_resolver<
+    InterceptImportedTrackFn,
+    ReportFileProgressFn,
+>(
+    repo: &mut (impl TrackCollectionRepo + MediaSourceCollectionRepo),
+    collection_id: CollectionId,
+    content_path_resolver: &VfsResolver,
+    source_dir_path: &ContentPath<'_>,
+    params: &Params,
+    handlers: &mut DirectoryImportHandlers<'_, InterceptImportedTrackFn, ReportFileProgressFn>,
+) -> Result<Outcome>
+where
+    InterceptImportedTrackFn: Fn(Track) -> Track + Send,
+    ReportFileProgressFn: FnMut(&ContentPath<'_>, &Summary),
+{
+    let DirectoryImportHandlers {
+        intercept_imported_track_fn,
+        report_file_progress_fn,
+        abort_flag,
+    } = handlers;
+    let dir_path = content_path_resolver.build_file_path(source_dir_path);
+    log::debug!(
+        "Importing files from directory: {dir_path}",
+        dir_path = dir_path.display()
+    );
+    let entries = read_dir_entries(content_path_resolver, &dir_path)?;
+    let files = entries
+        .iter()
+        .map(|(content_path, file_path)| (content_path, file_path.as_path()))
+        .collect::<Vec<_>>();
+    let content_digest_cache = if params.content_digest_workers.is_some() {
+        let content_paths = files
+            .iter()
+            .map(|(content_path, _)| *content_path)
+            .collect::<Vec<_>>();
+        ContentDigestCache::load_from_repo(repo, collection_id, &content_paths)?
+    } else {
+        ContentDigestCache::default()
+    };
+    // The content digests of subsequent files are computed in the background
+    // while importing the current file. Digests of files that have not been
+    // modified since their last import are reused.
+    with_content_digests(
+        &files,
+        &content_digest_cache,
+        params.content_digest_workers,
+        abort_flag,
+        |content_digests| {
+            let mut summary = Summary::default();
+            let mut visited_media_source_ids = Vec::with_capacity(entries.len());
+            let mut imported_media_sources_with_issues = Vec::with_capacity(entries.len() / 4);
+            // TODO: Import multiple directory entries in parallel.
+            for (content_path, file_path) in &entries {
+                if abort_flag.load(Ordering::Relaxed) {
+                    log::debug!(
+                        "Aborting import before visiting {path}",
+                        path = file_path.display()
+                    );
+                    return Ok(Outcome {
+                        completion: Completion::Aborted,
+                        summary,
+                        visited_media_source_ids,
+                        imported_media_sources_with_issues,
+                    });
+                }
+                let context = ImportReplacementFromFilePathContext::load_from_repo(
+                    repo,
+                    collection_id,
+                    content_path.clone(),
+                )?;
+                let content_digest = content_digests.next_digest();
+                let outcome = import_replacement_from_file_path(
+                    context,
+                    content_path_resolver,
+                    params,
+                    content_digest,
+                    intercept_imported_track_fn,
+                )?;
+                // Subdirectories are not imported and thus not reported.
+                let skipped_directory =
+                    matches!(outcome.replacement, Replacement::SkippedDirectory);
+                let ImportAndReplaceFromFilePathOutcome {
                     content_path,
                     import_issues,
-                ));
+                    media_source_id,
+                } = replace_after_imported_from_file_path(
+                    outcome,
+                    &mut summary,
+                    repo,
+                    content_path_resolver,
+                )?;
+                if !skipped_directory {
+                    report_file_progress_fn(&content_path, &summary);
+                }
+                if let Some(media_source_id) = media_source_id {
+                    visited_media_source_ids.push(media_source_id);
+                    if !import_issues.is_empty() {
+                        imported_media_sources_with_issues.push((
+                            media_source_id,
+                            content_path,
+                            import_issues,
+                        ));
+                    }
+                }
             }
-        }
-    }
-    Ok(Outcome {
-        completion: Completion::Finished,
-        summary,
-        visited_media_source_ids,
-        imported_media_sources_with_issues,
-    })
+            Ok(Outcome {
+                completion: Completion::Finished,
+                summary,
+                visited_media_source_ids,
+                imported_media_sources_with_issues,
+            })
+        },
+    )
 }
@@ -1,12 +1,14 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use nonicle::Canonicalize as _;
 use semval::prelude::*;
 
 use aoide_core::{track::TrackInvalidity, Track};
 
 use crate::InputResult;
 
+pub mod edit_tags;
 pub mod find_duplicates;
 pub mod purge;
 pub mod replace;
@@ -16,6 +18,9 @@ pub mod search;
 #[cfg(not(target_family = "wasm"))]
 pub mod find_unsynchronized;
 
+#[cfg(all(feature = "media-file", not(target_family = "wasm")))]
+pub mod bundle;
+
 #[cfg(all(feature = "media-file", not(target_family = "wasm")))]
 pub mod import_and_replace;
 
@@ -25,7 +30,9 @@ pub mod vfs;
 #[derive(Debug)]
 pub struct ValidatedInput(Track);
 
-pub fn validate_input(track: Track) -> InputResult<(ValidatedInput, Vec<TrackInvalidity>)> {
+pub fn validate_input(mut track: Track) -> InputResult<(ValidatedInput, Vec<TrackInvalidity>)> {
+    // Tracks are indexed and stored in canonical form.
+    track.canonicalize();
     // Many tracks are expected to be inconsistent and invalid to some
     // extent and we simply cannot reject all of them. The invalidities
     // are returned together with the validated input.
@@ -1,16 +1,16 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aoide_core::{Track, TrackEntity};
+use aoide_core::{media::content::ContentPath, Track, TrackEntity};
 use aoide_core_api::track::replace::Summary;
 use aoide_repo::{
     track::{CollectionRepo as TrackCollectionRepo, ReplaceMode, ReplaceOutcome, ReplaceParams},
-    CollectionId, MediaSourceId,
+    CollectionId, MediaSourceId, OptionalRepoResult as _,
 };
 
 use crate::Result;
 
-use super::ValidatedInput;
+use super::{validate_input, ValidatedInput};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[allow(clippy::struct_excessive_bools)]
@@ -133,6 +133,90 @@ where
     Ok(completion)
 }
 
+/// The action performed by [`replace_by_content_path()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaceAction {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+#[derive(Debug)]
+pub struct ReplaceByContentPathOutcome {
+    pub action: ReplaceAction,
+
+    /// The created, updated, or unchanged entity.
+    pub entity: TrackEntity,
+}
+
+/// Update the track at the given content path or create it.
+///
+/// The content path of the media source is replaced by `content_path`.
+///
+/// Existing tracks are not updated if their contents are equivalent
+/// to avoid bumping the revision, see [`Track::content_equivalent()`].
+/// Otherwise the revision is bumped while preserving the original
+/// `collected_at` time stamp.
+#[allow(clippy::missing_panics_doc)] // Never panics
+pub fn replace_by_content_path<Repo>(
+    repo: &mut Repo,
+    collection_id: CollectionId,
+    content_path: ContentPath<'static>,
+    mut track: Track,
+) -> Result<ReplaceByContentPathOutcome>
+where
+    Repo: TrackCollectionRepo,
+{
+    track.media_source.content.link.path = content_path;
+    let (validated_input, invalidities) = validate_input(track)?;
+    if !invalidities.is_empty() {
+        log::debug!("Replacing invalid track: {invalidities:?}");
+    }
+    let ValidatedInput(track) = &validated_input;
+    if let Some((_, _, entity)) = repo
+        .load_track_entity_by_media_source_content_path(
+            collection_id,
+            &track.media_source.content.link.path,
+        )
+        .optional()?
+    {
+        if entity.body.track.content_equivalent(track) {
+            return Ok(ReplaceByContentPathOutcome {
+                action: ReplaceAction::Unchanged,
+                entity,
+            });
+        }
+    }
+    let params = ReplaceParams {
+        mode: ReplaceMode::UpdateOrCreate,
+        preserve_collected_at: true,
+        update_last_synchronized_rev: false,
+    };
+    let outcome = match replace_collected_track_by_media_source_content_path(
+        repo,
+        collection_id,
+        params,
+        validated_input,
+    )? {
+        Outcome::Created(_, entity) => ReplaceByContentPathOutcome {
+            action: ReplaceAction::Created,
+            entity,
+        },
+        Outcome::Updated(_, entity) => ReplaceByContentPathOutcome {
+            action: ReplaceAction::Updated,
+            entity,
+        },
+        Outcome::Unchanged(_, entity) => ReplaceByContentPathOutcome {
+            action: ReplaceAction::Unchanged,
+            entity,
+        },
+        Outcome::NotCreated(_) | Outcome::NotUpdated(..) => {
+            unreachable!("neither create-only nor update-only")
+        }
+    };
+    Ok(outcome)
+}
+
 #[cfg(all(feature = "media-file", not(target_family = "wasm")))]
 pub fn replace_many_by_media_source_content_path<Repo>(
     repo: &mut Repo,
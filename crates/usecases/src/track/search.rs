@@ -5,7 +5,8 @@ use std::time::Instant;
 
 use aoide_core::track::Entity;
 use aoide_core_api::{
-    track::search::{Filter, Params, SortOrder},
+    filtering::MAX_NESTING_DEPTH,
+    track::search::{FieldGroups, Filter, Params, SortOrder},
     Pagination,
 };
 use aoide_repo::{
@@ -14,7 +15,18 @@ use aoide_repo::{
     CollectionId, RepoResult, ReservableRecordCollector,
 };
 
-use crate::Result;
+use crate::{InputError, InputResult, Result};
+
+/// Reject filters that are nested too deeply.
+pub fn validate_filter(filter: &Filter) -> InputResult<()> {
+    let nesting_depth = filter.nesting_depth();
+    if nesting_depth > MAX_NESTING_DEPTH {
+        return Err(InputError(anyhow::anyhow!(
+            "filter nesting depth {nesting_depth} exceeds the maximum of {MAX_NESTING_DEPTH}"
+        )));
+    }
+    Ok(())
+}
 
 pub fn search<Repo>(
     repo: &mut Repo,
@@ -22,13 +34,21 @@ pub fn search<Repo>(
     pagination: &Pagination,
     filter: Option<&Filter>,
     ordering: &[SortOrder],
+    field_groups: FieldGroups,
     collector: &mut impl ReservableRecordCollector<Header = RecordHeader, Record = Entity>,
 ) -> RepoResult<usize>
 where
     Repo: TrackCollectionRepo,
 {
     let timed = Instant::now();
-    let num_tracks = repo.search_tracks(collection_id, pagination, filter, ordering, collector)?;
+    let num_tracks = repo.search_tracks_with_field_groups(
+        collection_id,
+        pagination,
+        filter,
+        ordering,
+        field_groups,
+        collector,
+    )?;
     log::debug!(
         "Search returned {num_tracks} track(s) and took {elapsed_millis} ms",
         elapsed_millis = timed.elapsed().as_secs_f64() * 1000.0,
@@ -52,8 +72,12 @@ where
     let Params {
         resolve_url_from_content_path,
         filter,
+        field_groups,
         ordering,
     } = params;
+    if let Some(filter) = filter {
+        validate_filter(filter)?;
+    }
     let collection_ctx = crate::collection::vfs::RepoContext::resolve_override(
         repo,
         collection_uid,
@@ -81,6 +105,7 @@ where
             pagination,
             filter.as_ref(),
             ordering,
+            *field_groups,
             &mut collector,
         )
     } else {
@@ -90,6 +115,7 @@ where
             pagination,
             filter.as_ref(),
             ordering,
+            *field_groups,
             collector,
         )
     }
@@ -110,15 +136,35 @@ where
     let Params {
         resolve_url_from_content_path,
         filter,
+        field_groups,
         ordering,
     } = params;
+    if let Some(filter) = &filter {
+        validate_filter(filter)?;
+    }
     let collection_id = repo.resolve_collection_id(collection_uid)?;
     if resolve_url_from_content_path.is_some() {
         // TODO: Support relative paths for URLs?
         log::warn!("Ignoring unsupported parameter {resolve_url_from_content_path:?}");
-        search(repo, collection_id, pagination, filter, ordering, collector)
+        search(
+            repo,
+            collection_id,
+            pagination,
+            filter,
+            ordering,
+            field_groups,
+            collector,
+        )
     } else {
-        search(repo, collection_id, pagination, filter, ordering, collector)
+        search(
+            repo,
+            collection_id,
+            pagination,
+            filter,
+            ordering,
+            field_groups,
+            collector,
+        )
     }
     .map_err(Into::into)
 }
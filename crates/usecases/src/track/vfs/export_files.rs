@@ -19,7 +19,10 @@ use aoide_core::{
     util::url::BaseUrl,
     CollectionUid, TrackEntity,
 };
-use aoide_core_api::{track::search::Filter, Pagination};
+use aoide_core_api::{
+    track::search::{FieldGroups, Filter},
+    Pagination,
+};
 use aoide_repo::{RecordCollector, ReservableRecordCollector};
 
 use crate::{Error, Result};
@@ -321,6 +324,7 @@ where
             &pagination,
             filter,
             unordered,
+            FieldGroups::all(),
             &mut exporter,
         )?;
         let total_count = exporter.outcome.total_count();
@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// Process items on a bounded number of scoped worker threads.
+///
+/// Each worker repeatedly takes the next unprocessed item and sends the
+/// result of `process_fn` together with the index of the item. Items for
+/// which `process_fn` returns `None` are skipped. The results are consumed
+/// by `consume_fn` on the current thread in the order in which they have
+/// been finished, i.e. not necessarily in the order of the items.
+///
+/// All workers stop when `abort_flag` is set or when `consume_fn` returns.
+pub(crate) fn with_worker_pool<I, R, T>(
+    items: &[I],
+    worker_count: usize,
+    abort_flag: &AtomicBool,
+    process_fn: impl Fn(&I) -> Option<R> + Sync,
+    consume_fn: impl FnOnce(mpsc::Receiver<(usize, R)>) -> T,
+) -> T
+where
+    I: Sync,
+    R: Send,
+{
+    let next_index = AtomicUsize::new(0);
+    let consumed_flag = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..worker_count.min(items.len()) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+            let consumed_flag = &consumed_flag;
+            let process_fn = &process_fn;
+            scope.spawn(move || loop {
+                if abort_flag.load(Ordering::Relaxed) || consumed_flag.load(Ordering::Relaxed) {
+                    break;
+                }
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let Some(result) = process_fn(item) else {
+                    continue;
+                };
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        // Only the workers keep the channel open
+        drop(sender);
+        let result = consume_fn(receiver);
+        // Stop all workers that are still running before joining them
+        consumed_flag.store(true, Ordering::Relaxed);
+        result
+    })
+}
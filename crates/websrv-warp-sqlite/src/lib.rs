@@ -1,19 +1,35 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{convert::Infallible, error::Error as StdError, result::Result as StdResult};
+use std::{
+    convert::Infallible,
+    error::Error as StdError,
+    future::Future,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    result::Result as StdResult,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, Instant},
+};
 
 use serde::Serialize;
 use thiserror::Error;
+use tracing::Level;
 use warp::{
     body::BodyDeserializeError,
-    http::StatusCode,
+    http::{HeaderMap, Method, StatusCode},
+    path::FullPath,
     reject::{self, InvalidHeader, InvalidQuery, MethodNotAllowed, Reject, Rejection},
-    Reply,
+    Filter, Reply,
 };
 
 use aoide_backend_webapi_json as api;
-use aoide_repo::RepoError;
+use aoide_core::{EntityRevision, EntityRevisionValue};
+use aoide_repo::{EntityContext, RepoError};
 use aoide_storage_sqlite::{
     self as db,
     connection::pool::{gatekeeper::Gatekeeper as DatabaseConnectionGatekeeper, PooledConnection},
@@ -25,11 +41,19 @@ pub enum Error {
     #[error(transparent)]
     BadRequest(anyhow::Error),
 
+    /// The request is valid but not permitted, e.g. modifying
+    /// a database that has been opened in read-only mode.
+    #[error(transparent)]
+    Forbidden(anyhow::Error),
+
     #[error("not found")]
-    NotFound,
+    NotFound { entity: Option<EntityContext> },
 
     #[error("conflict")]
-    Conflict,
+    Conflict {
+        entity: Option<EntityContext>,
+        current_rev: EntityRevision,
+    },
 
     #[error("service unavailable")]
     ServiceUnavailable,
@@ -62,8 +86,14 @@ impl From<uc::Error> for Error {
             From::MediaFile(err) => Self::Other(err.into()),
             From::Storage(err) => err.into(),
             From::Repository(err) => match err {
-                RepoError::NotFound => Self::NotFound,
-                RepoError::Conflict => Self::Conflict,
+                RepoError::NotFound { entity } => Self::NotFound { entity },
+                RepoError::Conflict {
+                    entity,
+                    current_rev,
+                } => Self::Conflict {
+                    entity,
+                    current_rev,
+                },
                 RepoError::Aborted => Self::ServiceUnavailable,
                 RepoError::Other(err) => Self::Other(err),
             },
@@ -81,6 +111,7 @@ impl From<db::Error> for Error {
             From::DatabaseConnectionPool(err) => Self::Other(err.into()),
             From::TaskScheduling(err) => Self::Other(err.into()),
             From::TaskTimeout { reason } => Self::Timeout { reason },
+            err @ From::ReadOnly => Self::Forbidden(err.into()),
             From::Other(err) => Self::Other(err),
         }
     }
@@ -105,8 +136,97 @@ where
         .and_then(|res| res.map_err(reject_on_error))
 }
 
+/// Reject a request that has not been handled before the deadline.
+///
+/// The rejection results in a `408 Request Timeout` response when
+/// recovered by [`handle_rejection()`]. Before rejecting the request
+/// the `abort_flag` is set to signal pending blocking tasks that they
+/// should stop instead of continuing in the background.
+pub async fn with_request_timeout<T>(
+    request_timeout: Duration,
+    abort_flag: &AtomicBool,
+    handler: impl Future<Output = std::result::Result<T, Rejection>>,
+) -> std::result::Result<T, Rejection> {
+    tokio::time::timeout(request_timeout, handler)
+        .await
+        .unwrap_or_else(|_| {
+            log::warn!(
+                "Aborting request after {request_timeout_millis} ms",
+                request_timeout_millis = request_timeout.as_millis()
+            );
+            abort_flag.store(true, Ordering::Relaxed);
+            Err(reject_on_error(Error::Timeout {
+                reason: "request timed out".to_string(),
+            }))
+        })
+}
+
+/// Spawns blocking database tasks for handling requests
+///
+/// The optional request timeout bounds the total time for handling a
+/// request, i.e. both acquiring a database connection and running the
+/// task. It is independent of the timeouts for acquiring a connection
+/// that are enforced by the [`DatabaseConnectionGatekeeper`].
+///
+/// Each abortable request gets its own abort flag. A timeout only
+/// aborts the task of the affected request.
+#[allow(missing_debug_implementations)]
+pub struct RequestGatekeeper {
+    connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
+    request_timeout: Option<Duration>,
+    pending_abort_flags: Mutex<Vec<Weak<AtomicBool>>>,
+}
+
+impl RequestGatekeeper {
+    #[must_use]
+    pub const fn new(
+        connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
+        request_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            connection_gatekeeper,
+            request_timeout,
+            pending_abort_flags: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn connection_gatekeeper(&self) -> &DatabaseConnectionGatekeeper {
+        &self.connection_gatekeeper
+    }
+
+    /// Abort all pending, abortable tasks.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn abort_pending_tasks(&self) {
+        let pending_abort_flags = self.pending_abort_flags.lock().unwrap();
+        for abort_flag in pending_abort_flags.iter().filter_map(Weak::upgrade) {
+            abort_flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn new_pending_abort_flag(&self) -> Arc<AtomicBool> {
+        let abort_flag = Arc::new(AtomicBool::new(false));
+        let mut pending_abort_flags = self.pending_abort_flags.lock().unwrap();
+        // Forget about the flags of finished requests
+        pending_abort_flags.retain(|abort_flag| abort_flag.strong_count() > 0);
+        pending_abort_flags.push(Arc::downgrade(&abort_flag));
+        abort_flag
+    }
+
+    async fn handle_request<T>(
+        &self,
+        abort_flag: &AtomicBool,
+        handler: impl Future<Output = std::result::Result<T, Rejection>>,
+    ) -> std::result::Result<T, Rejection> {
+        let Some(request_timeout) = self.request_timeout else {
+            return handler.await;
+        };
+        with_request_timeout(request_timeout, abort_flag, handler).await
+    }
+}
+
 pub async fn spawn_blocking_write_task<H, T, E>(
-    gatekeeper: &DatabaseConnectionGatekeeper,
+    gatekeeper: &RequestGatekeeper,
     handler: H,
 ) -> std::result::Result<T, Rejection>
 where
@@ -114,11 +234,14 @@ where
     T: Send + 'static,
     E: Into<Error> + Send + 'static,
 {
-    after_blocking_task_finished(gatekeeper.spawn_blocking_write_task(handler).await)
+    spawn_blocking_abortable_write_task(gatekeeper, |pooled_connection, _abort_flag| {
+        handler(pooled_connection)
+    })
+    .await
 }
 
 pub async fn spawn_blocking_read_task<H, T, E>(
-    gatekeeper: &DatabaseConnectionGatekeeper,
+    gatekeeper: &RequestGatekeeper,
     handler: H,
 ) -> std::result::Result<T, Rejection>
 where
@@ -126,11 +249,77 @@ where
     T: Send + 'static,
     E: Into<Error> + Send + 'static,
 {
-    after_blocking_task_finished(gatekeeper.spawn_blocking_read_task(handler).await)
+    spawn_blocking_abortable_read_task(gatekeeper, |pooled_connection, _abort_flag| {
+        handler(pooled_connection)
+    })
+    .await
+}
+
+/// Spawns a blocking write task that receives the abort flag of the request.
+///
+/// The handler runs within the tracing span of the request, i.e. its
+/// log output is correlated by the request ID of [`with_request_id()`].
+///
+/// The flag is set when the request times out or when all pending
+/// tasks are aborted by [`RequestGatekeeper::abort_pending_tasks()`].
+pub async fn spawn_blocking_abortable_write_task<H, T, E>(
+    gatekeeper: &RequestGatekeeper,
+    handler: H,
+) -> std::result::Result<T, Rejection>
+where
+    H: FnOnce(PooledConnection, &AtomicBool) -> std::result::Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: Into<Error> + Send + 'static,
+{
+    let abort_flag = gatekeeper.new_pending_abort_flag();
+    // The handler inherits the span of the request with the request ID
+    let span = tracing::Span::current();
+    gatekeeper
+        .handle_request(&abort_flag, async {
+            let task_abort_flag = Arc::clone(&abort_flag);
+            after_blocking_task_finished(
+                gatekeeper
+                    .connection_gatekeeper
+                    .spawn_blocking_write_task(move |pooled_connection| {
+                        span.in_scope(|| handler(pooled_connection, &task_abort_flag))
+                    })
+                    .await,
+            )
+        })
+        .await
+}
+
+/// Spawns a blocking read task that receives the abort flag of the request.
+///
+/// See also [`spawn_blocking_abortable_write_task()`].
+pub async fn spawn_blocking_abortable_read_task<H, T, E>(
+    gatekeeper: &RequestGatekeeper,
+    handler: H,
+) -> std::result::Result<T, Rejection>
+where
+    H: FnOnce(PooledConnection, &AtomicBool) -> std::result::Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: Into<Error> + Send + 'static,
+{
+    let abort_flag = gatekeeper.new_pending_abort_flag();
+    // The handler inherits the span of the request with the request ID
+    let span = tracing::Span::current();
+    gatekeeper
+        .handle_request(&abort_flag, async {
+            let task_abort_flag = Arc::clone(&abort_flag);
+            after_blocking_task_finished(
+                gatekeeper
+                    .connection_gatekeeper
+                    .spawn_blocking_read_task(move |pooled_connection| {
+                        span.in_scope(|| handler(pooled_connection, &task_abort_flag))
+                    })
+                    .await,
+            )
+        })
+        .await
 }
 
 #[derive(Debug)]
-#[expect(dead_code, reason = "False positive.")]
 struct CustomReject {
     code: StatusCode,
     message: String,
@@ -144,6 +333,13 @@ impl Reject for CustomReject {}
 struct ErrorResponseBody {
     code: u16,
     message: String,
+
+    /// The current revision of a conflicting entity.
+    ///
+    /// Clients are supposed to re-fetch the entity and retry the
+    /// request with this revision.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_rev: Option<EntityRevisionValue>,
 }
 
 fn status_code_to_string(code: StatusCode) -> String {
@@ -156,6 +352,7 @@ fn status_code_to_string(code: StatusCode) -> String {
 pub async fn handle_rejection(reject: Rejection) -> StdResult<impl Reply, Infallible> {
     let code;
     let message;
+    let mut expected_rev = None;
 
     if reject.is_not_found() {
         code = StatusCode::NOT_FOUND;
@@ -188,13 +385,27 @@ pub async fn handle_rejection(reject: Rejection) -> StdResult<impl Reply, Infall
                 code = StatusCode::BAD_REQUEST;
                 message = err.to_string();
             }
-            Error::NotFound => {
+            Error::Forbidden(err) => {
+                code = StatusCode::FORBIDDEN;
+                message = err.to_string();
+            }
+            Error::NotFound { entity } => {
                 code = StatusCode::NOT_FOUND;
-                message = status_code_to_string(code);
+                message = entity.as_ref().map_or_else(
+                    || status_code_to_string(code),
+                    |entity| format!("{entity} not found"),
+                );
             }
-            Error::Conflict => {
+            Error::Conflict {
+                entity,
+                current_rev,
+            } => {
                 code = StatusCode::CONFLICT;
-                message = status_code_to_string(code);
+                message = entity.as_ref().map_or_else(
+                    || status_code_to_string(code),
+                    |entity| format!("conflicting revision of {entity}"),
+                );
+                expected_rev = Some(current_rev.value());
             }
             Error::ServiceUnavailable => {
                 code = StatusCode::SERVICE_UNAVAILABLE;
@@ -223,7 +434,335 @@ pub async fn handle_rejection(reject: Rejection) -> StdResult<impl Reply, Infall
     let json_reply = warp::reply::json(&ErrorResponseBody {
         code: code.as_u16(),
         message,
+        expected_rev,
     });
 
     Ok(warp::reply::with_status(json_reply, code))
 }
+
+/// HTTP header for confirming a shutdown request.
+pub const SHUTDOWN_CONFIRMATION_TOKEN_HEADER: &str = "x-shutdown-token";
+
+/// Compare secrets without revealing the position of the first mismatch.
+fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    lhs.iter()
+        .zip(rhs)
+        .fold(0, |diff, (lhs, rhs)| diff | (lhs ^ rhs))
+        == 0
+}
+
+/// The `POST /shutdown` route.
+///
+/// The route is disabled and rejected as not found if no
+/// `confirmation_token` is configured. Requests that don't provide
+/// the matching token in the [`SHUTDOWN_CONFIRMATION_TOKEN_HEADER`]
+/// are rejected as forbidden.
+///
+/// Confirmed requests are forwarded to `request_shutdown` that
+/// returns the response status code.
+pub fn shutdown_with_confirmation_token<F>(
+    confirmation_token: Option<String>,
+    request_shutdown: F,
+) -> impl Filter<Extract = (StatusCode,), Error = Rejection> + Clone
+where
+    F: Fn() -> StatusCode + Clone + Send + Sync + 'static,
+{
+    let confirmation_token: Option<Arc<str>> = confirmation_token.map(Into::into);
+    warp::post()
+        .and(warp::path("shutdown"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<String>(
+            SHUTDOWN_CONFIRMATION_TOKEN_HEADER,
+        ))
+        .and_then(move |token: Option<String>| {
+            let confirmation_token = confirmation_token.clone();
+            let request_shutdown = request_shutdown.clone();
+            async move {
+                let Some(confirmation_token) = confirmation_token else {
+                    return Err(reject::not_found());
+                };
+                if !token.is_some_and(|token| {
+                    constant_time_eq(token.as_bytes(), confirmation_token.as_bytes())
+                }) {
+                    log::warn!("Rejecting unconfirmed shutdown request");
+                    return Err(reject::custom(CustomReject {
+                        code: StatusCode::FORBIDDEN,
+                        message: "missing or invalid shutdown confirmation token".to_owned(),
+                    }));
+                }
+                Ok(request_shutdown())
+            }
+        })
+}
+
+/// HTTP header for correlating requests and responses.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longer request IDs are replaced by a generated ID.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+#[must_use]
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+fn request_id_from_headers(headers: &HeaderMap) -> Option<String> {
+    // Only visible ASCII characters are accepted
+    let request_id = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    (!request_id.is_empty() && request_id.len() <= MAX_REQUEST_ID_LEN)
+        .then(|| request_id.to_owned())
+}
+
+/// Correlate requests and responses by a request ID.
+///
+/// Reuses the ID from the `X-Request-Id` header of the request or
+/// generates a new one. The ID is recorded in the tracing span of the
+/// request and echoed in the response headers.
+///
+/// Should wrap all other filters, including the recovery by
+/// [`handle_rejection()`] to cover error responses.
+pub fn with_request_id<F, R>(
+    filter: F,
+) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone
+where
+    F: Filter<Extract = (R,), Error = Infallible> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    warp::header::headers_cloned()
+        .map(|headers: HeaderMap| {
+            let request_id = request_id_from_headers(&headers).unwrap_or_else(new_request_id);
+            tracing::Span::current().record("request_id", request_id.as_str());
+            request_id
+        })
+        .and(filter)
+        .map(|request_id: String, reply: R| {
+            warp::reply::with_header(reply, REQUEST_ID_HEADER, request_id)
+        })
+        .with(warp::trace(|info| {
+            tracing::info_span!(
+                "request",
+                method = %info.method(),
+                path = info.path(),
+                request_id = tracing::field::Empty,
+            )
+        }))
+}
+
+/// The tracing target of access log events.
+pub const ACCESS_LOG_TARGET: &str = "access";
+
+/// Log levels of access log events by the class of the response status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessLogLevels {
+    /// Informational, successful, and redirection responses (1xx, 2xx, 3xx).
+    pub success: Level,
+
+    /// Client errors (4xx).
+    pub client_error: Level,
+
+    /// Server errors (5xx).
+    pub server_error: Level,
+}
+
+impl AccessLogLevels {
+    #[must_use]
+    pub fn level_for_status(&self, status: StatusCode) -> Level {
+        if status.is_server_error() {
+            self.server_error
+        } else if status.is_client_error() {
+            self.client_error
+        } else {
+            self.success
+        }
+    }
+}
+
+impl Default for AccessLogLevels {
+    fn default() -> Self {
+        Self {
+            success: Level::INFO,
+            client_error: Level::WARN,
+            server_error: Level::ERROR,
+        }
+    }
+}
+
+fn log_access(
+    level: Level,
+    method: &Method,
+    path: &str,
+    status: StatusCode,
+    latency: Duration,
+    request_id: &str,
+) {
+    let status = status.as_u16();
+    let latency_ms = latency.as_secs_f64() * 1000.0;
+    macro_rules! access_event {
+        ($level:path) => {
+            tracing::event!(
+                target: ACCESS_LOG_TARGET,
+                $level,
+                %method,
+                path,
+                status,
+                latency_ms,
+                request_id,
+                "Request handled"
+            )
+        };
+    }
+    match level {
+        Level::ERROR => access_event!(Level::ERROR),
+        Level::WARN => access_event!(Level::WARN),
+        Level::INFO => access_event!(Level::INFO),
+        Level::DEBUG => access_event!(Level::DEBUG),
+        _ => access_event!(Level::TRACE),
+    }
+}
+
+/// Emit a single access log event per request.
+///
+/// The event contains the method, path, status, latency, and request ID
+/// of the request, but neither the query nor the body. The level depends
+/// on the response status.
+///
+/// Should wrap [`with_request_id()`] for picking up the request ID
+/// from the response headers.
+pub fn with_access_log<F, R>(
+    filter: F,
+    levels: AccessLogLevels,
+) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone
+where
+    F: Filter<Extract = (R,), Error = Infallible> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    warp::method()
+        .and(warp::path::full())
+        .map(|method: Method, path: FullPath| (method, path, Instant::now()))
+        .and(filter)
+        .map(
+            move |(method, path, started_at): (Method, FullPath, Instant), reply: R| {
+                let response = reply.into_response();
+                let latency = started_at.elapsed();
+                let status = response.status();
+                let request_id = response
+                    .headers()
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default();
+                log_access(
+                    levels.level_for_status(status),
+                    &method,
+                    path.as_str(),
+                    status,
+                    latency,
+                    request_id,
+                );
+                response
+            },
+        )
+}
+
+/// The file that is served for unknown paths by
+/// [`static_files_with_spa_fallback()`].
+pub const SPA_INDEX_FILE_NAME: &str = "index.html";
+
+/// Serve static files from a directory, e.g. a single-page application.
+///
+/// Requests for files that don't exist are answered with the
+/// [`SPA_INDEX_FILE_NAME`] from the directory to support client-side
+/// routing via the history API. Paths starting with one of the
+/// `reserved_path_segments` like `api` are rejected instead.
+///
+/// Should be combined with all other routes using `or()` as the very
+/// last alternative, otherwise it would shadow them.
+#[must_use]
+pub fn static_files_with_spa_fallback(
+    dir_path: PathBuf,
+    reserved_path_segments: &'static [&'static str],
+) -> impl Filter<Extract = (warp::fs::File,), Error = Rejection> + Clone {
+    let index_file_path = dir_path.join(SPA_INDEX_FILE_NAME);
+    let fallback = warp::path::full()
+        .and_then(move |path: FullPath| async move {
+            let first_segment = path.as_str().trim_start_matches('/').split('/').next();
+            if first_segment.is_some_and(|segment| reserved_path_segments.contains(&segment)) {
+                return Err(reject::not_found());
+            }
+            Ok(())
+        })
+        .untuple_one()
+        .and(warp::fs::file(index_file_path));
+    warp::get().and(warp::fs::dir(dir_path).or(fallback).unify())
+}
+
+/// PEM files for terminating TLS connections.
+#[derive(Debug, Clone, Copy)]
+pub struct TlsFiles<'a> {
+    /// The certificate chain.
+    pub cert_path: &'a Path,
+
+    /// The private key of the certificate.
+    pub key_path: &'a Path,
+}
+
+pub type ServerFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Bind a server that runs until the shutdown signal has been received.
+///
+/// Serves HTTPS if `tls_files` are provided and plain HTTP otherwise.
+/// Serving HTTPS requires the `tls` feature. Fails without binding the
+/// socket if one of the TLS files doesn't exist.
+///
+/// Returns the actual address the server is bound to, e.g. when using
+/// an ephemeral port.
+pub fn bind_server<F>(
+    filter: F,
+    socket_addr: SocketAddr,
+    tls_files: Option<TlsFiles<'_>>,
+    shutdown_signal: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<(SocketAddr, ServerFuture)>
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let server = warp::serve(filter);
+    let Some(TlsFiles {
+        cert_path,
+        key_path,
+    }) = tls_files
+    else {
+        let (socket_addr, server_future) =
+            server.try_bind_with_graceful_shutdown(socket_addr, shutdown_signal)?;
+        return Ok((socket_addr, Box::pin(server_future)));
+    };
+    #[cfg(feature = "tls")]
+    {
+        // Fail early without binding the socket
+        for (file_path, what) in [(cert_path, "certificate"), (key_path, "private key")] {
+            anyhow::ensure!(
+                file_path.is_file(),
+                "TLS {what} file {file_path} not found",
+                file_path = file_path.display(),
+            );
+        }
+        let (socket_addr, server_future) = server
+            .tls()
+            .cert_path(cert_path)
+            .key_path(key_path)
+            .try_bind_with_graceful_shutdown(socket_addr, shutdown_signal)?;
+        Ok((socket_addr, Box::pin(server_future)))
+    }
+    #[cfg(not(feature = "tls"))]
+    {
+        drop((server, shutdown_signal));
+        anyhow::bail!(
+            "TLS support is not enabled: cannot serve HTTPS with certificate {cert_path} and \
+             private key {key_path}",
+            cert_path = cert_path.display(),
+            key_path = key_path.display(),
+        );
+    }
+}
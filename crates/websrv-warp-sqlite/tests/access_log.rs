@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+use warp::{http::StatusCode, Filter, Reply};
+
+use aoide_websrv_warp_sqlite::{
+    handle_rejection, with_access_log, with_request_id, AccessLogLevels, ACCESS_LOG_TARGET,
+};
+
+#[derive(Debug)]
+struct CapturedEvent {
+    level: Level,
+    fields: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+struct FieldsVisitor(BTreeMap<String, String>);
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_owned(), format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+}
+
+#[derive(Clone, Default)]
+struct CaptureAccessEvents(Arc<Mutex<Vec<CapturedEvent>>>);
+
+impl<S: Subscriber> Layer<S> for CaptureAccessEvents {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != ACCESS_LOG_TARGET {
+            return;
+        }
+        let mut visitor = FieldsVisitor::default();
+        event.record(&mut visitor);
+        self.0.lock().unwrap().push(CapturedEvent {
+            level: *event.metadata().level(),
+            fields: visitor.0,
+        });
+    }
+}
+
+fn filter() -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone + 'static {
+    with_access_log(
+        with_request_id(
+            warp::path("ok")
+                .and(warp::path::end())
+                .map(warp::reply)
+                .recover(handle_rejection),
+        ),
+        AccessLogLevels::default(),
+    )
+}
+
+async fn request_and_capture_access_event(path: &str, request_id: &str) -> CapturedEvent {
+    let capture = CaptureAccessEvents::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+    warp::test::request()
+        .path(path)
+        .header("X-Request-Id", request_id)
+        .body("secret body")
+        .reply(&filter())
+        .await;
+    let mut events = std::mem::take(&mut *capture.0.lock().unwrap());
+    assert_eq!(1, events.len());
+    events.pop().unwrap()
+}
+
+#[tokio::test]
+async fn log_successful_request() {
+    let event = request_and_capture_access_event("/ok?query=secret", "client-1").await;
+    assert_eq!(Level::INFO, event.level);
+    assert_eq!(Some("GET"), event.fields.get("method").map(String::as_str));
+    assert_eq!(Some("/ok"), event.fields.get("path").map(String::as_str));
+    assert_eq!(Some("200"), event.fields.get("status").map(String::as_str));
+    assert_eq!(
+        Some("client-1"),
+        event.fields.get("request_id").map(String::as_str)
+    );
+    assert!(event.fields.contains_key("latency_ms"));
+    // Neither the query nor the body must be logged.
+    assert!(event.fields.values().all(|value| !value.contains("secret")));
+}
+
+#[tokio::test]
+async fn log_failing_request() {
+    let event = request_and_capture_access_event("/missing", "client-2").await;
+    assert_eq!(Level::WARN, event.level);
+    assert_eq!(Some("GET"), event.fields.get("method").map(String::as_str));
+    assert_eq!(
+        Some("/missing"),
+        event.fields.get("path").map(String::as_str)
+    );
+    assert_eq!(
+        Some(StatusCode::NOT_FOUND.as_str()),
+        event.fields.get("status").map(String::as_str)
+    );
+    assert_eq!(
+        Some("client-2"),
+        event.fields.get("request_id").map(String::as_str)
+    );
+    assert!(event.fields.contains_key("latency_ms"));
+}
@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::sync::{Arc, Mutex};
+
+use warp::{http::StatusCode, Filter as _};
+
+use aoide_core::{
+    collection::MediaSourceConfig,
+    media::content::{ContentPathConfig, VirtualFilePathConfig},
+    util::url::BaseUrl,
+    Collection,
+};
+use aoide_repo_sqlite::{initialize_database, DbConnection};
+use aoide_storage_sqlite::connection::{establish_connection, Storage};
+use aoide_usecases_sqlite as uc;
+use aoide_websrv_warp_sqlite::{after_blocking_task_finished, handle_rejection, Error};
+
+fn new_collection(title: &str) -> Collection {
+    Collection {
+        title: title.into(),
+        notes: None,
+        kind: None,
+        color: None,
+        media_source_config: MediaSourceConfig {
+            content_path: ContentPathConfig::VirtualFilePath(VirtualFilePathConfig {
+                root_url: BaseUrl::parse_strict("file:///home/test/music/").unwrap(),
+                excluded_paths: vec![],
+            }),
+        },
+    }
+}
+
+fn establish_initialized_connection() -> DbConnection {
+    let mut connection = establish_connection(&Storage::InMemory, &Default::default()).unwrap();
+    initialize_database(&mut connection).unwrap();
+    uc::database::migrate_schema(&mut connection).unwrap();
+    connection
+}
+
+#[tokio::test]
+async fn stale_update_returns_conflict_with_current_revision() {
+    let mut connection = establish_initialized_connection();
+    let created_entity =
+        uc::collection::create(&mut connection, new_collection("Collection")).unwrap();
+    let updated_entity = uc::collection::update(
+        &mut connection,
+        created_entity.hdr.clone(),
+        new_collection("Updated Collection"),
+    )
+    .unwrap();
+    let connection = Arc::new(Mutex::new(connection));
+
+    // Update the collection again with the outdated header of the created entity.
+    let stale_header = created_entity.hdr.clone();
+    let filter = warp::path("collection")
+        .and(warp::path::end())
+        .and_then(move || {
+            let connection = Arc::clone(&connection);
+            let stale_header = stale_header.clone();
+            async move {
+                let res = uc::collection::update(
+                    &mut connection.lock().unwrap(),
+                    stale_header,
+                    new_collection("Stale Collection"),
+                );
+                after_blocking_task_finished(Ok::<_, Error>(res))
+                    .map(|entity| warp::reply::json(&entity.hdr.rev.value()))
+            }
+        })
+        .recover(handle_rejection);
+
+    let response = warp::test::request()
+        .path("/collection")
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::CONFLICT, response.status());
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "code": 409,
+            "message": format!("conflicting revision of collection {}", created_entity.hdr.uid),
+            "expectedRev": updated_entity.hdr.rev.value(),
+        }),
+        body
+    );
+}
@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::sync::{Arc, Mutex};
+
+use warp::{http::StatusCode, Filter as _};
+
+use aoide_core::{EntityUid, TrackUid};
+use aoide_repo_sqlite::{initialize_database, DbConnection};
+use aoide_storage_sqlite::connection::{establish_connection, Storage};
+use aoide_usecases_sqlite as uc;
+use aoide_websrv_warp_sqlite::{after_blocking_task_finished, handle_rejection, Error};
+
+fn establish_initialized_connection() -> DbConnection {
+    let mut connection = establish_connection(&Storage::InMemory, &Default::default()).unwrap();
+    initialize_database(&mut connection).unwrap();
+    uc::database::migrate_schema(&mut connection).unwrap();
+    connection
+}
+
+#[tokio::test]
+async fn load_missing_track_returns_not_found_with_uid() {
+    let connection = Arc::new(Mutex::new(establish_initialized_connection()));
+    let missing_uid = TrackUid::from_untyped(EntityUid::new());
+
+    let filter = {
+        let missing_uid = missing_uid.clone();
+        warp::path("track")
+            .and(warp::path::end())
+            .and_then(move || {
+                let connection = Arc::clone(&connection);
+                let missing_uid = missing_uid.clone();
+                async move {
+                    let res =
+                        uc::track::load::load_one(&mut connection.lock().unwrap(), &missing_uid);
+                    after_blocking_task_finished(Ok::<_, Error>(res))
+                        .map(|entity| warp::reply::json(&entity.hdr.rev.value()))
+                }
+            })
+            .recover(handle_rejection)
+    };
+
+    let response = warp::test::request().path("/track").reply(&filter).await;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "code": 404,
+            "message": format!("track {missing_uid} not found"),
+        }),
+        body
+    );
+}
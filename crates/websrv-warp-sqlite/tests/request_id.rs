@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::convert::Infallible;
+
+use warp::{http::StatusCode, Filter, Reply};
+
+use aoide_websrv_warp_sqlite::{handle_rejection, with_request_id, REQUEST_ID_HEADER};
+
+fn filter() -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone + 'static {
+    with_request_id(
+        warp::path("ok")
+            .and(warp::path::end())
+            .map(warp::reply)
+            .recover(handle_rejection),
+    )
+}
+
+#[tokio::test]
+async fn generate_request_id() {
+    let response = warp::test::request().path("/ok").reply(&filter()).await;
+    assert_eq!(StatusCode::OK, response.status());
+    let request_id = response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .expect("request ID")
+        .to_str()
+        .unwrap();
+    assert!(!request_id.is_empty());
+}
+
+#[tokio::test]
+async fn echo_supplied_request_id() {
+    let response = warp::test::request()
+        .path("/ok")
+        .header("X-Request-Id", "client-42")
+        .reply(&filter())
+        .await;
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        Some("client-42"),
+        response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+    );
+}
+
+#[tokio::test]
+async fn echo_supplied_request_id_on_error_response() {
+    let response = warp::test::request()
+        .path("/missing")
+        .header("X-Request-Id", "client-43")
+        .reply(&filter())
+        .await;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+    assert_eq!(
+        Some("client-43"),
+        response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+    );
+}
@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    convert::Infallible,
+    fmt,
+    num::{NonZeroU32, NonZeroU64},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+use warp::{Filter, Reply};
+
+use aoide_storage_sqlite::connection::{
+    pool::{
+        create_connection_pool,
+        gatekeeper::{Config, Gatekeeper},
+    },
+    Storage,
+};
+use aoide_websrv_warp_sqlite::{
+    handle_rejection, spawn_blocking_read_task, with_request_id, Error, RequestGatekeeper,
+    REQUEST_ID_HEADER,
+};
+
+const HANDLER_MESSAGE: &str = "Handling request";
+
+struct RequestId(String);
+
+#[derive(Default)]
+struct RequestIdVisitor(Option<String>);
+
+impl Visit for RequestIdVisitor {
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "request_id" {
+            self.0 = Some(value.to_owned());
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor(Option<String>);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Captures the request IDs of the spans that enclose handler events.
+#[derive(Clone, Default)]
+struct CaptureHandlerRequestIds(Arc<Mutex<Vec<Option<String>>>>);
+
+impl<S> Layer<S> for CaptureHandlerRequestIds
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = RequestIdVisitor::default();
+        values.record(&mut visitor);
+        if let (Some(request_id), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(RequestId(request_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        if visitor.0.as_deref() != Some(HANDLER_MESSAGE) {
+            return;
+        }
+        let request_id = ctx.event_scope(event).and_then(|scope| {
+            scope.from_root().find_map(|span| {
+                span.extensions()
+                    .get::<RequestId>()
+                    .map(|RequestId(request_id)| request_id.clone())
+            })
+        });
+        self.0.lock().unwrap().push(request_id);
+    }
+}
+
+/// Handlers run on blocking threads and need a global subscriber.
+fn capture() -> &'static CaptureHandlerRequestIds {
+    static CAPTURE: OnceLock<CaptureHandlerRequestIds> = OnceLock::new();
+    CAPTURE.get_or_init(|| {
+        let capture = CaptureHandlerRequestIds::default();
+        tracing::subscriber::set_global_default(
+            tracing_subscriber::registry().with(capture.clone()),
+        )
+        .unwrap();
+        capture
+    })
+}
+
+fn new_gatekeeper() -> Arc<RequestGatekeeper> {
+    let connection_pool =
+        create_connection_pool(&Storage::InMemory, NonZeroU32::MIN, Default::default()).unwrap();
+    let connection_gatekeeper = Gatekeeper::new(
+        connection_pool,
+        Config {
+            acquire_read_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+            acquire_write_timeout_millis: NonZeroU64::new(10_000).unwrap(),
+            fairness_policy: Default::default(),
+        },
+    );
+    Arc::new(RequestGatekeeper::new(
+        Arc::new(connection_gatekeeper),
+        None,
+    ))
+}
+
+fn filter(
+    gatekeeper: Arc<RequestGatekeeper>,
+) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone + 'static {
+    with_request_id(
+        warp::path("blocking")
+            .and(warp::path::end())
+            .and_then(move || {
+                let gatekeeper = Arc::clone(&gatekeeper);
+                async move {
+                    spawn_blocking_read_task(&gatekeeper, |_pooled_connection| {
+                        tracing::info!("{HANDLER_MESSAGE}");
+                        Ok::<_, Error>(warp::reply())
+                    })
+                    .await
+                }
+            })
+            .recover(handle_rejection),
+    )
+}
+
+#[tokio::test]
+async fn blocking_handler_inherits_request_id() {
+    let capture = capture();
+    let filter = filter(new_gatekeeper());
+
+    let response = warp::test::request()
+        .path("/blocking")
+        .header("X-Request-Id", "client-44")
+        .reply(&filter)
+        .await;
+    let generated_response = warp::test::request().path("/blocking").reply(&filter).await;
+    let generated_request_id = generated_response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .expect("generated request ID");
+
+    assert_eq!(
+        Some("client-44"),
+        response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+    );
+    assert_eq!(
+        vec![
+            Some("client-44".to_owned()),
+            Some(generated_request_id.to_owned())
+        ],
+        *capture.0.lock().unwrap()
+    );
+}
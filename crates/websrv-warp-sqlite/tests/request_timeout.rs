@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use warp::{http::StatusCode, Filter as _, Rejection};
+
+use aoide_websrv_warp_sqlite::{handle_rejection, with_request_timeout};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[tokio::test]
+async fn slow_handler_times_out_and_aborts_blocking_task() {
+    let abort_flag = Arc::new(AtomicBool::new(false));
+    let (aborted_tx, aborted_rx) = mpsc::channel();
+    let filter = warp::path("slow")
+        .and(warp::path::end())
+        .and_then({
+            let abort_flag = Arc::clone(&abort_flag);
+            move || {
+                let abort_flag = Arc::clone(&abort_flag);
+                let aborted_tx = aborted_tx.clone();
+                async move {
+                    let task_abort_flag = Arc::clone(&abort_flag);
+                    let handler = async move {
+                        tokio::task::spawn_blocking(move || {
+                            // Artificially slow task that only finishes when aborted.
+                            while !task_abort_flag.load(Ordering::Relaxed) {
+                                std::thread::sleep(Duration::from_millis(1));
+                            }
+                            aborted_tx.send(()).unwrap();
+                        })
+                        .await
+                        .unwrap();
+                        Ok::<_, Rejection>(warp::reply())
+                    };
+                    with_request_timeout(REQUEST_TIMEOUT, &abort_flag, handler).await
+                }
+            }
+        })
+        .recover(handle_rejection);
+
+    let started_at = Instant::now();
+    let response = warp::test::request().path("/slow").reply(&filter).await;
+    assert_eq!(StatusCode::REQUEST_TIMEOUT, response.status());
+    assert!(started_at.elapsed() >= REQUEST_TIMEOUT);
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "code": 408,
+            "message": "request timed out",
+        }),
+        body
+    );
+
+    // The blocking task must not be orphaned.
+    assert!(abort_flag.load(Ordering::Relaxed));
+    aborted_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+}
+
+#[tokio::test]
+async fn fast_handler_finishes_before_timeout() {
+    let abort_flag = Arc::new(AtomicBool::new(false));
+    let filter = warp::path("fast")
+        .and(warp::path::end())
+        .and_then({
+            let abort_flag = Arc::clone(&abort_flag);
+            move || {
+                let abort_flag = Arc::clone(&abort_flag);
+                async move {
+                    with_request_timeout(REQUEST_TIMEOUT, &abort_flag, async {
+                        Ok::<_, Rejection>(warp::reply())
+                    })
+                    .await
+                }
+            }
+        })
+        .recover(handle_rejection);
+
+    let response = warp::test::request().path("/fast").reply(&filter).await;
+    assert_eq!(StatusCode::OK, response.status());
+    assert!(!abort_flag.load(Ordering::Relaxed));
+}
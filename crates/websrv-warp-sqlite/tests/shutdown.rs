@@ -0,0 +1,82 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use warp::{http::StatusCode, Filter as _};
+
+use aoide_websrv_warp_sqlite::{
+    handle_rejection, shutdown_with_confirmation_token, SHUTDOWN_CONFIRMATION_TOKEN_HEADER,
+};
+
+const CONFIRMATION_TOKEN: &str = "s3cr3t";
+
+fn shutdown_filter(
+    confirmation_token: Option<&str>,
+    shutdown_requests: &Arc<AtomicUsize>,
+) -> impl warp::Filter<Extract = (impl warp::Reply + use<>,), Error = std::convert::Infallible>
+       + Clone
+       + use<> {
+    let shutdown_requests = Arc::clone(shutdown_requests);
+    shutdown_with_confirmation_token(confirmation_token.map(ToOwned::to_owned), move || {
+        shutdown_requests.fetch_add(1, Ordering::Relaxed);
+        StatusCode::ACCEPTED
+    })
+    .recover(handle_rejection)
+}
+
+#[tokio::test]
+async fn shutdown_route_is_disabled_without_confirmation_token() {
+    let shutdown_requests = Arc::new(AtomicUsize::new(0));
+    let filter = shutdown_filter(None, &shutdown_requests);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/shutdown")
+        .header(SHUTDOWN_CONFIRMATION_TOKEN_HEADER, CONFIRMATION_TOKEN)
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+    assert_eq!(0, shutdown_requests.load(Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn reject_shutdown_with_missing_or_wrong_confirmation_token() {
+    let shutdown_requests = Arc::new(AtomicUsize::new(0));
+    let filter = shutdown_filter(Some(CONFIRMATION_TOKEN), &shutdown_requests);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/shutdown")
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/shutdown")
+        .header(SHUTDOWN_CONFIRMATION_TOKEN_HEADER, "wrong")
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+    assert_eq!(0, shutdown_requests.load(Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn accept_shutdown_with_confirmation_token() {
+    let shutdown_requests = Arc::new(AtomicUsize::new(0));
+    let filter = shutdown_filter(Some(CONFIRMATION_TOKEN), &shutdown_requests);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/shutdown")
+        .header(SHUTDOWN_CONFIRMATION_TOKEN_HEADER, CONFIRMATION_TOKEN)
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::ACCEPTED, response.status());
+    assert_eq!(1, shutdown_requests.load(Ordering::Relaxed));
+}
@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use warp::{http::StatusCode, Filter as _};
+
+use aoide_websrv_warp_sqlite::{handle_rejection, static_files_with_spa_fallback};
+
+const INDEX_HTML: &str = "<html><body>index</body></html>";
+
+const APP_JS: &str = "console.log('app');";
+
+#[tokio::test]
+async fn serve_static_files_with_spa_fallback() {
+    let static_dir = tempfile::tempdir().unwrap();
+    std::fs::write(static_dir.path().join("index.html"), INDEX_HTML).unwrap();
+    std::fs::create_dir(static_dir.path().join("assets")).unwrap();
+    std::fs::write(static_dir.path().join("assets").join("app.js"), APP_JS).unwrap();
+
+    let api_filter = warp::path("api")
+        .and(warp::path("about"))
+        .and(warp::path::end())
+        .map(|| "api");
+    let filter = api_filter
+        .or(static_files_with_spa_fallback(
+            static_dir.path().to_path_buf(),
+            &["api"],
+        ))
+        .recover(handle_rejection);
+
+    // Known asset
+    let response = warp::test::request()
+        .path("/assets/app.js")
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(APP_JS.as_bytes(), response.body());
+
+    // Unknown non-API paths are routed on the client
+    for path in ["/", "/collections/abc/tracks", "/assets/missing.js"] {
+        let response = warp::test::request().path(path).reply(&filter).await;
+        assert_eq!(StatusCode::OK, response.status(), "{path}");
+        assert_eq!(INDEX_HTML.as_bytes(), response.body(), "{path}");
+    }
+
+    // API routes take precedence
+    let response = warp::test::request()
+        .path("/api/about")
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!("api".as_bytes(), response.body());
+
+    // Unknown API paths are not shadowed
+    let response = warp::test::request()
+        .path("/api/unknown")
+        .reply(&filter)
+        .await;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+}
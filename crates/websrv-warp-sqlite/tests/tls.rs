@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use tokio::sync::oneshot;
+use warp::{http::StatusCode, Filter as _};
+
+use aoide_websrv_warp_sqlite::{bind_server, handle_rejection, with_request_id, TlsFiles};
+
+const LOCALHOST: ([u8; 4], u16) = ([127, 0, 0, 1], 0);
+
+fn write_self_signed_cert(dir_path: &Path) -> (PathBuf, PathBuf) {
+    let rcgen::CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(vec!["localhost".to_owned(), "127.0.0.1".to_owned()])
+            .unwrap();
+    let cert_path = dir_path.join("cert.pem");
+    let key_path = dir_path.join("key.pem");
+    std::fs::write(&cert_path, cert.pem()).unwrap();
+    std::fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+    (cert_path, key_path)
+}
+
+fn spawn_server(tls_files: Option<TlsFiles<'_>>) -> (SocketAddr, oneshot::Sender<()>) {
+    let filter = with_request_id(
+        warp::path("ok")
+            .and(warp::path::end())
+            .map(warp::reply)
+            .recover(handle_rejection),
+    );
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let (socket_addr, server) = bind_server(filter, LOCALHOST.into(), tls_files, async {
+        shutdown_rx.await.ok();
+    })
+    .unwrap();
+    tokio::spawn(server);
+    (socket_addr, shutdown_tx)
+}
+
+fn new_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        // The self-signed certificate is not trusted
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serve_https_with_self_signed_cert() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (cert_path, key_path) = write_self_signed_cert(temp_dir.path());
+    let (socket_addr, shutdown_tx) = spawn_server(Some(TlsFiles {
+        cert_path: &cert_path,
+        key_path: &key_path,
+    }));
+
+    let client = new_client();
+    let response = client
+        .get(format!("https://{socket_addr}/ok"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+
+    // Plain HTTP requests are not accepted
+    assert!(client
+        .get(format!("http://{socket_addr}/ok"))
+        .send()
+        .await
+        .is_err());
+
+    shutdown_tx.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn serve_plain_http_without_tls() {
+    let (socket_addr, shutdown_tx) = spawn_server(None);
+    let response = new_client()
+        .get(format!("http://{socket_addr}/ok"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    shutdown_tx.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn fail_to_bind_server_if_tls_files_are_missing() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (cert_path, _key_path) = write_self_signed_cert(temp_dir.path());
+    let missing_key_path = temp_dir.path().join("missing.pem");
+    let res = bind_server(
+        warp::any().map(warp::reply),
+        LOCALHOST.into(),
+        Some(TlsFiles {
+            cert_path: &cert_path,
+            key_path: &missing_key_path,
+        }),
+        async {},
+    );
+    assert!(res.is_err());
+}
@@ -353,7 +353,7 @@ fn render_bottom_panel(
 
 #[must_use]
 fn track_list_item_label(track: &TrackListItem) -> String {
-    let track_title = track.title.as_deref().unwrap_or("Untitled");
+    let track_title = &track.title;
     let track_artist = &track.artist;
     let album_title = &track.album_title;
     let album_artist = &track.album_artist;
@@ -365,27 +365,16 @@ fn track_list_item_label(track: &TrackListItem) -> String {
             None
         }
     });
-    let label = match (track_artist, album_title, album_artist) {
-        (Some(track_artist), Some(album_title), Some(album_artist)) => {
-            if track_artist == album_artist {
-                format!("{track_artist} - {track_title} [{album_title}]")
-            } else {
-                format!("{track_artist} - {track_title} [{album_title} by {album_artist}]")
-            }
-        }
-        (None, Some(album_title), Some(album_artist)) => {
-            format!("{track_title} [{album_title} by {album_artist}]")
+    let label = match (album_title, album_artist) {
+        (Some(album_title), Some(album_artist)) if album_artist != track_artist => {
+            format!("{track_artist} - {track_title} [{album_title} by {album_artist}]")
         }
-        (Some(track_artist), Some(album_title), None) => {
+        (Some(album_title), _) => {
             format!("{track_artist} - {track_title} [{album_title}]")
         }
-        (Some(track_artist), None, _) => {
+        (None, _) => {
             format!("{track_artist} - {track_title}")
         }
-        (None, Some(album_title), None) => {
-            format!("{track_title} [{album_title}]")
-        }
-        (None, None, _) => track_title.to_string(),
     };
     let key = track
         .key
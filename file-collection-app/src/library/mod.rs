@@ -78,11 +78,17 @@ pub struct SharedState {
 impl SharedState {
     #[must_use]
     fn new(initial_settings: settings::State) -> Self {
+        let last_track_search = initial_settings.last_track_search().cloned();
         let settings = Arc::new(settings::SharedState::new(initial_settings));
         let collection = Arc::new(collection::SharedState::default());
-        let track_search = Arc::new(track_search::SharedState::new(track_search::State::new(
-            track_search::default_params(),
-        )));
+        let track_search = Arc::new(track_search::SharedState::new(
+            track_search::State::new(track_search::default_params())
+                .with_debounce_delay(Some(track_search::DEBOUNCE_DELAY)),
+        ));
+        if let Some(last_track_search) = last_track_search {
+            // The search is re-issued as soon as the collection is ready.
+            let _ = track_search.restore_last_search(last_track_search);
+        }
         Self {
             settings,
             collection,
@@ -471,6 +477,10 @@ impl Library {
             &self.shared_state.track_search,
             Some(track_search::DEFAULT_PREFETCH_LIMIT),
         ));
+        rt.spawn(track_search::tasklet::on_state_changed_update_last_search(
+            &self.shared_state.track_search,
+            Arc::downgrade(&self.shared_state.settings),
+        ));
     }
 
     pub fn spawn_event_tasks<E>(&self, rt: &tokio::runtime::Handle, event_emitter: &E)
@@ -1,7 +1,10 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{num::NonZeroUsize, time::Instant};
+use std::{
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
 
 use discro::{Ref, Subscriber};
 
@@ -46,6 +49,9 @@ const DEFAULT_SORT_ORDER: &[SortOrder] = &[SortOrder {
     direction: SortDirection::Descending,
 }];
 
+/// Delay searching while the user is still typing.
+pub(super) const DEBOUNCE_DELAY: Duration = Duration::from_millis(250);
+
 const DEFAULT_PREFETCH_LIMIT_USIZE: usize = 250;
 pub(super) const DEFAULT_PREFETCH_LIMIT: NonZeroUsize =
     NonZeroUsize::MIN.saturating_add(DEFAULT_PREFETCH_LIMIT_USIZE - 1);
@@ -31,8 +31,8 @@ pub struct TrackListItem {
     pub entity_uid: TrackUid,
     pub content_url: Option<Url>,
 
-    pub artist: Option<String>,
-    pub title: Option<String>,
+    pub artist: String,
+    pub title: String,
     pub album_artist: Option<String>,
     pub album_title: Option<String>,
     pub album_subtitle: Option<String>,
@@ -58,8 +58,8 @@ impl TrackListItem {
         content_url: Option<Url>,
         track: &aoide::Track,
     ) -> Self {
-        let artist = track.track_artist().map(ToOwned::to_owned);
-        let title = track.track_title().map(ToOwned::to_owned);
+        let artist = track.display_artist().to_owned();
+        let title = track.display_title().to_owned();
         let album_artist = track.album_artist().map(ToOwned::to_owned);
         let album_title = track.album_title().map(ToOwned::to_owned);
         let album_subtitle = track.album_subtitle().map(ToOwned::to_owned);
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::{
+    fmt,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     num::{NonZeroU32, NonZeroU64},
+    path::PathBuf,
     time::Duration,
 };
 
@@ -20,11 +22,126 @@ use serde::{Deserialize, Serialize};
 pub struct Config {
     pub network: NetworkConfig,
     pub database: DatabaseConfig,
+
+    #[serde(default)]
+    pub frontend: FrontendConfig,
+
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+}
+
+impl Config {
+    /// A copy without secrets, e.g. for publishing.
+    #[must_use]
+    pub fn redacted(&self) -> Self {
+        let mut redacted = self.clone();
+        redacted.shutdown.confirmation_token = None;
+        redacted
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontendConfig {
+    /// Serve a single-page application from this directory if configured.
+    ///
+    /// Replaces the embedded `index.html`. Unknown paths outside of
+    /// the API are answered with the `index.html` from this directory
+    /// to support client-side routing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_dir: Option<PathBuf>,
+}
+
+/// The `POST /shutdown` route.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// The route is disabled by default.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Shutdown requests must provide this token.
+    ///
+    /// The route remains disabled if no token is configured.
+    ///
+    /// The token is stored in plain text when saving the configuration
+    /// into the configuration file of the app. It is redacted from the
+    /// debug output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation_token: Option<String>,
+}
+
+impl fmt::Debug for ShutdownConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            enabled,
+            confirmation_token,
+        } = self;
+        f.debug_struct("ShutdownConfig")
+            .field("enabled", enabled)
+            .field(
+                "confirmation_token",
+                &confirmation_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+impl ShutdownConfig {
+    /// The confirmation token if the route is enabled.
+    #[must_use]
+    pub fn enabled_confirmation_token(&self) -> Option<&str> {
+        if !self.enabled {
+            return None;
+        }
+        self.confirmation_token.as_deref()
+    }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NetworkConfig {
     pub endpoint: EndpointConfig,
+
+    /// Serve HTTPS instead of plain HTTP if configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+
+    /// Abort requests that take longer than this timeout if configured.
+    ///
+    /// Independent of the timeouts for acquiring a database connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout_millis: Option<NonZeroU64>,
+}
+
+impl NetworkConfig {
+    #[must_use]
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout_millis
+            .map(|millis| Duration::from_millis(millis.get()))
+    }
+}
+
+/// PEM files for terminating TLS connections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// Both the certificate and the private key must be provided together.
+    pub fn try_from_paths(
+        cert_path: Option<PathBuf>,
+        key_path: Option<PathBuf>,
+    ) -> anyhow::Result<Option<Self>> {
+        match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Ok(Some(Self {
+                cert_path,
+                key_path,
+            })),
+            (None, None) => Ok(None),
+            (Some(_), None) => anyhow::bail!("missing TLS private key for certificate"),
+            (None, Some(_)) => anyhow::bail!("missing TLS certificate for private key"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -96,8 +213,10 @@ impl Default for DatabaseConfig {
                         acquire_write_timeout_millis: non_zero_duration_as_millis(
                             DEFAULT_DATABASE_CONNECTION_TIMEOUT_ACQUIRE_WRITE,
                         ),
+                        fairness_policy: Default::default(),
                     },
                 },
+                pragma: Default::default(),
             },
             migrate_schema_on_startup: true,
         }
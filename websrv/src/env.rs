@@ -3,19 +3,24 @@
 
 use std::{
     env::{self, VarError},
+    fmt,
     net::IpAddr,
-    num::NonZeroU32,
-    str::ParseBoolError,
+    num::{NonZeroU32, NonZeroU64},
+    path::PathBuf,
+    str::{FromStr, ParseBoolError},
 };
 
-use aoide_storage_sqlite::connection::{Storage as SqliteDatabaseStorage, IN_MEMORY_STORAGE};
+use aoide_storage_sqlite::connection::{
+    pool::gatekeeper::FairnessPolicy as DatabaseConnectionFairnessPolicy,
+    Storage as SqliteDatabaseStorage, IN_MEMORY_STORAGE,
+};
 use dotenvy::dotenv;
 use tracing::{subscriber::set_global_default, Subscriber};
 use tracing_log::LogTracer;
 use tracing_subscriber::EnvFilter;
 use url::Url;
 
-use crate::config::Config;
+use crate::config::{Config, TlsConfig};
 
 pub(crate) fn init_environment() {
     if let Ok(path) = dotenv() {
@@ -115,6 +120,62 @@ fn parse_endpoint_port() -> Option<u16> {
         .flatten()
 }
 
+const TLS_CERT_PATH_ENV: &str = "TLS_CERT_PATH";
+
+const TLS_KEY_PATH_ENV: &str = "TLS_KEY_PATH";
+
+fn parse_path_var_with_key(key: &str) -> Result<Option<PathBuf>, VarError> {
+    read_optional_var(key).map(|var| {
+        var.and_then(|var| {
+            log::debug!("{key} = {var}");
+            let trimmed = var.trim();
+            (!trimmed.is_empty()).then(|| trimmed.into())
+        })
+    })
+}
+
+/// Both the certificate and the private key must be provided together.
+fn parse_tls_config() -> anyhow::Result<Option<TlsConfig>> {
+    let cert_path = parse_path_var_with_key(TLS_CERT_PATH_ENV)?;
+    let key_path = parse_path_var_with_key(TLS_KEY_PATH_ENV)?;
+    TlsConfig::try_from_paths(cert_path, key_path).map_err(|err| {
+        anyhow::anyhow!("Invalid '{TLS_CERT_PATH_ENV}' and '{TLS_KEY_PATH_ENV}': {err}")
+    })
+}
+
+const FRONTEND_STATIC_DIR_ENV: &str = "FRONTEND_STATIC_DIR";
+
+fn parse_frontend_static_dir() -> anyhow::Result<Option<PathBuf>> {
+    parse_path_var_with_key(FRONTEND_STATIC_DIR_ENV)
+        .map_err(|err| anyhow::anyhow!("Invalid '{FRONTEND_STATIC_DIR_ENV}': {err}"))
+}
+
+const SHUTDOWN_ENABLED_ENV: &str = "SHUTDOWN_ENABLED";
+
+fn parse_shutdown_enabled() -> Option<bool> {
+    parse_option_bool_var_with_key(SHUTDOWN_ENABLED_ENV)
+}
+
+const SHUTDOWN_CONFIRMATION_TOKEN_ENV: &str = "SHUTDOWN_CONFIRMATION_TOKEN";
+
+fn parse_shutdown_confirmation_token() -> anyhow::Result<Option<String>> {
+    // The token is a secret and must not be logged.
+    read_optional_var(SHUTDOWN_CONFIRMATION_TOKEN_ENV)
+        .map(|var| {
+            var.and_then(|var| {
+                let trimmed = var.trim();
+                (!trimmed.is_empty()).then(|| trimmed.to_owned())
+            })
+        })
+        .map_err(|err| anyhow::anyhow!("Invalid '{SHUTDOWN_CONFIRMATION_TOKEN_ENV}': {err}"))
+}
+
+const REQUEST_TIMEOUT_MILLIS_ENV: &str = "REQUEST_TIMEOUT_MILLIS";
+
+fn parse_request_timeout_millis() -> Option<NonZeroU64> {
+    parse_option_var_with_key(REQUEST_TIMEOUT_MILLIS_ENV)
+}
+
 const DATABASE_URL_ENV: &str = "DATABASE_URL";
 
 fn parse_sqlite_database_storage() -> Option<SqliteDatabaseStorage> {
@@ -152,21 +213,33 @@ fn parse_sqlite_database_storage() -> Option<SqliteDatabaseStorage> {
 const DATABASE_CONNECTION_POOL_SIZE_ENV: &str = "DATABASE_CONNECTION_POOL_SIZE";
 
 fn parse_database_connection_pool_size() -> Option<NonZeroU32> {
-    read_optional_var(DATABASE_CONNECTION_POOL_SIZE_ENV)
+    parse_option_var_with_key(DATABASE_CONNECTION_POOL_SIZE_ENV)
+}
+
+const DATABASE_CONNECTION_MAX_CONSECUTIVE_READS_ENV: &str =
+    "DATABASE_CONNECTION_MAX_CONSECUTIVE_READS";
+
+fn parse_database_connection_max_consecutive_reads() -> Option<NonZeroU32> {
+    parse_option_var_with_key(DATABASE_CONNECTION_MAX_CONSECUTIVE_READS_ENV)
+}
+
+fn parse_option_var_with_key<T>(key: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    read_optional_var(key)
         .map_err(|err| err.to_string())
         .and_then(|var| {
             var.map(|var| {
-                log::debug!("{DATABASE_CONNECTION_POOL_SIZE_ENV} = {var}");
+                log::debug!("{key} = {var}");
                 if var.trim().is_empty() {
                     // Silently ignore whitespace
                     Ok(None)
                 } else {
-                    var.parse().map(Some).map_err(|err| {
-                        format!(
-                            "Failed to parse '{DATABASE_CONNECTION_POOL_SIZE_ENV}' = '{var}': \
-                             {err}"
-                        )
-                    })
+                    var.parse()
+                        .map(Some)
+                        .map_err(|err| format!("Failed to parse '{key}' = '{var}': {err}"))
                 }
             })
             .transpose()
@@ -216,22 +289,44 @@ fn parse_database_migrate_schema_on_startup() -> Option<bool> {
     parse_option_bool_var_with_key(DATABASE_MIGRATE_SCHEMA_ON_STARTUP_ENV)
 }
 
-pub(crate) fn parse_config_into(config: &mut Config) {
+pub(crate) fn parse_config_into(config: &mut Config) -> anyhow::Result<()> {
     if let Some(ip_addr) = parse_endpoint_ip_addr() {
         config.network.endpoint.ip_addr = ip_addr;
     }
     if let Some(port) = parse_endpoint_port() {
         config.network.endpoint.port = port;
     }
+    if let Some(tls) = parse_tls_config()? {
+        config.network.tls = Some(tls);
+    }
+    if let Some(request_timeout_millis) = parse_request_timeout_millis() {
+        config.network.request_timeout_millis = Some(request_timeout_millis);
+    }
     if let Some(storage) = parse_sqlite_database_storage() {
         config.database.connection.storage = storage;
     }
     if let Some(connection_pool_size) = parse_database_connection_pool_size() {
         config.database.connection.pool.max_size = connection_pool_size;
     }
+    if let Some(max_consecutive_reads) = parse_database_connection_max_consecutive_reads() {
+        config.database.connection.pool.gatekeeper.fairness_policy =
+            DatabaseConnectionFairnessPolicy::ReadBatch {
+                max_consecutive_reads,
+            };
+    }
     if let Some(migrate_schema_on_startup) = parse_database_migrate_schema_on_startup() {
         config.database.migrate_schema_on_startup = migrate_schema_on_startup;
     }
+    if let Some(static_dir) = parse_frontend_static_dir()? {
+        config.frontend.static_dir = Some(static_dir);
+    }
+    if let Some(enabled) = parse_shutdown_enabled() {
+        config.shutdown.enabled = enabled;
+    }
+    if let Some(confirmation_token) = parse_shutdown_confirmation_token()? {
+        config.shutdown.confirmation_token = Some(confirmation_token);
+    }
+    Ok(())
 }
 
 #[cfg(feature = "launcher-ui")]
@@ -24,7 +24,7 @@ pub(crate) enum State {
 enum InternalState {
     Idle,
     Running {
-        config: Config,
+        config: Box<Config>,
 
         current_state_rx: discro::Subscriber<State>,
         runtime_command_tx: mpsc::UnboundedSender<RuntimeCommand>,
@@ -128,7 +128,7 @@ impl Launcher {
         });
 
         self.state = InternalState::Running {
-            config,
+            config: Box::new(config),
             current_state_rx,
             runtime_command_tx,
             runtime: Box::new(runtime),
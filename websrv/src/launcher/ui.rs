@@ -3,6 +3,7 @@
 
 use std::{
     ffi::OsStr,
+    num::NonZeroU64,
     path::Path,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -26,13 +27,23 @@ use crate::{
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct NetworkConfig {
     endpoint: EndpointConfig,
+    // Not editable
+    tls: Option<crate::config::TlsConfig>,
+    // Not editable
+    request_timeout_millis: Option<NonZeroU64>,
 }
 
 impl From<crate::config::NetworkConfig> for NetworkConfig {
     fn from(from: crate::config::NetworkConfig) -> Self {
-        let crate::config::NetworkConfig { endpoint } = from;
+        let crate::config::NetworkConfig {
+            endpoint,
+            tls,
+            request_timeout_millis,
+        } = from;
         Self {
             endpoint: endpoint.into(),
+            tls,
+            request_timeout_millis,
         }
     }
 }
@@ -41,9 +52,17 @@ impl TryFrom<NetworkConfig> for crate::config::NetworkConfig {
     type Error = anyhow::Error;
 
     fn try_from(from: NetworkConfig) -> anyhow::Result<Self> {
-        let NetworkConfig { endpoint } = from;
+        let NetworkConfig {
+            endpoint,
+            tls,
+            request_timeout_millis,
+        } = from;
         let endpoint = endpoint.try_into()?;
-        Ok(Self { endpoint })
+        Ok(Self {
+            endpoint,
+            tls,
+            request_timeout_millis,
+        })
     }
 }
 
@@ -104,7 +123,11 @@ struct Config {
 
 impl From<crate::config::Config> for Config {
     fn from(from: crate::config::Config) -> Self {
-        let crate::config::Config { network, database } = from;
+        // All other settings are not editable and are preserved
+        // from the last config when launching.
+        let crate::config::Config {
+            network, database, ..
+        } = from;
         Self {
             network: network.into(),
             database: database.into(),
@@ -138,7 +138,10 @@ fn main() {
     // Override config with environment variables
     log::info!("Patching configuration from .env file and environment variables");
     let mut config = initial_config.clone();
-    env::parse_config_into(&mut config);
+    if let Err(err) = env::parse_config_into(&mut config) {
+        log::error!("Failed to patch configuration: {err}");
+        return;
+    }
     let save_config_on_exit = if config == initial_config {
         true
     } else {
@@ -1,21 +1,13 @@
 // SPDX-FileCopyrightText: Copyright (C) 2018-2024 Uwe Klotz <uwedotklotzatgmaildotcom> et al.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{
-    borrow::Cow,
-    convert::Infallible,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
-};
+use std::{borrow::Cow, convert::Infallible, str::FromStr, sync::Arc};
 
 use aoide_backend_webapi_json as api;
-use aoide_core::{CollectionUid, PlaylistUid, TrackUid};
+use aoide_core::{EntityUid, EntityUidTyped};
 use aoide_repo_sqlite::DEFAULT_VACUUM_MODE;
 use aoide_storage_sqlite::{
-    cleanse_database,
-    connection::pool::gatekeeper::{Gatekeeper as DatabaseConnectionGatekeeper, PendingTasks},
+    check_integrity, cleanse_database, connection::pool::gatekeeper::PendingTasks,
 };
 use aoide_usecases::{
     media::tracker::{
@@ -26,7 +18,7 @@ use aoide_usecases::{
     playlist::CollectionFilter,
 };
 use aoide_usecases_sqlite as uc;
-use aoide_websrv_warp_sqlite as websrv;
+use aoide_websrv_warp_sqlite::{self as websrv, RequestGatekeeper};
 #[cfg(feature = "json-schema")]
 use schemars::schema_for;
 use tokio::sync::{watch, Mutex};
@@ -41,6 +33,32 @@ async fn reply_media_tracker_progress(
     )))
 }
 
+/// Entity UID in a path
+///
+/// Accepts both the canonical and the URL-safe encoding.
+struct UidPathParam<T: 'static>(EntityUidTyped<T>);
+
+impl<T> FromStr for UidPathParam<T> {
+    type Err = anyhow::Error;
+
+    fn from_str(encoded: &str) -> anyhow::Result<Self> {
+        if encoded.len() == EntityUid::URL_SAFE_LEN {
+            EntityUidTyped::from_url_safe(encoded)
+        } else {
+            encoded.parse()
+        }
+        .map(Self)
+    }
+}
+
+fn path_param_uid<T>(
+) -> impl Filter<Extract = (EntityUidTyped<T>,), Error = warp::Rejection> + Clone
+where
+    T: Send + Sync + 'static,
+{
+    warp::path::param::<UidPathParam<T>>().map(|UidPathParam(uid)| uid)
+}
+
 // TODO: Move into separate request handler
 #[derive(serde::Deserialize)]
 struct CleanseDatabaseQueryParams {
@@ -50,22 +68,20 @@ struct CleanseDatabaseQueryParams {
 #[allow(clippy::too_many_lines)] // TODO
 pub(crate) fn create_filters(
     rt: &tokio::runtime::Handle,
-    shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
-    abort_flag: Arc<AtomicBool>,
+    shared_connection_gatekeeper: Arc<RequestGatekeeper>,
 ) -> BoxedFilter<(impl Reply + use<>,)> {
     // The trailing comma is required!
     let shared_connection_gatekeeper =
         warp::any().map(move || Arc::clone(&shared_connection_gatekeeper));
-    let abort_flag = warp::any().map(move || Arc::clone(&abort_flag));
 
     let media_tracker_progress = Arc::new(Mutex::new(MediaTrackerProgress::Idle));
     let media_tracker_progress = warp::any().map(move || Arc::clone(&media_tracker_progress));
 
     log::info!("Creating API routes");
 
-    let path_param_collection_uid = warp::path::param::<CollectionUid>();
-    let path_param_track_uid = warp::path::param::<TrackUid>();
-    let path_param_playlist_uid = warp::path::param::<PlaylistUid>();
+    let path_param_collection_uid = path_param_uid::<aoide_core::collection::EntityType>();
+    let path_param_track_uid = path_param_uid::<aoide_core::track::EntityType>();
+    let path_param_playlist_uid = path_param_uid::<aoide_core::playlist::EntityType>();
 
     let collections_path = warp::path("c");
     let tracks_path = warp::path("t");
@@ -82,6 +98,8 @@ pub(crate) fn create_filters(
     let schema_post_path = schema_path.and(warp::path("post"));
     #[cfg(feature = "json-schema")]
     let schema_put_path = schema_path.and(warp::path("put"));
+    #[cfg(feature = "json-schema")]
+    let schema_patch_path = schema_path.and(warp::path("patch"));
 
     // Collections
     let collections_create = warp::post()
@@ -90,17 +108,20 @@ pub(crate) fn create_filters(
         .and(warp::body::json())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |request_body, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
-                websrv::spawn_blocking_write_task(&shared_connection_gatekeeper, move |mut pooled_connection| {
-                        api::collection::create::handle_request(&mut pooled_connection, request_body)
-                    })
-                    .await
-                    .map(|response_body| {
-                        warp::reply::with_status(
-                            warp::reply::json(&response_body),
-                            StatusCode::CREATED,
+            move |request_body, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                websrv::spawn_blocking_write_task(
+                    &shared_connection_gatekeeper,
+                    move |mut pooled_connection| {
+                        api::collection::create::handle_request(
+                            &mut pooled_connection,
+                            request_body,
                         )
-                    })
+                    },
+                )
+                .await
+                .map(|response_body| {
+                    warp::reply::with_status(warp::reply::json(&response_body), StatusCode::CREATED)
+                })
             },
         );
     #[cfg(feature = "json-schema")]
@@ -120,7 +141,7 @@ pub(crate) fn create_filters(
 
     let collections_update = warp::put()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(warp::path::end())
         .and(warp::query())
         .and(warp::body::json())
@@ -129,7 +150,7 @@ pub(crate) fn create_filters(
             move |uid,
                   query_params,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -149,7 +170,7 @@ pub(crate) fn create_filters(
     let collections_update_schema = warp::get()
         .and(schema_put_path)
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(warp::path::end())
         .map(|_uid| {
             let query_schema = schema_for!(api::collection::update::QueryParams);
@@ -163,13 +184,58 @@ pub(crate) fn create_filters(
             warp::reply::json(&schema)
         });
 
+    let collections_patch = warp::patch()
+        .and(collections_path)
+        .and(path_param_collection_uid.clone())
+        .and(warp::path::end())
+        .and(warp::query())
+        .and(warp::body::json())
+        .and(shared_connection_gatekeeper.clone())
+        .and_then(
+            move |uid,
+                  query_params,
+                  request_body,
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                websrv::spawn_blocking_write_task(
+                    &shared_connection_gatekeeper,
+                    move |mut pooled_connection| {
+                        api::collection::patch::handle_request(
+                            &mut pooled_connection,
+                            uid,
+                            query_params,
+                            request_body,
+                        )
+                    },
+                )
+                .await
+                .map(|response_body| warp::reply::json(&response_body))
+            },
+        );
+    #[cfg(feature = "json-schema")]
+    let collections_patch_schema = warp::get()
+        .and(schema_patch_path)
+        .and(collections_path)
+        .and(path_param_collection_uid.clone())
+        .and(warp::path::end())
+        .map(|_uid| {
+            let query_schema = schema_for!(api::collection::patch::QueryParams);
+            let request_schema = schema_for!(api::collection::patch::RequestBody);
+            let response_schema = schema_for!(api::collection::patch::ResponseBody);
+            let schema = serde_json::json!({
+                "query": query_schema,
+                "request": request_schema,
+                "response": response_schema,
+            });
+            warp::reply::json(&schema)
+        });
+
     let collections_delete = warp::delete()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |uid, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |uid, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -187,12 +253,18 @@ pub(crate) fn create_filters(
         .and(warp::query())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |query_params, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
-                websrv::spawn_blocking_read_task(&shared_connection_gatekeeper,move |mut pooled_connection| {
-                        api::collection::load_all::handle_request(&mut pooled_connection, query_params)
-                    })
-                    .await
-                    .map(|response_body| warp::reply::json(&response_body))
+            move |query_params, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                websrv::spawn_blocking_read_task(
+                    &shared_connection_gatekeeper,
+                    move |mut pooled_connection| {
+                        api::collection::load_all::handle_request(
+                            &mut pooled_connection,
+                            query_params,
+                        )
+                    },
+                )
+                .await
+                .map(|response_body| warp::reply::json(&response_body))
             },
         );
     #[cfg(feature = "json-schema")]
@@ -212,11 +284,11 @@ pub(crate) fn create_filters(
 
     let collections_load_one = warp::get()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |uid, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |uid, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -231,7 +303,7 @@ pub(crate) fn create_filters(
     let collections_load_one_schema = warp::get()
         .and(schema_get_path)
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(warp::path::end())
         .map(|_uid| {
             let response_schema = schema_for!(api::collection::load_one::ResponseBody);
@@ -247,7 +319,7 @@ pub(crate) fn create_filters(
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -277,6 +349,7 @@ pub(crate) fn create_filters(
         .or(collections_load_all_kinds)
         .or(collections_create)
         .or(collections_update)
+        .or(collections_patch)
         .or(collections_delete);
 
     #[cfg(feature = "json-schema")]
@@ -285,7 +358,8 @@ pub(crate) fn create_filters(
         .or(collections_load_one_schema)
         .or(collections_load_all_kinds_schema)
         .or(collections_create_schema)
-        .or(collections_update_schema);
+        .or(collections_update_schema)
+        .or(collections_patch_schema);
 
     let media_tracker_get_progress = warp::get()
         .and(media_tracker_path)
@@ -299,7 +373,7 @@ pub(crate) fn create_filters(
         );
     let media_tracker_post_collection_query_status = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_tracker_path)
         .and(warp::path("query-status"))
         .and(warp::path::end())
@@ -308,7 +382,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -325,21 +399,19 @@ pub(crate) fn create_filters(
         );
     let media_tracker_post_collection_scan = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_tracker_path)
         .and(warp::path("scan-directories"))
         .and(warp::path::end())
         .and(warp::body::json())
         .and(shared_connection_gatekeeper.clone())
         .and(media_tracker_progress.clone())
-        .and(abort_flag.clone())
         .and_then({
             let rt = rt.clone();
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
-                  media_tracker_progress: Arc<Mutex<MediaTrackerProgress>>,
-                  abort_flag: Arc<AtomicBool>| {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>,
+                  media_tracker_progress: Arc<Mutex<MediaTrackerProgress>>| {
                 let rt = rt.clone();
                 async move {
                     let (progress_event_tx, mut progress_event_rx) = watch::channel(None);
@@ -361,10 +433,9 @@ pub(crate) fn create_filters(
                         log::debug!("Unwatching media tracker scanning");
                         *media_tracker_progress.lock().await = MediaTrackerProgress::Idle;
                     });
-                    let response = websrv::spawn_blocking_write_task(
+                    let response = websrv::spawn_blocking_abortable_write_task(
                         &shared_connection_gatekeeper,
-                        move |mut pooled_connection| {
-                            abort_flag.store(false, Ordering::Relaxed);
+                        move |mut pooled_connection, abort_flag| {
                             api::media::tracker::scan_directories::handle_request(
                                 &mut pooled_connection,
                                 &uid,
@@ -378,7 +449,7 @@ pub(crate) fn create_filters(
                                     );
                                     }
                                 },
-                                &abort_flag,
+                                abort_flag,
                             )
                         },
                     )
@@ -394,21 +465,19 @@ pub(crate) fn create_filters(
         });
     let media_tracker_post_collection_import = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_tracker_path)
         .and(warp::path("import-files"))
         .and(warp::path::end())
         .and(warp::body::json())
         .and(shared_connection_gatekeeper.clone())
         .and(media_tracker_progress.clone())
-        .and(abort_flag.clone())
         .and_then({
             let rt = rt.clone();
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
-                  media_tracker_progress: Arc<Mutex<MediaTrackerProgress>>,
-                  abort_flag: Arc<AtomicBool>| {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>,
+                  media_tracker_progress: Arc<Mutex<MediaTrackerProgress>>| {
                 let rt = rt.clone();
                 async move {
                     let (progress_event_tx, mut progress_event_rx) = watch::channel(None);
@@ -420,7 +489,7 @@ pub(crate) fn create_filters(
                             let progress = progress_event_rx
                                 .borrow()
                                 .as_ref()
-                                .map(|event: &ImportProgressEvent| event.summary.clone());
+                                .map(|event: &ImportProgressEvent| event.progress.clone());
                             // Borrow has already been released at this point
                             if let Some(progress) = progress {
                                 *media_tracker_progress.lock().await =
@@ -430,10 +499,9 @@ pub(crate) fn create_filters(
                         log::debug!("Unwatching media tracker importing");
                         *media_tracker_progress.lock().await = MediaTrackerProgress::Idle;
                     });
-                    let response = websrv::spawn_blocking_write_task(
+                    let response = websrv::spawn_blocking_abortable_write_task(
                         &shared_connection_gatekeeper,
-                        move |mut pooled_connection| {
-                            abort_flag.store(false, Ordering::Relaxed);
+                        move |mut pooled_connection, abort_flag| {
                             api::media::tracker::import_files::handle_request(
                                 &mut pooled_connection,
                                 &uid,
@@ -447,7 +515,7 @@ pub(crate) fn create_filters(
                                     );
                                     }
                                 },
-                                &abort_flag,
+                                abort_flag,
                             )
                         },
                     )
@@ -463,7 +531,7 @@ pub(crate) fn create_filters(
         });
     let media_tracker_post_collection_untrack = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_tracker_path)
         .and(warp::path("untrack-directories"))
         .and(warp::path::end())
@@ -472,7 +540,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -489,21 +557,19 @@ pub(crate) fn create_filters(
         );
     let media_tracker_post_collection_find_untracked_files = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_tracker_path)
         .and(warp::path("find-untracked-files"))
         .and(warp::path::end())
         .and(warp::body::json())
         .and(shared_connection_gatekeeper.clone())
         .and(media_tracker_progress)
-        .and(abort_flag.clone())
         .and_then({
             let rt = rt.clone();
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
-                  media_tracker_progress: Arc<Mutex<MediaTrackerProgress>>,
-                  abort_flag: Arc<AtomicBool>| {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>,
+                  media_tracker_progress: Arc<Mutex<MediaTrackerProgress>>| {
                 let rt = rt.clone();
                 async move {
                     let (progress_event_tx, mut progress_event_rx) = watch::channel(None);
@@ -525,10 +591,9 @@ pub(crate) fn create_filters(
                         log::debug!("Unwatching media tracker finding untracked");
                         *media_tracker_progress.lock().await = MediaTrackerProgress::Idle;
                     });
-                    let response = websrv::spawn_blocking_read_task(
+                    let response = websrv::spawn_blocking_abortable_read_task(
                         &shared_connection_gatekeeper,
-                        move |mut pooled_connection| {
-                            abort_flag.store(false, Ordering::Relaxed);
+                        move |mut pooled_connection, abort_flag| {
                             api::media::tracker::find_untracked_files::handle_request(
                                 &mut pooled_connection,
                                 &uid,
@@ -542,7 +607,7 @@ pub(crate) fn create_filters(
                                     );
                                     }
                                 },
-                                &abort_flag,
+                                abort_flag,
                             )
                         },
                     )
@@ -567,7 +632,7 @@ pub(crate) fn create_filters(
     // TODO: Add OpenAPI docs for all collected media source requests
     let collected_media_sources_relocate = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_source_path)
         .and(warp::path("relocate"))
         .and(warp::path::end())
@@ -576,7 +641,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -593,7 +658,7 @@ pub(crate) fn create_filters(
         );
     let collected_media_sources_purge_orphaned = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_source_path)
         .and(warp::path("purge-orphaned"))
         .and(warp::path::end())
@@ -602,7 +667,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -619,7 +684,7 @@ pub(crate) fn create_filters(
         );
     let collected_media_sources_purge_untracked = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(media_source_path)
         .and(warp::path("purge-untracked"))
         .and(warp::path::end())
@@ -628,7 +693,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -649,7 +714,7 @@ pub(crate) fn create_filters(
 
     let collected_tracks_resolve = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(tracks_path)
         .and(warp::path("resolve"))
         .and(warp::path::end())
@@ -658,7 +723,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -673,9 +738,35 @@ pub(crate) fn create_filters(
                 .map(|response_body| warp::reply::json(&response_body))
             },
         );
+    let collected_tracks_load_many = warp::post()
+        .and(collections_path)
+        .and(path_param_collection_uid.clone())
+        .and(tracks_path)
+        .and(warp::path("load-many"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(shared_connection_gatekeeper.clone())
+        .and_then(
+            move |uid,
+                  request_body,
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                websrv::spawn_blocking_read_task(
+                    &shared_connection_gatekeeper,
+                    move |mut pooled_connection| {
+                        api::track::load_many_in_collection::handle_request(
+                            &mut pooled_connection,
+                            &uid,
+                            request_body,
+                        )
+                    },
+                )
+                .await
+                .map(|response_body| warp::reply::json(&response_body))
+            },
+        );
     let collected_tracks_search = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(tracks_path)
         .and(warp::path("search"))
         .and(warp::path::end())
@@ -686,7 +777,7 @@ pub(crate) fn create_filters(
             move |uid,
                   query_params,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -704,7 +795,7 @@ pub(crate) fn create_filters(
         );
     let collected_tracks_replace = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(tracks_path)
         .and(warp::path("replace"))
         .and(warp::path::end())
@@ -715,7 +806,7 @@ pub(crate) fn create_filters(
             move |uid,
                   query_params,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -734,30 +825,27 @@ pub(crate) fn create_filters(
     let collected_tracks_import_and_replace = {
         warp::post()
             .and(collections_path)
-            .and(path_param_collection_uid)
+            .and(path_param_collection_uid.clone())
             .and(tracks_path)
             .and(warp::path("import-and-replace"))
             .and(warp::path::end())
             .and(warp::query())
             .and(warp::body::json())
             .and(shared_connection_gatekeeper.clone())
-            .and(abort_flag.clone())
             .and_then(
                 move |uid,
                       query_params,
                       request_body,
-                      shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>,
-                      abort_flag: Arc<AtomicBool>| async move {
-                    websrv::spawn_blocking_write_task(
+                      shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                    websrv::spawn_blocking_abortable_write_task(
                         &shared_connection_gatekeeper,
-                        move |mut pooled_connection| {
-                            abort_flag.store(false, Ordering::Relaxed);
+                        move |mut pooled_connection, abort_flag| {
                             api::track::import_and_replace::handle_request(
                                 &mut pooled_connection,
                                 &uid,
                                 query_params,
                                 request_body,
-                                &abort_flag,
+                                abort_flag,
                             )
                         },
                     )
@@ -768,7 +856,7 @@ pub(crate) fn create_filters(
     };
     let collected_tracks_find_unsynchronized = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(tracks_path)
         .and(warp::path("find-unsynchronized"))
         .and(warp::path::end())
@@ -779,15 +867,16 @@ pub(crate) fn create_filters(
             move |uid,
                   query_params,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
-                websrv::spawn_blocking_read_task(
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                websrv::spawn_blocking_abortable_read_task(
                     &shared_connection_gatekeeper,
-                    move |mut pooled_connection| {
+                    move |mut pooled_connection, abort_flag| {
                         api::track::find_unsynchronized::handle_request(
                             &mut pooled_connection,
                             &uid,
                             query_params,
                             request_body,
+                            abort_flag,
                         )
                     },
                 )
@@ -798,7 +887,7 @@ pub(crate) fn create_filters(
     // TODO: Add API docs.
     let collected_tracks_export_vfs = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(tracks_path)
         .and(warp::path("export-vfs"))
         .and(warp::path::end())
@@ -807,7 +896,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -824,6 +913,7 @@ pub(crate) fn create_filters(
         );
 
     let collected_tracks_filters = collected_tracks_resolve
+        .or(collected_tracks_load_many)
         .or(collected_tracks_search)
         .or(collected_tracks_replace)
         .or(collected_tracks_import_and_replace)
@@ -833,11 +923,11 @@ pub(crate) fn create_filters(
     // Tracks
     let tracks_load_one = warp::get()
         .and(tracks_path)
-        .and(path_param_track_uid)
+        .and(path_param_track_uid.clone())
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |uid, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |uid, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -855,40 +945,43 @@ pub(crate) fn create_filters(
         .and(warp::body::json())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |request_body, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |request_body, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
-                    &shared_connection_gatekeeper,move |mut pooled_connection| {
-                        api::track::load_many::handle_request(&mut pooled_connection, request_body)
-                    })
-                    .await
-                    .map(|response_body| warp::reply::json(&response_body))
-            },
-        );
-    let tracks_export_metadata = warp::post()
-        .and(tracks_path)
-        .and(path_param_track_uid)
-        .and(warp::path("export-metadata"))
-        .and(warp::path::end())
-        .and(warp::query())
-        .and(shared_connection_gatekeeper.clone())
-        .and_then(
-            move |track_uid,
-                  query_params,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
-                websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
-                        api::track::export_metadata::handle_request(
-                            &mut pooled_connection,
-                            &track_uid,
-                            query_params,
-                        )
+                        api::track::load_many::handle_request(&mut pooled_connection, request_body)
                     },
                 )
                 .await
                 .map(|response_body| warp::reply::json(&response_body))
             },
         );
+    let tracks_export_metadata =
+        warp::post()
+            .and(tracks_path)
+            .and(path_param_track_uid.clone())
+            .and(warp::path("export-metadata"))
+            .and(warp::path::end())
+            .and(warp::query())
+            .and(shared_connection_gatekeeper.clone())
+            .and_then(
+                move |track_uid,
+                      query_params,
+                      shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                    websrv::spawn_blocking_write_task(
+                        &shared_connection_gatekeeper,
+                        move |mut pooled_connection| {
+                            api::track::export_metadata::handle_request(
+                                &mut pooled_connection,
+                                &track_uid,
+                                query_params,
+                            )
+                        },
+                    )
+                    .await
+                    .map(|response_body| warp::reply::json(&response_body))
+                },
+            );
     let tracks_filters = tracks_load_many
         .or(tracks_load_one)
         .or(tracks_export_metadata);
@@ -899,8 +992,7 @@ pub(crate) fn create_filters(
         .and(warp::body::json())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |request_body, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -919,11 +1011,11 @@ pub(crate) fn create_filters(
         );
     let playlists_load_one = warp::get()
         .and(playlists_path)
-        .and(path_param_playlist_uid)
+        .and(path_param_playlist_uid.clone())
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |uid, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |uid, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -940,8 +1032,7 @@ pub(crate) fn create_filters(
         .and(warp::query())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |query_params,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |query_params, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_read_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -958,7 +1049,7 @@ pub(crate) fn create_filters(
         );
     let playlists_update = warp::put()
         .and(playlists_path)
-        .and(path_param_playlist_uid)
+        .and(path_param_playlist_uid.clone())
         .and(warp::path::end())
         .and(warp::query())
         .and(warp::body::json())
@@ -967,7 +1058,7 @@ pub(crate) fn create_filters(
             move |uid,
                   query_params,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -985,11 +1076,11 @@ pub(crate) fn create_filters(
         );
     let playlists_delete = warp::delete()
         .and(playlists_path)
-        .and(path_param_playlist_uid)
+        .and(path_param_playlist_uid.clone())
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |uid, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |uid, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -1002,7 +1093,7 @@ pub(crate) fn create_filters(
         );
     let playlists_entries_patch = warp::patch()
         .and(playlists_path)
-        .and(path_param_playlist_uid)
+        .and(path_param_playlist_uid.clone())
         .and(warp::path("entries"))
         .and(warp::path::end())
         .and(warp::query())
@@ -1012,7 +1103,7 @@ pub(crate) fn create_filters(
             move |uid,
                   query_params,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -1037,7 +1128,7 @@ pub(crate) fn create_filters(
 
     let collected_playlists_create = warp::post()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(playlists_path)
         .and(warp::path::end())
         .and(warp::body::json())
@@ -1045,7 +1136,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |collection_uid,
                   request_body,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -1064,7 +1155,7 @@ pub(crate) fn create_filters(
         );
     let collected_playlists_load_all = warp::get()
         .and(collections_path)
-        .and(path_param_collection_uid)
+        .and(path_param_collection_uid.clone())
         .and(playlists_path)
         .and(warp::path::end())
         .and(warp::query())
@@ -1072,7 +1163,7 @@ pub(crate) fn create_filters(
         .and_then(
             move |collection_uid,
                   query_params,
-                  shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+                  shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 let collection_filter = CollectionFilter {
                     uid: Some(Cow::Owned(collection_uid)),
                 };
@@ -1098,22 +1189,22 @@ pub(crate) fn create_filters(
         .and(warp::path("pending-tasks"))
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
-        .map(
-            |shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| {
-                let PendingTasks { read, write } = shared_connection_gatekeeper.pending_tasks();
-                warp::reply::json(&serde_json::json!({
-                    "read": read,
-                    "write": write,
-                }))
-            },
-        );
+        .map(|shared_connection_gatekeeper: Arc<RequestGatekeeper>| {
+            let PendingTasks { read, write } = shared_connection_gatekeeper
+                .connection_gatekeeper()
+                .pending_tasks();
+            warp::reply::json(&serde_json::json!({
+                "read": read,
+                "write": write,
+            }))
+        });
     let storage_post_abort_current_task = warp::post()
         .and(storage_path)
         .and(warp::path("abort-current-task"))
         .and(warp::path::end())
-        .and(abort_flag.clone())
-        .map(|abort_flag: Arc<AtomicBool>| {
-            abort_flag.store(true, Ordering::Relaxed);
+        .and(shared_connection_gatekeeper.clone())
+        .map(|shared_connection_gatekeeper: Arc<RequestGatekeeper>| {
+            shared_connection_gatekeeper.abort_pending_tasks();
             StatusCode::ACCEPTED
         });
     let storage_migrate_schema = warp::post()
@@ -1122,7 +1213,7 @@ pub(crate) fn create_filters(
         .and(warp::path::end())
         .and(shared_connection_gatekeeper.clone())
         .and_then(
-            move |shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 websrv::spawn_blocking_write_task(
                     &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
@@ -1133,6 +1224,31 @@ pub(crate) fn create_filters(
                 .map(|()| StatusCode::NO_CONTENT)
             },
         );
+    let storage_check_integrity = warp::get()
+        .and(storage_path)
+        .and(warp::path("integrity-check"))
+        .and(warp::path::end())
+        .and(shared_connection_gatekeeper.clone())
+        .and_then(
+            move |shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
+                websrv::spawn_blocking_read_task(
+                    &shared_connection_gatekeeper,
+                    move |mut pooled_connection| check_integrity(&mut pooled_connection),
+                )
+                .await
+                .map(|report| {
+                    let problems = report
+                        .problems
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>();
+                    warp::reply::json(&serde_json::json!({
+                        "ok": report.is_ok(),
+                        "problems": problems,
+                    }))
+                })
+            },
+        );
     let storage_cleanse = warp::post()
         .and(storage_path)
         .and(warp::path("cleanse"))
@@ -1140,24 +1256,27 @@ pub(crate) fn create_filters(
         .and(warp::query())
         .and(shared_connection_gatekeeper)
         .and_then(
-            move |query_params, shared_connection_gatekeeper: Arc<DatabaseConnectionGatekeeper>| async move {
+            move |query_params, shared_connection_gatekeeper: Arc<RequestGatekeeper>| async move {
                 let CleanseDatabaseQueryParams { vacuum } = query_params;
                 let vacuum_mode = if vacuum {
                     Some(DEFAULT_VACUUM_MODE)
                 } else {
                     None
                 };
-                websrv::spawn_blocking_write_task(&shared_connection_gatekeeper,
+                websrv::spawn_blocking_write_task(
+                    &shared_connection_gatekeeper,
                     move |mut pooled_connection| {
                         cleanse_database(&mut pooled_connection, vacuum_mode)
-                    })
-                    .await
-                    .map(|()| StatusCode::NO_CONTENT)
+                    },
+                )
+                .await
+                .map(|()| StatusCode::NO_CONTENT)
             },
         );
     let storage_filters = storage_get_pending_tasks
         .or(storage_post_abort_current_task)
         .or(storage_migrate_schema)
+        .or(storage_check_integrity)
         .or(storage_cleanse);
 
     collected_tracks_filters
@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::{
-    collections::HashMap,
     net::SocketAddr,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -17,10 +16,14 @@ use aoide_storage_sqlite::connection::pool::{
     get_pooled_connection,
 };
 use aoide_usecases_sqlite as uc;
-use aoide_websrv_warp_sqlite::handle_rejection;
+use aoide_websrv_warp_sqlite::{
+    bind_server, handle_rejection, shutdown_with_confirmation_token,
+    static_files_with_spa_fallback, with_access_log, with_request_id, AccessLogLevels,
+    RequestGatekeeper, TlsFiles,
+};
 use time::OffsetDateTime;
 use tokio::{sync::mpsc, time::sleep};
-use warp::{http::StatusCode, Filter};
+use warp::{http::StatusCode, Filter, Reply};
 
 use super::{config::Config, routing};
 use crate::config::DatabaseConfig;
@@ -55,7 +58,11 @@ fn provision_database(config: &DatabaseConfig) -> anyhow::Result<DatabaseConnect
     // allowed readers while writers require exclusive access.
     let pool_max_size = config.connection.pool.max_size;
     log::info!("Creating connection pool of max. size {pool_max_size}");
-    let connection_pool = create_connection_pool(&config.connection.storage, pool_max_size)?;
+    let connection_pool = create_connection_pool(
+        &config.connection.storage,
+        pool_max_size,
+        config.connection.pragma,
+    )?;
 
     log::info!("Initializing database");
     initialize_database(&mut *get_pooled_connection(&connection_pool)?)?;
@@ -91,13 +98,11 @@ pub(crate) async fn run(
     "version": env!("CARGO_PKG_VERSION"),
     "instance": {
         "launched_at": launched_at,
-        "config": config,
+        "config": config.redacted(),
+        // Environment variables are not published, because they
+        // might contain secrets like the shutdown confirmation token.
         "environment": {
             "current_dir": std::env::current_dir().unwrap_or_default(),
-            "vars": std::env::vars().fold(HashMap::new(), |mut vars, (key, val)| {
-                debug_assert!(!vars.contains_key(&key));
-                vars.insert(key, val);
-                vars}),
         },
     }
     });
@@ -106,20 +111,24 @@ pub(crate) async fn run(
 
     // POST /shutdown
     let (server_shutdown_tx, mut server_shutdown_rx) = mpsc::unbounded_channel::<()>();
+    let shutdown_confirmation_token = config.shutdown.enabled_confirmation_token();
+    if config.shutdown.enabled && shutdown_confirmation_token.is_none() {
+        log::warn!("Disabling shutdown route without a confirmation token");
+    }
     let shutdown_filter = {
         let server_shutdown_tx = server_shutdown_tx.clone();
-        warp::post()
-            .and(warp::path("shutdown"))
-            .and(warp::path::end())
-            .map(move || {
-                server_shutdown_tx
-                    .send(())
-                    .map(|()| StatusCode::ACCEPTED)
-                    .map_err(|_| {
+        shutdown_with_confirmation_token(
+            shutdown_confirmation_token.map(ToOwned::to_owned),
+            move || {
+                server_shutdown_tx.send(()).map_or_else(
+                    |_| {
                         log::warn!("Failed to forward shutdown request");
                         StatusCode::BAD_GATEWAY
-                    })
-            })
+                    },
+                    |()| StatusCode::ACCEPTED,
+                )
+            },
+        )
     };
 
     // GET /about
@@ -128,11 +137,20 @@ pub(crate) async fn run(
         .and(warp::path::end())
         .map(move || warp::reply::json(&about_json));
 
-    let abort_flag = Arc::new(AtomicBool::new(false));
+    let request_timeout = config.network.request_timeout();
+    if let Some(request_timeout) = request_timeout {
+        log::info!(
+            "Aborting requests after {request_timeout_millis} ms",
+            request_timeout_millis = request_timeout.as_millis()
+        );
+    }
+    let shared_request_gatekeeper = Arc::new(RequestGatekeeper::new(
+        Arc::clone(&shared_connection_pool),
+        request_timeout,
+    ));
     let api_filters = warp::path("api").and(self::routing::api::create_filters(
         rt,
-        Arc::clone(&shared_connection_pool),
-        abort_flag,
+        shared_request_gatekeeper,
     ));
 
     // Static content
@@ -145,19 +163,37 @@ pub(crate) async fn run(
     });
     let static_filters = openapi_yaml;
 
-    let static_filters = static_filters.or(warp::path::end().map(|| warp::reply::html(INDEX_HTML)));
+    // The frontend must be the last alternative, because it serves
+    // index.html for all unknown paths outside of the API.
+    let frontend_filter = if let Some(static_dir) = &config.frontend.static_dir {
+        log::info!(
+            "Serving frontend from directory: {static_dir}",
+            static_dir = static_dir.display()
+        );
+        static_files_with_spa_fallback(static_dir.clone(), &["api"])
+            .map(|file| Box::new(file) as Box<dyn Reply>)
+            .boxed()
+    } else {
+        warp::path::end()
+            .map(|| Box::new(warp::reply::html(INDEX_HTML)) as Box<dyn Reply>)
+            .boxed()
+    };
 
     let all_filters = api_filters
         .or(static_filters)
         .or(shutdown_filter)
-        .or(about_filter);
+        .or(about_filter)
+        .or(frontend_filter);
 
     log::info!("Initializing server");
 
-    let server = warp::serve(
-        all_filters
-            .with(warp::cors().allow_any_origin())
-            .recover(handle_rejection),
+    let server_filter = with_access_log(
+        with_request_id(
+            all_filters
+                .with(warp::cors().allow_any_origin())
+                .recover(handle_rejection),
+        ),
+        AccessLogLevels::default(),
     );
 
     log::info!("Starting");
@@ -167,21 +203,33 @@ pub(crate) async fn run(
     let (socket_addr, server_listener) = {
         let mut command_rx = command_rx;
         let abort_pending_tasks_on_termination = Arc::clone(&abort_pending_tasks_on_termination);
-        server.bind_with_graceful_shutdown(config.network.endpoint.socket_addr(), async move {
-            tokio::select! {
-                Some(()) = server_shutdown_rx.recv() => (),
-                Some(command) = command_rx.recv() => {
-                    match command {
-                        Command::Terminate {
-                            abort_pending_tasks,
-                        } => {
-                            abort_pending_tasks_on_termination.store(abort_pending_tasks, Ordering::Release);
+        let tls_files = config.network.tls.as_ref().map(|tls| TlsFiles {
+            cert_path: &tls.cert_path,
+            key_path: &tls.key_path,
+        });
+        if tls_files.is_some() {
+            log::info!("Terminating TLS connections");
+        }
+        bind_server(
+            server_filter,
+            config.network.endpoint.socket_addr(),
+            tls_files,
+            async move {
+                tokio::select! {
+                    Some(()) = server_shutdown_rx.recv() => (),
+                    Some(command) = command_rx.recv() => {
+                        match command {
+                            Command::Terminate {
+                                abort_pending_tasks,
+                            } => {
+                                abort_pending_tasks_on_termination.store(abort_pending_tasks, Ordering::Release);
+                            }
                         }
                     }
+                    else => (),
                 }
-                else => (),
-            }
-        })
+            },
+        )?
     };
 
     // Give the server some time to become ready and start listening